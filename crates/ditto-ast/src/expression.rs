@@ -1,6 +1,7 @@
 use crate::{FullyQualifiedName, FullyQualifiedProperName, Name, PrimType, ProperName, Span, Type};
 use non_empty_vec::NonEmpty;
 use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt};
 
 /// The real business value.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -58,6 +59,84 @@ pub enum Expression {
         /// The expression to evaluate otherwise.
         false_clause: Box<Self>,
     },
+    /// A pattern matching expression.
+    ///
+    /// ```ditto
+    /// match maybe_five with
+    /// | Just(five) -> five
+    /// | Nothing -> 0
+    /// end
+    /// ```
+    Match {
+        /// The source span for this expression.
+        span: Span,
+
+        /// The output type of this match (i.e. the type of every arm).
+        match_type: Type,
+
+        /// The expression being matched.
+        expression: Box<Self>,
+
+        /// Patterns to be matched, in order, and the expressions to evaluate for the
+        /// first one that matches.
+        arms: NonEmpty<(Pattern, Self)>,
+    },
+    /// A local binding.
+    ///
+    /// ```ditto
+    /// let five = 5; in five
+    /// ```
+    Let {
+        /// The source span for this expression.
+        span: Span,
+
+        /// The source span for the bound name.
+        name_span: Span,
+
+        /// The bound name.
+        name: Name,
+
+        /// The expression being bound.
+        expression: Box<Self>,
+
+        /// The expression in which `name` is bound.
+        body: Box<Self>,
+    },
+    /// A binary operator expression.
+    ///
+    /// ```ditto
+    /// 1 + 2 * 3
+    /// ```
+    BinOp {
+        /// The source span for this expression.
+        span: Span,
+
+        /// The type of this expression.
+        binop_type: Type,
+
+        /// The operator.
+        operator: BinOp,
+
+        /// The left operand.
+        lhs: Box<Self>,
+
+        /// The right operand.
+        rhs: Box<Self>,
+    },
+    /// Numeric negation.
+    ///
+    /// Negated literals are folded into [Expression::Int] and [Expression::Float].
+    ///
+    /// ```ditto
+    /// -x
+    /// ```
+    Negate {
+        /// The source span for this expression.
+        span: Span,
+
+        /// The expression being negated.
+        expression: Box<Self>,
+    },
     /// A value constructor local to the current module, e.g. `Just` and `Ok`.
     LocalConstructor {
         /// The source span for this expression.
@@ -80,6 +159,17 @@ pub enum Expression {
         /// The canonical constructor.
         constructor: FullyQualifiedProperName,
     },
+    /// A built-in value constructor, e.g. `Maybe.Just`.
+    PrimConstructor {
+        /// The source span for this expression.
+        span: Span,
+
+        /// The type of this constructor.
+        constructor_type: Type,
+
+        /// The primitive constructor being referenced.
+        constructor: PrimConstructor,
+    },
     /// A value local to the current module, e.g. `foo`.
     LocalVariable {
         /// The source span for this expression.
@@ -99,6 +189,12 @@ pub enum Expression {
         variable_type: Type,
         /// The foreign variable [Name].
         variable: Name,
+        /// The name of the JavaScript export, if it differs from `variable`.
+        ///
+        /// ```ditto
+        /// foreign "if" as if_impl : (Bool, a, a) -> a;
+        /// ```
+        foreign_name: Option<String>,
     },
     /// A value that has been imported
     ImportedVariable {
@@ -111,11 +207,35 @@ pub enum Expression {
         /// The canonical variable.
         variable: FullyQualifiedName,
     },
+    /// A built-in value, e.g. `debug_log`.
+    PrimVariable {
+        /// The source span for this expression.
+        span: Span,
+
+        /// The type of this variable.
+        variable_type: Type,
+
+        /// The primitive value being referenced.
+        variable: PrimValue,
+    },
     /// A string literal.
     String {
         /// The source span for this expression.
         span: Span,
         /// `"string"`
+        ///
+        /// This is the source text between the quotes, so escape sequences
+        /// (such as `\"` or `\u{1F600}`) haven't been evaluated.
+        value: String,
+    },
+    /// A character literal.
+    Char {
+        /// The source span for this expression.
+        span: Span,
+        /// `'c'`
+        ///
+        /// This is the source text between the quotes, so escape sequences
+        /// (such as `\n` or `\u{1F600}`) haven't been evaluated.
         value: String,
     },
     /// An integer literal.
@@ -127,8 +247,8 @@ pub enum Expression {
         /// This value is a [String] because:
         ///
         /// 1. We want to avoid any compile-time evaluation that would result in parsing the string.
-        /// For example, if the integer appears in ditto source as "005" we want to preserve that in the
-        /// generated code.
+        /// For example, if the integer appears in ditto source as "005" we want to preserve that
+        /// (although code generators might need to normalize it).
         /// 2. Storing as a string avoids overflow issues.
         value: String,
     },
@@ -146,6 +266,21 @@ pub enum Expression {
         /// 2. Storing as a string avoids float overflow and precision issues.
         value: String,
     },
+    /// Inline JavaScript, which is trusted to have the annotated type.
+    ///
+    /// ```ditto
+    /// js "Date.now()" : Int
+    /// ```
+    InlineJs {
+        /// The source span for this expression.
+        span: Span,
+        /// The JavaScript source, which is emitted verbatim.
+        ///
+        /// Unlike string literals, escape sequences have been evaluated.
+        code: String,
+        /// The annotated type.
+        value_type: Type,
+    },
     /// An array literal.
     Array {
         /// The source span for this expression.
@@ -155,6 +290,43 @@ pub enum Expression {
         /// Array elements.
         elements: Vec<Self>,
     },
+    /// A fixed-length tuple.
+    ///
+    /// ```ditto
+    /// (5, "five")
+    /// ```
+    Tuple {
+        /// The source span for this expression.
+        span: Span,
+        /// Tuple elements (there's always at least one).
+        elements: Vec<Self>,
+    },
+    /// An anonymous record.
+    ///
+    /// ```ditto
+    /// { name = "Jane", age = 42 }
+    /// ```
+    Record {
+        /// The source span for this expression.
+        span: Span,
+        /// The labelled field values.
+        fields: BTreeMap<Name, Self>,
+    },
+    /// Accessing a record field.
+    ///
+    /// ```ditto
+    /// person.name
+    /// ```
+    RecordAccess {
+        /// The source span for this expression.
+        span: Span,
+        /// The type of the accessed field.
+        field_type: Type,
+        /// The record being accessed.
+        target: Box<Self>,
+        /// The label of the accessed field.
+        label: Name,
+    },
     /// `true`
     True {
         /// The source span for this expression.
@@ -193,22 +365,45 @@ impl Expression {
                 }
             }
             Self::If { output_type, .. } => output_type.clone(),
+            Self::Match { match_type, .. } => match_type.clone(),
+            Self::Let { body, .. } => body.get_type(),
+            Self::BinOp { binop_type, .. } => binop_type.clone(),
+            Self::Negate { expression, .. } => expression.get_type(),
             Self::LocalConstructor {
                 constructor_type, ..
             } => constructor_type.clone(),
             Self::ImportedConstructor {
                 constructor_type, ..
             } => constructor_type.clone(),
+            Self::PrimConstructor {
+                constructor_type, ..
+            } => constructor_type.clone(),
             Self::LocalVariable { variable_type, .. } => variable_type.clone(),
             Self::ForeignVariable { variable_type, .. } => variable_type.clone(),
             Self::ImportedVariable { variable_type, .. } => variable_type.clone(),
+            Self::PrimVariable { variable_type, .. } => variable_type.clone(),
             Self::String { .. } => Type::PrimConstructor(PrimType::String),
+            Self::Char { .. } => Type::PrimConstructor(PrimType::Char),
             Self::Int { .. } => Type::PrimConstructor(PrimType::Int),
             Self::Float { .. } => Type::PrimConstructor(PrimType::Float),
+            Self::InlineJs { value_type, .. } => value_type.clone(),
             Self::Array { element_type, .. } => Type::Call {
                 function: Box::new(Type::PrimConstructor(PrimType::Array)),
                 arguments: NonEmpty::new(element_type.clone()),
             },
+            Self::Tuple { elements, .. } => Type::Tuple {
+                // SAFETY: tuples can't be empty
+                elements: unsafe {
+                    NonEmpty::new_unchecked(elements.iter().map(Self::get_type).collect())
+                },
+            },
+            Self::Record { fields, .. } => Type::Record {
+                fields: fields
+                    .iter()
+                    .map(|(label, value)| (label.clone(), value.get_type()))
+                    .collect(),
+            },
+            Self::RecordAccess { field_type, .. } => field_type.clone(),
             Self::True { .. } => Type::PrimConstructor(PrimType::Bool),
             Self::False { .. } => Type::PrimConstructor(PrimType::Bool),
             Self::Unit { .. } => Type::PrimConstructor(PrimType::Unit),
@@ -220,15 +415,26 @@ impl Expression {
             Self::Function { span, .. } => *span,
             Self::Call { span, .. } => *span,
             Self::If { span, .. } => *span,
+            Self::Match { span, .. } => *span,
+            Self::Let { span, .. } => *span,
+            Self::BinOp { span, .. } => *span,
+            Self::Negate { span, .. } => *span,
             Self::LocalConstructor { span, .. } => *span,
             Self::ImportedConstructor { span, .. } => *span,
+            Self::PrimConstructor { span, .. } => *span,
             Self::LocalVariable { span, .. } => *span,
             Self::ForeignVariable { span, .. } => *span,
             Self::ImportedVariable { span, .. } => *span,
+            Self::PrimVariable { span, .. } => *span,
             Self::String { span, .. } => *span,
+            Self::Char { span, .. } => *span,
             Self::Int { span, .. } => *span,
             Self::Float { span, .. } => *span,
+            Self::InlineJs { span, .. } => *span,
             Self::Array { span, .. } => *span,
+            Self::Tuple { span, .. } => *span,
+            Self::Record { span, .. } => *span,
+            Self::RecordAccess { span, .. } => *span,
             Self::True { span, .. } => *span,
             Self::False { span, .. } => *span,
             Self::Unit { span, .. } => *span,
@@ -236,6 +442,167 @@ impl Expression {
     }
 }
 
+/// A binary operator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BinOp {
+    /// `+`
+    Add,
+    /// `-`
+    Subtract,
+    /// `*`
+    Multiply,
+    /// `/`, which truncates towards zero for `Int`s.
+    ///
+    /// Dividing an `Int` by zero isn't checked, see `Int.div` for that.
+    /// `Float` division follows IEEE 754, so dividing by zero gives
+    /// `Float.infinity`, `Float.neg_infinity` or `Float.nan` (for `0.0 / 0.0`).
+    Divide,
+    /// `==`
+    Equals,
+    /// `!=`
+    NotEquals,
+    /// `<`
+    LessThan,
+    /// `<=`
+    LessThanOrEquals,
+    /// `>`
+    GreaterThan,
+    /// `>=`
+    GreaterThanOrEquals,
+    /// `&&`
+    And,
+    /// `||`
+    Or,
+}
+
+impl fmt::Display for BinOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Add => write!(f, "+"),
+            Self::Subtract => write!(f, "-"),
+            Self::Multiply => write!(f, "*"),
+            Self::Divide => write!(f, "/"),
+            Self::Equals => write!(f, "=="),
+            Self::NotEquals => write!(f, "!="),
+            Self::LessThan => write!(f, "<"),
+            Self::LessThanOrEquals => write!(f, "<="),
+            Self::GreaterThan => write!(f, ">"),
+            Self::GreaterThanOrEquals => write!(f, ">="),
+            Self::And => write!(f, "&&"),
+            Self::Or => write!(f, "||"),
+        }
+    }
+}
+
+/// Values that are built in to the language.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PrimValue {
+    /// `debug_log : (String, a) -> a`
+    ///
+    /// Logs the message and value, returning the value unchanged.
+    DebugLog,
+    /// `Float.infinity : Float`
+    FloatInfinity,
+    /// `Float.neg_infinity : Float`
+    FloatNegInfinity,
+    /// `Float.nan : Float`
+    ///
+    /// Not a number. Note that this is never equal to anything,
+    /// including itself, so `Float.nan == Float.nan` is `false`.
+    FloatNan,
+    /// `Int.div : (Int, Int) -> Maybe(Int)`
+    ///
+    /// Division that truncates towards zero, like `/`, but returns
+    /// `Maybe.Nothing` rather than trapping when the divisor is zero.
+    IntDiv,
+}
+
+impl fmt::Display for PrimValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(qualifier) = self.qualifier() {
+            write!(f, "{}.", qualifier)?;
+        }
+        write!(f, "{}", self.as_name())
+    }
+}
+
+impl PrimValue {
+    /// Return this value as a [Name], without any qualifier.
+    pub fn as_name(&self) -> Name {
+        Name(String::from(match self {
+            Self::DebugLog => "debug_log",
+            Self::FloatInfinity => "infinity",
+            Self::FloatNegInfinity => "neg_infinity",
+            Self::FloatNan => "nan",
+            Self::IntDiv => "div",
+        }))
+    }
+
+    /// The qualifier this value has to be referenced with, if any.
+    pub fn qualifier(&self) -> Option<ProperName> {
+        match self {
+            Self::DebugLog => None,
+            Self::FloatInfinity | Self::FloatNegInfinity | Self::FloatNan => {
+                Some(ProperName(String::from("Float")))
+            }
+            Self::IntDiv => Some(PrimType::Int.as_proper_name()),
+        }
+    }
+}
+
+/// Value constructors that are built in to the language.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PrimConstructor {
+    /// `Maybe.Just : (a) -> Maybe(a)`
+    MaybeJust,
+    /// `Maybe.Nothing : Maybe(a)`
+    MaybeNothing,
+}
+
+impl fmt::Display for PrimConstructor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.qualifier(), self.as_proper_name())
+    }
+}
+
+impl PrimConstructor {
+    /// Return this constructor as a [ProperName], without its qualifier.
+    pub fn as_proper_name(&self) -> ProperName {
+        ProperName(String::from(match self {
+            Self::MaybeJust => "Just",
+            Self::MaybeNothing => "Nothing",
+        }))
+    }
+
+    /// The qualifier this constructor has to be referenced with.
+    pub fn qualifier(&self) -> ProperName {
+        self.constructed_type().as_proper_name()
+    }
+
+    /// The primitive type this constructor belongs to.
+    pub fn constructed_type(&self) -> PrimType {
+        match self {
+            Self::MaybeJust | Self::MaybeNothing => PrimType::Maybe,
+        }
+    }
+
+    /// The number of arguments this constructor takes.
+    pub fn arity(&self) -> usize {
+        match self {
+            Self::MaybeJust => 1,
+            Self::MaybeNothing => 0,
+        }
+    }
+
+    /// Every constructor of the given primitive type, sorted by name.
+    pub fn all_of(prim_type: &PrimType) -> Vec<Self> {
+        match prim_type {
+            PrimType::Maybe => vec![Self::MaybeJust, Self::MaybeNothing],
+            _ => Vec::new(),
+        }
+    }
+}
+
 /// An "argument" is passed to a function call.
 ///
 /// ```ditto
@@ -265,6 +632,75 @@ impl Argument {
     }
 }
 
+/// A pattern to be matched.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Pattern {
+    /// A local constructor pattern.
+    LocalConstructor {
+        /// The source span for this pattern.
+        span: Span,
+        /// The constructor [ProperName].
+        constructor: ProperName,
+        /// The names of the constructor fields, if it was declared with named fields.
+        field_names: Option<Vec<Name>>,
+        /// Patterns for the constructor arguments.
+        arguments: Vec<Self>,
+    },
+    /// An imported constructor pattern.
+    ImportedConstructor {
+        /// The source span for this pattern.
+        span: Span,
+        /// The canonical constructor.
+        constructor: FullyQualifiedProperName,
+        /// The names of the constructor fields, if it was declared with named fields.
+        field_names: Option<Vec<Name>>,
+        /// Patterns for the constructor arguments.
+        arguments: Vec<Self>,
+    },
+    /// A built-in constructor pattern, e.g. `Maybe.Just(x)`.
+    PrimConstructor {
+        /// The source span for this pattern.
+        span: Span,
+        /// The primitive constructor.
+        constructor: PrimConstructor,
+        /// Patterns for the constructor arguments.
+        arguments: Vec<Self>,
+    },
+    /// A tuple pattern.
+    Tuple {
+        /// The source span for this pattern.
+        span: Span,
+        /// Patterns for the tuple elements.
+        elements: Vec<Self>,
+    },
+    /// A variable binding pattern.
+    Variable {
+        /// The source span for this pattern.
+        span: Span,
+        /// The name being bound.
+        name: Name,
+    },
+    /// A pattern that matches anything, without binding it.
+    Wildcard {
+        /// The source span for this pattern.
+        span: Span,
+    },
+}
+
+impl Pattern {
+    /// Return the source [Span] for this [Pattern].
+    pub fn get_span(&self) -> Span {
+        match self {
+            Self::LocalConstructor { span, .. } => *span,
+            Self::ImportedConstructor { span, .. } => *span,
+            Self::PrimConstructor { span, .. } => *span,
+            Self::Tuple { span, .. } => *span,
+            Self::Variable { span, .. } => *span,
+            Self::Wildcard { span } => *span,
+        }
+    }
+}
+
 /// Binds a variable as part of a function header.
 ///
 /// After (successful) type-checking we should know the type of all binders,
@@ -280,6 +716,13 @@ pub enum FunctionBinder {
         /// The name being bound.
         value: Name,
     },
+    /// `_`, which ignores the argument.
+    Wildcard {
+        /// The source span for this binder.
+        span: Span,
+        /// The type of this binder.
+        binder_type: Type,
+    },
 }
 
 impl FunctionBinder {
@@ -287,12 +730,14 @@ impl FunctionBinder {
     pub fn get_type(&self) -> Type {
         match self {
             Self::Name { binder_type, .. } => binder_type.clone(),
+            Self::Wildcard { binder_type, .. } => binder_type.clone(),
         }
     }
     /// Return the source [Span] for this [FunctionBinder].
     pub fn get_span(&self) -> Span {
         match self {
             Self::Name { span, .. } => *span,
+            Self::Wildcard { span, .. } => *span,
         }
     }
 }
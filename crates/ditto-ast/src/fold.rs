@@ -0,0 +1,455 @@
+//! Owned, rewriting traversal of [Expression] and [Type] trees.
+//!
+//! Implement [Folder], overriding only the methods for the nodes you want to
+//! rewrite. The default methods call the `fold_*` functions in this module,
+//! which rebuild each node from its folded children.
+use crate::{Argument, Expression, FunctionBinder, Pattern, Type};
+use non_empty_vec::NonEmpty;
+
+/// A rewriting pass over an [Expression] tree.
+pub trait Folder {
+    /// Fold an [Expression].
+    fn fold_expression(&mut self, expression: Expression) -> Expression {
+        fold_expression(self, expression)
+    }
+    /// Fold an [Argument].
+    fn fold_argument(&mut self, argument: Argument) -> Argument {
+        fold_argument(self, argument)
+    }
+    /// Fold a [FunctionBinder].
+    fn fold_function_binder(&mut self, function_binder: FunctionBinder) -> FunctionBinder {
+        fold_function_binder(self, function_binder)
+    }
+    /// Fold a [Pattern].
+    fn fold_pattern(&mut self, pattern: Pattern) -> Pattern {
+        fold_pattern(self, pattern)
+    }
+    /// Fold a [Type].
+    fn fold_type(&mut self, t: Type) -> Type {
+        fold_type(self, t)
+    }
+}
+
+/// Rebuild an [Expression] from its folded children.
+pub fn fold_expression<F: Folder + ?Sized>(folder: &mut F, expression: Expression) -> Expression {
+    match expression {
+        Expression::Function {
+            span,
+            binders,
+            box body,
+        } => Expression::Function {
+            span,
+            binders: binders
+                .into_iter()
+                .map(|binder| folder.fold_function_binder(binder))
+                .collect(),
+            body: Box::new(folder.fold_expression(body)),
+        },
+        Expression::Call {
+            span,
+            call_type,
+            box function,
+            arguments,
+        } => Expression::Call {
+            span,
+            call_type: folder.fold_type(call_type),
+            function: Box::new(folder.fold_expression(function)),
+            arguments: arguments
+                .into_iter()
+                .map(|argument| folder.fold_argument(argument))
+                .collect(),
+        },
+        Expression::If {
+            span,
+            output_type,
+            box condition,
+            box true_clause,
+            box false_clause,
+        } => Expression::If {
+            span,
+            output_type: folder.fold_type(output_type),
+            condition: Box::new(folder.fold_expression(condition)),
+            true_clause: Box::new(folder.fold_expression(true_clause)),
+            false_clause: Box::new(folder.fold_expression(false_clause)),
+        },
+        Expression::Match {
+            span,
+            match_type,
+            box expression,
+            arms,
+        } => Expression::Match {
+            span,
+            match_type: folder.fold_type(match_type),
+            expression: Box::new(folder.fold_expression(expression)),
+            arms: {
+                let (head, tail) = arms.split_first();
+                let mut fold_arm = |(pattern, expression): &(Pattern, Expression)| {
+                    (
+                        folder.fold_pattern(pattern.clone()),
+                        folder.fold_expression(expression.clone()),
+                    )
+                };
+                let mut folded = NonEmpty::new(fold_arm(head));
+                for arm in tail {
+                    folded.push(fold_arm(arm));
+                }
+                folded
+            },
+        },
+        Expression::Let {
+            span,
+            name_span,
+            name,
+            box expression,
+            box body,
+        } => Expression::Let {
+            span,
+            name_span,
+            name,
+            expression: Box::new(folder.fold_expression(expression)),
+            body: Box::new(folder.fold_expression(body)),
+        },
+        Expression::BinOp {
+            span,
+            binop_type,
+            operator,
+            box lhs,
+            box rhs,
+        } => Expression::BinOp {
+            span,
+            binop_type: folder.fold_type(binop_type),
+            operator,
+            lhs: Box::new(folder.fold_expression(lhs)),
+            rhs: Box::new(folder.fold_expression(rhs)),
+        },
+        Expression::Negate {
+            span,
+            box expression,
+        } => Expression::Negate {
+            span,
+            expression: Box::new(folder.fold_expression(expression)),
+        },
+        Expression::LocalConstructor {
+            span,
+            constructor_type,
+            constructor,
+        } => Expression::LocalConstructor {
+            span,
+            constructor_type: folder.fold_type(constructor_type),
+            constructor,
+        },
+        Expression::ImportedConstructor {
+            span,
+            constructor_type,
+            constructor,
+        } => Expression::ImportedConstructor {
+            span,
+            constructor_type: folder.fold_type(constructor_type),
+            constructor,
+        },
+        Expression::PrimConstructor {
+            span,
+            constructor_type,
+            constructor,
+        } => Expression::PrimConstructor {
+            span,
+            constructor_type: folder.fold_type(constructor_type),
+            constructor,
+        },
+        Expression::LocalVariable {
+            span,
+            variable_type,
+            variable,
+        } => Expression::LocalVariable {
+            span,
+            variable_type: folder.fold_type(variable_type),
+            variable,
+        },
+        Expression::ForeignVariable {
+            span,
+            variable_type,
+            variable,
+            foreign_name,
+        } => Expression::ForeignVariable {
+            span,
+            variable_type: folder.fold_type(variable_type),
+            variable,
+            foreign_name,
+        },
+        Expression::ImportedVariable {
+            span,
+            variable_type,
+            variable,
+        } => Expression::ImportedVariable {
+            span,
+            variable_type: folder.fold_type(variable_type),
+            variable,
+        },
+        Expression::PrimVariable {
+            span,
+            variable_type,
+            variable,
+        } => Expression::PrimVariable {
+            span,
+            variable_type: folder.fold_type(variable_type),
+            variable,
+        },
+        Expression::Array {
+            span,
+            element_type,
+            elements,
+        } => Expression::Array {
+            span,
+            element_type: folder.fold_type(element_type),
+            elements: elements
+                .into_iter()
+                .map(|element| folder.fold_expression(element))
+                .collect(),
+        },
+        Expression::Tuple { span, elements } => Expression::Tuple {
+            span,
+            elements: elements
+                .into_iter()
+                .map(|element| folder.fold_expression(element))
+                .collect(),
+        },
+        Expression::Record { span, fields } => Expression::Record {
+            span,
+            fields: fields
+                .into_iter()
+                .map(|(label, value)| (label, folder.fold_expression(value)))
+                .collect(),
+        },
+        Expression::RecordAccess {
+            span,
+            field_type,
+            box target,
+            label,
+        } => Expression::RecordAccess {
+            span,
+            field_type: folder.fold_type(field_type),
+            target: Box::new(folder.fold_expression(target)),
+            label,
+        },
+        Expression::InlineJs {
+            span,
+            code,
+            value_type,
+        } => Expression::InlineJs {
+            span,
+            code,
+            value_type: folder.fold_type(value_type),
+        },
+        Expression::String { .. }
+        | Expression::Char { .. }
+        | Expression::Int { .. }
+        | Expression::Float { .. }
+        | Expression::True { .. }
+        | Expression::False { .. }
+        | Expression::Unit { .. } => expression,
+    }
+}
+
+/// Rebuild an [Argument] from its folded children.
+pub fn fold_argument<F: Folder + ?Sized>(folder: &mut F, argument: Argument) -> Argument {
+    match argument {
+        Argument::Expression(expression) => {
+            Argument::Expression(folder.fold_expression(expression))
+        }
+    }
+}
+
+/// Rebuild a [FunctionBinder] from its folded children.
+pub fn fold_function_binder<F: Folder + ?Sized>(
+    folder: &mut F,
+    function_binder: FunctionBinder,
+) -> FunctionBinder {
+    match function_binder {
+        FunctionBinder::Name {
+            span,
+            binder_type,
+            value,
+        } => FunctionBinder::Name {
+            span,
+            binder_type: folder.fold_type(binder_type),
+            value,
+        },
+        FunctionBinder::Wildcard { span, binder_type } => FunctionBinder::Wildcard {
+            span,
+            binder_type: folder.fold_type(binder_type),
+        },
+    }
+}
+
+/// Rebuild a [Pattern] from its folded children.
+pub fn fold_pattern<F: Folder + ?Sized>(folder: &mut F, pattern: Pattern) -> Pattern {
+    match pattern {
+        Pattern::LocalConstructor {
+            span,
+            constructor,
+            field_names,
+            arguments,
+        } => Pattern::LocalConstructor {
+            span,
+            constructor,
+            field_names,
+            arguments: arguments
+                .into_iter()
+                .map(|argument| folder.fold_pattern(argument))
+                .collect(),
+        },
+        Pattern::ImportedConstructor {
+            span,
+            constructor,
+            field_names,
+            arguments,
+        } => Pattern::ImportedConstructor {
+            span,
+            constructor,
+            field_names,
+            arguments: arguments
+                .into_iter()
+                .map(|argument| folder.fold_pattern(argument))
+                .collect(),
+        },
+        Pattern::PrimConstructor {
+            span,
+            constructor,
+            arguments,
+        } => Pattern::PrimConstructor {
+            span,
+            constructor,
+            arguments: arguments
+                .into_iter()
+                .map(|argument| folder.fold_pattern(argument))
+                .collect(),
+        },
+        Pattern::Tuple { span, elements } => Pattern::Tuple {
+            span,
+            elements: elements
+                .into_iter()
+                .map(|element| folder.fold_pattern(element))
+                .collect(),
+        },
+        Pattern::Variable { .. } | Pattern::Wildcard { .. } => pattern,
+    }
+}
+
+/// Rebuild a [Type] from its folded children.
+pub fn fold_type<F: Folder + ?Sized>(folder: &mut F, t: Type) -> Type {
+    match t {
+        Type::Call {
+            box function,
+            arguments,
+        } => Type::Call {
+            function: Box::new(folder.fold_type(function)),
+            arguments: {
+                let (head, tail) = arguments.split_first();
+                let mut folded = NonEmpty::new(folder.fold_type(head.clone()));
+                for argument in tail {
+                    folded.push(folder.fold_type(argument.clone()));
+                }
+                folded
+            },
+        },
+        Type::Function {
+            parameters,
+            box return_type,
+        } => Type::Function {
+            parameters: parameters
+                .into_iter()
+                .map(|parameter| folder.fold_type(parameter))
+                .collect(),
+            return_type: Box::new(folder.fold_type(return_type)),
+        },
+        Type::Record { fields } => Type::Record {
+            fields: fields
+                .into_iter()
+                .map(|(label, field_type)| (label, folder.fold_type(field_type)))
+                .collect(),
+        },
+        Type::Tuple { elements } => Type::Tuple {
+            elements: {
+                let (head, tail) = elements.split_first();
+                let mut folded = NonEmpty::new(folder.fold_type(head.clone()));
+                for element in tail {
+                    folded.push(folder.fold_type(element.clone()));
+                }
+                folded
+            },
+        },
+        Type::Constructor { .. } | Type::PrimConstructor(_) | Type::Variable { .. } => t,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fold_expression, Folder};
+    use crate::{name, Expression, PrimType, Span, Type};
+
+    #[test]
+    fn it_folds_expressions() {
+        // Replace every `true` with `false`
+        struct Negate;
+        impl Folder for Negate {
+            fn fold_expression(&mut self, expression: Expression) -> Expression {
+                match expression {
+                    Expression::True { span } => Expression::False { span },
+                    other => fold_expression(self, other),
+                }
+            }
+        }
+        let span = Span {
+            start_offset: 0,
+            end_offset: 0,
+        };
+        let bool_type = Type::PrimConstructor(PrimType::Bool);
+        let expression = Expression::If {
+            span,
+            output_type: bool_type.clone(),
+            condition: Box::new(Expression::LocalVariable {
+                span,
+                variable_type: bool_type.clone(),
+                variable: name!("x"),
+            }),
+            true_clause: Box::new(Expression::True { span }),
+            false_clause: Box::new(Expression::Array {
+                span,
+                element_type: bool_type,
+                elements: vec![Expression::True { span }, Expression::False { span }],
+            }),
+        };
+        let folded = Negate.fold_expression(expression);
+        assert!(matches!(
+            folded,
+            Expression::If {
+                true_clause: box Expression::False { .. },
+                false_clause: box Expression::Array { ref elements, .. },
+                ..
+            } if matches!(elements.as_slice(), [Expression::False { .. }, Expression::False { .. }])
+        ));
+    }
+
+    #[test]
+    fn it_folds_types() {
+        // Replace `Int` with `Float`
+        struct IntToFloat;
+        impl Folder for IntToFloat {
+            fn fold_type(&mut self, t: Type) -> Type {
+                match t {
+                    Type::PrimConstructor(PrimType::Int) => Type::PrimConstructor(PrimType::Float),
+                    other => super::fold_type(self, other),
+                }
+            }
+        }
+        let int = Type::PrimConstructor(PrimType::Int);
+        let folded = IntToFloat.fold_type(Type::Function {
+            parameters: vec![int.clone()],
+            return_type: Box::new(Type::Call {
+                function: Box::new(Type::PrimConstructor(PrimType::Array)),
+                arguments: non_empty_vec::ne_vec![int],
+            }),
+        });
+        assert_eq!(folded.debug_render(), "(Float) -> Array(Float)");
+    }
+}
@@ -69,8 +69,9 @@ where
 
 /// Extract the strongly connected components of a directed graph, reverse topologically sorted.
 ///
-/// The order of nodes within the [Scc::Cyclic] variant is arbitrary.
-/// For a deterministic version of this function see [toposort_deterministic].
+/// The result is stable for a given order of `nodes`, but the order of nodes within the
+/// [Scc::Cyclic] variant isn't otherwise meaningful.
+/// To sort them as well see [toposort_deterministic].
 pub fn toposort<Node, Key, GetKey, GetConnectedNodes>(
     nodes: Vec<Node>,
     get_key: GetKey,
@@ -101,13 +102,19 @@ where
             .get(&key)
             .unwrap_or_else(|| panic!("{:?} to be in {:?}", key, graph_nodes));
 
-        // NOTE presumably the arbitrary order of the connected_nodes iterator
-        // doesn't matter here?
-        connected_nodes.iter().for_each(|conn_key| {
-            let (conn_index, _) = graph_nodes
-                .get(conn_key)
-                .cloned()
-                .unwrap_or_else(|| panic!("{:?} to be in {:?}", conn_key, graph_nodes));
+        // The order of edges affects the order of the result, so add them in
+        // the order of `nodes` rather than in arbitrary `HashSet` order.
+        let mut conn_indices = connected_nodes
+            .iter()
+            .map(|conn_key| {
+                let (conn_index, _) = graph_nodes
+                    .get(conn_key)
+                    .unwrap_or_else(|| panic!("{:?} to be in {:?}", conn_key, graph_nodes));
+                *conn_index
+            })
+            .collect::<Vec<_>>();
+        conn_indices.sort();
+        conn_indices.into_iter().for_each(|conn_index| {
             graph.add_edge(*node_index, conn_index, "");
         });
     }
@@ -1,12 +1,16 @@
 #![doc = include_str!("../README.md")]
+#![feature(box_patterns)]
 #![warn(missing_docs)]
 
 mod expression;
+pub mod fold;
 pub mod graph;
 mod kind;
 mod module;
 mod name;
+mod normalize;
 mod r#type;
+pub mod visit;
 
 pub use ditto_cst::Span;
 pub use expression::*;
@@ -1,6 +1,9 @@
-use crate::{graph::Scc, Expression, Kind, ModuleName, Name, ProperName, Span, Type};
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use crate::{
+    graph::Scc, Expression, FullyQualifiedModuleName, Kind, ModuleName, Name, ProperName, Span,
+    Type,
+};
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::{BTreeMap, HashMap};
 
 /// A ditto module.
 ///
@@ -19,18 +22,25 @@ pub struct Module {
     pub exports: ModuleExports,
 
     /// Types defined in this module.
+    #[serde(serialize_with = "serialize_sorted")]
     pub types: ModuleTypes,
 
     /// Types defined in this module.
+    #[serde(serialize_with = "serialize_sorted")]
     pub constructors: ModuleConstructors,
 
     /// Top-level values defined within the module.
     ///
     /// The flattened names should form a unique list.
+    #[serde(serialize_with = "serialize_sorted")]
     pub values: ModuleValues,
 
     /// The topological sort order of `values`.
     pub values_toposort: Vec<Scc<Name>>,
+
+    /// Whether the module header was declared `@no_optimize`, in which case
+    /// none of its values are optimized.
+    pub no_optimize: bool,
     // REVIEW we could make the `values` and `values_toposort` fields private
     // and expose getter/setter methods, for safety? Might be overkill though...
 }
@@ -66,6 +76,21 @@ pub struct ModuleValue {
     pub name_span: Span,
     /// The value expression.
     pub expression: Expression,
+    /// Compiler hints from the declaration's attributes.
+    pub attributes: ValueAttributes,
+}
+
+/// Compiler hints that a value declaration can opt into.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValueAttributes {
+    /// `@no_inline` keeps the value's body out of the module's exports, so that
+    /// uses always refer to the exported binding.
+    pub no_inline: bool,
+    /// `@inline` allows bigger bodies to be inlined by dependent modules,
+    /// including functions, which are substituted at their call sites.
+    pub inline: bool,
+    /// `@no_optimize` generates the value as written.
+    pub no_optimize: bool,
 }
 
 impl Module {
@@ -96,6 +121,10 @@ pub struct ModuleConstructor {
     ///
     /// For `Ok(a)`, the field is `[a]`.
     pub fields: Vec<Type>,
+    /// Labels for the `fields`, if this constructor was declared with named fields.
+    ///
+    /// For `Person { name: String, age: Int }`, this is `Some(["name", "age"])`.
+    pub field_names: Option<Vec<Name>>,
     /// The type returned when this constructor is applied to its `fields`.
     pub return_type: Type,
     /// The name of the type this constructor belongs to.
@@ -122,10 +151,13 @@ impl ModuleConstructor {
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ModuleExports {
     /// Exposed type constructors.
+    #[serde(serialize_with = "serialize_sorted")]
     pub types: ModuleExportsTypes,
     /// Exposed type constructors.
+    #[serde(serialize_with = "serialize_sorted")]
     pub constructors: ModuleExportsConstructors,
     /// Exposed values.
+    #[serde(serialize_with = "serialize_sorted")]
     pub values: ModuleExportsValues,
 }
 
@@ -141,6 +173,10 @@ pub struct ModuleExportsType {
     pub doc_position: usize,
     /// The kind of the exposed type.
     pub kind: Kind,
+    /// The module that declared this type, if it's been re-exported from another module.
+    ///
+    /// Like the types of exposed values, this is relative to the exporting package.
+    pub declared_in: Option<FullyQualifiedModuleName>,
 }
 
 /// The type of `module_exports.constructors`, for convenience.
@@ -155,10 +191,19 @@ pub struct ModuleExportsConstructor {
     pub doc_position: usize,
     /// The type of the exposed constructor.
     pub constructor_type: Type,
+    /// Labels for the constructor fields, if it was declared with named fields.
+    pub field_names: Option<Vec<Name>>,
     /// The name of the type this constructor belongs to.
     ///
     /// Used for associating `module_exports.constructors` with `module_exports.types`.
     pub return_type_name: ProperName,
+    /// Every constructor of the return type, sorted, including any that aren't exported.
+    ///
+    /// Needed for checking pattern matches are exhaustive when only some constructors are
+    /// exported or imported.
+    pub type_constructors: Vec<ProperName>,
+    /// The module this constructor is forwarded from, if it's been re-exported.
+    pub reexported_from: Option<FullyQualifiedModuleName>,
 }
 
 /// The type of `module_exports.values`, for convenience.
@@ -173,4 +218,22 @@ pub struct ModuleExportsValue {
     pub doc_position: usize,
     /// The type of the exposed value.
     pub value_type: Type,
+    /// The module this value is forwarded from, if it's been re-exported.
+    pub reexported_from: Option<FullyQualifiedModuleName>,
+    /// The definition of a small constant, which optimized builds of dependent
+    /// modules can substitute for references to the value.
+    ///
+    /// Constructors in the body are local to the exporting module.
+    pub inline_body: Option<Expression>,
+}
+
+/// Serialize a [HashMap] with its keys in order, so that equal modules serialize
+/// to the same bytes.
+fn serialize_sorted<K, V, S>(map: &HashMap<K, V>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    K: Ord + Serialize,
+    V: Serialize,
+    S: Serializer,
+{
+    map.iter().collect::<BTreeMap<_, _>>().serialize(serializer)
 }
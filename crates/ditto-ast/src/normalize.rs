@@ -0,0 +1,176 @@
+//! Alpha-renaming of type variables to a canonical sequence.
+//!
+//! Type variable ids are handed out by the checker as it goes, so they depend
+//! on the order things happened to be inferred in. Renaming them in order of
+//! appearance means semantically identical modules serialize identically,
+//! which keeps content hashes (and so incremental builds) stable.
+//!
+//! Every top-level item is quantified on its own, so each one is renamed
+//! independently, starting from zero.
+use crate::{
+    fold::{fold_type, Folder},
+    Module, ModuleConstructor, ModuleExports, Type,
+};
+use std::collections::HashMap;
+
+impl Module {
+    /// Rename type variables to a canonical sequence, so that modules that only
+    /// differ in the order their types were inferred serialize identically.
+    pub fn normalize_type_variables(mut self) -> Self {
+        self.exports = self.exports.normalize_type_variables();
+        for module_value in self.values.values_mut() {
+            let mut renamer = Renamer::default();
+            // The value's type comes first, so that it's renamed the same way
+            // as the corresponding export
+            renamer.fold_type(module_value.expression.get_type());
+            let expression = std::mem::replace(
+                &mut module_value.expression,
+                crate::Expression::Unit {
+                    span: module_value.name_span,
+                },
+            );
+            module_value.expression = renamer.fold_expression(expression);
+        }
+        for constructor in self.constructors.values_mut() {
+            let mut renamer = Renamer::default();
+            let ModuleConstructor {
+                fields,
+                return_type,
+                ..
+            } = constructor;
+            *return_type = renamer.fold_type(return_type.clone());
+            for field in fields.iter_mut() {
+                *field = renamer.fold_type(field.clone());
+            }
+        }
+        self
+    }
+}
+
+impl ModuleExports {
+    /// Rename type variables to a canonical sequence, see [Module::normalize_type_variables].
+    pub fn normalize_type_variables(mut self) -> Self {
+        for exported_value in self.values.values_mut() {
+            exported_value.value_type =
+                Renamer::default().fold_type(exported_value.value_type.clone());
+        }
+        for exported_constructor in self.constructors.values_mut() {
+            exported_constructor.constructor_type =
+                normalize_constructor_type(exported_constructor.constructor_type.clone());
+        }
+        self
+    }
+}
+
+/// Constructors are renamed starting from the type they return, so that all the
+/// constructors of a type agree on the names of its parameters.
+fn normalize_constructor_type(constructor_type: Type) -> Type {
+    let mut renamer = Renamer::default();
+    match constructor_type {
+        Type::Function {
+            parameters,
+            box return_type,
+        } => {
+            let return_type = renamer.fold_type(return_type);
+            Type::Function {
+                parameters: parameters
+                    .into_iter()
+                    .map(|parameter| renamer.fold_type(parameter))
+                    .collect(),
+                return_type: Box::new(return_type),
+            }
+        }
+        constructor_type => renamer.fold_type(constructor_type),
+    }
+}
+
+/// Renames type variables in the order they're first seen.
+#[derive(Default)]
+struct Renamer {
+    vars: HashMap<usize, usize>,
+}
+
+impl Folder for Renamer {
+    fn fold_type(&mut self, t: Type) -> Type {
+        match t {
+            Type::Variable {
+                variable_kind,
+                var,
+                source_name,
+            } => {
+                let next_var = self.vars.len();
+                Type::Variable {
+                    variable_kind,
+                    var: *self.vars.entry(var).or_insert(next_var),
+                    source_name,
+                }
+            }
+            t => fold_type(self, t),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{normalize_constructor_type, Renamer};
+    use crate::{
+        fold::Folder, module_name, name, proper_name, FullyQualifiedProperName, Kind, Qualified,
+        Type,
+    };
+    use non_empty_vec::ne_vec;
+
+    fn var(var: usize) -> Type {
+        Type::Variable {
+            variable_kind: Kind::Type,
+            var,
+            source_name: None,
+        }
+    }
+
+    #[test]
+    fn it_renames_in_order_of_appearance() {
+        let t = Type::Function {
+            parameters: vec![var(7), var(3), var(7)],
+            return_type: Box::new(var(12)),
+        };
+        let renamed = Renamer::default().fold_type(t);
+        assert_eq!(renamed.debug_render(), "($0, $1, $0) -> $2");
+    }
+
+    #[test]
+    fn it_keeps_source_names() {
+        let t = Type::Function {
+            parameters: vec![Type::Variable {
+                variable_kind: Kind::Type,
+                var: 5,
+                source_name: Some(name!("a")),
+            }],
+            return_type: Box::new(var(2)),
+        };
+        let renamed = Renamer::default().fold_type(t);
+        assert_eq!(renamed.debug_render_verbose(), "(a$0) -> $1");
+    }
+
+    #[test]
+    fn it_renames_constructors_from_their_return_type() {
+        let result = |ok, err| Type::Call {
+            function: Box::new(Type::Constructor {
+                constructor_kind: Kind::Type,
+                canonical_value: FullyQualifiedProperName {
+                    module_name: (None, module_name!("Test")),
+                    value: proper_name!("Result"),
+                },
+                source_value: Some(Qualified {
+                    module_name: None,
+                    value: proper_name!("Result"),
+                }),
+            }),
+            arguments: ne_vec![var(ok), var(err)],
+        };
+        let err = normalize_constructor_type(Type::Function {
+            parameters: vec![var(9)],
+            return_type: Box::new(result(4, 9)),
+        });
+        assert_eq!(err.debug_render(), "($1) -> Result($0, $1)");
+    }
+}
@@ -1,7 +1,10 @@
 use crate::{FullyQualifiedProperName, Kind, Name, ProperName, QualifiedProperName};
 use non_empty_vec::NonEmpty;
 use serde::{Deserialize, Serialize};
-use std::fmt;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt,
+};
 
 /// The type of expressions.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -54,6 +57,24 @@ pub enum Type {
         /// Optional name for this type if one was present in the source.
         source_name: Option<Name>,
     },
+    /// An anonymous record type.
+    ///
+    /// ```ditto
+    /// { name: String, age: Int }
+    /// ```
+    Record {
+        /// The labelled field types.
+        fields: BTreeMap<Name, Self>,
+    },
+    /// A fixed-length tuple type.
+    ///
+    /// ```ditto
+    /// (Int, String)
+    /// ```
+    Tuple {
+        /// The element types, in order.
+        elements: NonEmpty<Self>,
+    },
 }
 
 /// Ditto's primitive types.
@@ -67,10 +88,14 @@ pub enum PrimType {
     Float,
     /// `"five" : String`
     String,
+    /// `'5' : Char`
+    Char,
     /// `true : Bool`
     Bool,
     /// `unit : Unit`
     Unit,
+    /// `Maybe.Just(5) : Maybe(Int)`
+    Maybe,
 }
 
 impl fmt::Display for PrimType {
@@ -80,8 +105,10 @@ impl fmt::Display for PrimType {
             Self::Int => write!(f, "Int"),
             Self::Float => write!(f, "Float"),
             Self::String => write!(f, "String"),
+            Self::Char => write!(f, "Char"),
             Self::Bool => write!(f, "Bool"),
             Self::Unit => write!(f, "Unit"),
+            Self::Maybe => write!(f, "Maybe"),
         }
     }
 }
@@ -100,8 +127,12 @@ impl PrimType {
             Self::Int => Kind::Type,
             Self::Float => Kind::Type,
             Self::String => Kind::Type,
+            Self::Char => Kind::Type,
             Self::Bool => Kind::Type,
             Self::Unit => Kind::Type,
+            Self::Maybe => Kind::Function {
+                parameters: NonEmpty::new(Kind::Type),
+            },
         }
     }
 }
@@ -118,6 +149,8 @@ impl Type {
             Self::PrimConstructor(prim) => prim.get_kind(),
             Self::Call { .. } => Kind::Type, // we don't have curried types!
             Self::Function { .. } => Kind::Type,
+            Self::Record { .. } => Kind::Type,
+            Self::Tuple { .. } => Kind::Type,
         }
     }
 
@@ -158,6 +191,85 @@ impl Type {
         output
     }
 
+    /// Render the type as a scheme, quantified over all of its type variables.
+    ///
+    /// Type variables keep their source names where possible, otherwise they're
+    /// named in order of appearance.
+    ///
+    /// ```ditto
+    /// forall a b. ((a) -> b, Array(a)) -> Array(b)
+    /// ```
+    pub fn render_scheme(&self) -> String {
+        let mut variables = Vec::new();
+        self.collect_variables(&mut variables);
+
+        // Unnamed variables shouldn't steal names from named ones
+        let source_names = variables
+            .iter()
+            .filter_map(|(_, source_name)| source_name.as_ref().map(|name| name.0.clone()))
+            .collect::<HashSet<_>>();
+        let mut fresh_names = (0..).map(friendly_name);
+
+        let mut quantified = Vec::new();
+        let mut names: HashMap<usize, String> = HashMap::new();
+        for (var, source_name) in variables {
+            let name = match source_name {
+                Some(name) if !quantified.contains(&name.0) => name.0,
+                _ => fresh_names
+                    .by_ref()
+                    .find(|name| !source_names.contains(name) && !quantified.contains(name))
+                    .unwrap(),
+            };
+            names.insert(var, name.clone());
+            quantified.push(name);
+        }
+
+        let rendered = self.debug_render_with(|var, _| names[&var].clone());
+        if quantified.is_empty() {
+            rendered
+        } else {
+            format!("forall {}. {}", quantified.join(" "), rendered)
+        }
+    }
+
+    /// Collect distinct type variables in the order they're rendered.
+    fn collect_variables(&self, variables: &mut Vec<(usize, Option<Name>)>) {
+        match self {
+            Self::Variable {
+                var, source_name, ..
+            } => {
+                if !variables.iter().any(|(seen, _)| seen == var) {
+                    variables.push((*var, source_name.clone()));
+                }
+            }
+            Self::Constructor { .. } | Self::PrimConstructor(_) => {}
+            Self::Call {
+                function,
+                arguments,
+            } => {
+                function.collect_variables(variables);
+                arguments
+                    .iter()
+                    .for_each(|argument| argument.collect_variables(variables));
+            }
+            Self::Function {
+                parameters,
+                return_type,
+            } => {
+                parameters
+                    .iter()
+                    .for_each(|parameter| parameter.collect_variables(variables));
+                return_type.collect_variables(variables);
+            }
+            Self::Record { fields } => fields
+                .values()
+                .for_each(|field_type| field_type.collect_variables(variables)),
+            Self::Tuple { elements } => elements
+                .iter()
+                .for_each(|element| element.collect_variables(variables)),
+        }
+    }
+
     fn debug_render_rec<F>(&self, render_var: F, output: &mut String)
     where
         F: Fn(usize, Option<Name>) -> String + Copy,
@@ -214,10 +326,52 @@ impl Type {
                 output.push_str(") -> ");
                 return_type.debug_render_rec(render_var, output);
             }
+
+            Self::Record { fields } => {
+                output.push_str("{ ");
+                let fields_len = fields.len();
+                fields
+                    .iter()
+                    .enumerate()
+                    .for_each(|(i, (label, field_type))| {
+                        output.push_str(&label.0);
+                        output.push_str(": ");
+                        field_type.debug_render_rec(render_var, output);
+                        if i + 1 != fields_len {
+                            output.push_str(", ");
+                        }
+                    });
+                output.push_str(" }");
+            }
+
+            Self::Tuple { elements } => {
+                output.push('(');
+                let elements_len: usize = elements.len().into();
+                elements.iter().enumerate().for_each(|(i, element)| {
+                    element.debug_render_rec(render_var, output);
+                    if i + 1 != elements_len {
+                        output.push_str(", ");
+                    }
+                });
+                if elements_len == 1 {
+                    // Disambiguate from parentheses
+                    output.push(',');
+                }
+                output.push(')');
+            }
         };
     }
 }
 
+/// `a`, `b`, ..., `z`, `a1`, `b1`, ...
+fn friendly_name(i: usize) -> String {
+    let letter = (b'a' + (i % 26) as u8) as char;
+    match i / 26 {
+        0 => letter.to_string(),
+        n => format!("{}{}", letter, n),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
@@ -312,4 +466,52 @@ mod tests {
             "() -> (String, Bool, Bar.Baz) -> ((a) -> b) -> Maybe(Result($2, $34))",
         );
     }
+
+    #[test]
+    fn it_renders_tuples() {
+        let pair = Type::Tuple {
+            elements: ne_vec![
+                Type::PrimConstructor(PrimType::Int),
+                Type::Variable {
+                    variable_kind: Kind::Type,
+                    var: 0,
+                    source_name: Some(name!("a")),
+                }
+            ],
+        };
+        assert_eq!(pair.debug_render(), "(Int, a)");
+        let single = Type::Tuple {
+            elements: ne_vec![pair],
+        };
+        assert_eq!(single.debug_render(), "((Int, a),)");
+    }
+
+    #[test]
+    fn it_renders_schemes() {
+        let var = |var, source_name: Option<&str>| Type::Variable {
+            variable_kind: Kind::Type,
+            var,
+            source_name: source_name.map(|name| name!(name)),
+        };
+        let identity = Type::Function {
+            parameters: vec![var(3, None)],
+            return_type: Box::new(var(3, None)),
+        };
+        assert_eq!(identity.render_scheme(), "forall a. (a) -> a");
+
+        let map = Type::Function {
+            parameters: vec![
+                Type::Function {
+                    parameters: vec![var(7, None)],
+                    return_type: Box::new(var(2, Some("a"))),
+                },
+                var(7, None),
+            ],
+            return_type: Box::new(var(2, Some("a"))),
+        };
+        assert_eq!(map.render_scheme(), "forall b a. ((b) -> a, b) -> a");
+
+        let int = Type::PrimConstructor(PrimType::Int);
+        assert_eq!(int.render_scheme(), "Int");
+    }
 }
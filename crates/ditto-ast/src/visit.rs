@@ -0,0 +1,345 @@
+//! Read-only traversal of [Expression] and [Type] trees.
+//!
+//! Implement [Visitor], overriding only the methods for the nodes you're
+//! interested in. The default methods call the `walk_*` functions in this module,
+//! which recurse into child nodes. An overridden method can call the corresponding
+//! `walk_*` function itself to keep recursing.
+//!
+//! ```ignore
+//! struct CountCalls(usize);
+//!
+//! impl Visitor for CountCalls {
+//!     fn visit_expression(&mut self, expression: &Expression) {
+//!         if let Expression::Call { .. } = expression {
+//!             self.0 += 1;
+//!         }
+//!         walk_expression(self, expression)
+//!     }
+//! }
+//! ```
+use crate::{Argument, Expression, FunctionBinder, Pattern, Span, Type};
+
+/// A read-only pass over an [Expression] tree.
+pub trait Visitor {
+    /// Visit an [Expression].
+    fn visit_expression(&mut self, expression: &Expression) {
+        walk_expression(self, expression)
+    }
+    /// Visit an [Argument].
+    fn visit_argument(&mut self, argument: &Argument) {
+        walk_argument(self, argument)
+    }
+    /// Visit a [FunctionBinder].
+    fn visit_function_binder(&mut self, function_binder: &FunctionBinder) {
+        walk_function_binder(self, function_binder)
+    }
+    /// Visit a [Pattern].
+    fn visit_pattern(&mut self, pattern: &Pattern) {
+        walk_pattern(self, pattern)
+    }
+    /// Visit a [Type].
+    fn visit_type(&mut self, t: &Type) {
+        walk_type(self, t)
+    }
+}
+
+/// Visit the children of an [Expression], including any types it carries.
+pub fn walk_expression<V: Visitor + ?Sized>(visitor: &mut V, expression: &Expression) {
+    match expression {
+        Expression::Function { binders, body, .. } => {
+            for binder in binders {
+                visitor.visit_function_binder(binder);
+            }
+            visitor.visit_expression(body);
+        }
+        Expression::Call {
+            call_type,
+            function,
+            arguments,
+            ..
+        } => {
+            visitor.visit_type(call_type);
+            visitor.visit_expression(function);
+            for argument in arguments {
+                visitor.visit_argument(argument);
+            }
+        }
+        Expression::If {
+            output_type,
+            condition,
+            true_clause,
+            false_clause,
+            ..
+        } => {
+            visitor.visit_type(output_type);
+            visitor.visit_expression(condition);
+            visitor.visit_expression(true_clause);
+            visitor.visit_expression(false_clause);
+        }
+        Expression::Match {
+            match_type,
+            expression,
+            arms,
+            ..
+        } => {
+            visitor.visit_type(match_type);
+            visitor.visit_expression(expression);
+            for (pattern, arm_expression) in arms.iter() {
+                visitor.visit_pattern(pattern);
+                visitor.visit_expression(arm_expression);
+            }
+        }
+        Expression::Let {
+            expression, body, ..
+        } => {
+            visitor.visit_expression(expression);
+            visitor.visit_expression(body);
+        }
+        Expression::BinOp {
+            binop_type,
+            lhs,
+            rhs,
+            ..
+        } => {
+            visitor.visit_type(binop_type);
+            visitor.visit_expression(lhs);
+            visitor.visit_expression(rhs);
+        }
+        Expression::Negate { expression, .. } => visitor.visit_expression(expression),
+        Expression::LocalConstructor {
+            constructor_type, ..
+        }
+        | Expression::ImportedConstructor {
+            constructor_type, ..
+        }
+        | Expression::PrimConstructor {
+            constructor_type, ..
+        } => {
+            visitor.visit_type(constructor_type);
+        }
+        Expression::LocalVariable { variable_type, .. }
+        | Expression::ForeignVariable { variable_type, .. }
+        | Expression::ImportedVariable { variable_type, .. }
+        | Expression::PrimVariable { variable_type, .. } => {
+            visitor.visit_type(variable_type);
+        }
+        Expression::Array {
+            element_type,
+            elements,
+            ..
+        } => {
+            visitor.visit_type(element_type);
+            for element in elements {
+                visitor.visit_expression(element);
+            }
+        }
+        Expression::Tuple { elements, .. } => {
+            for element in elements {
+                visitor.visit_expression(element);
+            }
+        }
+        Expression::Record { fields, .. } => {
+            for value in fields.values() {
+                visitor.visit_expression(value);
+            }
+        }
+        Expression::RecordAccess {
+            field_type, target, ..
+        } => {
+            visitor.visit_type(field_type);
+            visitor.visit_expression(target);
+        }
+        Expression::InlineJs { value_type, .. } => visitor.visit_type(value_type),
+        Expression::String { .. }
+        | Expression::Char { .. }
+        | Expression::Int { .. }
+        | Expression::Float { .. }
+        | Expression::True { .. }
+        | Expression::False { .. }
+        | Expression::Unit { .. } => {}
+    }
+}
+
+/// Visit the children of an [Argument].
+pub fn walk_argument<V: Visitor + ?Sized>(visitor: &mut V, argument: &Argument) {
+    match argument {
+        Argument::Expression(expression) => visitor.visit_expression(expression),
+    }
+}
+
+/// Visit the children of a [FunctionBinder].
+pub fn walk_function_binder<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    function_binder: &FunctionBinder,
+) {
+    match function_binder {
+        FunctionBinder::Name { binder_type, .. } | FunctionBinder::Wildcard { binder_type, .. } => {
+            visitor.visit_type(binder_type)
+        }
+    }
+}
+
+/// Visit the children of a [Pattern].
+pub fn walk_pattern<V: Visitor + ?Sized>(visitor: &mut V, pattern: &Pattern) {
+    match pattern {
+        Pattern::LocalConstructor { arguments, .. }
+        | Pattern::ImportedConstructor { arguments, .. }
+        | Pattern::PrimConstructor { arguments, .. } => {
+            for argument in arguments {
+                visitor.visit_pattern(argument);
+            }
+        }
+        Pattern::Tuple { elements, .. } => {
+            for element in elements {
+                visitor.visit_pattern(element);
+            }
+        }
+        Pattern::Variable { .. } | Pattern::Wildcard { .. } => {}
+    }
+}
+
+/// Visit the children of a [Type].
+pub fn walk_type<V: Visitor + ?Sized>(visitor: &mut V, t: &Type) {
+    match t {
+        Type::Call {
+            function,
+            arguments,
+        } => {
+            visitor.visit_type(function);
+            for argument in arguments.iter() {
+                visitor.visit_type(argument);
+            }
+        }
+        Type::Function {
+            parameters,
+            return_type,
+        } => {
+            for parameter in parameters {
+                visitor.visit_type(parameter);
+            }
+            visitor.visit_type(return_type);
+        }
+        Type::Record { fields } => {
+            for field_type in fields.values() {
+                visitor.visit_type(field_type);
+            }
+        }
+        Type::Tuple { elements } => {
+            for element in elements.iter() {
+                visitor.visit_type(element);
+            }
+        }
+        Type::Constructor { .. } | Type::PrimConstructor(_) | Type::Variable { .. } => {}
+    }
+}
+
+/// Collect the spans of an [Expression] and all of its sub-expressions, binders and patterns,
+/// in the order they're visited (parents before children).
+pub fn collect_spans(expression: &Expression) -> Vec<Span> {
+    struct SpanCollector(Vec<Span>);
+
+    impl Visitor for SpanCollector {
+        fn visit_expression(&mut self, expression: &Expression) {
+            self.0.push(expression.get_span());
+            walk_expression(self, expression)
+        }
+        fn visit_function_binder(&mut self, function_binder: &FunctionBinder) {
+            self.0.push(function_binder.get_span());
+            walk_function_binder(self, function_binder)
+        }
+        fn visit_pattern(&mut self, pattern: &Pattern) {
+            self.0.push(pattern.get_span());
+            walk_pattern(self, pattern)
+        }
+    }
+
+    let mut collector = SpanCollector(Vec::new());
+    collector.visit_expression(expression);
+    collector.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{collect_spans, walk_type, Visitor};
+    use crate::{name, Argument, Expression, FunctionBinder, PrimType, Span, Type};
+
+    fn span(start_offset: usize, end_offset: usize) -> Span {
+        Span {
+            start_offset,
+            end_offset,
+        }
+    }
+
+    // (x) -> f(x, [5])
+    fn example() -> Expression {
+        let int = Type::PrimConstructor(PrimType::Int);
+        Expression::Function {
+            span: span(0, 16),
+            binders: vec![FunctionBinder::Name {
+                span: span(1, 2),
+                binder_type: int.clone(),
+                value: name!("x"),
+            }],
+            body: Box::new(Expression::Call {
+                span: span(7, 16),
+                call_type: int.clone(),
+                function: Box::new(Expression::LocalVariable {
+                    span: span(7, 8),
+                    variable_type: Type::Function {
+                        parameters: vec![int.clone(), int.clone()],
+                        return_type: Box::new(int.clone()),
+                    },
+                    variable: name!("f"),
+                }),
+                arguments: vec![
+                    Argument::Expression(Expression::LocalVariable {
+                        span: span(9, 10),
+                        variable_type: int.clone(),
+                        variable: name!("x"),
+                    }),
+                    Argument::Expression(Expression::Array {
+                        span: span(12, 15),
+                        element_type: int,
+                        elements: vec![Expression::Int {
+                            span: span(13, 14),
+                            value: String::from("5"),
+                        }],
+                    }),
+                ],
+            }),
+        }
+    }
+
+    #[test]
+    fn it_collects_spans() {
+        assert_eq!(
+            collect_spans(&example()),
+            vec![
+                span(0, 16),
+                span(1, 2),
+                span(7, 16),
+                span(7, 8),
+                span(9, 10),
+                span(12, 15),
+                span(13, 14),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_visits_types() {
+        struct CountPrims(usize);
+        impl Visitor for CountPrims {
+            fn visit_type(&mut self, t: &Type) {
+                if let Type::PrimConstructor(_) = t {
+                    self.0 += 1;
+                }
+                walk_type(self, t)
+            }
+        }
+        let mut count_prims = CountPrims(0);
+        count_prims.visit_expression(&example());
+        // binder + call + function (3) + variable + element type
+        assert_eq!(count_prims.0, 7);
+    }
+}
@@ -0,0 +1,140 @@
+use crate::{
+    kindchecker,
+    result::{TypeError, Warnings},
+    supply::Supply,
+    typechecker,
+};
+use ditto_ast::{unqualified, FullyQualifiedProperName, Module, Type};
+use ditto_cst as cst;
+
+/// The environment that a standalone expression is checked in.
+///
+/// The [Default] environment only contains primitives.
+#[derive(Default)]
+pub struct ExpressionEnv {
+    kindchecker_env: kindchecker::Env,
+    typechecker_env: typechecker::Env,
+}
+
+impl ExpressionEnv {
+    /// An environment containing the types, constructors and values declared
+    /// by a checked module.
+    ///
+    /// Note that the module's imports aren't in scope.
+    pub fn from_module(module: &Module) -> Self {
+        let mut env = Self::default();
+        let module_name = (None, module.module_name.clone());
+        for (type_name, module_type) in module.types.iter() {
+            env.kindchecker_env.types.insert(
+                unqualified(type_name.clone()),
+                kindchecker::EnvType::Constructor {
+                    canonical_value: FullyQualifiedProperName {
+                        module_name: module_name.clone(),
+                        value: type_name.clone(),
+                    },
+                    constructor_kind: module_type.kind.clone(),
+                },
+            );
+        }
+        for (constructor_name, constructor) in module.constructors.iter() {
+            let constructor_scheme = env.typechecker_env.generalize(constructor.get_type());
+            env.typechecker_env.constructors.insert(
+                unqualified(constructor_name.clone()),
+                typechecker::EnvConstructor::ModuleConstructor {
+                    constructor_scheme,
+                    constructor: constructor_name.clone(),
+                    field_names: constructor.field_names.clone(),
+                },
+            );
+        }
+        for (name, module_value) in module.values.iter() {
+            let variable_scheme = env
+                .typechecker_env
+                .generalize(module_value.expression.get_type());
+            env.typechecker_env.values.insert(
+                unqualified(name.clone()),
+                typechecker::EnvValue::ModuleValue {
+                    span: module_value.name_span,
+                    variable_scheme,
+                    variable: name.clone(),
+                },
+            );
+        }
+        env
+    }
+}
+
+/// Why a standalone expression couldn't be checked.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone)]
+pub enum ExpressionError {
+    /// The expression doesn't parse.
+    Parse(cst::ParseError),
+    /// The expression doesn't typecheck.
+    Type(TypeError),
+}
+
+/// Parse and infer the type of a standalone expression, e.g. `(a) -> a`.
+pub fn infer_expression(
+    env: &ExpressionEnv,
+    source: &str,
+) -> Result<(Type, Warnings), ExpressionError> {
+    let cst_expression = cst::Expression::parse(source).map_err(ExpressionError::Parse)?;
+    let (expression, _value_references, _constructor_references, _type_references, warnings, _) =
+        typechecker::typecheck_with(
+            &env.kindchecker_env,
+            &env.typechecker_env,
+            Supply::default(),
+            None,
+            cst_expression,
+        )
+        .map_err(ExpressionError::Type)?;
+    Ok((expression.get_type(), warnings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{infer_expression, ExpressionEnv, ExpressionError};
+    use crate::TypeError;
+
+    #[test]
+    fn it_infers_standalone_lambdas() {
+        let env = ExpressionEnv::default();
+        let (t, warnings) = infer_expression(&env, "(a) -> a").unwrap();
+        assert_eq!(t.debug_render(), "($0) -> $0");
+        assert!(warnings.is_empty());
+
+        let (t, _) = infer_expression(&env, "(x: Int, f: (Int) -> a) -> f(x)").unwrap();
+        assert_eq!(t.debug_render(), "(Int, (Int) -> a) -> a");
+
+        assert!(matches!(
+            infer_expression(&env, "(a) ->"),
+            Err(ExpressionError::Parse(_))
+        ));
+        assert!(matches!(
+            infer_expression(&env, "(a) -> b"),
+            Err(ExpressionError::Type(TypeError::UnknownVariable { .. }))
+        ));
+    }
+
+    #[test]
+    fn it_infers_expressions_in_a_module_env() {
+        let cst_module = ditto_cst::Module::parse(
+            r#"
+            module Test exports (..);
+            type Maybe(a) = Just(a) | Nothing;
+            five: Int = 5;
+            "#,
+        )
+        .unwrap();
+        let (module, _warnings) =
+            crate::check_module(&crate::Everything::default(), cst_module).unwrap();
+        let env = ExpressionEnv::from_module(&module);
+
+        let (t, _) = infer_expression(&env, "() -> Just(five)").unwrap();
+        assert_eq!(t.debug_render(), "() -> Maybe(Int)");
+
+        let (t, _) = infer_expression(&env, "(m: Maybe(a)): Maybe(a) -> m").unwrap();
+        assert_eq!(t.debug_render(), "(Maybe(a)) -> Maybe(a)");
+    }
+}
@@ -14,6 +14,10 @@ lazy_static! {
             unqualified(PrimType::String.as_proper_name()),
             EnvType::PrimConstructor(PrimType::String),
         ),
+        (
+            unqualified(PrimType::Char.as_proper_name()),
+            EnvType::PrimConstructor(PrimType::Char),
+        ),
         (
             unqualified(PrimType::Int.as_proper_name()),
             EnvType::PrimConstructor(PrimType::Int),
@@ -30,6 +34,10 @@ lazy_static! {
             unqualified(PrimType::Array.as_proper_name()),
             EnvType::PrimConstructor(PrimType::Array),
         ),
+        (
+            unqualified(PrimType::Maybe.as_proper_name()),
+            EnvType::PrimConstructor(PrimType::Maybe),
+        ),
     ]);
 }
 
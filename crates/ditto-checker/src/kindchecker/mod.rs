@@ -8,11 +8,11 @@ pub use env::*;
 pub use state::*;
 pub use substitution::*;
 
-use crate::result::{Result, TypeError};
+use crate::result::{RecordKind, Result, TypeError};
 use ditto_ast::{Kind, Name, QualifiedProperName, Span, Type};
 use ditto_cst as cst;
 use non_empty_vec::NonEmpty;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 #[cfg(test)]
 pub fn kindcheck(
@@ -69,6 +69,19 @@ pub fn infer(env: &Env, state: &mut State, cst_type: cst::Type) -> Result<Type>
                 .map(|env_type_variable| env_type_variable.to_type(variable))?;
             Ok(ast_type)
         }
+        Wildcard(wildcard) => {
+            if !state.allow_wildcards {
+                return Err(TypeError::UnexpectedTypeWildcard {
+                    span: wildcard.0.get_span(),
+                });
+            }
+            let (var, variable_kind) = state.supply.fresh_kind();
+            Ok(Type::Variable {
+                variable_kind,
+                var,
+                source_name: None,
+            })
+        }
         Constructor(constructor) => {
             let span = constructor.get_span(); // grab this before the move
             let constructor = QualifiedProperName::from(constructor);
@@ -189,6 +202,42 @@ pub fn infer(env: &Env, state: &mut State, cst_type: cst::Type) -> Result<Type>
                 }),
             }
         }
+        Record(braces) => {
+            let mut field_spans: HashMap<Name, Span> = HashMap::new();
+            let mut fields = BTreeMap::new();
+            for cst::RecordTypeField {
+                label,
+                type_annotation,
+            } in braces.value.into_iter()
+            {
+                let label_span = label.get_span();
+                let label = Name::from(label);
+                if let Some(previous_field) = field_spans.get(&label) {
+                    return Err(TypeError::DuplicateRecordField {
+                        previous_field: *previous_field,
+                        duplicate_field: label_span,
+                        record_kind: RecordKind::Type,
+                        values: None,
+                    });
+                }
+                field_spans.insert(label.clone(), label_span);
+                let field_type = check(env, state, Kind::Type, type_annotation.1)?;
+                fields.insert(label, field_type);
+            }
+            Ok(Type::Record { fields })
+        }
+        Tuple(parens) => {
+            let cst::CommaSep1 {
+                head: head_element,
+                tail: tail_elements,
+                ..
+            } = parens.value;
+            let mut elements = NonEmpty::new(check(env, state, Kind::Type, *head_element)?);
+            for (_comma, element) in tail_elements {
+                elements.push(check(env, state, Kind::Type, *element)?);
+            }
+            Ok(Type::Tuple { elements })
+        }
     }
 }
 
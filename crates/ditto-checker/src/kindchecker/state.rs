@@ -9,6 +9,10 @@ pub struct State {
     pub substitution: Substitution,
     pub warnings: Warnings,
     pub type_references: TypeReferences,
+    /// Whether `_` can stand in for a type to be inferred.
+    ///
+    /// This is only the case for annotations within value declarations.
+    pub allow_wildcards: bool,
 }
 
 pub type TypeReferences = References<QualifiedProperName>;
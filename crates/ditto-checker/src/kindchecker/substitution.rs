@@ -86,6 +86,23 @@ impl Substitution {
                 source_value,
             },
             Type::PrimConstructor(prim_type) => Type::PrimConstructor(prim_type),
+            Type::Record { fields } => Type::Record {
+                fields: fields
+                    .into_iter()
+                    .map(|(label, field_type)| (label, self.apply_type(field_type)))
+                    .collect(),
+            },
+            Type::Tuple { elements } => Type::Tuple {
+                elements: unsafe {
+                    NonEmpty::new_unchecked(
+                        elements
+                            .iter()
+                            .cloned()
+                            .map(|element| self.apply_type(element))
+                            .collect(),
+                    )
+                },
+            },
         }
     }
     pub fn apply_constructor(&self, constructor: ModuleConstructor) -> ModuleConstructor {
@@ -94,6 +111,7 @@ impl Substitution {
             doc_position,
             constructor_name_span,
             fields,
+            field_names,
             return_type,
             return_type_name,
         } = constructor;
@@ -103,6 +121,7 @@ impl Substitution {
             doc_position,
             constructor_name_span,
             fields: fields.into_iter().map(|t| self.apply_type(t)).collect(),
+            field_names,
             return_type: self.apply_type(return_type),
             return_type_name,
         }
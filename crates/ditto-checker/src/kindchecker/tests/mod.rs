@@ -1,3 +1,5 @@
 pub(self) mod macros;
 mod prim;
+mod record;
+mod tuple;
 mod variable;
@@ -0,0 +1,15 @@
+use super::macros::*;
+use crate::TypeError::*;
+
+#[test]
+fn it_kindchecks_as_expected() {
+    assert_kind!("{ name: String }", "Type");
+    assert_kind!("{ name: String, ages: Array(Int) }", "Type");
+    assert_kind!("{ nested: { inner: Bool } }", "Type");
+}
+
+#[test]
+fn it_errors_as_expected() {
+    assert_type_error!("{ wrapped: Array }", KindsNotEqual { .. });
+    assert_type_error!("{ a: Int, a: Int }", DuplicateRecordField { .. });
+}
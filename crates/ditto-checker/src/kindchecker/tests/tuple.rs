@@ -0,0 +1,14 @@
+use super::macros::*;
+use crate::TypeError::*;
+
+#[test]
+fn it_kindchecks_as_expected() {
+    assert_kind!("(Int, String)", "Type");
+    assert_kind!("(Int,)", "Type");
+    assert_kind!("((Bool, Char), Array(Int))", "Type");
+}
+
+#[test]
+fn it_errors_as_expected() {
+    assert_type_error!("(Array, Int)", KindsNotEqual { .. });
+}
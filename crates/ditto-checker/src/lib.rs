@@ -3,11 +3,15 @@
 #![warn(missing_docs)]
 
 mod collections;
+mod expression;
 mod kindchecker;
 mod module;
 mod result;
 mod supply;
 mod typechecker;
 
-pub use module::{check_module, Everything, Modules};
-pub use result::{Result, TypeError, TypeErrorReport, Warning, WarningReport, Warnings};
+pub use expression::{infer_expression, ExpressionEnv, ExpressionError};
+pub use module::{check_module, check_module_with_options, CheckOptions, Everything, Modules};
+pub use result::{
+    RecordKind, Result, TypeError, TypeErrorReport, UnknownName, Warning, WarningReport, Warnings,
+};
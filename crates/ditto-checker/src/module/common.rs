@@ -1,5 +1,33 @@
+use crate::result::{Result, TypeError};
 use ditto_cst as cst;
 
+/// Keeps a value out of the cross-module inliner, so that its uses always
+/// refer to the exported binding.
+pub static ATTRIBUTE_NO_INLINE: &str = "no_inline";
+
+/// Lets dependent modules inline a bigger body, and substitute a function at
+/// its call sites.
+pub static ATTRIBUTE_INLINE: &str = "inline";
+
+/// Generates a module or value as written, skipping optimizations.
+pub static ATTRIBUTE_NO_OPTIMIZE: &str = "no_optimize";
+
+/// Check that an attribute is one of the `expected` names.
+pub fn check_attribute(attribute: &cst::Attribute, expected: &[&str]) -> Result<()> {
+    let name = &attribute.name.0.value;
+    if expected.contains(&name.as_str()) {
+        return Ok(());
+    }
+    Err(TypeError::UnknownAttribute {
+        span: attribute.get_span(),
+        attribute: name.clone(),
+        expected: expected
+            .iter()
+            .map(|attribute| format!("@{}", attribute))
+            .collect(),
+    })
+}
+
 pub fn extract_doc_comments<T>(token: &cst::Token<T>) -> Vec<String> {
     token
         .leading_comments
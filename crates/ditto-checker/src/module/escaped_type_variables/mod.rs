@@ -0,0 +1,70 @@
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    result::{Result, TypeError},
+    typechecker::Env,
+};
+use ditto_ast::{
+    visit::{walk_type, Visitor},
+    ModuleValues, Type,
+};
+use std::collections::HashSet;
+
+/// Check that every type variable mentioned by a top-level declaration is
+/// quantified by that declaration's scheme.
+///
+/// Fresh variables that escape inference would otherwise be serialized as
+/// meaningless `$n` types in the module's exports, so this reports them as an
+/// internal compiler error. Only run in debug builds.
+pub fn check_escaped_type_variables(env: &Env, values: &ModuleValues) -> Result<()> {
+    let env_type_variables = env.free_type_variables();
+
+    let mut names = values.keys().collect::<Vec<_>>();
+    names.sort();
+    for name in names {
+        let module_value = &values[name];
+        let scheme = env.generalize(module_value.expression.get_type());
+
+        let mut collector = AnonymousTypeVariables::default();
+        collector.visit_type(&scheme.signature);
+        let mut escaped = collector
+            .0
+            .difference(&scheme.forall)
+            .copied()
+            .collect::<Vec<_>>();
+
+        // Types within the expression needn't be quantified at the top-level,
+        // but they shouldn't be referring to anything unbound in the environment
+        let mut collector = AnonymousTypeVariables::default();
+        collector.visit_expression(&module_value.expression);
+        escaped.extend(collector.0.intersection(&env_type_variables).copied());
+
+        if let Some(var) = escaped.into_iter().min() {
+            return Err(TypeError::EscapedTypeVariable {
+                span: module_value.name_span,
+                declaration: name.clone(),
+                var,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Type variables that weren't introduced by a type annotation.
+#[derive(Default)]
+struct AnonymousTypeVariables(HashSet<usize>);
+
+impl Visitor for AnonymousTypeVariables {
+    fn visit_type(&mut self, t: &Type) {
+        if let Type::Variable {
+            var,
+            source_name: None,
+            ..
+        } = t
+        {
+            self.0.insert(*var);
+        }
+        walk_type(self, t)
+    }
+}
@@ -0,0 +1,95 @@
+use super::check_escaped_type_variables;
+use crate::{
+    module::{
+        tests::macros::{assert_module_ok, parse_and_check_module},
+        Everything,
+    },
+    typechecker::{Env, EnvValue, Scheme},
+    TypeError,
+};
+use ditto_ast::{
+    name, unqualified, Expression, Kind, ModuleValue, ModuleValues, Span, Type, ValueAttributes,
+};
+use std::collections::HashSet;
+
+#[test]
+fn it_quantifies_exported_empty_arrays() {
+    let module = assert_module_ok!(
+        r#"
+        module Test exports (xs);
+        xs = [];
+        "#
+    );
+    let exported = module.exports.values.get(&name!("xs")).unwrap();
+    assert_eq!(exported.value_type.debug_render(), "Array($0)");
+
+    let mut everything = Everything::default();
+    everything
+        .modules
+        .insert(module.module_name.clone(), module.exports);
+    let result = parse_and_check_module!(
+        r#"
+        module Main exports (..);
+        import Test;
+        ints : Array(Int) = Test.xs;
+        strings : Array(String) = Test.xs;
+        "#,
+        &everything
+    );
+    assert!(matches!(result, Ok(_)), "{:#?}", result.unwrap_err());
+}
+
+#[test]
+fn it_errors_for_escaped_type_variables() {
+    let span = Span {
+        start_offset: 0,
+        end_offset: 4,
+    };
+    let var = |var| Type::Variable {
+        variable_kind: Kind::Type,
+        var,
+        source_name: None,
+    };
+
+    // Simulate a checker bug, where `leak` was checked against an environment
+    // that still mentions an unsolved variable
+    let mut env = Env::default();
+    env.values.insert(
+        unqualified(name!("oops")),
+        EnvValue::ModuleValue {
+            span,
+            variable_scheme: Scheme {
+                forall: HashSet::new(),
+                signature: var(3),
+            },
+            variable: name!("oops"),
+        },
+    );
+    let mut values = ModuleValues::new();
+    values.insert(
+        name!("leak"),
+        ModuleValue {
+            doc_comments: Vec::new(),
+            name_span: span,
+            expression: Expression::Array {
+                span,
+                element_type: var(3),
+                elements: Vec::new(),
+            },
+            attributes: ValueAttributes::default(),
+        },
+    );
+
+    let result = check_escaped_type_variables(&env, &values);
+    assert!(
+        matches!(
+            &result,
+            Err(TypeError::EscapedTypeVariable { declaration, var: 3, .. })
+                if declaration.0 == "leak"
+        ),
+        "{:#?}",
+        result
+    );
+
+    assert!(check_escaped_type_variables(&Env::default(), &values).is_ok());
+}
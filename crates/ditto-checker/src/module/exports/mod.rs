@@ -1,26 +1,145 @@
 #[cfg(test)]
 mod tests;
 
+use super::imports::{requalify_type, Everything, ImportedModules};
 use crate::result::{Result, TypeError, Warning, Warnings};
 use ditto_ast::{
-    Module, ModuleExportsConstructor, ModuleExportsType, ModuleExportsValue, ModuleType,
-    ModuleValue, Name, ProperName, Span,
+    Argument, Expression, FullyQualifiedModuleName, FunctionBinder, Module, ModuleConstructors,
+    ModuleExportsConstructor, ModuleExportsConstructors, ModuleExportsType, ModuleExportsValue,
+    ModuleName, ModuleType, ModuleValue, Name, PrimValue, ProperName, Span, Type,
 };
 use ditto_cst as cst;
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    hash::Hash,
+};
+
+/// Spans of the import lines that were used by `module` re-exports.
+pub type ReexportedImportLines = HashSet<Span>;
 
-pub fn add_exports(cst_exports: cst::Exports, module: Module) -> Result<(Module, Warnings)> {
+pub fn add_exports(
+    everything: &Everything,
+    imported_modules: &ImportedModules,
+    cst_exports: cst::Exports,
+    module: Module,
+) -> Result<(Module, ReexportedImportLines, Warnings)> {
     // NOTE we're assuming the `module` arguments has an empty `ModuleExports` here
-    match cst_exports {
-        cst::Exports::Everything { .. } => export_everything(module),
+    let (mut module, reexported_import_lines, warnings) = match cst_exports {
+        cst::Exports::Everything { .. } => export_everything(module)?,
+        cst::Exports::Nothing(nothing) => (
+            module,
+            ReexportedImportLines::new(),
+            vec![Warning::NoExports {
+                span: nothing.get_span(),
+            }],
+        ),
         cst::Exports::List(box cst::Parens { value: exports, .. }) => {
-            export_list(module, exports.as_vec())
+            export_list(everything, imported_modules, module, exports.as_vec())?
+        }
+    };
+    add_inline_bodies(&mut module);
+    Ok((module, reexported_import_lines, warnings))
+}
+
+/// Bodies with more nodes than this aren't worth duplicating at every use.
+const INLINE_SIZE_LIMIT: usize = 8;
+
+/// Like [INLINE_SIZE_LIMIT], for values that are declared `@inline`.
+const INLINE_HINT_SIZE_LIMIT: usize = 32;
+
+/// Attach the bodies of small values to their exports, so that optimized
+/// builds of dependent modules can use them in place of the import.
+///
+/// Only pure expressions are inlined, and they can only refer to exported
+/// constructors and the parameters of the inlined function, so they never need
+/// anything the dependent module can't import. Functions are only inlined when
+/// they're declared `@inline`, and as they can't refer to the module's own
+/// values they can't be recursive. Re-exported values are left alone.
+fn add_inline_bodies(module: &mut Module) {
+    for (name, exported_value) in module.exports.values.iter_mut() {
+        if exported_value.reexported_from.is_some() {
+            continue;
+        }
+        if let Some(module_value) = module.values.get(name) {
+            let attributes = module_value.attributes;
+            if attributes.no_inline {
+                continue;
+            }
+            let size = match &module_value.expression {
+                Expression::Function { binders, body, .. } if attributes.inline => {
+                    let parameters = binders
+                        .iter()
+                        .filter_map(|binder| match binder {
+                            FunctionBinder::Name { value, .. } => Some(value.clone()),
+                            FunctionBinder::Wildcard { .. } => None,
+                        })
+                        .collect();
+                    inline_size(&module.exports.constructors, &parameters, body)
+                }
+                Expression::Function { .. } => None,
+                expression => {
+                    inline_size(&module.exports.constructors, &HashSet::new(), expression)
+                }
+            };
+            let limit = if attributes.inline {
+                INLINE_HINT_SIZE_LIMIT
+            } else {
+                INLINE_SIZE_LIMIT
+            };
+            if size.map_or(false, |size| size <= limit) {
+                exported_value.inline_body = Some(module_value.expression.clone());
+            }
         }
     }
 }
 
+/// The number of nodes in an inlinable expression, or `None` if it can't be inlined.
+fn inline_size(
+    exported_constructors: &ModuleExportsConstructors,
+    parameters: &HashSet<Name>,
+    expression: &Expression,
+) -> Option<usize> {
+    let size = |expression| inline_size(exported_constructors, parameters, expression);
+    match expression {
+        Expression::String { .. }
+        | Expression::Char { .. }
+        | Expression::Int { .. }
+        | Expression::Float { .. }
+        | Expression::True { .. }
+        | Expression::False { .. }
+        | Expression::Unit { .. }
+        | Expression::PrimConstructor { .. } => Some(1),
+        // Logging at every use would change what's logged
+        Expression::PrimVariable { variable, .. } => (*variable != PrimValue::DebugLog).then(|| 1),
+        Expression::LocalConstructor { constructor, .. } => {
+            exported_constructors.contains_key(constructor).then(|| 1)
+        }
+        Expression::LocalVariable { variable, .. } => parameters.contains(variable).then(|| 1),
+        Expression::Call {
+            function,
+            arguments,
+            ..
+        } => arguments
+            .iter()
+            .try_fold(size(function)?, |total, Argument::Expression(argument)| {
+                Some(total + size(argument)?)
+            }),
+        Expression::If {
+            condition,
+            true_clause,
+            false_clause,
+            ..
+        } => Some(1 + size(condition)? + size(true_clause)? + size(false_clause)?),
+        Expression::BinOp { lhs, rhs, .. } => Some(1 + size(lhs)? + size(rhs)?),
+        Expression::Negate { expression, .. } => Some(1 + size(expression)?),
+        Expression::RecordAccess { target, .. } => Some(1 + size(target)?),
+        _ => None,
+    }
+}
+
 /// Handle `exports (..)`
-fn export_everything(mut module: Module) -> Result<(Module, Warnings)> {
+fn export_everything(mut module: Module) -> Result<(Module, ReexportedImportLines, Warnings)> {
     let warnings = Warnings::new();
 
     // TYPES
@@ -35,6 +154,7 @@ fn export_everything(mut module: Module) -> Result<(Module, Warnings)> {
                 doc_comments,
                 doc_position,
                 kind,
+                declared_in: None,
             },
         );
     }
@@ -46,13 +166,17 @@ fn export_everything(mut module: Module) -> Result<(Module, Warnings)> {
         let constructor_type = constructor.get_type();
         let doc_comments = constructor.doc_comments.clone();
         let return_type_name = constructor.return_type_name.clone();
+        let type_constructors = type_constructors(&module.constructors, &return_type_name);
         module.exports.constructors.insert(
             proper_name.clone(),
             ModuleExportsConstructor {
                 doc_comments,
                 doc_position,
                 constructor_type,
+                field_names: constructor.field_names.clone(),
                 return_type_name,
+                type_constructors,
+                reexported_from: None,
             },
         );
     }
@@ -69,20 +193,41 @@ fn export_everything(mut module: Module) -> Result<(Module, Warnings)> {
                 doc_comments,
                 doc_position,
                 value_type,
+                reexported_from: None,
+                inline_body: None,
             },
         );
     }
 
-    Ok((module, warnings))
+    Ok((module, ReexportedImportLines::new(), warnings))
 }
 
-fn export_list(mut module: Module, expose_list: Vec<cst::Export>) -> Result<(Module, Warnings)> {
+fn export_list(
+    everything: &Everything,
+    imported_modules: &ImportedModules,
+    mut module: Module,
+    expose_list: Vec<cst::Export>,
+) -> Result<(Module, ReexportedImportLines, Warnings)> {
     let mut warnings = Warnings::new();
     let mut values_seen: HashMap<Name, Span> = HashMap::new();
     let mut types_seen: HashMap<ProperName, Span> = HashMap::new();
+    let mut constructors_seen: HashMap<ProperName, Span> = HashMap::new();
+    let mut reexports = Reexports::default();
 
     for (doc_position, expose) in expose_list.into_iter().enumerate() {
         match expose {
+            cst::Export::Module(module_keyword, module_name) => {
+                let span = module_keyword.0.get_span().merge(&module_name.get_span());
+                reexport_module(
+                    everything,
+                    imported_modules,
+                    &mut module,
+                    &mut reexports,
+                    doc_position,
+                    span,
+                    ModuleName::from(module_name),
+                )?;
+            }
             cst::Export::Value(name) => {
                 let span = name.get_span();
                 let name = Name::from(name);
@@ -109,6 +254,8 @@ fn export_list(mut module: Module, expose_list: Vec<cst::Export>) -> Result<(Mod
                             doc_comments: doc_comments.to_vec(),
                             doc_position,
                             value_type,
+                            reexported_from: None,
+                            inline_body: None,
                         },
                     );
                 } else {
@@ -119,6 +266,37 @@ fn export_list(mut module: Module, expose_list: Vec<cst::Export>) -> Result<(Mod
                 let span = type_name.get_span();
                 let type_name = ProperName::from(type_name);
 
+                if include_constructors.is_none() && !module.types.contains_key(&type_name) {
+                    // Not a type, so this might be a single constructor export
+                    // (`exports (Just)`)
+                    if let Some(ctor) = module.constructors.get(&type_name) {
+                        if let Some(&previous_export) = constructors_seen.get(&type_name) {
+                            warnings.push(Warning::DuplicateConstructorExport {
+                                previous_export,
+                                duplicate_export: span,
+                            })
+                        } else {
+                            constructors_seen.insert(type_name.clone(), span);
+                        }
+                        module.exports.constructors.insert(
+                            type_name,
+                            ModuleExportsConstructor {
+                                doc_comments: ctor.doc_comments.clone(),
+                                doc_position: ctor.doc_position,
+                                constructor_type: ctor.get_type(),
+                                field_names: ctor.field_names.clone(),
+                                return_type_name: ctor.return_type_name.clone(),
+                                type_constructors: type_constructors(
+                                    &module.constructors,
+                                    &ctor.return_type_name,
+                                ),
+                                reexported_from: None,
+                            },
+                        );
+                        continue;
+                    }
+                }
+
                 if let Some(&previous_export) = types_seen.get(&type_name) {
                     warnings.push(Warning::DuplicateTypeExport {
                         previous_export,
@@ -138,6 +316,7 @@ fn export_list(mut module: Module, expose_list: Vec<cst::Export>) -> Result<(Mod
                             doc_comments: doc_comments.to_vec(),
                             doc_position,
                             kind: kind.clone(),
+                            declared_in: None,
                         },
                     );
                 } else {
@@ -145,6 +324,7 @@ fn export_list(mut module: Module, expose_list: Vec<cst::Export>) -> Result<(Mod
                 }
 
                 if include_constructors.is_some() {
+                    let type_constructors = type_constructors(&module.constructors, &type_name);
                     module
                         .exports
                         .constructors
@@ -160,7 +340,10 @@ fn export_list(mut module: Module, expose_list: Vec<cst::Export>) -> Result<(Mod
                                                 doc_comments: ctor.doc_comments.clone(),
                                                 doc_position: ctor.doc_position,
                                                 constructor_type: ctor.get_type(),
+                                                field_names: ctor.field_names.clone(),
                                                 return_type_name: ctor.return_type_name.clone(),
+                                                type_constructors: type_constructors.clone(),
+                                                reexported_from: None,
                                             },
                                         ))
                                     } else {
@@ -173,5 +356,148 @@ fn export_list(mut module: Module, expose_list: Vec<cst::Export>) -> Result<(Mod
         }
     }
 
-    Ok((module, warnings))
+    Ok((module, reexports.import_lines, warnings))
+}
+
+/// All the constructors of the given type, including those that aren't exported.
+fn type_constructors(constructors: &ModuleConstructors, type_name: &ProperName) -> Vec<ProperName> {
+    let mut type_constructors = constructors
+        .iter()
+        .filter(|(_, constructor)| constructor.return_type_name == *type_name)
+        .map(|(constructor_name, _)| constructor_name.clone())
+        .collect::<Vec<_>>();
+    type_constructors.sort();
+    type_constructors
+}
+
+/// Everything re-exported so far, to check for conflicts.
+#[derive(Default)]
+struct Reexports {
+    types: HashMap<ProperName, Span>,
+    constructors: HashMap<ProperName, Span>,
+    values: HashMap<Name, Span>,
+    import_lines: ReexportedImportLines,
+}
+
+/// Handle `module Some.Module` in an export list, by merging everything exported
+/// by that (imported) module into the exports of the current module.
+fn reexport_module(
+    everything: &Everything,
+    imported_modules: &ImportedModules,
+    module: &mut Module,
+    reexports: &mut Reexports,
+    doc_position: usize,
+    span: Span,
+    module_name: ModuleName,
+) -> Result<()> {
+    let (package_name, import_line_span) =
+        imported_modules.get(&module_name).cloned().ok_or_else(|| {
+            TypeError::UnknownModuleExport {
+                span,
+                module_name: module_name.clone(),
+            }
+        })?;
+    reexports.import_lines.insert(import_line_span);
+
+    let modules = match package_name {
+        Some(ref package_name) => everything.packages.get(package_name),
+        None => Some(&everything.modules),
+    };
+    let exports = modules
+        .and_then(|modules| modules.get(&module_name))
+        .expect("imported modules should have been checked already");
+
+    // Everything we merge in needs to be relative to the current package
+    let requalify_module_name =
+        |(declared_package_name, declared_module_name): FullyQualifiedModuleName| {
+            (
+                declared_package_name.or_else(|| package_name.clone()),
+                declared_module_name,
+            )
+        };
+    let requalify = |ast_type: Type| match package_name {
+        Some(ref package_name) => requalify_type(ast_type, package_name),
+        None => ast_type,
+    };
+    let reexported_from = (package_name.clone(), module_name.clone());
+
+    for (type_name, exported_type) in exports.types.iter() {
+        let declaration = module.types.get(type_name).map(|t| t.type_name_span);
+        check_reexport(&mut reexports.types, declaration, type_name, span)?;
+        module.exports.types.insert(
+            type_name.clone(),
+            ModuleExportsType {
+                doc_comments: exported_type.doc_comments.clone(),
+                doc_position,
+                kind: exported_type.kind.clone(),
+                declared_in: Some(requalify_module_name(
+                    exported_type
+                        .declared_in
+                        .clone()
+                        .unwrap_or_else(|| (None, module_name.clone())),
+                )),
+            },
+        );
+    }
+
+    for (constructor_name, exported_constructor) in exports.constructors.iter() {
+        let declaration = module
+            .constructors
+            .get(constructor_name)
+            .map(|ctor| ctor.constructor_name_span);
+        check_reexport(
+            &mut reexports.constructors,
+            declaration,
+            constructor_name,
+            span,
+        )?;
+        module.exports.constructors.insert(
+            constructor_name.clone(),
+            ModuleExportsConstructor {
+                doc_comments: exported_constructor.doc_comments.clone(),
+                doc_position: exported_constructor.doc_position,
+                constructor_type: requalify(exported_constructor.constructor_type.clone()),
+                field_names: exported_constructor.field_names.clone(),
+                return_type_name: exported_constructor.return_type_name.clone(),
+                type_constructors: exported_constructor.type_constructors.clone(),
+                reexported_from: Some(reexported_from.clone()),
+            },
+        );
+    }
+
+    for (name, exported_value) in exports.values.iter() {
+        let declaration = module.values.get(name).map(|value| value.name_span);
+        check_reexport(&mut reexports.values, declaration, name, span)?;
+        module.exports.values.insert(
+            name.clone(),
+            ModuleExportsValue {
+                doc_comments: exported_value.doc_comments.clone(),
+                doc_position,
+                value_type: requalify(exported_value.value_type.clone()),
+                reexported_from: Some(reexported_from.clone()),
+                inline_body: None,
+            },
+        );
+    }
+
+    Ok(())
+}
+
+/// Check that a re-exported name isn't already declared in the current module,
+/// or re-exported from another module.
+fn check_reexport<K: Clone + Eq + Hash + fmt::Display>(
+    reexported: &mut HashMap<K, Span>,
+    declaration: Option<Span>,
+    name: &K,
+    span: Span,
+) -> Result<()> {
+    if let Some(previous_binding) = declaration.or_else(|| reexported.get(name).copied()) {
+        return Err(TypeError::ReexportConflict {
+            previous_binding,
+            new_binding: span,
+            name: name.to_string(),
+        });
+    }
+    reexported.insert(name.clone(), span);
+    Ok(())
 }
@@ -1,6 +1,13 @@
 mod macros;
 
-use crate::{module::tests::macros::assert_module_err, TypeError, Warning};
+use crate::{
+    module::{
+        tests::macros::{assert_module_err, assert_module_ok, parse_and_check_module},
+        Everything,
+    },
+    TypeError, Warning,
+};
+use ditto_ast::{module_name, name, proper_name};
 use macros::*;
 
 #[test]
@@ -88,6 +95,49 @@ fn it_handles_type_exports() {
     );
 }
 
+#[test]
+fn it_handles_constructor_exports() {
+    // Abstract type
+    assert_module_exports!(
+        r#"
+        module Test exports (Maybe, nothing);
+        type Maybe(a) = Just(a) | Nothing;
+        nothing : Maybe(Int) = Nothing;
+        "#,
+        warnings = [],
+        types = [("", "Maybe", "(Type) -> Type")],
+        constructors = [],
+        values = [("", "nothing", "Maybe(Int)")]
+    );
+
+    // A specific constructor
+    assert_module_exports!(
+        r#"
+        module Test exports (Maybe, Just);
+        type Maybe(a) = Just(a) | Nothing;
+        "#,
+        warnings = [],
+        types = [("", "Maybe", "(Type) -> Type")],
+        constructors = [("", "Just", "(a) -> Maybe(a)", "Maybe")],
+        values = []
+    );
+
+    // Constructors without the type
+    assert_module_exports!(
+        r#"
+        module Test exports (Just, Nothing);
+        type Maybe(a) = Just(a) | Nothing;
+        "#,
+        warnings = [],
+        types = [],
+        constructors = [
+            ("", "Just", "(a) -> Maybe(a)", "Maybe"),
+            ("", "Nothing", "Maybe(a)", "Maybe")
+        ],
+        values = []
+    );
+}
+
 #[test]
 fn it_doesnt_export_foreign_values() {
     assert_module_exports!(
@@ -105,6 +155,15 @@ fn it_doesnt_export_foreign_values() {
 
 #[test]
 fn it_warns_as_expected() {
+    assert_module_exports!(
+        r#"
+        module Main exports ();
+        "#,
+        warnings = [Warning::NoExports { .. }],
+        types = [],
+        constructors = [],
+        values = []
+    );
     assert_module_exports!(
         r#"
         module Test exports (a, a);
@@ -148,6 +207,16 @@ fn it_warns_as_expected() {
         constructors = [],
         values = []
     );
+    assert_module_exports!(
+        r#"
+        module Test exports (Maybe, Just, Just);
+        type Maybe(a) = Just(a) | Nothing;
+        "#,
+        warnings = [Warning::DuplicateConstructorExport { .. }],
+        types = [("", "Maybe", "(Type) -> Type")],
+        constructors = [("", "Just", "(a) -> Maybe(a)", "Maybe")],
+        values = []
+    );
     assert_module_exports!(
         r#"
         module Test exports (A(..));
@@ -177,3 +246,178 @@ fn it_errors_as_expected() {
         TypeError::UnknownTypeExport { .. }
     );
 }
+
+#[test]
+fn it_exports_the_bodies_of_small_constants() {
+    let module = assert_module_ok!(
+        r#"
+        module Test exports (Maybe(..), Hidden, five, greeting, just_five, seed, hidden, sum, id);
+        type Maybe(a) = Just(a) | Nothing;
+        type Hidden = Hidden(Int);
+        five = 5;
+        greeting = "hello";
+        just_five = Just(5);
+        -- kept behind the import
+        @no_inline
+        seed = 42;
+        hidden = Hidden(5);
+        sum = 2 + 3;
+        id = (a) -> a;
+        "#
+    );
+    let mut inlined = module
+        .exports
+        .values
+        .iter()
+        .filter(|(_, value)| value.inline_body.is_some())
+        .map(|(name, _)| name.0.as_str())
+        .collect::<Vec<_>>();
+    inlined.sort_unstable();
+    assert_eq!(inlined, ["five", "greeting", "just_five", "sum"]);
+    assert_eq!(
+        module.exports.values[&name!("seed")].doc_comments,
+        ["kept behind the import"]
+    );
+}
+
+#[test]
+fn it_exports_the_bodies_of_inline_functions() {
+    let module = assert_module_ok!(
+        r#"
+        module Test exports (..);
+        @inline
+        add = (a: Int, b: Int): Int -> a + b;
+        @inline
+        forever = (n: Int): Int -> forever(n);
+        @inline
+        shout = (greeting: String): String -> debug_log(greeting, greeting);
+        @inline
+        @no_inline
+        kept = (a: Int): Int -> a;
+        not_hinted = (a: Int): Int -> a;
+        @inline
+        big = (a: Int): Int ->
+            a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a;
+        @inline
+        offset = 100 * 60;
+        "#
+    );
+    let mut inlined = module
+        .exports
+        .values
+        .iter()
+        .filter(|(_, value)| value.inline_body.is_some())
+        .map(|(name, _)| name.0.as_str())
+        .collect::<Vec<_>>();
+    inlined.sort_unstable();
+    assert_eq!(inlined, ["add", "offset"]);
+}
+
+fn data_maybe() -> Everything {
+    let module = assert_module_ok!(
+        r#"
+        module Data.Maybe exports (Maybe(..), nothing_int);
+        type Maybe(a) = Just(a) | Nothing;
+        nothing_int : Maybe(Int) = Nothing;
+        "#
+    );
+    let mut everything = Everything::default();
+    everything
+        .modules
+        .insert(module.module_name.clone(), module.exports);
+    everything
+}
+
+#[test]
+fn it_reexports_modules() {
+    let mut everything = data_maybe();
+    let result = parse_and_check_module!(
+        r#"
+        module Prelude exports (module Data.Maybe, five);
+        import Data.Maybe;
+        five = 5;
+        "#,
+        &everything
+    );
+    assert!(matches!(result, Ok(_)), "{:#?}", result.unwrap_err());
+    let (module, warnings) = result.unwrap();
+    assert!(warnings.is_empty(), "{:#?}", warnings);
+
+    let data_maybe = (None, module_name!("Data", "Maybe"));
+    let exports = &module.exports;
+    assert_eq!(
+        exports.types[&proper_name!("Maybe")].declared_in,
+        Some(data_maybe.clone())
+    );
+    assert_eq!(
+        exports.constructors[&proper_name!("Just")].reexported_from,
+        Some(data_maybe.clone())
+    );
+    assert_eq!(
+        exports.values[&name!("nothing_int")].reexported_from,
+        Some(data_maybe)
+    );
+    assert_eq!(exports.values[&name!("five")].reexported_from, None);
+
+    // Re-exported types are the same as the originals
+    everything
+        .modules
+        .insert(module.module_name.clone(), module.exports);
+    let result = parse_and_check_module!(
+        r#"
+        module Main exports (..);
+        import Data.Maybe;
+        import Prelude;
+        x : Maybe.Maybe(Int) = Prelude.Just(Prelude.five);
+        y : Prelude.Maybe(Int) = Maybe.nothing_int;
+        z : Maybe.Maybe(Int) = Prelude.nothing_int;
+        "#,
+        &everything
+    );
+    assert!(matches!(result, Ok(_)), "{:#?}", result.unwrap_err());
+}
+
+#[test]
+fn it_errors_for_bad_reexports() {
+    assert_module_err!(
+        r#"
+        module Prelude exports (module Data.Maybe);
+        "#,
+        TypeError::UnknownModuleExport { .. }
+    );
+
+    let everything = data_maybe();
+    let result = parse_and_check_module!(
+        r#"
+        module Prelude exports (module Data.Maybe);
+        import Data.Maybe;
+        nothing_int = 5;
+        "#,
+        &everything
+    );
+    assert!(
+        matches!(
+            &result,
+            Err(TypeError::ReexportConflict { name, .. }) if name == "nothing_int"
+        ),
+        "{:#?}",
+        result
+    );
+
+    let result = parse_and_check_module!(
+        r#"
+        module Prelude exports (module Data.Maybe);
+        import Data.Maybe;
+        type Option = Nothing;
+        "#,
+        &everything
+    );
+    assert!(
+        matches!(
+            &result,
+            Err(TypeError::ReexportConflict { name, .. }) if name == "Nothing"
+        ),
+        "{:#?}",
+        result
+    );
+}
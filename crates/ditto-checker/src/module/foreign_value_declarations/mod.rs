@@ -1,41 +1,154 @@
 #[cfg(test)]
 mod tests;
 
+use super::Everything;
 use crate::{
     kindchecker::{self, merge_references, EnvTypes, TypeReferences},
-    result::{Result, Warnings},
+    result::{Result, TypeError, Warning, Warnings},
     typechecker,
 };
-use ditto_ast::{Name, Span, Type};
-use ditto_cst::ForeignValueDeclaration;
+use ditto_ast::{Name, ProperName, Span, Type};
+use ditto_cst::{self as cst, ForeignValueDeclaration};
 
 #[allow(clippy::type_complexity)]
 pub fn kindcheck_foreign_value_declarations(
+    everything: &Everything,
     env_types: &EnvTypes,
     foreign_value_declarations: Vec<ForeignValueDeclaration>,
-) -> Result<(Vec<(Span, Name, Type)>, TypeReferences, Warnings)> {
+) -> Result<(
+    Vec<(Span, Name, Option<String>, Type)>,
+    TypeReferences,
+    Warnings,
+)> {
     let mut foreign_values = Vec::new();
     let mut type_references = TypeReferences::new();
     let mut warnings = Warnings::new();
     for ForeignValueDeclaration {
         foreign_keyword,
+        foreign_name,
         name,
         type_annotation,
         semicolon,
     } in foreign_value_declarations
     {
         let span = foreign_keyword.0.get_span().merge(&semicolon.0.get_span());
+        if let Some(variable_span) = find_type_variable(&type_annotation.1) {
+            warnings.push(Warning::PolymorphicForeignValue {
+                span: variable_span,
+            });
+        }
         let mut state = kindchecker::State::default();
         let foreign_type = typechecker::pre_ast::check_type_annotation(
             env_types,
             &mut kindchecker::EnvTypeVariables::new(),
             &mut state,
             type_annotation,
-        )?;
+        )
+        .map_err(|err| match err {
+            // Foreign declarations are the FFI boundary, so it's worth
+            // being a bit more helpful here.
+            TypeError::UnknownTypeConstructor { span, constructor } => {
+                let suggested_import = if constructor.module_name.is_none() {
+                    suggest_import(everything, &constructor.value)
+                } else {
+                    None
+                };
+                TypeError::UnknownForeignTypeConstructor {
+                    span,
+                    constructor,
+                    suggested_import,
+                }
+            }
+            other => other,
+        })?;
         let name = Name::from(name);
-        foreign_values.push((span, name, foreign_type));
+        let foreign_name = foreign_name
+            .map(|(string_token, _as_keyword)| {
+                if is_valid_foreign_name(&string_token.value) {
+                    Ok(string_token.value)
+                } else {
+                    Err(TypeError::InvalidForeignName {
+                        span: string_token.get_span(),
+                        foreign_name: string_token.value,
+                    })
+                }
+            })
+            .transpose()?;
+        foreign_values.push((span, name, foreign_name, foreign_type));
         type_references = merge_references(type_references, state.type_references);
         warnings.extend(state.warnings);
     }
     Ok((foreign_values, type_references, warnings))
 }
+
+/// Whether a foreign name can be imported from JavaScript as it is.
+///
+/// Reserved words are fine here (e.g. `import { if as x }`), so this is really
+/// checking for an `IdentifierName`.
+fn is_valid_foreign_name(foreign_name: &str) -> bool {
+    let mut chars = foreign_name.chars();
+    chars
+        .next()
+        .map_or(false, |c| c.is_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+/// Find the first type variable mentioned in a foreign type (if any).
+fn find_type_variable(t: &cst::Type) -> Option<Span> {
+    use cst::Type::*;
+    match t {
+        Parens(parens) => find_type_variable(&parens.value),
+        Call {
+            function,
+            arguments,
+        } => match function {
+            cst::TypeCallFunction::Variable(var) => Some(var.get_span()),
+            cst::TypeCallFunction::Constructor(_) => arguments
+                .value
+                .iter()
+                .find_map(|argument| find_type_variable(argument)),
+        },
+        Function {
+            parameters,
+            right_arrow: _,
+            return_type,
+        } => parameters
+            .value
+            .as_ref()
+            .and_then(|parameters| {
+                parameters
+                    .iter()
+                    .find_map(|parameter| find_type_variable(parameter))
+            })
+            .or_else(|| find_type_variable(return_type)),
+        Record(braces) => braces
+            .value
+            .iter()
+            .find_map(|field| find_type_variable(&field.type_annotation.1)),
+        Tuple(parens) => parens
+            .value
+            .iter()
+            .find_map(|element| find_type_variable(element)),
+        Constructor(_) | Wildcard(_) => None,
+        Variable(var) => Some(var.get_span()),
+    }
+}
+
+/// Look for a dependency module that exports the given type.
+fn suggest_import(everything: &Everything, type_name: &ProperName) -> Option<String> {
+    let mut suggestions = everything
+        .packages
+        .iter()
+        .flat_map(|(package_name, modules)| {
+            modules
+                .iter()
+                .filter(|(_, exports)| exports.types.contains_key(type_name))
+                .map(move |(module_name, _)| {
+                    format!("import ({}) {} ({});", package_name, module_name, type_name)
+                })
+        })
+        .collect::<Vec<_>>();
+    // Sort so that we're deterministic
+    suggestions.sort();
+    suggestions.into_iter().next()
+}
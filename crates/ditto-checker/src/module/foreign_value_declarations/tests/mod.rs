@@ -1,4 +1,7 @@
-use crate::{module::tests::macros::assert_module_ok, Warning};
+use crate::{
+    module::tests::macros::{assert_module_err, assert_module_ok, assert_module_warnings},
+    TypeError,
+};
 
 #[test]
 fn it_handles_foreign_values() {
@@ -24,11 +27,68 @@ fn it_handles_foreign_values() {
 
 #[test]
 fn it_warns_for_unused() {
-    assert_module_ok!(
+    assert_module_warnings!(
         r#"
         module Test exports (..);
+        -- expect-warning: unused-foreign-value
         foreign foo : (Int) -> Bool;
+    "#
+    );
+}
+
+#[test]
+fn it_warns_for_polymorphic_foreign_values() {
+    assert_module_warnings!(
+        r#"
+        module Test exports (..);
+        -- expect-warning: polymorphic-foreign-value
+        foreign unsafe_id : (a) -> a;
+        five : Int = unsafe_id(5);
+    "#
+    );
+}
+
+#[test]
+fn it_errors_for_unknown_foreign_types() {
+    assert_module_err!(
+        r#"
+        module Test exports (..);
+        foreign parse : (String) -> Json;
     "#,
-        [Warning::UnusedForeignValue { .. }]
+        TypeError::UnknownForeignTypeConstructor {
+            suggested_import: None,
+            ..
+        }
+    );
+}
+
+#[test]
+fn it_errors_for_wildcards() {
+    assert_module_err!(
+        r#"
+        module Test exports (..);
+        foreign length : (Array(_)) -> Int;
+    "#,
+        TypeError::UnexpectedTypeWildcard { .. }
+    );
+}
+
+#[test]
+fn it_errors_for_invalid_foreign_names() {
+    assert_module_err!(
+        r#"
+        module Test exports (..);
+        foreign "foo-bar" as foo_bar : Int;
+    "#,
+        TypeError::InvalidForeignName { .. }
+    );
+
+    // Reserved words can still be imported
+    assert_module_ok!(
+        r#"
+        module Test exports (..);
+        foreign "if" as if_impl : (Bool, Int, Int) -> Int;
+        five = if_impl(true, 5, 6);
+    "#
     );
 }
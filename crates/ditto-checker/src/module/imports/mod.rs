@@ -8,8 +8,8 @@ use crate::{
 };
 use ditto_ast::{
     unqualified, FullyQualifiedName, FullyQualifiedProperName, Kind, ModuleExports,
-    ModuleExportsConstructors, ModuleExportsTypes, ModuleExportsValues, ModuleName, Name,
-    PackageName, ProperName, QualifiedName, QualifiedProperName, Span, Type,
+    ModuleExportsConstructors, ModuleExportsType, ModuleExportsTypes, ModuleExportsValues,
+    ModuleName, Name, PackageName, ProperName, QualifiedName, QualifiedProperName, Span, Type,
 };
 use ditto_cst as cst;
 use non_empty_vec::NonEmpty;
@@ -27,6 +27,10 @@ pub struct Everything {
 /// A map of module names to their exports.
 pub type Modules = HashMap<ModuleName, ModuleExports>;
 
+/// Imported modules (by their full name, not their alias), along with the package
+/// they were imported from and the span of their import line.
+pub type ImportedModules = HashMap<ModuleName, (Option<PackageName>, Span)>;
+
 type ImportedTypes = PristineMap<QualifiedProperName, ImportedType>;
 
 type ImportedConstructors = PristineMap<QualifiedProperName, ImportedConstructor>;
@@ -47,6 +51,8 @@ pub struct ImportedConstructor {
     pub constructor_span: Span,
     pub constructor_scheme: Scheme,
     pub constructor: FullyQualifiedProperName,
+    pub field_names: Option<Vec<Name>>,
+    pub type_constructors: Vec<ProperName>,
 }
 
 #[derive(Clone)]
@@ -57,6 +63,28 @@ pub struct ImportedValue {
     pub variable: FullyQualifiedName,
 }
 
+/// Collect the modules that are imported, so that they can be re-exported.
+pub fn imported_modules(imports: &[cst::ImportLine]) -> ImportedModules {
+    imports
+        .iter()
+        .map(|import_line| {
+            let package_name = import_line
+                .package
+                .as_ref()
+                .map(|parens| PackageName::from(parens.value.clone()));
+            let import_line_span = import_line
+                .import_keyword
+                .0
+                .get_span()
+                .merge(&import_line.module_name.get_span());
+            (
+                ModuleName::from(import_line.module_name.clone()),
+                (package_name, import_line_span),
+            )
+        })
+        .collect()
+}
+
 pub fn extract_imports(
     everything: &Everything,
     imports: Vec<cst::ImportLine>,
@@ -302,15 +330,16 @@ fn import_all_types_qualified(
             module_name: Some(import_module_name.clone()),
             value: type_name.clone(),
         };
-        let fully_qualified_type_name = FullyQualifiedProperName {
-            module_name: (package_name.clone(), module_name.clone()),
-            value: type_name.clone(),
-        };
         let imported_type = ImportedType {
             import_line_span,
             type_span: module_name_span,
             kind: exported_type.kind.clone(),
-            canonical_type_name: fully_qualified_type_name,
+            canonical_type_name: canonical_type_name(
+                &package_name,
+                &module_name,
+                type_name,
+                exported_type,
+            ),
         };
         // Unchecked because exported_types are unique.
         imported_types.insert_else(qualified_type_name, imported_type, |collision| {
@@ -352,6 +381,8 @@ fn import_all_constructors_qualified(
             constructor_span: module_name_span,
             constructor_scheme: Scheme::from(constructor_type),
             constructor: fully_qualified_constructor_name,
+            field_names: exported_constructor.field_names.clone(),
+            type_constructors: exported_constructor.type_constructors.clone(),
         };
 
         // Unchecked because exported_constructors are unique.
@@ -416,17 +447,18 @@ fn import_unqualified_list(
                 let type_name = ProperName::from(type_name);
 
                 if let Some(exported_type) = exports.types.get(&type_name) {
-                    let fully_qualified_type_name = FullyQualifiedProperName {
-                        module_name: (package_name.clone(), module_name.clone()),
-                        value: type_name.clone(),
-                    };
                     imported_types.insert_with_warning(
                         unqualified(type_name.clone()),
                         ImportedType {
                             import_line_span,
                             type_span: type_name_span,
                             kind: exported_type.kind.clone(),
-                            canonical_type_name: fully_qualified_type_name,
+                            canonical_type_name: canonical_type_name(
+                                &package_name,
+                                &module_name,
+                                &type_name,
+                                exported_type,
+                            ),
                         },
                         // Warn in the case of `import Foo (Bar, Bar, Bar(..))`
                         |collision| {
@@ -472,11 +504,43 @@ fn import_unqualified_list(
                                             ),
                                             value: ctor_name.clone(),
                                         },
+                                        field_names: ctor.field_names.clone(),
+                                        type_constructors: ctor.type_constructors.clone(),
                                     },
                                 )
                             },
                         ));
                     }
+                } else if let (None, Some(ctor)) =
+                    (&everything, exports.constructors.get(&type_name))
+                {
+                    // Importing a single constructor (`import Foo (Just)`)
+                    let constructor_type = if let Some(ref package_name) = package_name {
+                        requalify_type(ctor.constructor_type.clone(), package_name)
+                    } else {
+                        ctor.constructor_type.clone()
+                    };
+                    imported_constructors.insert_with_warning(
+                        unqualified(type_name.clone()),
+                        ImportedConstructor {
+                            import_line_span,
+                            constructor_span: type_name_span,
+                            constructor_scheme: Scheme::from(constructor_type),
+                            constructor: FullyQualifiedProperName {
+                                module_name: (package_name.clone(), module_name.clone()),
+                                value: type_name,
+                            },
+                            field_names: ctor.field_names.clone(),
+                            type_constructors: ctor.type_constructors.clone(),
+                        },
+                        // Warn in the case of `import Foo (Bar, Bar)`
+                        |collision| {
+                            warnings.push(Warning::DuplicateConstructorImport {
+                                previous_import: collision.existing_value.constructor_span,
+                                duplicate_import: collision.new_value.constructor_span,
+                            });
+                        },
+                    );
                 } else {
                     return Err(TypeError::UnknownTypeImport {
                         span: type_name_span,
@@ -490,7 +554,32 @@ fn import_unqualified_list(
     Ok((imported_types, imported_constructors, imported_values))
 }
 
-fn requalify_type(ast_type: Type, package_name: &PackageName) -> Type {
+/// The name of an exported type, as it was declared.
+///
+/// Re-exported types need to keep their original name, otherwise they wouldn't
+/// unify with the same type imported from the module that declared it.
+fn canonical_type_name(
+    package_name: &Option<PackageName>,
+    module_name: &ModuleName,
+    type_name: &ProperName,
+    exported_type: &ModuleExportsType,
+) -> FullyQualifiedProperName {
+    let (declared_package_name, declared_module_name) = exported_type
+        .declared_in
+        .clone()
+        .unwrap_or_else(|| (None, module_name.clone()));
+    FullyQualifiedProperName {
+        module_name: (
+            declared_package_name.or_else(|| package_name.clone()),
+            declared_module_name,
+        ),
+        value: type_name.clone(),
+    }
+}
+
+/// Types exported from another package refer to that package's own types as
+/// belonging to the "current" package, this makes them refer to `package_name`.
+pub fn requalify_type(ast_type: Type, package_name: &PackageName) -> Type {
     match ast_type {
         Type::Constructor {
             canonical_value:
@@ -546,5 +635,22 @@ fn requalify_type(ast_type: Type, package_name: &PackageName) -> Type {
             return_type: Box::new(requalify_type(return_type, package_name)),
         },
         Type::PrimConstructor(prim_type) => Type::PrimConstructor(prim_type),
+        Type::Record { fields } => Type::Record {
+            fields: fields
+                .into_iter()
+                .map(|(label, field_type)| (label, requalify_type(field_type, package_name)))
+                .collect(),
+        },
+        Type::Tuple { elements } => Type::Tuple {
+            elements: unsafe {
+                NonEmpty::new_unchecked(
+                    elements
+                        .iter()
+                        .cloned()
+                        .map(|element| requalify_type(element, package_name))
+                        .collect(),
+                )
+            },
+        },
     }
 }
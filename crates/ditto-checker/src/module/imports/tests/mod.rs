@@ -1,5 +1,12 @@
 mod macros;
-use crate::{module::tests::macros::assert_module_err, TypeError, Warning};
+use crate::{
+    module::{
+        check_module_with_options,
+        tests::macros::{assert_module_err, parse_and_check_module},
+        CheckOptions, Everything,
+    },
+    TypeError, Warning,
+};
 use macros::*;
 
 #[test]
@@ -19,6 +26,77 @@ fn it_handles_value_imports() {
     );
 }
 
+#[test]
+fn it_only_imports_qualified_names_without_an_import_list() {
+    assert_modules_ok!(
+        r#" 
+        module Test exports (..);
+        import Data.Stuff as S;
+        five : S.Five = S.Five;
+        also_five : Int = S.five;
+        "#,
+        warnings = [],
+        [r#" 
+        module Data.Stuff exports (Five(..), five);
+        type Five = Five;
+        five = 5;
+        "#],
+    );
+
+    assert_modules_err!(
+        r#" 
+        module Test exports (..);
+        import Data.Stuff as S;
+        also_five : Int = five;
+        "#,
+        error = TypeError::UnknownVariable { .. },
+        [r#" 
+        module Data.Stuff exports (five);
+        five = 5;
+        "#],
+    );
+
+    assert_modules_err!(
+        r#" 
+        module Test exports (..);
+        import Data.Stuff as S;
+        five = Five;
+        "#,
+        error = TypeError::UnknownConstructor { .. },
+        [r#" 
+        module Data.Stuff exports (Five(..));
+        type Five = Five;
+        "#],
+    );
+
+    assert_modules_err!(
+        r#" 
+        module Test exports (..);
+        import Data.Stuff as S;
+        five : Five = S.Five;
+        "#,
+        error = TypeError::UnknownTypeConstructor { .. },
+        [r#" 
+        module Data.Stuff exports (Five(..));
+        type Five = Five;
+        "#],
+    );
+
+    // The alias isn't a module name
+    assert_modules_err!(
+        r#" 
+        module Test exports (..);
+        import Data.Stuff as S;
+        also_five : Int = Stuff.five;
+        "#,
+        error = TypeError::UnknownVariable { .. },
+        [r#" 
+        module Data.Stuff exports (five);
+        five = 5;
+        "#],
+    );
+}
+
 #[test]
 fn it_handles_type_imports() {
     assert_modules_ok!(
@@ -35,6 +113,79 @@ fn it_handles_type_imports() {
     );
 }
 
+#[test]
+fn it_handles_constructor_imports() {
+    assert_modules_ok!(
+        r#" 
+        module Test exports (..);
+        import Data.Maybe (Maybe, Just);
+        maybe_five : Maybe(Int) = Just(5);
+        "#,
+        warnings = [],
+        [r#" 
+        module Data.Maybe exports (Maybe, Just);
+        type Maybe(a) = Just(a) | Nothing;
+        "#],
+    );
+
+    // Only exported constructors are brought into scope with `(..)`
+    assert_modules_ok!(
+        r#" 
+        module Test exports (..);
+        import Data.Maybe (Maybe(..));
+        maybe_five : Maybe(Int) = Just(5);
+        "#,
+        warnings = [],
+        [r#" 
+        module Data.Maybe exports (Maybe, Just);
+        type Maybe(a) = Just(a) | Nothing;
+        "#],
+    );
+}
+
+#[test]
+fn it_checks_exhaustiveness_of_partially_imported_types() {
+    assert_modules_err!(
+        r#" 
+        module Test exports (..);
+        import Data.Maybe (Maybe, Just);
+        unwrap = (m: Maybe(Int)): Int -> match m with | Just(a) -> a end;
+        "#,
+        error = TypeError::MatchNotExhaustive { .. },
+        [r#" 
+        module Data.Maybe exports (Maybe(..));
+        type Maybe(a) = Just(a) | Nothing;
+        "#],
+    );
+
+    // Constructors that aren't exported still need to be covered
+    assert_modules_err!(
+        r#" 
+        module Test exports (..);
+        import Data.Maybe as M;
+        unwrap = (m: M.Maybe(Int)): Int -> match m with | M.Just(a) -> a end;
+        "#,
+        error = TypeError::MatchNotExhaustive { .. },
+        [r#" 
+        module Data.Maybe exports (Maybe, Just);
+        type Maybe(a) = Just(a) | Nothing;
+        "#],
+    );
+
+    assert_modules_ok!(
+        r#" 
+        module Test exports (..);
+        import Data.Maybe (Maybe, Just);
+        unwrap = (m: Maybe(Int)): Int -> match m with | Just(a) -> a | _ -> 0 end;
+        "#,
+        warnings = [],
+        [r#" 
+        module Data.Maybe exports (Maybe, Just);
+        type Maybe(a) = Just(a) | Nothing;
+        "#],
+    );
+}
+
 #[test]
 fn it_warns_as_expected() {
     assert_modules_ok!(
@@ -65,6 +216,19 @@ fn it_warns_as_expected() {
         "#],
     );
 
+    assert_modules_ok!(
+        r#"
+        module Test exports (..);
+        import Data.Maybe (Maybe, Just, Just);
+        five : Maybe(Int) = Just(5);
+        "#,
+        warnings = [Warning::DuplicateConstructorImport { .. }],
+        [r#"
+        module Data.Maybe exports (Maybe(..));
+        type Maybe(a) = Just(a) | Nothing;
+        "#],
+    );
+
     assert_modules_ok!(
         r#"
         module Test exports (..);
@@ -105,6 +269,19 @@ fn it_warns_as_expected() {
         "#],
     );
 
+    assert_modules_ok!(
+        r#"
+        module Test exports (..);
+        import Data.Five as F;
+        my_five = 5;
+        "#,
+        warnings = [Warning::UnusedImport { .. }],
+        [r#"
+        module Data.Five exports (five);
+        five : Int = 5;
+        "#],
+    );
+
     assert_modules_ok!(
         r#"
         module Test exports (..);
@@ -180,6 +357,19 @@ fn it_errors_as_expected() {
         "#],
     );
 
+    assert_modules_err!(
+        r#" 
+        module Test exports (..);
+        import Data.Maybe (Maybe(..));
+        nothing : Maybe(Int) = Nothing;
+        "#,
+        error = TypeError::UnknownConstructor { .. },
+        [r#" 
+        module Data.Maybe exports (Maybe, Just);
+        type Maybe(a) = Just(a) | Nothing;
+        "#],
+    );
+
     assert_modules_err!(
         r#" 
         module Test exports (..);
@@ -281,3 +471,66 @@ fn it_errors_as_expected() {
         ],
     );
 }
+
+#[test]
+fn it_warns_for_single_use_imports_when_asked() {
+    let (module, _warnings) = parse_and_check_module!(
+        r#"
+        module Data.Stuff exports (Five(..), five, six);
+        type Five = Five;
+        five = 5;
+        six = 6;
+        "#
+    )
+    .unwrap();
+    let mut everything = Everything::default();
+    everything
+        .modules
+        .insert(module.module_name, module.exports);
+
+    let check = |source: &str, single_use_imports: bool| {
+        let cst_module = ditto_cst::Module::parse(source).unwrap();
+        let options = CheckOptions { single_use_imports };
+        let (_module, warnings) =
+            check_module_with_options(&everything, cst_module, options).unwrap();
+        warnings
+    };
+
+    let single_use = r#"
+        module Test exports (..);
+        import Data.Stuff as S;
+        my_five = S.five;
+        "#;
+    let warnings = check(single_use, true);
+    assert!(
+        matches!(warnings.as_slice(), [Warning::SingleUseImport { .. }]),
+        "{:#?}",
+        warnings
+    );
+    // Off by default
+    let warnings = check(single_use, false);
+    assert!(warnings.is_empty(), "{:#?}", warnings);
+
+    // Every name on the line is only used once
+    let single_use_names = r#"
+        module Test exports (..);
+        import Data.Stuff (five, six);
+        my_numbers = [five, six];
+        "#;
+    let warnings = check(single_use_names, true);
+    assert!(
+        matches!(warnings.as_slice(), [Warning::SingleUseImport { .. }]),
+        "{:#?}",
+        warnings
+    );
+
+    let multi_use = r#"
+        module Test exports (..);
+        import Data.Stuff as S (five);
+        my_five = five;
+        also_five = five;
+        another_five : S.Five = S.Five;
+        "#;
+    let warnings = check(multi_use, true);
+    assert!(warnings.is_empty(), "{:#?}", warnings);
+}
@@ -2,12 +2,14 @@
 pub(crate) mod tests;
 
 mod common;
+mod escaped_type_variables;
 mod exports;
 mod foreign_value_declarations;
 mod imports;
 mod type_declarations;
 mod value_declarations;
 
+use escaped_type_variables::*;
 use exports::*;
 use foreign_value_declarations::*;
 use imports::*;
@@ -17,6 +19,7 @@ use value_declarations::*;
 
 use crate::{
     kindchecker::{self, merge_references},
+    module::common::{check_attribute, ATTRIBUTE_NO_OPTIMIZE},
     result::{Result, Warning, Warnings},
     typechecker,
 };
@@ -27,15 +30,39 @@ use ditto_ast::{
 use ditto_cst as cst;
 use std::collections::HashMap;
 
+/// Every attribute that a module header can have.
+static MODULE_ATTRIBUTES: &[&str] = &[ATTRIBUTE_NO_OPTIMIZE];
+
+/// Lints that are off by default, as they're more a matter of taste.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CheckOptions {
+    /// Report [Warning::SingleUseImport]s.
+    pub single_use_imports: bool,
+}
+
 /// Type-check, kind-check and lint a CST module.
 pub fn check_module(
     everything: &Everything,
     cst_module: cst::Module,
+) -> Result<(Module, Warnings)> {
+    check_module_with_options(everything, cst_module, CheckOptions::default())
+}
+
+/// Like [check_module], but with opt-in lints.
+pub fn check_module_with_options(
+    everything: &Everything,
+    cst_module: cst::Module,
+    options: CheckOptions,
 ) -> Result<(Module, Warnings)> {
     let mut warnings = Warnings::new();
 
+    for attribute in cst_module.header.attributes.iter() {
+        check_attribute(attribute, MODULE_ATTRIBUTES)?;
+    }
+    let no_optimize = cst_module.header.has_attribute(ATTRIBUTE_NO_OPTIMIZE);
     let module_name = ModuleName::from(cst_module.header.module_name);
 
+    let imported_modules = imported_modules(&cst_module.imports);
     let (imported_types, imported_constructors, imported_values, more_warnings) =
         extract_imports(everything, cst_module.imports)?;
 
@@ -60,6 +87,8 @@ pub fn check_module(
                 typechecker::EnvConstructor::ImportedConstructor {
                     constructor: imported_constructor.constructor,
                     constructor_scheme: imported_constructor.constructor_scheme,
+                    field_names: imported_constructor.field_names,
+                    type_constructors: imported_constructor.type_constructors,
                 },
             )
         },
@@ -131,18 +160,23 @@ pub fn check_module(
     let mut typechecker_env = typechecker::Env::default();
 
     let (foreign_value_declarations, more_type_references, more_warnings) =
-        kindcheck_foreign_value_declarations(&kindchecker_env.types, foreign_value_declarations)?;
+        kindcheck_foreign_value_declarations(
+            everything,
+            &kindchecker_env.types,
+            foreign_value_declarations,
+        )?;
 
     type_references = merge_references(type_references, more_type_references);
     warnings.extend(more_warnings);
 
-    for (span, name, foreign_type) in foreign_value_declarations.clone() {
+    for (span, name, foreign_name, foreign_type) in foreign_value_declarations.clone() {
         typechecker_env.values.insert(
             unqualified(name.clone()),
             typechecker::EnvValue::ForeignVariable {
                 span,
                 variable_scheme: typechecker::Scheme::from(foreign_type),
                 variable: name,
+                foreign_name,
             },
         );
     }
@@ -157,6 +191,7 @@ pub fn check_module(
             typechecker::EnvConstructor::ModuleConstructor {
                 constructor: proper_name.clone(),
                 constructor_scheme: typechecker_env.generalize(constructor.get_type()),
+                field_names: constructor.field_names.clone(),
             },
         );
     }
@@ -194,7 +229,13 @@ pub fn check_module(
         }
     }
 
-    let (module, more_warnings) = add_exports(
+    if cfg!(debug_assertions) {
+        check_escaped_type_variables(&typechecker_env, &values)?;
+    }
+
+    let (module, reexported_import_lines, more_warnings) = add_exports(
+        everything,
+        &imported_modules,
         cst_module.header.exports,
         Module {
             module_name,
@@ -203,6 +244,7 @@ pub fn check_module(
             constructors,
             values,
             values_toposort,
+            no_optimize,
         },
     )?;
     warnings.extend(more_warnings);
@@ -219,7 +261,7 @@ pub fn check_module(
     }
 
     // Check for unused foreign values
-    for (span, name, _foreign_type) in foreign_value_declarations {
+    for (span, name, _foreign_name, _foreign_type) in foreign_value_declarations {
         if !value_references.contains_key(&unqualified(name)) {
             warnings.push(Warning::UnusedForeignValue { span });
         }
@@ -266,32 +308,44 @@ pub fn check_module(
 
     // Check for unused imports
     // TODO check for any unused _unqualified_ imports specifically.
-    let mut import_usages: HashMap<Span, bool> = HashMap::new();
+    //
+    // Each import line is tracked by the most that any one of its names is
+    // referenced, so a line is only unused (or single-use) if all its names are.
+    let mut import_usages: HashMap<Span, usize> = HashMap::new();
+    let mut record_uses = |import_line_span: Span, uses: usize| {
+        let max_uses = import_usages.entry(import_line_span).or_default();
+        *max_uses = (*max_uses).max(uses);
+    };
     for (type_name, imported_type) in imported_types.0 {
-        let span = imported_type.import_line_span;
-        let used = type_references.contains_key(&type_name);
-        let current = import_usages.remove(&span);
-        import_usages.insert(span, current.unwrap_or(false) || used);
+        let uses = type_references.get(&type_name).copied().unwrap_or(0);
+        record_uses(imported_type.import_line_span, uses);
     }
     for (constructor_name, imported_constructor) in imported_constructors.0 {
-        let span = imported_constructor.import_line_span;
-        let used = constructor_references.contains_key(&constructor_name);
-        let current = import_usages.remove(&span);
-        import_usages.insert(span, current.unwrap_or(false) || used);
+        let uses = constructor_references
+            .get(&constructor_name)
+            .copied()
+            .unwrap_or(0);
+        record_uses(imported_constructor.import_line_span, uses);
     }
     for (qualified_name, imported_value) in imported_values.0 {
-        let span = imported_value.import_line_span;
-        let used = value_references.contains_key(&qualified_name);
-        let current = import_usages.remove(&span);
-        import_usages.insert(span, current.unwrap_or(false) || used);
+        let uses = value_references.get(&qualified_name).copied().unwrap_or(0);
+        record_uses(imported_value.import_line_span, uses);
     }
-    warnings.extend(import_usages.into_iter().filter_map(|(span, used)| {
-        if !used {
+    warnings.extend(import_usages.into_iter().filter_map(|(span, uses)| {
+        if reexported_import_lines.contains(&span) {
+            None
+        } else if uses == 0 {
             Some(Warning::UnusedImport { span })
+        } else if uses == 1 && options.single_use_imports {
+            Some(Warning::SingleUseImport { span })
         } else {
             None
         }
     }));
 
+    // Warnings are collected in whatever order things were checked (or hashed),
+    // so put them in source order
+    warnings.sort_by_key(Warning::sort_key);
+
     Ok((module, warnings))
 }
@@ -0,0 +1,134 @@
+//! Support for `-- expect-warning: <code>` annotations in test sources.
+//!
+//! An annotation expects a warning with the given code to be reported on the
+//! next line of code (blank lines and further annotations are skipped, so
+//! several warnings can be expected for the same line).
+use crate::{Warning, Warnings};
+
+const ANNOTATION_PREFIX: &str = "-- expect-warning:";
+
+/// `(line, code)` pairs, where lines are numbered from 1.
+type LineCodes<'a> = Vec<(usize, &'a str)>;
+
+/// Whether `source` contains any `expect-warning` annotations.
+pub(crate) fn has_annotations(source: &str) -> bool {
+    source
+        .lines()
+        .any(|line| line.trim().starts_with(ANNOTATION_PREFIX))
+}
+
+/// Panics unless `warnings` are exactly those annotated in `source`.
+pub(crate) fn assert_expected_warnings(source: &str, warnings: &Warnings) {
+    let mut expected = parse_annotations(source);
+    let mut actual: LineCodes = warnings
+        .iter()
+        .map(|warning| {
+            (
+                line_number(source, warning.get_span().start_offset),
+                warning.code(),
+            )
+        })
+        .collect();
+    expected.sort_unstable();
+    actual.sort_unstable();
+
+    let unexpected = difference(&actual, &expected);
+    let missing = difference(&expected, &actual);
+    if unexpected.is_empty() && missing.is_empty() {
+        return;
+    }
+    let mut message = String::from("warnings didn't match `expect-warning` annotations\n");
+    for (heading, line_codes) in [("unexpected", unexpected), ("missing", missing)] {
+        if !line_codes.is_empty() {
+            message.push_str(&format!("{}:\n", heading));
+            for (line, code) in line_codes {
+                message.push_str(&format!("    line {}: {}\n", line, code));
+            }
+        }
+    }
+    message.push_str(&format!("{:#?}", warnings));
+    panic!("{}", message);
+}
+
+fn parse_annotations(source: &str) -> LineCodes {
+    let mut expected = Vec::new();
+    let mut pending = Vec::new();
+    for (index, line) in source.lines().enumerate() {
+        let line = line.trim();
+        if let Some(code) = line.strip_prefix(ANNOTATION_PREFIX) {
+            let code = code.trim();
+            assert!(
+                Warning::CODES.contains(&code),
+                "unknown warning code in annotation on line {}: {:?}",
+                index + 1,
+                code
+            );
+            pending.push(code);
+        } else if !line.is_empty() {
+            expected.extend(pending.drain(..).map(|code| (index + 1, code)));
+        }
+    }
+    assert!(
+        pending.is_empty(),
+        "`expect-warning` annotations must precede a line of code: {:?}",
+        pending
+    );
+    expected
+}
+
+fn line_number(source: &str, offset: usize) -> usize {
+    source[..offset].matches('\n').count() + 1
+}
+
+/// Elements of `lhs` that aren't matched by an element of `rhs` (both sorted).
+fn difference<'a>(lhs: &[(usize, &'a str)], rhs: &[(usize, &'a str)]) -> LineCodes<'a> {
+    let mut rhs = rhs.iter().peekable();
+    let mut result = Vec::new();
+    for element in lhs {
+        while rhs.next_if(|other| *other < element).is_some() {}
+        if rhs.next_if(|other| *other == element).is_none() {
+            result.push(*element);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_parses_annotations() {
+        let source = r#"
+        -- expect-warning: unused-value-declaration
+        a = 5;
+
+        -- expect-warning: unused-function-binder
+
+        -- expect-warning: unused-value-declaration
+        b = (x) -> 5;
+        "#;
+        assert_eq!(
+            parse_annotations(source),
+            vec![
+                (3, "unused-value-declaration"),
+                (8, "unused-function-binder"),
+                (8, "unused-value-declaration")
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "unknown warning code")]
+    fn it_rejects_unknown_codes() {
+        parse_annotations("-- expect-warning: not-a-warning\na = 5;");
+    }
+
+    #[test]
+    fn it_diffs_line_codes() {
+        let lhs = vec![(1, "a"), (1, "a"), (2, "b"), (3, "c")];
+        let rhs = vec![(1, "a"), (3, "c"), (4, "d")];
+        assert_eq!(difference(&lhs, &rhs), vec![(1, "a"), (2, "b")]);
+        assert_eq!(difference(&rhs, &lhs), vec![(4, "d")]);
+    }
+}
@@ -11,6 +11,19 @@ macro_rules! assert_module_ok {
     }};
 }
 
+/// Check a module, asserting that it produces exactly the warnings annotated
+/// with `-- expect-warning: <code>` comments.
+macro_rules! assert_module_warnings {
+    ($source:expr) => {{
+        let source = $source;
+        let result = $crate::module::tests::macros::parse_and_check_module!(source);
+        assert!(matches!(result, Ok(_)), "{:#?}", result.unwrap_err());
+        let (module, warnings) = result.unwrap();
+        $crate::module::tests::expect_warning::assert_expected_warnings(source, &warnings);
+        module
+    }};
+}
+
 macro_rules! assert_module_err {
     ($source:expr, $err:pat_param) => {{
         let result = $crate::module::tests::macros::parse_and_check_module!($source);
@@ -41,4 +54,5 @@ macro_rules! parse_and_check_module {
 
 pub(crate) use assert_module_err;
 pub(crate) use assert_module_ok;
+pub(crate) use assert_module_warnings;
 pub(crate) use parse_and_check_module;
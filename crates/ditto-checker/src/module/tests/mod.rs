@@ -1 +1,73 @@
+pub(crate) mod expect_warning;
 pub(crate) mod macros;
+
+use crate::module::Everything;
+use macros::parse_and_check_module;
+
+#[test]
+fn it_reports_warnings_in_source_order() {
+    let mut everything = Everything::default();
+    for source in [
+        "module Data.A exports (a); a = 1;",
+        "module Data.B exports (b); b = 2;",
+        "module Data.C exports (c); c = 3;",
+        "module Data.D exports (d); d = 4;",
+    ] {
+        let (module, _warnings) = parse_and_check_module!(source).unwrap();
+        everything
+            .modules
+            .insert(module.module_name, module.exports);
+    }
+    let source = r#"
+        module Test exports (..);
+        import Data.A;
+        import Data.B (b);
+        import Data.C;
+        import Data.D;
+        first = (a, unused) -> a;
+        five = b;
+        second = (x, y) -> x;
+        third = (z) -> let unused = 5; in z;
+        "#;
+    let (_module, warnings) = parse_and_check_module!(source, &everything).unwrap();
+    let reported = warnings
+        .iter()
+        .map(|warning| {
+            let start = warning.get_span().start_offset;
+            (source[..start].matches('\n').count() + 1, warning.code())
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        reported,
+        vec![
+            (3, "unused-import"),
+            (5, "unused-import"),
+            (6, "unused-import"),
+            (7, "unused-function-binder"),
+            (9, "unused-function-binder"),
+            (10, "unused-let-binder"),
+        ]
+    );
+}
+
+#[test]
+fn it_checks_module_attributes() {
+    let (module, _warnings) =
+        parse_and_check_module!("@no_optimize module Test exports (..); five = 5;").unwrap();
+    assert!(module.no_optimize);
+
+    let (module, _warnings) =
+        parse_and_check_module!("module Test exports (..); five = 5;").unwrap();
+    assert!(!module.no_optimize);
+
+    let result = parse_and_check_module!("@inline module Test exports (..); five = 5;");
+    assert!(
+        matches!(
+            &result,
+            Err(crate::TypeError::UnknownAttribute { attribute, expected, .. })
+                if attribute == "inline" && expected == &["@no_optimize"]
+        ),
+        "{:#?}",
+        result
+    );
+}
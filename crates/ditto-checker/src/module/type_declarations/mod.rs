@@ -6,7 +6,7 @@ use crate::{
         self, merge_references, Env, EnvType, EnvTypeVariable, EnvTypes, State, TypeReferences,
     },
     module::common::extract_doc_comments,
-    result::{Result, TypeError, Warnings},
+    result::{RecordKind, Result, TypeError, Warnings},
     supply::Supply,
 };
 use ditto_ast::{
@@ -518,10 +518,43 @@ fn check_constructor(
     let constructor_name = ProperName::from(cst_constructor_name);
 
     let mut fields = Vec::new();
-    if let Some(cst_fields) = cst_fields {
-        for cst_type in cst_fields.value.into_iter() {
-            let field = kindchecker::check(env, state, Kind::Type, cst_type)?;
-            fields.push(field);
+    let mut field_names = None;
+    match cst_fields {
+        None => {}
+        Some(cst::ConstructorFields::Positional(cst_fields)) => {
+            for cst_type in cst_fields.value.into_iter() {
+                let field = kindchecker::check(env, state, Kind::Type, cst_type)?;
+                fields.push(field);
+            }
+        }
+        Some(
+            cst::ConstructorFields::Labelled(cst::Parens {
+                value: cst_fields, ..
+            })
+            | cst::ConstructorFields::Named(cst::Braces {
+                value: cst_fields, ..
+            }),
+        ) => {
+            let mut names = Vec::new();
+            let mut labels_seen = HashMap::new();
+            for cst_field in cst_fields.into_iter() {
+                let span = cst_field.label.get_span();
+                let label = Name::from(cst_field.label);
+                if let Some(previous_field) = labels_seen.remove(&label) {
+                    return Err(TypeError::DuplicateRecordField {
+                        previous_field,
+                        duplicate_field: span,
+                        record_kind: RecordKind::Type,
+                        values: None,
+                    });
+                }
+                labels_seen.insert(label.clone(), span);
+                let field =
+                    kindchecker::check(env, state, Kind::Type, cst_field.type_annotation.1)?;
+                fields.push(field);
+                names.push(label);
+            }
+            field_names = Some(names);
         }
     }
 
@@ -532,6 +565,7 @@ fn check_constructor(
             doc_position,
             constructor_name_span,
             fields,
+            field_names,
             return_type,
             return_type_name,
         },
@@ -584,7 +618,7 @@ fn toposort_type_declarations(
             .iter_constructors()
             .for_each(|constructor| {
                 if let Some(fields) = constructor.fields {
-                    fields.value.iter().for_each(|field| {
+                    fields.types().for_each(|field| {
                         get_connected_nodes_type_rec(field, nodes, accum);
                     })
                 }
@@ -630,7 +664,13 @@ fn toposort_type_declarations(
                     accum.insert(node.clone());
                 }
             }
-            Variable { .. } => {}
+            Variable { .. } | Wildcard(_) => {}
+            Record(braces) => braces.value.iter().for_each(|field| {
+                get_connected_nodes_type_rec(&field.type_annotation.1, nodes, accum);
+            }),
+            Tuple(parens) => parens.value.iter().for_each(|element| {
+                get_connected_nodes_type_rec(element, nodes, accum);
+            }),
         };
     }
 }
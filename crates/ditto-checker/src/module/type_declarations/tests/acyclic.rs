@@ -78,4 +78,5 @@ fn it_errors_as_expected() {
         "type Foo(a, a) = Foo(a)",
         DuplicateTypeDeclarationVariable { .. }
     );
+    assert_type_declaration_error!("type Box = Box(_)", UnexpectedTypeWildcard { .. });
 }
@@ -1,6 +1,7 @@
 mod acyclic;
 mod cyclic;
 pub(self) mod macros;
+mod record_constructors;
 mod toposort;
 
 use crate::{module::tests::macros::assert_module_err, TypeError};
@@ -0,0 +1,325 @@
+use super::macros::*;
+use crate::{
+    module::{
+        tests::macros::{assert_module_err, assert_module_ok, parse_and_check_module},
+        Everything,
+    },
+    TypeError::*,
+};
+
+#[test]
+fn it_typechecks_record_constructors() {
+    assert_type_declaration!(
+        "type Person = Person { name: String, age: Int }",
+        ("Person", "Type"),
+        [("Person", "(String, Int) -> Person")]
+    );
+    assert_type_declaration!(
+        "type Shape = Circle { radius: Float } | Square(Float)",
+        ("Shape", "Type"),
+        [
+            ("Circle", "(Float) -> Shape"),
+            ("Square", "(Float) -> Shape")
+        ]
+    );
+    assert_module_ok!(
+        r#"
+        module Test exports (..);
+
+        type Person = Person { name: String, age: Int };
+        type Wrapper(a) = Wrapper { value: a, label: String };
+
+        alice: Person = Person { name = "Alice", age = 30 };
+        bob: Person = Person { age = 25, name = "Bob" };
+        wrapped: Wrapper(Bool) = Wrapper { label = "yes", value = true };
+        wrap = (a) -> Wrapper { value = a, label = "" };
+        positional: Person = Person("Carol", 40);
+        "#
+    );
+}
+
+#[test]
+fn it_typechecks_labelled_constructor_fields() {
+    assert_type_declaration!(
+        "type User = User(name: String, age: Int)",
+        ("User", "Type"),
+        [("User", "(String, Int) -> User")]
+    );
+    let module = assert_module_ok!(
+        r#"
+        module Test exports (..);
+
+        type User = User(name: String, age: Int);
+
+        alice: User = User("Alice", 30);
+        bob: User = User { age = 25, name = "Bob" };
+        "#
+    );
+    let field_names = module
+        .constructors
+        .values()
+        .find_map(|constructor| constructor.field_names.as_ref())
+        .unwrap();
+    assert_eq!(
+        field_names
+            .iter()
+            .map(|name| name.0.as_str())
+            .collect::<Vec<_>>(),
+        vec!["name", "age"]
+    );
+    assert_type_declaration_error!(
+        "type User = User(name: String, name: Int)",
+        DuplicateRecordField { .. }
+    );
+}
+
+#[test]
+fn it_errors_for_bad_record_constructors() {
+    assert_type_declaration_error!(
+        "type Person = Person { name: String, name: Int }",
+        DuplicateRecordField { .. }
+    );
+    assert_module_err!(
+        r#"
+        module Test exports (..);
+        type Person = Person { name: String, age: Int };
+        alice = Person { name = "Alice" };
+        "#,
+        MissingRecordFields { .. }
+    );
+    assert_module_err!(
+        r#"
+        module Test exports (..);
+        type Person = Person { name: String, age: Int };
+        alice = Person { name = "Alice", age = 30, email = "" };
+        "#,
+        UnknownRecordField { .. }
+    );
+    assert_module_err!(
+        r#"
+        module Test exports (..);
+        type Person = Person { name: String, age: Int };
+        alice = Person { name = "Alice", age = 30, name = "Bob" };
+        "#,
+        DuplicateRecordField { .. }
+    );
+    assert_module_err!(
+        r#"
+        module Test exports (..);
+        type Person = Person { name: String, age: Int };
+        alice = Person { name = 30, age = "Alice" };
+        "#,
+        TypesNotEqual { .. }
+    );
+    assert_module_err!(
+        r#"
+        module Test exports (..);
+        type Maybe(a) = Just(a) | Nothing;
+        five = Just { value = 5 };
+        "#,
+        NotARecordConstructor { .. }
+    );
+    assert_module_err!(
+        r#"
+        module Test exports (..);
+        alice = Person { name = "Alice" };
+        "#,
+        UnknownConstructor { .. }
+    );
+}
+
+#[test]
+fn it_typechecks_record_constructor_patterns() {
+    assert_module_ok!(
+        r#"
+        module Test exports (..);
+
+        type Person = Person { name: String, age: Int };
+        type Wrapper(a) = Wrapper { value: a, label: String };
+
+        get_name = (person: Person): String ->
+            match person with
+            | Person { name = name, .. } -> name
+            end;
+
+        describe = (person: Person): (String, Int) ->
+            match person with
+            | Person { age = age, name = name } -> (name, age)
+            end;
+
+        unwrap = (wrapper: Wrapper(a)): a ->
+            match wrapper with
+            | Wrapper { value = value, .. } -> value
+            end;
+        "#
+    );
+    assert_module_ok!(
+        r#"
+        module Test exports (..);
+
+        type Role = Admin | Guest;
+        type User = User(name: String, role: Role);
+
+        is_admin = (user: User): Bool ->
+            match user with
+            | User { role = Admin, .. } -> true
+            | User { role = Guest, .. } -> false
+            end;
+        "#,
+        []
+    );
+}
+
+#[test]
+fn it_checks_record_constructor_pattern_exhaustiveness() {
+    assert_module_ok!(
+        r#"
+        module Test exports (..);
+
+        type Maybe(a) = Just(a) | Nothing;
+        type Shape = Circle { radius: Maybe(Float) } | Square(Float);
+
+        radius = (shape: Shape): Float ->
+            match shape with
+            | Circle { radius = Just(radius) } -> radius
+            | Circle { radius = Nothing } -> 0.0
+            | Square(_) -> 0.0
+            end;
+        "#
+    );
+    assert_module_err!(
+        r#"
+        module Test exports (..);
+
+        type Maybe(a) = Just(a) | Nothing;
+        type Shape = Circle { radius: Maybe(Float) } | Square(Float);
+
+        radius = (shape: Shape): Float ->
+            match shape with
+            | Circle { radius = Just(radius) } -> radius
+            | Square(_) -> 0.0
+            end;
+        "#,
+        MatchNotExhaustive { .. }
+    );
+}
+
+#[test]
+fn it_errors_for_bad_record_constructor_patterns() {
+    assert_module_err!(
+        r#"
+        module Test exports (..);
+        type Person = Person { name: String, age: Int };
+        get_name = (person: Person) -> match person with | Person { name = n } -> n end;
+        "#,
+        MissingRecordFields { .. }
+    );
+    assert_module_err!(
+        r#"
+        module Test exports (..);
+        type Person = Person { name: String, age: Int };
+        get_name = (person: Person) -> match person with | Person { email = e, .. } -> e end;
+        "#,
+        UnknownRecordField { .. }
+    );
+    assert_module_err!(
+        r#"
+        module Test exports (..);
+        type Person = Person { name: String, age: Int };
+        get_name = (person: Person) ->
+            match person with
+            | Person { name = a, name = b, .. } -> a
+            end;
+        "#,
+        DuplicateRecordField { .. }
+    );
+    assert_module_err!(
+        r#"
+        module Test exports (..);
+        type Maybe(a) = Just(a) | Nothing;
+        get = (maybe: Maybe(Int)) -> match maybe with | Just { value = v } -> v | _ -> 0 end;
+        "#,
+        NotARecordConstructor { .. }
+    );
+}
+
+#[test]
+fn it_typechecks_field_access() {
+    assert_module_ok!(
+        r#"
+        module Test exports (..);
+
+        type Person = Person { name: String, age: Int };
+        type User = User(name: String, admin: Bool);
+        type Wrapper(a) = Wrapper { value: a, label: String };
+
+        alice = Person { name = "Alice", age = 30 };
+        alice_name: String = alice.name;
+        alice_age: Int = alice.age;
+
+        is_admin = (user: User): Bool -> user.admin;
+
+        unwrap = (wrapper: Wrapper(a)): a -> wrapper.value;
+        five: Int = Wrapper { value = 5, label = "five" }.value;
+        "#
+    );
+
+    let module = assert_module_ok!(
+        r#"
+        module People exports (Person(..));
+        type Person = Person { name: String, age: Int };
+        "#
+    );
+    let mut everything = Everything::default();
+    everything
+        .modules
+        .insert(module.module_name.clone(), module.exports);
+    let result = parse_and_check_module!(
+        r#"
+        module Test exports (..);
+        import People (Person(..));
+        get_name = (person: Person): String -> person.name;
+        "#,
+        &everything
+    );
+    assert!(matches!(result, Ok(_)), "{:#?}", result.unwrap_err());
+}
+
+#[test]
+fn it_errors_for_bad_field_access() {
+    assert_module_err!(
+        r#"
+        module Test exports (..);
+        type Person = Person { name: String, age: Int };
+        email = (person: Person) -> person.email;
+        "#,
+        UnknownRecordField {
+            constructor: Some(_),
+            ..
+        }
+    );
+    assert_module_err!(
+        r#"
+        module Test exports (..);
+        type Shape = Circle { radius: Float } | Rectangle { width: Float, height: Float };
+        radius = (shape: Shape) -> shape.radius;
+        "#,
+        NotARecord { .. }
+    );
+    assert_module_err!(
+        r#"
+        module Test exports (..);
+        type Box = Box(Int);
+        unbox = (box: Box) -> box.value;
+        "#,
+        NotARecord { .. }
+    );
+    assert_module_err!(
+        r#"
+        module Test exports (..);
+        type Person = Person { name: String, age: Int };
+        age: String = Person { name = "Alice", age = 30 }.age;
+        "#,
+        TypesNotEqual { .. }
+    );
+}
@@ -3,21 +3,27 @@ mod tests;
 
 use crate::{
     kindchecker::{self, EnvTypeVariables, TypeReferences},
-    module::common::extract_doc_comments,
+    module::common::{
+        check_attribute, extract_doc_comments, ATTRIBUTE_INLINE, ATTRIBUTE_NO_INLINE,
+        ATTRIBUTE_NO_OPTIMIZE,
+    },
     result::{Result, TypeError, Warnings},
     supply::Supply,
     typechecker::{
-        self, merge_references, pre_ast, ConstructorReferences, Env, EnvValue, State,
-        ValueReferences,
+        self, check_wildcard_solved, cst_type_has_wildcards, cst_type_wildcard, merge_references,
+        pre_ast, ConstructorReferences, Env, EnvValue, Scheme, State, ValueReferences,
     },
 };
 use ditto_ast::{
     graph::{toposort, toposort_deterministic, Scc},
-    unqualified, ModuleValue, Name, Span,
+    unqualified, ModuleValue, Name, Span, ValueAttributes,
 };
 use ditto_cst as cst;
 use std::collections::{HashMap, HashSet};
 
+/// Every attribute that a value declaration can have.
+static VALUE_ATTRIBUTES: &[&str] = &[ATTRIBUTE_NO_INLINE, ATTRIBUTE_INLINE, ATTRIBUTE_NO_OPTIMIZE];
+
 #[allow(clippy::type_complexity)]
 pub fn typecheck_value_declarations(
     env_types: &kindchecker::EnvTypes,
@@ -32,7 +38,13 @@ pub fn typecheck_value_declarations(
 )> {
     // Need to check there aren't duplicate names before we toposort
     let mut declarations_seen: HashMap<_, Span> = HashMap::new();
-    for cst::ValueDeclaration { name, .. } in cst_value_declarations.iter() {
+    for cst::ValueDeclaration {
+        attributes, name, ..
+    } in cst_value_declarations.iter()
+    {
+        for attribute in attributes {
+            check_attribute(attribute, VALUE_ATTRIBUTES)?;
+        }
         let span = name.get_span();
         let name_string = name.0.value.clone();
         if let Some(previous) = declarations_seen.remove(&name_string) {
@@ -192,126 +204,142 @@ fn typecheck_cyclic_value_declarations(
     let mut pre_module_values = Vec::new();
     let mut type_references = TypeReferences::new();
 
-    for cst::ValueDeclaration {
-        name: cst_name,
-        type_annotation,
-        expression: cst_expression,
-        ..
-    } in cst_value_declarations
-    {
-        if let Some(type_annotation) = type_annotation {
-            let (expression, expression_type, more_warnings, more_type_references, new_supply) =
-                pre_ast::Expression::from_cst_annotated(
-                    &kindchecker::Env {
-                        types: env_types.clone(),
-                        type_variables: EnvTypeVariables::new(),
-                    },
-                    supply,
-                    type_annotation,
-                    cst_expression,
-                )?;
-
-            supply = new_supply;
-            type_references = merge_references(type_references, more_type_references);
-            warnings.extend(more_warnings);
-
-            let span = cst_name.get_span();
-            let doc_comments = extract_doc_comments(&cst_name.0);
-            let name_span = cst_name.get_span();
-            let name = Name::from(cst_name);
-
-            let env = Env {
-                values: env_values.clone(),
-                constructors: env.constructors.clone(),
-            };
-            env_values.insert(
-                unqualified(name.clone()),
-                EnvValue::ModuleValue {
-                    span,
-                    variable_scheme: env.generalize(expression_type),
-                    variable: name.clone(),
-                },
-            );
-
-            pre_module_values.push((doc_comments, name, name_span, expression));
-        } else {
-            let (expr, more_warnings, more_type_references, new_supply) =
-                pre_ast::Expression::from_cst(
-                    &kindchecker::Env {
-                        types: env_types.clone(),
-                        type_variables: EnvTypeVariables::new(),
-                    },
-                    supply,
-                    cst_expression,
-                )?;
-
-            supply = new_supply;
-            type_references = merge_references(type_references, more_type_references);
-            warnings.extend(more_warnings);
-
-            let span = cst_name.get_span();
-            let doc_comments = extract_doc_comments(&cst_name.0);
-            let name_span = cst_name.get_span();
-            let name = Name::from(cst_name);
-
-            let env = Env {
-                values: env_values.clone(),
-                constructors: env.constructors.clone(),
+    for cst_value_declaration in cst_value_declarations {
+        let attributes = value_attributes(&cst_value_declaration);
+        let doc_comments = value_declaration_doc_comments(&cst_value_declaration);
+        let cst::ValueDeclaration {
+            name: cst_name,
+            type_annotation,
+            expression: cst_expression,
+            ..
+        } = cst_value_declaration;
+        let kindchecker_env = kindchecker::Env {
+            types: env_types.clone(),
+            type_variables: EnvTypeVariables::new(),
+        };
+        let span = cst_name.get_span();
+        let name_span = cst_name.get_span();
+        let name = Name::from(cst_name);
+
+        let wildcard_span = type_annotation
+            .as_ref()
+            .and_then(|type_annotation| cst_type_wildcard(&type_annotation.1));
+        let (expression, expected_type, variable_scheme) =
+            if let Some(type_annotation) = type_annotation {
+                let has_wildcards = cst_type_has_wildcards(&type_annotation.1);
+                let (expression, expected_type, more_warnings, more_type_references, new_supply) =
+                    pre_ast::Expression::from_cst_annotated(
+                        &kindchecker_env,
+                        supply,
+                        type_annotation,
+                        cst_expression,
+                    )?;
+                supply = new_supply;
+                type_references = merge_references(type_references, more_type_references);
+                warnings.extend(more_warnings);
+
+                // Annotated values can be used polymorphically within the cycle,
+                // unless a wildcard leaves part of the type to be inferred.
+                let variable_scheme = if has_wildcards {
+                    Scheme {
+                        forall: HashSet::new(),
+                        signature: expected_type.clone(),
+                    }
+                } else {
+                    env.generalize(expected_type.clone())
+                };
+                (expression, expected_type, variable_scheme)
+            } else {
+                let (expression, more_warnings, more_type_references, new_supply) =
+                    pre_ast::Expression::from_cst(&kindchecker_env, supply, cst_expression)?;
+                supply = new_supply;
+                type_references = merge_references(type_references, more_type_references);
+                warnings.extend(more_warnings);
+
+                // Unannotated values are monomorphic until the whole cycle is checked,
+                // so that every use is unified with the eventual type.
+                let expected_type = supply.fresh_type();
+                let variable_scheme = Scheme {
+                    forall: HashSet::new(),
+                    signature: expected_type.clone(),
+                };
+                (expression, expected_type, variable_scheme)
             };
-            env_values.insert(
-                unqualified(name.clone()),
-                EnvValue::ModuleValue {
-                    span,
-                    // REVIEW we can probably shortcut this generalization logic?
-                    variable_scheme: env.generalize(supply.fresh_type()),
-                    variable: name.clone(),
-                },
-            );
-
-            pre_module_values.push((doc_comments, name, name_span, expr));
-        }
+
+        env_values.insert(
+            unqualified(name.clone()),
+            EnvValue::ModuleValue {
+                span,
+                variable_scheme,
+                variable: name.clone(),
+            },
+        );
+        pre_module_values.push((
+            doc_comments,
+            attributes,
+            name,
+            name_span,
+            wildcard_span,
+            expected_type,
+            expression,
+        ));
     }
 
     let env = Env {
-        values: env_values.clone(),
+        values: env_values,
         constructors: env.constructors.clone(),
     };
 
+    // The whole cycle shares a substitution, which is only applied once every
+    // declaration has been checked.
+    let mut state = State {
+        supply,
+        ..State::default()
+    };
     let mut module_values = Vec::new();
-    let mut value_references = ValueReferences::new();
-    let mut constructor_references = ConstructorReferences::new();
-
-    for (doc_comments, name, name_span, expr) in pre_module_values {
-        let mut state = State {
-            supply,
-            ..State::default()
-        };
-        let expression = typechecker::infer(&env, &mut state, expr)?;
-        let State {
-            substitution,
-            warnings: more_warnings,
-            value_references: new_value_references,
-            constructor_references: new_constructor_references,
-            supply: new_supply,
-            ..
-        } = state;
-
-        warnings.extend(more_warnings);
-        value_references = merge_references(value_references, new_value_references);
-        constructor_references =
-            merge_references(constructor_references, new_constructor_references);
-
-        supply = new_supply;
-        let expression = substitution.apply_expression(expression);
+    for (doc_comments, attributes, name, name_span, wildcard_span, expected_type, expr) in
+        pre_module_values
+    {
+        let expression = typechecker::check(&env, &mut state, expected_type, expr)?;
         module_values.push((
+            doc_comments,
+            attributes,
             name,
-            ModuleValue {
-                doc_comments,
-                name_span,
-                expression,
-            },
+            name_span,
+            wildcard_span,
+            expression,
         ));
     }
+
+    let State {
+        substitution,
+        warnings: more_warnings,
+        value_references,
+        constructor_references,
+        supply,
+        holes,
+    } = state;
+    typechecker::check_holes(&substitution, &supply, holes)?;
+    warnings.extend(more_warnings);
+
+    let module_values = module_values
+        .into_iter()
+        .map(
+            |(doc_comments, attributes, name, name_span, wildcard_span, expression)| {
+                let expression = substitution.apply_expression(expression);
+                check_wildcard_solved(wildcard_span, &expression.get_type())?;
+                Ok((
+                    name,
+                    ModuleValue {
+                        doc_comments,
+                        name_span,
+                        expression,
+                        attributes,
+                    },
+                ))
+            },
+        )
+        .collect::<Result<_>>()?;
     Ok((
         module_values,
         value_references,
@@ -334,6 +362,8 @@ fn typecheck_value_declaration(
     TypeReferences,
     Warnings,
 )> {
+    let attributes = value_attributes(&cst_value_declaration);
+    let doc_comments = value_declaration_doc_comments(&cst_value_declaration);
     let cst::ValueDeclaration {
         name,
         type_annotation,
@@ -347,8 +377,6 @@ fn typecheck_value_declaration(
     let (expression, value_references, constructor_references, type_references, warnings, _supply) =
         typechecker::typecheck_with(&kindchecker_env, env, supply, type_annotation, expression)?;
 
-    let doc_comments = extract_doc_comments(&name.0);
-
     let name_span = name.get_span();
     let name = Name::from(name);
     Ok((
@@ -357,6 +385,7 @@ fn typecheck_value_declaration(
             doc_comments,
             name_span,
             expression,
+            attributes,
         },
         value_references,
         constructor_references,
@@ -365,6 +394,22 @@ fn typecheck_value_declaration(
     ))
 }
 
+/// Documentation comments lead the first attribute, if there are any.
+fn value_declaration_doc_comments(value_declaration: &cst::ValueDeclaration) -> Vec<String> {
+    match value_declaration.attributes.first() {
+        Some(attribute) => extract_doc_comments(&attribute.at.0),
+        None => extract_doc_comments(&value_declaration.name.0),
+    }
+}
+
+fn value_attributes(value_declaration: &cst::ValueDeclaration) -> ValueAttributes {
+    ValueAttributes {
+        no_inline: value_declaration.has_attribute(ATTRIBUTE_NO_INLINE),
+        inline: value_declaration.has_attribute(ATTRIBUTE_INLINE),
+        no_optimize: value_declaration.has_attribute(ATTRIBUTE_NO_OPTIMIZE),
+    }
+}
+
 fn toposort_value_declarations(
     cst_value_declarations: Vec<cst::ValueDeclaration>,
 ) -> Vec<Scc<cst::ValueDeclaration>> {
@@ -435,7 +480,10 @@ fn toposort_value_declarations(
                         .difference(
                             &parameters
                                 .iter()
-                                .map(|(param, _)| param.0.value.clone())
+                                .filter_map(|(binder, _)| match binder {
+                                    cst::FunctionBinder::Name(name) => Some(name.0.value.clone()),
+                                    cst::FunctionBinder::Wildcard(_) => None,
+                                })
                                 .collect(),
                         )
                         .cloned()
@@ -455,6 +503,35 @@ fn toposort_value_declarations(
                 get_connected_nodes_rec(true_clause, nodes, accum);
                 get_connected_nodes_rec(false_clause, nodes, accum);
             }
+            Expression::Let {
+                head_declaration,
+                tail_declarations,
+                expression,
+                ..
+            } => {
+                let mut nodes = nodes.clone();
+                for declaration in
+                    std::iter::once(head_declaration.as_ref()).chain(tail_declarations)
+                {
+                    get_connected_nodes_rec(&declaration.expression, &nodes, accum);
+                    nodes.remove(&declaration.name.0.value);
+                }
+                get_connected_nodes_rec(expression, &nodes, accum);
+            }
+            Expression::Match {
+                expression,
+                head_arm,
+                tail_arms,
+                ..
+            } => {
+                get_connected_nodes_rec(expression, nodes, accum);
+                for arm in std::iter::once(head_arm.as_ref()).chain(tail_arms) {
+                    let mut pattern_names = Nodes::new();
+                    get_pattern_names(&arm.pattern, &mut pattern_names);
+                    let nodes = nodes.difference(&pattern_names).cloned().collect();
+                    get_connected_nodes_rec(&arm.expression, &nodes, accum);
+                }
+            }
             Expression::Array(elements) => {
                 if let Some(ref elements) = elements.value {
                     elements.iter().for_each(|element| {
@@ -462,17 +539,64 @@ fn toposort_value_declarations(
                     })
                 }
             }
+            Expression::Tuple(parens) => parens.value.iter().for_each(|element| {
+                get_connected_nodes_rec(element, nodes, accum);
+            }),
             Expression::Parens(parens) => {
                 get_connected_nodes_rec(&parens.value, nodes, accum);
             }
+            Expression::RecordAccess { target, .. } => {
+                get_connected_nodes_rec(target, nodes, accum);
+            }
+            Expression::Negate { expression, .. } => {
+                get_connected_nodes_rec(expression, nodes, accum);
+            }
+            Expression::BinOp { lhs, rhs, .. } => {
+                get_connected_nodes_rec(lhs, nodes, accum);
+                get_connected_nodes_rec(rhs, nodes, accum);
+            }
+            Expression::RecordConstructor { fields, .. } | Expression::Record(fields) => {
+                fields.value.iter().for_each(|field| {
+                    get_connected_nodes_rec(&field.value, nodes, accum);
+                })
+            }
             // noop
             Expression::Constructor(_qualified_proper_name) => {}
             Expression::String(_) => {}
+            Expression::Char(_) => {}
             Expression::Int(_) => {}
             Expression::Float(_) => {}
+            Expression::InlineJs { .. } => {}
             Expression::True(_) => {}
             Expression::False(_) => {}
             Expression::Unit(_) => {}
+            Expression::Hole(_) => {}
+        }
+    }
+
+    fn get_pattern_names(pattern: &cst::Pattern, accum: &mut Nodes) {
+        match pattern {
+            cst::Pattern::Constructor {
+                arguments: Some(arguments),
+                ..
+            } => arguments.value.iter().for_each(|argument| {
+                get_pattern_names(argument, accum);
+            }),
+            cst::Pattern::Constructor {
+                arguments: None, ..
+            } => {}
+            cst::Pattern::RecordConstructor { fields, .. } => {
+                fields.value.fields.iter().for_each(|field| {
+                    get_pattern_names(&field.pattern, accum);
+                })
+            }
+            cst::Pattern::Tuple(parens) => parens.value.iter().for_each(|element| {
+                get_pattern_names(element, accum);
+            }),
+            cst::Pattern::Variable { name } => {
+                accum.insert(name.0.value.clone());
+            }
+            cst::Pattern::Wildcard(_) => {}
         }
     }
 }
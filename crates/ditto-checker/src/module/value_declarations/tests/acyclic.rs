@@ -10,7 +10,25 @@ fn it_typechecks_as_expected() {
     assert_value_declaration!("id : (a) -> a = (a) -> a", "id", "(a) -> a");
 }
 
+#[test]
+fn it_infers_wildcards() {
+    assert_value_declaration!("five : _ = 5", "five", "Int");
+    assert_value_declaration!("xs : Array(_) = [1, 2, 3]", "xs", "Array(Int)");
+    assert_value_declaration!(
+        "singleton : (_) -> Array(_) = (x: Int) -> [x]",
+        "singleton",
+        "(Int) -> Array(Int)"
+    );
+    assert_value_declaration!(
+        "nested : { a: Array(Array(_)) } = { a = [[true]] }",
+        "nested",
+        "{ a: Array(Array(Bool)) }"
+    );
+}
+
 #[test]
 fn it_errors_as_expected() {
     assert_value_declaration_error!("foo : a = true", TypesNotEqual { .. });
+    assert_value_declaration_error!("xs : Array(_) = [1, 2.0]", TypesNotEqual { .. });
+    assert_value_declaration_error!("f : (_) -> Int = (x: Int) -> true", TypesNotEqual { .. });
 }
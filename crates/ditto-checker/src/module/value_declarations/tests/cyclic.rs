@@ -2,6 +2,7 @@ use crate::{
     module::tests::macros::{assert_module_err, assert_module_ok},
     TypeError,
 };
+use ditto_ast::Name;
 
 #[test]
 fn it_typechecks_as_expected() {
@@ -12,6 +13,34 @@ fn it_typechecks_as_expected() {
         b = a;
     "#
     );
+
+    let module = assert_module_ok!(
+        r#"
+        module Test exports (..);
+        countdown = (xs) -> if true then xs else countdown([5]);
+    "#
+    );
+    assert_value_type(&module, "countdown", "(Array(Int)) -> Array(Int)");
+
+    let module = assert_module_ok!(
+        r#"
+        module Test exports (..);
+        is_even = (n: Int) -> if true then true else is_odd(n);
+        is_odd = (n) -> is_even(n);
+    "#
+    );
+    assert_value_type(&module, "is_even", "(Int) -> Bool");
+    assert_value_type(&module, "is_odd", "(Int) -> Bool");
+
+    let module = assert_module_ok!(
+        r#"
+        module Test exports (..);
+        ping = (n) -> if true then [n] else pong(n);
+        pong = (n) -> ping(n);
+    "#
+    );
+    // Both bodies are checked against a shared substitution
+    assert_eq!(value_type(&module, "ping"), value_type(&module, "pong"));
 }
 
 #[test]
@@ -24,4 +53,49 @@ fn it_errors_as_expected() {
     "#,
         TypeError::NotAFunction { .. }
     );
+    assert_module_err!(
+        r#"
+        module Test exports (..);
+        f = (n) -> [f(n), true];
+    "#,
+        TypeError::TypesNotEqual { .. }
+    );
+    assert_module_err!(
+        r#"
+        module Test exports (..);
+        one = () -> two();
+        two = () -> if one() then 5 else 6;
+    "#,
+        TypeError::TypesNotEqual { .. }
+    );
+    assert_module_err!(
+        r#"
+        module Test exports (..);
+        loop : _ = loop;
+    "#,
+        TypeError::AmbiguousTypeWildcard { .. }
+    );
+    // Wildcards keep an annotated value monomorphic within its cycle
+    assert_module_err!(
+        r#"
+        module Test exports (..);
+        id : (_) -> _ = (a) -> if flag() && id(5) == 5 then a else a;
+        flag = () -> id(true);
+    "#,
+        TypeError::TypesNotEqual { .. }
+    );
+}
+
+fn assert_value_type(module: &ditto_ast::Module, name: &str, want: &str) {
+    assert_eq!(value_type(module, name), want);
+}
+
+fn value_type(module: &ditto_ast::Module, name: &str) -> String {
+    module
+        .values
+        .get(&Name(name.to_owned()))
+        .unwrap_or_else(|| panic!("{} not found", name))
+        .expression
+        .get_type()
+        .debug_render()
 }
@@ -4,8 +4,10 @@ pub(self) mod macros;
 mod toposort;
 
 use crate::{
-    module::tests::macros::{assert_module_err, assert_module_ok},
-    TypeError, Warning,
+    module::tests::macros::{
+        assert_module_err, assert_module_ok, assert_module_warnings, parse_and_check_module,
+    },
+    TypeError,
 };
 
 #[test]
@@ -21,23 +23,104 @@ fn it_errors_for_duplicates() {
 }
 
 #[test]
-fn it_warns_for_unused() {
+fn it_errors_for_unknown_attributes() {
+    let result = parse_and_check_module!(
+        r#"
+        module Test exports (..);
+        @inline_please
+        five = 5;
+    "#
+    );
+    assert!(
+        matches!(
+            &result,
+            Err(TypeError::UnknownAttribute { attribute, .. }) if attribute == "inline_please"
+        ),
+        "{:#?}",
+        result
+    );
+}
+
+#[test]
+fn it_generalizes_values() {
+    assert_module_ok!(
+        r#"
+        module Test exports (..);
+        id = (a) -> a;
+        five : Int = id(5);
+        hello : String = id("hello");
+        both = [id(five), id(id)(5)];
+    "#
+    );
+
+    // Local bindings are generalized too
+    assert_module_ok!(
+        r#"
+        module Test exports (..);
+        pair = (n: Int, s: String) ->
+            let
+                id = (a) -> a;
+            in
+            { n = id(n), s = id(s) };
+    "#
+    );
+
+    // Recursive values are generalized once the whole cycle has been checked
     assert_module_ok!(
+        r#"
+        module Test exports (..);
+        loop = (a) -> loop(a);
+        int_loop : Int = loop(5);
+        string_loop : String = loop("five");
+    "#
+    );
+}
+
+#[test]
+fn it_warns_for_unused() {
+    assert_module_warnings!(
         r#"
         module Test exports (a);
         a : Bool = true;
+        -- expect-warning: unused-value-declaration
         b : Int = 5;
-    "#,
-        [Warning::UnusedValueDeclaration { .. }]
+    "#
     );
 
-    assert_module_ok!(
+    assert_module_warnings!(
         r#"
         module Test exports (a, c);
         a : Bool = true;
+        -- expect-warning: unused-value-declaration
         b : Int = 5;
         c = (b) -> b; -- not referencing `b` above, that is still unused
-    "#,
-        [Warning::UnusedValueDeclaration { .. }]
+    "#
+    );
+}
+
+#[test]
+fn it_warns_for_unused_binders() {
+    assert_module_warnings!(
+        r#"
+        module Test exports (..);
+        type Maybe(a) = Just(a) | Nothing;
+        first = (
+            a,
+            -- expect-warning: unused-function-binder
+            b,
+        ) -> a;
+        five =
+            -- expect-warning: unused-let-binder
+            let six = 6;
+            in 5;
+        is_just = (maybe: Maybe(Int)): Bool ->
+            match maybe with
+            -- expect-warning: unused-pattern-binder
+            | Just(x) -> true
+            | _ -> false
+            -- expect-warning: redundant-match-arm
+            | Nothing -> false
+            end;
+    "#
     );
 }
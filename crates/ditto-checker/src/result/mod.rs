@@ -1,7 +1,7 @@
 mod type_error;
 mod warnings;
 
-pub use type_error::{TypeError, TypeErrorReport};
+pub use type_error::{RecordKind, TypeError, TypeErrorReport, UnknownName};
 pub use warnings::{Warning, WarningReport, Warnings};
 
 /// Typechecking result.
@@ -15,8 +15,18 @@ mod tests {
     )]
     fn golden_warnings(input: &str) -> String {
         let module = ditto_cst::Module::parse(input).unwrap();
-        let (_, warnings) = crate::check_module(&mk_everything(), module).unwrap();
+        let (ast_module, warnings) = crate::check_module(&mk_everything(), module).unwrap();
         assert!(!warnings.is_empty());
+
+        // Golden tests that have been annotated must warn exactly as annotated
+        if crate::module::tests::expect_warning::has_annotations(input) {
+            crate::module::tests::expect_warning::assert_expected_warnings(input, &warnings);
+        }
+
+        // While we're here, make sure the AST spans are well-formed
+        for module_value in ast_module.values.values() {
+            assert_spans_nested(&module_value.expression);
+        }
         let warnings = warnings
             .into_iter()
             .map(|warning| warning.into_report())
@@ -27,14 +37,11 @@ mod tests {
         let round_tripped: Vec<crate::WarningReport> = serde_json::from_str(&*json).unwrap();
         assert_eq!(warnings, round_tripped);
 
+        let source = std::sync::Arc::new(input.to_owned());
         warnings
             .into_iter()
             .map(|warning| {
-                render_diagnostic(
-                    miette::Report::from(warning)
-                        .with_source_code(miette::NamedSource::new("golden", input.to_string()))
-                        .as_ref(),
-                )
+                render_diagnostic(warning.with_source_code("golden", source.clone()).as_ref())
             })
             .collect::<Vec<_>>()
             .join("\n")
@@ -47,10 +54,59 @@ mod tests {
     fn golden_type_errors(input: &str) -> String {
         let module = ditto_cst::Module::parse(input).unwrap();
         let type_error = crate::check_module(&mk_everything(), module).unwrap_err();
-        let type_error_report = type_error.into_report("golden", input.to_string());
+        let type_error_report =
+            type_error.into_report("golden", std::sync::Arc::new(input.to_owned()));
         render_diagnostic(&type_error_report)
     }
 
+    /// Assert that every span in the expression is well-formed, and contained
+    /// by the span of its parent.
+    ///
+    /// Desugaring can reorder sub-expressions, so unlike CST spans they aren't
+    /// necessarily in source order.
+    fn assert_spans_nested(expression: &ditto_ast::Expression) {
+        use ditto_ast::{visit, Expression, FunctionBinder, Pattern, Span};
+
+        struct SpanChecker(Vec<Span>);
+
+        impl SpanChecker {
+            fn check(&self, span: Span) {
+                assert!(span.is_well_formed(), "malformed span: {:?}", span);
+                if let Some(parent) = self.0.last() {
+                    assert!(
+                        parent.contains(&span),
+                        "{:?} isn't contained by {:?}",
+                        span,
+                        parent
+                    );
+                }
+            }
+        }
+
+        impl visit::Visitor for SpanChecker {
+            fn visit_expression(&mut self, expression: &Expression) {
+                let span = expression.get_span();
+                self.check(span);
+                self.0.push(span);
+                visit::walk_expression(self, expression);
+                self.0.pop();
+            }
+            fn visit_function_binder(&mut self, function_binder: &FunctionBinder) {
+                self.check(function_binder.get_span());
+                visit::walk_function_binder(self, function_binder)
+            }
+            fn visit_pattern(&mut self, pattern: &Pattern) {
+                let span = pattern.get_span();
+                self.check(span);
+                self.0.push(span);
+                visit::walk_pattern(self, pattern);
+                self.0.pop();
+            }
+        }
+
+        visit::Visitor::visit_expression(&mut SpanChecker(Vec::new()), expression);
+    }
+
     fn mk_everything() -> crate::Everything {
         let data_stuff = {
             let source = r#"
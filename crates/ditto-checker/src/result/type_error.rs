@@ -1,9 +1,8 @@
 use ditto_ast::{
-    Kind, ModuleName, Name, PackageName, ProperName, Qualified, QualifiedName, QualifiedProperName,
-    Span, Type,
+    Kind, ModuleName, Name, PackageName, ProperName, QualifiedName, QualifiedProperName, Span, Type,
 };
 use miette::{Diagnostic, NamedSource, SourceSpan};
-use std::collections::HashSet;
+use std::{collections::HashSet, sync::Arc};
 use thiserror::Error;
 
 /// A fatal error encountered during type-checking or kind-checking.
@@ -28,10 +27,45 @@ pub enum TypeError {
         span: Span,
         constructor: QualifiedProperName,
     },
+    UnexpectedTypeWildcard {
+        span: Span,
+    },
+    AmbiguousTypeWildcard {
+        span: Span,
+    },
+    UnknownForeignTypeConstructor {
+        span: Span,
+        constructor: QualifiedProperName,
+        suggested_import: Option<String>,
+    },
+    /// A foreign name that isn't a JavaScript identifier (e.g. `"foo-bar"`).
+    InvalidForeignName {
+        span: Span,
+        foreign_name: String,
+    },
     NotAFunction {
         span: Span,
         actual_type: Type,
     },
+    NotARecord {
+        span: Span,
+        actual_type: Type,
+    },
+    UnsupportedEquality {
+        span: Span,
+        operand_type: Type,
+    },
+    UnsupportedOrdering {
+        span: Span,
+        operand_type: Type,
+    },
+    UnsupportedNegation {
+        span: Span,
+        operand_type: Type,
+    },
+    InvalidCharEscape {
+        span: Span,
+    },
     TypeNotAFunction {
         span: Span,
         actual_kind: Kind,
@@ -85,6 +119,16 @@ pub enum TypeError {
         span: Span,
         type_name: ProperName,
     },
+    UnknownModuleExport {
+        span: Span,
+        module_name: ModuleName,
+    },
+    /// A re-exported name is already declared or re-exported by the current module.
+    ReexportConflict {
+        previous_binding: Span,
+        new_binding: Span,
+        name: String,
+    },
     UnknownValueImport {
         span: Span,
         name: Name,
@@ -110,10 +154,19 @@ pub enum TypeError {
         previous_binder: Span,
         duplicate_binder: Span,
     },
+    DuplicatePatternBinder {
+        previous_binder: Span,
+        duplicate_binder: Span,
+    },
     DuplicateValueDeclaration {
         previous_declaration: Span,
         duplicate_declaration: Span,
     },
+    UnknownAttribute {
+        span: Span,
+        attribute: String,
+        expected: Vec<String>,
+    },
     DuplicateTypeDeclaration {
         previous_declaration: Span,
         duplicate_declaration: Span,
@@ -126,6 +179,47 @@ pub enum TypeError {
         previous_variable: Span,
         duplicate_variable: Span,
     },
+    DuplicateRecordField {
+        previous_field: Span,
+        duplicate_field: Span,
+        record_kind: RecordKind,
+        /// The values of both fields, if this is a record literal.
+        values: Option<(Span, Span)>,
+    },
+    UnknownRecordField {
+        span: Span,
+        label: Name,
+        /// The record constructor, if this isn't an anonymous record.
+        constructor: Option<QualifiedProperName>,
+        labels_in_scope: HashSet<Name>,
+    },
+    MissingRecordFields {
+        span: Span,
+        constructor: QualifiedProperName,
+        missing: Vec<Name>,
+    },
+    NotARecordConstructor {
+        span: Span,
+        constructor: QualifiedProperName,
+    },
+    MatchNotExhaustive {
+        span: Span,
+        missing: Vec<ProperName>,
+    },
+    TypedHole {
+        span: Span,
+        name: Name,
+        expected_type: Type,
+        /// Values in scope that have the expected type.
+        candidates: Vec<QualifiedName>,
+    },
+    RecordFieldsMismatch {
+        span: Span,
+        /// Labels that were expected but not found.
+        missing: Vec<Name>,
+        /// Labels that were found but not expected.
+        extra: Vec<Name>,
+    },
     ReboundImportType {
         previous_binding: Span,
         new_binding: Span,
@@ -141,12 +235,144 @@ pub enum TypeError {
         new_binding: Span,
         variable: QualifiedName,
     },
+    /// A type variable that isn't quantified survived checking of a top-level
+    /// declaration. This is always a checker bug.
+    EscapedTypeVariable {
+        span: Span,
+        declaration: Name,
+        var: usize,
+    },
+}
+
+/// Where a record's fields are listed, for error messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordKind {
+    /// A record literal, e.g. `{ x = 1 }` or `Point { x = 1 }`.
+    Literal,
+    /// A record type, e.g. `{ x: Int }` or `type Point = Point { x: Int }`.
+    Type,
+    /// A record pattern, e.g. `Point { x = x, .. }`.
+    Pattern,
+}
+
+impl std::fmt::Display for RecordKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Literal => write!(f, "record literal"),
+            Self::Type => write!(f, "record type"),
+            Self::Pattern => write!(f, "record pattern"),
+        }
+    }
+}
+
+/// A name that isn't in scope, which might be fixed by importing something.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UnknownName {
+    /// An unknown variable, e.g. `with_default`.
+    Value(Name),
+    /// An unknown type, e.g. `Maybe`.
+    Type(ProperName),
+    /// An unknown constructor, e.g. `Just`.
+    Constructor(ProperName),
 }
 
 impl TypeError {
+    /// The name this error is about, if importing something could fix it.
+    ///
+    /// Qualified names are left out, as the module they refer to should already
+    /// be imported.
+    pub fn unknown_name(&self) -> Option<UnknownName> {
+        match self {
+            Self::UnknownVariable {
+                variable:
+                    QualifiedName {
+                        module_name: None,
+                        value,
+                    },
+                ..
+            } => Some(UnknownName::Value(value.clone())),
+            Self::UnknownTypeConstructor {
+                constructor:
+                    QualifiedProperName {
+                        module_name: None,
+                        value,
+                    },
+                ..
+            } => Some(UnknownName::Type(value.clone())),
+            Self::UnknownConstructor {
+                constructor:
+                    QualifiedProperName {
+                        module_name: None,
+                        value,
+                    },
+                ..
+            } => Some(UnknownName::Constructor(value.clone())),
+            _ => None,
+        }
+    }
+
+    /// Like [TypeError::into_report], but suggesting import lines that would
+    /// bring the [unknown name](TypeError::unknown_name) into scope.
+    pub fn into_report_with_imports(
+        self,
+        source_name: impl AsRef<str>,
+        source: Arc<String>,
+        imports: Vec<String>,
+    ) -> TypeErrorReport {
+        if imports.is_empty() {
+            return self.into_report(source_name, source);
+        }
+        let help = format!(
+            "try importing it:\n{}",
+            imports
+                .iter()
+                .map(|import| format!("`{}`", import))
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+        match self {
+            Self::UnknownVariable {
+                span,
+                variable:
+                    QualifiedName {
+                        module_name: None, ..
+                    },
+                ..
+            } => TypeErrorReport::UnknownVariableWithImports {
+                input: NamedSource::new(source_name, source),
+                location: span_to_source_span(span),
+                help,
+            },
+            Self::UnknownTypeConstructor {
+                span,
+                constructor:
+                    QualifiedProperName {
+                        module_name: None, ..
+                    },
+            } => TypeErrorReport::UnknownTypeConstructorWithImports {
+                input: NamedSource::new(source_name, source),
+                location: span_to_source_span(span),
+                help,
+            },
+            Self::UnknownConstructor {
+                span,
+                constructor:
+                    QualifiedProperName {
+                        module_name: None, ..
+                    },
+                ..
+            } => TypeErrorReport::UnknownConstructorWithImports {
+                input: NamedSource::new(source_name, source),
+                location: span_to_source_span(span),
+                help,
+            },
+            other => other.into_report(source_name, source),
+        }
+    }
+
     /// Convert a [TypeError] to a pretty error report.
-    pub fn into_report(self, source_name: impl AsRef<str>, source: String) -> TypeErrorReport {
-        let input = NamedSource::new(source_name, source);
+    pub fn into_report(self, source_name: impl AsRef<str>, source: Arc<String>) -> TypeErrorReport {
+        let input = NamedSource::new(source_name, source.clone());
         match self {
             Self::UnknownVariable {
                 span,
@@ -156,7 +382,7 @@ impl TypeError {
                 let location = span_to_source_span(span);
                 if names_in_scope.is_empty() {
                     TypeErrorReport::UnknownVariable { input, location }
-                } else if let Some(suggestion) = find_suggestion(variable, names_in_scope) {
+                } else if let Some(suggestion) = find_suggestion(&variable, names_in_scope) {
                     TypeErrorReport::UnknownVariableWithSuggestion {
                         input,
                         location,
@@ -174,7 +400,7 @@ impl TypeError {
                 let location = span_to_source_span(span);
                 if ctors_in_scope.is_empty() {
                     TypeErrorReport::UnknownConstructor { input, location }
-                } else if let Some(suggestion) = find_suggestion(constructor, ctors_in_scope) {
+                } else if let Some(suggestion) = find_suggestion(&constructor, ctors_in_scope) {
                     TypeErrorReport::UnknownConstructorWithSuggestion {
                         input,
                         location,
@@ -192,6 +418,36 @@ impl TypeError {
                 input,
                 location: span_to_source_span(span),
             },
+            Self::UnexpectedTypeWildcard { span } => TypeErrorReport::UnexpectedTypeWildcard {
+                input,
+                location: span_to_source_span(span),
+            },
+            Self::AmbiguousTypeWildcard { span } => TypeErrorReport::AmbiguousTypeWildcard {
+                input,
+                location: span_to_source_span(span),
+            },
+            Self::UnknownForeignTypeConstructor {
+                span,
+                suggested_import: Some(suggestion),
+                ..
+            } => TypeErrorReport::UnknownForeignTypeConstructorWithSuggestion {
+                input,
+                location: span_to_source_span(span),
+                suggestion,
+            },
+            Self::UnknownForeignTypeConstructor { span, .. } => {
+                TypeErrorReport::UnknownForeignTypeConstructor {
+                    input,
+                    location: span_to_source_span(span),
+                }
+            }
+            Self::InvalidForeignName { span, foreign_name } => {
+                TypeErrorReport::InvalidForeignName {
+                    input,
+                    location: span_to_source_span(span),
+                    foreign_name,
+                }
+            }
             Self::TypesNotEqual {
                 span,
                 expected,
@@ -244,6 +500,36 @@ impl TypeError {
                 location: span_to_source_span(span),
                 expression_type: actual_type.debug_render(),
             },
+            Self::NotARecord { span, actual_type } => TypeErrorReport::NotARecord {
+                input,
+                location: span_to_source_span(span),
+                expression_type: actual_type.debug_render(),
+            },
+            Self::UnsupportedEquality { span, operand_type } => {
+                TypeErrorReport::UnsupportedEquality {
+                    input,
+                    location: span_to_source_span(span),
+                    operand_type: operand_type.debug_render(),
+                }
+            }
+            Self::UnsupportedOrdering { span, operand_type } => {
+                TypeErrorReport::UnsupportedOrdering {
+                    input,
+                    location: span_to_source_span(span),
+                    operand_type: operand_type.debug_render(),
+                }
+            }
+            Self::UnsupportedNegation { span, operand_type } => {
+                TypeErrorReport::UnsupportedNegation {
+                    input,
+                    location: span_to_source_span(span),
+                    operand_type: operand_type.debug_render(),
+                }
+            }
+            Self::InvalidCharEscape { span } => TypeErrorReport::InvalidCharEscape {
+                input,
+                location: span_to_source_span(span),
+            },
             Self::TypeNotAFunction { span, .. } => TypeErrorReport::TypeNotAFunction {
                 input,
                 location: span_to_source_span(span),
@@ -282,6 +568,23 @@ impl TypeError {
                 input,
                 location: span_to_source_span(span),
             },
+            Self::UnknownModuleExport { span, module_name } => {
+                TypeErrorReport::UnknownModuleExport {
+                    input,
+                    location: span_to_source_span(span),
+                    module_name: module_name.to_string(),
+                }
+            }
+            Self::ReexportConflict {
+                previous_binding,
+                new_binding,
+                name,
+            } => TypeErrorReport::ReexportConflict {
+                input,
+                previous_binding: span_to_source_span(previous_binding),
+                new_binding: span_to_source_span(new_binding),
+                name,
+            },
             Self::UnknownValueImport { span, .. } => TypeErrorReport::UnknownValueImport {
                 input,
                 location: span_to_source_span(span),
@@ -323,6 +626,14 @@ impl TypeError {
                 previous_parameter: span_to_source_span(previous_binder),
                 shadowing_parameter: span_to_source_span(duplicate_binder),
             },
+            Self::DuplicatePatternBinder {
+                previous_binder,
+                duplicate_binder,
+            } => TypeErrorReport::DuplicatePatternBinder {
+                input,
+                previous_binder: span_to_source_span(previous_binder),
+                duplicate_binder: span_to_source_span(duplicate_binder),
+            },
             Self::DuplicateValueDeclaration {
                 previous_declaration,
                 duplicate_declaration,
@@ -331,6 +642,16 @@ impl TypeError {
                 previous_definition: span_to_source_span(previous_declaration),
                 duplicate_definition: span_to_source_span(duplicate_declaration),
             },
+            Self::UnknownAttribute {
+                span,
+                attribute,
+                expected,
+            } => TypeErrorReport::UnknownAttribute {
+                input,
+                location: span_to_source_span(span),
+                attribute,
+                expected: expected.join(", "),
+            },
             Self::DuplicateTypeDeclaration {
                 previous_declaration,
                 duplicate_declaration,
@@ -355,6 +676,141 @@ impl TypeError {
                 previous_variable: span_to_source_span(previous_variable),
                 duplicate_variable: span_to_source_span(duplicate_variable),
             },
+            Self::DuplicateRecordField {
+                previous_field,
+                duplicate_field,
+                record_kind,
+                values,
+            } => {
+                let source_text = |span: Span| &source[span.start_offset..span.end_offset];
+                let identical_values = values.map_or(false, |(previous_value, duplicate_value)| {
+                    source_text(previous_value) == source_text(duplicate_value)
+                });
+                let record_kind = record_kind.to_string();
+                let previous_field = span_to_source_span(previous_field);
+                let duplicate_field = span_to_source_span(duplicate_field);
+                if identical_values {
+                    TypeErrorReport::DuplicateRecordFieldWithIdenticalValues {
+                        input,
+                        previous_field,
+                        duplicate_field,
+                        record_kind,
+                    }
+                } else {
+                    TypeErrorReport::DuplicateRecordField {
+                        input,
+                        previous_field,
+                        duplicate_field,
+                        record_kind,
+                    }
+                }
+            }
+            Self::UnknownRecordField {
+                span,
+                label,
+                constructor,
+                labels_in_scope,
+            } => {
+                let location = span_to_source_span(span);
+                let record = constructor.map_or_else(
+                    || String::from("this record"),
+                    |constructor| format!("`{}`", constructor),
+                );
+                if let Some(suggestion) = find_suggestion(&label, labels_in_scope) {
+                    TypeErrorReport::UnknownRecordFieldWithSuggestion {
+                        input,
+                        location,
+                        label: label.to_string(),
+                        record,
+                        suggestion,
+                    }
+                } else {
+                    TypeErrorReport::UnknownRecordField {
+                        input,
+                        location,
+                        label: label.to_string(),
+                        record,
+                    }
+                }
+            }
+            Self::MissingRecordFields {
+                span,
+                constructor,
+                missing,
+            } => TypeErrorReport::MissingRecordFields {
+                input,
+                location: span_to_source_span(span),
+                constructor: constructor.to_string(),
+                missing: missing
+                    .iter()
+                    .map(|label| format!("`{}`", label))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            },
+            Self::NotARecordConstructor { span, constructor } => {
+                TypeErrorReport::NotARecordConstructor {
+                    input,
+                    location: span_to_source_span(span),
+                    constructor: constructor.to_string(),
+                }
+            }
+            Self::MatchNotExhaustive { span, missing } => TypeErrorReport::MatchNotExhaustive {
+                input,
+                location: span_to_source_span(span),
+                missing: missing
+                    .iter()
+                    .map(|constructor| format!("`{}`", constructor))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            },
+            Self::TypedHole {
+                span,
+                name,
+                expected_type,
+                candidates,
+            } => TypeErrorReport::TypedHole {
+                input,
+                location: span_to_source_span(span),
+                name: name.to_string(),
+                expected_type: expected_type.debug_render(),
+                help: if candidates.is_empty() {
+                    String::from("nothing in scope has this type")
+                } else {
+                    format!(
+                        "in scope with this type: {}",
+                        candidates
+                            .iter()
+                            .map(|candidate| format!("`{}`", candidate))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    )
+                },
+            },
+            Self::RecordFieldsMismatch {
+                span,
+                missing,
+                extra,
+            } => {
+                let render_labels = |labels: Vec<Name>| {
+                    labels
+                        .iter()
+                        .map(|label| format!("`{}`", label))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                };
+                let mut help = Vec::new();
+                if !missing.is_empty() {
+                    help.push(format!("missing {}", render_labels(missing)));
+                }
+                if !extra.is_empty() {
+                    help.push(format!("unexpected {}", render_labels(extra)));
+                }
+                TypeErrorReport::RecordFieldsMismatch {
+                    input,
+                    location: span_to_source_span(span),
+                    help: help.join("\n"),
+                }
+            }
             Self::ReboundImportType {
                 previous_binding,
                 new_binding,
@@ -385,6 +841,16 @@ impl TypeError {
                 new_binding: span_to_source_span(new_binding),
                 constructor_name: constructor_name.to_string(),
             },
+            Self::EscapedTypeVariable {
+                span,
+                declaration,
+                var,
+            } => TypeErrorReport::EscapedTypeVariable {
+                input,
+                location: span_to_source_span(span),
+                declaration: declaration.to_string(),
+                var,
+            },
         }
     }
 }
@@ -413,6 +879,15 @@ pub enum TypeErrorReport {
         location: SourceSpan,
         suggestion: String,
     },
+    #[error("unknown variable")]
+    #[diagnostic(severity(Error), help("{help}"))]
+    UnknownVariableWithImports {
+        #[source_code]
+        input: NamedSource,
+        #[label("not in scope")]
+        location: SourceSpan,
+        help: String,
+    },
     #[error("unknown constructor")]
     #[diagnostic(severity(Error))]
     UnknownConstructor {
@@ -430,6 +905,15 @@ pub enum TypeErrorReport {
         location: SourceSpan,
         suggestion: String,
     },
+    #[error("unknown constructor")]
+    #[diagnostic(severity(Error), help("{help}"))]
+    UnknownConstructorWithImports {
+        #[source_code]
+        input: NamedSource,
+        #[label("not in scope")]
+        location: SourceSpan,
+        help: String,
+    },
     #[error("unknown type variable")]
     #[diagnostic(severity(Error))]
     UnknownTypeVariable {
@@ -447,6 +931,66 @@ pub enum TypeErrorReport {
         #[label("not in scope")]
         location: SourceSpan,
     },
+    #[error("unknown type constructor")]
+    #[diagnostic(severity(Error), help("{help}"))]
+    UnknownTypeConstructorWithImports {
+        #[source_code]
+        input: NamedSource,
+        #[label("not in scope")]
+        location: SourceSpan,
+        help: String,
+    },
+    #[error("unexpected type wildcard")]
+    #[diagnostic(
+        severity(Error),
+        help("wildcards can only be used in value annotations")
+    )]
+    UnexpectedTypeWildcard {
+        #[source_code]
+        input: NamedSource,
+        #[label("type needed here")]
+        location: SourceSpan,
+    },
+    #[error("ambiguous type wildcard")]
+    #[diagnostic(severity(Error), help("try replacing `_` with a concrete type"))]
+    AmbiguousTypeWildcard {
+        #[source_code]
+        input: NamedSource,
+        #[label("couldn't infer this type")]
+        location: SourceSpan,
+    },
+    #[error("unknown type constructor in foreign type")]
+    #[diagnostic(severity(Error), help("foreign types must be fully resolvable"))]
+    UnknownForeignTypeConstructor {
+        #[source_code]
+        input: NamedSource,
+        #[label("not in scope")]
+        location: SourceSpan,
+    },
+    #[error("unknown type constructor in foreign type")]
+    #[diagnostic(
+        severity(Error),
+        help("foreign types must be fully resolvable\ntry adding `{suggestion}`?")
+    )]
+    UnknownForeignTypeConstructorWithSuggestion {
+        #[source_code]
+        input: NamedSource,
+        #[label("not in scope")]
+        location: SourceSpan,
+        suggestion: String,
+    },
+    #[error("invalid foreign name")]
+    #[diagnostic(
+        severity(Error),
+        help("foreign names need to be valid JavaScript identifiers")
+    )]
+    InvalidForeignName {
+        #[source_code]
+        input: NamedSource,
+        #[label("`{foreign_name}` can't be imported")]
+        location: SourceSpan,
+        foreign_name: String,
+    },
     #[error("types don't unify")]
     #[diagnostic(severity(Error), help("expected {expected}\ngot {actual}"))]
     UnificationError {
@@ -475,6 +1019,16 @@ pub enum TypeErrorReport {
         #[label("here")]
         location: SourceSpan,
     },
+    #[error("internal compiler error: type variable ${var} escaped from `{declaration}`")]
+    #[diagnostic(severity(Error), help("please report how you did this"))]
+    EscapedTypeVariable {
+        #[source_code]
+        input: NamedSource,
+        #[label("here")]
+        location: SourceSpan,
+        declaration: String,
+        var: usize,
+    },
     #[error("infinite kind")]
     #[diagnostic(severity(Error), help("please report how you did this"))]
     InfiniteKind {
@@ -522,6 +1076,16 @@ pub enum TypeErrorReport {
         #[label("can't be redefined here")]
         duplicate_definition: SourceSpan,
     },
+    #[error("unknown attribute `@{attribute}`")]
+    #[diagnostic(severity(Error), help("expected one of: {expected}"))]
+    UnknownAttribute {
+        #[source_code]
+        input: NamedSource,
+        #[label("not an attribute")]
+        location: SourceSpan,
+        attribute: String,
+        expected: String,
+    },
     #[error("expression isn't callable")]
     #[diagnostic(severity(Error), help("expression has type: {expression_type}"))]
     NotAFunction {
@@ -531,6 +1095,59 @@ pub enum TypeErrorReport {
         location: SourceSpan,
         expression_type: String,
     },
+    #[error("expression isn't a record")]
+    #[diagnostic(severity(Error), help("expression has type: {expression_type}"))]
+    NotARecord {
+        #[source_code]
+        input: NamedSource,
+        #[label("can't access fields of this")]
+        location: SourceSpan,
+        expression_type: String,
+    },
+    #[error("values of type `{operand_type}` can't be compared for equality")]
+    #[diagnostic(
+        severity(Error),
+        help("functions can't be compared, nor can values containing them")
+    )]
+    UnsupportedEquality {
+        #[source_code]
+        input: NamedSource,
+        #[label("can't compare these")]
+        location: SourceSpan,
+        operand_type: String,
+    },
+    #[error("values of type `{operand_type}` can't be ordered")]
+    #[diagnostic(
+        severity(Error),
+        help("only `Int`, `Float`, `String` and `Char` values can be ordered")
+    )]
+    UnsupportedOrdering {
+        #[source_code]
+        input: NamedSource,
+        #[label("can't order these")]
+        location: SourceSpan,
+        operand_type: String,
+    },
+    #[error("values of type `{operand_type}` can't be negated")]
+    #[diagnostic(severity(Error), help("only `Int` and `Float` values can be negated"))]
+    UnsupportedNegation {
+        #[source_code]
+        input: NamedSource,
+        #[label("can't negate this")]
+        location: SourceSpan,
+        operand_type: String,
+    },
+    #[error("invalid character escape")]
+    #[diagnostic(
+        severity(Error),
+        help("unicode escapes must be a code point up to 10FFFF, excluding surrogates (D800 to DFFF)")
+    )]
+    InvalidCharEscape {
+        #[source_code]
+        input: NamedSource,
+        #[label("not a character")]
+        location: SourceSpan,
+    },
     #[error("type isn't callable")]
     #[diagnostic(severity(Error))]
     TypeNotAFunction {
@@ -575,6 +1192,29 @@ pub enum TypeErrorReport {
         location: SourceSpan,
         // TODO suggestions?
     },
+    #[error("unknown module export")]
+    #[diagnostic(
+        severity(Error),
+        help("modules need to be imported to be re-exported, try `import {module_name};`")
+    )]
+    UnknownModuleExport {
+        #[source_code]
+        input: NamedSource,
+        #[label("this module isn't imported?")]
+        location: SourceSpan,
+        module_name: String,
+    },
+    #[error("`{name}` is already defined")]
+    #[diagnostic(severity(Error))]
+    ReexportConflict {
+        #[source_code]
+        input: NamedSource,
+        #[label("defined here")]
+        previous_binding: SourceSpan,
+        #[label("re-exported again here")]
+        new_binding: SourceSpan,
+        name: String,
+    },
     #[error("unknown value import")]
     #[diagnostic(severity(Error))]
     UnknownValueImport {
@@ -612,6 +1252,16 @@ pub enum TypeErrorReport {
         #[label("name can't be reused here")]
         shadowing_parameter: SourceSpan,
     },
+    #[error("duplicate pattern variable")]
+    #[diagnostic(severity(Error))]
+    DuplicatePatternBinder {
+        #[source_code]
+        input: NamedSource,
+        #[label("previously bound here")]
+        previous_binder: SourceSpan,
+        #[label("name can't be reused here")]
+        duplicate_binder: SourceSpan,
+    },
     #[error("duplicate type declaration")]
     #[diagnostic(severity(Error))]
     DuplicateTypeDeclaration {
@@ -642,6 +1292,100 @@ pub enum TypeErrorReport {
         #[label("can't be reintroduced here")]
         duplicate_variable: SourceSpan,
     },
+    #[error("duplicate field")]
+    #[diagnostic(severity(Error))]
+    DuplicateRecordField {
+        #[source_code]
+        input: NamedSource,
+        #[label("previously defined here")]
+        previous_field: SourceSpan,
+        #[label("can't be redefined in this {record_kind}")]
+        duplicate_field: SourceSpan,
+        record_kind: String,
+    },
+    #[error("duplicate field")]
+    #[diagnostic(
+        severity(Error),
+        help("both fields have identical values, so one of them can be removed")
+    )]
+    DuplicateRecordFieldWithIdenticalValues {
+        #[source_code]
+        input: NamedSource,
+        #[label("previously defined here")]
+        previous_field: SourceSpan,
+        #[label("can't be redefined in this {record_kind}")]
+        duplicate_field: SourceSpan,
+        record_kind: String,
+    },
+    #[error("unknown field `{label}`")]
+    #[diagnostic(severity(Error))]
+    UnknownRecordField {
+        #[source_code]
+        input: NamedSource,
+        #[label("not a field of {record}")]
+        location: SourceSpan,
+        label: String,
+        record: String,
+    },
+    #[error("unknown field `{label}`")]
+    #[diagnostic(severity(Error), help("did you mean `{suggestion}`?"))]
+    UnknownRecordFieldWithSuggestion {
+        #[source_code]
+        input: NamedSource,
+        #[label("not a field of {record}")]
+        location: SourceSpan,
+        label: String,
+        record: String,
+        suggestion: String,
+    },
+    #[error("missing fields for `{constructor}`")]
+    #[diagnostic(severity(Error), help("add {missing}"))]
+    MissingRecordFields {
+        #[source_code]
+        input: NamedSource,
+        #[label("here")]
+        location: SourceSpan,
+        constructor: String,
+        missing: String,
+    },
+    #[error("match isn't exhaustive")]
+    #[diagnostic(severity(Error), help("add arms for {missing}"))]
+    MatchNotExhaustive {
+        #[source_code]
+        input: NamedSource,
+        #[label("not all constructors are covered")]
+        location: SourceSpan,
+        missing: String,
+    },
+    #[error("hole `?{name}` has type `{expected_type}`")]
+    #[diagnostic(severity(Error), help("{help}"))]
+    TypedHole {
+        #[source_code]
+        input: NamedSource,
+        #[label("fill me in")]
+        location: SourceSpan,
+        name: String,
+        expected_type: String,
+        help: String,
+    },
+    #[error("record fields don't match")]
+    #[diagnostic(severity(Error), help("{help}"))]
+    RecordFieldsMismatch {
+        #[source_code]
+        input: NamedSource,
+        #[label("here")]
+        location: SourceSpan,
+        help: String,
+    },
+    #[error("`{constructor}` doesn't have named fields")]
+    #[diagnostic(severity(Error), help("try calling it like a function instead"))]
+    NotARecordConstructor {
+        #[source_code]
+        input: NamedSource,
+        #[label("used with named fields here")]
+        location: SourceSpan,
+        constructor: String,
+    },
     #[error("duplicate import")]
     #[diagnostic(severity(Error))]
     DuplicateImportLine {
@@ -699,13 +1443,13 @@ pub enum TypeErrorReport {
 }
 
 fn find_suggestion<T: std::fmt::Display>(
-    needle: Qualified<T>,
-    haystack: HashSet<Qualified<T>>,
+    needle: &T,
+    haystack: impl IntoIterator<Item = T>,
 ) -> Option<String> {
     // REVIEW this is quite rough and ready!
     let mut engine: simsearch::SimSearch<String> = simsearch::SimSearch::new();
-    for qualified in haystack {
-        engine.insert(qualified.to_string(), &qualified.to_string());
+    for item in haystack {
+        engine.insert(item.to_string(), &item.to_string());
     }
     let results = engine.search(&needle.to_string());
     results.first().cloned() // REVIEW arbitrarily taking the first result, can probably improve this?
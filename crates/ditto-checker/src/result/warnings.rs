@@ -1,6 +1,7 @@
 use ditto_ast::Span;
-use miette::{Diagnostic, SourceSpan};
+use miette::{Diagnostic, NamedSource, Report, SourceSpan};
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use thiserror::Error;
 
 /// A collection of [Warning]s.
@@ -18,6 +19,10 @@ pub enum Warning {
         previous_export: Span,
         duplicate_export: Span,
     },
+    DuplicateConstructorExport {
+        previous_export: Span,
+        duplicate_export: Span,
+    },
     DuplicateValueImport {
         previous_import: Span,
         duplicate_import: Span,
@@ -26,9 +31,29 @@ pub enum Warning {
         previous_import: Span,
         duplicate_import: Span,
     },
+    DuplicateConstructorImport {
+        previous_import: Span,
+        duplicate_import: Span,
+    },
     UnusedFunctionBinder {
         span: Span,
     },
+    UnusedLetBinder {
+        span: Span,
+    },
+    UnusedPatternBinder {
+        span: Span,
+    },
+    RedundantMatchArm {
+        span: Span,
+    },
+    UnreachableBranch {
+        span: Span,
+    },
+    ConstantComparison {
+        span: Span,
+        value: bool,
+    },
     UnusedValueDeclaration {
         span: Span,
     },
@@ -44,9 +69,121 @@ pub enum Warning {
     UnusedImport {
         span: Span,
     },
+    SingleUseImport {
+        span: Span,
+    },
+    PolymorphicForeignValue {
+        span: Span,
+    },
+    NoExports {
+        span: Span,
+    },
 }
 
 impl Warning {
+    /// Every possible [Warning::code].
+    pub const CODES: &'static [&'static str] = &[
+        "duplicate-value-export",
+        "duplicate-type-export",
+        "duplicate-constructor-export",
+        "duplicate-value-import",
+        "duplicate-type-import",
+        "duplicate-constructor-import",
+        "unused-function-binder",
+        "unused-let-binder",
+        "unused-pattern-binder",
+        "redundant-match-arm",
+        "unreachable-branch",
+        "constant-comparison",
+        "unused-value-declaration",
+        "unused-foreign-value",
+        "unused-type-declaration",
+        "unused-type-constructors",
+        "unused-import",
+        "single-use-import",
+        "polymorphic-foreign-value",
+        "no-exports",
+    ];
+
+    /// Codes that are opt-in, i.e. allowed unless configured otherwise.
+    pub const ALLOWED_BY_DEFAULT: &'static [&'static str] = &["single-use-import"];
+
+    /// A stable identifier for this kind of warning, which can be used to suppress it.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::DuplicateValueExport { .. } => "duplicate-value-export",
+            Self::DuplicateTypeExport { .. } => "duplicate-type-export",
+            Self::DuplicateConstructorExport { .. } => "duplicate-constructor-export",
+            Self::DuplicateValueImport { .. } => "duplicate-value-import",
+            Self::DuplicateTypeImport { .. } => "duplicate-type-import",
+            Self::DuplicateConstructorImport { .. } => "duplicate-constructor-import",
+            Self::UnusedFunctionBinder { .. } => "unused-function-binder",
+            Self::UnusedLetBinder { .. } => "unused-let-binder",
+            Self::UnusedPatternBinder { .. } => "unused-pattern-binder",
+            Self::RedundantMatchArm { .. } => "redundant-match-arm",
+            Self::UnreachableBranch { .. } => "unreachable-branch",
+            Self::ConstantComparison { .. } => "constant-comparison",
+            Self::UnusedValueDeclaration { .. } => "unused-value-declaration",
+            Self::UnusedForeignValue { .. } => "unused-foreign-value",
+            Self::UnusedTypeDeclaration { .. } => "unused-type-declaration",
+            Self::UnusedTypeConstructors { .. } => "unused-type-constructors",
+            Self::UnusedImport { .. } => "unused-import",
+            Self::SingleUseImport { .. } => "single-use-import",
+            Self::PolymorphicForeignValue { .. } => "polymorphic-foreign-value",
+            Self::NoExports { .. } => "no-exports",
+        }
+    }
+
+    /// The primary source span for this warning.
+    pub fn get_span(&self) -> Span {
+        match self {
+            Self::DuplicateValueExport {
+                duplicate_export, ..
+            }
+            | Self::DuplicateTypeExport {
+                duplicate_export, ..
+            }
+            | Self::DuplicateConstructorExport {
+                duplicate_export, ..
+            } => *duplicate_export,
+            Self::DuplicateValueImport {
+                duplicate_import, ..
+            }
+            | Self::DuplicateTypeImport {
+                duplicate_import, ..
+            }
+            | Self::DuplicateConstructorImport {
+                duplicate_import, ..
+            } => *duplicate_import,
+            Self::UnusedFunctionBinder { span }
+            | Self::UnusedLetBinder { span }
+            | Self::UnusedPatternBinder { span }
+            | Self::RedundantMatchArm { span }
+            | Self::UnreachableBranch { span }
+            | Self::UnusedValueDeclaration { span }
+            | Self::UnusedForeignValue { span }
+            | Self::UnusedTypeDeclaration { span }
+            | Self::UnusedTypeConstructors { span }
+            | Self::UnusedImport { span }
+            | Self::SingleUseImport { span }
+            | Self::PolymorphicForeignValue { span }
+            | Self::NoExports { span } => *span,
+            Self::ConstantComparison { span, .. } => *span,
+        }
+    }
+
+    /// Where this warning should be ordered relative to others: by primary span,
+    /// then by position in [Warning::CODES].
+    pub(crate) fn sort_key(&self) -> (usize, usize, usize) {
+        let span = self.get_span();
+        let code = self.code();
+        let discriminant = Self::CODES
+            .iter()
+            .position(|known_code| *known_code == code)
+            .unwrap_or(Self::CODES.len());
+        (span.start_offset, span.end_offset, discriminant)
+    }
+
     /// Convert a warning to a pretty report.
     pub fn into_report(self) -> WarningReport {
         match self {
@@ -64,6 +201,13 @@ impl Warning {
                 previous_export: span_to_source_span(previous_export),
                 duplicate_export: span_to_source_span(duplicate_export),
             },
+            Self::DuplicateConstructorExport {
+                previous_export,
+                duplicate_export,
+            } => WarningReport::DuplicateConstructorExport {
+                previous_export: span_to_source_span(previous_export),
+                duplicate_export: span_to_source_span(duplicate_export),
+            },
             Self::DuplicateValueImport {
                 previous_import,
                 duplicate_import,
@@ -78,9 +222,32 @@ impl Warning {
                 previous_import: span_to_source_span(previous_import),
                 duplicate_import: span_to_source_span(duplicate_import),
             },
+            Self::DuplicateConstructorImport {
+                previous_import,
+                duplicate_import,
+            } => WarningReport::DuplicateConstructorImport {
+                previous_import: span_to_source_span(previous_import),
+                duplicate_import: span_to_source_span(duplicate_import),
+            },
             Self::UnusedFunctionBinder { span } => WarningReport::UnusedFunctionBinder {
                 location: span_to_source_span(span),
             },
+            Self::UnusedLetBinder { span } => WarningReport::UnusedLetBinder {
+                location: span_to_source_span(span),
+            },
+            Self::UnusedPatternBinder { span } => WarningReport::UnusedPatternBinder {
+                location: span_to_source_span(span),
+            },
+            Self::RedundantMatchArm { span } => WarningReport::RedundantMatchArm {
+                location: span_to_source_span(span),
+            },
+            Self::UnreachableBranch { span } => WarningReport::UnreachableBranch {
+                location: span_to_source_span(span),
+            },
+            Self::ConstantComparison { span, value } => WarningReport::ConstantComparison {
+                location: span_to_source_span(span),
+                value,
+            },
             Self::UnusedValueDeclaration { span } => WarningReport::UnusedValueDeclaration {
                 location: span_to_source_span(span),
             },
@@ -96,6 +263,15 @@ impl Warning {
             Self::UnusedImport { span } => WarningReport::UnusedImport {
                 location: span_to_source_span(span),
             },
+            Self::SingleUseImport { span } => WarningReport::SingleUseImport {
+                location: span_to_source_span(span),
+            },
+            Self::PolymorphicForeignValue { span } => WarningReport::PolymorphicForeignValue {
+                location: span_to_source_span(span),
+            },
+            Self::NoExports { span } => WarningReport::NoExports {
+                location: span_to_source_span(span),
+            },
         }
     }
 }
@@ -127,6 +303,16 @@ pub enum WarningReport {
         #[serde(with = "SourceSpanDef")]
         duplicate_export: SourceSpan,
     },
+    #[error("duplicate constructor export")]
+    #[diagnostic(severity(Warning))]
+    DuplicateConstructorExport {
+        #[label("previously exported here")]
+        #[serde(with = "SourceSpanDef")]
+        previous_export: SourceSpan,
+        #[label("already exported")]
+        #[serde(with = "SourceSpanDef")]
+        duplicate_export: SourceSpan,
+    },
     #[error("duplicate value import")]
     #[diagnostic(severity(Warning))]
     DuplicateValueImport {
@@ -147,6 +333,16 @@ pub enum WarningReport {
         #[serde(with = "SourceSpanDef")]
         duplicate_import: SourceSpan,
     },
+    #[error("duplicate constructor import")]
+    #[diagnostic(severity(Warning))]
+    DuplicateConstructorImport {
+        #[label("previously imported here")]
+        #[serde(with = "SourceSpanDef")]
+        previous_import: SourceSpan,
+        #[label("already imported")]
+        #[serde(with = "SourceSpanDef")]
+        duplicate_import: SourceSpan,
+    },
     #[error("unused function binder")]
     #[diagnostic(severity(Warning))]
     UnusedFunctionBinder {
@@ -154,6 +350,45 @@ pub enum WarningReport {
         #[serde(with = "SourceSpanDef")]
         location: SourceSpan,
     },
+    #[error("unused let binder")]
+    #[diagnostic(severity(Warning))]
+    UnusedLetBinder {
+        #[label("this isn't used")]
+        #[serde(with = "SourceSpanDef")]
+        location: SourceSpan,
+    },
+    #[error("unused pattern variable")]
+    #[diagnostic(severity(Warning), help("try replacing it with `_`?"))]
+    UnusedPatternBinder {
+        #[label("this isn't used")]
+        #[serde(with = "SourceSpanDef")]
+        location: SourceSpan,
+    },
+    #[error("redundant match arm")]
+    #[diagnostic(severity(Warning))]
+    RedundantMatchArm {
+        #[label("this will never match")]
+        #[serde(with = "SourceSpanDef")]
+        location: SourceSpan,
+    },
+    #[error("unreachable branch")]
+    #[diagnostic(severity(Warning))]
+    UnreachableBranch {
+        #[label("this branch is never taken")]
+        #[serde(with = "SourceSpanDef")]
+        location: SourceSpan,
+    },
+    #[error("constant comparison")]
+    #[diagnostic(
+        severity(Warning),
+        help("the values being compared are built with different constructors")
+    )]
+    ConstantComparison {
+        #[label("this is always `{value}`")]
+        #[serde(with = "SourceSpanDef")]
+        location: SourceSpan,
+        value: bool,
+    },
     #[error("unused top-level value")]
     #[diagnostic(severity(Warning))]
     UnusedValueDeclaration {
@@ -189,6 +424,45 @@ pub enum WarningReport {
         #[serde(with = "SourceSpanDef")]
         location: SourceSpan,
     },
+    #[error("import is only used once")]
+    #[diagnostic(
+        severity(Warning),
+        help("try qualifying the reference, or inlining what it refers to?")
+    )]
+    SingleUseImport {
+        #[label("only referenced once")]
+        #[serde(with = "SourceSpanDef")]
+        location: SourceSpan,
+    },
+    #[error("polymorphic foreign value")]
+    #[diagnostic(
+        severity(Warning),
+        help("foreign values can't usually be trusted to be polymorphic")
+    )]
+    PolymorphicForeignValue {
+        #[label("type variable in foreign type")]
+        #[serde(with = "SourceSpanDef")]
+        location: SourceSpan,
+    },
+    #[error("module exports nothing")]
+    #[diagnostic(
+        severity(Warning),
+        help("if this is an entry point, add it to `entry-modules` in ditto.toml")
+    )]
+    NoExports {
+        #[label("nothing exported")]
+        #[serde(with = "SourceSpanDef")]
+        location: SourceSpan,
+    },
+}
+
+impl WarningReport {
+    /// Attach the source code this warning was reported for, ready to be rendered.
+    ///
+    /// The `source` is shared, so it can be reused across all warnings for a file.
+    pub fn with_source_code(self, source_name: impl AsRef<str>, source: Arc<String>) -> Report {
+        Report::from(self).with_source_code(NamedSource::new(source_name, source))
+    }
 }
 
 /// Convert our [Span] to a miette [SourceSpan].
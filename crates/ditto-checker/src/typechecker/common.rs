@@ -1,4 +1,5 @@
-use ditto_ast::{Name, Type};
+use crate::result::{Result, TypeError};
+use ditto_ast::{Name, Span, Type};
 use ditto_cst as cst;
 use std::collections::HashSet;
 
@@ -34,6 +35,16 @@ fn type_variables_rec(ast_type: &Type, accum: &mut HashSet<usize>) {
         Variable { var, .. } => {
             accum.insert(*var);
         }
+        Record { fields } => {
+            fields.values().for_each(|field_type| {
+                type_variables_rec(field_type, accum);
+            });
+        }
+        Tuple { elements } => {
+            elements.iter().for_each(|element| {
+                type_variables_rec(element, accum);
+            });
+        }
     }
 }
 
@@ -73,10 +84,73 @@ fn cst_type_variables_rec(t: &cst::Type, accum: &mut HashSet<Name>) {
             }
             cst_type_variables_rec(return_type, accum);
         }
-        Constructor { .. } => {}
+        Constructor { .. } | Wildcard(_) => {}
         Variable(var) => {
             accum.insert(Name::from(var.clone()));
         }
+        Record(braces) => {
+            braces.value.iter().for_each(|field| {
+                cst_type_variables_rec(&field.type_annotation.1, accum);
+            });
+        }
+        Tuple(parens) => {
+            parens.value.iter().for_each(|element| {
+                cst_type_variables_rec(element, accum);
+            });
+        }
+    }
+}
+
+/// Find the span of an annotation that is nothing but a wildcard, such as `_` or `(_)`.
+pub fn cst_type_wildcard(t: &cst::Type) -> Option<Span> {
+    match t {
+        cst::Type::Parens(parens) => cst_type_wildcard(&parens.value),
+        cst::Type::Wildcard(wildcard) => Some(wildcard.0.get_span()),
+        _ => None,
+    }
+}
+
+/// Make sure a top-level wildcard annotation was solved to something more
+/// specific than an anonymous type variable.
+pub fn check_wildcard_solved(wildcard_span: Option<Span>, ast_type: &Type) -> Result<()> {
+    match (wildcard_span, ast_type) {
+        (
+            Some(span),
+            Type::Variable {
+                source_name: None, ..
+            },
+        ) => Err(TypeError::AmbiguousTypeWildcard { span }),
+        _ => Ok(()),
+    }
+}
+
+pub fn cst_type_has_wildcards(t: &cst::Type) -> bool {
+    use cst::Type::*;
+    match t {
+        Parens(parens) => cst_type_has_wildcards(&parens.value),
+        Call { arguments, .. } => arguments
+            .value
+            .iter()
+            .any(|arg| cst_type_has_wildcards(arg)),
+        Function {
+            parameters,
+            right_arrow: _,
+            return_type,
+        } => {
+            parameters.value.as_ref().map_or(false, |parameters| {
+                parameters.iter().any(|param| cst_type_has_wildcards(param))
+            }) || cst_type_has_wildcards(return_type)
+        }
+        Constructor { .. } | Variable(_) => false,
+        Wildcard(_) => true,
+        Record(braces) => braces
+            .value
+            .iter()
+            .any(|field| cst_type_has_wildcards(&field.type_annotation.1)),
+        Tuple(parens) => parens
+            .value
+            .iter()
+            .any(|element| cst_type_has_wildcards(element)),
     }
 }
 
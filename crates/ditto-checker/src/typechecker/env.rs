@@ -1,20 +1,137 @@
-use super::{common::type_variables, Scheme};
+use super::{common::type_variables, Scheme, Substitution};
 use crate::supply::Supply;
 use ditto_ast::{
-    Expression, FullyQualifiedName, FullyQualifiedProperName, Name, ProperName, QualifiedName,
-    QualifiedProperName, Span, Type,
+    Expression, FullyQualifiedName, FullyQualifiedProperName, Kind, Name, Pattern, PrimConstructor,
+    PrimType, PrimValue, ProperName, Qualified, QualifiedName, QualifiedProperName, Span, Type,
 };
+use lazy_static::lazy_static;
+use non_empty_vec::NonEmpty;
 use std::{
     collections::{HashMap, HashSet},
     default::Default,
 };
 
-#[derive(Default)]
+lazy_static! {
+    pub static ref PRIM_VALUES: EnvValues = HashMap::from_iter(vec![
+        prim_value(
+            PrimValue::DebugLog,
+            // debug_log : (String, a) -> a
+            Scheme::from(Type::Function {
+                parameters: vec![
+                    Type::PrimConstructor(PrimType::String),
+                    Type::Variable {
+                        variable_kind: Kind::Type,
+                        var: 0,
+                        source_name: None,
+                    },
+                ],
+                return_type: Box::new(Type::Variable {
+                    variable_kind: Kind::Type,
+                    var: 0,
+                    source_name: None,
+                }),
+            }),
+        ),
+        prim_value(
+            PrimValue::FloatInfinity,
+            Scheme::from(Type::PrimConstructor(PrimType::Float)),
+        ),
+        prim_value(
+            PrimValue::FloatNegInfinity,
+            Scheme::from(Type::PrimConstructor(PrimType::Float)),
+        ),
+        prim_value(
+            PrimValue::FloatNan,
+            Scheme::from(Type::PrimConstructor(PrimType::Float)),
+        ),
+        prim_value(
+            PrimValue::IntDiv,
+            // Int.div : (Int, Int) -> Maybe(Int)
+            Scheme::from(Type::Function {
+                parameters: vec![
+                    Type::PrimConstructor(PrimType::Int),
+                    Type::PrimConstructor(PrimType::Int),
+                ],
+                return_type: Box::new(Type::Call {
+                    function: Box::new(Type::PrimConstructor(PrimType::Maybe)),
+                    arguments: NonEmpty::new(Type::PrimConstructor(PrimType::Int)),
+                }),
+            }),
+        ),
+    ]);
+    pub static ref PRIM_CONSTRUCTORS: EnvConstructors = {
+        let a = Type::Variable {
+            variable_kind: Kind::Type,
+            var: 0,
+            source_name: None,
+        };
+        let maybe_a = Type::Call {
+            function: Box::new(Type::PrimConstructor(PrimType::Maybe)),
+            arguments: NonEmpty::new(a.clone()),
+        };
+        HashMap::from_iter(vec![
+            prim_constructor(
+                PrimConstructor::MaybeJust,
+                // Maybe.Just : (a) -> Maybe(a)
+                Scheme::from(Type::Function {
+                    parameters: vec![a],
+                    return_type: Box::new(maybe_a.clone()),
+                }),
+            ),
+            prim_constructor(
+                PrimConstructor::MaybeNothing,
+                // Maybe.Nothing : Maybe(a)
+                Scheme::from(maybe_a),
+            ),
+        ])
+    };
+}
+
+fn prim_value(variable: PrimValue, variable_scheme: Scheme) -> (QualifiedName, EnvValue) {
+    let name = Qualified {
+        module_name: variable.qualifier(),
+        value: variable.as_name(),
+    };
+    (
+        name,
+        EnvValue::PrimVariable {
+            variable_scheme,
+            variable,
+        },
+    )
+}
+
+fn prim_constructor(
+    constructor: PrimConstructor,
+    constructor_scheme: Scheme,
+) -> (QualifiedProperName, EnvConstructor) {
+    let name = Qualified {
+        module_name: Some(constructor.qualifier()),
+        value: constructor.as_proper_name(),
+    };
+    (
+        name,
+        EnvConstructor::PrimConstructor {
+            constructor_scheme,
+            constructor,
+        },
+    )
+}
+
 pub struct Env {
     pub constructors: EnvConstructors,
     pub values: EnvValues,
 }
 
+impl Default for Env {
+    fn default() -> Self {
+        Self {
+            constructors: PRIM_CONSTRUCTORS.clone(),
+            values: PRIM_VALUES.clone(),
+        }
+    }
+}
+
 impl Env {
     /// Abstracts a type over all type variables which are free in the type
     /// but not free in the given typing context.
@@ -32,7 +149,31 @@ impl Env {
             signature: ast_type,
         }
     }
-    fn free_type_variables(&self) -> HashSet<usize> {
+    /// Like [Env::generalize], but for use mid-inference, where the types in
+    /// this environment might still mention solved type variables.
+    pub fn generalize_with(&self, substitution: &Substitution, ast_type: Type) -> Scheme {
+        let env_type_variables = self
+            .values
+            .values()
+            .flat_map(|env_value| {
+                let Scheme { forall, signature } = env_value.get_scheme();
+                type_variables(&substitution.apply(signature))
+                    .difference(&forall)
+                    .copied()
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        let forall = type_variables(&ast_type)
+            .difference(&env_type_variables)
+            .copied()
+            .collect();
+
+        Scheme {
+            forall,
+            signature: ast_type,
+        }
+    }
+    pub(crate) fn free_type_variables(&self) -> HashSet<usize> {
         self.constructors
             .values()
             .map(|env_constructor| env_constructor.get_scheme().free_type_variables())
@@ -59,12 +200,17 @@ pub enum EnvValue {
         span: Span,
         variable_scheme: Scheme,
         variable: Name,
+        foreign_name: Option<String>,
     },
     ImportedVariable {
         span: Span,
         variable_scheme: Scheme,
         variable: FullyQualifiedName,
     },
+    PrimVariable {
+        variable_scheme: Scheme,
+        variable: PrimValue,
+    },
 }
 
 impl EnvValue {
@@ -82,11 +228,13 @@ impl EnvValue {
             Self::ForeignVariable {
                 variable_scheme,
                 variable,
+                foreign_name,
                 ..
             } => Expression::ForeignVariable {
                 span,
                 variable_type: variable_scheme.clone().instantiate(supply),
                 variable: variable.clone(),
+                foreign_name: foreign_name.clone(),
             },
             Self::ImportedVariable {
                 variable_scheme,
@@ -97,6 +245,14 @@ impl EnvValue {
                 variable_type: variable_scheme.clone().instantiate(supply),
                 variable: variable.clone(),
             },
+            Self::PrimVariable {
+                variable_scheme,
+                variable,
+            } => Expression::PrimVariable {
+                span,
+                variable_type: variable_scheme.clone().instantiate(supply),
+                variable: variable.clone(),
+            },
         }
     }
 
@@ -111,6 +267,9 @@ impl EnvValue {
             Self::ImportedVariable {
                 variable_scheme, ..
             } => variable_scheme.clone(),
+            Self::PrimVariable {
+                variable_scheme, ..
+            } => variable_scheme.clone(),
         }
     }
 }
@@ -118,15 +277,24 @@ impl EnvValue {
 pub type EnvConstructors = HashMap<QualifiedProperName, EnvConstructor>;
 
 #[derive(Debug, Clone)]
+#[allow(clippy::enum_variant_names)]
 pub enum EnvConstructor {
     ModuleConstructor {
         constructor_scheme: Scheme,
         constructor: ProperName,
+        field_names: Option<Vec<Name>>,
     },
     #[allow(dead_code)]
     ImportedConstructor {
         constructor_scheme: Scheme,
         constructor: FullyQualifiedProperName,
+        field_names: Option<Vec<Name>>,
+        /// Every constructor of the constructed type, including any that aren't in scope.
+        type_constructors: Vec<ProperName>,
+    },
+    PrimConstructor {
+        constructor_scheme: Scheme,
+        constructor: PrimConstructor,
     },
 }
 
@@ -154,10 +322,56 @@ impl EnvConstructor {
                     constructor: constructor.clone(),
                 }
             }
+            Self::PrimConstructor {
+                constructor_scheme,
+                constructor,
+            } => Expression::PrimConstructor {
+                span,
+                constructor_type: constructor_scheme.clone().instantiate(supply),
+                constructor: *constructor,
+            },
         }
     }
 
-    fn get_scheme(&self) -> Scheme {
+    pub fn to_pattern(&self, span: Span, arguments: Vec<Pattern>) -> Pattern {
+        match self {
+            Self::ModuleConstructor {
+                constructor,
+                field_names,
+                ..
+            } => Pattern::LocalConstructor {
+                span,
+                constructor: constructor.clone(),
+                field_names: field_names.clone(),
+                arguments,
+            },
+            Self::ImportedConstructor {
+                constructor,
+                field_names,
+                ..
+            } => Pattern::ImportedConstructor {
+                span,
+                constructor: constructor.clone(),
+                field_names: field_names.clone(),
+                arguments,
+            },
+            Self::PrimConstructor { constructor, .. } => Pattern::PrimConstructor {
+                span,
+                constructor: *constructor,
+                arguments,
+            },
+        }
+    }
+
+    pub fn get_field_names(&self) -> Option<&[Name]> {
+        match self {
+            Self::ModuleConstructor { field_names, .. } => field_names.as_deref(),
+            Self::ImportedConstructor { field_names, .. } => field_names.as_deref(),
+            Self::PrimConstructor { .. } => None,
+        }
+    }
+
+    pub fn get_scheme(&self) -> Scheme {
         match self {
             Self::ModuleConstructor {
                 constructor_scheme, ..
@@ -165,6 +379,9 @@ impl EnvConstructor {
             Self::ImportedConstructor {
                 constructor_scheme, ..
             } => constructor_scheme.clone(),
+            Self::PrimConstructor {
+                constructor_scheme, ..
+            } => constructor_scheme.clone(),
         }
     }
 }
@@ -0,0 +1,341 @@
+use super::{Env, EnvConstructor};
+use ditto_ast::{
+    FullyQualifiedProperName, Pattern, PrimConstructor, PrimType, ProperName, QualifiedProperName,
+    Type,
+};
+
+/// Returns the constructors that aren't completely covered by `patterns`.
+///
+/// For tuples, this is the first element position that isn't covered.
+/// An empty result means the patterns are exhaustive.
+pub fn missing_constructors(env: &Env, patterns: &[&Pattern]) -> Vec<ProperName> {
+    let rows = patterns
+        .iter()
+        .map(|pattern| vec![refutable(pattern)])
+        .collect::<Vec<_>>();
+    missing_from_rows(env, rows)
+}
+
+/// Whether `pattern` matches any value of its type.
+pub fn is_irrefutable(pattern: &Pattern) -> bool {
+    refutable(pattern).map_or(true, |pattern| match pattern {
+        Pattern::Tuple { elements, .. } => elements.iter().all(is_irrefutable),
+        _ => false,
+    })
+}
+
+/// The constructor of `constructed_type`, if the type has exactly one.
+///
+/// If the constructor is in scope under several names, an unqualified name
+/// is preferred.
+pub fn sole_constructor<'env>(
+    env: &'env Env,
+    constructed_type: &Type,
+) -> Option<(&'env QualifiedProperName, &'env EnvConstructor)> {
+    let type_name = constructed_type_name(constructed_type)?;
+    let (name, env_constructor) = env
+        .constructors
+        .iter()
+        .filter(|(_, env_constructor)| {
+            let (_, constructed_type) = constructor_signature(env_constructor);
+            constructed_type_name(constructed_type) == Some(type_name)
+        })
+        .min_by_key(|(name, _)| name.module_name.is_some())?;
+    let constructors = match env_constructor {
+        EnvConstructor::ImportedConstructor {
+            constructor,
+            type_constructors,
+            ..
+        } => constructors_of_imported_type(env, constructor, type_constructors),
+        EnvConstructor::ModuleConstructor { .. } => constructors_of_type(env, type_name),
+        EnvConstructor::PrimConstructor { constructor, .. } => {
+            constructors_of_prim_type(&constructor.constructed_type())
+        }
+    };
+    if constructors.len() == 1 {
+        Some((name, env_constructor))
+    } else {
+        None
+    }
+}
+
+/// Like [is_exhaustive], but returns the uncovered constructors of the first
+/// column that isn't exhaustive.
+///
+/// There must be at least one row.
+fn missing_from_rows(env: &Env, rows: Vec<Row>) -> Vec<ProperName> {
+    if rows[0].is_empty() {
+        return Vec::new();
+    }
+    match rows.iter().find_map(|row| row[0]) {
+        Some(Pattern::Tuple { elements, .. }) => {
+            let constructor = Constructor::tuple(elements.len());
+            missing_from_rows(env, specialize(&rows, &constructor))
+        }
+        Some(pattern) => constructors_of_pattern(env, pattern)
+            .into_iter()
+            .filter(|constructor| !is_exhaustive(env, specialize(&rows, constructor)))
+            .map(|constructor| constructor.id.name())
+            .collect(),
+        None => {
+            // The first column is all wildcards, so move on to the next one
+            let rows = rows
+                .into_iter()
+                .map(|row| row[1..].to_vec())
+                .collect::<Vec<_>>();
+            missing_from_rows(env, rows)
+        }
+    }
+}
+
+/// `None` represents a pattern that matches anything.
+type Row<'a> = Vec<Option<&'a Pattern>>;
+
+/// Does the pattern matrix match every possible vector of values?
+///
+/// Every row is expected to be the same length.
+fn is_exhaustive(env: &Env, rows: Vec<Row>) -> bool {
+    match rows.first() {
+        None => return false,
+        Some(row) if row.is_empty() => return true,
+        Some(_) => {}
+    }
+    let first_constructor = rows.iter().find_map(|row| row[0]);
+    if let Some(pattern) = first_constructor {
+        constructors_of_pattern(env, pattern)
+            .iter()
+            .all(|constructor| is_exhaustive(env, specialize(&rows, constructor)))
+    } else {
+        // The first column is all wildcards, so move on to the next one
+        let rows = rows
+            .into_iter()
+            .map(|row| row[1..].to_vec())
+            .collect::<Vec<_>>();
+        is_exhaustive(env, rows)
+    }
+}
+
+/// Keep the rows that could match `constructor`, replacing their first
+/// column with the constructor arguments.
+fn specialize<'a>(rows: &[Row<'a>], constructor: &Constructor) -> Vec<Row<'a>> {
+    rows.iter()
+        .filter_map(|row| {
+            let mut specialized = match row[0] {
+                None => vec![None; constructor.arity],
+                Some(pattern) if constructor.id.matches(pattern) => {
+                    pattern_arguments(pattern).iter().map(refutable).collect()
+                }
+                Some(_) => return None,
+            };
+            specialized.extend_from_slice(&row[1..]);
+            Some(specialized)
+        })
+        .collect()
+}
+
+/// Tuple patterns are treated as constructor patterns here, even though
+/// they're only refutable if one of their elements is.
+fn refutable(pattern: &Pattern) -> Option<&Pattern> {
+    match pattern {
+        Pattern::Variable { .. } | Pattern::Wildcard { .. } => None,
+        Pattern::LocalConstructor { .. }
+        | Pattern::ImportedConstructor { .. }
+        | Pattern::PrimConstructor { .. }
+        | Pattern::Tuple { .. } => Some(pattern),
+    }
+}
+
+fn pattern_arguments(pattern: &Pattern) -> &[Pattern] {
+    match pattern {
+        Pattern::LocalConstructor { arguments, .. } => arguments,
+        Pattern::ImportedConstructor { arguments, .. } => arguments,
+        Pattern::PrimConstructor { arguments, .. } => arguments,
+        Pattern::Tuple { elements, .. } => elements,
+        Pattern::Variable { .. } | Pattern::Wildcard { .. } => &[],
+    }
+}
+
+struct Constructor {
+    id: ConstructorId,
+    arity: usize,
+}
+
+impl Constructor {
+    /// Tuples behave like a type with a single constructor.
+    fn tuple(arity: usize) -> Self {
+        Self {
+            id: ConstructorId::Tuple(arity),
+            arity,
+        }
+    }
+}
+
+/// Identifies a constructor regardless of how it was referenced.
+#[derive(PartialEq)]
+enum ConstructorId {
+    Local(ProperName),
+    Imported(FullyQualifiedProperName),
+    Prim(PrimConstructor),
+    Tuple(usize),
+}
+
+impl ConstructorId {
+    fn from_env_constructor(env_constructor: &EnvConstructor) -> Self {
+        match env_constructor {
+            EnvConstructor::ModuleConstructor { constructor, .. } => {
+                Self::Local(constructor.clone())
+            }
+            EnvConstructor::ImportedConstructor { constructor, .. } => {
+                Self::Imported(constructor.clone())
+            }
+            EnvConstructor::PrimConstructor { constructor, .. } => Self::Prim(*constructor),
+        }
+    }
+
+    fn matches(&self, pattern: &Pattern) -> bool {
+        match (self, pattern) {
+            (Self::Local(id), Pattern::LocalConstructor { constructor, .. }) => id == constructor,
+            (Self::Imported(id), Pattern::ImportedConstructor { constructor, .. }) => {
+                id == constructor
+            }
+            (Self::Prim(id), Pattern::PrimConstructor { constructor, .. }) => id == constructor,
+            (Self::Tuple(arity), Pattern::Tuple { elements, .. }) => *arity == elements.len(),
+            _ => false,
+        }
+    }
+
+    fn name(&self) -> ProperName {
+        match self {
+            Self::Local(name) => name.clone(),
+            Self::Imported(fully_qualified) => fully_qualified.value.clone(),
+            // Built-in constructors are always referenced with their qualifier
+            Self::Prim(constructor) => ProperName(constructor.to_string()),
+            // Tuples are expanded rather than reported as missing
+            Self::Tuple(_) => unreachable!(),
+        }
+    }
+}
+
+/// All the constructors for the type constructed by `pattern`.
+fn constructors_of_pattern(env: &Env, pattern: &Pattern) -> Vec<Constructor> {
+    match pattern {
+        Pattern::Tuple { elements, .. } => return vec![Constructor::tuple(elements.len())],
+        Pattern::PrimConstructor { constructor, .. } => {
+            return constructors_of_prim_type(&constructor.constructed_type());
+        }
+        _ => {}
+    }
+    let env_constructor = env.constructors.values().find(|env_constructor| {
+        ConstructorId::from_env_constructor(env_constructor).matches(pattern)
+    });
+    match env_constructor {
+        Some(EnvConstructor::ImportedConstructor {
+            constructor,
+            type_constructors,
+            ..
+        }) => constructors_of_imported_type(env, constructor, type_constructors),
+        Some(env_constructor) => {
+            let (_, constructed_type) = constructor_signature(env_constructor);
+            constructed_type_name(constructed_type)
+                .map_or_else(Vec::new, |type_name| constructors_of_type(env, type_name))
+        }
+        None => Vec::new(),
+    }
+}
+
+/// All the constructors of an imported type, sorted by name.
+///
+/// Not every constructor is necessarily in scope, as types can be partially
+/// exported or imported, so we can't rely on the environment alone.
+fn constructors_of_imported_type(
+    env: &Env,
+    constructor: &FullyQualifiedProperName,
+    type_constructors: &[ProperName],
+) -> Vec<Constructor> {
+    type_constructors
+        .iter()
+        .map(|constructor_name| {
+            let id = ConstructorId::Imported(FullyQualifiedProperName {
+                module_name: constructor.module_name.clone(),
+                value: constructor_name.clone(),
+            });
+            // Constructors that aren't in scope can't be matched by any pattern,
+            // so their arity doesn't matter
+            let arity = env
+                .constructors
+                .values()
+                .find(|env_constructor| ConstructorId::from_env_constructor(env_constructor) == id)
+                .map_or(0, |env_constructor| {
+                    constructor_signature(env_constructor).0
+                });
+            Constructor { id, arity }
+        })
+        .collect()
+}
+
+/// All the constructors in scope for the given type, sorted by name.
+fn constructors_of_type(env: &Env, type_name: &FullyQualifiedProperName) -> Vec<Constructor> {
+    let mut constructors: Vec<Constructor> = Vec::new();
+    for env_constructor in env.constructors.values() {
+        let (arity, constructed_type) = constructor_signature(env_constructor);
+        if constructed_type_name(constructed_type) != Some(type_name) {
+            continue;
+        }
+        let id = ConstructorId::from_env_constructor(env_constructor);
+        // The same constructor can be in scope under different names
+        if constructors.iter().any(|constructor| constructor.id == id) {
+            continue;
+        }
+        constructors.push(Constructor { id, arity });
+    }
+    constructors.sort_by_key(|constructor| constructor.id.name());
+    constructors
+}
+
+/// All the constructors of a built-in type, sorted by name.
+fn constructors_of_prim_type(prim_type: &PrimType) -> Vec<Constructor> {
+    PrimConstructor::all_of(prim_type)
+        .into_iter()
+        .map(|constructor| Constructor {
+            id: ConstructorId::Prim(constructor),
+            arity: constructor.arity(),
+        })
+        .collect()
+}
+
+/// The number of arguments a constructor takes, and the type it constructs.
+fn constructor_signature(env_constructor: &EnvConstructor) -> (usize, &Type) {
+    let signature = match env_constructor {
+        EnvConstructor::ModuleConstructor {
+            constructor_scheme, ..
+        } => &constructor_scheme.signature,
+        EnvConstructor::ImportedConstructor {
+            constructor_scheme, ..
+        } => &constructor_scheme.signature,
+        EnvConstructor::PrimConstructor {
+            constructor_scheme, ..
+        } => &constructor_scheme.signature,
+    };
+    match signature {
+        Type::Function {
+            parameters,
+            return_type,
+        } => (parameters.len(), return_type),
+        constructed_type => (0, constructed_type),
+    }
+}
+
+fn constructed_type_name(constructed_type: &Type) -> Option<&FullyQualifiedProperName> {
+    match constructed_type {
+        Type::Constructor {
+            canonical_value, ..
+        } => Some(canonical_value),
+        Type::Call {
+            function: box Type::Constructor {
+                canonical_value, ..
+            },
+            ..
+        } => Some(canonical_value),
+        _ => None,
+    }
+}
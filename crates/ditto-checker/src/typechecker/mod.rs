@@ -1,5 +1,6 @@
 mod common;
 mod env;
+mod exhaustiveness;
 pub mod pre_ast;
 mod scheme;
 mod state;
@@ -16,12 +17,16 @@ use substitution::*;
 
 use crate::{
     kindchecker::{self, TypeReferences},
-    result::{Result, TypeError, Warning, Warnings},
+    result::{RecordKind, Result, TypeError, Warning, Warnings},
     supply::Supply,
 };
-use ditto_ast::{unqualified, Argument, Expression, FunctionBinder, PrimType, Span, Type};
+use ditto_ast::{
+    unqualified, Argument, BinOp, Expression, FullyQualifiedProperName, FunctionBinder, Name,
+    Pattern, PrimConstructor, PrimType, ProperName, Span, Type,
+};
 use ditto_cst as cst;
-use std::collections::HashSet;
+use non_empty_vec::NonEmpty;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 #[cfg(test)]
 pub fn typecheck(
@@ -59,6 +64,7 @@ pub fn typecheck_with(
     Supply,
 )> {
     if let Some(type_annotation) = cst_type_annotation {
+        let wildcard_span = cst_type_wildcard(&type_annotation.1);
         let (expr, expected, mut warnings, type_references, supply) =
             pre::Expression::from_cst_annotated(
                 kindchecker_env,
@@ -78,10 +84,12 @@ pub fn typecheck_with(
             value_references,
             constructor_references,
             supply,
-            ..
+            holes,
         } = state;
+        check_holes(&substitution, &supply, holes)?;
         warnings.extend(more_warnings);
         let expression = substitution.apply_expression(expression);
+        check_wildcard_solved(wildcard_span, &expression.get_type())?;
         Ok((
             expression,
             value_references,
@@ -105,8 +113,9 @@ pub fn typecheck_with(
             value_references,
             constructor_references,
             supply,
-            ..
+            holes,
         } = state;
+        check_holes(&substitution, &supply, holes)?;
         warnings.extend(more_warnings);
         let expression = substitution.apply_expression(expression);
         Ok((
@@ -126,8 +135,34 @@ pub fn infer(env: &Env, state: &mut State, expr: pre::Expression) -> Result<Expr
         pre::Expression::False { span } => Ok(Expression::False { span }),
         pre::Expression::Unit { span } => Ok(Expression::Unit { span }),
         pre::Expression::String { span, value } => Ok(Expression::String { span, value }),
+        pre::Expression::Char { span, value } => Ok(Expression::Char { span, value }),
         pre::Expression::Int { span, value } => Ok(Expression::Int { span, value }),
         pre::Expression::Float { span, value } => Ok(Expression::Float { span, value }),
+        pre::Expression::Hole { span, name } => {
+            let hole_type = state.supply.fresh_type();
+            state.holes.push(Hole {
+                span,
+                name: name.clone(),
+                hole_type: hole_type.clone(),
+                env_values: env.values.clone(),
+            });
+            // Holes always fail type-checking (see `check_holes`),
+            // so this expression never makes it out of the checker
+            Ok(Expression::LocalVariable {
+                span,
+                variable_type: hole_type,
+                variable: name,
+            })
+        }
+        pre::Expression::InlineJs {
+            span,
+            code,
+            js_type,
+        } => Ok(Expression::InlineJs {
+            span,
+            code,
+            value_type: js_type,
+        }),
         pre::Expression::Array { span, elements } => {
             if let Some((head, tail)) = split_first_owned(elements) {
                 let head = infer(env, state, head)?;
@@ -188,6 +223,197 @@ pub fn infer(env: &Env, state: &mut State, expr: pre::Expression) -> Result<Expr
                     }
                 })
         }
+        pre::Expression::RecordConstructor {
+            span,
+            constructor_span,
+            constructor,
+            fields,
+        } => {
+            let field_names = env
+                .constructors
+                .get(&constructor)
+                .ok_or_else(|| {
+                    let ctors_in_scope = env.constructors.keys().cloned().collect();
+                    TypeError::UnknownConstructor {
+                        span: constructor_span,
+                        constructor: constructor.clone(),
+                        ctors_in_scope,
+                    }
+                })?
+                .get_field_names()
+                .ok_or_else(|| TypeError::NotARecordConstructor {
+                    span: constructor_span,
+                    constructor: constructor.clone(),
+                })?;
+
+            let mut values: HashMap<Name, (Span, pre::Expression)> = HashMap::new();
+            for pre::RecordField {
+                label_span,
+                label,
+                value,
+            } in fields
+            {
+                if let Some((previous_field, previous_value)) = values.get(&label) {
+                    return Err(TypeError::DuplicateRecordField {
+                        previous_field: *previous_field,
+                        duplicate_field: label_span,
+                        record_kind: RecordKind::Literal,
+                        values: Some((previous_value.get_span(), value.get_span())),
+                    });
+                }
+                if !field_names.contains(&label) {
+                    return Err(TypeError::UnknownRecordField {
+                        span: label_span,
+                        label,
+                        constructor: Some(constructor),
+                        labels_in_scope: field_names.iter().cloned().collect(),
+                    });
+                }
+                values.insert(label, (label_span, value));
+            }
+
+            let missing = field_names
+                .iter()
+                .filter(|label| !values.contains_key(label))
+                .cloned()
+                .collect::<Vec<_>>();
+            if !missing.is_empty() {
+                return Err(TypeError::MissingRecordFields {
+                    span,
+                    constructor,
+                    missing,
+                });
+            }
+
+            // NOTE record construction is sugar for calling the constructor
+            // with its fields in declaration order.
+            let arguments = field_names
+                .iter()
+                .filter_map(|label| values.remove(label))
+                .map(|(_, value)| pre::Argument::Expression(value))
+                .collect();
+            infer(
+                env,
+                state,
+                pre::Expression::Call {
+                    span,
+                    function: Box::new(pre::Expression::Constructor {
+                        span: constructor_span,
+                        constructor,
+                    }),
+                    arguments,
+                },
+            )
+        }
+        pre::Expression::Tuple { span, elements } => {
+            // NOTE each element is inferred independently
+            let elements = elements
+                .into_iter()
+                .map(|element| infer(env, state, element))
+                .collect::<Result<Vec<_>>>()?;
+            Ok(Expression::Tuple { span, elements })
+        }
+        pre::Expression::Record { span, fields } => {
+            let mut label_spans: HashMap<Name, Span> = HashMap::new();
+            let mut values = BTreeMap::new();
+            for pre::RecordField {
+                label_span,
+                label,
+                value,
+            } in fields
+            {
+                if let Some(previous_field) = label_spans.get(&label) {
+                    let previous_value: &Expression = &values[&label];
+                    return Err(TypeError::DuplicateRecordField {
+                        previous_field: *previous_field,
+                        duplicate_field: label_span,
+                        record_kind: RecordKind::Literal,
+                        values: Some((previous_value.get_span(), value.get_span())),
+                    });
+                }
+                label_spans.insert(label.clone(), label_span);
+                values.insert(label, infer(env, state, value)?);
+            }
+            Ok(Expression::Record {
+                span,
+                fields: values,
+            })
+        }
+        pre::Expression::RecordAccess {
+            span,
+            box target,
+            label_span,
+            label,
+        } => {
+            let target = infer(env, state, target)?;
+            match state.substitution.apply(target.get_type()) {
+                Type::Record { mut fields } => {
+                    if let Some(field_type) = fields.remove(&label) {
+                        Ok(Expression::RecordAccess {
+                            span,
+                            field_type,
+                            target: Box::new(target),
+                            label,
+                        })
+                    } else {
+                        Err(TypeError::UnknownRecordField {
+                            span: label_span,
+                            label,
+                            constructor: None,
+                            labels_in_scope: fields.into_keys().collect(),
+                        })
+                    }
+                }
+                // Fields of a single-constructor type can be accessed by name
+                // too, e.g. `person.name` for `type Person = Person { name: String }`
+                actual_type => match exhaustiveness::sole_constructor(env, &actual_type) {
+                    Some((constructor, env_constructor)) => {
+                        let field_names = env_constructor.get_field_names().ok_or_else(|| {
+                            TypeError::NotARecord {
+                                span: target.get_span(),
+                                actual_type: actual_type.clone(),
+                            }
+                        })?;
+                        let index = field_names
+                            .iter()
+                            .position(|field_name| *field_name == label)
+                            .ok_or_else(|| TypeError::UnknownRecordField {
+                                span: label_span,
+                                label: label.clone(),
+                                constructor: Some(constructor.clone()),
+                                labels_in_scope: field_names.iter().cloned().collect(),
+                            })?;
+                        let constructor_type =
+                            env_constructor.get_scheme().instantiate(&mut state.supply);
+                        let (mut parameters, return_type) = match constructor_type {
+                            Type::Function {
+                                parameters,
+                                return_type,
+                            } => (parameters, return_type),
+                            // Constructors with named fields always take arguments
+                            _ => unreachable!(),
+                        };
+                        let constraint = Constraint {
+                            expected: actual_type,
+                            actual: *return_type,
+                        };
+                        unify(state, target.get_span(), constraint)?;
+                        Ok(Expression::RecordAccess {
+                            span,
+                            field_type: parameters.swap_remove(index),
+                            target: Box::new(target),
+                            label,
+                        })
+                    }
+                    // NOTE records are closed, so we need to know the full record type
+                    // by this point (i.e. `(r) -> r.a` needs an annotation).
+                    None => Err(TypeError::NotARecord {
+                        span: target.get_span(),
+                        actual_type,
+                    }),
+                },
+            }
+        }
         pre::Expression::If {
             span,
             box condition,
@@ -198,6 +424,16 @@ pub fn infer(env: &Env, state: &mut State, expr: pre::Expression) -> Result<Expr
             let true_clause = infer(env, state, true_clause)?;
             let true_type = state.substitution.apply(true_clause.get_type());
             let false_clause = check(env, state, true_type.clone(), false_clause)?;
+            // A literal condition means one of the branches is dead code
+            match condition {
+                Expression::True { .. } => state.warnings.push(Warning::UnreachableBranch {
+                    span: false_clause.get_span(),
+                }),
+                Expression::False { .. } => state.warnings.push(Warning::UnreachableBranch {
+                    span: true_clause.get_span(),
+                }),
+                _ => {}
+            }
             Ok(Expression::If {
                 span,
                 output_type: true_type,
@@ -206,6 +442,169 @@ pub fn infer(env: &Env, state: &mut State, expr: pre::Expression) -> Result<Expr
                 false_clause: Box::new(false_clause),
             })
         }
+        pre::Expression::Match {
+            span,
+            box expression,
+            arms,
+        } => typecheck_match(env, state, None, span, expression, arms),
+        pre::Expression::Let {
+            span,
+            name_span,
+            type_annotation,
+            name,
+            box expression,
+            box body,
+        } => {
+            let expression = if let Some(expected) = type_annotation {
+                check(env, state, expected, expression)?
+            } else {
+                infer(env, state, expression)?
+            };
+            let variable_scheme = env.generalize_with(
+                &state.substitution,
+                state.substitution.apply(expression.get_type()),
+            );
+
+            let qualified_name = unqualified(name.clone());
+            let original_count = state.value_references.remove(&qualified_name);
+
+            let mut env_values = env.values.clone();
+            env_values.insert(
+                qualified_name.clone(),
+                EnvValue::ModuleValue {
+                    span: name_span,
+                    variable_scheme,
+                    variable: name.clone(),
+                },
+            );
+            let env = Env {
+                values: env_values,
+                constructors: env.constructors.clone(),
+            };
+            let body = infer(&env, state, body)?;
+
+            // Check for an unused binding
+            if state.value_references.remove(&qualified_name).is_none() {
+                state
+                    .warnings
+                    .push(Warning::UnusedLetBinder { span: name_span });
+            }
+
+            // Restore the shadowed reference count
+            if let Some(count) = original_count {
+                state.value_references.insert(qualified_name, count);
+            }
+
+            Ok(Expression::Let {
+                span,
+                name_span,
+                name,
+                expression: Box::new(expression),
+                body: Box::new(body),
+            })
+        }
+        pre::Expression::BinOp {
+            span,
+            operator: operator @ (BinOp::And | BinOp::Or),
+            box lhs,
+            box rhs,
+        } => {
+            let bool_type = Type::PrimConstructor(PrimType::Bool);
+            let lhs = check(env, state, bool_type.clone(), lhs)?;
+            let rhs = check(env, state, bool_type.clone(), rhs)?;
+            Ok(Expression::BinOp {
+                span,
+                binop_type: bool_type,
+                operator,
+                lhs: Box::new(lhs),
+                rhs: Box::new(rhs),
+            })
+        }
+        pre::Expression::BinOp {
+            span,
+            operator,
+            box lhs,
+            box rhs,
+        } => {
+            let lhs = infer(env, state, lhs)?;
+            let operand_type = state.substitution.apply(lhs.get_type());
+            let rhs = check(env, state, operand_type.clone(), rhs)?;
+            let operand_type = state.substitution.apply(operand_type);
+            let binop_type = match operator {
+                BinOp::Add | BinOp::Subtract | BinOp::Multiply | BinOp::Divide => {
+                    match operand_type {
+                        numeric_type @ Type::PrimConstructor(PrimType::Int | PrimType::Float) => {
+                            numeric_type
+                        }
+                        operand_type => default_operand_type(state, lhs.get_span(), operand_type)?,
+                    }
+                }
+                BinOp::Equals | BinOp::NotEquals => {
+                    // Equality is structural, so anything goes except functions
+                    if contains_function_type(&operand_type) {
+                        return Err(TypeError::UnsupportedEquality { span, operand_type });
+                    }
+                    if let (Some(lhs_constructor), Some(rhs_constructor)) =
+                        (head_constructor(&lhs), head_constructor(&rhs))
+                    {
+                        if lhs_constructor != rhs_constructor {
+                            state.warnings.push(Warning::ConstantComparison {
+                                span,
+                                value: operator == BinOp::NotEquals,
+                            });
+                        }
+                    }
+                    Type::PrimConstructor(PrimType::Bool)
+                }
+                BinOp::LessThan
+                | BinOp::LessThanOrEquals
+                | BinOp::GreaterThan
+                | BinOp::GreaterThanOrEquals => {
+                    match operand_type {
+                        Type::PrimConstructor(
+                            PrimType::Int | PrimType::Float | PrimType::String | PrimType::Char,
+                        ) => {}
+                        operand_type @ Type::Variable { .. } => {
+                            default_operand_type(state, lhs.get_span(), operand_type)?;
+                        }
+                        operand_type => {
+                            return Err(TypeError::UnsupportedOrdering { span, operand_type });
+                        }
+                    }
+                    Type::PrimConstructor(PrimType::Bool)
+                }
+                BinOp::And | BinOp::Or => unreachable!(),
+            };
+            Ok(Expression::BinOp {
+                span,
+                binop_type,
+                operator,
+                lhs: Box::new(lhs),
+                rhs: Box::new(rhs),
+            })
+        }
+        pre::Expression::Negate {
+            span,
+            box expression,
+        } => {
+            let expression = infer(env, state, expression)?;
+            match state.substitution.apply(expression.get_type()) {
+                Type::PrimConstructor(PrimType::Int | PrimType::Float) => {}
+                operand_type @ Type::Variable { .. } => {
+                    default_operand_type(state, expression.get_span(), operand_type)?;
+                }
+                operand_type => {
+                    return Err(TypeError::UnsupportedNegation {
+                        span: expression.get_span(),
+                        operand_type,
+                    });
+                }
+            }
+            Ok(Expression::Negate {
+                span,
+                expression: Box::new(expression),
+            })
+        }
         pre::Expression::Call {
             span,
             box function,
@@ -235,6 +634,18 @@ pub fn infer(env: &Env, state: &mut State, expr: pre::Expression) -> Result<Expr
                             pre::Argument::Expression(expr) => {
                                 check(env, state, expected, expr).map(Argument::Expression)
                             }
+                            pre::Argument::Piped(expr) => {
+                                let expression = infer(env, state, expr)?;
+                                unify(
+                                    state,
+                                    function.get_span(),
+                                    Constraint {
+                                        expected,
+                                        actual: expression.get_type(),
+                                    },
+                                )?;
+                                Ok(Argument::Expression(expression))
+                            }
                         })
                         .collect::<Result<Vec<_>>>()?;
 
@@ -249,7 +660,7 @@ pub fn infer(env: &Env, state: &mut State, expr: pre::Expression) -> Result<Expr
                     let arguments = arguments
                         .into_iter()
                         .map(|arg| match arg {
-                            pre::Argument::Expression(expr) => {
+                            pre::Argument::Expression(expr) | pre::Argument::Piped(expr) => {
                                 infer(env, state, expr).map(Argument::Expression)
                             }
                         })
@@ -345,6 +756,16 @@ pub fn infer(env: &Env, state: &mut State, expr: pre::Expression) -> Result<Expr
                             value,
                         });
                     }
+                    pre_ast::FunctionBinder::Wildcard {
+                        span,
+                        type_annotation,
+                    } => {
+                        // Wildcards don't bind anything, so they can't conflict
+                        // and they can't be unused
+                        let binder_type =
+                            type_annotation.unwrap_or_else(|| state.supply.fresh_type());
+                        binders.push(FunctionBinder::Wildcard { span, binder_type });
+                    }
                 }
             }
             let env = Env {
@@ -358,7 +779,11 @@ pub fn infer(env: &Env, state: &mut State, expr: pre::Expression) -> Result<Expr
             };
 
             // Check for unused binders
-            for FunctionBinder::Name { span, value, .. } in binders.iter() {
+            for binder in binders.iter() {
+                let (span, value) = match binder {
+                    FunctionBinder::Name { span, value, .. } => (span, value),
+                    FunctionBinder::Wildcard { .. } => continue,
+                };
                 let qualified_name = unqualified(value.clone());
                 if !state.value_references.contains_key(&qualified_name) {
                     state
@@ -381,12 +806,78 @@ pub fn infer(env: &Env, state: &mut State, expr: pre::Expression) -> Result<Expr
     }
 }
 
+/// Operands that aren't otherwise constrained default to `Int`.
+fn default_operand_type(state: &mut State, span: Span, operand_type: Type) -> Result<Type> {
+    let int_type = Type::PrimConstructor(PrimType::Int);
+    unify(
+        state,
+        span,
+        Constraint {
+            expected: int_type.clone(),
+            actual: operand_type,
+        },
+    )?;
+    Ok(int_type)
+}
+
+/// The constructor an expression is statically known to be built with, if any.
+///
+/// This is deliberately conservative: only a constructor or a direct call to one counts.
+fn head_constructor(expression: &Expression) -> Option<HeadConstructor> {
+    match expression {
+        Expression::LocalConstructor { constructor, .. } => {
+            Some(HeadConstructor::Local(constructor))
+        }
+        Expression::ImportedConstructor { constructor, .. } => {
+            Some(HeadConstructor::Imported(constructor))
+        }
+        Expression::PrimConstructor { constructor, .. } => Some(HeadConstructor::Prim(constructor)),
+        Expression::Call { function, .. } => match function.as_ref() {
+            Expression::LocalConstructor { .. }
+            | Expression::ImportedConstructor { .. }
+            | Expression::PrimConstructor { .. } => head_constructor(function),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[derive(PartialEq)]
+enum HeadConstructor<'a> {
+    Local(&'a ProperName),
+    Imported(&'a FullyQualifiedProperName),
+    Prim(&'a PrimConstructor),
+}
+
+/// Whether a function type appears anywhere within the given type.
+fn contains_function_type(t: &Type) -> bool {
+    match t {
+        Type::Function { .. } => true,
+        Type::Call {
+            function,
+            arguments,
+        } => contains_function_type(function) || arguments.iter().any(contains_function_type),
+        Type::Record { fields } => fields.values().any(contains_function_type),
+        Type::Tuple { elements } => elements.iter().any(contains_function_type),
+        Type::Constructor { .. } | Type::PrimConstructor(_) | Type::Variable { .. } => false,
+    }
+}
+
 pub fn check(
     env: &Env,
     state: &mut State,
     expected: Type,
     expr: pre::Expression,
 ) -> Result<Expression> {
+    if let pre::Expression::Match {
+        span,
+        box expression,
+        arms,
+    } = expr
+    {
+        // Check each arm directly, so that mismatches are reported for the offending arm
+        return typecheck_match(env, state, Some(expected), span, expression, arms);
+    }
     let expression = infer(env, state, expr)?;
     unify(
         state,
@@ -399,6 +890,382 @@ pub fn check(
     Ok(expression)
 }
 
+/// Fail with the first typed hole (if any), reporting the type it's expected to have
+/// and which values in scope have that type.
+pub fn check_holes(substitution: &Substitution, supply: &Supply, holes: Vec<Hole>) -> Result<()> {
+    let hole = if let Some(hole) = holes.into_iter().min_by_key(|hole| hole.span.start_offset) {
+        hole
+    } else {
+        return Ok(());
+    };
+    let expected_type = substitution.apply(hole.hole_type);
+    let mut candidates = hole
+        .env_values
+        .iter()
+        .filter_map(|(name, env_value)| {
+            // Unify against a throwaway copy of the substitution,
+            // so that candidates can't influence each other
+            let mut candidate_state = State {
+                supply: Supply(supply.peek()),
+                substitution: Substitution(substitution.0.clone()),
+                ..State::default()
+            };
+            let candidate_type = env_value
+                .to_expression(hole.span, &mut candidate_state.supply)
+                .get_type();
+            let constraint = Constraint {
+                expected: expected_type.clone(),
+                actual: candidate_type,
+            };
+            unify(&mut candidate_state, hole.span, constraint)
+                .ok()
+                .map(|_| name.clone())
+        })
+        .collect::<Vec<_>>();
+    candidates.sort_by_key(|name| name.to_string());
+    Err(TypeError::TypedHole {
+        span: hole.span,
+        name: hole.name,
+        expected_type,
+        candidates,
+    })
+}
+
+fn typecheck_match(
+    env: &Env,
+    state: &mut State,
+    expected: Option<Type>,
+    span: Span,
+    expression: pre::Expression,
+    arms: Vec<(pre::Pattern, pre::Expression)>,
+) -> Result<Expression> {
+    let expression = infer(env, state, expression)?;
+    let expression_type = expression.get_type();
+
+    let (head_arm, tail_arms) =
+        split_first_owned(arms).expect("match expressions should have at least one arm");
+
+    let head_arm = typecheck_match_arm(env, state, &expression_type, expected, head_arm)?;
+    let match_type = state.substitution.apply(head_arm.1.get_type());
+
+    let mut arms = NonEmpty::new(head_arm);
+    for arm in tail_arms {
+        let arm = typecheck_match_arm(env, state, &expression_type, Some(match_type.clone()), arm)?;
+        arms.push(arm);
+    }
+
+    // Anything after an arm that matches everything can never be reached
+    let mut irrefutable_arm = false;
+    for (pattern, _) in arms.iter() {
+        if irrefutable_arm {
+            state.warnings.push(Warning::RedundantMatchArm {
+                span: pattern.get_span(),
+            });
+        } else if exhaustiveness::is_irrefutable(pattern) {
+            irrefutable_arm = true;
+        }
+    }
+
+    let patterns = arms.iter().map(|(pattern, _)| pattern).collect::<Vec<_>>();
+    let missing = exhaustiveness::missing_constructors(env, &patterns);
+    if !missing.is_empty() {
+        return Err(TypeError::MatchNotExhaustive { span, missing });
+    }
+
+    Ok(Expression::Match {
+        span,
+        match_type,
+        expression: Box::new(expression),
+        arms,
+    })
+}
+
+fn typecheck_match_arm(
+    env: &Env,
+    state: &mut State,
+    expression_type: &Type,
+    expected: Option<Type>,
+    (pattern, expression): (pre::Pattern, pre::Expression),
+) -> Result<(Pattern, Expression)> {
+    let mut env_values = env.values.clone();
+    let mut binders = Vec::new();
+    let mut original_value_references = ValueReferences::new();
+
+    let pattern = check_pattern(
+        env,
+        state,
+        &mut env_values,
+        &mut binders,
+        &mut original_value_references,
+        expression_type.clone(),
+        pattern,
+    )?;
+
+    let env = Env {
+        values: env_values,
+        constructors: env.constructors.clone(),
+    };
+    let expression = if let Some(expected) = expected {
+        check(&env, state, expected, expression)?
+    } else {
+        infer(&env, state, expression)?
+    };
+
+    // Check for unused binders
+    for (span, name) in binders {
+        let qualified_name = unqualified(name);
+        if !state.value_references.contains_key(&qualified_name) {
+            state.warnings.push(Warning::UnusedPatternBinder { span });
+        } else {
+            state.value_references.remove(&qualified_name);
+        }
+    }
+
+    // Restore shadowed reference counts
+    state.value_references.extend(original_value_references);
+
+    Ok((pattern, expression))
+}
+
+/// Check that a pattern matches the `expected` type, binding any pattern variables
+/// in `env_values`.
+fn check_pattern(
+    env: &Env,
+    state: &mut State,
+    env_values: &mut EnvValues,
+    binders: &mut Vec<(Span, Name)>,
+    original_value_references: &mut ValueReferences,
+    expected: Type,
+    pattern: pre::Pattern,
+) -> Result<Pattern> {
+    match pattern {
+        pre::Pattern::Wildcard { span } => Ok(Pattern::Wildcard { span }),
+        pre::Pattern::Variable { span, name } => {
+            // Check this binder doesn't conflict with others in the same pattern
+            if let Some((previous_binder, _)) = binders.iter().find(|(_, found)| *found == name) {
+                return Err(TypeError::DuplicatePatternBinder {
+                    previous_binder: *previous_binder,
+                    duplicate_binder: span,
+                });
+            }
+
+            let qualified_name = unqualified(name.clone());
+
+            if let Some(count) = state.value_references.remove(&qualified_name) {
+                original_value_references.insert(qualified_name.clone(), count);
+                state.value_references.insert(qualified_name.clone(), 0);
+            }
+
+            env_values.insert(
+                qualified_name,
+                EnvValue::ModuleValue {
+                    span,
+                    variable_scheme: Scheme {
+                        forall: HashSet::new(),
+                        signature: expected,
+                    },
+                    variable: name.clone(),
+                },
+            );
+
+            binders.push((span, name.clone()));
+
+            Ok(Pattern::Variable { span, name })
+        }
+        pre::Pattern::Constructor {
+            span,
+            constructor,
+            arguments,
+        } => {
+            if let Some(count) = state.constructor_references.get_mut(&constructor) {
+                *count += 1
+            } else {
+                state.constructor_references.insert(constructor.clone(), 1);
+            }
+            let env_constructor = env.constructors.get(&constructor).ok_or_else(|| {
+                let ctors_in_scope = env.constructors.keys().cloned().collect();
+                TypeError::UnknownConstructor {
+                    span,
+                    constructor,
+                    ctors_in_scope,
+                }
+            })?;
+
+            let constructor_type = env_constructor
+                .to_expression(span, &mut state.supply)
+                .get_type();
+
+            let (parameters, constructed_type) = match constructor_type {
+                Type::Function {
+                    parameters,
+                    box return_type,
+                } => (parameters, return_type),
+                constructed_type => (Vec::new(), constructed_type),
+            };
+
+            if arguments.len() != parameters.len() {
+                return Err(TypeError::ArgumentLengthMismatch {
+                    function_span: span,
+                    wanted: parameters.len(),
+                    got: arguments.len(),
+                });
+            }
+
+            unify(
+                state,
+                span,
+                Constraint {
+                    expected,
+                    actual: constructed_type,
+                },
+            )?;
+
+            let arguments = arguments
+                .into_iter()
+                .zip(parameters.into_iter())
+                .map(|(argument, parameter)| {
+                    check_pattern(
+                        env,
+                        state,
+                        env_values,
+                        binders,
+                        original_value_references,
+                        parameter,
+                        argument,
+                    )
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            Ok(env_constructor.to_pattern(span, arguments))
+        }
+        pre::Pattern::RecordConstructor {
+            span,
+            constructor_span,
+            constructor,
+            fields,
+            rest,
+        } => {
+            let field_names = env
+                .constructors
+                .get(&constructor)
+                .ok_or_else(|| {
+                    let ctors_in_scope = env.constructors.keys().cloned().collect();
+                    TypeError::UnknownConstructor {
+                        span: constructor_span,
+                        constructor: constructor.clone(),
+                        ctors_in_scope,
+                    }
+                })?
+                .get_field_names()
+                .ok_or_else(|| TypeError::NotARecordConstructor {
+                    span: constructor_span,
+                    constructor: constructor.clone(),
+                })?;
+
+            let mut patterns = HashMap::new();
+            for pre::RecordPatternField {
+                label_span,
+                label,
+                pattern,
+            } in fields
+            {
+                if let Some((previous_field, _)) = patterns.get(&label) {
+                    return Err(TypeError::DuplicateRecordField {
+                        previous_field: *previous_field,
+                        duplicate_field: label_span,
+                        record_kind: RecordKind::Pattern,
+                        values: None,
+                    });
+                }
+                if !field_names.contains(&label) {
+                    return Err(TypeError::UnknownRecordField {
+                        span: label_span,
+                        label,
+                        constructor: Some(constructor),
+                        labels_in_scope: field_names.iter().cloned().collect(),
+                    });
+                }
+                patterns.insert(label, (label_span, pattern));
+            }
+
+            if !rest {
+                let missing = field_names
+                    .iter()
+                    .filter(|label| !patterns.contains_key(label))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                if !missing.is_empty() {
+                    return Err(TypeError::MissingRecordFields {
+                        span,
+                        constructor,
+                        missing,
+                    });
+                }
+            }
+
+            // NOTE like record construction, this is sugar for the positional
+            // pattern, with any fields skipped by `..` matching anything.
+            let arguments = field_names
+                .iter()
+                .map(|label| {
+                    patterns
+                        .remove(label)
+                        .map_or(pre::Pattern::Wildcard { span }, |(_, pattern)| pattern)
+                })
+                .collect();
+            check_pattern(
+                env,
+                state,
+                env_values,
+                binders,
+                original_value_references,
+                expected,
+                pre::Pattern::Constructor {
+                    span,
+                    constructor,
+                    arguments,
+                },
+            )
+        }
+        pre::Pattern::Tuple { span, elements } => {
+            let element_types = elements
+                .iter()
+                .map(|_| state.supply.fresh_type())
+                .collect::<Vec<_>>();
+
+            unify(
+                state,
+                span,
+                Constraint {
+                    expected,
+                    actual: Type::Tuple {
+                        // This is safe as tuple patterns are non-empty
+                        elements: unsafe { NonEmpty::new_unchecked(element_types.clone()) },
+                    },
+                },
+            )?;
+
+            let elements = elements
+                .into_iter()
+                .zip(element_types.into_iter())
+                .map(|(element, element_type)| {
+                    check_pattern(
+                        env,
+                        state,
+                        env_values,
+                        binders,
+                        original_value_references,
+                        element_type,
+                        element,
+                    )
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            Ok(Pattern::Tuple { span, elements })
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Constraint {
     expected: Type,
@@ -583,6 +1450,93 @@ fn unify_else(
 
             Ok(())
         }
+        Constraint {
+            expected: Type::Record {
+                fields: expected_fields,
+            },
+            actual: Type::Record {
+                fields: actual_fields,
+            },
+        } => {
+            let missing = expected_fields
+                .keys()
+                .filter(|label| !actual_fields.contains_key(label))
+                .cloned()
+                .collect::<Vec<_>>();
+            let extra = actual_fields
+                .keys()
+                .filter(|label| !expected_fields.contains_key(label))
+                .cloned()
+                .collect::<Vec<_>>();
+            if !missing.is_empty() || !extra.is_empty() {
+                return Err(TypeError::RecordFieldsMismatch {
+                    span,
+                    missing,
+                    extra,
+                });
+            }
+            let err = TypeError::TypesNotEqual {
+                span,
+                expected: Type::Record {
+                    fields: expected_fields.clone(),
+                },
+                actual: Type::Record {
+                    fields: actual_fields.clone(),
+                },
+            };
+            // NOTE the labels are the same, so these line up
+            let fields = expected_fields
+                .into_values()
+                .zip(actual_fields.into_values());
+
+            for (expected_field, actual_field) in fields {
+                unify_else(
+                    state,
+                    span,
+                    Constraint {
+                        expected: expected_field,
+                        actual: actual_field,
+                    },
+                    Some(&err),
+                )?;
+            }
+
+            Ok(())
+        }
+
+        Constraint {
+            expected: Type::Tuple {
+                elements: expected_elements,
+            },
+            actual: Type::Tuple {
+                elements: actual_elements,
+            },
+        } if expected_elements.len() == actual_elements.len() => {
+            let err = TypeError::TypesNotEqual {
+                span,
+                expected: Type::Tuple {
+                    elements: expected_elements.clone(),
+                },
+                actual: Type::Tuple {
+                    elements: actual_elements.clone(),
+                },
+            };
+            let elements = expected_elements.iter().zip(actual_elements.iter());
+
+            for (expected_element, actual_element) in elements {
+                unify_else(
+                    state,
+                    span,
+                    Constraint {
+                        expected: expected_element.clone(),
+                        actual: actual_element.clone(),
+                    },
+                    Some(&err),
+                )?;
+            }
+
+            Ok(())
+        }
 
         // BANG
         Constraint { expected, actual } => Err(err.cloned().unwrap_or(TypeError::TypesNotEqual {
@@ -5,10 +5,10 @@ use crate::{
         check, Env, EnvTypeVariable, EnvTypeVariables, EnvTypes, State, Substitution,
         TypeReferences,
     },
-    result::{Result, Warnings},
+    result::{Result, TypeError, Warnings},
     supply::Supply,
 };
-use ditto_ast::{Kind, Name, QualifiedName, QualifiedProperName, Span, Type};
+use ditto_ast::{BinOp, Kind, Name, QualifiedName, QualifiedProperName, Span, Type};
 use ditto_cst as cst;
 use std::collections::hash_map;
 
@@ -30,10 +30,51 @@ pub enum Expression {
         true_clause: Box<Self>,
         false_clause: Box<Self>,
     },
+    Match {
+        span: Span,
+        expression: Box<Self>,
+        /// NOTE this is non-empty.
+        arms: Vec<(Pattern, Self)>,
+    },
+    Let {
+        span: Span,
+        name_span: Span,
+        type_annotation: Option<Type>,
+        name: Name,
+        expression: Box<Self>,
+        body: Box<Self>,
+    },
+    BinOp {
+        span: Span,
+        operator: BinOp,
+        lhs: Box<Self>,
+        rhs: Box<Self>,
+    },
+    /// NOTE negated literals are folded into `Int` and `Float`.
+    Negate {
+        span: Span,
+        expression: Box<Self>,
+    },
     Constructor {
         span: Span,
         constructor: QualifiedProperName,
     },
+    RecordConstructor {
+        span: Span,
+        constructor_span: Span,
+        constructor: QualifiedProperName,
+        fields: Vec<RecordField>,
+    },
+    Record {
+        span: Span,
+        fields: Vec<RecordField>,
+    },
+    RecordAccess {
+        span: Span,
+        target: Box<Self>,
+        label_span: Span,
+        label: Name,
+    },
     Variable {
         span: Span,
         variable: QualifiedName,
@@ -42,6 +83,10 @@ pub enum Expression {
         span: Span,
         value: String,
     },
+    Char {
+        span: Span,
+        value: String,
+    },
     Int {
         span: Span,
         value: String,
@@ -50,10 +95,25 @@ pub enum Expression {
         span: Span,
         value: String,
     },
+    Hole {
+        span: Span,
+        name: Name,
+    },
+    /// NOTE the type of inline JavaScript is trusted, like a foreign value.
+    InlineJs {
+        span: Span,
+        code: String,
+        js_type: Type,
+    },
     Array {
         span: Span,
         elements: Vec<Self>,
     },
+    /// NOTE this is non-empty.
+    Tuple {
+        span: Span,
+        elements: Vec<Self>,
+    },
     True {
         span: Span,
     },
@@ -71,13 +131,89 @@ pub enum FunctionBinder {
         type_annotation: Option<Type>,
         value: Name,
     },
+    Wildcard {
+        span: Span,
+        type_annotation: Option<Type>,
+    },
 }
 
 pub enum Argument {
     Expression(Expression),
+    /// The left hand side of a `|>`.
+    ///
+    /// Mismatches are reported at the function being piped into, rather than
+    /// at the (possibly long) pipeline leading up to it.
+    Piped(Expression),
+}
+
+pub enum Pattern {
+    Constructor {
+        span: Span,
+        constructor: QualifiedProperName,
+        arguments: Vec<Self>,
+    },
+    RecordConstructor {
+        span: Span,
+        constructor_span: Span,
+        constructor: QualifiedProperName,
+        fields: Vec<RecordPatternField>,
+        /// Whether fields that aren't listed are ignored, i.e. `..`
+        rest: bool,
+    },
+    Tuple {
+        span: Span,
+        elements: Vec<Self>,
+    },
+    Variable {
+        span: Span,
+        name: Name,
+    },
+    Wildcard {
+        span: Span,
+    },
+}
+
+pub struct RecordField {
+    pub label_span: Span,
+    pub label: Name,
+    pub value: Expression,
+}
+
+pub struct RecordPatternField {
+    pub label_span: Span,
+    pub label: Name,
+    pub pattern: Pattern,
 }
 
 impl Expression {
+    pub fn get_span(&self) -> Span {
+        match self {
+            Self::Function { span, .. }
+            | Self::Call { span, .. }
+            | Self::If { span, .. }
+            | Self::Match { span, .. }
+            | Self::Let { span, .. }
+            | Self::BinOp { span, .. }
+            | Self::Negate { span, .. }
+            | Self::Constructor { span, .. }
+            | Self::RecordConstructor { span, .. }
+            | Self::Record { span, .. }
+            | Self::RecordAccess { span, .. }
+            | Self::Variable { span, .. }
+            | Self::String { span, .. }
+            | Self::Char { span, .. }
+            | Self::Int { span, .. }
+            | Self::Float { span, .. }
+            | Self::Hole { span, .. }
+            | Self::InlineJs { span, .. }
+            | Self::Array { span, .. }
+            | Self::Tuple { span, .. }
+            | Self::True { span, .. }
+            | Self::False { span, .. }
+            | Self::Unit { span, .. } => *span,
+        }
+    }
+
     pub fn from_cst(
         env: &Env,
         supply: Supply,
@@ -85,6 +221,7 @@ impl Expression {
     ) -> Result<(Expression, Warnings, TypeReferences, Supply)> {
         let mut state = State {
             supply,
+            allow_wildcards: true,
             ..State::default()
         };
         let expression = convert_cst(env, &mut state, cst_expression)?;
@@ -107,6 +244,7 @@ impl Expression {
     ) -> Result<(Expression, Type, Warnings, TypeReferences, Supply)> {
         let mut state = State {
             supply,
+            allow_wildcards: true,
             ..State::default()
         };
         let mut env = Env {
@@ -154,10 +292,60 @@ fn convert_cst(
             span,
             constructor: QualifiedProperName::from(ctor),
         }),
+        cst::Expression::RecordConstructor {
+            constructor,
+            fields: braces,
+        } => {
+            let constructor_span = constructor.get_span();
+            Ok(Expression::RecordConstructor {
+                span,
+                constructor_span,
+                constructor: QualifiedProperName::from(constructor),
+                fields: convert_cst_record_fields(env, state, braces)?,
+            })
+        }
+        cst::Expression::Record(braces) => Ok(Expression::Record {
+            span,
+            fields: convert_cst_record_fields(env, state, braces)?,
+        }),
+        cst::Expression::RecordAccess {
+            box target, label, ..
+        } => Ok(Expression::RecordAccess {
+            span,
+            target: Box::new(convert_cst(env, state, target)?),
+            label_span: label.get_span(),
+            label: Name::from(label),
+        }),
+        cst::Expression::Negate {
+            expression: box cst::Expression::Int(cst::Token { value, .. }),
+            ..
+        } => Ok(Expression::Int {
+            span,
+            value: format!("-{}", strip_number_separators(value)),
+        }),
+        cst::Expression::Negate {
+            expression: box cst::Expression::Float(cst::Token { value, .. }),
+            ..
+        } => Ok(Expression::Float {
+            span,
+            value: format!("-{}", strip_number_separators(value)),
+        }),
+        cst::Expression::Negate { box expression, .. } => Ok(Expression::Negate {
+            span,
+            expression: Box::new(convert_cst(env, state, expression)?),
+        }),
         cst::Expression::Unit { .. } => Ok(Expression::Unit { span }),
         cst::Expression::True { .. } => Ok(Expression::True { span }),
         cst::Expression::False { .. } => Ok(Expression::False { span }),
         cst::Expression::String(cst::Token { value, .. }) => Ok(Expression::String { span, value }),
+        cst::Expression::Char(cst::Token { value, .. }) => {
+            check_char_escape(span, &value)?;
+            Ok(Expression::Char { span, value })
+        }
+        cst::Expression::Hole(cst::Token { value, .. }) => Ok(Expression::Hole {
+            span,
+            name: Name(value),
+        }),
         cst::Expression::Int(cst::Token { value, .. }) => Ok(Expression::Int {
             span,
             value: strip_number_separators(value),
@@ -166,6 +354,28 @@ fn convert_cst(
             span,
             value: strip_number_separators(value),
         }),
+        cst::Expression::InlineJs {
+            code,
+            type_annotation,
+            ..
+        } => {
+            // Wildcards would let the trusted type be inferred from its usage,
+            // which is never what you want
+            let allow_wildcards = std::mem::replace(&mut state.allow_wildcards, false);
+            let js_type = check_type_annotation(
+                &env.types,
+                &mut env.type_variables.clone(),
+                state,
+                type_annotation,
+            );
+            state.allow_wildcards = allow_wildcards;
+            Ok(Expression::InlineJs {
+                span,
+                // The parser has already rejected bad escapes
+                code: cst::unescape_string(&code.value).expect("valid string escapes"),
+                js_type: js_type?,
+            })
+        }
         cst::Expression::Array(brackets) => {
             let mut elements = Vec::new();
             if let Some(cst_elements) = brackets.value {
@@ -176,6 +386,14 @@ fn convert_cst(
             }
             Ok(Expression::Array { span, elements })
         }
+        cst::Expression::Tuple(parens) => {
+            let mut elements = Vec::new();
+            for cst_element in parens.value.into_iter() {
+                let element = convert_cst(env, state, *cst_element)?;
+                elements.push(element);
+            }
+            Ok(Expression::Tuple { span, elements })
+        }
         cst::Expression::If {
             box condition,
             box true_clause,
@@ -187,6 +405,127 @@ fn convert_cst(
             true_clause: Box::new(convert_cst(env, state, true_clause)?),
             false_clause: Box::new(convert_cst(env, state, false_clause)?),
         }),
+        cst::Expression::Match {
+            box expression,
+            box head_arm,
+            tail_arms,
+            ..
+        } => {
+            let expression = convert_cst(env, state, expression)?;
+            let mut arms = Vec::new();
+            for arm in std::iter::once(head_arm).chain(tail_arms) {
+                let cst::MatchArm {
+                    pattern,
+                    box expression,
+                    ..
+                } = arm;
+                arms.push((
+                    convert_cst_pattern(pattern),
+                    convert_cst(env, state, expression)?,
+                ));
+            }
+            Ok(Expression::Match {
+                span,
+                expression: Box::new(expression),
+                arms,
+            })
+        }
+        cst::Expression::Let {
+            box head_declaration,
+            tail_declarations,
+            box expression,
+            ..
+        } => {
+            // NOTE multiple declarations are nested,
+            // i.e. `let x = 5; y = x; in y` is equivalent to `let x = 5; in let y = x; in y`
+            let mut env_type_variables = env.type_variables.clone();
+            let mut declarations = Vec::new();
+            for declaration in std::iter::once(head_declaration).chain(tail_declarations) {
+                let cst::ValueDeclaration {
+                    name,
+                    type_annotation,
+                    expression,
+                    ..
+                } = declaration;
+                let name_span = name.get_span();
+                let type_annotation = if let Some(type_annotation) = type_annotation {
+                    Some(check_type_annotation(
+                        &env.types,
+                        &mut env_type_variables,
+                        state,
+                        type_annotation,
+                    )?)
+                } else {
+                    None
+                };
+                let expression = convert_cst(
+                    &Env {
+                        types: env.types.clone(),
+                        type_variables: env_type_variables.clone(),
+                    },
+                    state,
+                    expression,
+                )?;
+                declarations.push((name_span, type_annotation, Name::from(name), expression));
+            }
+            let body = convert_cst(
+                &Env {
+                    types: env.types.clone(),
+                    type_variables: env_type_variables,
+                },
+                state,
+                expression,
+            )?;
+            Ok(declarations.into_iter().rev().fold(
+                body,
+                |body, (name_span, type_annotation, name, expression)| Expression::Let {
+                    span,
+                    name_span,
+                    type_annotation,
+                    name,
+                    expression: Box::new(expression),
+                    body: Box::new(body),
+                },
+            ))
+        }
+        cst::Expression::BinOp {
+            box lhs,
+            operator: cst::BinOp::Pipe(_),
+            box rhs,
+        } => {
+            // `x |> f` is sugar for `f(x)`
+            let argument = convert_cst(env, state, lhs)?;
+            let function = convert_cst(env, state, rhs)?;
+            Ok(Expression::Call {
+                span,
+                function: Box::new(function),
+                arguments: vec![Argument::Piped(argument)],
+            })
+        }
+        cst::Expression::BinOp {
+            box lhs,
+            operator,
+            box rhs,
+        } => Ok(Expression::BinOp {
+            span,
+            operator: match operator {
+                cst::BinOp::Plus(_) => BinOp::Add,
+                cst::BinOp::Minus(_) => BinOp::Subtract,
+                cst::BinOp::Times(_) => BinOp::Multiply,
+                cst::BinOp::Divide(_) => BinOp::Divide,
+                cst::BinOp::Equals(_) => BinOp::Equals,
+                cst::BinOp::NotEquals(_) => BinOp::NotEquals,
+                cst::BinOp::LessThan(_) => BinOp::LessThan,
+                cst::BinOp::LessThanOrEquals(_) => BinOp::LessThanOrEquals,
+                cst::BinOp::GreaterThan(_) => BinOp::GreaterThan,
+                cst::BinOp::GreaterThanOrEquals(_) => BinOp::GreaterThanOrEquals,
+                cst::BinOp::And(_) => BinOp::And,
+                cst::BinOp::Or(_) => BinOp::Or,
+                cst::BinOp::Pipe(_) => unreachable!(),
+            },
+            lhs: Box::new(convert_cst(env, state, lhs)?),
+            rhs: Box::new(convert_cst(env, state, rhs)?),
+        }),
         cst::Expression::Call {
             box function,
             arguments: parens,
@@ -216,8 +555,8 @@ fn convert_cst(
 
             let mut binders = Vec::new();
             if let Some(parameters) = parameters.value {
-                for (name, type_annotation) in parameters.into_iter() {
-                    let span = name.get_span();
+                for (binder, type_annotation) in parameters.into_iter() {
+                    let span = binder.get_span();
                     let type_annotation = if let Some(type_annotation) = type_annotation {
                         Some(check_type_annotation(
                             &env.types,
@@ -228,11 +567,16 @@ fn convert_cst(
                     } else {
                         None
                     };
-                    let value = Name::from(name);
-                    binders.push(FunctionBinder::Name {
-                        span,
-                        type_annotation,
-                        value,
+                    binders.push(match binder {
+                        cst::FunctionBinder::Name(name) => FunctionBinder::Name {
+                            span,
+                            type_annotation,
+                            value: Name::from(name),
+                        },
+                        cst::FunctionBinder::Wildcard(_) => FunctionBinder::Wildcard {
+                            span,
+                            type_annotation,
+                        },
                     });
                 }
             }
@@ -267,6 +611,77 @@ fn convert_cst(
     }
 }
 
+fn convert_cst_pattern(cst_pattern: cst::Pattern) -> Pattern {
+    let span = cst_pattern.get_span();
+    match cst_pattern {
+        cst::Pattern::Constructor {
+            constructor,
+            arguments,
+        } => Pattern::Constructor {
+            span,
+            constructor: QualifiedProperName::from(constructor),
+            arguments: arguments.map_or_else(Vec::new, |parens| {
+                parens
+                    .value
+                    .into_iter()
+                    .map(|box pattern| convert_cst_pattern(pattern))
+                    .collect()
+            }),
+        },
+        cst::Pattern::RecordConstructor {
+            constructor,
+            fields: braces,
+        } => Pattern::RecordConstructor {
+            span,
+            constructor_span: constructor.get_span(),
+            constructor: QualifiedProperName::from(constructor),
+            rest: braces.value.rest.is_some(),
+            fields: braces
+                .value
+                .fields
+                .into_iter()
+                .map(|field| RecordPatternField {
+                    label_span: field.label.get_span(),
+                    label: Name::from(field.label),
+                    pattern: convert_cst_pattern(*field.pattern),
+                })
+                .collect(),
+        },
+        cst::Pattern::Tuple(parens) => Pattern::Tuple {
+            span,
+            elements: parens
+                .value
+                .into_iter()
+                .map(|box pattern| convert_cst_pattern(pattern))
+                .collect(),
+        },
+        cst::Pattern::Variable { name } => Pattern::Variable {
+            span,
+            name: Name::from(name),
+        },
+        cst::Pattern::Wildcard(_) => Pattern::Wildcard { span },
+    }
+}
+
+fn convert_cst_record_fields(
+    env: &Env,
+    state: &mut State,
+    braces: cst::BracesList1<cst::RecordField>,
+) -> Result<Vec<RecordField>> {
+    let mut fields = Vec::new();
+    for cst_field in braces.value.into_iter() {
+        let cst::RecordField {
+            label, box value, ..
+        } = cst_field;
+        fields.push(RecordField {
+            label_span: label.get_span(),
+            label: Name::from(label),
+            value: convert_cst(env, state, value)?,
+        });
+    }
+    Ok(fields)
+}
+
 pub fn check_type_annotation(
     env_types: &EnvTypes,
     env_type_variables: &mut EnvTypeVariables,
@@ -313,6 +728,13 @@ fn substitute_type_annotations(subst: &Substitution, expression: Expression) ->
                         type_annotation: type_annotation.map(|t| subst.apply_type(t)),
                         value,
                     },
+                    FunctionBinder::Wildcard {
+                        span,
+                        type_annotation,
+                    } => FunctionBinder::Wildcard {
+                        span,
+                        type_annotation: type_annotation.map(|t| subst.apply_type(t)),
+                    },
                 })
                 .collect(),
             return_type_annotation: return_type_annotation.map(|t| subst.apply_type(t)),
@@ -331,6 +753,9 @@ fn substitute_type_annotations(subst: &Substitution, expression: Expression) ->
                     Argument::Expression(expr) => {
                         Argument::Expression(substitute_type_annotations(subst, expr))
                     }
+                    Argument::Piped(expr) => {
+                        Argument::Piped(substitute_type_annotations(subst, expr))
+                    }
                 })
                 .collect(),
         },
@@ -345,11 +770,95 @@ fn substitute_type_annotations(subst: &Substitution, expression: Expression) ->
             true_clause: Box::new(substitute_type_annotations(subst, true_clause)),
             false_clause: Box::new(substitute_type_annotations(subst, false_clause)),
         },
+        Match {
+            span,
+            box expression,
+            arms,
+        } => Match {
+            span,
+            expression: Box::new(substitute_type_annotations(subst, expression)),
+            arms: arms
+                .into_iter()
+                .map(|(pattern, expression)| {
+                    (pattern, substitute_type_annotations(subst, expression))
+                })
+                .collect(),
+        },
+        Let {
+            span,
+            name_span,
+            type_annotation,
+            name,
+            box expression,
+            box body,
+        } => Let {
+            span,
+            name_span,
+            type_annotation: type_annotation.map(|t| subst.apply_type(t)),
+            name,
+            expression: Box::new(substitute_type_annotations(subst, expression)),
+            body: Box::new(substitute_type_annotations(subst, body)),
+        },
+        BinOp {
+            span,
+            operator,
+            box lhs,
+            box rhs,
+        } => BinOp {
+            span,
+            operator,
+            lhs: Box::new(substitute_type_annotations(subst, lhs)),
+            rhs: Box::new(substitute_type_annotations(subst, rhs)),
+        },
+        Negate {
+            span,
+            box expression,
+        } => Negate {
+            span,
+            expression: Box::new(substitute_type_annotations(subst, expression)),
+        },
         Constructor { span, constructor } => Constructor { span, constructor },
+        RecordConstructor {
+            span,
+            constructor_span,
+            constructor,
+            fields,
+        } => RecordConstructor {
+            span,
+            constructor_span,
+            constructor,
+            fields: substitute_record_fields(subst, fields),
+        },
+        Record { span, fields } => Record {
+            span,
+            fields: substitute_record_fields(subst, fields),
+        },
+        RecordAccess {
+            span,
+            box target,
+            label_span,
+            label,
+        } => RecordAccess {
+            span,
+            target: Box::new(substitute_type_annotations(subst, target)),
+            label_span,
+            label,
+        },
         Variable { span, variable } => Variable { span, variable },
         String { span, value } => String { span, value },
+        Char { span, value } => Char { span, value },
         Int { span, value } => Int { span, value },
         Float { span, value } => Float { span, value },
+        Hole { span, name } => Hole { span, name },
+        InlineJs {
+            span,
+            code,
+            js_type,
+        } => InlineJs {
+            span,
+            code,
+            js_type: subst.apply_type(js_type),
+        },
         Array { span, elements } => Array {
             span,
             elements: elements
@@ -357,12 +866,54 @@ fn substitute_type_annotations(subst: &Substitution, expression: Expression) ->
                 .map(|element| substitute_type_annotations(subst, element))
                 .collect(),
         },
+        Tuple { span, elements } => Tuple {
+            span,
+            elements: elements
+                .into_iter()
+                .map(|element| substitute_type_annotations(subst, element))
+                .collect(),
+        },
         True { span } => True { span },
         False { span } => False { span },
         Unit { span } => Unit { span },
     }
 }
 
+fn substitute_record_fields(subst: &Substitution, fields: Vec<RecordField>) -> Vec<RecordField> {
+    fields
+        .into_iter()
+        .map(
+            |RecordField {
+                 label_span,
+                 label,
+                 value,
+             }| RecordField {
+                label_span,
+                label,
+                value: substitute_type_annotations(subst, value),
+            },
+        )
+        .collect()
+}
+
+/// The grammar only checks that unicode escapes are (at most six) hex digits,
+/// this checks they're actually a character.
+fn check_char_escape(span: Span, value: &str) -> Result<()> {
+    if let Some(hex) = value
+        .strip_prefix("\\u{")
+        .and_then(|hex| hex.strip_suffix('}'))
+    {
+        let is_char = u32::from_str_radix(hex, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .is_some();
+        if !is_char {
+            return Err(TypeError::InvalidCharEscape { span });
+        }
+    }
+    Ok(())
+}
+
 fn strip_number_separators(value: String) -> String {
     value.replace('_', "")
 }
@@ -1,6 +1,6 @@
-use super::Substitution;
+use super::{EnvValues, Substitution};
 use crate::{result::Warnings, supply::Supply};
-use ditto_ast::{QualifiedName, QualifiedProperName};
+use ditto_ast::{Name, QualifiedName, QualifiedProperName, Span, Type};
 use std::collections::HashMap;
 
 #[derive(Default)]
@@ -10,6 +10,16 @@ pub struct State {
     pub warnings: Warnings,
     pub value_references: ValueReferences,
     pub constructor_references: ConstructorReferences,
+    pub holes: Vec<Hole>,
+}
+
+/// A typed hole, which is reported once the final substitution is known.
+pub struct Hole {
+    pub span: Span,
+    pub name: Name,
+    pub hole_type: Type,
+    /// The values that were in scope at the hole.
+    pub env_values: EnvValues,
 }
 
 pub type ValueReferences = References<QualifiedName>;
@@ -67,6 +67,22 @@ impl Substitution {
                 source_value: _,
             } => ast_type,
             Type::PrimConstructor(_) => ast_type,
+            Type::Record { fields } => Type::Record {
+                fields: fields
+                    .into_iter()
+                    .map(|(label, t)| (label, self.apply_rec(t, depth)))
+                    .collect(),
+            },
+            Type::Tuple { elements } => Type::Tuple {
+                elements: {
+                    let (head, tail) = elements.split_first();
+                    let mut elements = NonEmpty::new(self.apply_rec(head.clone(), depth));
+                    for t in tail {
+                        elements.push(self.apply_rec(t.clone(), depth));
+                    }
+                    elements
+                },
+            },
         }
     }
 
@@ -109,6 +125,12 @@ impl Substitution {
                             binder_type: self.apply(binder_type),
                             value,
                         },
+                        FunctionBinder::Wildcard { span, binder_type } => {
+                            FunctionBinder::Wildcard {
+                                span,
+                                binder_type: self.apply(binder_type),
+                            }
+                        }
                     })
                     .collect(),
                 body: Box::new(self.apply_expression(body)),
@@ -126,6 +148,39 @@ impl Substitution {
                 true_clause: Box::new(self.apply_expression(true_clause)),
                 false_clause: Box::new(self.apply_expression(false_clause)),
             },
+            Match {
+                span,
+                match_type,
+                box expression,
+                arms,
+            } => Match {
+                span,
+                match_type: self.apply(match_type),
+                expression: Box::new(self.apply_expression(expression)),
+                arms: {
+                    let (head, tail) = arms.split_first();
+                    let (head_pattern, head_expression) = head.clone();
+                    let mut arms =
+                        NonEmpty::new((head_pattern, self.apply_expression(head_expression)));
+                    for (pattern, expression) in tail.iter().cloned() {
+                        arms.push((pattern, self.apply_expression(expression)));
+                    }
+                    arms
+                },
+            },
+            Let {
+                span,
+                name_span,
+                name,
+                box expression,
+                box body,
+            } => Let {
+                span,
+                name_span,
+                name,
+                expression: Box::new(self.apply_expression(expression)),
+                body: Box::new(self.apply_expression(body)),
+            },
             LocalConstructor {
                 constructor_type,
                 span,
@@ -144,6 +199,15 @@ impl Substitution {
                 span,
                 constructor,
             },
+            PrimConstructor {
+                constructor_type,
+                span,
+                constructor,
+            } => PrimConstructor {
+                constructor_type: self.apply(constructor_type),
+                span,
+                constructor,
+            },
             LocalVariable {
                 variable_type,
                 span,
@@ -157,10 +221,12 @@ impl Substitution {
                 variable_type,
                 span,
                 variable,
+                foreign_name,
             } => ForeignVariable {
                 variable_type: self.apply(variable_type),
                 span,
                 variable,
+                foreign_name,
             },
             ImportedVariable {
                 variable_type,
@@ -171,6 +237,15 @@ impl Substitution {
                 span,
                 variable,
             },
+            PrimVariable {
+                variable_type,
+                span,
+                variable,
+            } => PrimVariable {
+                variable_type: self.apply(variable_type),
+                span,
+                variable,
+            },
             Array {
                 span,
                 element_type,
@@ -183,11 +258,66 @@ impl Substitution {
                     .map(|element| self.apply_expression(element))
                     .collect(),
             },
+            RecordAccess {
+                span,
+                field_type,
+                box target,
+                label,
+            } => RecordAccess {
+                span,
+                field_type: self.apply(field_type),
+                target: Box::new(self.apply_expression(target)),
+                label,
+            },
+            BinOp {
+                span,
+                binop_type,
+                operator,
+                box lhs,
+                box rhs,
+            } => BinOp {
+                span,
+                binop_type: self.apply(binop_type),
+                operator,
+                lhs: Box::new(self.apply_expression(lhs)),
+                rhs: Box::new(self.apply_expression(rhs)),
+            },
+            Negate {
+                span,
+                box expression,
+            } => Negate {
+                span,
+                expression: Box::new(self.apply_expression(expression)),
+            },
+            Record { span, fields } => Record {
+                span,
+                fields: fields
+                    .into_iter()
+                    .map(|(label, value)| (label, self.apply_expression(value)))
+                    .collect(),
+            },
+            Tuple { span, elements } => Tuple {
+                span,
+                elements: elements
+                    .into_iter()
+                    .map(|element| self.apply_expression(element))
+                    .collect(),
+            },
+            InlineJs {
+                span,
+                code,
+                value_type,
+            } => InlineJs {
+                span,
+                code,
+                value_type: self.apply(value_type),
+            },
             // noop
             True { .. } => expression,
             False { .. } => expression,
             Unit { .. } => expression,
             String { .. } => expression,
+            Char { .. } => expression,
             Int { .. } => expression,
             Float { .. } => expression,
         }
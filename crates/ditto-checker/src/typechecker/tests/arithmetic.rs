@@ -0,0 +1,25 @@
+use super::macros::*;
+use crate::TypeError::*;
+
+#[test]
+fn it_typechecks_as_expected() {
+    assert_type!("1 + 2", "Int");
+    assert_type!("1 - 2 * 3 / 4", "Int");
+    assert_type!("1.0 + 2.5", "Float");
+    assert_type!("(1.0 - 2.0) * 3.0 / 4.0", "Float");
+    assert_type!("(a: Float, b) -> a * b", "(Float, Float) -> Float");
+    assert_type!("(a, b: Float) -> a + b", "(Float, Float) -> Float");
+    assert_type!("(a) -> a + 1", "(Int) -> Int");
+    assert_type!("(a, b) -> a - b", "(Int, Int) -> Int");
+    assert_type!("[1 + 1, 2 * 2]", "Array(Int)");
+}
+
+#[test]
+fn it_errors_as_expected() {
+    assert_type_error!("1 + 2.0", TypesNotEqual { .. });
+    assert_type_error!("1.0 * 2", TypesNotEqual { .. });
+    assert_type_error!(r#""a" + "b""#, TypesNotEqual { .. });
+    assert_type_error!("true / false", TypesNotEqual { .. });
+    assert_type_error!("(a: a) -> a + a", TypesNotEqual { .. });
+    assert_type_error!("(f: (Int) -> Int) -> f + 1", TypesNotEqual { .. });
+}
@@ -1,7 +1,20 @@
 use super::macros::*;
+use crate::TypeError::*;
 
 #[test]
 fn it_typechecks_as_expected() {
     assert_type!("true", "Bool");
     assert_type!("false", "Bool");
+    assert_type!("true && false", "Bool");
+    assert_type!("true || false", "Bool");
+    assert_type!("(a) -> a && true", "(Bool) -> Bool");
+    assert_type!("(a, b) -> a || b && a", "(Bool, Bool) -> Bool");
+    assert_type!("1 < 2 && 3 > 2 || false", "Bool");
+}
+
+#[test]
+fn it_errors_as_expected() {
+    assert_type_error!("true && 1", TypesNotEqual { .. });
+    assert_type_error!("unit || false", TypesNotEqual { .. });
+    assert_type_error!("(f: () -> Bool) -> f || true", TypesNotEqual { .. });
 }
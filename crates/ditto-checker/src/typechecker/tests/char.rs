@@ -0,0 +1,19 @@
+use super::macros::*;
+use crate::TypeError::*;
+
+#[test]
+fn it_typechecks_as_expected() {
+    assert_type!("'a'", "Char");
+    assert_type!(r"'\n'", "Char");
+    assert_type!(r"'\u{1F600}'", "Char");
+    assert_type!("['a', 'b']", "Array(Char)");
+    assert_type!("(c: Char) -> c == 'a'", "(Char) -> Bool");
+    assert_type!("(c) -> 'a' <= c", "(Char) -> Bool");
+}
+
+#[test]
+fn it_errors_as_expected() {
+    assert_type_error!("'a' == \"a\"", TypesNotEqual { .. });
+    assert_type_error!(r"'\u{D800}'", InvalidCharEscape { .. });
+    assert_type_error!(r"'\u{110000}'", InvalidCharEscape { .. });
+}
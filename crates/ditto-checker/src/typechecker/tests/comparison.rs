@@ -0,0 +1,103 @@
+use super::macros::*;
+use crate::{
+    module::tests::macros::{assert_module_err, assert_module_ok, assert_module_warnings},
+    TypeError::*,
+};
+
+#[test]
+fn it_typechecks_as_expected() {
+    assert_type!("1 == 2", "Bool");
+    assert_type!("1.0 != 2.5", "Bool");
+    assert_type!(r#""a" == "b""#, "Bool");
+    assert_type!("true != false", "Bool");
+    assert_type!("unit == unit", "Bool");
+    assert_type!("1 + 2 == 3", "Bool");
+    assert_type!("1 < 2", "Bool");
+    assert_type!("1.0 <= 2.0", "Bool");
+    assert_type!(r#""a" > "b""#, "Bool");
+    assert_type!("(a, b: Float) -> a >= b", "(Float, Float) -> Bool");
+    assert_type!("(a) -> a == 1", "(Int) -> Bool");
+    assert_type!("(a, b) -> a < b", "(Int, Int) -> Bool");
+    assert_type!("(a, b) -> a == b", "($1, $1) -> Bool");
+    assert_type!("(a: a, b: a): Bool -> a != b", "(a, a) -> Bool");
+    assert_type!("[1, 2] == [3]", "Bool");
+    assert_type!("[[true]] != []", "Bool");
+    assert_type!(r#"{ x = 1, y = "y" } == { x = 2, y = "z" }"#, "Bool");
+}
+
+#[test]
+fn it_errors_as_expected() {
+    assert_type_error!("1 == 2.0", TypesNotEqual { .. });
+    assert_type_error!(r#""a" < 1"#, TypesNotEqual { .. });
+    assert_type_error!("(f: (Int) -> Int) -> f == f", UnsupportedEquality { .. });
+    assert_type_error!("(f: (Int) -> Int) -> [f] != []", UnsupportedEquality { .. });
+    assert_type_error!(
+        "(r: { f: () -> Int }) -> r == r",
+        UnsupportedEquality { .. }
+    );
+    assert_type_error!("true < false", UnsupportedOrdering { .. });
+    assert_type_error!("unit >= unit", UnsupportedOrdering { .. });
+    assert_type_error!("[1] == [1.0]", TypesNotEqual { .. });
+    assert_module_err!(
+        r#"
+        module Test exports (..);
+
+        type Maybe(a) = Just(a) | Nothing;
+
+        same = Just(() -> 5) == Nothing;
+        "#,
+        UnsupportedEquality { .. }
+    );
+}
+
+#[test]
+fn it_compares_constructors_structurally() {
+    assert_module_ok!(
+        r#"
+        module Test exports (..);
+
+        type Maybe(a) = Just(a) | Nothing;
+
+        same = Just(5) == Nothing;
+
+        different = (a: Maybe(a), b: Maybe(a)): Bool -> a != b;
+        "#
+    );
+}
+
+#[test]
+fn it_warns_about_constant_comparisons() {
+    assert_module_warnings!(
+        r#"
+        module Test exports (..);
+
+        type Maybe(a) = Just(a) | Nothing;
+
+        -- expect-warning: constant-comparison
+        never = (x: Int): Bool -> Just(x) == Nothing;
+
+        -- expect-warning: constant-comparison
+        always = (x: Int): Bool -> Nothing != Just(x);
+
+        maybe_equal = (x: Int): Bool -> Just(x) == Just(5);
+
+        maybe_nothing = (m: Maybe(Int)): Bool -> m == Nothing;
+        "#
+    );
+}
+
+#[test]
+fn it_reports_mismatched_right_operands() {
+    let source = "1 == true";
+    let cst_expression = ditto_cst::Expression::parse(source).unwrap();
+    let err = crate::typechecker::typecheck(None, cst_expression).unwrap_err();
+    assert!(
+        matches!(
+            err,
+            TypesNotEqual { span, .. }
+                if span.start_offset == 5 && span.end_offset == source.len()
+        ),
+        "{:#?}",
+        err
+    );
+}
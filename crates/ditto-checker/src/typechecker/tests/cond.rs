@@ -1,5 +1,5 @@
 use super::macros::*;
-use crate::TypeError::*;
+use crate::{TypeError::*, Warning};
 
 #[test]
 fn it_typechecks_as_expected() {
@@ -20,3 +20,26 @@ fn it_errors_as_expected() {
         TypesNotEqual { .. }
     );
 }
+
+#[test]
+fn it_warns_about_unreachable_branches() {
+    assert_unreachable(r#"if true then "yea" else "nay""#, &[r#""nay""#]);
+    assert_unreachable(r#"if false then "yea" else "nay""#, &[r#""yea""#]);
+    assert_unreachable("if true then if false then 1 else 2 else 3", &["1", "3"]);
+    assert_unreachable("(b) -> if b then 1 else 2", &[]);
+}
+
+/// Assert the source of every unreachable branch.
+fn assert_unreachable(expr: &str, want: &[&str]) {
+    let cst_expression = ditto_cst::Expression::parse(expr).unwrap();
+    let (_, _, _, _, warnings, _) = crate::typechecker::typecheck(None, cst_expression).unwrap();
+    let mut unreachable = warnings
+        .iter()
+        .map(|warning| match warning {
+            Warning::UnreachableBranch { span } => &expr[span.start_offset..span.end_offset],
+            other => panic!("unexpected warning: {:#?}", other),
+        })
+        .collect::<Vec<_>>();
+    unreachable.sort_unstable();
+    assert_eq!(unreachable, want);
+}
@@ -0,0 +1,17 @@
+use super::macros::*;
+use crate::TypeError::*;
+
+#[test]
+fn it_typechecks_as_expected() {
+    assert_type!(r#" debug_log                   "#, "(String, $1) -> $1");
+    assert_type!(r#" debug_log("five", 5)        "#, "Int");
+    assert_type!(r#" debug_log("five", "5")      "#, "String");
+    assert_type!(r#" debug_log("fives", [5, 5])  "#, "Array(Int)");
+    assert_type!(r#" (x: a) -> debug_log("x", x) "#, "(a) -> a");
+}
+
+#[test]
+fn it_errors_as_expected() {
+    assert_type_error!(r#" debug_log(5, 5)     "#, TypesNotEqual { .. });
+    assert_type_error!(r#" debug_log("five")   "#, ArgumentLengthMismatch { .. });
+}
@@ -9,8 +9,22 @@ fn it_typechecks_as_expected() {
     assert_type!("50_000_000.000_05", "Float");
 }
 
+#[test]
+fn it_typechecks_special_values() {
+    assert_type!("Float.infinity                ", "Float");
+    assert_type!("Float.neg_infinity            ", "Float");
+    assert_type!("Float.nan                     ", "Float");
+    assert_type!("-Float.infinity               ", "Float");
+    assert_type!("[5.0, Float.nan]              ", "Array(Float)");
+    assert_type!("Float.nan == Float.nan        ", "Bool");
+    assert_type!("(): Float -> Float.neg_infinity", "() -> Float");
+}
+
 #[test]
 fn it_errors_as_expected() {
     assert_type_error!("(): Float -> 5", TypesNotEqual { .. });
     assert_type_error!("(): Int -> 5.0", TypesNotEqual { .. });
+    assert_type_error!("(): Int -> Float.infinity", TypesNotEqual { .. });
+    assert_type_error!("Float.pi", UnknownVariable { .. });
+    assert_type_error!("nan", UnknownVariable { .. });
 }
@@ -61,4 +61,7 @@ fn it_warns_as_expected() {
         "(a, b) -> b",
         [UnusedFunctionBinder { .. }]
     );
+    // Wildcards are never unused, and never duplicates
+    assert_type!("(_, _, x: Int) -> x", "($0, $1, Int) -> Int", []);
+    assert_type!("(_: String) -> 5", "(String) -> Int", []);
 }
@@ -0,0 +1,46 @@
+use super::macros::*;
+use crate::TypeError::{self, *};
+
+#[test]
+fn it_errors_as_expected() {
+    assert_type_error!("?help", TypedHole { .. });
+    assert_type_error!("[5, ?help]", TypedHole { .. });
+    // Holes are reported even if the rest of the expression is fine
+    assert_type_error!("(a) -> if true then a else ?help", TypedHole { .. });
+}
+
+#[test]
+fn it_reports_the_expected_type_and_candidates() {
+    let (expected_type, candidates) = typed_hole("(a: Int, b: String, c: Int): Int -> ?help");
+    assert_eq!(expected_type, "Int");
+    assert_eq!(candidates, vec!["a", "c"]);
+
+    let (expected_type, candidates) = typed_hole("(f: (Int) -> Bool) -> if f(?n) then 1 else 2");
+    assert_eq!(expected_type, "Int");
+    assert!(candidates.is_empty());
+
+    let (expected_type, _) = typed_hole("(a: Int) -> [a, ?help]");
+    assert_eq!(expected_type, "Int");
+}
+
+#[test]
+fn it_reports_the_first_hole() {
+    let (expected_type, _) = typed_hole(r#"(a: Int) -> ([?first, "first"], ?second + a)"#);
+    assert_eq!(expected_type, "String");
+}
+
+fn typed_hole(expr: &str) -> (String, Vec<String>) {
+    let cst_expression = ditto_cst::Expression::parse(expr).unwrap();
+    match crate::typechecker::typecheck(None, cst_expression) {
+        Err(TypeError::TypedHole {
+            expected_type,
+            candidates,
+            ..
+        }) => (
+            expected_type.debug_render(),
+            candidates.iter().map(|name| name.to_string()).collect(),
+        ),
+        Err(err) => panic!("unexpected error: {:#?}", err),
+        Ok(_) => panic!("unexpected typecheck"),
+    }
+}
@@ -0,0 +1,20 @@
+use super::macros::*;
+use crate::TypeError::*;
+
+#[test]
+fn it_typechecks_as_expected() {
+    assert_type!(r#"js "Date.now()" : Int"#, "Int");
+    assert_type!(
+        r#"js "(a, b) => a + b" : (Int, Int) -> Int"#,
+        "(Int, Int) -> Int"
+    );
+    assert_type!(r#"(js "(a, b) => a + b" : (Int, Int) -> Int)(1, 2)"#, "Int");
+    assert_type!(r#"[js "5" : Int, 5]"#, "Array(Int)");
+}
+
+#[test]
+fn it_errors_as_expected() {
+    assert_type_error!(r#"[js "5" : Int, 5.0]"#, TypesNotEqual { .. });
+    assert_type_error!(r#"js "5" : _"#, UnexpectedTypeWildcard { .. });
+    assert_type_error!(r#"js "5" : Nope"#, UnknownTypeConstructor { .. });
+}
@@ -1,4 +1,5 @@
 use super::macros::*;
+use crate::TypeError::*;
 
 #[test]
 fn it_typechecks_as_expected() {
@@ -7,3 +8,23 @@ fn it_typechecks_as_expected() {
     assert_type!("(((5)))       ", "Int");
     assert_type!("5_50_500      ", "Int");
 }
+
+#[test]
+fn it_typechecks_checked_division() {
+    assert_type!("Int.div                  ", "(Int, Int) -> Maybe(Int)");
+    assert_type!("Int.div(7, 2)            ", "Maybe(Int)");
+    assert_type!("Int.div(7, 0)            ", "Maybe(Int)");
+    assert_type!("(n) -> Int.div(n, 0)     ", "(Int) -> Maybe(Int)");
+    assert_type!(
+        "match Int.div(7, 0) with | Maybe.Just(n) -> n | Maybe.Nothing -> 0 end",
+        "Int"
+    );
+}
+
+#[test]
+fn it_errors_as_expected() {
+    assert_type_error!("Int.div(7.0, 2.0)", TypesNotEqual { .. });
+    assert_type_error!("Int.div(7)", ArgumentLengthMismatch { .. });
+    assert_type_error!("(): Int -> Int.div(7, 2)", TypesNotEqual { .. });
+    assert_type_error!("div(7, 2)", UnknownVariable { .. });
+}
@@ -0,0 +1,32 @@
+use super::macros::*;
+use crate::{TypeError::*, Warning::*};
+
+#[test]
+fn it_typechecks_as_expected() {
+    assert_type!("let five = 5; in five", "Int");
+    assert_type!("let five: Int = 5; in [five]", "Array(Int)");
+    assert_type!(r#"let a = "a"; b = [a]; in b"#, "Array(String)");
+    assert_type!("let a = 1; in let b = a; in b", "Int");
+    assert_type!("let five = 5; in true", "Bool", [UnusedLetBinder { .. }]);
+    assert_type!(
+        "let five = 5; in (five) -> five",
+        "($0) -> $0",
+        [UnusedLetBinder { .. }]
+    );
+    assert_type!(
+        "let id = (a) -> a; in if id(true) then id(1) else id(2)",
+        "Int"
+    );
+    assert_type!(
+        "let id = (a) -> a; in let f = id; in [f(id)(5), f(5)]",
+        "Array(Int)"
+    );
+    assert_type!("(a) -> let b = a; in [b, 5]", "(Int) -> Array(Int)");
+}
+
+#[test]
+fn it_errors_as_expected() {
+    assert_type_error!("let five: String = 5; in five", TypesNotEqual { .. });
+    assert_type_error!("let a = b; b = 5; in a", UnknownVariable { .. });
+    assert_type_error!("let a = 5; in if a then a else a", TypesNotEqual { .. });
+}
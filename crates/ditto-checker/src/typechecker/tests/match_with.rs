@@ -0,0 +1,173 @@
+use super::macros::*;
+use crate::{
+    module::tests::macros::{assert_module_err, assert_module_ok, parse_and_check_module},
+    TypeError::*,
+    Warning::*,
+};
+use ditto_ast::proper_name;
+
+#[test]
+fn it_typechecks_as_expected() {
+    assert_type!("match 5 with | x -> x end", "Int");
+    assert_type!("match 5 with | _ -> true end", "Bool");
+    assert_type!("(a) -> match a with | b -> [b] end", "($0) -> Array($0)");
+    assert_type!(
+        "match 5 with | x -> true end",
+        "Bool",
+        [UnusedPatternBinder { .. }]
+    );
+    assert_module_ok!(
+        r#"
+        module Test exports (..);
+
+        type Maybe(a) = Just(a) | Nothing;
+
+        with_default = (maybe: Maybe(a), default: a): a ->
+            match maybe with
+            | Just(a) -> a
+            | Nothing -> default
+            end;
+
+        five: Int = with_default(Just(5), 0);
+        "#
+    );
+    assert_module_ok!(
+        r#"
+        module Test exports (..);
+
+        type Maybe(a) = Just(a) | Nothing;
+        type Pair(a, b) = Pair(a, b);
+
+        first = (maybe_pair) ->
+            match maybe_pair with
+            | Just(Pair(a, _)) -> Just(a)
+            | _ -> Nothing
+            end;
+        "#
+    );
+    assert_module_ok!(
+        r#"
+        module Test exports (..);
+
+        type Person = Person { name: String, age: Int };
+
+        get_name = (person) -> match person with | Person(name, _) -> name end;
+        "#
+    );
+    assert_module_ok!(
+        r#"
+        module Test exports (..);
+
+        type Maybe(a) = Just(a) | Nothing;
+
+        is_just = (maybe) -> match maybe with | Just(unused) -> true | Nothing -> false end;
+        "#,
+        [UnusedPatternBinder { .. }]
+    );
+}
+
+#[test]
+fn it_checks_exhaustiveness() {
+    assert_module_ok!(
+        r#"
+        module Test exports (..);
+
+        type Maybe(a) = Just(a) | Nothing;
+
+        is_nested_just = (maybe) ->
+            match maybe with
+            | Just(Just(_)) -> true
+            | Just(Nothing) -> false
+            | Nothing -> false
+            end;
+        "#
+    );
+    assert_module_ok!(
+        r#"
+        module Test exports (..);
+
+        type Maybe(a) = Just(a) | Nothing;
+
+        is_just = (maybe) ->
+            match maybe with
+            | Nothing -> false
+            | _ -> true
+            | Just(_) -> true
+            end;
+        "#,
+        [RedundantMatchArm { .. }]
+    );
+    let err = parse_and_check_module!(
+        r#"
+        module Test exports (..);
+
+        type Abc = A | B | C;
+
+        to_int = (abc) -> match abc with | A -> 1 | C -> 3 end;
+        "#
+    )
+    .unwrap_err();
+    assert!(
+        matches!(err, MatchNotExhaustive { ref missing, .. } if missing == &[proper_name!("B")]),
+        "{:#?}",
+        err
+    );
+    assert_module_err!(
+        r#"
+        module Test exports (..);
+
+        type Maybe(a) = Just(a) | Nothing;
+
+        is_nested_just = (maybe) ->
+            match maybe with
+            | Just(Just(_)) -> true
+            | Nothing -> false
+            end;
+        "#,
+        MatchNotExhaustive { .. }
+    );
+}
+
+#[test]
+fn it_errors_as_expected() {
+    assert_type_error!(
+        "match 5 with | x -> x | _ -> true end",
+        TypesNotEqual { .. }
+    );
+    assert_type_error!("match 5 with | Just(x) -> x end", UnknownConstructor { .. });
+    assert_type_error!(
+        "let x: String = match 5 with | y -> y end; in x",
+        TypesNotEqual { .. }
+    );
+    assert_type_error!("match 5 with | x -> x | _ -> x end", UnknownVariable { .. });
+    assert_module_err!(
+        r#"
+        module Test exports (..);
+
+        type Maybe(a) = Just(a) | Nothing;
+
+        x = match Just(5) with | Just -> 5 | _ -> 0 end;
+        "#,
+        ArgumentLengthMismatch { .. }
+    );
+    assert_module_err!(
+        r#"
+        module Test exports (..);
+
+        type Maybe(a) = Just(a) | Nothing;
+
+        x = match 5 with | Just(n) -> n | _ -> 0 end;
+        "#,
+        TypesNotEqual { .. }
+    );
+    assert_module_err!(
+        r#"
+        module Test exports (..);
+
+        type Pair(a, b) = Pair(a, b);
+
+        x = match Pair(1, 2) with | Pair(a, a) -> a end;
+        "#,
+        DuplicatePatternBinder { .. }
+    );
+}
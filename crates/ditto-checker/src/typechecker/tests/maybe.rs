@@ -0,0 +1,61 @@
+use super::macros::*;
+use crate::{
+    module::tests::macros::{assert_module_err, assert_module_ok},
+    TypeError::*,
+};
+
+#[test]
+fn it_typechecks_as_expected() {
+    assert_type!("Maybe.Just(5)                 ", "Maybe(Int)");
+    assert_type!("Maybe.Just                    ", "($1) -> Maybe($1)");
+    assert_type!("Maybe.Nothing                 ", "Maybe($1)");
+    assert_type!("[Maybe.Just(5), Maybe.Nothing]", "Array(Maybe(Int))");
+    assert_type!(
+        "(m: Maybe(String)) -> m       ",
+        "(Maybe(String)) -> Maybe(String)"
+    );
+    assert_type!(
+        "(m) -> match m with | Maybe.Just(a) -> a | Maybe.Nothing -> 0 end",
+        "(Maybe(Int)) -> Int"
+    );
+}
+
+#[test]
+fn it_errors_as_expected() {
+    assert_type_error!("[Maybe.Just(5), Maybe.Just(true)]", TypesNotEqual { .. });
+    assert_type_error!("Just(5)", UnknownConstructor { .. });
+    assert_type_error!("Maybe.Some(5)", UnknownConstructor { .. });
+    assert_type_error!(
+        "(m) -> match m with | Maybe.Just(a) -> a end",
+        MatchNotExhaustive { .. }
+    );
+}
+
+#[test]
+fn it_can_be_shadowed() {
+    assert_module_ok!(
+        r#"
+        module Test exports (..);
+
+        type Maybe(a) = Just(a) | Nothing;
+
+        with_default = (maybe: Maybe(a), default: a): a ->
+            match maybe with
+            | Just(a) -> a
+            | Nothing -> default
+            end;
+
+        five: Int = with_default(Just(5), 0);
+        "#
+    );
+    assert_module_err!(
+        r#"
+        module Test exports (..);
+
+        type Maybe(a) = Just(a) | Nothing;
+
+        five: Maybe(Int) = Int.div(10, 2);
+        "#,
+        TypesNotEqual { .. }
+    );
+}
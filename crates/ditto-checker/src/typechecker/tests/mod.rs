@@ -1,10 +1,23 @@
+mod arithmetic;
 mod array;
 mod bool;
 mod call;
+mod char;
+mod comparison;
 mod cond;
+mod debug_log;
 mod float;
 mod function;
+mod hole;
+mod inline_js;
 mod int;
+mod let_in;
 pub(self) mod macros;
+mod match_with;
+mod maybe;
+mod negate;
+mod pipe;
+mod record;
 mod string;
+mod tuple;
 mod unit;
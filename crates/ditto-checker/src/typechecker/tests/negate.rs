@@ -0,0 +1,32 @@
+use super::macros::*;
+use crate::TypeError::*;
+
+#[test]
+fn it_typechecks_as_expected() {
+    assert_type!("-5", "Int");
+    assert_type!("-5.0", "Float");
+    assert_type!("- -5", "Int");
+    assert_type!("-(1 + 2)", "Int");
+    assert_type!("(x: Float) -> -x", "(Float) -> Float");
+    assert_type!("(x) -> -x", "(Int) -> Int");
+    assert_type!("(f: (Int) -> Int) -> -f(1)", "((Int) -> Int) -> Int");
+}
+
+#[test]
+fn it_folds_negated_literals() {
+    let cst_expression = ditto_cst::Expression::parse("-1_000").unwrap();
+    let (expression, ..) = crate::typechecker::typecheck(None, cst_expression).unwrap();
+    assert!(
+        matches!(expression, ditto_ast::Expression::Int { ref value, .. } if value == "-1000"),
+        "{:#?}",
+        expression
+    );
+}
+
+#[test]
+fn it_errors_as_expected() {
+    assert_type_error!("-true", UnsupportedNegation { .. });
+    assert_type_error!(r#"-"five""#, UnsupportedNegation { .. });
+    assert_type_error!("-[1]", UnsupportedNegation { .. });
+    assert_type_error!("(x: a) -> -x", TypesNotEqual { .. });
+}
@@ -0,0 +1,56 @@
+use super::macros::*;
+use crate::TypeError::*;
+
+#[test]
+fn it_typechecks_as_expected() {
+    assert_type!(
+        "(x: Int, f: (Int) -> String) -> x |> f",
+        "(Int, (Int) -> String) -> String"
+    );
+    assert_type!("5 |> ((n) -> n + 1) |> ((n) -> n == 6)", "Bool");
+    assert_type!(
+        "(f: (Int) -> (Float) -> Bool) -> 1.0 |> f(2)",
+        "((Int) -> (Float) -> Bool) -> Bool"
+    );
+}
+
+#[test]
+fn it_errors_as_expected() {
+    assert_type_error!("5 |> true", NotAFunction { .. });
+    assert_type_error!("5 |> ((a, b) -> a)", ArgumentLengthMismatch { .. });
+    assert_type_error!(r#""five" |> ((n) -> n + 1)"#, TypesNotEqual { .. });
+}
+
+#[test]
+fn it_reports_errors_at_the_original_location() {
+    let source = "5 |> true";
+    let cst_expression = ditto_cst::Expression::parse(source).unwrap();
+    let err = crate::typechecker::typecheck(None, cst_expression).unwrap_err();
+    assert!(
+        matches!(
+            err,
+            NotAFunction { span, .. }
+                if span.start_offset == 5 && span.end_offset == source.len()
+        ),
+        "{:#?}",
+        err
+    );
+}
+
+#[test]
+fn it_reports_mismatches_at_the_pipeline_step() {
+    let source = "5 |> ((n) -> n + 1) |> ((s: String) -> s) |> ((s) -> s)";
+    let cst_expression = ditto_cst::Expression::parse(source).unwrap();
+    let err = crate::typechecker::typecheck(None, cst_expression).unwrap_err();
+    let step = "(s: String) -> s";
+    let step_start = source.find(step).unwrap();
+    assert!(
+        matches!(
+            err,
+            TypesNotEqual { span, .. }
+                if span.start_offset == step_start && span.end_offset == step_start + step.len()
+        ),
+        "{:#?}",
+        err
+    );
+}
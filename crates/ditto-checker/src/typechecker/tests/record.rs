@@ -0,0 +1,63 @@
+use super::macros::*;
+use crate::TypeError::*;
+
+#[test]
+fn it_typechecks_as_expected() {
+    assert_type!("{ a = 5 }", "{ a: Int }");
+    assert_type!(r#"{ b = "b", a = 5 }"#, "{ a: Int, b: String }");
+    assert_type!("{ a = { b = [true] } }", "{ a: { b: Array(Bool) } }");
+    assert_type!(
+        "if true then { a = 1, b = 2 } else { b = 3, a = 4 }",
+        "{ a: Int, b: Int }"
+    );
+    assert_type!("(r: { a: Int }) -> r", "({ a: Int }) -> { a: Int }");
+    assert_type!("(a) -> { a = a }", "($0) -> { a: $0 }");
+    assert_type!("[{ a = [] }, { a = [5] }]", "Array({ a: Array(Int) })");
+}
+
+#[test]
+fn it_typechecks_accesses_as_expected() {
+    assert_type!("{ a = 5 }.a", "Int");
+    assert_type!(r#"{ a = { b = "c" } }.a.b"#, "String");
+    assert_type!(
+        "(r: { a: Int, b: Bool }) -> r.b",
+        "({ a: Int, b: Bool }) -> Bool"
+    );
+    assert_type!(
+        "(r: { f: (Int) -> Int }) -> r.f(5)",
+        "({ f: (Int) -> Int }) -> Int"
+    );
+    assert_type!("(r: { a: Int }) -> r.a + 1", "({ a: Int }) -> Int");
+}
+
+#[test]
+fn it_errors_for_accesses_as_expected() {
+    assert_type_error!(
+        "{ a = 5 }.b",
+        UnknownRecordField {
+            constructor: None,
+            ..
+        }
+    );
+    assert_type_error!("5.a", NotARecord { .. });
+    assert_type_error!("(r) -> r.a", NotARecord { .. });
+    assert_type_error!("{ a = 5 }.a.b", NotARecord { .. });
+}
+
+#[test]
+fn it_errors_as_expected() {
+    assert_type_error!("{ a = 1, a = 2 }", DuplicateRecordField { .. });
+    assert_type_error!(
+        "if true then { a = 1 } else { b = 1 }",
+        RecordFieldsMismatch { .. }
+    );
+    assert_type_error!(
+        "if true then { a = 1 } else { a = 1, b = 2 }",
+        RecordFieldsMismatch { .. }
+    );
+    assert_type_error!(
+        "if true then { a = 1 } else { a = true }",
+        TypesNotEqual { .. }
+    );
+    assert_type_error!("(r: { a: Int }) -> r({ a = 5 })", NotAFunction { .. });
+}
@@ -0,0 +1,118 @@
+use super::macros::*;
+use crate::{
+    module::tests::macros::{assert_module_err, assert_module_ok, parse_and_check_module},
+    TypeError::*,
+    Warning::*,
+};
+use ditto_ast::proper_name;
+
+#[test]
+fn it_typechecks_as_expected() {
+    assert_type!("(5, true)", "(Int, Bool)");
+    assert_type!(r#"("five",)"#, "(String,)");
+    assert_type!("((1, 2.0), [unit])", "((Int, Float), Array(Unit))");
+    assert_type!("(a) -> (a, a)", "($0) -> ($0, $0)");
+    assert_type!(
+        "(pair: (Int, String)) -> pair",
+        "((Int, String)) -> (Int, String)"
+    );
+    assert_type!("[(1, true), (2, false)]", "Array((Int, Bool))");
+    assert_type!("if true then (1, 2) else (3, 4)", "(Int, Int)");
+}
+
+#[test]
+fn it_typechecks_patterns_as_expected() {
+    assert_type!("match (5, true) with | (x, _) -> x end", "Int");
+    assert_type!(
+        "(pair: (a, b)) -> match pair with | (x, y) -> (y, x) end",
+        "((a, b)) -> (b, a)"
+    );
+    assert_type!("match (1, (2, 3)) with | (_, (y, _)) -> y end", "Int");
+    assert_type!(
+        "match (5, true) with | (x, _) -> x | _ -> 0 end",
+        "Int",
+        [RedundantMatchArm { .. }]
+    );
+    assert_module_ok!(
+        r#"
+        module Test exports (..);
+
+        type Maybe(a) = Just(a) | Nothing;
+
+        first_just = (pair) ->
+            match pair with
+            | (Just(a), _) -> Just(a)
+            | (Nothing, Just(b)) -> Just(b)
+            | (Nothing, Nothing) -> Nothing
+            end;
+        "#
+    );
+}
+
+#[test]
+fn it_checks_exhaustiveness() {
+    let err = parse_and_check_module!(
+        r#"
+        module Test exports (..);
+
+        type Maybe(a) = Just(a) | Nothing;
+
+        both = (pair) ->
+            match pair with
+            | (Just(a), Just(b)) -> Just((a, b))
+            | (Nothing, _) -> Nothing
+            end;
+        "#
+    )
+    .unwrap_err();
+    assert!(
+        matches!(err, MatchNotExhaustive { ref missing, .. } if missing == &[proper_name!("Just")]),
+        "{:#?}",
+        err
+    );
+    let err = parse_and_check_module!(
+        r#"
+        module Test exports (..);
+
+        type Abc = A | B | C;
+
+        pick = (pair) ->
+            match pair with
+            | (_, A) -> 1
+            | (_, C) -> 3
+            end;
+        "#
+    )
+    .unwrap_err();
+    assert!(
+        matches!(err, MatchNotExhaustive { ref missing, .. } if missing == &[proper_name!("B")]),
+        "{:#?}",
+        err
+    );
+}
+
+#[test]
+fn it_errors_as_expected() {
+    assert_type_error!("if true then (1, 2) else (1, 2, 3)", TypesNotEqual { .. });
+    assert_type_error!("if true then (1, 2) else (1, true)", TypesNotEqual { .. });
+    assert_type_error!("(x: (Int, Int)) -> x.a", NotARecord { .. });
+    assert_type_error!("match 5 with | (x, _) -> x end", TypesNotEqual { .. });
+    assert_type_error!(
+        "match (1, 2) with | (x, _, _) -> x end",
+        TypesNotEqual { .. }
+    );
+    assert_type_error!(
+        "match (1, 2) with | (x, x) -> x end",
+        DuplicatePatternBinder { .. }
+    );
+    assert_module_err!(
+        r#"
+        module Test exports (..);
+
+        type Maybe(a) = Just(a) | Nothing;
+
+        x = match (Just(1), 2) with | (Just(n), m) -> n + m end;
+        "#,
+        MatchNotExhaustive { .. }
+    );
+}
@@ -0,0 +1,3 @@
+export function addImpl(a, b) {
+  return a + b;
+}
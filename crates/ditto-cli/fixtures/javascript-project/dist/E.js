@@ -1,3 +1,4 @@
 import {eFromIntImpl as foreign$eFromIntImpl,} from "../src/E.js";
+if (typeof foreign$eFromIntImpl!=="function"){throw new Error("foreign value `e_from_int_impl` should be a function");}
 const eFromInt = foreign$eFromIntImpl;
 export {eFromInt};
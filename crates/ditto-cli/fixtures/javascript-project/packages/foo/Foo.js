@@ -1,4 +1,5 @@
 import {fooToBoolImpl as foreign$fooToBoolImpl,} from "../../.ditto/packages/foo/src/Foo.js";
+if (typeof foreign$fooToBoolImpl!=="function"){throw new Error("foreign value `foo_to_bool_impl` should be a function");}
 const Foo = ["Foo",];
 const fooToBool = foreign$fooToBoolImpl;
 export {Foo,fooToBool};
@@ -202,7 +202,7 @@ fn write_ditto_module<P: AsRef<Path>>(path: P, contents: String) -> Result<()> {
             contents
         )
     })?;
-    let formatted = ditto_fmt::format_module(module);
+    let formatted = ditto_fmt::format_module(module, &contents);
     fs::write(&path, formatted)
         .into_diagnostic()
         .wrap_err(format!(
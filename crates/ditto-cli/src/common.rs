@@ -1,6 +1,12 @@
 use log::debug;
-use miette::{miette, IntoDiagnostic, Result, WrapErr};
-use std::path::PathBuf;
+use miette::{
+    miette, Diagnostic, GraphicalReportHandler, IntoDiagnostic, ReportHandler, Result, Severity,
+    WrapErr,
+};
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
 
 pub fn get_ditto_cache_dir() -> Result<PathBuf> {
     let mut cache_dir = dirs::cache_dir().ok_or_else(|| miette!("Error getting cache dir"))?;
@@ -18,6 +24,30 @@ pub fn get_ditto_cache_dir() -> Result<PathBuf> {
     Ok(cache_dir)
 }
 
+/// Resolve an executable like a shell would, returning `None` if it doesn't exist.
+///
+/// Bare names are looked up on the `PATH`, anything else is treated as a path.
+pub fn find_executable(exe: &str) -> Option<PathBuf> {
+    let exe_path = Path::new(exe);
+    if exe_path.components().count() > 1 {
+        return exe_path.is_file().then(|| exe_path.to_path_buf());
+    }
+    let path_var = std::env::var_os("PATH")?;
+    std::env::split_paths(&path_var).find_map(|dir| {
+        let candidate = dir.join(exe);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+        if cfg!(windows) {
+            let candidate = candidate.with_extension("exe");
+            if candidate.is_file() {
+                return Some(candidate);
+            }
+        }
+        None
+    })
+}
+
 pub fn is_plain() -> bool {
     if let Ok(plain) = std::env::var("DITTO_PLAIN") {
         plain != "false"
@@ -25,3 +55,257 @@ pub fn is_plain() -> bool {
         !atty::is(atty::Stream::Stdout) || !atty::is(atty::Stream::Stderr)
     }
 }
+
+/// Whether locations in diagnostics should be printed as (clickable) terminal hyperlinks.
+pub fn supports_hyperlinks() -> bool {
+    !is_plain() && supports_hyperlinks::supports_hyperlinks()
+}
+
+/// Environment variable used to pass the diagnostics format down to CLI calls made by ninja.
+pub static DIAGNOSTICS_ENV: &str = "DITTO_DIAGNOSTICS";
+
+/// How diagnostics should be printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticsFormat {
+    /// Pretty, human readable reports.
+    Graphical,
+    /// One JSON object per diagnostic, optionally including a rendered code frame.
+    Json { frames: bool },
+}
+
+impl DiagnosticsFormat {
+    pub fn from_env() -> Self {
+        match std::env::var(DIAGNOSTICS_ENV).as_deref() {
+            Ok("json") => Self::Json { frames: false },
+            Ok("json-frames") => Self::Json { frames: true },
+            _ => Self::Graphical,
+        }
+    }
+
+    pub fn set_env(self) {
+        match self {
+            Self::Graphical => std::env::remove_var(DIAGNOSTICS_ENV),
+            Self::Json { frames: false } => std::env::set_var(DIAGNOSTICS_ENV, "json"),
+            Self::Json { frames: true } => std::env::set_var(DIAGNOSTICS_ENV, "json-frames"),
+        }
+    }
+}
+
+/// A [ReportHandler] that prints diagnostics as JSON.
+pub struct JsonReportHandler {
+    frames: bool,
+}
+
+impl JsonReportHandler {
+    pub fn new(frames: bool) -> Self {
+        Self { frames }
+    }
+}
+
+impl ReportHandler for JsonReportHandler {
+    fn debug(&self, diagnostic: &dyn Diagnostic, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            return fmt::Debug::fmt(diagnostic, f);
+        }
+        write!(f, "{}", render_diagnostic_json(diagnostic, self.frames))
+    }
+}
+
+/// A [ReportHandler] that starts each diagnostic with its location, e.g.
+///
+/// ```text
+/// error[DT0001]: message
+///   --> src/Foo.ditto:12:5
+/// ```
+///
+/// which terminals and tools like `errorformat` can pick up,
+/// followed by the usual graphical report.
+pub struct LocatedReportHandler {
+    graphical: GraphicalReportHandler,
+    hyperlinks: bool,
+}
+
+impl LocatedReportHandler {
+    pub fn new(graphical: GraphicalReportHandler, hyperlinks: bool) -> Self {
+        Self {
+            graphical,
+            hyperlinks,
+        }
+    }
+
+    pub fn render_report(
+        &self,
+        f: &mut impl fmt::Write,
+        diagnostic: &dyn Diagnostic,
+    ) -> fmt::Result {
+        let severity = match diagnostic.severity() {
+            Some(Severity::Error) | None => "error",
+            Some(Severity::Warning) => "warning",
+            Some(Severity::Advice) => "advice",
+        };
+        match diagnostic.code() {
+            Some(code) => writeln!(f, "{}[{}]: {}", severity, code, diagnostic)?,
+            None => writeln!(f, "{}: {}", severity, diagnostic)?,
+        }
+        if let Some((path, line, column)) = diagnostic_location(diagnostic) {
+            let location = format!("{}:{}:{}", path, line, column);
+            if self.hyperlinks {
+                // https://gist.github.com/egmontkob/eb114294efbcd5adb1944c9f3cb5feda
+                writeln!(
+                    f,
+                    "  --> \u{1b}]8;;{}\u{1b}\\{}\u{1b}]8;;\u{1b}\\",
+                    file_url(&path),
+                    location
+                )?;
+            } else {
+                writeln!(f, "  --> {}", location)?;
+            }
+        }
+        self.graphical.render_report(f, diagnostic)
+    }
+}
+
+impl ReportHandler for LocatedReportHandler {
+    fn debug(&self, diagnostic: &dyn Diagnostic, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            return fmt::Debug::fmt(diagnostic, f);
+        }
+        self.render_report(f, diagnostic)
+    }
+}
+
+/// The path, line and column (both one-based) of the first label of a diagnostic.
+fn diagnostic_location(diagnostic: &dyn Diagnostic) -> Option<(String, usize, usize)> {
+    let source_code = diagnostic.source_code()?;
+    let label = diagnostic.labels()?.next()?;
+    let contents = source_code.read_span(label.inner(), 0, 0).ok()?;
+    let path = contents.name()?.to_owned();
+    Some((path, contents.line() + 1, contents.column() + 1))
+}
+
+fn file_url(path: &str) -> String {
+    let path = std::env::current_dir()
+        .map(|current_dir| current_dir.join(path))
+        .unwrap_or_else(|_| PathBuf::from(path));
+    let path = path.to_string_lossy().replace('\\', "/");
+    if path.starts_with('/') {
+        format!("file://{}", path)
+    } else {
+        // Windows, e.g. `C:/...`
+        format!("file:///{}", path)
+    }
+}
+
+/// Render a diagnostic as a single line of JSON.
+///
+/// If `frames` is true then the JSON object will include a `"frame"` field
+/// containing the offending source lines, rendered as they would be in a
+/// terminal (minus the colors).
+pub fn render_diagnostic_json(diagnostic: &dyn Diagnostic, frames: bool) -> String {
+    let mut json = String::new();
+    miette::JSONReportHandler::new()
+        .render_report(&mut json, diagnostic)
+        .expect("error rendering diagnostic to JSON");
+    if !frames {
+        return json;
+    }
+    let mut frame = String::new();
+    miette::GraphicalReportHandler::new_themed(miette::GraphicalTheme::unicode_nocolor())
+        .render_report(&mut frame, diagnostic)
+        .expect("error rendering diagnostic frame");
+    match serde_json::from_str(&json) {
+        Ok(serde_json::Value::Object(mut object)) => {
+            object.insert(String::from("frame"), serde_json::Value::String(frame));
+            serde_json::Value::Object(object).to_string()
+        }
+        _ => json,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{file_url, LocatedReportHandler};
+    use miette::{Diagnostic, GraphicalReportHandler, GraphicalTheme, NamedSource, SourceSpan};
+    use thiserror::Error;
+
+    #[derive(Error, Debug, Diagnostic)]
+    #[error("unknown variable")]
+    #[diagnostic(severity(Error), code(DT0001))]
+    struct UnknownVariable {
+        #[source_code]
+        input: NamedSource,
+        #[label("not in scope")]
+        location: SourceSpan,
+    }
+
+    #[derive(Error, Debug, Diagnostic)]
+    #[error("something went wrong")]
+    struct Unlocated;
+
+    fn unknown_variable() -> UnknownVariable {
+        UnknownVariable {
+            input: NamedSource::new(
+                "src/Foo.ditto",
+                "module Foo exports (..);\nfive = nope;\n".to_string(),
+            ),
+            location: (32, 4).into(),
+        }
+    }
+
+    fn render(diagnostic: &dyn Diagnostic, hyperlinks: bool) -> String {
+        let mut rendered = String::new();
+        LocatedReportHandler::new(graphical(), hyperlinks)
+            .render_report(&mut rendered, diagnostic)
+            .unwrap();
+        rendered
+    }
+
+    fn graphical() -> GraphicalReportHandler {
+        GraphicalReportHandler::new_themed(GraphicalTheme::unicode_nocolor())
+    }
+
+    fn render_graphical(diagnostic: &dyn Diagnostic) -> String {
+        let mut rendered = String::new();
+        graphical()
+            .render_report(&mut rendered, diagnostic)
+            .unwrap();
+        rendered
+    }
+
+    #[test]
+    fn it_renders_plain_locations() {
+        let diagnostic = unknown_variable();
+        assert_eq!(
+            render(&diagnostic, false),
+            format!(
+                "error[DT0001]: unknown variable\n  --> src/Foo.ditto:2:8\n{}",
+                render_graphical(&diagnostic)
+            )
+        );
+    }
+
+    #[test]
+    fn it_renders_hyperlinked_locations() {
+        let diagnostic = unknown_variable();
+        assert_eq!(
+            render(&diagnostic, true),
+            format!(
+                "error[DT0001]: unknown variable\n  --> \u{1b}]8;;{}\u{1b}\\src/Foo.ditto:2:8\u{1b}]8;;\u{1b}\\\n{}",
+                file_url("src/Foo.ditto"),
+                render_graphical(&diagnostic)
+            )
+        );
+        assert!(file_url("src/Foo.ditto").ends_with("/src/Foo.ditto"));
+    }
+
+    #[test]
+    fn it_renders_diagnostics_without_locations() {
+        assert_eq!(
+            render(&Unlocated, true),
+            format!(
+                "error: something went wrong\n{}",
+                render_graphical(&Unlocated)
+            )
+        );
+    }
+}
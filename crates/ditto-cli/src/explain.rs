@@ -0,0 +1,202 @@
+//! Explain the inferred type of a top-level value.
+//!
+//! This checks the module (and everything it imports) from source, so it
+//! doesn't depend on the state of a previous `ditto make`.
+use crate::make::{find_ditto_files, get_package_sources};
+use clap::{Arg, ArgMatches, Command};
+use ditto_ast as ast;
+use ditto_checker as checker;
+use ditto_config::{read_config, CONFIG_FILE_NAME};
+use ditto_cst as cst;
+use miette::{bail, miette, IntoDiagnostic, Result, WrapErr};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+pub fn command<'a>(name: &str) -> Command<'a> {
+    Command::new(name)
+        .about("Print the inferred type of a top-level value")
+        .arg(
+            Arg::new("module")
+                .required(true)
+                .takes_value(true)
+                .help("Module defining the value, e.g. Data.Maybe"),
+        )
+        .arg(
+            Arg::new("name")
+                .required(true)
+                .takes_value(true)
+                .help("Name of the value"),
+        )
+}
+
+pub fn run(matches: &ArgMatches) -> Result<()> {
+    let config_path: PathBuf = [".", CONFIG_FILE_NAME].iter().collect();
+    let config = read_config(&config_path)?;
+    let module_name = matches.value_of("module").unwrap();
+    let name = matches.value_of("name").unwrap();
+
+    let mut sources = ModuleSources::default();
+    sources.add_package(None, &find_ditto_files(&config.src_dir)?)?;
+    for (package_name, package_sources) in get_package_sources(&config)? {
+        let package_name = ast::PackageName(package_name.into_string());
+        sources.add_package(Some(package_name), &package_sources.ditto)?;
+    }
+
+    let module_name = parse_module_name(module_name)?;
+    let module = sources.check(&(None, module_name))?;
+    println!("{} : {}", name, explain_type(&module, name)?);
+    Ok(())
+}
+
+/// Render the type of the top-level value `name`, or error with suggestions.
+fn explain_type(module: &ast::Module, name: &str) -> Result<String> {
+    let value_name = ast::Name(name.to_string());
+    if let Some(exported) = module.exports.values.get(&value_name) {
+        return Ok(exported.value_type.render_scheme());
+    }
+    if let Some(module_value) = module.values.get(&value_name) {
+        return Ok(module_value.expression.get_type().render_scheme());
+    }
+
+    let mut engine: simsearch::SimSearch<String> = simsearch::SimSearch::new();
+    let names = module
+        .exports
+        .values
+        .keys()
+        .chain(module.values.keys())
+        .map(|name| name.0.clone())
+        .collect::<HashSet<_>>();
+    for name in names {
+        engine.insert(name.clone(), &name);
+    }
+    let mut suggestions = engine.search(name);
+    // Sort for determinism
+    suggestions.sort();
+    if suggestions.is_empty() {
+        bail!(
+            "{} doesn't define a value named `{}`",
+            module.module_name,
+            name
+        )
+    } else {
+        bail!(
+            "{} doesn't define a value named `{}`, did you mean: {}?",
+            module.module_name,
+            name,
+            suggestions
+                .iter()
+                .map(|suggestion| format!("`{}`", suggestion))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+}
+
+fn parse_module_name(module_name: &str) -> Result<ast::ModuleName> {
+    let header = format!("module {} exports (..);", module_name);
+    let (header, _imports) = cst::parse_header_and_imports(&header)
+        .map_err(|_| miette!("invalid module name: {:?}", module_name))?;
+    Ok(ast::ModuleName::from(header.module_name))
+}
+
+type ModuleKey = (Option<ast::PackageName>, ast::ModuleName);
+
+/// Every known module, checked on demand.
+#[derive(Default)]
+struct ModuleSources {
+    paths: HashMap<ModuleKey, (PathBuf, Arc<String>)>,
+    exports: HashMap<ModuleKey, ast::ModuleExports>,
+    checking: HashSet<ModuleKey>,
+}
+
+impl ModuleSources {
+    fn add_package(
+        &mut self,
+        package_name: Option<ast::PackageName>,
+        paths: &[PathBuf],
+    ) -> Result<()> {
+        for path in paths {
+            let source = read_source(path)?;
+            // Modules that don't parse are skipped, `ditto make` will complain about them
+            if let Ok((header, _imports)) = cst::parse_header_and_imports(&source) {
+                let module_name = ast::ModuleName::from(header.module_name);
+                self.paths.insert(
+                    (package_name.clone(), module_name),
+                    (path.to_path_buf(), source),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn check(&mut self, key: &ModuleKey) -> Result<ast::Module> {
+        let (path, source) = self
+            .paths
+            .get(key)
+            .cloned()
+            .ok_or_else(|| miette!("unknown module {}", render_key(key)))?;
+        let name = path.to_string_lossy().into_owned();
+
+        let cst_module =
+            cst::Module::parse(&source).map_err(|err| err.into_report(&name, source.clone()))?;
+
+        if !self.checking.insert(key.clone()) {
+            bail!("modules form a cycle at {}", render_key(key));
+        }
+        let mut everything = checker::Everything::default();
+        for import_line in cst_module.imports.iter() {
+            let import_module_name = ast::ModuleName::from(import_line.module_name.clone());
+            let import_package_name = import_line
+                .package
+                .as_ref()
+                .map(|parens| ast::PackageName(parens.value.0.value.clone()));
+            // Imports without a package refer to the importing module's package
+            let import_key = (
+                import_package_name.clone().or_else(|| key.0.clone()),
+                import_module_name.clone(),
+            );
+            let exports = match self.exports.get(&import_key) {
+                Some(exports) => exports.clone(),
+                None if self.paths.contains_key(&import_key) => {
+                    let module = self.check(&import_key)?;
+                    self.exports.insert(import_key, module.exports.clone());
+                    module.exports
+                }
+                // Let the checker report unknown imports
+                None => continue,
+            };
+            if let Some(package_name) = import_package_name {
+                everything
+                    .packages
+                    .entry(package_name)
+                    .or_default()
+                    .insert(import_module_name, exports);
+            } else {
+                everything.modules.insert(import_module_name, exports);
+            }
+        }
+        self.checking.remove(key);
+
+        let (module, _warnings) = checker::check_module(&everything, cst_module)
+            .map_err(|err| err.into_report(&name, source))?;
+        Ok(module)
+    }
+}
+
+fn read_source(path: &Path) -> Result<Arc<String>> {
+    fs::read_to_string(path)
+        .map(Arc::from)
+        .into_diagnostic()
+        .wrap_err(format!("error reading {}", path.to_string_lossy()))
+}
+
+fn render_key((package_name, module_name): &ModuleKey) -> String {
+    match package_name {
+        Some(package_name) => format!("{}:{}", package_name.0, module_name),
+        None => module_name.to_string(),
+    }
+}
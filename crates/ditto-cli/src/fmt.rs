@@ -1,9 +1,11 @@
 use clap::{Arg, ArgMatches, Command};
+use ditto_config::{read_config, FmtOptions, CONFIG_FILE_NAME};
 use miette::{bail, IntoDiagnostic, Result, WrapErr};
 use std::{
     fs,
     io::{self, Read, Write},
-    path::Path,
+    path::{Path, PathBuf},
+    sync::Arc,
 };
 
 pub fn command<'a>(name: &str) -> Command<'a> {
@@ -15,6 +17,7 @@ pub fn command<'a>(name: &str) -> Command<'a> {
 }
 
 pub fn run(matches: &ArgMatches) -> Result<()> {
+    let options = fmt_options()?;
     if matches.is_present("stdin") {
         if matches.is_present("globs") {
             bail!("can only specify `--stdin` or paths, not both")
@@ -23,7 +26,7 @@ pub fn run(matches: &ArgMatches) -> Result<()> {
         io::stdin()
             .read_to_string(&mut contents)
             .into_diagnostic()?;
-        let formatted = fmt("stdin".into(), &contents)?;
+        let formatted = fmt("stdin".into(), &contents, options)?;
         if matches.is_present("check") {
             if formatted != contents {
                 bail!("Stdin isn't formatted");
@@ -39,7 +42,7 @@ pub fn run(matches: &ArgMatches) -> Result<()> {
         let exit_error = false;
         for path in globs {
             if check {
-                match fmt_path(path) {
+                match fmt_path(path, options) {
                     Err(report) => {
                         eprintln!("{:?}", report);
                     }
@@ -51,7 +54,7 @@ pub fn run(matches: &ArgMatches) -> Result<()> {
                 }
             } else {
                 eprintln!("Formatting {}", path);
-                if let Err(report) = fmt_inplace(path) {
+                if let Err(report) = fmt_inplace(path, options) {
                     eprintln!("{:?}", report);
                 }
             }
@@ -63,8 +66,8 @@ pub fn run(matches: &ArgMatches) -> Result<()> {
     Ok(())
 }
 
-fn fmt_inplace<P: AsRef<Path>>(path: P) -> Result<()> {
-    let formatted = fmt_path(&path)?.0;
+fn fmt_inplace<P: AsRef<Path>>(path: P, options: FmtOptions) -> Result<()> {
+    let formatted = fmt_path(&path, options)?.0;
     fs::write(&path, formatted)
         .into_diagnostic()
         .wrap_err(format!(
@@ -73,20 +76,36 @@ fn fmt_inplace<P: AsRef<Path>>(path: P) -> Result<()> {
         ))
 }
 
-fn fmt_path<P: AsRef<Path>>(path: P) -> Result<(String, String)> {
+fn fmt_path<P: AsRef<Path>>(path: P, options: FmtOptions) -> Result<(String, String)> {
     // TODO gracefully handle file not existing?
     let unformatted = fs::read_to_string(&path)
         .into_diagnostic()
         .wrap_err(format!("error reading {}", path.as_ref().to_string_lossy()))?;
 
-    let formatted = fmt(path.as_ref().to_string_lossy().into_owned(), &unformatted)?;
+    let formatted = fmt(
+        path.as_ref().to_string_lossy().into_owned(),
+        &unformatted,
+        options,
+    )?;
     Ok((formatted, unformatted))
 }
 
-pub fn fmt(name: String, contents: &str) -> Result<String> {
+pub fn fmt(name: String, contents: &str, options: FmtOptions) -> Result<String> {
     // TODO `ditto-fmt` could expose a function along these lines?
     let module = ditto_cst::Module::parse(contents)
-        .map_err(|err| err.into_report(&name, contents.to_string()))?;
+        .map_err(|err| err.into_report(&name, Arc::new(contents.to_owned())))?;
     // TODO check that formatted file still parses if we're feeling paranoid
-    Ok(ditto_fmt::format_module(module))
+    Ok(ditto_fmt::format_module_with_options(
+        module, contents, options,
+    ))
+}
+
+/// Formatting options for the current package, if we're in one.
+fn fmt_options() -> Result<FmtOptions> {
+    let config_path: PathBuf = [".", CONFIG_FILE_NAME].iter().collect();
+    if !config_path.exists() {
+        return Ok(FmtOptions::default());
+    }
+    let config = read_config(&config_path)?;
+    Ok(config.fmt.options())
 }
@@ -0,0 +1,308 @@
+//! Building an index of everything that can be imported from installed packages.
+//!
+//! Building this needs every module of every installed package to be parsed,
+//! so it's done once when packages are checked, rather than whenever a lookup
+//! is needed. Each package is indexed separately, keyed by a hash of its
+//! contents, so that only changed packages need re-indexing.
+use crate::pkg::{hash_package_contents, list_installed_packages, mk_packages_dir};
+use clap::{Arg, ArgMatches, Command};
+use ditto_config::{read_config, Config, CONFIG_FILE_NAME};
+use ditto_cst as cst;
+use ditto_make::{
+    mk_index_dir, mk_index_path, read_index, IndexEntry, IndexEntryKind, IndexReexport,
+    PackageIndex,
+};
+use log::{debug, warn};
+use miette::{IntoDiagnostic, Result, WrapErr};
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+pub fn command<'a>(name: &str) -> Command<'a> {
+    Command::new(name)
+        .about("Search installed packages for something to import")
+        .arg(
+            Arg::new("name")
+                .required(true)
+                .takes_value(true)
+                .help("Name to search for"),
+        )
+}
+
+pub fn run(matches: &ArgMatches) -> Result<()> {
+    let config_path: PathBuf = [".", CONFIG_FILE_NAME].iter().collect();
+    let config = read_config(&config_path)?;
+    let name = matches.value_of("name").unwrap();
+    let entries = search_index(&config, name)?;
+    if entries.is_empty() {
+        println!("Nothing found for {:?}", name);
+    }
+    for entry in entries {
+        print!("{}", entry.render_import());
+        match (entry.kind, entry.summary) {
+            (IndexEntryKind::Value, Some(summary)) => println!(" -- {} : {}", entry.name, summary),
+            _ => println!(),
+        }
+    }
+    Ok(())
+}
+
+/// Find every indexed entry with the given name.
+///
+/// The index is only read when this is called, and should be up to date with
+/// installed packages as of the last `ditto make`.
+pub fn search_index(config: &Config, name: &str) -> Result<Vec<IndexEntry>> {
+    let entries = read_index(&mk_index_dir(&config.ditto_dir))?;
+    Ok(entries
+        .into_iter()
+        .filter(|entry| entry.name == name)
+        .collect())
+}
+
+/// Bring the index in line with the installed packages.
+pub fn update_index(config: &Config) -> Result<()> {
+    let index_dir = mk_index_dir(&config.ditto_dir);
+    if !index_dir.exists() {
+        fs::create_dir_all(&index_dir)
+            .into_diagnostic()
+            .wrap_err(format!(
+                "error creating index dir: {:?}",
+                index_dir.as_os_str()
+            ))?;
+    }
+
+    let mut indexed = HashSet::new();
+    for package_path in list_installed_packages(&mk_packages_dir(config))? {
+        let package_name = package_path
+            .file_name()
+            .unwrap() // listed paths should have a file name
+            .to_string_lossy()
+            .into_owned();
+
+        let index_path = mk_index_path(&index_dir, &package_name);
+        indexed.insert(index_path.clone());
+
+        let hash = hash_package_contents(&package_path)?;
+        match PackageIndex::read(&index_path) {
+            Ok(Some(package_index)) if package_index.hash == hash => {
+                debug!("Index for {:?} is up to date", package_name);
+                continue;
+            }
+            Ok(_) => {}
+            Err(err) => {
+                // It'll be rebuilt
+                warn!("Corrupted index: {:?}", err);
+            }
+        }
+
+        debug!("Indexing {:?}", package_name);
+        let (entries, reexports) = index_package(&package_name, &package_path)?;
+        PackageIndex {
+            hash,
+            entries,
+            reexports,
+        }
+        .write(&index_path)?;
+    }
+
+    // Tidy up packages that have since been removed
+    for entry in fs::read_dir(&index_dir).into_diagnostic()? {
+        let path = entry.into_diagnostic()?.path();
+        if !indexed.contains(&path) {
+            debug!("Removing stale index {}", path.to_string_lossy());
+            fs::remove_file(&path).into_diagnostic()?;
+        }
+    }
+    Ok(())
+}
+
+fn index_package(
+    package_name: &str,
+    package_path: &Path,
+) -> Result<(Vec<IndexEntry>, Vec<IndexReexport>)> {
+    let mut config_path = package_path.to_path_buf();
+    config_path.push(CONFIG_FILE_NAME);
+    let config = read_config(&config_path)?;
+
+    let mut src_dir = package_path.to_path_buf();
+    src_dir.push(config.src_dir);
+
+    let mut ditto_files = ditto_make::find_ditto_files(&src_dir)
+        .into_diagnostic()
+        .wrap_err(format!(
+            "error finding ditto files in {}",
+            src_dir.to_string_lossy()
+        ))?;
+    ditto_files.sort();
+
+    let mut entries = Vec::new();
+    let mut reexports = Vec::new();
+    for path in ditto_files {
+        let source = fs::read_to_string(&path)
+            .into_diagnostic()
+            .wrap_err(format!("error reading {}", path.to_string_lossy()))?;
+        match cst::Module::parse(&source) {
+            Ok(module) => {
+                let (module_entries, module_reexports) =
+                    index_module(package_name, &source, module);
+                entries.extend(module_entries);
+                reexports.extend(module_reexports);
+            }
+            Err(_) => {
+                // Errors will be reported when the package is built
+                warn!("Not indexing {}, it doesn't parse", path.to_string_lossy());
+            }
+        }
+    }
+    Ok((entries, reexports))
+}
+
+fn index_module(
+    package_name: &str,
+    source: &str,
+    module: cst::Module,
+) -> (Vec<IndexEntry>, Vec<IndexReexport>) {
+    let module_name = module.header.module_name.render();
+    let type_names = module
+        .declarations
+        .iter()
+        .filter_map(|declaration| match declaration {
+            cst::Declaration::Type(type_declaration) => {
+                Some(type_declaration.type_name().0.value.clone())
+            }
+            _ => None,
+        })
+        .collect::<HashSet<_>>();
+    let exports = match module.header.exports {
+        cst::Exports::Everything(_) => None,
+        cst::Exports::Nothing(_) => Some(Vec::new()),
+        cst::Exports::List(exports) => Some(exports.value.as_vec()),
+    };
+    let is_value_exported = |name: &str| {
+        exports.as_ref().map_or(true, |exports| {
+            exports.iter().any(|export| match export {
+                cst::Export::Value(value) => value.0.value == name,
+                cst::Export::Type(..) | cst::Export::Module(..) => false,
+            })
+        })
+    };
+    // Constructors can also be exported on their own (`exports (Just)`),
+    // so long as there isn't a type with the same name
+    let is_constructor_exported = |name: &str| {
+        !type_names.contains(name)
+            && exports.as_ref().map_or(false, |exports| {
+                exports.iter().any(|export| match export {
+                    cst::Export::Type(proper_name, None) => proper_name.0.value == name,
+                    _ => false,
+                })
+            })
+    };
+    // Returns whether the type is exported, and whether its constructors are too
+    let type_exports = |name: &str| {
+        exports.as_ref().map_or((true, true), |exports| {
+            exports
+                .iter()
+                .find_map(|export| match export {
+                    cst::Export::Type(proper_name, everything) if proper_name.0.value == name => {
+                        Some((true, everything.is_some()))
+                    }
+                    _ => None,
+                })
+                .unwrap_or((false, false))
+        })
+    };
+
+    let entry = |name: &str, kind, summary| IndexEntry {
+        name: name.to_owned(),
+        package: package_name.to_owned(),
+        module: module_name.clone(),
+        kind,
+        summary,
+    };
+
+    // Re-exported modules are resolved when the index is read
+    let reexports = exports
+        .iter()
+        .flatten()
+        .filter_map(|export| match export {
+            cst::Export::Module(_, reexported_module_name) => {
+                let from_module = reexported_module_name.render();
+                // The import line says which package the module belongs to
+                let import_line = module
+                    .imports
+                    .iter()
+                    .find(|import_line| import_line.module_name.render() == from_module)?;
+                let from_package = import_line.package.as_ref().map_or_else(
+                    || package_name.to_owned(),
+                    |parens| parens.value.0.value.clone(),
+                );
+                Some(IndexReexport {
+                    package: package_name.to_owned(),
+                    module: module_name.clone(),
+                    from_package,
+                    from_module,
+                })
+            }
+            _ => None,
+        })
+        .collect();
+
+    let mut entries = Vec::new();
+    for declaration in module.declarations {
+        match declaration {
+            cst::Declaration::Value(value_declaration) => {
+                let cst::ValueDeclaration {
+                    name,
+                    type_annotation,
+                    ..
+                } = *value_declaration;
+                if is_value_exported(&name.0.value) {
+                    let summary =
+                        type_annotation.map(|annotation| render_type(source, &annotation.1));
+                    entries.push(entry(&name.0.value, IndexEntryKind::Value, summary));
+                }
+            }
+            cst::Declaration::ForeignValue(foreign_value_declaration) => {
+                let cst::ForeignValueDeclaration {
+                    name,
+                    type_annotation,
+                    ..
+                } = *foreign_value_declaration;
+                if is_value_exported(&name.0.value) {
+                    let summary = Some(render_type(source, &type_annotation.1));
+                    entries.push(entry(&name.0.value, IndexEntryKind::Value, summary));
+                }
+            }
+            cst::Declaration::Type(type_declaration) => {
+                let type_name = type_declaration.type_name().0.value.clone();
+                let (type_exported, constructors_exported) = type_exports(&type_name);
+                if type_exported {
+                    entries.push(entry(&type_name, IndexEntryKind::Type, None));
+                }
+                for constructor in type_declaration.iter_constructors() {
+                    let constructor_name = &constructor.constructor_name.0.value;
+                    if constructors_exported || is_constructor_exported(constructor_name) {
+                        entries.push(entry(
+                            constructor_name,
+                            IndexEntryKind::Constructor,
+                            // The type it constructs, if it can be imported with `Type(..)`
+                            type_exported.then(|| type_name.clone()),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    (entries, reexports)
+}
+
+/// Render a type as it appears in the source, but on a single line.
+fn render_type(source: &str, t: &cst::Type) -> String {
+    let span = t.get_span();
+    source[span.start_offset..span.end_offset]
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -0,0 +1,429 @@
+use crate::make::find_ditto_files;
+use clap::{Arg, ArgMatches, Command};
+use ditto_config::{read_config, ComplexityLints, LintLevel, NamingLints, CONFIG_FILE_NAME};
+use ditto_cst::{
+    complexity,
+    naming::{self, NamingRule, NamingViolation},
+    Declaration, Module, Span,
+};
+use miette::{bail, Diagnostic, IntoDiagnostic, NamedSource, Result, SourceSpan, WrapErr};
+use std::{fs, path::PathBuf, sync::Arc};
+use thiserror::Error;
+
+pub fn command<'a>(name: &str) -> Command<'a> {
+    Command::new(name)
+        .about("Check code against configured lints")
+        .arg(
+            Arg::new("only")
+                .long("only")
+                .takes_value(true)
+                .possible_values(["complexity", "naming"])
+                .help("Only run this group of lints"),
+        )
+        .arg(max_arg(
+            "max-declarations",
+            "Maximum number of declarations in a module",
+        ))
+        .arg(max_arg(
+            "max-function-binders",
+            "Maximum number of binders for a function",
+        ))
+        .arg(max_arg(
+            "max-nesting-depth",
+            "Maximum nesting depth of an expression",
+        ))
+        .arg(max_arg(
+            "max-module-lines",
+            "Maximum number of lines in a module",
+        ))
+}
+
+fn max_arg<'a>(name: &'a str, help: &'a str) -> Arg<'a> {
+    Arg::new(name)
+        .long(name)
+        .takes_value(true)
+        .value_name("N")
+        .validator(|value| value.parse::<usize>())
+        .help(help)
+}
+
+pub fn run(matches: &ArgMatches) -> Result<()> {
+    let config_path: PathBuf = [".", CONFIG_FILE_NAME].iter().collect();
+    let config = read_config(&config_path)?;
+
+    // NOTE: these are the only lints that can be run without typechecking
+    let only = matches.value_of("only");
+    let mut limits = config.lints.complexity.clone();
+    override_limit(matches, "max-declarations", &mut limits.max_declarations)?;
+    override_limit(
+        matches,
+        "max-function-binders",
+        &mut limits.max_function_binders,
+    )?;
+    override_limit(matches, "max-nesting-depth", &mut limits.max_nesting_depth)?;
+    override_limit(matches, "max-module-lines", &mut limits.max_module_lines)?;
+
+    let mut ditto_files = find_ditto_files(&config.src_dir)?;
+    ditto_files.sort();
+
+    let mut failed = false;
+    for path in ditto_files {
+        let name = path.to_string_lossy().into_owned();
+        let source = fs::read_to_string(&path)
+            .into_diagnostic()
+            .wrap_err(format!("error reading {}", name))?;
+
+        let module = match Module::parse(&source) {
+            Ok(module) => module,
+            Err(err) => {
+                eprintln!(
+                    "{:?}",
+                    miette::Report::from(err.into_report(&name, source.into()))
+                );
+                failed = true;
+                continue;
+            }
+        };
+
+        let source: Arc<String> = source.into();
+        if only != Some("naming") {
+            for violation in check_complexity(&module, &source, &limits) {
+                let report = violation.into_report(&name, source.clone());
+                eprintln!("{:?}", miette::Report::from(report));
+                failed = true;
+            }
+        }
+        if only != Some("complexity") {
+            for (level, violation) in check_naming(&module, &config.lints.naming) {
+                let report = naming_report(violation, &name, source.clone());
+                eprintln!("{:?}", miette::Report::from(report));
+                failed |= level == LintLevel::Deny;
+            }
+        }
+    }
+    if failed {
+        bail!("Some lints failed");
+    }
+    Ok(())
+}
+
+fn override_limit(matches: &ArgMatches, name: &str, limit: &mut Option<usize>) -> Result<()> {
+    if let Some(value) = matches.value_of(name) {
+        *limit = Some(value.parse().into_diagnostic()?);
+    }
+    Ok(())
+}
+
+/// Check a module against complexity limits.
+pub fn check_complexity(
+    module: &Module,
+    source: &str,
+    limits: &ComplexityLints,
+) -> Vec<ComplexityViolation> {
+    let mut violations = Vec::new();
+
+    if let Some(allowed) = limits.max_module_lines {
+        let measured = complexity::count_lines(source);
+        if measured > allowed {
+            let header = &module.header;
+            violations.push(ComplexityViolation {
+                description: "module has too many lines",
+                measured,
+                allowed,
+                span: header
+                    .module_keyword
+                    .0
+                    .get_span()
+                    .merge(&header.semicolon.0.get_span()),
+            });
+        }
+    }
+
+    if let Some(allowed) = limits.max_declarations {
+        let measured = complexity::count_declarations(module);
+        if measured > allowed {
+            violations.push(ComplexityViolation {
+                description: "module has too many declarations",
+                measured,
+                allowed,
+                // Point at the first declaration that goes over the limit
+                span: module.declarations[allowed].get_span(),
+            });
+        }
+    }
+
+    for declaration in module.declarations.iter() {
+        if let Declaration::Value(value_declaration) = declaration {
+            let expression = &value_declaration.expression;
+            if let Some(allowed) = limits.max_function_binders {
+                let measured = complexity::max_function_binders(expression);
+                if measured > allowed {
+                    violations.push(ComplexityViolation {
+                        description: "function has too many binders",
+                        measured,
+                        allowed,
+                        span: value_declaration.get_span(),
+                    });
+                }
+            }
+            if let Some(allowed) = limits.max_nesting_depth {
+                let measured = complexity::nesting_depth(expression);
+                if measured > allowed {
+                    violations.push(ComplexityViolation {
+                        description: "expression is too deeply nested",
+                        measured,
+                        allowed,
+                        span: value_declaration.get_span(),
+                    });
+                }
+            }
+        }
+    }
+    violations
+}
+
+/// A module that exceeded one of its complexity limits.
+#[derive(Debug, PartialEq)]
+pub struct ComplexityViolation {
+    pub description: &'static str,
+    pub measured: usize,
+    pub allowed: usize,
+    pub span: Span,
+}
+
+impl ComplexityViolation {
+    fn into_report(self, name: &str, input: Arc<String>) -> ComplexityViolationReport {
+        ComplexityViolationReport {
+            input: NamedSource::new(name, input),
+            span: (
+                self.span.start_offset,
+                self.span.end_offset - self.span.start_offset,
+            )
+                .into(),
+            description: self.description,
+            measured: self.measured,
+            allowed: self.allowed,
+        }
+    }
+}
+
+#[derive(Error, Debug, Diagnostic)]
+#[error("{description}: {measured} is more than the allowed {allowed}")]
+#[diagnostic(severity(Error))]
+struct ComplexityViolationReport {
+    #[source_code]
+    input: NamedSource,
+    #[label("here")]
+    span: SourceSpan,
+    description: &'static str,
+    measured: usize,
+    allowed: usize,
+}
+
+/// Check a module against the configured naming conventions.
+///
+/// Only violations that aren't allowed are returned.
+pub fn check_naming(module: &Module, lints: &NamingLints) -> Vec<(LintLevel, NamingViolation)> {
+    naming::check_names(module)
+        .into_iter()
+        .filter(|violation| !lints.is_allowed(&violation.name))
+        .filter_map(|violation| {
+            let level = match violation.rule {
+                NamingRule::SnakeCase => lints.snake_case,
+                NamingRule::UpperCamelCase => lints.upper_camel_case,
+                NamingRule::CapitalizedModules => lints.capitalized_modules,
+                NamingRule::ConsecutiveUnderscores => lints.consecutive_underscores,
+                NamingRule::TrailingUnderscore => lints.trailing_underscore,
+            }?;
+            if level == LintLevel::Allow {
+                return None;
+            }
+            Some((level, violation))
+        })
+        .collect()
+}
+
+fn naming_report(
+    violation: NamingViolation,
+    name: &str,
+    input: Arc<String>,
+) -> NamingViolationReport {
+    let help = violation.suggestion.map(|suggestion| {
+        if violation.edits.is_some() {
+            format!(
+                "rename to `{}` (this can be applied automatically)",
+                suggestion
+            )
+        } else {
+            format!("rename to `{}`", suggestion)
+        }
+    });
+    NamingViolationReport {
+        input: NamedSource::new(name, input),
+        span: (
+            violation.span.start_offset,
+            violation.span.end_offset - violation.span.start_offset,
+        )
+            .into(),
+        code: violation.rule.code(),
+        name: violation.name,
+        help,
+    }
+}
+
+#[derive(Error, Debug, Diagnostic)]
+#[error("`{name}` breaks the {code} naming convention")]
+#[diagnostic(severity(Warning))]
+struct NamingViolationReport {
+    #[source_code]
+    input: NamedSource,
+    #[label("here")]
+    span: SourceSpan,
+    code: &'static str,
+    name: String,
+    #[help]
+    help: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_complexity, check_naming};
+    use ditto_config::{ComplexityLints, LintLevel, NamingLints};
+    use ditto_cst::{naming::apply_edits, Module};
+
+    static SOURCE: &str = "module Test exports (..);
+five = 5;
+add = (a, b) -> [[a], [b]];
+";
+
+    fn check(limits: ComplexityLints) -> Vec<&'static str> {
+        let module = Module::parse(SOURCE).unwrap();
+        check_complexity(&module, SOURCE, &limits)
+            .into_iter()
+            .map(|violation| violation.description)
+            .collect()
+    }
+
+    #[test]
+    fn it_allows_everything_by_default() {
+        assert!(check(ComplexityLints::default()).is_empty());
+    }
+
+    #[test]
+    fn it_checks_limits_at_the_boundary() {
+        let at_limit = ComplexityLints {
+            max_declarations: Some(2),
+            max_function_binders: Some(2),
+            max_nesting_depth: Some(4),
+            max_module_lines: Some(3),
+        };
+        assert!(check(at_limit).is_empty());
+
+        let below_limit = ComplexityLints {
+            max_declarations: Some(1),
+            max_function_binders: Some(1),
+            max_nesting_depth: Some(3),
+            max_module_lines: Some(2),
+        };
+        assert_eq!(
+            check(below_limit),
+            vec![
+                "module has too many lines",
+                "module has too many declarations",
+                "function has too many binders",
+                "expression is too deeply nested",
+            ]
+        );
+    }
+
+    #[test]
+    fn it_reports_the_first_excess_declaration() {
+        let module = Module::parse(SOURCE).unwrap();
+        let violations = check_complexity(
+            &module,
+            SOURCE,
+            &ComplexityLints {
+                max_declarations: Some(1),
+                ..ComplexityLints::default()
+            },
+        );
+        let span = violations[0].span;
+        assert!(SOURCE[span.start_offset..span.end_offset].starts_with("add ="));
+        assert_eq!((violations[0].measured, violations[0].allowed), (2, 1));
+    }
+
+    static NAMING_SOURCE: &str = "module Test exports (..);
+ffi_fetchJson = 5;
+fiveFive = 55;
+double = (myNumber) -> [myNumber, myNumber];
+";
+
+    fn check_names(lints: NamingLints) -> Vec<(LintLevel, String)> {
+        let module = Module::parse(NAMING_SOURCE).unwrap();
+        check_naming(&module, &lints)
+            .into_iter()
+            .map(|(level, violation)| (level, violation.name))
+            .collect()
+    }
+
+    #[test]
+    fn it_allows_all_names_by_default() {
+        assert!(check_names(NamingLints::default()).is_empty());
+    }
+
+    #[test]
+    fn it_checks_naming_levels_and_patterns() {
+        let lints = NamingLints {
+            snake_case: Some(LintLevel::Deny),
+            ..NamingLints::default()
+        };
+        assert_eq!(
+            check_names(lints.clone()),
+            vec![
+                (LintLevel::Deny, "ffi_fetchJson".to_string()),
+                (LintLevel::Deny, "fiveFive".to_string()),
+                (LintLevel::Deny, "myNumber".to_string()),
+            ]
+        );
+        assert_eq!(
+            check_names(NamingLints {
+                allow: vec!["ffi_*".to_string()],
+                ..lints
+            }),
+            vec![
+                (LintLevel::Deny, "fiveFive".to_string()),
+                (LintLevel::Deny, "myNumber".to_string()),
+            ]
+        );
+        assert!(check_names(NamingLints {
+            snake_case: Some(LintLevel::Allow),
+            ..NamingLints::default()
+        })
+        .is_empty());
+    }
+
+    #[test]
+    fn it_only_fixes_local_binders() {
+        let module = Module::parse(NAMING_SOURCE).unwrap();
+        let violations = check_naming(
+            &module,
+            &NamingLints {
+                snake_case: Some(LintLevel::Warn),
+                ..NamingLints::default()
+            },
+        );
+        let edits = violations
+            .iter()
+            .filter_map(|(_, violation)| violation.edits.clone())
+            .flatten()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            apply_edits(NAMING_SOURCE, &edits),
+            "module Test exports (..);
+ffi_fetchJson = 5;
+fiveFive = 55;
+double = (my_number) -> [my_number, my_number];
+"
+        );
+    }
+}
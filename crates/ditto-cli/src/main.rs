@@ -1,11 +1,16 @@
 mod bootstrap;
 mod common;
+mod explain;
 mod fmt;
+mod index;
+mod lint;
 mod lsp;
 mod make;
 mod ninja;
 mod pkg;
+mod project_paths;
 mod spinner;
+mod verify_build;
 mod version;
 
 use clap::{ArgMatches, Command};
@@ -32,7 +37,12 @@ fn command<'a>(version_short: &'a str, version_long: &'a str) -> Command<'a> {
         .subcommand(bootstrap::command("bootstrap").display_order(0))
         .subcommand(make::command("make").display_order(1))
         .subcommand(fmt::command("fmt").display_order(2))
-        .subcommand(lsp::command("lsp").display_order(3))
+        .subcommand(lint::command("lint").display_order(3))
+        .subcommand(lsp::command("lsp").display_order(4))
+        .subcommand(pkg::command("pkg").display_order(5))
+        .subcommand(index::command("search").display_order(6))
+        .subcommand(verify_build::command("verify-build").display_order(7))
+        .subcommand(explain::command("explain-type").display_order(8))
         .subcommand(
             ninja::command("ninja")
                 // For internal use !
@@ -56,6 +66,16 @@ async fn run(matches: &ArgMatches, version: &Version) -> Result<()> {
         ninja::run(matches).await
     } else if let Some(matches) = matches.subcommand_matches("fmt") {
         fmt::run(matches)
+    } else if let Some(matches) = matches.subcommand_matches("lint") {
+        lint::run(matches)
+    } else if let Some(matches) = matches.subcommand_matches("pkg") {
+        pkg::run(matches).await
+    } else if let Some(matches) = matches.subcommand_matches("search") {
+        index::run(matches)
+    } else if let Some(matches) = matches.subcommand_matches("verify-build") {
+        verify_build::run(matches, version).await
+    } else if let Some(matches) = matches.subcommand_matches("explain-type") {
+        explain::run(matches)
     } else if let Some(matches) = matches.subcommand_matches("bootstrap") {
         bootstrap::run(matches, version)
     } else {
@@ -66,7 +86,12 @@ async fn run(matches: &ArgMatches, version: &Version) -> Result<()> {
 #[tokio::main]
 async fn main() {
     if let Err(err) = try_main().await {
-        eprintln!("{:?}", err);
+        if let Some(skipped) = err.downcast_ref::<ditto_make::SkippedModule>() {
+            // The root cause has been reported elsewhere, so keep this brief
+            eprintln!("{}", skipped.render_note());
+        } else {
+            eprintln!("{:?}", err);
+        }
         std::process::exit(1);
     }
     std::process::exit(0);
@@ -82,15 +107,20 @@ async fn try_main() -> Result<()> {
     }));
 
     miette::set_hook(Box::new(|_diagnostic| {
+        if let common::DiagnosticsFormat::Json { frames } = common::DiagnosticsFormat::from_env() {
+            return Box::new(common::JsonReportHandler::new(frames));
+        }
         // https://github.com/zkat/miette/blob/468843aa5c36ddac690dfe3a1fdaabe050a36563/src/handlers/theme.rs#L63
-        Box::new(
-            miette::GraphicalReportHandler::new().with_theme(if common::is_plain() {
-                //miette::GraphicalTheme::ascii()
-                miette::GraphicalTheme::unicode_nocolor()
-            } else {
-                miette::GraphicalTheme::unicode()
-            }),
-        )
+        let graphical = miette::GraphicalReportHandler::new().with_theme(if common::is_plain() {
+            //miette::GraphicalTheme::ascii()
+            miette::GraphicalTheme::unicode_nocolor()
+        } else {
+            miette::GraphicalTheme::unicode()
+        });
+        Box::new(common::LocatedReportHandler::new(
+            graphical,
+            common::supports_hyperlinks(),
+        ))
     }))
     .expect("Error installing miette hook");
 
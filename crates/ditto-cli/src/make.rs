@@ -1,11 +1,16 @@
-use crate::{common, ninja::get_ninja_exe, pkg, spinner::Spinner, version::Version};
+use crate::{common, ninja::get_ninja_exe, pkg, project_paths, spinner::Spinner, version::Version};
 use clap::{Arg, ArgMatches, Command};
 use console::Style;
-use ditto_config::{read_config, Config, PackageName, CONFIG_FILE_NAME};
-use ditto_make::{self as make, BuildNinja, GetWarnings, PackageSources, Sources};
+use ditto_config::{
+    read_config, Config, LintLevel, Lints, PackageName, ProfileName, CONFIG_FILE_NAME,
+};
+use ditto_make::{
+    self as make, BuildManifest, BuildNinja, BundlePlan, CodegenOptions, GetWarnings,
+    PackageSources, PlanError, RebuildExplanation, Sources, StaleSource,
+};
 use fs2::FileExt;
 use log::{debug, trace};
-use miette::{IntoDiagnostic, Result, WrapErr};
+use miette::{miette, Diagnostic, IntoDiagnostic, Result, WrapErr};
 use notify::Watcher;
 use std::{
     collections::HashMap,
@@ -15,26 +20,111 @@ use std::{
     path::{Path, PathBuf},
     process::{self, ExitStatus, Stdio},
     sync::mpsc,
-    time::{Duration, Instant},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
+use thiserror::Error;
 
 pub static COMPILE_SUBCOMMAND: &str = "compile";
 
 pub fn command<'a>(name: &str) -> Command<'a> {
-    Command::new(name).about("Build a project").arg(
-        Arg::new("watch")
-            .short('w')
-            .long("watch")
-            .help("Watch files for changes"),
-    )
+    Command::new(name)
+        .about("Build a project")
+        .arg(
+            Arg::new("watch")
+                .short('w')
+                .long("watch")
+                .help("Watch files for changes"),
+        )
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .help("Print diagnostics as JSON"),
+        )
+        .arg(
+            Arg::new("json-frames")
+                .long("json-frames")
+                .help("Print diagnostics as JSON, including code frames"),
+        )
+        .arg(
+            Arg::new("explain-build")
+                .long("explain-build")
+                .help("Explain why modules are being rebuilt"),
+        )
+        .arg(
+            Arg::new("allow")
+                .long("allow")
+                .takes_value(true)
+                .value_name("CODE")
+                .multiple_occurrences(true)
+                .help("Suppress warnings with this code"),
+        )
+        .arg(
+            Arg::new("deny")
+                .long("deny")
+                .takes_value(true)
+                .value_name("CODE")
+                .multiple_occurrences(true)
+                .help("Treat warnings with this code as errors"),
+        )
+        .arg(
+            Arg::new("frozen")
+                .long("frozen")
+                .help("Require ditto.lock to be up to date"),
+        )
+        .arg(
+            Arg::new("bundle")
+                .long("bundle")
+                .takes_value(true)
+                .value_name("FILE")
+                .help("Bundle the generated JavaScript into a single script"),
+        )
+        .arg(
+            Arg::new("profile")
+                .long("profile")
+                .takes_value(true)
+                .value_name("PROFILE")
+                .possible_values(ProfileName::ALL)
+                .default_value(ProfileName::default().as_str())
+                .help("Build with the settings of this profile"),
+        )
+        .arg(
+            Arg::new("emit-only")
+                .long("emit-only")
+                .takes_value(true)
+                .value_name("MODULE")
+                .help("Only build this module and the modules it depends on"),
+        )
+        .arg(
+            Arg::new("diagnostics-file")
+                .long("diagnostics-file")
+                .takes_value(true)
+                .value_name("FILE")
+                .requires("watch")
+                .help("Write all current diagnostics to this file as JSON after every rebuild")
+                .long_help(
+                    "Write all current diagnostics to this file as JSON after every rebuild.\n\n\
+                     Builds keep going after failures (like `ninja -k 0`), \
+                     so that every module that can be checked is diagnosed.",
+                ),
+        )
+        .arg(pkg::jobs_arg())
 }
 
 pub async fn run(matches: &ArgMatches, ditto_version: &Version) -> Result<()> {
+    if matches.is_present("json-frames") {
+        common::DiagnosticsFormat::Json { frames: true }.set_env();
+    } else if matches.is_present("json") {
+        common::DiagnosticsFormat::Json { frames: false }.set_env();
+    }
     if matches.is_present("watch") {
         run_watch(matches, ditto_version).await
     } else {
-        let status = run_once(matches, ditto_version).await?;
-        process::exit(status.code().unwrap_or(0));
+        match run_once(matches, ditto_version, None).await {
+            Ok(outcome) => process::exit(outcome.status.code().unwrap_or(0)),
+            // Already printed, so exit as the build would have
+            Err(err) if err.downcast_ref::<PlanningParseErrors>().is_some() => process::exit(1),
+            Err(err) => Err(err),
+        }
     }
 }
 
@@ -98,20 +188,31 @@ pub async fn run_watch(matches: &ArgMatches, ditto_version: &Version) -> Result<
         )
         .into_diagnostic()?;
 
+    // Outputs shouldn't be under `src`, but be defensive in case they are
+    // (or a symlink means they are) as rebuilding for them would loop forever
+    let current_dir = std::env::current_dir().into_diagnostic()?;
+    let ignored_dirs = read_config(&PathBuf::from(CONFIG_FILE_NAME))
+        .map(|config| project_paths::output_dirs(&current_dir, &config))
+        .unwrap_or_default();
+
+    let diagnostics_file = matches.value_of("diagnostics-file").map(PathBuf::from);
+    let mut generation = 0;
+
     // Clear screen initially
     // (other watching tools do this)
-    clearscreen::clear()
-        .into_diagnostic()
-        .wrap_err("error clearing screen")?;
+    clear_screen()?;
 
     //let print_done = || {
     //    println!("{}", Style::new().green().bold().apply_to("Done"));
     //};
 
-    if let Err(err) = run_once(matches, ditto_version).await {
-        // print the error but don't exit!
-        eprintln!("{:?}", err);
-    }
+    run_watch_iteration(
+        matches,
+        ditto_version,
+        diagnostics_file.as_deref(),
+        &mut generation,
+    )
+    .await?;
     //print_done();
 
     // Listen for changes...
@@ -125,23 +226,19 @@ pub async fn run_watch(matches: &ArgMatches, ditto_version: &Version) -> Result<
                 ..
             }) if paths.len() == 1 => {
                 let path = paths.pop().unwrap();
-                let event_path_extension = path.extension().and_then(|ext| ext.to_str());
-                // Be selective about what we re-run for.
-                // I.e. don't re-run for foreign files etc.
-                if matches!(
-                    event_path_extension,
-                    // ditto source file
-                    Some("ditto") | 
-                    // config file
-                    Some("toml")
-                ) {
-                    clearscreen::clear()
-                        .into_diagnostic()
-                        .wrap_err("error clearing screen")?;
-                    if let Err(err) = run_once(matches, ditto_version).await {
-                        // print the error but don't exit!
-                        eprintln!("{:?}", err);
-                    }
+                let is_ignored = project_paths::is_within_any(
+                    &project_paths::resolve_path(&current_dir, &path),
+                    &ignored_dirs,
+                );
+                if is_watched_path(&path) && !is_ignored {
+                    clear_screen()?;
+                    run_watch_iteration(
+                        matches,
+                        ditto_version,
+                        diagnostics_file.as_deref(),
+                        &mut generation,
+                    )
+                    .await?;
                     //print_done();
                 }
             }
@@ -152,9 +249,115 @@ pub async fn run_watch(matches: &ArgMatches, ditto_version: &Version) -> Result<
     }
 }
 
-pub async fn run_once(_matches: &ArgMatches, ditto_version: &Version) -> Result<ExitStatus> {
+/// Be selective about what we re-run for.
+/// I.e. don't re-run for foreign files etc.
+fn is_watched_path(path: &Path) -> bool {
+    // NOTE extensions are compared case-insensitively,
+    // as Windows file systems usually are
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map_or(false, |ext| {
+            // ditto source file
+            ext.eq_ignore_ascii_case("ditto")
+                // config file
+                || ext.eq_ignore_ascii_case("toml")
+        })
+}
+
+fn clear_screen() -> Result<()> {
+    // Plain output is probably being piped somewhere, so don't mess with it
+    if common::is_plain() {
+        return Ok(());
+    }
+    clearscreen::clear()
+        .into_diagnostic()
+        .wrap_err("error clearing screen")
+}
+
+/// Run a build without exiting on failure,
+/// writing the resulting diagnostics if a `diagnostics_file` is given.
+///
+/// If sources were edited while the build was running then the build is
+/// immediately rerun, rather than presenting stale results as current.
+async fn run_watch_iteration(
+    matches: &ArgMatches,
+    ditto_version: &Version,
+    diagnostics_file: Option<&Path>,
+    generation: &mut u64,
+) -> Result<()> {
+    let mut diagnostics = Diagnostics::default();
+    loop {
+        let result = run_once(
+            matches,
+            ditto_version,
+            diagnostics_file.map(|_| &mut diagnostics),
+        )
+        .await;
+        match result {
+            Ok(MakeOutcome { stale_sources, .. }) if !stale_sources.is_empty() => {
+                clear_screen()?;
+                for StaleSource { source, .. } in stale_sources {
+                    debug!(
+                        "{} changed during the build, rebuilding",
+                        source.to_string_lossy()
+                    );
+                }
+                diagnostics = Diagnostics::default();
+                continue;
+            }
+            Ok(_) => {}
+            Err(err) => report_watch_error(err, &mut diagnostics),
+        }
+        break;
+    }
+    if let Some(diagnostics_file) = diagnostics_file {
+        *generation += 1;
+        let result = diagnostics
+            .write(diagnostics_file, *generation)
+            .wrap_err(format!(
+                "error writing diagnostics to {:?}",
+                diagnostics_file.to_string_lossy()
+            ));
+        if let Err(err) = result {
+            // Keep watching, the next rebuild might have better luck
+            log::error!("{:?}", err);
+            eprintln!("{:?}", err);
+        }
+    }
+    Ok(())
+}
+
+/// Print an error from a watch build without exiting,
+/// and collect it with the other diagnostics.
+fn report_watch_error(err: miette::Report, diagnostics: &mut Diagnostics) {
+    if let Some(PlanningParseErrors(reports)) = err.downcast_ref() {
+        // These have already been printed
+        reports
+            .iter()
+            .for_each(|report| diagnostics.push_report(report));
+        return;
+    }
+    // print the error but don't exit!
+    eprintln!("{:?}", err);
+    diagnostics.push_report(&err);
+}
+
+/// The result of a single build.
+pub struct MakeOutcome {
+    /// The exit status of the build.
+    pub status: ExitStatus,
+    /// Sources that were edited while the build was running.
+    pub stale_sources: Vec<StaleSource>,
+}
+
+pub async fn run_once(
+    matches: &ArgMatches,
+    ditto_version: &Version,
+    diagnostics: Option<&mut Diagnostics>,
+) -> Result<MakeOutcome> {
     let config_path: PathBuf = [".", CONFIG_FILE_NAME].iter().collect();
     let config = read_config(&config_path)?;
+    project_paths::check_project_paths(config_path.parent().unwrap_or(Path::new(".")), &config)?;
 
     // Need to acquire a lock on the build directory as lots of `ditto make`
     // processes running concurrently will cause problems!
@@ -164,17 +367,39 @@ pub async fn run_once(_matches: &ArgMatches, ditto_version: &Version) -> Result<
     // Install/remove packages as needed
     // (this is a nicer pattern than requiring a run of a separate CLI command, IMO)
     if !config.dependencies.is_empty() {
-        pkg::check_packages_up_to_date(&config)
+        let frozen = matches.is_present("frozen");
+        if frozen {
+            pkg::check_frozen_lockfile(&config)?;
+        }
+        pkg::check_packages_up_to_date(&config, pkg::get_jobs(matches))
             .await
             .wrap_err("error checking packages are up to date")?;
+        pkg::check_lockfile(&config, frozen)?;
     }
 
     let now = Instant::now(); // for timing
 
     // Do the work
-    let status = make(&config_path, &config, ditto_version)
-        .await
-        .wrap_err("error running make")?;
+    let explain_build = matches.is_present("explain-build");
+    let lint_overrides = get_lint_overrides(matches);
+    let bundle_path = matches.value_of("bundle").map(PathBuf::from);
+    let profile = matches
+        .value_of("profile")
+        .and_then(ProfileName::parse)
+        .unwrap_or_default();
+    let outcome = make(
+        &config_path,
+        &config,
+        ditto_version,
+        explain_build,
+        &lint_overrides,
+        profile,
+        matches.value_of("emit-only"),
+        bundle_path.as_deref(),
+        diagnostics,
+    )
+    .await
+    .wrap_err("error running make")?;
 
     lock.unlock()
         .into_diagnostic()
@@ -182,15 +407,75 @@ pub async fn run_once(_matches: &ArgMatches, ditto_version: &Version) -> Result<
 
     debug!("make ran in {}ms", now.elapsed().as_millis());
 
-    Ok(status)
+    Ok(outcome)
+}
+
+/// Warning levels passed on the command line, which override the config.
+fn get_lint_overrides(matches: &ArgMatches) -> Lints {
+    let mut lint_overrides = Lints::new();
+    for (arg, level) in [("allow", LintLevel::Allow), ("deny", LintLevel::Deny)] {
+        if let Some(codes) = matches.values_of(arg) {
+            for code in codes {
+                lint_overrides.insert(code.to_owned(), level);
+            }
+        }
+    }
+    lint_overrides
 }
 
-async fn make(config_path: &Path, config: &Config, ditto_version: &Version) -> Result<ExitStatus> {
-    let (build_ninja, get_warnings) = generate_build_ninja(config_path, config, ditto_version)
-        .wrap_err("error generating build.ninja")?;
+#[allow(clippy::too_many_arguments)]
+async fn make(
+    config_path: &Path,
+    config: &Config,
+    ditto_version: &Version,
+    explain_build: bool,
+    lint_overrides: &Lints,
+    profile: ProfileName,
+    emit_only: Option<&str>,
+    bundle_path: Option<&Path>,
+    diagnostics: Option<&mut Diagnostics>,
+) -> Result<MakeOutcome> {
+    let (build_ninja, get_warnings) = generate_build_ninja(
+        config_path,
+        config,
+        ditto_version,
+        lint_overrides,
+        profile,
+        emit_only,
+    )
+    .wrap_err("error generating build.ninja")?;
 
     trace!("build.ninja generated");
 
+    let bundle_plan = if bundle_path.is_some() {
+        if !config.targets_js() {
+            return Err(miette!(
+                "can't bundle a project that doesn't target JavaScript"
+            ));
+        }
+        Some(
+            BundlePlan::new(
+                &config.name,
+                &build_ninja,
+                config.codegen_js_config.unit,
+                CodegenOptions::for_profile(config.profile(profile)),
+            )
+            .wrap_err("error planning bundle")?,
+        )
+    } else {
+        None
+    };
+
+    let build_manifest = BuildManifest::new(&build_ninja, &ditto_version.semversion, config_path)
+        .wrap_err("error hashing build inputs")?;
+
+    let mut build_manifest_path = config.ditto_dir.to_path_buf();
+    build_manifest_path.push(BUILD_MANIFEST_FILE);
+
+    if explain_build {
+        explain(&build_manifest, &build_manifest_path)?;
+    }
+
     let mut build_ninja_path = config.ditto_dir.to_path_buf();
     build_ninja_path.push("build");
     build_ninja_path.set_extension("ninja");
@@ -226,10 +511,101 @@ async fn make(config_path: &Path, config: &Config, ditto_version: &Version) -> R
         );
     }
 
+    let status = run_ninja(&build_ninja_path, get_warnings, diagnostics).await?;
+    let mut stale_sources = Vec::new();
+    if status.success() {
+        let build_manifest = build_manifest
+            .refresh()
+            .and_then(|build_manifest| {
+                build_manifest.write(&build_manifest_path)?;
+                Ok(build_manifest)
+            })
+            .wrap_err(format!(
+                "error writing build manifest {:?}",
+                build_manifest_path.to_string_lossy()
+            ))?;
+
+        stale_sources = build_manifest
+            .stale_sources()
+            .wrap_err("error checking for sources edited during the build")?;
+        // ninja only compares timestamps, so an edit that landed while a
+        // source was being compiled could go unnoticed.
+        // Removing the outputs makes sure the next build picks it up.
+        for StaleSource { outputs, .. } in stale_sources.iter() {
+            for output in outputs {
+                if output.exists() {
+                    fs::remove_file(output)
+                        .into_diagnostic()
+                        .wrap_err(format!("error removing {}", output.to_string_lossy()))?;
+                }
+            }
+        }
+
+        if let (Some(bundle_plan), Some(bundle_path)) = (bundle_plan, bundle_path) {
+            bundle_plan.write(bundle_path)?;
+            debug!("bundle written to {:?}", bundle_path.to_string_lossy());
+        }
+    }
+    Ok(MakeOutcome {
+        status,
+        stale_sources,
+    })
+}
+
+pub static BUILD_MANIFEST_FILE: &str = "build-manifest";
+
+fn explain(build_manifest: &BuildManifest, previous_path: &Path) -> Result<()> {
+    let previous = BuildManifest::read(previous_path).wrap_err(format!(
+        "error reading build manifest {:?}",
+        previous_path.to_string_lossy()
+    ))?;
+    if let Some(previous) = previous {
+        let explanations = build_manifest.explain(&previous);
+        if explanations.is_empty() {
+            println!(
+                "{}",
+                Style::new().white().dim().apply_to("Nothing to rebuild")
+            );
+        }
+        for RebuildExplanation {
+            description,
+            reason,
+        } in explanations
+        {
+            println!("{}: {}", Style::new().bold().apply_to(description), reason);
+        }
+    } else {
+        println!(
+            "{}",
+            Style::new()
+                .white()
+                .dim()
+                .apply_to("No previous build manifest, everything will be built")
+        );
+    }
+    Ok(())
+}
+
+async fn run_ninja(
+    build_ninja_path: &Path,
+    get_warnings: GetWarnings,
+    mut diagnostics: Option<&mut Diagnostics>,
+) -> Result<ExitStatus> {
     static NINJA_STATUS_MESSAGE: &str = "__NINJA";
 
     let ninja_exe = get_ninja_exe().await?;
-    let mut child = process::Command::new(&ninja_exe)
+    let mut command = process::Command::new(&ninja_exe);
+    if diagnostics.is_some() {
+        // Only done when collecting diagnostics (i.e. `--diagnostics-file`),
+        // as otherwise the first failure is all that's reported anyway
+        command
+            // Keep going, so that we collect errors for as many modules as possible
+            .arg("-k")
+            .arg("0")
+            // Get diagnostics in a format we can collect (and still print)
+            .env(common::DIAGNOSTICS_ENV, "json-frames");
+    }
+    let mut child = command
         .arg("-f")
         .arg(&build_ninja_path)
         .stdout(Stdio::piped())
@@ -258,15 +634,13 @@ async fn make(config_path: &Path, config: &Config, ditto_version: &Version) -> R
             // Nothing to do,
             // still need to print warnings though
             let warnings = get_warnings()?;
+            if let Some(diagnostics) = diagnostics {
+                warnings
+                    .iter()
+                    .for_each(|warning| diagnostics.push_report(warning));
+            }
             if !warnings.is_empty() {
-                let warnings_len = warnings.len();
-                for (i, warning) in warnings.into_iter().enumerate() {
-                    if i == warnings_len - 1 {
-                        eprintln!("{:?}", warning);
-                    } else {
-                        eprint!("{:?}", warning);
-                    }
-                }
+                print_warnings(warnings);
             } else {
                 println!("{}", Style::new().white().dim().apply_to("Nothing to do"));
             }
@@ -286,6 +660,9 @@ async fn make(config_path: &Path, config: &Config, ditto_version: &Version) -> R
             // so we need to replicate that behavior when forwarding ninja
             // output for a consistent experience.
             let mut printed_initial_newline = false;
+            // Modules that weren't checked because a dependency failed,
+            // which are collapsed into a single line
+            let mut skipped_modules = Vec::new();
             while let Some(Ok(line)) = stdout_lines.next() {
                 if line.starts_with(NINJA_STATUS_MESSAGE) {
                     spinner.set_message(line.trim_start_matches(NINJA_STATUS_MESSAGE).to_owned());
@@ -294,30 +671,41 @@ async fn make(config_path: &Path, config: &Config, ditto_version: &Version) -> R
                     // The following line prints the command that was run (and failed)
                     // so swallow it
                     stdout_lines.next();
+                } else if let Some(module) =
+                    ditto_make::SkippedModule::parse_note(&console::strip_ansi_codes(&line))
+                {
+                    skipped_modules.push(module.to_owned());
                 } else {
                     if !printed_initial_newline {
                         spinner.println("\n");
                         printed_initial_newline = true
                     }
+                    let line = match diagnostics.as_deref_mut() {
+                        Some(diagnostics) => diagnostics.push_line(line),
+                        None => line,
+                    };
                     spinner.println(line);
                 }
             }
 
+            if let Some(summary) = summarize_skipped_modules(skipped_modules) {
+                spinner.println(summary);
+            }
+
             let status = child.wait().expect("error waiting for ninja to exit");
             spinner.finish();
+            // Warnings from the modules that were checked successfully are
+            // collected even if something else failed, so that the diagnostics
+            // file has the whole picture (the errors are already in there)
+            let warnings = get_warnings()?;
+            if let Some(diagnostics) = diagnostics {
+                warnings
+                    .iter()
+                    .for_each(|warning| diagnostics.push_report(warning));
+            }
             if status.success() {
                 // Only print warnings if there wasn't an error
-                let warnings = get_warnings()?;
-                if !warnings.is_empty() {
-                    let warnings_len = warnings.len();
-                    for (i, warning) in warnings.into_iter().enumerate() {
-                        if i == warnings_len - 1 {
-                            eprintln!("{:?}", warning);
-                        } else {
-                            eprint!("{:?}", warning);
-                        }
-                    }
-                }
+                print_warnings(warnings);
             }
             Ok(status)
         }
@@ -326,12 +714,104 @@ async fn make(config_path: &Path, config: &Config, ditto_version: &Version) -> R
     }
 }
 
+fn summarize_skipped_modules(mut modules: Vec<String>) -> Option<String> {
+    if modules.is_empty() {
+        return None;
+    }
+    modules.sort();
+    modules.dedup();
+    Some(format!(
+        "note: skipped {} module{} because of the errors above: {}",
+        modules.len(),
+        if modules.len() == 1 { "" } else { "s" },
+        modules.join(", ")
+    ))
+}
+
+fn print_warnings(warnings: Vec<miette::Report>) {
+    let is_json = matches!(
+        common::DiagnosticsFormat::from_env(),
+        common::DiagnosticsFormat::Json { .. }
+    );
+    let warnings_len = warnings.len();
+    for (i, warning) in warnings.into_iter().enumerate() {
+        // JSON diagnostics are printed one per line
+        if is_json || i == warnings_len - 1 {
+            eprintln!("{:?}", warning);
+        } else {
+            eprint!("{:?}", warning);
+        }
+    }
+}
+
+/// Diagnostics collected from a build, for `--diagnostics-file`.
+#[derive(Default)]
+pub struct Diagnostics(Vec<serde_json::Value>);
+
+impl Diagnostics {
+    fn push_report(&mut self, report: &miette::Report) {
+        let json = common::render_diagnostic_json(&**report, false);
+        if let Ok(value) = serde_json::from_str(&json) {
+            self.0.push(value);
+        }
+    }
+
+    /// Collect a line of ninja output if it's a diagnostic,
+    /// returning the line that should be printed in its place.
+    fn push_line(&mut self, line: String) -> String {
+        let mut object = match serde_json::from_str(&console::strip_ansi_codes(&line)) {
+            Ok(serde_json::Value::Object(object)) => object,
+            _ => return line,
+        };
+        let frame = object.remove("frame");
+        let without_frame = serde_json::Value::Object(object);
+        let print = match (common::DiagnosticsFormat::from_env(), frame) {
+            (common::DiagnosticsFormat::Json { frames: true }, _) => line,
+            (common::DiagnosticsFormat::Json { frames: false }, _) => without_frame.to_string(),
+            (common::DiagnosticsFormat::Graphical, Some(serde_json::Value::String(frame))) => frame,
+            (common::DiagnosticsFormat::Graphical, _) => line,
+        };
+        self.0.push(without_frame);
+        print
+    }
+
+    /// Atomically (over)write `path` with the collected diagnostics.
+    fn write(self, path: &Path, generation: u64) -> Result<()> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .into_diagnostic()?
+            .as_millis() as u64;
+        let document = serde_json::json!({
+            "generation": generation,
+            "timestamp": timestamp,
+            "diagnostics": self.0,
+        });
+
+        let dir = match path.parent() {
+            Some(parent) if parent != Path::new("") => parent,
+            _ => Path::new("."),
+        };
+        fs::create_dir_all(dir).into_diagnostic()?;
+        // Write to a temporary file and rename it,
+        // so that readers never see a partially written file
+        let mut file = tempfile::NamedTempFile::new_in(dir).into_diagnostic()?;
+        file.write_all(document.to_string().as_bytes())
+            .into_diagnostic()?;
+        file.persist(path).into_diagnostic()?;
+        Ok(())
+    }
+}
+
 fn generate_build_ninja(
     config_path: &Path,
     config: &Config,
     ditto_version: &Version,
+    lint_overrides: &Lints,
+    profile: ProfileName,
+    emit_only: Option<&str>,
 ) -> Result<(BuildNinja, GetWarnings)> {
-    let mut build_dir = config.ditto_dir.to_path_buf();
+    // ninja doesn't understand verbatim paths
+    let mut build_dir = make::simplify_path(&config.ditto_dir);
     build_dir.push("build");
     build_dir.push(&ditto_version.semversion.to_string());
 
@@ -339,7 +819,23 @@ fn generate_build_ninja(
         .into_diagnostic()
         .wrap_err("error getting current executable")?;
 
+    if !config.src_dir.exists() {
+        return Err(miette!(
+            "source directory {} doesn't exist",
+            config.src_dir.to_string_lossy()
+        ));
+    }
     let ditto_sources = find_ditto_files(&config.src_dir)?;
+    if ditto_sources.is_empty() {
+        // Probably a mistake, but not necessarily an error
+        eprintln!(
+            "{}",
+            Style::new().yellow().apply_to(format!(
+                "warning: no .ditto files found in {}",
+                config.src_dir.to_string_lossy()
+            ))
+        );
+    }
 
     let sources = Sources {
         config: config_path.to_path_buf(),
@@ -356,21 +852,34 @@ fn generate_build_ninja(
         COMPILE_SUBCOMMAND,
         sources,
         package_sources,
+        lint_overrides,
+        profile,
+        emit_only,
     );
-    if let Err(ref report) = result {
-        // This is a bit brittle, but we want parse errors encountered during
-        // build planning to be indistinguishable from parse errors encountered
-        // during the actual build
-        if report.root_cause().to_string() == "syntax error" {
-            //                                  ^^ BEWARE relying on this string is brittle,
-            eprintln!("{:?}", report);
-            std::process::exit(1);
+    match result {
+        Ok(ok) => Ok(ok),
+        Err(PlanError::ModuleParseErrors(reports)) => {
+            // We want parse errors encountered during build planning to be
+            // indistinguishable from parse errors encountered during the actual build
+            for report in reports.iter() {
+                eprintln!("{:?}", report);
+            }
+            // NOTE it's up to the caller whether this should exit the process,
+            // as it shouldn't when watching
+            Err(PlanningParseErrors(reports).into())
         }
+        Err(PlanError::Other(report)) => Err(report),
     }
-    result
 }
 
-fn get_package_sources(config: &Config) -> Result<PackageSources> {
+/// Parse errors encountered during build planning.
+///
+/// The individual reports have already been printed by the time this is returned.
+#[derive(Error, Debug, Diagnostic)]
+#[error("error parsing modules")]
+struct PlanningParseErrors(Vec<miette::Report>);
+
+pub fn get_package_sources(config: &Config) -> Result<PackageSources> {
     let mut package_sources = HashMap::new();
     for path in pkg::list_installed_packages(&pkg::mk_packages_dir(config))? {
         let package_name =
@@ -396,7 +905,7 @@ fn get_sources_for_dir(dir: &Path) -> Result<Sources> {
     })
 }
 
-fn find_ditto_files<P: AsRef<Path>>(root: P) -> Result<Vec<PathBuf>> {
+pub fn find_ditto_files<P: AsRef<Path>>(root: P) -> Result<Vec<PathBuf>> {
     make::find_ditto_files(root.as_ref())
         .into_diagnostic()
         .wrap_err(format!(
@@ -422,7 +931,7 @@ fn acquire_lock(config: &Config) -> Result<impl FileExt> {
             ))?;
     }
 
-    let mut lock_file = config.ditto_dir.to_path_buf();
+    let mut lock_file = make::simplify_path(&config.ditto_dir);
     lock_file.push(LOCK_FILE);
 
     debug!("Opening lock file at {}", lock_file.to_string_lossy());
@@ -447,3 +956,56 @@ fn acquire_lock(config: &Config) -> Result<impl FileExt> {
         Ok(file)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        is_watched_path, report_watch_error, summarize_skipped_modules, Diagnostics,
+        PlanningParseErrors,
+    };
+    use miette::{miette, Report, WrapErr};
+    use std::path::PathBuf;
+
+    #[test]
+    fn it_watches_ditto_and_config_files() {
+        assert!(is_watched_path(&PathBuf::from("src/Main.ditto")));
+        assert!(is_watched_path(&PathBuf::from(
+            r"C:\project\src\Main.ditto"
+        )));
+        assert!(is_watched_path(&PathBuf::from(
+            r"\\?\C:\project\src\Main.DITTO"
+        )));
+        assert!(is_watched_path(&PathBuf::from("./ditto.toml")));
+        assert!(!is_watched_path(&PathBuf::from(r"src\Main.js")));
+        assert!(!is_watched_path(&PathBuf::from("src/Main")));
+    }
+
+    #[test]
+    fn it_keeps_watching_after_planning_parse_errors() {
+        let err: Report = PlanningParseErrors(vec![miette!("oops"), miette!("oops again")]).into();
+        let err = Err::<(), _>(err)
+            .wrap_err("error generating build.ninja")
+            .wrap_err("error running make")
+            .unwrap_err();
+        let mut diagnostics = Diagnostics::default();
+        report_watch_error(err, &mut diagnostics);
+        assert_eq!(diagnostics.0.len(), 2);
+
+        let mut diagnostics = Diagnostics::default();
+        report_watch_error(miette!("something else"), &mut diagnostics);
+        assert_eq!(diagnostics.0.len(), 1);
+    }
+
+    #[test]
+    fn it_summarizes_skipped_modules() {
+        assert_eq!(summarize_skipped_modules(vec![]), None);
+        let skipped = ["Data.Api", "Main", "Data.Api"]
+            .iter()
+            .map(|module| module.to_string())
+            .collect();
+        assert_eq!(
+            summarize_skipped_modules(skipped).unwrap(),
+            "note: skipped 2 modules because of the errors above: Data.Api, Main"
+        );
+    }
+}
@@ -3,13 +3,14 @@ use clap::{arg, ArgMatches, Command};
 use console::Emoji;
 use futures_util::StreamExt;
 use log::debug;
-use miette::{miette, IntoDiagnostic, Result, WrapErr};
+use miette::{miette, Diagnostic, IntoDiagnostic, Result, WrapErr};
 use std::{
     env,
     io::{Cursor, Write},
     path::{Path, PathBuf},
     process,
 };
+use thiserror::Error;
 
 pub fn command<'a>(name: &str) -> Command<'a> {
     Command::new(name)
@@ -34,7 +35,11 @@ pub async fn get_ninja_exe() -> Result<String> {
     match env::var_os("DITTO_NINJA") {
         Some(ninja_env) => {
             debug!("DITTO_NINJA set to {:?}", ninja_env);
-            Ok(ninja_env.to_string_lossy().into_owned())
+            let exe = ninja_env.to_string_lossy().into_owned();
+            if common::find_executable(&exe).is_none() {
+                return Err(MissingNinja { exe }.into());
+            }
+            Ok(exe)
         }
         None => {
             debug!("DITTO_NINJA not set, checking for cached ninja bin");
@@ -49,6 +54,15 @@ pub async fn get_ninja_exe() -> Result<String> {
     }
 }
 
+#[derive(Error, Debug, Diagnostic)]
+#[error("couldn't find ninja executable {exe:?}")]
+#[diagnostic(help(
+    "install ninja (https://ninja-build.org) and point DITTO_NINJA at it, or unset DITTO_NINJA to use a ninja downloaded by ditto"
+))]
+struct MissingNinja {
+    exe: String,
+}
+
 /// ~/.cache/ditto/ninja-bin/ninja_1-10-2
 fn get_cached_ninja_bin_path() -> Result<PathBuf> {
     let mut cached_ninja_dir = common::get_ditto_cache_dir()?;
@@ -1,23 +1,231 @@
 // Maybe this should live in it's own crate?
-use crate::{common::is_plain, spinner::Spinner};
+use crate::{common::is_plain, index, spinner::Spinner};
+use clap::{Arg, ArgMatches, Command};
 use console::{Emoji, Style};
 use ditto_config::{
-    read_config, Config, Dependencies, PackageName, PackageSetPackages as Packages, PackageSpec,
-    CONFIG_FILE_NAME,
+    read_config, read_lockfile, write_lockfile, Config, Dependencies, LockedPackage, Lockfile,
+    PackageName, PackageSetPackages as Packages, PackageSpec, CONFIG_FILE_NAME, LOCKFILE_NAME,
 };
 use indicatif::MultiProgress;
 use log::{debug, warn};
-use miette::{miette, IntoDiagnostic, Result, WrapErr};
+use miette::{bail, miette, IntoDiagnostic, Result, WrapErr};
+use sha2::{Digest, Sha256};
 use std::{
-    collections::{hash_map::DefaultHasher, HashSet},
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
     ffi::OsStr,
     fs,
     hash::{Hash, Hasher},
     io::BufReader,
     path::{Path, PathBuf},
+    sync::Arc,
 };
+use tokio::sync::Semaphore;
+
+pub fn command<'a>(name: &str) -> Command<'a> {
+    Command::new(name)
+        .about("Manage package dependencies")
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("outdated").about("List locked packages that differ from the package set"),
+        )
+        .subcommand(
+            Command::new("update")
+                .about("Reinstall packages from the package set")
+                .arg(
+                    Arg::new("name")
+                        .takes_value(true)
+                        .help("Only update this package"),
+                )
+                .arg(jobs_arg()),
+        )
+}
+
+/// Number of packages to install concurrently, unless overridden by `--jobs`.
+pub const DEFAULT_JOBS: usize = 4;
+
+pub fn jobs_arg<'a>() -> Arg<'a> {
+    Arg::new("jobs")
+        .short('j')
+        .long("jobs")
+        .takes_value(true)
+        .value_name("N")
+        .validator(|value| value.parse::<usize>())
+        .help("Maximum number of packages to install concurrently")
+}
+
+pub fn get_jobs(matches: &ArgMatches) -> usize {
+    matches
+        .value_of("jobs")
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_JOBS)
+}
+
+pub async fn run(matches: &ArgMatches) -> Result<()> {
+    let config_path: PathBuf = [".", CONFIG_FILE_NAME].iter().collect();
+    let config = read_config(&config_path)?;
+    if matches.subcommand_matches("outdated").is_some() {
+        run_outdated(&config)
+    } else if let Some(matches) = matches.subcommand_matches("update") {
+        run_update(&config, matches.value_of("name"), get_jobs(matches)).await
+    } else {
+        unreachable!()
+    }
+}
+
+fn run_outdated(config: &Config) -> Result<()> {
+    let available_packages = config.resolve_packages()?;
+    let lockfile_path: PathBuf = [".", LOCKFILE_NAME].iter().collect();
+    let lockfile = read_lockfile(&lockfile_path)?.unwrap_or_default();
+    let locked_packages = lockfile
+        .packages
+        .iter()
+        .map(|locked| (&locked.name, locked))
+        .collect::<HashMap<_, _>>();
+
+    let mut package_names = locked_packages
+        .keys()
+        .copied()
+        .chain(config.dependencies.iter())
+        .collect::<Vec<_>>();
+    package_names.sort();
+    package_names.dedup();
+
+    let mut rows = Vec::new();
+    for package_name in package_names {
+        let locked = locked_packages.get(package_name);
+        let available = available_packages.get(package_name);
+        // A package that can't be read shouldn't stop the others being reported
+        let available_version = available.and_then(|spec| read_spec_version(spec).ok().flatten());
+        if let (Some(locked), Some(available)) = (locked, available) {
+            if &locked.source == available && locked.version == available_version {
+                continue;
+            }
+        }
+        rows.push([
+            package_name.as_str().to_string(),
+            locked.map_or_else(
+                || String::from("-"),
+                |locked| render_package_version(&locked.source, locked.version.as_ref()),
+            ),
+            available.map_or_else(
+                || String::from("-"),
+                |spec| render_package_version(spec, available_version.as_ref()),
+            ),
+            match available {
+                Some(spec) if permits_version(spec, available_version.as_ref()) => {
+                    String::from("yes")
+                }
+                Some(_) => String::from("no"),
+                None => String::from("-"),
+            },
+            if config.dependencies.contains(package_name) {
+                String::from("yes")
+            } else {
+                String::from("no")
+            },
+        ]);
+    }
+
+    if rows.is_empty() {
+        println!(
+            "{}",
+            Style::new()
+                .white()
+                .dim()
+                .apply_to("All packages are up to date")
+        );
+        return Ok(());
+    }
+
+    let header = [
+        String::from("package"),
+        String::from("locked"),
+        String::from("available"),
+        String::from("permitted"),
+        String::from("direct"),
+    ];
+    let mut widths = [0; 5];
+    for row in std::iter::once(&header).chain(rows.iter()) {
+        for (width, cell) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let render_row = |row: &[String; 5]| {
+        row.iter()
+            .zip(widths.iter())
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+    if is_plain() {
+        println!("{}", render_row(&header));
+    } else {
+        println!("{}", Style::new().bold().apply_to(render_row(&header)));
+    }
+    for row in rows.iter() {
+        println!("{}", render_row(row));
+    }
+    Ok(())
+}
+
+/// The version a package declares, falling back to where it comes from.
+fn render_package_version(spec: &PackageSpec, version: Option<&semver::Version>) -> String {
+    version.map_or_else(|| render_package_spec(spec), |version| version.to_string())
+}
+
+fn render_package_spec(spec: &PackageSpec) -> String {
+    match spec {
+        PackageSpec::Path { path, .. } => path.to_string_lossy().into_owned(),
+    }
+}
 
-pub async fn check_packages_up_to_date(config: &Config) -> Result<()> {
+/// Read the version declared by the package at `spec`, without installing it.
+fn read_spec_version(spec: &PackageSpec) -> Result<Option<semver::Version>> {
+    match spec {
+        PackageSpec::Path { path, .. } => Ok(read_config(path.join(CONFIG_FILE_NAME))?.version),
+    }
+}
+
+/// Does the version requirement of `spec` (if any) allow installing `version`?
+fn permits_version(spec: &PackageSpec, version: Option<&semver::Version>) -> bool {
+    match spec {
+        PackageSpec::Path {
+            version: Some(requirement),
+            ..
+        } => version.map_or(false, |version| requirement.matches(version)),
+        PackageSpec::Path { version: None, .. } => true,
+    }
+}
+
+async fn run_update(config: &Config, package_name: Option<&str>, jobs: usize) -> Result<()> {
+    let packages_dir = get_or_create_packages_dir(config)?;
+    let installed_packages = get_installed_packages(&packages_dir)?;
+    if let Some(package_name) = package_name {
+        let package_name = PackageName::new_unchecked(package_name.to_string());
+        if !installed_packages.contains_key(&package_name) {
+            return Err(miette!("{:?} isn't installed", package_name.as_str()));
+        }
+        remove_package(&packages_dir, package_name.as_str())?;
+    } else {
+        for package_name in installed_packages.keys() {
+            remove_package(&packages_dir, package_name.as_str())?;
+        }
+    }
+
+    // Removing the hash forces `check_packages_up_to_date` to reinstall
+    // whatever is now missing.
+    let hash_file = mk_hash_file(&packages_dir);
+    if hash_file.exists() {
+        fs::remove_file(&hash_file).into_diagnostic()?;
+    }
+    check_packages_up_to_date(config, jobs).await?;
+    check_lockfile(config, false)
+}
+
+/// Install any missing or outdated packages, at most `jobs` at a time.
+pub async fn check_packages_up_to_date(config: &Config, jobs: usize) -> Result<()> {
     debug!("Checking if packages are up to date");
 
     let available_packages = config.resolve_packages()?.clone();
@@ -41,7 +249,8 @@ pub async fn check_packages_up_to_date(config: &Config) -> Result<()> {
         if let Ok(got_hash) = got_hash_string.parse::<u64>() {
             if want_hash == got_hash {
                 debug!("Packages are up to date");
-                return Ok(());
+                // Path packages can change without being reinstalled
+                return index::update_index(config);
             }
         } else {
             warn!("Corrupted hash file? got {}", got_hash_string);
@@ -60,16 +269,14 @@ pub async fn check_packages_up_to_date(config: &Config) -> Result<()> {
     }
 
     let installed_packages = get_installed_packages(&packages_dir)?;
-    let mut multi_progress = MultiProgress::new();
     update_dependencies(
-        &mut multi_progress,
         &packages_dir,
         &config.dependencies,
-        &mut Dependencies::new(),
         &installed_packages,
         &available_packages,
-    )?;
-    multi_progress.join().into_diagnostic()?;
+        jobs,
+    )
+    .await?;
 
     debug!(
         "Updating {} with {}",
@@ -77,9 +284,134 @@ pub async fn check_packages_up_to_date(config: &Config) -> Result<()> {
         want_hash
     );
     fs::write(hash_file, want_hash.to_string().as_bytes()).into_diagnostic()?;
+    index::update_index(config)
+}
+
+/// Check the lockfile agrees with the package set, without installing anything.
+///
+/// This is for `--frozen`, so that a stale lockfile is reported before any
+/// packages are (re)installed. The package contents are checked afterwards
+/// by [check_lockfile].
+pub fn check_frozen_lockfile(config: &Config) -> Result<()> {
+    let lockfile_path: PathBuf = [".", LOCKFILE_NAME].iter().collect();
+    let lockfile = read_lockfile(&lockfile_path)?
+        .ok_or_else(|| miette!("{} is missing, but --frozen was given", LOCKFILE_NAME))?;
+    let available_packages = config.resolve_packages()?;
+    let is_locked = |package_name: &PackageName| {
+        lockfile
+            .packages
+            .iter()
+            .any(|locked| &locked.name == package_name)
+    };
+    let is_out_of_date = !config.dependencies.iter().all(is_locked)
+        || lockfile
+            .packages
+            .iter()
+            .any(|locked| available_packages.get(&locked.name) != Some(&locked.source));
+    if is_out_of_date {
+        bail!("{} is out of date, but --frozen was given", LOCKFILE_NAME);
+    }
     Ok(())
 }
 
+/// Check the lockfile matches the installed packages, (re)writing it if needed.
+///
+/// If `frozen` then the lockfile must already be up to date.
+pub fn check_lockfile(config: &Config, frozen: bool) -> Result<()> {
+    let lockfile_path: PathBuf = [".", LOCKFILE_NAME].iter().collect();
+    let want_lockfile = resolve_lockfile(config)?;
+    let got_lockfile = read_lockfile(&lockfile_path)?;
+    if got_lockfile.as_ref() == Some(&want_lockfile) {
+        debug!("Lockfile is up to date");
+        return Ok(());
+    }
+    if frozen {
+        bail!(
+            "{} is {}, but --frozen was given",
+            LOCKFILE_NAME,
+            if got_lockfile.is_some() {
+                "out of date"
+            } else {
+                "missing"
+            }
+        );
+    }
+    debug!("Writing {}", lockfile_path.to_string_lossy());
+    write_lockfile(&lockfile_path, &want_lockfile)
+}
+
+/// Lock the installed packages that are (transitively) depended on.
+fn resolve_lockfile(config: &Config) -> Result<Lockfile> {
+    let packages_dir = get_or_create_packages_dir(config)?;
+    let installed_packages = get_installed_packages(&packages_dir)?;
+
+    let mut packages = Vec::new();
+    let mut visited = HashSet::new();
+    let mut stack = config.dependencies.iter().cloned().collect::<Vec<_>>();
+    while let Some(package_name) = stack.pop() {
+        if !visited.insert(package_name.clone()) {
+            continue;
+        }
+        let spec = installed_packages
+            .get(&package_name)
+            .ok_or_else(|| miette!("{:?} isn't installed", package_name.as_str()))?;
+        let mut package_path = packages_dir.clone();
+        package_path.push(package_name.as_str());
+        let config = read_package_config(&packages_dir, &package_name)?;
+        packages.push(LockedPackage {
+            hash: hash_package_contents(&package_path)?,
+            source: spec.clone(),
+            version: config.version.clone(),
+            name: package_name.clone(),
+        });
+        stack.extend(config.dependencies);
+    }
+    packages.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(Lockfile { packages })
+}
+
+/// Hash the files of an installed package, ignoring hidden files and directories
+/// (such as `.ditto`).
+///
+/// This ends up in `ditto.lock`, so it's a SHA-256 (as hex) rather than
+/// something that might change between toolchains.
+pub fn hash_package_contents(package_path: &Path) -> Result<String> {
+    let mut files = Vec::new();
+    let mut stack = vec![package_path.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        for entry in fs::read_dir(&dir).into_diagnostic()? {
+            let path = entry.into_diagnostic()?.path();
+            let is_hidden = path
+                .file_name()
+                .map_or(false, |name| name.to_string_lossy().starts_with('.'));
+            if is_hidden {
+                continue;
+            }
+            if path.is_dir() {
+                stack.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+    // Sort for determinism
+    files.sort();
+
+    let mut hasher = Sha256::new();
+    for path in files {
+        let relative = path.strip_prefix(package_path).into_diagnostic()?;
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        let contents = fs::read(&path).into_diagnostic()?;
+        // Length prefixes so that moving bytes between a path and its contents
+        // changes the hash
+        for bytes in [relative.as_bytes(), contents.as_slice()] {
+            hasher.update((bytes.len() as u64).to_le_bytes());
+            hasher.update(bytes);
+        }
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
 fn hash_packages_inputs(dependencies: &Dependencies, packages: &Packages) -> u64 {
     let mut dependencies = dependencies.iter().cloned().collect::<Vec<_>>();
 
@@ -98,91 +430,241 @@ fn hash_packages_inputs(dependencies: &Dependencies, packages: &Packages) -> u64
     hasher.finish()
 }
 
-// TODO make this async
-fn update_dependencies(
-    multi_progress: &mut MultiProgress,
+/// Install or reinstall any packages that are missing or out of date,
+/// visiting the dependency graph one level at a time.
+///
+/// Packages at the same level are installed concurrently (at most `jobs` at a
+/// time), and failures are collected so that every broken package is reported
+/// rather than just the first.
+async fn update_dependencies(
     packages_dir: &Path,
     dependencies: &Dependencies,
-    updated_dependencies: &mut Dependencies,
     installed_packages: &Packages,
     available_packages: &Packages,
+    jobs: usize,
 ) -> Result<()> {
-    for dependency in dependencies {
-        if updated_dependencies.contains(dependency) {
-            continue;
-        }
-        match (
-            installed_packages.get(dependency),
-            available_packages.get(dependency),
-        ) {
-            (Some(installed_spec), Some(available_spec)) => {
-                if *installed_spec != *available_spec {
-                    // Specs differ, update
-
-                    //let mut progress = multi_progress.add(ProgressBar::new_spinner());
-                    let mut spinner = Spinner::new_with_prefix(dependency.as_str().to_string());
-                    debug!("Removing existing install of {}", dependency.as_str());
-                    spinner.set_message("removing existing install");
-                    remove_package(packages_dir, dependency)?;
-                    install_package(spinner, packages_dir, dependency, available_spec)?;
-                }
-                updated_dependencies.insert(dependency.clone());
-                let config = read_package_config(packages_dir, dependency)?;
-                update_dependencies(
-                    multi_progress,
-                    packages_dir,
-                    &config.dependencies,
-                    updated_dependencies,
-                    installed_packages,
-                    available_packages,
-                )?
+    let mut visited = Dependencies::new();
+    let mut failures = Vec::new();
+    let mut level = dependencies.iter().cloned().collect::<Vec<_>>();
+    while !level.is_empty() {
+        let mut installs = Vec::new();
+        let mut up_to_date = Vec::new();
+        for dependency in level {
+            if !visited.insert(dependency.clone()) {
+                continue;
             }
-            (None, Some(available_spec)) => {
-                // Not installed
-
-                //let mut progress = multi_progress.add(ProgressBar::new_spinner());
-                let spinner = Spinner::new_with_prefix(dependency.as_str().to_string());
-
-                install_package(spinner, packages_dir, dependency, available_spec)?;
-                updated_dependencies.insert(dependency.clone());
-                let config = read_package_config(packages_dir, dependency)?;
-                update_dependencies(
-                    multi_progress,
-                    packages_dir,
-                    &config.dependencies,
-                    updated_dependencies,
-                    installed_packages,
-                    available_packages,
-                )?
+            match (
+                installed_packages.get(&dependency),
+                available_packages.get(&dependency),
+            ) {
+                (Some(installed_spec), Some(available_spec))
+                    if *installed_spec == *available_spec =>
+                {
+                    up_to_date.push(dependency);
+                }
+                (installed_spec, Some(available_spec)) => {
+                    installs.push(PackageInstall {
+                        reinstall: installed_spec.is_some(),
+                        spec: available_spec.clone(),
+                        name: dependency,
+                    });
+                }
+                (Some(_installed_spec), None) => {
+                    failures.push(miette!(
+                        "{:?} package installed, but no longer in the package set?",
+                        dependency.as_str()
+                    ));
+                }
+                (None, None) => {
+                    failures.push(miette!(
+                        "{:?} not available in the package set",
+                        dependency.as_str()
+                    ));
+                }
             }
-            (Some(_installed_spec), None) => {
-                return Err(miette!(
-                    "{:?} package installed, but no longer in the package set?",
-                    dependency
-                ));
+        }
+
+        let names = installs
+            .iter()
+            .map(|install| install.name.clone())
+            .collect::<Vec<_>>();
+        let results = install_packages(packages_dir, installs, jobs).await;
+        for (name, result) in names.into_iter().zip(results) {
+            match result {
+                Ok(()) => up_to_date.push(name),
+                Err(err) => {
+                    failures.push(err.wrap_err(format!("error installing {:?}", name.as_str())))
+                }
             }
-            (None, None) => {
-                return Err(miette!("{:?} not available in the package set", dependency));
+        }
+
+        let mut next_level = Vec::new();
+        for name in up_to_date {
+            match read_package_config(packages_dir, &name) {
+                Ok(config) => next_level.extend(config.dependencies),
+                Err(err) => failures.push(err.wrap_err(format!(
+                    "error reading config for package {:?}",
+                    name.as_str()
+                ))),
             }
         }
+        level = next_level;
     }
-    Ok(())
+
+    match failures.len() {
+        0 => Ok(()),
+        1 => Err(failures.pop().unwrap()),
+        n => Err(miette!(
+            "{} packages failed to install:\n{}",
+            n,
+            failures
+                .iter()
+                .map(|failure| format!(
+                    "  - {}",
+                    failure
+                        .chain()
+                        .map(|err| err.to_string())
+                        .collect::<Vec<_>>()
+                        .join(": ")
+                ))
+                .collect::<Vec<_>>()
+                .join("\n")
+        )),
+    }
+}
+
+struct PackageInstall {
+    name: PackageName,
+    spec: PackageSpec,
+    /// Whether an existing (outdated) install needs removing first.
+    reinstall: bool,
+}
+
+/// Install packages concurrently, returning the results in the same order.
+async fn install_packages(
+    packages_dir: &Path,
+    installs: Vec<PackageInstall>,
+    jobs: usize,
+) -> Vec<Result<()>> {
+    if installs.is_empty() {
+        return Vec::new();
+    }
+    let multi_progress = MultiProgress::new();
+    let installs = installs
+        .into_iter()
+        .map(|install| {
+            let spinner =
+                Spinner::new_with_prefix_in(&multi_progress, install.name.as_str().to_string());
+            (install, spinner)
+        })
+        .collect::<Vec<_>>();
+
+    // The progress bars are only drawn while this is running
+    let progress = tokio::task::spawn_blocking(move || multi_progress.join());
+
+    let packages_dir = packages_dir.to_path_buf();
+    let results = run_bounded(installs, jobs, move |(install, mut spinner)| {
+        let result = install_package(&mut spinner, &packages_dir, &install);
+        match result {
+            Ok(()) => spinner.success("installed"),
+            Err(ref err) => spinner.fail(err.to_string()),
+        }
+        result
+    })
+    .await;
+
+    if let Err(err) = progress
+        .await
+        .into_diagnostic()
+        .and_then(|result| result.into_diagnostic())
+    {
+        warn!("error drawing progress: {}", err);
+    }
+    results
+}
+
+/// Call `f` on each item on the blocking thread pool, at most `jobs` at a
+/// time, returning the results in the order given.
+async fn run_bounded<T, F>(items: Vec<T>, jobs: usize, f: F) -> Vec<Result<()>>
+where
+    T: Send + 'static,
+    F: Fn(T) -> Result<()> + Send + Sync + 'static,
+{
+    let semaphore = Arc::new(Semaphore::new(jobs.max(1)));
+    let f = Arc::new(f);
+    let handles = items
+        .into_iter()
+        .map(|item| {
+            let semaphore = semaphore.clone();
+            let f = f.clone();
+            tokio::spawn(async move {
+                let _permit = semaphore.acquire_owned().await.into_diagnostic()?;
+                tokio::task::spawn_blocking(move || f(item))
+                    .await
+                    .into_diagnostic()?
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut results = Vec::with_capacity(handles.len());
+    for handle in handles {
+        results.push(handle.await.into_diagnostic().and_then(|result| result));
+    }
+    results
 }
 
 const EXTENSION_SPEC: &str = "spec";
 
+/// Extension for entries that are still being written.
+///
+/// These are renamed into place once complete, so an interrupted install
+/// never looks installed (and gets tidied up by [get_installed_packages]).
+const EXTENSION_PARTIAL: &str = "partial";
+
 fn install_package(
-    mut spinner: Spinner,
+    spinner: &mut Spinner,
     packages_dir: &Path,
-    package_name: &str,
-    spec: &PackageSpec,
+    install: &PackageInstall,
 ) -> Result<()> {
-    debug!("Installing {:?}", package_name);
+    let PackageInstall {
+        name: package_name,
+        spec,
+        reinstall,
+    } = install;
+    if *reinstall {
+        debug!("Removing existing install of {}", package_name.as_str());
+        spinner.set_message("removing existing install");
+        remove_package(packages_dir, package_name)?;
+    }
+    debug!("Installing {:?}", package_name.as_str());
+    let mut partial_path = packages_dir.to_path_buf();
+    partial_path.push(package_name.as_str());
+    partial_path.set_extension(EXTENSION_PARTIAL);
     match spec {
-        PackageSpec::Path { path: src } => {
+        PackageSpec::Path { path: src, version } => {
+            if !src.is_dir() {
+                bail!("{} isn't a directory", src.to_string_lossy());
+            }
+            if let Some(requirement) = version {
+                let package_config = read_config(src.join(CONFIG_FILE_NAME))?;
+                match package_config.version {
+                    Some(version) if requirement.matches(&version) => {}
+                    Some(version) => bail!(
+                        "{} {} doesn't match the requirement {}",
+                        package_name.as_str(),
+                        version,
+                        requirement
+                    ),
+                    None => bail!(
+                        "{} doesn't have a version, but {} was required",
+                        package_name.as_str(),
+                        requirement
+                    ),
+                }
+            }
             let mut dst = packages_dir.to_path_buf();
             let src = pathdiff::diff_paths(src, packages_dir).unwrap();
-            dst.push(package_name);
+            dst.push(package_name.as_str());
 
             debug!(
                 "linking {} -> {}",
@@ -194,23 +676,24 @@ fn install_package(
                 dst.to_string_lossy(),
                 src.to_string_lossy(),
             ));
-            symlink::symlink_dir(src, dst).into_diagnostic()?;
-
-            let mut spec_path = packages_dir.to_path_buf();
-            spec_path.push(package_name);
-            spec_path.set_extension(EXTENSION_SPEC);
-            let spec_file = fs::File::create(&spec_path).into_diagnostic()?;
-            serde_json::to_writer(spec_file, spec).into_diagnostic()?;
-
-            debug!(
-                "{:?} spec written to {}",
-                package_name,
-                spec_path.to_string_lossy()
-            );
-
-            spinner.success("installed")
+            symlink::symlink_dir(src, &partial_path).into_diagnostic()?;
+            fs::rename(&partial_path, &dst).into_diagnostic()?;
         }
     }
+
+    // The spec is written last, as it marks the package as installed
+    let mut spec_path = packages_dir.to_path_buf();
+    spec_path.push(package_name.as_str());
+    spec_path.set_extension(EXTENSION_SPEC);
+    let spec_file = fs::File::create(&partial_path).into_diagnostic()?;
+    serde_json::to_writer(spec_file, spec).into_diagnostic()?;
+    fs::rename(&partial_path, &spec_path).into_diagnostic()?;
+
+    debug!(
+        "{:?} spec written to {}",
+        package_name.as_str(),
+        spec_path.to_string_lossy()
+    );
     Ok(())
 }
 
@@ -218,7 +701,7 @@ fn remove_package(packages_dir: &Path, package_name: &str) -> Result<()> {
     debug!("Removing package {:?}", package_name);
     for result in fs::read_dir(packages_dir).into_diagnostic()? {
         let entry = result.into_diagnostic()?;
-        if entry.path().starts_with(package_name) {
+        if entry.path().file_stem() == Some(OsStr::new(package_name)) {
             remove_dir_entry(entry)?;
         }
     }
@@ -351,3 +834,53 @@ pub fn mk_packages_dir(config: &Config) -> PathBuf {
     path.push("packages");
     path
 }
+
+#[cfg(test)]
+mod tests {
+    use super::run_bounded;
+    use miette::miette;
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        thread,
+        time::Duration,
+    };
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn it_bounds_concurrency_and_keeps_every_failure() {
+        let running = Arc::new(AtomicUsize::new(0));
+        let max_running = Arc::new(AtomicUsize::new(0));
+        let results = run_bounded((0..8).collect(), 3, {
+            let running = running.clone();
+            let max_running = max_running.clone();
+            move |i: usize| {
+                let now_running = running.fetch_add(1, Ordering::SeqCst) + 1;
+                max_running.fetch_max(now_running, Ordering::SeqCst);
+                // Pretend to fetch something
+                thread::sleep(Duration::from_millis(50));
+                running.fetch_sub(1, Ordering::SeqCst);
+                if i == 5 {
+                    Err(miette!("package {} is broken", i))
+                } else {
+                    Ok(())
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(results.len(), 8);
+        for (i, result) in results.iter().enumerate() {
+            if i == 5 {
+                let err = result.as_ref().unwrap_err();
+                assert_eq!(err.to_string(), "package 5 is broken");
+            } else {
+                assert!(result.is_ok(), "{:?}", result);
+            }
+        }
+        let max_running = max_running.load(Ordering::SeqCst);
+        assert!(max_running <= 3, "{} jobs ran at once", max_running);
+        assert!(max_running > 1, "jobs didn't run concurrently");
+    }
+}
@@ -0,0 +1,222 @@
+use ditto_config::{Config, CONFIG_FILE_NAME};
+use miette::{Diagnostic, IntoDiagnostic, Result};
+use std::path::{Component, Path, PathBuf};
+use thiserror::Error;
+
+/// Check that the directories a build reads from and writes to don't get
+/// tangled up with each other.
+///
+/// Otherwise builds can end up consuming their own outputs, and watchers can
+/// get stuck in a loop rebuilding for changes they caused.
+pub fn check_project_paths(config_dir: &Path, config: &Config) -> Result<()> {
+    let config_dir = resolve_path(&std::env::current_dir().into_diagnostic()?, config_dir);
+    let dirs = project_dirs(&config_dir, config);
+    if let Some((a, b)) = find_overlap(&dirs) {
+        return Err(OverlappingDirs {
+            a_name: a.0,
+            a_path: a.1.clone(),
+            b_name: b.0,
+            b_path: b.1.clone(),
+        }
+        .into());
+    }
+    if let Some(project_dir) = find_enclosing_project(&config_dir, &config.ditto_dir) {
+        return Err(NestedProject {
+            config_dir,
+            ditto_dir: project_dir.join(&config.ditto_dir),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Directories that are written to during a build, which shouldn't trigger a rebuild.
+pub fn output_dirs(config_dir: &Path, config: &Config) -> Vec<PathBuf> {
+    project_dirs(config_dir, config)
+        .into_iter()
+        .filter_map(|(name, path)| (name != SRC_DIR).then(|| path))
+        .collect()
+}
+
+/// Is the (resolved) `path` inside one of the `dirs`?
+pub fn is_within_any(path: &Path, dirs: &[PathBuf]) -> bool {
+    dirs.iter().any(|dir| path.starts_with(dir))
+}
+
+static SRC_DIR: &str = "source";
+
+fn project_dirs(config_dir: &Path, config: &Config) -> Vec<(&'static str, PathBuf)> {
+    vec![
+        (SRC_DIR, resolve_path(config_dir, &config.src_dir)),
+        ("ditto", resolve_path(config_dir, &config.ditto_dir)),
+        (
+            "output",
+            resolve_path(config_dir, &config.codegen_js_config.dist_dir),
+        ),
+        (
+            "packages",
+            resolve_path(config_dir, &config.codegen_js_config.packages_dir),
+        ),
+    ]
+}
+
+/// Find the first pair of (resolved) directories where one contains the other.
+fn find_overlap<'a>(
+    dirs: &'a [(&'static str, PathBuf)],
+) -> Option<(&'a (&'static str, PathBuf), &'a (&'static str, PathBuf))> {
+    for (i, a) in dirs.iter().enumerate() {
+        for b in dirs.iter().skip(i + 1) {
+            // NOTE `Path::starts_with` compares whole components,
+            // so `src` doesn't contain `src2`
+            if a.1.starts_with(&b.1) || b.1.starts_with(&a.1) {
+                return Some((a, b));
+            }
+        }
+    }
+    None
+}
+
+/// Find a project whose ditto directory contains `config_dir`, returning the project directory.
+///
+/// NOTE the ditto directory isn't configurable, so it's assumed other projects use the
+/// same one.
+fn find_enclosing_project(config_dir: &Path, ditto_dir: &Path) -> Option<PathBuf> {
+    config_dir.ancestors().skip(1).find_map(|ancestor| {
+        let is_enclosing = ancestor.join(CONFIG_FILE_NAME).is_file()
+            && config_dir.starts_with(resolve_path(ancestor, ditto_dir));
+        is_enclosing.then(|| ancestor.to_path_buf())
+    })
+}
+
+/// Make `path` absolute (relative to `base`), resolving symlinks and `..`.
+///
+/// Unlike [std::fs::canonicalize], this works for paths that don't exist (yet),
+/// by canonicalizing the longest prefix that does and normalizing the rest.
+pub fn resolve_path(base: &Path, path: &Path) -> PathBuf {
+    let joined = base.join(path);
+    let mut existing = joined.as_path();
+    let mut rest = Vec::new();
+    let mut resolved = loop {
+        if let Ok(canonical) = existing.canonicalize() {
+            break canonical;
+        }
+        match (existing.parent(), existing.components().next_back()) {
+            (Some(parent), Some(component)) => {
+                rest.push(component);
+                existing = parent;
+            }
+            _ => break PathBuf::new(),
+        }
+    };
+    for component in rest.into_iter().rev() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            component => resolved.push(component),
+        }
+    }
+    resolved
+}
+
+#[derive(Error, Debug, Diagnostic)]
+#[error("{a_name} directory {a_path:?} overlaps with {b_name} directory {b_path:?}")]
+#[diagnostic(help(
+    "builds would read their own outputs, make sure these directories are separate"
+))]
+struct OverlappingDirs {
+    a_name: &'static str,
+    a_path: PathBuf,
+    b_name: &'static str,
+    b_path: PathBuf,
+}
+
+#[derive(Error, Debug, Diagnostic)]
+#[error("project at {config_dir:?} is inside the ditto directory {ditto_dir:?}")]
+#[diagnostic(help("run ditto from the root of the project that owns {ditto_dir:?} instead"))]
+struct NestedProject {
+    config_dir: PathBuf,
+    ditto_dir: PathBuf,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_enclosing_project, find_overlap, resolve_path};
+    use ditto_config::CONFIG_FILE_NAME;
+    use std::{fs, path::Path};
+
+    #[test]
+    fn it_resolves_paths_that_dont_exist() {
+        let tmp = tempfile::tempdir().unwrap();
+        let base = tmp.path().canonicalize().unwrap();
+        fs::create_dir(base.join("src")).unwrap();
+        assert_eq!(
+            resolve_path(&base, Path::new("./src/../.ditto/build")),
+            base.join(".ditto").join("build")
+        );
+        assert_eq!(resolve_path(&base.join("src"), Path::new("..")), base);
+    }
+
+    #[test]
+    fn it_finds_overlapping_dirs() {
+        let tmp = tempfile::tempdir().unwrap();
+        let base = tmp.path().canonicalize().unwrap();
+        let resolve = |name, path| (name, resolve_path(&base, Path::new(path)));
+
+        let dirs = vec![resolve("source", "src"), resolve("ditto", ".ditto")];
+        assert!(find_overlap(&dirs).is_none());
+
+        // Sharing a prefix isn't overlapping
+        let dirs = vec![resolve("source", "src"), resolve("output", "src2")];
+        assert!(find_overlap(&dirs).is_none());
+
+        let dirs = vec![
+            resolve("source", "src"),
+            resolve("ditto", "src/../src/.ditto"),
+        ];
+        let (a, b) = find_overlap(&dirs).unwrap();
+        assert_eq!((a.0, b.0), ("source", "ditto"));
+
+        let dirs = vec![resolve("source", "."), resolve("output", "dist")];
+        assert!(find_overlap(&dirs).is_some());
+    }
+
+    #[test]
+    fn it_finds_dirs_overlapping_through_symlinks() {
+        let tmp = tempfile::tempdir().unwrap();
+        let base = tmp.path().canonicalize().unwrap();
+        fs::create_dir(base.join(".ditto")).unwrap();
+        symlink::symlink_dir(base.join(".ditto"), base.join("src")).unwrap();
+        let dirs = vec![
+            ("source", resolve_path(&base, Path::new("src"))),
+            ("ditto", resolve_path(&base, Path::new(".ditto"))),
+        ];
+        assert!(find_overlap(&dirs).is_some());
+    }
+
+    #[test]
+    fn it_finds_enclosing_projects() {
+        let tmp = tempfile::tempdir().unwrap();
+        let base = tmp.path().canonicalize().unwrap();
+        let nested = base.join(".ditto").join("packages").join("foo");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(base.join(CONFIG_FILE_NAME), "").unwrap();
+        fs::write(nested.join(CONFIG_FILE_NAME), "").unwrap();
+
+        assert_eq!(
+            find_enclosing_project(&nested, Path::new(".ditto")),
+            Some(base.clone())
+        );
+        assert_eq!(find_enclosing_project(&base, Path::new(".ditto")), None);
+
+        // A project symlinked into packages is resolved to where it really lives
+        let local = base.join("local");
+        fs::create_dir(&local).unwrap();
+        fs::write(local.join(CONFIG_FILE_NAME), "").unwrap();
+        let link = base.join(".ditto").join("packages").join("local");
+        symlink::symlink_dir(&local, &link).unwrap();
+        let resolved = resolve_path(&base, &link);
+        assert_eq!(find_enclosing_project(&resolved, Path::new(".ditto")), None);
+    }
+}
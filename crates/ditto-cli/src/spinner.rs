@@ -1,5 +1,5 @@
 use crate::common;
-use indicatif::{ProgressBar, ProgressStyle};
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use std::borrow::Cow;
 
 pub struct Spinner {
@@ -9,21 +9,29 @@ pub struct Spinner {
 
 impl Spinner {
     pub fn new() -> Self {
-        Self::new_impl(None)
+        Self::new_impl(None, None)
     }
 
     pub fn new_with_prefix(prefix: String) -> Self {
-        Self::new_impl(Some(prefix))
+        Self::new_impl(Some(prefix), None)
     }
 
-    fn new_impl(prefix: Option<String>) -> Self {
+    /// Like [Spinner::new_with_prefix], but drawn as one line of a [MultiProgress].
+    pub fn new_with_prefix_in(multi_progress: &MultiProgress, prefix: String) -> Self {
+        Self::new_impl(Some(prefix), Some(multi_progress))
+    }
+
+    fn new_impl(prefix: Option<String>, multi_progress: Option<&MultiProgress>) -> Self {
         if common::is_plain() {
             return Self {
                 progress: None,
                 prefix,
             };
         }
-        let progress = ProgressBar::new_spinner();
+        let mut progress = ProgressBar::new_spinner();
+        if let Some(multi_progress) = multi_progress {
+            progress = multi_progress.add(progress);
+        }
         if let Some(ref prefix) = prefix {
             progress.set_style(
                 ProgressStyle::default_spinner()
@@ -97,7 +105,7 @@ impl Spinner {
         }
     }
 
-    pub fn _fail(self, message: impl Into<Cow<'static, str>>) {
+    pub fn fail(self, message: impl Into<Cow<'static, str>>) {
         if let Some(progress) = self.progress {
             if let Some(prefix) = self.prefix {
                 progress
@@ -0,0 +1,397 @@
+use crate::{make::BUILD_MANIFEST_FILE, pkg, version::Version};
+use clap::{Arg, ArgMatches, Command};
+use console::Style;
+use ditto_config::{read_config, Config, ProfileName, CONFIG_FILE_NAME};
+use ditto_make::BuildManifest;
+use log::debug;
+use miette::{bail, IntoDiagnostic, Result, WrapErr};
+use serde::Serialize;
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeMap},
+    env::current_exe,
+    fs,
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+    process,
+};
+
+/// Exit code used when the build output doesn't match.
+static EXIT_DIVERGED: i32 = 1;
+
+/// Exit code used when verification was skipped.
+static EXIT_SKIPPED: i32 = 2;
+
+pub fn command<'a>(name: &str) -> Command<'a> {
+    Command::new(name)
+        .about("Rebuild the project from scratch and check the output is identical")
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .help("Print the verification report as JSON"),
+        )
+}
+
+pub async fn run(matches: &ArgMatches, ditto_version: &Version) -> Result<()> {
+    let is_json = matches.is_present("json");
+    let report = verify(ditto_version).await?;
+    if is_json {
+        println!("{}", serde_json::to_string(&report).into_diagnostic()?);
+    } else {
+        report.print();
+    }
+    process::exit(report.exit_code());
+}
+
+#[derive(Serialize)]
+#[serde(tag = "status", rename_all = "kebab-case")]
+enum Report {
+    Reproducible {
+        files: usize,
+    },
+    Diverged {
+        files: usize,
+        differences: Vec<Difference>,
+    },
+    Skipped {
+        reason: String,
+    },
+}
+
+#[derive(Serialize)]
+struct Difference {
+    path: PathBuf,
+    #[serde(flatten)]
+    kind: DifferenceKind,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+enum DifferenceKind {
+    /// The file exists in the existing output but wasn't rebuilt.
+    Missing,
+    /// The file was rebuilt but isn't in the existing output.
+    Unexpected,
+    /// The file contents differ.
+    Changed {
+        /// Offset of the first differing byte.
+        offset: usize,
+        /// Bytes of the existing file, starting from the line containing `offset`.
+        existing: Vec<u8>,
+        /// Bytes of the rebuilt file, starting from the line containing `offset`.
+        rebuilt: Vec<u8>,
+    },
+}
+
+impl Report {
+    fn exit_code(&self) -> i32 {
+        match self {
+            Self::Reproducible { .. } => 0,
+            Self::Diverged { .. } => EXIT_DIVERGED,
+            Self::Skipped { .. } => EXIT_SKIPPED,
+        }
+    }
+
+    fn print(&self) {
+        match self {
+            Self::Reproducible { files } => {
+                println!(
+                    "{} ({} files checked)",
+                    Style::new()
+                        .green()
+                        .bold()
+                        .apply_to("Build is reproducible"),
+                    files
+                );
+            }
+            Self::Skipped { reason } => {
+                println!(
+                    "{}: {}",
+                    Style::new()
+                        .yellow()
+                        .bold()
+                        .apply_to("Skipping verification"),
+                    reason
+                );
+            }
+            Self::Diverged { files, differences } => {
+                println!(
+                    "{} ({} of {} files differ)",
+                    Style::new()
+                        .red()
+                        .bold()
+                        .apply_to("Build is not reproducible"),
+                    differences.len(),
+                    files
+                );
+                for Difference { path, kind } in differences {
+                    let path = path.to_string_lossy();
+                    match kind {
+                        DifferenceKind::Missing => {
+                            println!("\n{}: not produced by the rebuild", path);
+                        }
+                        DifferenceKind::Unexpected => {
+                            println!("\n{}: only produced by the rebuild", path);
+                        }
+                        DifferenceKind::Changed {
+                            offset,
+                            existing,
+                            rebuilt,
+                        } => {
+                            println!("\n{}: first difference at byte {}", path, offset);
+                            let line_offset = offset - offset % HEXDUMP_WIDTH;
+                            println!("  existing: {}", hexdump_line(line_offset, existing));
+                            println!("  rebuilt:  {}", hexdump_line(line_offset, rebuilt));
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+async fn verify(ditto_version: &Version) -> Result<Report> {
+    let config_path: PathBuf = [".", CONFIG_FILE_NAME].iter().collect();
+    let config = read_config(&config_path)?;
+
+    let mut build_manifest_path = config.ditto_dir.to_path_buf();
+    build_manifest_path.push(BUILD_MANIFEST_FILE);
+    let build_manifest = if let Some(build_manifest) = BuildManifest::read(&build_manifest_path)? {
+        build_manifest
+    } else {
+        return Ok(Report::Skipped {
+            reason: String::from("no existing build output, run `ditto make` first"),
+        });
+    };
+    if let Some(reason) =
+        build_manifest.check_provenance(&ditto_version.semversion, &config_path)?
+    {
+        return Ok(Report::Skipped {
+            reason: format!("existing build output is stale ({})", reason),
+        });
+    }
+
+    if !config.targets_js() {
+        return Ok(Report::Skipped {
+            reason: String::from("no code generation targets configured"),
+        });
+    }
+
+    let tempdir = tempfile::tempdir().into_diagnostic()?;
+    prepare_rebuild_dir(&config, &config_path, tempdir.path())?;
+    // Rebuild with the same profile, as profiles generate different code
+    rebuild(tempdir.path(), build_manifest.profile())?;
+
+    let mut existing_trees = Vec::new();
+    let mut rebuilt_trees = Vec::new();
+    for dir in [
+        &config.codegen_js_config.dist_dir,
+        &config.codegen_js_config.packages_dir,
+    ] {
+        existing_trees.push((dir.to_path_buf(), dir.to_path_buf()));
+        rebuilt_trees.push((dir.to_path_buf(), tempdir.path().join(dir)));
+    }
+
+    // Hash both trees in parallel
+    let (existing, rebuilt) = tokio::join!(
+        tokio::task::spawn_blocking(move || hash_trees(&existing_trees)),
+        tokio::task::spawn_blocking(move || hash_trees(&rebuilt_trees)),
+    );
+    let existing = existing.into_diagnostic()??;
+    let rebuilt = rebuilt.into_diagnostic()??;
+
+    let files = existing.len().max(rebuilt.len());
+    let mut differences = Vec::new();
+    for (path, existing_hash) in existing.iter() {
+        match rebuilt.get(path) {
+            None => differences.push(Difference {
+                path: path.clone(),
+                kind: DifferenceKind::Missing,
+            }),
+            Some(rebuilt_hash) if rebuilt_hash != existing_hash => {
+                let existing_bytes = fs::read(path).into_diagnostic()?;
+                let rebuilt_bytes = fs::read(tempdir.path().join(path)).into_diagnostic()?;
+                differences.push(Difference {
+                    path: path.clone(),
+                    kind: first_difference(&existing_bytes, &rebuilt_bytes),
+                });
+            }
+            Some(_) => {}
+        }
+    }
+    for path in rebuilt.keys() {
+        if !existing.contains_key(path) {
+            differences.push(Difference {
+                path: path.clone(),
+                kind: DifferenceKind::Unexpected,
+            });
+        }
+    }
+    differences.sort_by(|a, b| a.path.cmp(&b.path));
+
+    if differences.is_empty() {
+        Ok(Report::Reproducible { files })
+    } else {
+        Ok(Report::Diverged { files, differences })
+    }
+}
+
+/// Set up a copy of the current project in `rebuild_dir`, sharing sources
+/// and installed packages with the original.
+fn prepare_rebuild_dir(config: &Config, config_path: &Path, rebuild_dir: &Path) -> Result<()> {
+    fs::copy(config_path, rebuild_dir.join(CONFIG_FILE_NAME))
+        .into_diagnostic()
+        .wrap_err("error copying config")?;
+
+    let src_dir = fs::canonicalize(&config.src_dir)
+        .into_diagnostic()
+        .wrap_err(format!(
+            "error resolving {}",
+            config.src_dir.to_string_lossy()
+        ))?;
+    symlink::symlink_dir(src_dir, rebuild_dir.join(&config.src_dir)).into_diagnostic()?;
+
+    let rebuild_ditto_dir = rebuild_dir.join(&config.ditto_dir);
+    fs::create_dir_all(&rebuild_ditto_dir).into_diagnostic()?;
+
+    let packages_dir = pkg::mk_packages_dir(config);
+    if packages_dir.exists() {
+        let packages_dir = fs::canonicalize(packages_dir).into_diagnostic()?;
+        let mut rebuild_packages_dir = rebuild_ditto_dir;
+        rebuild_packages_dir.push("packages");
+        symlink::symlink_dir(packages_dir, rebuild_packages_dir).into_diagnostic()?;
+    }
+    Ok(())
+}
+
+fn rebuild(rebuild_dir: &Path, profile: ProfileName) -> Result<()> {
+    let ditto_bin = current_exe()
+        .into_diagnostic()
+        .wrap_err("error getting current executable")?;
+    debug!("Rebuilding in {}", rebuild_dir.to_string_lossy());
+    let output = process::Command::new(ditto_bin)
+        .arg("make")
+        .arg("--profile")
+        .arg(profile.as_str())
+        .current_dir(rebuild_dir)
+        .env("DITTO_PLAIN", "true")
+        .output()
+        .into_diagnostic()
+        .wrap_err("error running ditto make")?;
+    if !output.status.success() {
+        bail!(
+            "rebuild failed:\n{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    Ok(())
+}
+
+/// Hash every file under the given `(prefix, root)` directories, keyed by
+/// `prefix` joined with the path relative to `root`.
+fn hash_trees(roots: &[(PathBuf, PathBuf)]) -> Result<BTreeMap<PathBuf, u64>> {
+    let mut hashes = BTreeMap::new();
+    for (prefix, root) in roots {
+        let mut stack = vec![root.clone()];
+        while let Some(dir) = stack.pop() {
+            if !dir.exists() {
+                continue;
+            }
+            for entry in fs::read_dir(&dir).into_diagnostic()? {
+                let path = entry.into_diagnostic()?.path();
+                if path.is_dir() {
+                    stack.push(path);
+                    continue;
+                }
+                let contents = fs::read(&path).into_diagnostic()?;
+                let mut hasher = DefaultHasher::new();
+                contents.hash(&mut hasher);
+                let relative = path.strip_prefix(root).into_diagnostic()?;
+                hashes.insert(prefix.join(relative), hasher.finish());
+            }
+        }
+    }
+    Ok(hashes)
+}
+
+static HEXDUMP_WIDTH: usize = 16;
+
+fn first_difference(existing: &[u8], rebuilt: &[u8]) -> DifferenceKind {
+    let offset = existing
+        .iter()
+        .zip(rebuilt.iter())
+        .position(|(a, b)| a != b)
+        .unwrap_or_else(|| existing.len().min(rebuilt.len()));
+    let line_offset = offset - offset % HEXDUMP_WIDTH;
+    let line = |bytes: &[u8]| {
+        bytes
+            .iter()
+            .skip(line_offset)
+            .take(HEXDUMP_WIDTH)
+            .copied()
+            .collect::<Vec<_>>()
+    };
+    DifferenceKind::Changed {
+        offset,
+        existing: line(existing),
+        rebuilt: line(rebuilt),
+    }
+}
+
+/// Render a single `hexdump -C` style line.
+fn hexdump_line(offset: usize, bytes: &[u8]) -> String {
+    let hex = (0..HEXDUMP_WIDTH)
+        .map(|i| {
+            bytes
+                .get(i)
+                .map_or_else(|| String::from("  "), |byte| format!("{:02x}", byte))
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    let ascii = bytes
+        .iter()
+        .map(|byte| {
+            if byte.is_ascii_graphic() || *byte == b' ' {
+                *byte as char
+            } else {
+                '.'
+            }
+        })
+        .collect::<String>();
+    format!("{:08x}  {}  |{}|", offset, hex, ascii)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{first_difference, hexdump_line, DifferenceKind};
+
+    #[test]
+    fn it_finds_the_first_difference() {
+        let existing = b"export { a, b, c };\n";
+        let rebuilt = b"export { a, c, b };\n";
+        match first_difference(existing, rebuilt) {
+            DifferenceKind::Changed {
+                offset,
+                existing,
+                rebuilt,
+            } => {
+                assert_eq!(offset, 12);
+                assert_eq!(existing, b"export { a, b, c".to_vec());
+                assert_eq!(rebuilt, b"export { a, c, b".to_vec());
+            }
+            _ => panic!("expected a change"),
+        }
+        match first_difference(b"abc", b"abcd") {
+            DifferenceKind::Changed { offset, .. } => assert_eq!(offset, 3),
+            _ => panic!("expected a change"),
+        }
+    }
+
+    #[test]
+    fn it_renders_hexdump_lines() {
+        assert_eq!(
+            hexdump_line(16, b"ab\n"),
+            "00000010  61 62 0a                                         |ab.|"
+        );
+    }
+}
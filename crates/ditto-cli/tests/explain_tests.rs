@@ -0,0 +1,58 @@
+use std::{
+    fs,
+    io::Result,
+    process::{Command, Output},
+};
+
+#[test]
+fn it_explains_types() -> Result<()> {
+    let project_dir = tempfile::tempdir()?;
+    fs::write(
+        project_dir.path().join("ditto.toml"),
+        "name = \"explain-project\"\n",
+    )?;
+    fs::create_dir(project_dir.path().join("src"))?;
+    fs::write(
+        project_dir.path().join("src").join("Util.ditto"),
+        "module Util exports (..);\n\nid = (a) -> a;\n",
+    )?;
+    fs::write(
+        project_dir.path().join("src").join("Main.ditto"),
+        "module Main exports (..);\n\nimport Util (id);\n\nfive = id(5);\n\nalways = (a, b) -> id(a);\n",
+    )?;
+
+    let ditto_bin = env!("CARGO_BIN_EXE_ditto");
+    let explain = |module: &str, name: &str| -> Result<Output> {
+        Command::new(ditto_bin)
+            .args(&["explain-type", module, name])
+            .current_dir(project_dir.path())
+            .env("DITTO_PLAIN", "true")
+            .output()
+    };
+
+    let output = explain("Util", "id")?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "explain-type failed: {:?}", output);
+    assert_eq!(stdout.trim(), "id : forall a. (a) -> a");
+
+    // Imports are checked too
+    let output = explain("Main", "five")?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "explain-type failed: {:?}", output);
+    assert_eq!(stdout.trim(), "five : Int");
+
+    let output = explain("Main", "always")?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "explain-type failed: {:?}", output);
+    assert_eq!(stdout.trim(), "always : forall a b. (a, b) -> a");
+
+    let output = explain("Util", "idd")?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(
+        stderr.contains("Util doesn't define a value named `idd`, did you mean: `id`?"),
+        "{}",
+        stderr
+    );
+    Ok(())
+}
@@ -1,7 +1,10 @@
 use std::{
     fs,
-    io::Result,
+    io::{Error, ErrorKind, Result},
+    path::Path,
     process::{Command, Stdio},
+    thread,
+    time::{Duration, Instant},
 };
 
 #[test]
@@ -32,3 +35,603 @@ fn it_makes_javascript_project() -> Result<()> {
     );
     Ok(())
 }
+
+#[test]
+fn it_prints_json_diagnostics_with_frames() -> Result<()> {
+    // Clean
+    let _whatever = fs::remove_dir_all("fixtures/warnings-project/.ditto");
+
+    let ditto_bin = env!("CARGO_BIN_EXE_ditto");
+
+    let output = Command::new(ditto_bin)
+        .args(&["make", "--json-frames"])
+        .current_dir("fixtures/warnings-project")
+        .env("DITTO_PLAIN", "true")
+        .output()?;
+    assert_eq!(output.status.code(), Some(0), "ditto make failed");
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let frames = stderr
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter_map(|diagnostic| diagnostic["frame"].as_str().map(String::from))
+        .collect::<Vec<_>>();
+    assert!(
+        frames
+            .iter()
+            .any(|frame| frame.contains("main = (unused_argument) -> 5;")),
+        "expected a code frame in: {}",
+        stderr
+    );
+    Ok(())
+}
+
+#[test]
+fn it_verifies_reproducible_builds() -> Result<()> {
+    let ditto_bin = env!("CARGO_BIN_EXE_ditto");
+
+    let exit = Command::new(ditto_bin)
+        .arg("make")
+        .current_dir("fixtures/javascript-project")
+        .env("DITTO_PLAIN", "true")
+        .stdout(Stdio::inherit())
+        .status()?;
+    assert_eq!(exit.code(), Some(0), "ditto make failed");
+
+    let output = Command::new(ditto_bin)
+        .args(&["verify-build", "--json"])
+        .current_dir("fixtures/javascript-project")
+        .env("DITTO_PLAIN", "true")
+        .output()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "verify-build failed: {}",
+        stdout
+    );
+
+    let report = serde_json::from_str::<serde_json::Value>(stdout.trim()).unwrap();
+    assert_eq!(report["status"], "reproducible");
+    assert!(report["files"].as_u64().unwrap() > 0);
+    Ok(())
+}
+
+#[test]
+fn it_denies_and_allows_warnings_by_code() -> Result<()> {
+    // Clean
+    let _whatever = fs::remove_dir_all("fixtures/warnings-project/.ditto");
+
+    let ditto_bin = env!("CARGO_BIN_EXE_ditto");
+
+    let output = Command::new(ditto_bin)
+        .args(&[
+            "make",
+            "--deny",
+            "unused-value-declaration",
+            "--allow",
+            "unused-function-binder",
+        ])
+        .current_dir("fixtures/warnings-project")
+        .env("DITTO_PLAIN", "true")
+        .output()?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(1), "ditto make succeeded");
+    assert!(
+        stdout.contains("unused_value = 5;") || stderr.contains("unused_value = 5;"),
+        "expected the denied warning to be reported: {}{}",
+        stdout,
+        stderr
+    );
+    assert!(
+        !stdout.contains("unused_argument") && !stderr.contains("unused_argument"),
+        "expected the allowed warning to be suppressed: {}{}",
+        stdout,
+        stderr
+    );
+
+    // Allowing both means the build succeeds silently
+    let output = Command::new(ditto_bin)
+        .args(&[
+            "make",
+            "--allow",
+            "unused-value-declaration",
+            "--allow",
+            "unused-function-binder",
+        ])
+        .current_dir("fixtures/warnings-project")
+        .env("DITTO_PLAIN", "true")
+        .output()?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "ditto make failed: {}",
+        stderr
+    );
+    assert!(
+        !stderr.contains("unused"),
+        "unexpected warnings: {}",
+        stderr
+    );
+    Ok(())
+}
+
+#[test]
+fn it_errors_for_unknown_warning_codes() -> Result<()> {
+    let ditto_bin = env!("CARGO_BIN_EXE_ditto");
+
+    let output = Command::new(ditto_bin)
+        .args(&["make", "--deny", "not-a-real-code"])
+        .current_dir("fixtures/warnings-project")
+        .env("DITTO_PLAIN", "true")
+        .output()?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_ne!(output.status.code(), Some(0), "ditto make succeeded");
+    assert!(
+        stderr.contains("unknown warning code `not-a-real-code`"),
+        "expected an unknown code error: {}",
+        stderr
+    );
+    Ok(())
+}
+
+#[test]
+fn it_bundles_javascript() -> Result<()> {
+    // Clean
+    let _whatever = fs::remove_dir_all("fixtures/bundle-project/.ditto");
+    let _whatever = fs::remove_file("fixtures/bundle-project/bundle.js");
+
+    make_bundle(&[])?;
+    let bundle = fs::read_to_string("fixtures/bundle-project/bundle.js")?;
+    assert!(
+        bundle.contains("foreign value `add_impl` should be a function"),
+        "dev bundles should check foreign values: {}",
+        bundle
+    );
+    assert!(
+        !bundle.contains("import ") && !bundle.contains("export "),
+        "bundles shouldn't be modules: {}",
+        bundle
+    );
+    assert_eq!(run_bundle()?, r#"["Pair",5,10]"#);
+
+    // The bundle follows the profile, like the rest of the build
+    make_bundle(&["--profile", "release"])?;
+    let bundle = fs::read_to_string("fixtures/bundle-project/bundle.js")?;
+    assert!(
+        bundle.starts_with("// Generated by ditto"),
+        "release bundles should have a banner: {}",
+        bundle
+    );
+    assert!(
+        !bundle.contains("should be a function"),
+        "release bundles shouldn't check foreign values: {}",
+        bundle
+    );
+    assert_eq!(run_bundle()?, r#"["Pair",5,10]"#);
+    Ok(())
+}
+
+fn make_bundle(args: &[&str]) -> Result<()> {
+    let ditto_bin = env!("CARGO_BIN_EXE_ditto");
+
+    let output = Command::new(ditto_bin)
+        .args(&["make", "--bundle", "bundle.js"])
+        .args(args)
+        .current_dir("fixtures/bundle-project")
+        .env("DITTO_PLAIN", "true")
+        .output()?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "ditto make failed: {}",
+        stderr
+    );
+    Ok(())
+}
+
+/// Load `bundle-project/bundle.js` with node, returning what it printed.
+fn run_bundle() -> Result<String> {
+    let output = Command::new("node")
+        .args(&[
+            "--input-type=module",
+            "--eval",
+            // Bundles are classic scripts that assign a global, rather than modules
+            "import { readFileSync } from 'fs'; \
+            (0, eval)(readFileSync('./bundle.js', 'utf8')); \
+            const bundle = await globalThis.bundleProject; \
+            console.log(JSON.stringify(bundle.Main.main))",
+        ])
+        .current_dir("fixtures/bundle-project")
+        .output()
+        .map_err(|err| match err.kind() {
+            ErrorKind::NotFound => Error::new(
+                ErrorKind::NotFound,
+                "couldn't find `node`, install Node.js (https://nodejs.org) to run this test",
+            ),
+            _ => err,
+        })?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "node failed: {}", stderr);
+    Ok(stdout.trim().to_string())
+}
+
+#[test]
+fn it_makes_a_nested_layout_project() -> Result<()> {
+    // Clean
+    let _whatever = fs::remove_dir_all("fixtures/nested-project/.ditto");
+    let _whatever = fs::remove_dir_all("fixtures/nested-project/dist");
+
+    let ditto_bin = env!("CARGO_BIN_EXE_ditto");
+
+    let output = Command::new(ditto_bin)
+        .arg("make")
+        .current_dir("fixtures/nested-project")
+        .env("DITTO_PLAIN", "true")
+        .output()?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "ditto make failed: {}",
+        stderr
+    );
+    assert!(Path::new("fixtures/nested-project/dist/Data/Numbers/Ints.js").exists());
+    assert!(!Path::new("fixtures/nested-project/dist/Data.Numbers.Ints.js").exists());
+
+    let output = Command::new("node")
+        .args(&[
+            "--input-type=module",
+            "--eval",
+            "import { main } from './dist/Main.js'; console.log(JSON.stringify(main))",
+        ])
+        .current_dir("fixtures/nested-project")
+        .output()
+        .map_err(|err| match err.kind() {
+            ErrorKind::NotFound => Error::new(
+                ErrorKind::NotFound,
+                "couldn't find `node`, install Node.js (https://nodejs.org) to run this test",
+            ),
+            _ => err,
+        })?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "node failed: {}", stderr);
+    assert_eq!(stdout.trim(), r#"[["Pair",5,10],["Pair",10,5]]"#);
+    Ok(())
+}
+
+#[test]
+fn it_writes_a_diagnostics_file_when_watching() -> Result<()> {
+    let project_dir = tempfile::tempdir()?;
+    fs::write(
+        project_dir.path().join("ditto.toml"),
+        "name = \"diagnostics-project\"\n",
+    )?;
+    fs::create_dir(project_dir.path().join("src"))?;
+    let main_path = project_dir.path().join("src").join("Main.ditto");
+    fs::write(&main_path, MAIN_OK)?;
+
+    let ditto_bin = env!("CARGO_BIN_EXE_ditto");
+
+    let mut child = Command::new(ditto_bin)
+        .args(&["make", "--watch", "--diagnostics-file", "diagnostics.json"])
+        .current_dir(project_dir.path())
+        .env("DITTO_PLAIN", "true")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    let diagnostics_path = project_dir.path().join("diagnostics.json");
+    let result = std::panic::catch_unwind(|| {
+        // Initial build
+        let document = wait_for_diagnostics(&diagnostics_path, 0, None, |_| true);
+        let generation = document["generation"].as_u64().unwrap();
+        assert_eq!(document["diagnostics"], serde_json::json!([]));
+
+        // Introduce an error
+        let document = wait_for_diagnostics(
+            &diagnostics_path,
+            generation,
+            Some((&main_path, MAIN_ERROR)),
+            |document| count_errors(document) > 0,
+        );
+        let generation = document["generation"].as_u64().unwrap();
+        assert!(
+            document["diagnostics"][0]["message"].is_string(),
+            "expected a diagnostic: {}",
+            document
+        );
+
+        // Fix the error
+        let document = wait_for_diagnostics(
+            &diagnostics_path,
+            generation,
+            Some((&main_path, MAIN_OK)),
+            |document| count_errors(document) == 0,
+        );
+        assert_eq!(document["diagnostics"], serde_json::json!([]));
+    });
+
+    child.kill()?;
+    if let Err(panic) = result {
+        std::panic::resume_unwind(panic);
+    }
+    Ok(())
+}
+
+#[test]
+fn it_writes_and_checks_a_lockfile() -> Result<()> {
+    let dependency_dir = tempfile::tempdir()?;
+    fs::write(
+        dependency_dir.path().join("ditto.toml"),
+        "name = \"dependency\"\n",
+    )?;
+    fs::create_dir(dependency_dir.path().join("src"))?;
+    let dependency_path = dependency_dir.path().join("src").join("Dependency.ditto");
+    fs::write(
+        &dependency_path,
+        "module Dependency exports (five);\n\nfive = 5;\n",
+    )?;
+
+    let project_dir = tempfile::tempdir()?;
+    fs::write(
+        project_dir.path().join("ditto.toml"),
+        format!(
+            "name = \"lockfile-project\"\ndependencies = [\"dependency\"]\n\n[package-set.packages]\ndependency = {{ path = \"{}\" }}\n",
+            dependency_dir.path().to_string_lossy().replace('\\', "/")
+        ),
+    )?;
+    fs::create_dir(project_dir.path().join("src"))?;
+    fs::write(project_dir.path().join("src").join("Main.ditto"), MAIN_OK)?;
+
+    let ditto_bin = env!("CARGO_BIN_EXE_ditto");
+    let make = |args: &[&str]| {
+        Command::new(ditto_bin)
+            .arg("make")
+            .args(args)
+            .current_dir(project_dir.path())
+            .env("DITTO_PLAIN", "true")
+            .output()
+    };
+
+    // The lockfile is written
+    let output = make(&[])?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "ditto make failed: {}",
+        stderr
+    );
+    let lockfile = fs::read_to_string(project_dir.path().join("ditto.lock"))?;
+    assert!(
+        lockfile.contains("name = \"dependency\""),
+        "unexpected lockfile: {}",
+        lockfile
+    );
+
+    // ...and satisfied
+    let output = make(&["--frozen"])?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "ditto make --frozen failed: {}",
+        stderr
+    );
+
+    // Changing the dependency means it's no longer satisfied
+    fs::write(
+        &dependency_path,
+        "module Dependency exports (five);\n\nfive = 55;\n",
+    )?;
+    let output = make(&["--frozen"])?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_ne!(
+        output.status.code(),
+        Some(0),
+        "ditto make --frozen succeeded"
+    );
+    assert!(
+        stderr.contains("ditto.lock is out of date"),
+        "expected a lockfile error: {}",
+        stderr
+    );
+
+    // A missing lockfile is reported before anything is installed
+    fs::remove_file(project_dir.path().join("ditto.lock"))?;
+    fs::remove_dir_all(project_dir.path().join(".ditto"))?;
+    let output = make(&["--frozen"])?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_ne!(
+        output.status.code(),
+        Some(0),
+        "ditto make --frozen succeeded"
+    );
+    assert!(
+        stderr.contains("ditto.lock is missing"),
+        "expected a lockfile error: {}",
+        stderr
+    );
+    assert!(!project_dir.path().join(".ditto").join("packages").exists());
+    Ok(())
+}
+
+#[test]
+fn it_reports_missing_and_empty_source_directories() -> Result<()> {
+    let project_dir = tempfile::tempdir()?;
+    fs::write(
+        project_dir.path().join("ditto.toml"),
+        "name = \"empty-project\"\n",
+    )?;
+
+    let ditto_bin = env!("CARGO_BIN_EXE_ditto");
+    let make = || {
+        Command::new(ditto_bin)
+            .arg("make")
+            .current_dir(project_dir.path())
+            .env("DITTO_PLAIN", "true")
+            .output()
+    };
+
+    // A missing source directory is an error
+    let output = make()?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_ne!(output.status.code(), Some(0), "ditto make succeeded");
+    assert!(
+        stderr.contains("source directory src doesn't exist"),
+        "expected a missing directory error: {}",
+        stderr
+    );
+
+    // An empty one is only a warning
+    fs::create_dir(project_dir.path().join("src"))?;
+    let output = make()?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "ditto make failed: {}",
+        stderr
+    );
+    assert!(
+        stderr.contains("no .ditto files found in src"),
+        "expected an empty directory warning: {}",
+        stderr
+    );
+    Ok(())
+}
+
+#[test]
+fn it_explains_a_missing_ninja_executable() -> Result<()> {
+    let project_dir = tempfile::tempdir()?;
+    fs::write(
+        project_dir.path().join("ditto.toml"),
+        "name = \"missing-ninja-project\"\n",
+    )?;
+    fs::create_dir(project_dir.path().join("src"))?;
+    fs::write(project_dir.path().join("src").join("Main.ditto"), MAIN_OK)?;
+
+    let ditto_bin = env!("CARGO_BIN_EXE_ditto");
+
+    let output = Command::new(ditto_bin)
+        .arg("make")
+        .current_dir(project_dir.path())
+        .env("DITTO_PLAIN", "true")
+        .env("DITTO_NINJA", "definitely-not-ninja")
+        .output()?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_ne!(output.status.code(), Some(0), "ditto make succeeded");
+    assert!(
+        stderr.contains("couldn't find ninja executable \"definitely-not-ninja\""),
+        "expected a missing ninja error: {}",
+        stderr
+    );
+    assert!(
+        stderr.contains("https://ninja-build.org"),
+        "expected some help: {}",
+        stderr
+    );
+    Ok(())
+}
+
+#[test]
+fn it_switches_between_build_profiles() -> Result<()> {
+    let project_dir = tempfile::tempdir()?;
+    fs::write(
+        project_dir.path().join("ditto.toml"),
+        "name = \"profiles-project\"\ntargets = [\"web\"]\n",
+    )?;
+    fs::create_dir(project_dir.path().join("src"))?;
+    fs::write(project_dir.path().join("src").join("Main.ditto"), MAIN_OK)?;
+
+    let ditto_bin = env!("CARGO_BIN_EXE_ditto");
+    let make = |profile: &str| -> Result<String> {
+        let output = Command::new(ditto_bin)
+            .args(&["make", "--profile", profile])
+            .current_dir(project_dir.path())
+            .env("DITTO_PLAIN", "true")
+            .output()?;
+        assert_eq!(
+            output.status.code(),
+            Some(0),
+            "ditto make --profile {} failed: {}",
+            profile,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    };
+    let main_js = || fs::read_to_string(project_dir.path().join("dist").join("Main.js"));
+
+    make("dev")?;
+    assert!(!main_js()?.starts_with("//"), "unexpected banner");
+
+    make("release")?;
+    assert!(
+        main_js()?.starts_with("// Generated by ditto from src/Main.ditto"),
+        "expected a banner: {}",
+        main_js()?
+    );
+
+    // Switching back regenerates the JavaScript, but the dev artifacts are reused
+    make("dev")?;
+    assert!(!main_js()?.starts_with("//"), "unexpected banner");
+    let stdout = make("dev")?;
+    assert!(
+        stdout.contains("Nothing to do"),
+        "expected no work: {}",
+        stdout
+    );
+    Ok(())
+}
+
+static MAIN_OK: &str = "module Main exports (main);\n\nmain = 5;\n";
+static MAIN_ERROR: &str = "module Main exports (main);\n\nmain = not_defined;\n";
+
+fn count_errors(document: &serde_json::Value) -> usize {
+    document["diagnostics"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|diagnostic| diagnostic["severity"] == "error")
+        .count()
+}
+
+/// Poll the diagnostics file until a newer generation satisfies `predicate`,
+/// (re)writing `source` in case the watcher misses an event.
+fn wait_for_diagnostics(
+    diagnostics_path: &Path,
+    previous_generation: u64,
+    source: Option<(&Path, &str)>,
+    predicate: impl Fn(&serde_json::Value) -> bool,
+) -> serde_json::Value {
+    let started = Instant::now();
+    let mut last_written: Option<Instant> = None;
+    loop {
+        if let Some((path, contents)) = source {
+            if last_written.map_or(true, |instant| instant.elapsed() > Duration::from_secs(2)) {
+                fs::write(path, contents).unwrap();
+                last_written = Some(Instant::now());
+            }
+        }
+        // The file is written atomically, so it should always parse
+        if let Ok(contents) = fs::read_to_string(diagnostics_path) {
+            let document = serde_json::from_str::<serde_json::Value>(&contents)
+                .unwrap_or_else(|err| panic!("invalid diagnostics file {}: {}", contents, err));
+            let generation = document["generation"].as_u64().unwrap();
+            if generation > previous_generation && predicate(&document) {
+                return document;
+            }
+        }
+        assert!(
+            started.elapsed() < Duration::from_secs(60),
+            "timed out waiting for diagnostics"
+        );
+        thread::sleep(Duration::from_millis(100));
+    }
+}
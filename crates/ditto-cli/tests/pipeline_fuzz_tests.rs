@@ -0,0 +1,675 @@
+//! Push randomly generated (but well-typed) modules through the whole pipeline,
+//! checking the invariants that no single crate can check on its own.
+//!
+//! Run more iterations (or reproduce a failure) with:
+//!
+//! ```sh
+//! DITTO_FUZZ_ITERATIONS=10000 cargo test -p ditto-cli --test pipeline_fuzz_tests
+//! DITTO_FUZZ_SEED=1234 cargo test -p ditto-cli --test pipeline_fuzz_tests
+//! ```
+use ditto_checker as checker;
+use ditto_codegen_js as js;
+use ditto_cst as cst;
+use std::{collections::HashSet, fmt, fs, path::PathBuf, process::Command};
+
+#[test]
+fn random_modules_survive_the_pipeline() {
+    let iterations = env_u64("DITTO_FUZZ_ITERATIONS").unwrap_or(100);
+    let seeds = match env_u64("DITTO_FUZZ_SEED") {
+        Some(seed) => seed..seed + 1,
+        None => 0..iterations,
+    };
+    for seed in seeds {
+        let module = Generator::new(seed).gen_module();
+        if let Err(failure) = run_pipeline(&module.to_string()) {
+            let module = minimize(module, &failure);
+            let path = dump(seed, &module);
+            panic!(
+                "seed {} failed: {}\n\nminimized source written to {:?}:\n\n{}",
+                seed, failure, path, module
+            );
+        }
+    }
+}
+
+fn env_u64(key: &str) -> Option<u64> {
+    let value = std::env::var(key).ok()?;
+    Some(
+        value
+            .parse()
+            .unwrap_or_else(|_| panic!("{} should be a number", key)),
+    )
+}
+
+// -----------------------------------------------------------------------------
+// Invariants
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FailureKind {
+    Parse,
+    Check,
+    InvalidJs,
+    DuplicateIdent,
+    FormatChangedOutput,
+}
+
+#[derive(Debug)]
+struct Failure {
+    kind: FailureKind,
+    message: String,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}: {}", self.kind, self.message)
+    }
+}
+
+fn fail<T>(kind: FailureKind, message: impl ToString) -> Result<T, Failure> {
+    Err(Failure {
+        kind,
+        message: message.to_string(),
+    })
+}
+
+/// Everything a module compiles to, for comparing before and after formatting.
+#[derive(PartialEq)]
+struct Artifacts {
+    js: String,
+    exports: serde_json::Value,
+}
+
+fn run_pipeline(source: &str) -> Result<(), Failure> {
+    let artifacts = compile(source)?;
+    check_js_syntax(&artifacts.js)?;
+    check_unique_idents(&artifacts.js)?;
+
+    let cst_module = cst::Module::parse(source).expect("already parsed");
+    let formatted = ditto_fmt::format_module(cst_module, source);
+    let reformatted = compile(&formatted)?;
+    if reformatted != artifacts {
+        return fail(
+            FailureKind::FormatChangedOutput,
+            format!(
+                "formatting changed the output\n\nformatted source:\n\n{}\n\nbefore:\n\n{}\n\nafter:\n\n{}",
+                formatted, artifacts.js, reformatted.js
+            ),
+        );
+    }
+    Ok(())
+}
+
+fn compile(source: &str) -> Result<Artifacts, Failure> {
+    let cst_module = match cst::Module::parse(source) {
+        Ok(cst_module) => cst_module,
+        Err(err) => return fail(FailureKind::Parse, format!("{:?}", err)),
+    };
+    let ast_module = match checker::check_module(&checker::Everything::default(), cst_module) {
+        Ok((ast_module, _warnings)) => ast_module,
+        Err(err) => return fail(FailureKind::Check, format!("{:?}", err)),
+    };
+    // NOTE `ModuleExports` is a bunch of `HashMap`s, and `serde_json::Value`
+    // compares maps regardless of their order
+    let exports = serde_json::to_value(&ast_module.exports).unwrap();
+    let js = js::codegen(
+        &js::Config {
+            module_name_to_path: Box::new(|_| unreachable!("modules aren't imported")),
+            foreign_module_path: "./foreign.js".into(),
+            strip_debug: false,
+            optimize: false,
+            inline_values: js::InlineValues::new(),
+            runtime_checks: false,
+            banner: None,
+            module_format: js::ModuleFormat::Esm,
+            unit: js::UnitRepresentation::Undefined,
+            pretty: false,
+            source_map: None,
+        },
+        ast_module,
+    );
+    Ok(Artifacts { js, exports })
+}
+
+fn check_js_syntax(js: &str) -> Result<(), Failure> {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("fuzz.mjs");
+    fs::write(&path, js).unwrap();
+    let output = Command::new("node")
+        .arg("--check")
+        .arg(&path)
+        .output()
+        .expect("node should be installed");
+    if output.status.success() {
+        Ok(())
+    } else {
+        fail(
+            FailureKind::InvalidJs,
+            format!("{}\n{}", String::from_utf8_lossy(&output.stderr), js),
+        )
+    }
+}
+
+/// Every statement is rendered on its own line, so the top-level declarations
+/// can be found without parsing.
+fn check_unique_idents(js: &str) -> Result<(), Failure> {
+    let mut seen = HashSet::new();
+    for line in js.lines() {
+        let ident = if let Some(rest) = line.strip_prefix("function ") {
+            rest.split('(').next()
+        } else if let Some(rest) = line.strip_prefix("const ") {
+            rest.split(|c| c == ' ' || c == '=').next()
+        } else if let Some(rest) = line.strip_prefix("let ") {
+            rest.split(';').next()
+        } else {
+            None
+        };
+        if let Some(ident) = ident {
+            if !seen.insert(ident.trim()) {
+                return fail(
+                    FailureKind::DuplicateIdent,
+                    format!("`{}` is declared more than once\n{}", ident, js),
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+// -----------------------------------------------------------------------------
+// Minimizing
+
+/// Shrink a failing module, keeping changes that fail in the same way.
+fn minimize(mut module: FuzzModule, failure: &Failure) -> FuzzModule {
+    let still_fails = |module: &FuzzModule| matches!(run_pipeline(&module.to_string()), Err(other) if other.kind == failure.kind);
+    loop {
+        let mut shrunk = false;
+        for i in (0..module.declarations.len()).rev() {
+            let mut candidate = module.clone();
+            candidate.declarations.remove(i);
+            if still_fails(&candidate) {
+                module = candidate;
+                shrunk = true;
+            }
+        }
+        for i in 0..module.declarations.len() {
+            let declaration = &module.declarations[i];
+            let leaf = Generator::new(0).gen_leaf(&[], &declaration.declaration_type);
+            if declaration.expression == leaf {
+                continue;
+            }
+            let mut candidate = module.clone();
+            candidate.declarations[i].expression = leaf;
+            if still_fails(&candidate) {
+                module = candidate;
+                shrunk = true;
+            }
+        }
+        if !shrunk {
+            return module;
+        }
+    }
+}
+
+fn dump(seed: u64, module: &FuzzModule) -> PathBuf {
+    let path =
+        PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(format!("pipeline-fuzz-{}.ditto", seed));
+    fs::write(&path, module.to_string()).unwrap();
+    path
+}
+
+// -----------------------------------------------------------------------------
+// Generating
+//
+// This mirrors the shape of `ditto_ast`, but only covers what the generator
+// knows how to produce (no records or pattern matching yet).
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Type {
+    Int,
+    Float,
+    String,
+    Bool,
+    Array(Box<Type>),
+    Function(Vec<Type>, Box<Type>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expression {
+    Int(u32),
+    Float(u32, u32),
+    /// The source text between the quotes.
+    String(String),
+    Bool(bool),
+    Variable(String),
+    Array(Vec<Expression>),
+    If(Box<Expression>, Box<Expression>, Box<Expression>),
+    Function {
+        parameters: Vec<(String, Type)>,
+        return_type: Type,
+        body: Box<Expression>,
+    },
+    Call(Box<Expression>, Vec<Expression>),
+    Operator(Box<Expression>, &'static str, Box<Expression>),
+    Let(Vec<Declaration>, Box<Expression>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Declaration {
+    name: String,
+    declaration_type: Type,
+    expression: Expression,
+}
+
+#[derive(Debug, Clone)]
+struct FuzzModule {
+    declarations: Vec<Declaration>,
+}
+
+/// Names that end up mangled (or need to be) in the generated JavaScript.
+static AWKWARD_NAMES: &[&str] = &[
+    "const",
+    "class",
+    "new",
+    "delete",
+    "void",
+    "this",
+    "super",
+    "default",
+    "console",
+    "undefined",
+    "foo_bar",
+    "x1",
+    "a_b_c",
+];
+
+static WORDS: &[&str] = &[
+    "apple", "berry", "cherry", "date", "elder", "fig", "grape", "kiwi",
+];
+
+/// String contents, including every escape sequence.
+static STRING_PIECES: &[&str] = &[
+    "hello",
+    " ",
+    "世界",
+    "\\\"",
+    "\\\\",
+    "\\n",
+    "\\t",
+    "\\r",
+    "\\u{1F600}",
+    "\\u{41}",
+    "'",
+    "\n",
+];
+
+struct Generator {
+    state: u64,
+    used_names: HashSet<String>,
+}
+
+impl Generator {
+    fn new(seed: u64) -> Self {
+        Self {
+            state: seed,
+            used_names: HashSet::new(),
+        }
+    }
+
+    /// SplitMix64, which is plenty random for this.
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    fn chance(&mut self, one_in: usize) -> bool {
+        self.below(one_in) == 0
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    /// A name that hasn't been used anywhere else in the module, so nothing is shadowed.
+    fn fresh_name(&mut self) -> String {
+        let mut name = if self.chance(4) {
+            self.pick(AWKWARD_NAMES).to_string()
+        } else {
+            let first = self.pick(WORDS);
+            let second = self.pick(WORDS);
+            format!("{}_{}", first, second)
+        };
+        if self.used_names.contains(&name) {
+            name = format!("{}_{}", name, self.used_names.len());
+        }
+        self.used_names.insert(name.clone());
+        name
+    }
+
+    fn gen_module(&mut self) -> FuzzModule {
+        let mut declarations: Vec<Declaration> = Vec::new();
+        for _ in 0..1 + self.below(8) {
+            let env = declarations
+                .iter()
+                .map(|declaration| {
+                    (
+                        declaration.name.clone(),
+                        declaration.declaration_type.clone(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            let declaration_type = self.gen_type(2);
+            let expression = self.gen_expression(&env, &declaration_type, 3);
+            declarations.push(Declaration {
+                name: self.fresh_name(),
+                declaration_type,
+                expression,
+            });
+        }
+        FuzzModule { declarations }
+    }
+
+    fn gen_type(&mut self, depth: usize) -> Type {
+        match self.below(if depth == 0 { 4 } else { 6 }) {
+            0 => Type::Int,
+            1 => Type::Float,
+            2 => Type::String,
+            3 => Type::Bool,
+            4 => Type::Array(Box::new(self.gen_type(depth - 1))),
+            _ => Type::Function(
+                (0..self.below(3))
+                    .map(|_| self.gen_type(depth - 1))
+                    .collect(),
+                Box::new(self.gen_type(depth - 1)),
+            ),
+        }
+    }
+
+    fn gen_expression(&mut self, env: &[(String, Type)], t: &Type, depth: usize) -> Expression {
+        if depth == 0 {
+            return self.gen_leaf(env, t);
+        }
+        match self.below(6) {
+            0 => Expression::If(
+                Box::new(self.gen_expression(env, &Type::Bool, depth - 1)),
+                Box::new(self.gen_expression(env, t, depth - 1)),
+                Box::new(self.gen_expression(env, t, depth - 1)),
+            ),
+            1 => {
+                let name = self.fresh_name();
+                let declaration_type = self.gen_type(1);
+                let expression = self.gen_expression(env, &declaration_type, depth - 1);
+                let mut env = env.to_vec();
+                env.push((name.clone(), declaration_type.clone()));
+                let body = self.gen_expression(&env, t, depth - 1);
+                Expression::Let(
+                    vec![Declaration {
+                        name,
+                        declaration_type,
+                        expression,
+                    }],
+                    Box::new(body),
+                )
+            }
+            2 => {
+                let callables = env
+                    .iter()
+                    .filter_map(|(name, variable_type)| match variable_type {
+                        Type::Function(parameters, return_type) if **return_type == *t => {
+                            Some((name.clone(), parameters.clone()))
+                        }
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+                if callables.is_empty() {
+                    // Call a function literal straight away
+                    let parameter_types = (0..self.below(3)).map(|_| self.gen_type(1)).collect();
+                    let function_type = Type::Function(parameter_types, Box::new(t.clone()));
+                    return self.gen_call(env, &function_type, depth);
+                }
+                let (name, parameters) = self.pick(&callables).clone();
+                let arguments = parameters
+                    .iter()
+                    .map(|parameter| self.gen_expression(env, parameter, depth - 1))
+                    .collect();
+                Expression::Call(Box::new(Expression::Variable(name)), arguments)
+            }
+            3 => self
+                .gen_operator(env, t, depth)
+                .unwrap_or_else(|| self.gen_leaf(env, t)),
+            4 => self.gen_leaf(env, t),
+            _ => self.gen_structural(env, t, depth),
+        }
+    }
+
+    fn gen_call(
+        &mut self,
+        env: &[(String, Type)],
+        function_type: &Type,
+        depth: usize,
+    ) -> Expression {
+        let parameter_types = match function_type {
+            Type::Function(parameter_types, _) => parameter_types,
+            _ => unreachable!(),
+        };
+        let function = self.gen_structural(env, function_type, depth);
+        let arguments = parameter_types
+            .iter()
+            .map(|parameter_type| self.gen_expression(env, parameter_type, depth - 1))
+            .collect();
+        Expression::Call(Box::new(function), arguments)
+    }
+
+    /// An expression built from the shape of the type.
+    fn gen_structural(&mut self, env: &[(String, Type)], t: &Type, depth: usize) -> Expression {
+        match t {
+            Type::Array(element_type) => Expression::Array(
+                (0..1 + self.below(3))
+                    .map(|_| self.gen_expression(env, element_type, depth - 1))
+                    .collect(),
+            ),
+            Type::Function(parameter_types, return_type) => {
+                let parameters = parameter_types
+                    .iter()
+                    .map(|parameter_type| (self.fresh_name(), parameter_type.clone()))
+                    .collect::<Vec<_>>();
+                let mut env = env.to_vec();
+                env.extend(parameters.iter().cloned());
+                let body = self.gen_expression(&env, return_type, depth - 1);
+                Expression::Function {
+                    parameters,
+                    return_type: (**return_type).clone(),
+                    body: Box::new(body),
+                }
+            }
+            _ => self.gen_leaf(env, t),
+        }
+    }
+
+    fn gen_operator(
+        &mut self,
+        env: &[(String, Type)],
+        t: &Type,
+        depth: usize,
+    ) -> Option<Expression> {
+        let (operand_type, operator) = match t {
+            Type::Int => (Type::Int, *self.pick(&["+", "-", "*"])),
+            Type::Float => (Type::Float, *self.pick(&["+", "-", "*", "/"])),
+            Type::Bool => match self.below(3) {
+                0 => (Type::Bool, *self.pick(&["&&", "||"])),
+                1 => (
+                    self.pick(&[Type::Int, Type::Float]).clone(),
+                    *self.pick(&["<", "<=", ">", ">="]),
+                ),
+                // Equality is structural, so it works for anything but functions
+                _ => {
+                    let mut operand_type = self.gen_type(1);
+                    while contains_function(&operand_type) {
+                        operand_type = self.gen_type(1);
+                    }
+                    (operand_type, *self.pick(&["==", "!="]))
+                }
+            },
+            _ => return None,
+        };
+        Some(Expression::Operator(
+            Box::new(self.gen_expression(env, &operand_type, depth - 1)),
+            operator,
+            Box::new(self.gen_expression(env, &operand_type, depth - 1)),
+        ))
+    }
+
+    /// A variable of the right type if there is one, otherwise a literal.
+    fn gen_leaf(&mut self, env: &[(String, Type)], t: &Type) -> Expression {
+        let variables = env
+            .iter()
+            .filter(|(_, variable_type)| variable_type == t)
+            .map(|(name, _)| name.clone())
+            .collect::<Vec<_>>();
+        if !variables.is_empty() && !self.chance(3) {
+            return Expression::Variable(self.pick(&variables).clone());
+        }
+        match t {
+            Type::Int => Expression::Int(self.below(1000) as u32),
+            Type::Float => Expression::Float(self.below(100) as u32, self.below(100) as u32),
+            Type::String => Expression::String(
+                (0..self.below(4))
+                    .map(|_| *self.pick(STRING_PIECES))
+                    .collect(),
+            ),
+            Type::Bool => Expression::Bool(self.chance(2)),
+            Type::Array(element_type) => Expression::Array(vec![self.gen_leaf(env, element_type)]),
+            Type::Function(parameter_types, return_type) => {
+                let parameters = parameter_types
+                    .iter()
+                    .map(|parameter_type| (self.fresh_name(), parameter_type.clone()))
+                    .collect::<Vec<_>>();
+                let mut env = env.to_vec();
+                env.extend(parameters.iter().cloned());
+                Expression::Function {
+                    parameters,
+                    return_type: (**return_type).clone(),
+                    body: Box::new(self.gen_leaf(&env, return_type)),
+                }
+            }
+        }
+    }
+}
+
+fn contains_function(t: &Type) -> bool {
+    match t {
+        Type::Function(..) => true,
+        Type::Array(element_type) => contains_function(element_type),
+        _ => false,
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Printing
+//
+// Everything that isn't atomic is parenthesized, so the printer doesn't need
+// to know about precedence. Tidying that up is the formatter's job.
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Int => write!(f, "Int"),
+            Self::Float => write!(f, "Float"),
+            Self::String => write!(f, "String"),
+            Self::Bool => write!(f, "Bool"),
+            Self::Array(element_type) => write!(f, "Array({})", element_type),
+            Self::Function(parameters, return_type) => {
+                write!(f, "(")?;
+                write_comma_sep(f, parameters)?;
+                write!(f, ") -> {}", return_type)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Int(value) => write!(f, "{}", value),
+            Self::Float(whole, fraction) => write!(f, "{}.{}", whole, fraction),
+            Self::String(value) => write!(f, "\"{}\"", value),
+            Self::Bool(value) => write!(f, "{}", value),
+            Self::Variable(name) => write!(f, "{}", name),
+            Self::Array(elements) => {
+                write!(f, "[")?;
+                write_comma_sep(f, elements)?;
+                write!(f, "]")
+            }
+            Self::If(condition, true_clause, false_clause) => write!(
+                f,
+                "(if {} then {} else {})",
+                condition, true_clause, false_clause
+            ),
+            Self::Function {
+                parameters,
+                return_type,
+                body,
+            } => {
+                write!(f, "((")?;
+                for (i, (name, parameter_type)) in parameters.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", name, parameter_type)?;
+                }
+                // Return type annotations can't be function types without parens
+                write!(f, "): ({}) -> {})", return_type, body)
+            }
+            Self::Call(function, arguments) => {
+                write!(f, "{}(", function)?;
+                write_comma_sep(f, arguments)?;
+                write!(f, ")")
+            }
+            Self::Operator(lhs, operator, rhs) => write!(f, "({} {} {})", lhs, operator, rhs),
+            Self::Let(declarations, body) => {
+                write!(f, "(let ")?;
+                for declaration in declarations {
+                    write!(f, "{} ", declaration)?;
+                }
+                write!(f, "in {})", body)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Declaration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} : {} = {};",
+            self.name, self.declaration_type, self.expression
+        )
+    }
+}
+
+impl fmt::Display for FuzzModule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "module Fuzz exports (..);")?;
+        for declaration in self.declarations.iter() {
+            writeln!(f)?;
+            writeln!(f, "{}", declaration)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_comma_sep(f: &mut fmt::Formatter, items: &[impl fmt::Display]) -> fmt::Result {
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{}", item)?;
+    }
+    Ok(())
+}
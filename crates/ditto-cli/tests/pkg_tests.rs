@@ -1 +1,391 @@
 // TODO test package install/remove scenarios
+
+use std::{
+    fs,
+    io::Result,
+    path::Path,
+    process::{Command, Output},
+};
+
+#[test]
+fn it_indexes_installed_packages() -> Result<()> {
+    let foo_dir = tempfile::tempdir()?;
+    write_package(
+        foo_dir.path(),
+        "foo",
+        "Foo",
+        "module Foo exports (Maybe(..), with_default);\n\ntype Maybe(a) = Just(a) | Nothing;\n\nwith_default = (maybe: Maybe(a), default: a): a -> default;\n\nprivate = 5;\n",
+    )?;
+    fs::create_dir_all(foo_dir.path().join("src").join("Foo"))?;
+    fs::write(
+        foo_dir.path().join("src").join("Foo").join("Prelude.ditto"),
+        "module Foo.Prelude exports (module Foo, Only);\n\nimport Foo;\n\ntype Wrapper = Only(Int);\n",
+    )?;
+    let bar_dir = tempfile::tempdir()?;
+    write_package(
+        bar_dir.path(),
+        "bar",
+        "Bar.Baz",
+        "module Bar.Baz exports (..);\n\nfive : Int = 5;\n",
+    )?;
+
+    let project_dir = tempfile::tempdir()?;
+    fs::write(
+        project_dir.path().join("ditto.toml"),
+        format!(
+            "name = \"index-project\"\ndependencies = [\"foo\", \"bar\"]\n\n[package-set.packages]\nfoo = {{ path = \"{}\" }}\nbar = {{ path = \"{}\" }}\n",
+            foo_dir.path().to_string_lossy().replace('\\', "/"),
+            bar_dir.path().to_string_lossy().replace('\\', "/"),
+        ),
+    )?;
+    let index_dir = project_dir.path().join(".ditto").join("index");
+
+    let ditto_bin = env!("CARGO_BIN_EXE_ditto");
+    let ditto = |args: &[&str]| -> Result<Output> {
+        let output = Command::new(ditto_bin)
+            .args(args)
+            .current_dir(project_dir.path())
+            .env("DITTO_PLAIN", "true")
+            .output()?;
+        assert_eq!(
+            output.status.code(),
+            Some(0),
+            "ditto {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        Ok(output)
+    };
+
+    ditto(&["pkg", "update"])?;
+    let foo_index = fs::read_to_string(index_dir.join("foo.json"))?;
+    let bar_index = fs::read_to_string(index_dir.join("bar.json"))?;
+    for entry in [
+        r#"{"name":"Maybe","package":"foo","module":"Foo","kind":"type","summary":null}"#,
+        r#"{"name":"Just","package":"foo","module":"Foo","kind":"constructor","summary":"Maybe"}"#,
+        r#"{"name":"Nothing","package":"foo","module":"Foo","kind":"constructor","summary":"Maybe"}"#,
+        r#"{"name":"with_default","package":"foo","module":"Foo","kind":"value","summary":"a"}"#,
+    ] {
+        assert!(foo_index.contains(entry), "{} not in {}", entry, foo_index);
+    }
+    assert!(!foo_index.contains("private"), "{}", foo_index);
+    assert!(
+        bar_index.contains(
+            r#"{"name":"five","package":"bar","module":"Bar.Baz","kind":"value","summary":"Int"}"#
+        ),
+        "{}",
+        bar_index
+    );
+
+    let output = ditto(&["search", "Just"])?;
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "import (foo) Foo (Maybe(..));\nimport (foo) Foo.Prelude (Maybe(..));\n"
+    );
+    let output = ditto(&["search", "Only"])?;
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "import (foo) Foo.Prelude (Only);\n"
+    );
+
+    // Unknown names are suggested an import from the index
+    fs::create_dir_all(project_dir.path().join("src"))?;
+    fs::write(
+        project_dir.path().join("src").join("Main.ditto"),
+        "module Main exports (..);\n\nfallback = with_default;\n",
+    )?;
+    let output = Command::new(ditto_bin)
+        .arg("make")
+        .current_dir(project_dir.path())
+        .env("DITTO_PLAIN", "true")
+        .output()?;
+    // Errors from ninja are forwarded to stdout
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_ne!(output.status.code(), Some(0), "ditto make succeeded");
+    assert!(
+        stdout.contains("import (foo) Foo (with_default);"),
+        "{}",
+        stdout
+    );
+    fs::remove_file(project_dir.path().join("src").join("Main.ditto"))?;
+
+    // Changing one package only re-indexes that package
+    fs::write(
+        bar_dir.path().join("src").join("Bar").join("Baz.ditto"),
+        "module Bar.Baz exports (..);\n\nsix : Int = 6;\n",
+    )?;
+    ditto(&["pkg", "update"])?;
+    assert_eq!(fs::read_to_string(index_dir.join("foo.json"))?, foo_index);
+    let new_bar_index = fs::read_to_string(index_dir.join("bar.json"))?;
+    assert_ne!(new_bar_index, bar_index);
+    assert!(
+        new_bar_index.contains(r#""name":"six""#),
+        "{}",
+        new_bar_index
+    );
+    assert!(
+        !new_bar_index.contains(r#""name":"five""#),
+        "{}",
+        new_bar_index
+    );
+
+    Ok(())
+}
+
+#[test]
+fn it_reports_every_broken_package() -> Result<()> {
+    let good_dir = tempfile::tempdir()?;
+    write_package(
+        good_dir.path(),
+        "good",
+        "Good",
+        "module Good exports (..);\n\nfive = 5;\n",
+    )?;
+    let missing_dir = tempfile::tempdir()?;
+
+    let project_dir = tempfile::tempdir()?;
+    fs::write(
+        project_dir.path().join("ditto.toml"),
+        format!(
+            "name = \"broken-project\"\ndependencies = [\"good\", \"broken\", \"also-broken\"]\n\n[package-set.packages]\ngood = {{ path = \"{}\" }}\nbroken = {{ path = \"{}\" }}\nalso-broken = {{ path = \"{}\" }}\n",
+            good_dir.path().to_string_lossy().replace('\\', "/"),
+            missing_dir.path().join("broken").to_string_lossy().replace('\\', "/"),
+            missing_dir.path().join("also-broken").to_string_lossy().replace('\\', "/"),
+        ),
+    )?;
+
+    let ditto_bin = env!("CARGO_BIN_EXE_ditto");
+    let output = Command::new(ditto_bin)
+        .args(&["pkg", "update", "--jobs", "2"])
+        .current_dir(project_dir.path())
+        .env("DITTO_PLAIN", "true")
+        .output()?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_ne!(output.status.code(), Some(0), "ditto pkg update succeeded");
+    assert!(
+        stderr.contains("2 packages failed to install"),
+        "expected both failures to be reported: {}",
+        stderr
+    );
+    assert!(stderr.contains("\"broken\""), "{}", stderr);
+    assert!(stderr.contains("\"also-broken\""), "{}", stderr);
+
+    // The good package is still installed
+    let packages_dir = project_dir.path().join(".ditto").join("packages");
+    assert!(packages_dir.join("good").join("ditto.toml").exists());
+    assert!(packages_dir.join("good.spec").exists());
+    assert!(!packages_dir.join("broken.spec").exists());
+    Ok(())
+}
+
+#[test]
+fn it_lists_and_updates_outdated_packages() -> Result<()> {
+    let packages_dir = tempfile::tempdir()?;
+    let foo_v1 = packages_dir.path().join("foo-v1");
+    let foo_v2 = packages_dir.path().join("foo-v2");
+    let bar = packages_dir.path().join("bar");
+    for (dir, name, module_name) in [
+        (&foo_v1, "foo", "Foo"),
+        (&foo_v2, "foo", "Foo"),
+        (&bar, "bar", "Bar"),
+    ] {
+        fs::create_dir_all(dir)?;
+        write_package(
+            dir,
+            name,
+            module_name,
+            &format!("module {} exports (..);\n\nfive = 5;\n", module_name),
+        )?;
+    }
+    let render_path = |path: &Path| path.to_string_lossy().replace('\\', "/");
+
+    let project_dir = tempfile::tempdir()?;
+    let write_config = |dependencies: &str, packages: &str| {
+        fs::write(
+            project_dir.path().join("ditto.toml"),
+            format!(
+                "name = \"outdated-project\"\ndependencies = [{}]\n\n[package-set.packages]\n{}",
+                dependencies, packages
+            ),
+        )
+    };
+    let ditto_bin = env!("CARGO_BIN_EXE_ditto");
+    let ditto = |args: &[&str]| -> Result<Output> {
+        Command::new(ditto_bin)
+            .args(args)
+            .current_dir(project_dir.path())
+            .env("DITTO_PLAIN", "true")
+            .output()
+    };
+    let outdated_rows = || -> Result<Vec<Vec<String>>> {
+        let output = ditto(&["pkg", "outdated"])?;
+        assert_eq!(output.status.code(), Some(0), "{:?}", output);
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|line| line.split_whitespace().map(String::from).collect())
+            .collect())
+    };
+
+    write_config(
+        "\"foo\"",
+        &format!("foo = {{ path = \"{}\" }}\n", render_path(&foo_v1)),
+    )?;
+    assert_eq!(ditto(&["pkg", "update"])?.status.code(), Some(0));
+    assert_eq!(
+        outdated_rows()?,
+        vec![vec!["All", "packages", "are", "up", "to", "date"]]
+    );
+
+    // A changed source and a new dependency are both outdated
+    write_config(
+        "\"foo\", \"bar\"",
+        &format!(
+            "foo = {{ path = \"{}\" }}\nbar = {{ path = \"{}\" }}\n",
+            render_path(&foo_v2),
+            render_path(&bar)
+        ),
+    )?;
+    let (foo_v1_path, foo_v2_path, bar_path) = (
+        render_path(&foo_v1),
+        render_path(&foo_v2),
+        render_path(&bar),
+    );
+    assert_eq!(
+        outdated_rows()?,
+        vec![
+            vec!["package", "locked", "available", "permitted", "direct"],
+            vec!["bar", "-", bar_path.as_str(), "yes", "yes"],
+            vec![
+                "foo",
+                foo_v1_path.as_str(),
+                foo_v2_path.as_str(),
+                "yes",
+                "yes"
+            ],
+        ]
+    );
+
+    let output = ditto(&["pkg", "update", "baz"])?;
+    assert_ne!(output.status.code(), Some(0));
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("\"baz\" isn't installed"),
+        "{:?}",
+        output
+    );
+
+    let output = ditto(&["pkg", "update", "foo"])?;
+    assert_eq!(output.status.code(), Some(0), "{:?}", output);
+    assert_eq!(
+        outdated_rows()?,
+        vec![vec!["All", "packages", "are", "up", "to", "date"]]
+    );
+    let installed_spec = fs::read_to_string(
+        project_dir
+            .path()
+            .join(".ditto")
+            .join("packages")
+            .join("foo.spec"),
+    )?;
+    assert!(
+        installed_spec.contains("foo-v2"),
+        "{} wasn't updated",
+        installed_spec
+    );
+
+    // Versioned packages are compared by their locked version
+    let versioned_dir = |version: &str| -> Result<String> {
+        let dir = packages_dir.path().join(format!("foo-{}", version));
+        fs::create_dir_all(&dir)?;
+        write_package(
+            &dir,
+            "foo",
+            "Foo",
+            "module Foo exports (..);\n\nfive = 5;\n",
+        )?;
+        fs::write(
+            dir.join("ditto.toml"),
+            format!("name = \"foo\"\nversion = \"{}\"\n", version),
+        )?;
+        Ok(render_path(&dir))
+    };
+    let write_versioned_config = |path: &str| {
+        write_config(
+            "\"foo\"",
+            &format!("foo = {{ path = \"{}\", version = \"^1.0\" }}\n", path),
+        )
+    };
+    write_versioned_config(&versioned_dir("1.0.0")?)?;
+    let output = ditto(&["pkg", "update"])?;
+    assert_eq!(output.status.code(), Some(0), "{:?}", output);
+    assert_eq!(
+        outdated_rows()?,
+        vec![vec!["All", "packages", "are", "up", "to", "date"]]
+    );
+
+    // The lock is older than what's available, but the requirement allows it
+    write_versioned_config(&versioned_dir("1.1.0")?)?;
+    assert_eq!(
+        outdated_rows()?,
+        vec![
+            vec!["package", "locked", "available", "permitted", "direct"],
+            vec!["foo", "1.0.0", "1.1.0", "yes", "yes"],
+        ]
+    );
+
+    // ...whereas here it doesn't
+    write_versioned_config(&versioned_dir("2.0.0")?)?;
+    assert_eq!(
+        outdated_rows()?,
+        vec![
+            vec!["package", "locked", "available", "permitted", "direct"],
+            vec!["foo", "1.0.0", "2.0.0", "no", "yes"],
+        ]
+    );
+    let output = ditto(&["pkg", "update", "foo"])?;
+    assert_ne!(output.status.code(), Some(0), "{:?}", output);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("foo 2.0.0 doesn't match the requirement ^1.0"),
+        "{}",
+        stderr
+    );
+
+    // The failed update left foo uninstalled, so update everything
+    write_versioned_config(&versioned_dir("1.1.0")?)?;
+    let output = ditto(&["pkg", "update"])?;
+    assert_eq!(output.status.code(), Some(0), "{:?}", output);
+    assert_eq!(
+        outdated_rows()?,
+        vec![vec!["All", "packages", "are", "up", "to", "date"]]
+    );
+    let lockfile = fs::read_to_string(project_dir.path().join("ditto.lock"))?;
+    assert!(lockfile.contains("version = \"1.1.0\""), "{}", lockfile);
+
+    // Package sources we can't install are rejected up front
+    for (spec, kind) in [
+        (r#"{ git = "https://example.com/foo.git" }"#, "git"),
+        (r#"{ version = "1.0.0" }"#, "registry"),
+    ] {
+        write_config("\"foo\"", &format!("foo = {}\n", spec))?;
+        for command in ["outdated", "update"] {
+            let output = ditto(&["pkg", command])?;
+            assert_ne!(output.status.code(), Some(0), "{:?}", output);
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            assert!(
+                stderr.contains(&format!("{} packages aren't supported yet", kind)),
+                "{}",
+                stderr
+            );
+        }
+    }
+    Ok(())
+}
+
+fn write_package(dir: &Path, name: &str, module_name: &str, source: &str) -> Result<()> {
+    fs::write(dir.join("ditto.toml"), format!("name = \"{}\"\n", name))?;
+    let mut module_path = dir.join("src");
+    module_path.extend(module_name.split('.'));
+    module_path.set_extension("ditto");
+    fs::create_dir_all(module_path.parent().unwrap())?;
+    fs::write(module_path, source)
+}
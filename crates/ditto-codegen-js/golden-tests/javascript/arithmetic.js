@@ -0,0 +1,26 @@
+function halve(n) {
+  return Math.trunc(n / 2);
+}
+const negativeTruncated = Math.trunc(-7 / 2);
+const truncated = Math.trunc(7 / 2);
+function average(x, y) {
+  return (x + y) / 2.0;
+}
+const rightNested = 10 - (5 - 1);
+const leftAssoc = 10 - 5 - 1;
+const grouped = (1 + 2) * (3 - 4);
+const precedence = 1 + 2 * 3 - Math.trunc(4 / 2);
+function add(a, b) {
+  return a + b;
+}
+export {
+  add,
+  average,
+  grouped,
+  halve,
+  leftAssoc,
+  negativeTruncated,
+  precedence,
+  rightNested,
+  truncated,
+};
@@ -0,0 +1,10 @@
+function inRange(n) {
+  return (n >= 0 && n < 10) || n === 100;
+}
+function either(a, b) {
+  return a || b;
+}
+function both(a, b) {
+  return a && b;
+}
+export { both, either, inRange };
@@ -0,0 +1,9 @@
+function isLowercase(c) {
+  return c >= "a" && c <= "z";
+}
+const emoji = "\u{1F600}";
+const doubleQuote = '"';
+const quote = "'";
+const newline = "\n";
+const letter = "a";
+export { doubleQuote, emoji, isLowercase, letter, newline, quote };
@@ -0,0 +1,10 @@
+"use strict";
+const { log: foreign$log } = require("./foreign.js");
+const { five: Data$Stuff$five } = require("Data.Stuff");
+const { id: test_stuff$Data$Stuff$id } = require("test-stuff/Data.Stuff");
+function Answer($0) {
+  return ["Answer", $0];
+}
+const answer = Answer(test_stuff$Data$Stuff$id(Data$Stuff$five));
+const logged = foreign$log(answer);
+module.exports = { Answer, answer, logged };
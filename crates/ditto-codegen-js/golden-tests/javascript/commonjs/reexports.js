@@ -0,0 +1,14 @@
+"use strict";
+const answer = 42;
+module.exports = {
+  answer,
+  Five: require("Data.Stuff").Five,
+  Just: require("Data.Stuff").Just,
+  Nothing: require("Data.Stuff").Nothing,
+  addFive: require("Data.Stuff").addFive,
+  five: require("Data.Stuff").five,
+  fiveString: require("Data.Stuff").fiveString,
+  id: require("Data.Stuff").id,
+  justFive: require("Data.Stuff").justFive,
+  seed: require("Data.Stuff").seed,
+};
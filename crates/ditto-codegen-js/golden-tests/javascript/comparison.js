@@ -0,0 +1,13 @@
+const atLeast = 2.5 >= 1.0;
+function greater(a, b) {
+  return a + 1 > b;
+}
+const atMost = 1 <= 2;
+const less = 1 < 2;
+function differ(a, b) {
+  return a !== b;
+}
+function isZero(n) {
+  return n === 0;
+}
+export { atLeast, atMost, differ, greater, isZero, less };
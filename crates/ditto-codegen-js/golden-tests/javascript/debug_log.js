@@ -0,0 +1,5 @@
+const five = ((message, value) => {
+  console.log(message, value);
+  return value;
+})("five", 5);
+export { five };
@@ -0,0 +1,7 @@
+function isNan(x) {
+  return x !== x;
+}
+const nan = NaN;
+const negInfinity = -Infinity;
+const infinity = Infinity;
+export { infinity, isNan, nan, negInfinity };
@@ -12,3 +12,31 @@ export function h(name, attrs) {
 export function arrayMapImpl(array, f) {
   return array.map(f);
 }
+
+/**
+ * @param {boolean} condition
+ * @param {number} t
+ * @param {number} f
+ * @returns {number}
+ */
+function if_(condition, t, f) {
+  return condition ? t : f;
+}
+
+export { if_ as if };
+
+/**
+ * @param {number} n
+ * @returns {number}
+ */
+function new_(n) {
+  return n;
+}
+
+export { new_ as $new };
+
+const console_ = 5;
+
+export { console_ as console };
+
+export default 5;
@@ -0,0 +1,8 @@
+import {
+  $new as foreign$$new,
+  console as foreign$$console,
+} from "./foreign.js";
+function thing() {
+  return foreign$$new(foreign$$console);
+}
+export { thing };
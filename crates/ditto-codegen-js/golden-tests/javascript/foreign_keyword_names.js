@@ -0,0 +1,8 @@
+import {
+  default as foreign$defaultImpl,
+  if as foreign$ifImpl,
+} from "./foreign.js";
+function choose(b) {
+  return foreign$ifImpl(b, foreign$defaultImpl, 5);
+}
+export { choose };
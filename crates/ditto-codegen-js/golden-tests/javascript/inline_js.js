@@ -0,0 +1,9 @@
+const quote = '"';
+const max = Math.max(1, 2) * 2;
+function isArray(x) {
+  return Array.isArray(x);
+}
+const add = (a, b) => a + b;
+const three = add(1, 2);
+const now = Date.now();
+export { add, isArray, max, now, quote, three };
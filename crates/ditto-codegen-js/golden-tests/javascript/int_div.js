@@ -0,0 +1,26 @@
+function $int_div(a, b) {
+  if (b === 0) {
+    return ["Nothing"];
+  }
+  return ["Just", Math.trunc(a / b)];
+}
+const wrap = value => ["Just", value];
+const nothing = ["Nothing"];
+const justFive = ["Just", 5];
+function withDefault(maybe, $default) {
+  return ($match => {
+    if ($match[0] === "Just") {
+      const n = $match[1];
+      return n;
+    }
+    if ($match[0] === "Nothing") {
+      return $default;
+    }
+    throw new Error("Pattern match error");
+  })(maybe);
+}
+const byZero = $int_div(7, 0);
+function halve(n) {
+  return $int_div(n, 2);
+}
+export { byZero, halve, justFive, nothing, withDefault, wrap };
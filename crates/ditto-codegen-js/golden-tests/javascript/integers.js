@@ -0,0 +1,7 @@
+const padded = 7;
+const negativeHex = -0x10;
+const flags = 0b10101010;
+const permissions = 0o755;
+const mask = 0xffff;
+const million = 1000000;
+export { flags, mask, million, negativeHex, padded, permissions };
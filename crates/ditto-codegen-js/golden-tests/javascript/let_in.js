@@ -0,0 +1,21 @@
+function curried(a) {
+  return b => {
+    const both = [a, b];
+    return both;
+  };
+}
+function wrap(n) {
+  return (n => {
+    return n;
+  })([n]);
+}
+function greet(firstName) {
+  const greeting = "hello";
+  const words = [greeting, firstName];
+  return words;
+}
+const five = (() => {
+  const n = 5;
+  return n;
+})();
+export { curried, five, greet, wrap };
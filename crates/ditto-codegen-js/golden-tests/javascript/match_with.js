@@ -0,0 +1,17 @@
+function Just($0) {
+  return ["Just", $0];
+}
+const Nothing = ["Nothing"];
+function Person($0, $1) {
+  return { $: "Person", name: $0, age: $1 };
+}
+function nameOr(maybePerson, fallback) {
+  return ($match => {
+    if ($match[0] === "Just" && $match[1].$ === "Person") {
+      const name = $match[1].name;
+      return name;
+    }
+    return fallback;
+  })(maybePerson);
+}
+export { Just, Nothing, Person, nameOr };
@@ -0,0 +1,16 @@
+function negateSum(a, b) {
+  return -(a + b);
+}
+function sub(x) {
+  return x - -1;
+}
+function twice(x) {
+  return -(-x);
+}
+function negate(x) {
+  return -x;
+}
+const padded = -7;
+const minusHalf = -0.5;
+const minusFive = -5;
+export { minusFive, minusHalf, negate, negateSum, padded, sub, twice };
@@ -0,0 +1,8 @@
+import { addFive as Data$Stuff$addFive } from "Data.Stuff";
+const adder = Data$Stuff$addFive;
+const fifteen = Data$Stuff$addFive(5 + 5);
+function plusFive(n) {
+  return n + 5;
+}
+const ten = 5 + 5;
+export { adder, fifteen, plusFive, ten };
@@ -0,0 +1,6 @@
+import { Just as Data$Stuff$just, seed as Data$Stuff$seed } from "Data.Stuff";
+const seeded = [Data$Stuff$seed, 5];
+const maybeFive = Data$Stuff$just(5);
+const greeting = "five";
+const ten = [5, 5];
+export { greeting, maybeFive, seeded, ten };
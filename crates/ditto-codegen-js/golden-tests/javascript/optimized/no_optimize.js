@@ -0,0 +1,4 @@
+import { addFive as Data$Stuff$addFive, five as Data$Stuff$five } from "Data.Stuff";
+const kept = true ? Data$Stuff$addFive(Data$Stuff$five) : 0;
+const folded = 5 + 5;
+export { folded, kept };
@@ -0,0 +1,4 @@
+import { addFive as Data$Stuff$addFive, five as Data$Stuff$five } from "Data.Stuff";
+const alsoKept = Data$Stuff$five;
+const kept = true ? Data$Stuff$addFive(Data$Stuff$five) : 0;
+export { alsoKept, kept };
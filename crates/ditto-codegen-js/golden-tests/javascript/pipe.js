@@ -0,0 +1,8 @@
+function double(n) {
+  return n * 2;
+}
+function increment(n) {
+  return n + 1;
+}
+const result = double(increment(5));
+export { double, increment, result };
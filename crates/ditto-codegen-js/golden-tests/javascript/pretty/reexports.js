@@ -0,0 +1,3 @@
+export { Five, Just, Nothing, addFive, five, fiveString, id, justFive, seed } from "Data.Stuff";
+const answer = 42;
+export { answer };
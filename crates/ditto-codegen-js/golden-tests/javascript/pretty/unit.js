@@ -0,0 +1,4 @@
+function checkUnit(a) {
+  return { same: a === undefined, value: undefined };
+}
+export { checkUnit };
@@ -0,0 +1,6 @@
+function getName(p) {
+  return p.name;
+}
+const person = { address: { city: "Paris" }, name: "Alice" };
+const city = person.address.city;
+export { city, getName, person };
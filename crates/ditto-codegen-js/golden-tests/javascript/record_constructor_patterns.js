@@ -0,0 +1,75 @@
+const Admin = ["Admin"];
+function Circle($0) {
+  return { $: "Circle", radius: $0 };
+}
+const Guest = ["Guest"];
+function Just($0) {
+  return ["Just", $0];
+}
+const Nothing = ["Nothing"];
+function Person($0, $1) {
+  return { $: "Person", name: $0, role: $1 };
+}
+function Rectangle($0, $1) {
+  return { $: "Rectangle", width: $0, height: $1 };
+}
+const aliceName = Person("Alice", Guest).name;
+function roleOf(person) {
+  return person.role;
+}
+function nickname(maybePerson) {
+  return ($match => {
+    if (
+      $match[0] === "Just" &&
+      $match[1].$ === "Person" &&
+      $match[1].role[0] === "Admin"
+    ) {
+      return "admin";
+    }
+    if ($match[0] === "Just" && $match[1].$ === "Person") {
+      const name = $match[1].name;
+      return name;
+    }
+    if ($match[0] === "Nothing") {
+      return "nobody";
+    }
+    throw new Error("Pattern match error");
+  })(maybePerson);
+}
+function area(shape) {
+  return ($match => {
+    if ($match.$ === "Circle") {
+      const r = $match.radius;
+      return r * r;
+    }
+    if ($match.$ === "Rectangle") {
+      const w = $match.width;
+      const h = $match.height;
+      return w * h;
+    }
+    throw new Error("Pattern match error");
+  })(shape);
+}
+function nameOf(person) {
+  return ($match => {
+    if ($match.$ === "Person") {
+      const name = $match.name;
+      return name;
+    }
+    throw new Error("Pattern match error");
+  })(person);
+}
+export {
+  Admin,
+  Circle,
+  Guest,
+  Just,
+  Nothing,
+  Person,
+  Rectangle,
+  aliceName,
+  area,
+  nameOf,
+  nickname,
+  roleOf,
+};
@@ -0,0 +1,11 @@
+function Circle($0) {
+  return { $: "Circle", radius: $0 };
+}
+function Person($0, $1) {
+  return { $: "Person", name: $0, age: $1 };
+}
+function Square($0) {
+  return ["Square", $0];
+}
+const people = [Person("Alice", 30), Person("Bob", 25)];
+export { Circle, Person, Square };
@@ -0,0 +1,6 @@
+function getPerson(name, age) {
+  return { age: age, name: name };
+}
+const nested = { inner: { values: [1, 2] } };
+const person = { age: 30, name: "Alice" };
+export { getPerson, nested, person };
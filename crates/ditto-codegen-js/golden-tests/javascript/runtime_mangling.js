@@ -0,0 +1,10 @@
+const $for = 5;
+const $console = "not the console";
+const $undefined = undefined;
+function logUnit() {
+  return ((message, value) => {
+    console.log(message, value);
+    return value;
+  })($console, $undefined);
+}
+export { $console, $for, $undefined, logUnit };
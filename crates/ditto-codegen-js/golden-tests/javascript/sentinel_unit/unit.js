@@ -0,0 +1,4 @@
+function checkUnit(a) {
+  return { same: a === Symbol.for("ditto.unit"), value: Symbol.for("ditto.unit") };
+}
+export { checkUnit };
@@ -0,0 +1,6 @@
+const multiline = "line one\nline two";
+const mixed = 'tab\tnewline\n"\\\u{41}';
+const smiley = "\u{1F600} ok";
+const backslash = "C:\\ditto";
+const quoted = 'she said "hi"';
+export { backslash, mixed, multiline, quoted, smiley };
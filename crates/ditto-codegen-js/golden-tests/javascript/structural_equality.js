@@ -0,0 +1,23 @@
+function $equals(a, b) {
+  if (a === b) {
+    return true;
+  }
+  if (a instanceof Object && b instanceof Object) {
+    const keys = Object.keys(a);
+    return (
+      keys.length === Object.keys(b).length &&
+      keys.every((key) => $equals(a[key], b[key]))
+    );
+  }
+  return false;
+}
+function Just($0) {
+  return ["Just", $0];
+}
+const Nothing = ["Nothing"];
+const sameInts = 1 === 1;
+const differentArrays = !$equals([1, 2], [1, 3]);
+function sameMaybe(a, b) {
+  return $equals(a, b);
+}
+export { Just, Nothing, differentArrays, sameInts, sameMaybe };
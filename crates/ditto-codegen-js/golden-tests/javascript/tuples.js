@@ -0,0 +1,24 @@
+function Just($0) {
+  return ["Just", $0];
+}
+const Nothing = ["Nothing"];
+function firstJust(tuple) {
+  return ($match => {
+    if ($match[0][0] === "Just") {
+      const a = $match[0][1];
+      return Just(a);
+    }
+    const b = $match[1];
+    return b;
+  })(tuple);
+}
+function swap(tuple) {
+  return ($match => {
+    const a = $match[0];
+    const b = $match[1];
+    return [b, a];
+  })(tuple);
+}
+const single = [true];
+const pair = [1, "one"];
+export { Just, Nothing, firstJust, pair, single, swap };
@@ -0,0 +1,8 @@
+function third(_$0, _$1, z) {
+  return z;
+}
+function always(x, _$0) {
+  return x;
+}
+const five = always(5, undefined);
+export { always, five, third };
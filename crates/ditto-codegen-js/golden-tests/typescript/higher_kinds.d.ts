@@ -1,6 +1,8 @@
-export declare type HigherKinded<T0, T1> = ["HigherKinded", any];
-export declare function HigherKinded<T2>($0: any): HigherKinded<any, T2>;
-export declare type HigherKindedInt<T0> = ["HigherKindedInt", any];
-export declare function HigherKindedInt($0: any): HigherKindedInt<any>;
-export declare const hkArray: HigherKinded<any, number>;
-export declare const hkiArray: HigherKindedInt<any>;
+export declare type HigherKinded<T0, T2> = ["HigherKinded", unknown];
+export declare function HigherKinded<T2>(
+  $0: unknown,
+): HigherKinded<unknown, T2>;
+export declare type HigherKindedInt<T0> = ["HigherKindedInt", unknown];
+export declare function HigherKindedInt($0: unknown): HigherKindedInt<unknown>;
+export declare const hkArray: HigherKinded<unknown, number>;
+export declare const hkiArray: HigherKindedInt<unknown>;
@@ -1,5 +1,5 @@
-import * as Data$Stuff from "Data.Stuff";
-import * as test_stuff$Data$Stuff from "test-stuff/Data.Stuff";
+import type * as Data$Stuff from "Data.Stuff";
+import type * as test_stuff$Data$Stuff from "test-stuff/Data.Stuff";
 export declare type MyFive = [
   "MyFive",
   Data$Stuff.Five,
@@ -0,0 +1,2 @@
+export declare type User = { $: "User"; name: string; age: number };
+export declare function User($0: string, $1: number): User;
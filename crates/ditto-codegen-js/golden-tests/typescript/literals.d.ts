@@ -1,3 +1,4 @@
+export declare const character: string;
 export declare const emptyArray: Array<never>;
 export declare const float: number;
 export declare const int: number;
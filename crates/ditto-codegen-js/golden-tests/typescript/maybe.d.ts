@@ -0,0 +1,7 @@
+export declare function Just<T0>($0: T0): Maybe<T0>;
+export declare type Maybe<T0> = ["Just", T0] | ["Nothing"];
+export declare const Nothing: Maybe<never>;
+export declare function isJust($0: Maybe<number>): boolean;
+export declare const justFive: Maybe<number>;
+export declare const nested: Maybe<Maybe<boolean>>;
+export declare function withDefault<T0>($0: Maybe<T0>, $1: T0): T0;
@@ -0,0 +1,7 @@
+export declare function Circle($0: number): Shape;
+export declare type Person = { $: "Person"; name: string; age: number };
+export declare function Person($0: string, $1: number): Person;
+export declare type Shape =
+  | { $: "Circle"; radius: number }
+  | ["Square", number];
+export declare function Square($0: number): Shape;
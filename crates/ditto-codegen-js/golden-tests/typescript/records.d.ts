@@ -0,0 +1,2 @@
+export declare function getName<T0>($0: { name: T0 }): { name: T0 };
+export declare const person: { age: number; name: string };
@@ -0,0 +1,3 @@
+export declare function first<T0>($0: [T0, number]): T0;
+export declare const nested: [[boolean], Array<number>];
+export declare const pair: [number, string];
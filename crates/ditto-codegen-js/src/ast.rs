@@ -1,7 +1,14 @@
+use ditto_ast::Span;
+
 pub struct Module {
+    /// A comment to render before anything else.
+    pub banner: Option<String>,
     pub imports: Vec<ImportStatement>,
-    pub statements: Vec<ModuleStatement>,
+    /// Top-level statements, with the span of the ditto code they came from (if any).
+    pub statements: Vec<(ModuleStatement, Option<Span>)>,
     pub exports: Vec<Ident>,
+    /// Exports that are forwarded from other modules.
+    pub reexports: Vec<ReexportStatement>,
 }
 
 /// <https://developer.mozilla.org/en-US/docs/Glossary/Identifier>
@@ -22,6 +29,14 @@ pub struct ImportStatement {
     pub path: String,
 }
 
+/// ```javascript
+/// export { foo, bar } from "./Some/Module.js";
+/// ```
+pub struct ReexportStatement {
+    pub idents: Vec<Ident>,
+    pub path: String,
+}
+
 pub enum ModuleStatement {
     /// ```javascript
     /// const ident = expression
@@ -43,26 +58,45 @@ pub enum ModuleStatement {
         parameters: Vec<Ident>,
         body: Block,
     },
+    /// ```javascript
+    /// if (condition) { block }
+    /// ```
+    If { condition: Expression, block: Block },
 }
 
 /// A bunch of statements surrounded by braces.
+#[derive(Clone)]
 pub struct Block(pub Vec<BlockStatement>);
 
 /// A single JavaScript statement.
 ///
 /// These end with a semicolon.
+#[derive(Clone)]
 pub enum BlockStatement {
     /// ```javascript
     /// const ident = expression;
     /// ```
-    _ConstAssignment { ident: Ident, value: Expression },
+    ConstAssignment { ident: Ident, value: Expression },
+    /// ```javascript
+    /// if (condition) { block }
+    /// ```
+    If { condition: Expression, block: Block },
     /// ```javascript
     /// return bar;
     /// return;
     /// ```
     Return(Option<Expression>),
+    /// ```javascript
+    /// throw new Error("message");
+    /// ```
+    Throw(String),
+    /// ```javascript
+    /// console.log(message);
+    /// ```
+    Expression(Expression),
 }
 
+#[derive(Clone)]
 pub enum Expression {
     /// `true`
     True,
@@ -98,11 +132,51 @@ pub enum Expression {
         false_clause: Box<Expression>,
     },
     /// ```javascript
+    /// lhs && rhs
+    /// lhs === rhs
+    /// lhs + rhs
+    /// ```
+    Operator {
+        op: Operator,
+        lhs: Box<Expression>,
+        rhs: Box<Expression>,
+    },
+    /// ```javascript
+    /// !operand
+    /// ```
+    Not(Box<Expression>),
+    /// ```javascript
+    /// -operand
+    /// ```
+    Negate(Box<Expression>),
+    /// ```javascript
+    /// typeof operand
+    /// ```
+    TypeOf(Box<Expression>),
+    /// ```javascript
+    /// target[index]
+    /// ```
+    IndexAccess {
+        target: Box<Expression>,
+        index: Box<Expression>,
+    },
+    /// ```javascript
+    /// target.property
+    /// ```
+    PropertyAccess {
+        target: Box<Expression>,
+        property: String,
+    },
+    /// ```javascript
     /// []
     /// [5, 5, 5]
     /// ```
     Array(Vec<Expression>),
     /// ```javascript
+    /// { $: "Person", name: "x", age: 5 }
+    /// ```
+    Object(Vec<(String, Expression)>),
+    /// ```javascript
     /// 5
     /// 5.0
     /// ```
@@ -115,9 +189,54 @@ pub enum Expression {
     /// undefined
     /// ```
     Undefined,
+    /// Inline JavaScript, which is emitted verbatim.
+    ///
+    /// ```javascript
+    /// (Date.now())
+    /// ```
+    Raw(String),
+}
+
+/// A binary operator.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    /// `&&`
+    And,
+    /// `===`
+    Equals,
+    /// `+`
+    Add,
+    /// `-`
+    Subtract,
+    /// `*`
+    Multiply,
+    /// `/`
+    Divide,
+    /// `!==`
+    NotEquals,
+    /// `<`
+    LessThan,
+    /// `<=`
+    LessThanOrEquals,
+    /// `>`
+    GreaterThan,
+    /// `>=`
+    GreaterThanOrEquals,
+    /// `instanceof`
+    InstanceOf,
+    /// `||`
+    Or,
+}
+
+impl Operator {
+    /// Whether `a op (b op c)` is equivalent to `(a op b) op c`.
+    pub fn is_associative(&self) -> bool {
+        matches!(self, Self::And | Self::Or)
+    }
 }
 
 /// The _body_ of an arrow function.
+#[derive(Clone)]
 pub enum ArrowFunctionBody {
     /// ```javascript
     /// () => expression;
@@ -126,5 +245,5 @@ pub enum ArrowFunctionBody {
     /// ```javascript
     /// () => { block }
     /// ```
-    _Block(Block),
+    Block(Block),
 }
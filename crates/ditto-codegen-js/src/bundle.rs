@@ -0,0 +1,241 @@
+use crate::{
+    ast::{Ident, ImportStatement, Module, ReexportStatement},
+    convert::{self, Config, UnitRepresentation},
+    render::{Render, NEWLINE},
+};
+use ditto_ast::{FullyQualifiedModuleName, PackageName};
+use std::{cell::RefCell, collections::HashMap, fmt, rc::Rc};
+
+/// Settings that apply to every module in a bundle.
+pub struct BundleConfig {
+    /// The package being bundled.
+    ///
+    /// The bundle is assigned to a global variable named after this package,
+    /// e.g. `bundleProject` for `bundle-project`.
+    pub package_name: PackageName,
+    /// Remove `debug_log` calls, so that nothing is logged at runtime.
+    pub strip_debug: bool,
+    /// Simplify expressions whose outcome is known at compile time.
+    pub optimize: bool,
+    /// Check that foreign values are present when the bundle is loaded.
+    pub runtime_checks: bool,
+    /// A comment to put at the top of the bundle, without the `//`.
+    pub banner: Option<String>,
+    /// How `unit` is represented at runtime.
+    pub unit: UnitRepresentation,
+}
+
+/// A module to be included in a bundle.
+pub struct BundleModule {
+    /// The package and name of the module.
+    pub module_name: FullyQualifiedModuleName,
+    /// The checked module.
+    pub module: ditto_ast::Module,
+    /// Location of the foreign module, relative to the bundle.
+    pub foreign_module_path: String,
+}
+
+/// Reasons a bundle can't be generated.
+#[derive(Debug)]
+pub enum BundleError {
+    /// A module imports a module that wasn't given to [bundle] before it.
+    ModuleNotInBundle {
+        /// The module doing the importing.
+        importing_module: FullyQualifiedModuleName,
+        /// The missing module.
+        imported_module: FullyQualifiedModuleName,
+    },
+}
+
+impl fmt::Display for BundleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ModuleNotInBundle {
+                importing_module,
+                imported_module,
+            } => write!(
+                f,
+                "{} imports {}, which isn't in the bundle",
+                display_module_name(importing_module),
+                display_module_name(imported_module)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BundleError {}
+
+fn display_module_name((package_name, module_name): &FullyQualifiedModuleName) -> String {
+    match package_name {
+        Some(package_name) => format!("{}:{}", package_name, module_name),
+        None => module_name.to_string(),
+    }
+}
+
+/// Generate a single JavaScript script containing all of the given ditto modules.
+///
+/// Modules are expected to be topologically sorted, i.e. a module should appear
+/// _after_ all of the modules it imports.
+///
+/// Each module is scoped to its own IIFE, and imports between bundled modules
+/// are resolved to local references. The bundle itself is an async IIFE, so it
+/// has no top-level `import` or `export` and can be loaded as a classic script.
+/// Foreign modules are loaded with a dynamic `import()`, relative to the bundle.
+///
+/// The IIFE's promise is assigned to a global variable named after the package
+/// (see [BundleConfig::package_name]), and resolves to an object containing the
+/// modules of the current package, keyed by module name.
+pub fn bundle(
+    modules: Vec<BundleModule>,
+    bundle_config: &BundleConfig,
+) -> Result<String, BundleError> {
+    // Modules are added as they're bundled, so importing a module that appears
+    // later (or not at all) is caught too
+    let mut module_idents: HashMap<FullyQualifiedModuleName, Ident> = HashMap::new();
+    let mut foreign_imports: Vec<(String, Ident)> = Vec::new();
+    let mut module_scopes = Vec::new();
+    let mut package_modules = Vec::new();
+    // Modules come after their imports, so the constants they use are known by the time
+    let mut inline_values = convert::InlineValues::new();
+
+    for (i, bundle_module) in modules.into_iter().enumerate() {
+        let BundleModule {
+            module_name,
+            module,
+            foreign_module_path,
+        } = bundle_module;
+        let exported_inline_values =
+            convert::exported_inline_values(&module_name, &module.exports).collect::<Vec<_>>();
+
+        let missing_module = Rc::new(RefCell::new(None));
+        let config = Config {
+            // Internal imports are "imported" from the module's scope identifier
+            module_name_to_path: {
+                let module_idents = module_idents.clone();
+                let missing_module = Rc::clone(&missing_module);
+                Box::new(move |module_name| match module_idents.get(&module_name) {
+                    Some(ident) => ident.0.clone(),
+                    None => {
+                        // Reported once the module has been converted
+                        missing_module.borrow_mut().get_or_insert(module_name);
+                        String::new()
+                    }
+                })
+            },
+            foreign_module_path,
+            strip_debug: bundle_config.strip_debug,
+            optimize: bundle_config.optimize,
+            inline_values: inline_values.clone(),
+            runtime_checks: bundle_config.runtime_checks,
+            // The bundle gets a single banner
+            banner: None,
+            // Imports are rewritten as local references, so the format doesn't matter
+            module_format: convert::ModuleFormat::Esm,
+            unit: bundle_config.unit,
+            pretty: false,
+            source_map: None,
+        };
+        let Module {
+            imports,
+            statements,
+            exports,
+            reexports,
+            ..
+        } = convert::convert_module(&config, module);
+        if let Some(imported_module) = missing_module.take() {
+            return Err(BundleError::ModuleNotInBundle {
+                importing_module: module_name,
+                imported_module,
+            });
+        }
+
+        let module_ident = mk_module_ident(i);
+        let mut scope = format!("const {} = (() => {{", module_ident.0);
+        scope.push_str(NEWLINE);
+        for ImportStatement { idents, path } in imports {
+            let source = if path.starts_with(MODULE_IDENT_PREFIX) {
+                path
+            } else if let Some((_, ident)) = foreign_imports.iter().find(|(p, _)| *p == path) {
+                ident.0.clone()
+            } else {
+                let ident = Ident(format!("$foreign{}", foreign_imports.len()));
+                foreign_imports.push((path, ident.clone()));
+                ident.0
+            };
+            for (aliased, ident) in idents {
+                scope.push_str(&format!("const {} = {}.{};", ident.0, source, aliased.0));
+                scope.push_str(NEWLINE);
+            }
+        }
+        for (statement, _span) in statements {
+            statement.render(&mut scope);
+            scope.push_str(NEWLINE);
+        }
+        scope.push_str("return {");
+        scope.push_str(
+            &exports
+                .iter()
+                .map(|ident| ident.0.clone())
+                // Re-exported modules are always part of the bundle
+                .chain(
+                    reexports
+                        .iter()
+                        .flat_map(|ReexportStatement { idents, path }| {
+                            idents
+                                .iter()
+                                .map(move |ident| format!("{0}:{1}.{0}", ident.0, path))
+                        }),
+                )
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+        scope.push_str("};");
+        scope.push_str(NEWLINE);
+        scope.push_str("})();");
+        module_scopes.push(scope);
+
+        module_idents.insert(module_name.clone(), module_ident.clone());
+        inline_values.extend(exported_inline_values);
+        if let (None, module_name) = module_name {
+            package_modules.push((module_name.into_string("."), module_ident));
+        }
+    }
+
+    let mut accum = String::new();
+    if let Some(ref banner) = bundle_config.banner {
+        for line in banner.lines() {
+            accum.push_str("// ");
+            accum.push_str(line);
+            accum.push_str(NEWLINE);
+        }
+    }
+    // `var` so that the bundle is reachable as a property of the global object
+    accum.push_str(&format!(
+        "var {} = (async () => {{",
+        convert::name_string_to_ident_string(bundle_config.package_name.0.clone())
+    ));
+    accum.push_str(NEWLINE);
+    for (path, ident) in foreign_imports {
+        accum.push_str(&format!("const {} = await import(\"{}\");", ident.0, path));
+        accum.push_str(NEWLINE);
+    }
+    for scope in module_scopes {
+        accum.push_str(&scope);
+        accum.push_str(NEWLINE);
+    }
+    accum.push_str("return {");
+    for (module_name, module_ident) in package_modules {
+        accum.push_str(&format!("\"{}\":{},", module_name, module_ident.0));
+    }
+    accum.push_str("};");
+    accum.push_str(NEWLINE);
+    accum.push_str("})();");
+    accum.push_str(NEWLINE);
+    Ok(accum)
+}
+
+static MODULE_IDENT_PREFIX: &str = "$module";
+
+fn mk_module_ident(i: usize) -> Ident {
+    Ident(format!("{}{}", MODULE_IDENT_PREFIX, i))
+}
@@ -1,9 +1,13 @@
 use crate::ast::{
-    ArrowFunctionBody, Block, BlockStatement, Expression, Ident, ImportStatement, Module,
-    ModuleStatement,
+    ident, ArrowFunctionBody, Block, BlockStatement, Expression, Ident, ImportStatement, Module,
+    ModuleStatement, Operator, ReexportStatement,
 };
 use convert_case::{Case, Casing};
-use ditto_ast::graph::Scc;
+use ditto_ast::{
+    fold::{self, Folder},
+    graph::Scc,
+    visit::{self, Visitor},
+};
 use lazy_static::lazy_static;
 use std::{
     collections::{HashMap, HashSet},
@@ -16,28 +20,154 @@ pub struct Config {
     pub module_name_to_path: Box<dyn Fn(ditto_ast::FullyQualifiedModuleName) -> String>,
     /// Location of the foreign module.
     pub foreign_module_path: String,
+    /// Remove `debug_log` calls, so that nothing is logged at runtime.
+    pub strip_debug: bool,
+    /// Simplify expressions whose outcome is known at compile time,
+    /// e.g. `if true then a else b`, unless the module or value is declared
+    /// `@no_optimize`.
+    pub optimize: bool,
+    /// Definitions of small constants exported by other modules, which replace
+    /// references to them when optimizing.
+    pub inline_values: InlineValues,
+    /// Check that foreign values are present (and that functions are functions)
+    /// when the module is loaded.
+    pub runtime_checks: bool,
+    /// A comment to put at the top of the module, without the `//`.
+    pub banner: Option<String>,
+    /// The module system to generate imports and exports for.
+    pub module_format: ModuleFormat,
+    /// How `unit` is represented at runtime.
+    pub unit: UnitRepresentation,
+    /// Lay out the generated code over indented lines, so that it's readable
+    /// without an external formatter.
+    pub pretty: bool,
+    /// Source map settings, required by [codegen_with_sourcemap](crate::codegen_with_sourcemap).
+    pub source_map: Option<SourceMapConfig>,
 }
 
-pub fn convert_module(config: &Config, ast_module: ditto_ast::Module) -> Module {
+/// Inlinable values by their canonical name, see
+/// [ModuleExportsValue::inline_body](ditto_ast::ModuleExportsValue::inline_body).
+pub type InlineValues = HashMap<ditto_ast::FullyQualifiedName, ditto_ast::Expression>;
+
+/// Collect the inlinable values exported by `module_name`, to add to [InlineValues].
+pub fn exported_inline_values<'a>(
+    module_name: &'a ditto_ast::FullyQualifiedModuleName,
+    exports: &'a ditto_ast::ModuleExports,
+) -> impl Iterator<Item = (ditto_ast::FullyQualifiedName, ditto_ast::Expression)> + 'a {
+    exports.values.iter().filter_map(move |(name, value)| {
+        let body = value.inline_body.clone()?;
+        let name = ditto_ast::FullyQualifiedName {
+            module_name: module_name.clone(),
+            value: name.clone(),
+        };
+        Some((name, body))
+    })
+}
+
+/// Where a generated module and its ditto source live, for source maps.
+pub struct SourceMapConfig {
+    /// File name of the generated JavaScript, e.g. `Data.Maybe.js`.
+    pub file: String,
+    /// Path to the ditto source, relative to the source map.
+    pub source_path: String,
+    /// The ditto source itself, needed to map byte offsets to lines and columns.
+    pub source: String,
+}
+
+/// How generated modules import and export values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModuleFormat {
+    /// ES modules, using `import` and `export` statements.
+    Esm,
+    /// CommonJS modules, using `require(...)` and `module.exports`.
+    Cjs,
+}
+
+/// How `unit` values look to JavaScript.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnitRepresentation {
+    /// `undefined`, which is what functions without a `return` produce.
+    Undefined,
+    /// `Symbol.for("ditto.unit")`, which is shared between every module (and
+    /// every package) without needing a runtime module to import it from.
+    Sentinel,
+}
+
+pub fn convert_module(config: &Config, mut ast_module: ditto_ast::Module) -> Module {
+    if config.strip_debug {
+        ast_module.values = ast_module
+            .values
+            .into_iter()
+            .map(|(name, mut module_value)| {
+                module_value.expression = StripDebugLogs.fold_expression(module_value.expression);
+                (name, module_value)
+            })
+            .collect();
+    }
+
+    // `@no_optimize` modules and values are generated as written
+    if config.optimize && !ast_module.no_optimize {
+        ast_module.values = ast_module
+            .values
+            .into_iter()
+            .map(|(name, mut module_value)| {
+                if module_value.attributes.no_optimize {
+                    return (name, module_value);
+                }
+                // Inline first, so that the inlined values can be simplified
+                let expression =
+                    InlineImports(&config.inline_values).fold_expression(module_value.expression);
+                module_value.expression = Simplify.fold_expression(expression);
+                (name, module_value)
+            })
+            .collect();
+    }
+
     let mut statements = Vec::new();
 
+    if config.runtime_checks {
+        statements.extend(
+            foreign_value_checks(&ast_module)
+                .into_iter()
+                .map(|check| (check, None)),
+        );
+    }
+
+    if ast_module
+        .values
+        .values()
+        .any(|module_value| uses_structural_equality(&module_value.expression))
+    {
+        statements.push((structural_equality_helper(), None));
+    }
+
+    if ast_module
+        .values
+        .values()
+        .any(|module_value| uses_int_div(&module_value.expression))
+    {
+        statements.push((int_div_helper(), None));
+    }
+
     let mut constructors = ast_module
         .constructors
         .clone()
         .into_iter()
         .collect::<Vec<_>>();
 
-    // Sort for determinism in tests
-    if cfg!(debug_assertions) {
-        constructors.sort_by(|a, b| a.0.cmp(&b.0));
-    }
+    // Sort for determinism
+    constructors.sort_by(|a, b| a.0.cmp(&b.0));
 
     for (proper_name, module_constructor) in constructors {
+        let span = Some(module_constructor.constructor_name_span);
         if module_constructor.fields.is_empty() {
-            statements.push(ModuleStatement::ConstAssignment {
-                ident: Ident::from(proper_name.clone()),
-                value: Expression::Array(vec![Expression::String(proper_name.0)]),
-            });
+            statements.push((
+                ModuleStatement::ConstAssignment {
+                    ident: Ident::from(proper_name.clone()),
+                    value: Expression::Array(vec![Expression::String(proper_name.0)]),
+                },
+                span,
+            ));
         } else {
             let field_idents = module_constructor
                 .fields
@@ -46,19 +176,41 @@ pub fn convert_module(config: &Config, ast_module: ditto_ast::Module) -> Module
                 .map(|(i, _type)| Ident(format!("${}", i)))
                 .collect::<Vec<_>>();
 
-            let mut elements = vec![Expression::String(proper_name.0.clone())];
-            elements.extend(field_idents.clone().into_iter().map(Expression::Variable));
-
-            let return_expr = Expression::Array(elements);
+            let return_expr = if let Some(field_names) = module_constructor.field_names {
+                // Constructors with named fields are represented as objects,
+                // with the constructor name stored under `$`.
+                let mut properties =
+                    vec![(String::from("$"), Expression::String(proper_name.0.clone()))];
+                properties.extend(
+                    field_names
+                        .into_iter()
+                        .zip(field_idents.clone())
+                        .map(|(field_name, ident)| (field_name.0, Expression::Variable(ident))),
+                );
+                Expression::Object(properties)
+            } else {
+                let mut elements = vec![Expression::String(proper_name.0.clone())];
+                elements.extend(field_idents.clone().into_iter().map(Expression::Variable));
+                Expression::Array(elements)
+            };
 
-            statements.push(ModuleStatement::Function {
-                ident: Ident::from(proper_name),
-                parameters: field_idents,
-                body: Block(vec![BlockStatement::Return(Some(return_expr))]),
-            });
+            statements.push((
+                ModuleStatement::Function {
+                    ident: Ident::from(proper_name),
+                    parameters: field_idents,
+                    body: Block(vec![BlockStatement::Return(Some(return_expr))]),
+                },
+                span,
+            ));
         }
     }
 
+    let value_spans = ast_module
+        .values
+        .iter()
+        .map(|(name, module_value)| (name.clone(), module_value.name_span))
+        .collect::<HashMap<_, _>>();
+
     let mut imported_idents = ImportedIdentReferences::new();
 
     for scc in ast_module.values_toposorted().into_iter() {
@@ -79,18 +231,21 @@ pub fn convert_module(config: &Config, ast_module: ditto_ast::Module) -> Module
                             body,
                         } = ast_expression
                         {
-                            statements.push(ModuleStatement::Function {
-                                ident: Ident::from(name),
-                                parameters: binders
-                                    .into_iter()
-                                    .map(|binder| match binder {
-                                        ditto_ast::FunctionBinder::Name { value, .. } => {
-                                            Ident::from(value)
-                                        }
-                                    })
-                                    .collect(),
-                                body: convert_expression_to_block(&mut imported_idents, *body),
-                            });
+                            let parameters = convert_function_binders(binders);
+                            let span = value_spans.get(&name).copied();
+                            statements.push((
+                                ModuleStatement::Function {
+                                    ident: Ident::from(name),
+                                    body: convert_expression_to_block(
+                                        &mut imported_idents,
+                                        config.unit,
+                                        parameters.iter().cloned().collect(),
+                                        *body,
+                                    ),
+                                    parameters,
+                                },
+                                span,
+                            ));
                         } else {
                             panic!("i can't believe you've done this")
                         }
@@ -98,49 +253,73 @@ pub fn convert_module(config: &Config, ast_module: ditto_ast::Module) -> Module
                 } else {
                     let mut assignments = Vec::new();
                     for (name, ast_expression) in cyclic {
-                        statements.push(ModuleStatement::LetDeclaration {
-                            ident: Ident::from(name.clone()),
-                        });
-                        assignments.push(ModuleStatement::Assignment {
-                            ident: Ident::from(name),
-                            value: convert_expression(&mut imported_idents, ast_expression),
-                        });
+                        let span = value_spans.get(&name).copied();
+                        statements.push((
+                            ModuleStatement::LetDeclaration {
+                                ident: Ident::from(name.clone()),
+                            },
+                            span,
+                        ));
+                        assignments.push((
+                            ModuleStatement::Assignment {
+                                ident: Ident::from(name),
+                                value: convert_expression(
+                                    &mut imported_idents,
+                                    config.unit,
+                                    ast_expression,
+                                ),
+                            },
+                            span,
+                        ));
                     }
                     statements.extend(assignments);
                 }
             }
-            Scc::Acyclic((name, ast_expression)) => match ast_expression {
-                ditto_ast::Expression::Function {
-                    span: _,
-                    binders,
-                    body,
-                } => {
-                    statements.push(ModuleStatement::Function {
-                        ident: Ident::from(name),
-                        parameters: binders
-                            .into_iter()
-                            .map(|binder| match binder {
-                                ditto_ast::FunctionBinder::Name { value, .. } => Ident::from(value),
-                            })
-                            .collect(),
-                        body: convert_expression_to_block(&mut imported_idents, *body),
-                    });
+            Scc::Acyclic((name, ast_expression)) => {
+                let span = value_spans.get(&name).copied();
+                match ast_expression {
+                    ditto_ast::Expression::Function {
+                        span: _,
+                        binders,
+                        body,
+                    } => {
+                        let parameters = convert_function_binders(binders);
+                        statements.push((
+                            ModuleStatement::Function {
+                                ident: Ident::from(name),
+                                body: convert_expression_to_block(
+                                    &mut imported_idents,
+                                    config.unit,
+                                    parameters.iter().cloned().collect(),
+                                    *body,
+                                ),
+                                parameters,
+                            },
+                            span,
+                        ));
+                    }
+                    _ => statements.push((
+                        ModuleStatement::ConstAssignment {
+                            ident: Ident::from(name),
+                            value: convert_expression(
+                                &mut imported_idents,
+                                config.unit,
+                                ast_expression,
+                            ),
+                        },
+                        span,
+                    )),
                 }
-                _ => statements.push(ModuleStatement::ConstAssignment {
-                    ident: Ident::from(name),
-                    value: convert_expression(&mut imported_idents, ast_expression),
-                }),
-            },
+            }
         }
     }
 
     let mut imports = imported_idents
         .into_iter()
         .map(|(imported_module, mut idents)| {
-            if cfg!(debug_assertions) {
-                // Sort for determinism
-                idents.sort_by(|a, b| a.0 .0.cmp(&b.0 .0));
-            }
+            // Sort for determinism, and import each reference once
+            idents.sort();
+            idents.dedup();
             ImportStatement {
                 path: match imported_module {
                     ImportedModule::Module(module_name) => {
@@ -153,28 +332,58 @@ pub fn convert_module(config: &Config, ast_module: ditto_ast::Module) -> Module
         })
         .collect::<Vec<_>>();
 
-    if cfg!(debug_assertions) {
-        // Sort for determinism
-        imports.sort_by(|a, b| a.path.cmp(&b.path));
-    }
+    // Sort for determinism
+    imports.sort_by(|a, b| a.path.cmp(&b.path));
 
-    let mut exports = ast_module
+    let mut exports = Vec::new();
+    let mut reexported_idents: HashMap<ditto_ast::FullyQualifiedModuleName, Vec<Ident>> =
+        HashMap::new();
+    let exported = ast_module
         .exports
         .values
-        .into_keys()
-        .map(Ident::from)
-        .chain(ast_module.exports.constructors.into_keys().map(Ident::from))
+        .into_iter()
+        .map(|(name, value)| (Ident::from(name), value.reexported_from))
+        .chain(
+            ast_module
+                .exports
+                .constructors
+                .into_iter()
+                .map(|(proper_name, ctor)| (Ident::from(proper_name), ctor.reexported_from)),
+        );
+    for (ident, reexported_from) in exported {
+        match reexported_from {
+            Some(module_name) => reexported_idents
+                .entry(module_name)
+                .or_default()
+                .push(ident),
+            None => exports.push(ident),
+        }
+    }
+
+    // Sort for determinism
+    exports.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut reexports = reexported_idents
+        .into_iter()
+        .map(|(module_name, mut idents)| {
+            // Sort for determinism
+            idents.sort_by(|a, b| a.0.cmp(&b.0));
+            ReexportStatement {
+                path: (config.module_name_to_path)(module_name),
+                idents,
+            }
+        })
         .collect::<Vec<_>>();
 
-    if cfg!(debug_assertions) {
-        // Sort for determinism
-        exports.sort_by(|a, b| a.0.cmp(&b.0));
-    }
+    // Sort for determinism
+    reexports.sort_by(|a, b| a.path.cmp(&b.path));
 
     Module {
+        banner: config.banner.clone(),
         imports,
         statements,
         exports,
+        reexports,
     }
 }
 
@@ -189,45 +398,118 @@ enum ImportedModule {
 /// (foo, Some$Module$foo)
 type ImportedIdent = (Ident, Ident);
 
+/// Converts an expression to a block that returns it.
+///
+/// `let` bindings are hoisted into the block as `const` statements, so long as
+/// that doesn't redeclare one of the `bound_idents` of the enclosing function,
+/// or shadow a variable that an earlier statement refers to.
 fn convert_expression_to_block(
     imported_idents: &mut ImportedIdentReferences,
-    ast_expression: ditto_ast::Expression,
+    unit: UnitRepresentation,
+    mut bound_idents: HashSet<Ident>,
+    mut ast_expression: ditto_ast::Expression,
 ) -> Block {
-    Block(vec![BlockStatement::Return(Some(convert_expression(
-        imported_idents,
-        ast_expression,
-    )))])
+    let mut statements = Vec::new();
+    let mut mentioned = HashSet::new();
+    loop {
+        match ast_expression {
+            ditto_ast::Expression::Let {
+                name,
+                box expression,
+                box body,
+                ..
+            } if !bound_idents.contains(&Ident::from(name.clone())) && {
+                mentioned.extend(local_variables(&expression));
+                !mentioned.contains(&name)
+            } =>
+            {
+                let ident = Ident::from(name);
+                bound_idents.insert(ident.clone());
+                statements.push(BlockStatement::ConstAssignment {
+                    ident,
+                    value: convert_expression(imported_idents, unit, expression),
+                });
+                ast_expression = body;
+            }
+            _ => {
+                statements.push(BlockStatement::Return(Some(convert_expression(
+                    imported_idents,
+                    unit,
+                    ast_expression,
+                ))));
+                return Block(statements);
+            }
+        }
+    }
+}
+
+fn convert_function_binders(binders: Vec<ditto_ast::FunctionBinder>) -> Vec<Ident> {
+    let mut wildcards = 0;
+    binders
+        .into_iter()
+        .map(|binder| match binder {
+            ditto_ast::FunctionBinder::Name { value, .. } => Ident::from(value),
+            ditto_ast::FunctionBinder::Wildcard { .. } => {
+                // Ditto names can't start with `_`, so this can't collide
+                let ident = Ident(format!("_${}", wildcards));
+                wildcards += 1;
+                ident
+            }
+        })
+        .collect()
 }
 
 fn convert_expression(
     imported_idents: &mut ImportedIdentReferences,
+    unit: UnitRepresentation,
     ast_expression: ditto_ast::Expression,
 ) -> Expression {
     match ast_expression {
-        ditto_ast::Expression::Function { binders, body, .. } => Expression::ArrowFunction {
-            parameters: binders
-                .into_iter()
-                .map(|binder| match binder {
-                    ditto_ast::FunctionBinder::Name { value, .. } => Ident::from(value),
-                })
-                .collect(),
-            body: Box::new(ArrowFunctionBody::Expression(convert_expression(
-                imported_idents,
-                *body,
-            ))),
-        },
+        ditto_ast::Expression::Function { binders, body, .. } => {
+            let parameters = convert_function_binders(binders);
+            let body = if let ditto_ast::Expression::Let { .. } = *body {
+                ArrowFunctionBody::Block(convert_expression_to_block(
+                    imported_idents,
+                    unit,
+                    parameters.iter().cloned().collect(),
+                    *body,
+                ))
+            } else {
+                ArrowFunctionBody::Expression(convert_expression(imported_idents, unit, *body))
+            };
+            Expression::ArrowFunction {
+                parameters,
+                body: Box::new(body),
+            }
+        }
 
+        // Built-in constructors are applied directly, see `maybe_constructor`
+        ditto_ast::Expression::Call {
+            function:
+                box ditto_ast::Expression::PrimConstructor {
+                    constructor: ditto_ast::PrimConstructor::MaybeJust,
+                    ..
+                },
+            mut arguments,
+            ..
+        } if arguments.len() == 1 => match arguments.pop() {
+            Some(ditto_ast::Argument::Expression(value)) => maybe_constructor(
+                ditto_ast::PrimConstructor::MaybeJust,
+                vec![convert_expression(imported_idents, unit, value)],
+            ),
+            None => unreachable!(),
+        },
         ditto_ast::Expression::Call {
             function,
             arguments,
             ..
         } => Expression::Call {
-            function: Box::new(convert_expression(imported_idents, *function)),
+            function: Box::new(convert_expression(imported_idents, unit, *function)),
             arguments: arguments
                 .into_iter()
                 .map(|arg| match arg {
                     ditto_ast::Argument::Expression(expr) => {
-                        convert_expression(imported_idents, expr)
+                        convert_expression(imported_idents, unit, expr)
                     }
                 })
                 .collect(),
@@ -239,18 +521,112 @@ fn convert_expression(
             false_clause,
             ..
         } => Expression::Conditional {
-            condition: Box::new(convert_expression(imported_idents, *condition)),
-            true_clause: Box::new(convert_expression(imported_idents, *true_clause)),
-            false_clause: Box::new(convert_expression(imported_idents, *false_clause)),
+            condition: Box::new(convert_expression(imported_idents, unit, *condition)),
+            true_clause: Box::new(convert_expression(imported_idents, unit, *true_clause)),
+            false_clause: Box::new(convert_expression(imported_idents, unit, *false_clause)),
+        },
+
+        ditto_ast::Expression::Match {
+            expression, arms, ..
+        } => {
+            let matched_ident = ident!("$match");
+            let mut statements = Vec::new();
+            let mut is_irrefutable = false;
+            for (pattern, arm_expression) in arms.iter().cloned() {
+                let mut conditions = Vec::new();
+                let mut arm_statements = Vec::new();
+                convert_pattern(
+                    Expression::Variable(matched_ident.clone()),
+                    pattern,
+                    &mut conditions,
+                    &mut arm_statements,
+                );
+                arm_statements.push(BlockStatement::Return(Some(convert_expression(
+                    imported_idents,
+                    unit,
+                    arm_expression,
+                ))));
+                let condition = conditions
+                    .into_iter()
+                    .reduce(|lhs, rhs| Expression::Operator {
+                        op: Operator::And,
+                        lhs: Box::new(lhs),
+                        rhs: Box::new(rhs),
+                    });
+                if let Some(condition) = condition {
+                    statements.push(BlockStatement::If {
+                        condition,
+                        block: Block(arm_statements),
+                    });
+                } else {
+                    // This arm always matches, so any remaining arms are unreachable
+                    statements.extend(arm_statements);
+                    is_irrefutable = true;
+                    break;
+                }
+            }
+            if !is_irrefutable {
+                statements.push(BlockStatement::Throw(String::from("Pattern match error")));
+            }
+            Expression::Call {
+                function: Box::new(Expression::ArrowFunction {
+                    parameters: vec![matched_ident],
+                    body: Box::new(ArrowFunctionBody::Block(Block(statements))),
+                }),
+                arguments: vec![convert_expression(imported_idents, unit, *expression)],
+            }
+        }
+
+        ditto_ast::Expression::Let {
+            name,
+            box expression,
+            box body,
+            ..
+        } if local_variables(&expression).contains(&name) => {
+            // `const x = [x];` would refer to itself, so bind it as a parameter instead
+            let ident = Ident::from(name);
+            Expression::Call {
+                function: Box::new(Expression::ArrowFunction {
+                    parameters: vec![ident.clone()],
+                    body: Box::new(ArrowFunctionBody::Block(convert_expression_to_block(
+                        imported_idents,
+                        unit,
+                        HashSet::from([ident]),
+                        body,
+                    ))),
+                }),
+                arguments: vec![convert_expression(imported_idents, unit, expression)],
+            }
+        }
+
+        let_expression @ ditto_ast::Expression::Let { .. } => Expression::Call {
+            function: Box::new(Expression::ArrowFunction {
+                parameters: Vec::new(),
+                body: Box::new(ArrowFunctionBody::Block(convert_expression_to_block(
+                    imported_idents,
+                    unit,
+                    HashSet::new(),
+                    let_expression,
+                ))),
+            }),
+            arguments: Vec::new(),
         },
 
         ditto_ast::Expression::LocalVariable { variable, .. } => {
             Expression::Variable(Ident::from(variable))
         }
 
-        ditto_ast::Expression::ForeignVariable { variable, .. } => {
+        ditto_ast::Expression::ForeignVariable {
+            variable,
+            foreign_name,
+            ..
+        } => {
             let module_name = ImportedModule::ForeignModule;
-            let aliased = Ident::from(variable.clone());
+            let aliased = foreign_name
+                // The checker makes sure this is a valid identifier name, and reserved
+                // words are fine as import names, so it's used as written
+                .map(Ident)
+                .unwrap_or_else(|| foreign_import_ident(variable.clone()));
             let ident = mk_foreign_ident(variable.0);
             if let Some(idents) = imported_idents.get_mut(&module_name) {
                 idents.push((aliased, ident.clone()));
@@ -272,9 +648,67 @@ fn convert_expression(
                 Expression::Variable(ident)
             }
         }
+        ditto_ast::Expression::PrimVariable {
+            variable: ditto_ast::PrimValue::FloatInfinity,
+            ..
+        } => Expression::Variable(ident!("Infinity")),
+        ditto_ast::Expression::PrimVariable {
+            variable: ditto_ast::PrimValue::FloatNegInfinity,
+            ..
+        } => Expression::Negate(Box::new(Expression::Variable(ident!("Infinity")))),
+        ditto_ast::Expression::PrimVariable {
+            variable: ditto_ast::PrimValue::FloatNan,
+            ..
+        } => Expression::Variable(ident!("NaN")),
+        ditto_ast::Expression::PrimVariable {
+            variable: ditto_ast::PrimValue::IntDiv,
+            ..
+        } => Expression::Variable(INT_DIV_IDENT.clone()),
+        ditto_ast::Expression::PrimVariable {
+            variable: ditto_ast::PrimValue::DebugLog,
+            ..
+        } => {
+            // (message, value) => { console.log(message, value); return value; }
+            let message = ident!("message");
+            let value = ident!("value");
+            Expression::ArrowFunction {
+                parameters: vec![message.clone(), value.clone()],
+                body: Box::new(ArrowFunctionBody::Block(Block(vec![
+                    BlockStatement::Expression(Expression::Call {
+                        function: Box::new(Expression::PropertyAccess {
+                            target: Box::new(Expression::Variable(ident!("console"))),
+                            property: String::from("log"),
+                        }),
+                        arguments: vec![
+                            Expression::Variable(message),
+                            Expression::Variable(value.clone()),
+                        ],
+                    }),
+                    BlockStatement::Return(Some(Expression::Variable(value))),
+                ]))),
+            }
+        }
         ditto_ast::Expression::LocalConstructor { constructor, .. } => {
             Expression::Variable(Ident::from(constructor))
         }
+        ditto_ast::Expression::PrimConstructor {
+            constructor: constructor @ ditto_ast::PrimConstructor::MaybeNothing,
+            ..
+        } => maybe_constructor(constructor, Vec::new()),
+        ditto_ast::Expression::PrimConstructor {
+            constructor: constructor @ ditto_ast::PrimConstructor::MaybeJust,
+            ..
+        } => {
+            // Not applied directly, so wrap it in `(value) => ["Just", value]`
+            let value = ident!("value");
+            Expression::ArrowFunction {
+                parameters: vec![value.clone()],
+                body: Box::new(ArrowFunctionBody::Expression(maybe_constructor(
+                    constructor,
+                    vec![Expression::Variable(value)],
+                ))),
+            }
+        }
         ditto_ast::Expression::ImportedConstructor { constructor, .. } => {
             let aliased = Ident::from(constructor.value.clone());
             let module_name = ImportedModule::Module(constructor.module_name.clone());
@@ -287,19 +721,783 @@ fn convert_expression(
                 Expression::Variable(ident)
             }
         }
-        ditto_ast::Expression::String { value, .. } => Expression::String(value),
+        ditto_ast::Expression::BinOp {
+            operator: operator @ (ditto_ast::BinOp::Equals | ditto_ast::BinOp::NotEquals),
+            box lhs,
+            box rhs,
+            ..
+        } if is_structural_type(&lhs.get_type()) => {
+            let equals = Expression::Call {
+                function: Box::new(Expression::Variable(STRUCTURAL_EQUALITY_IDENT.clone())),
+                arguments: vec![
+                    convert_expression(imported_idents, unit, lhs),
+                    convert_expression(imported_idents, unit, rhs),
+                ],
+            };
+            if operator == ditto_ast::BinOp::NotEquals {
+                Expression::Not(Box::new(equals))
+            } else {
+                equals
+            }
+        }
+        // Integer division truncates towards zero, rather than producing a fraction
+        ditto_ast::Expression::BinOp {
+            operator: ditto_ast::BinOp::Divide,
+            binop_type: ditto_ast::Type::PrimConstructor(ditto_ast::PrimType::Int),
+            box lhs,
+            box rhs,
+            ..
+        } => Expression::Call {
+            function: Box::new(Expression::PropertyAccess {
+                target: Box::new(Expression::Variable(ident!("Math"))),
+                property: String::from("trunc"),
+            }),
+            arguments: vec![Expression::Operator {
+                op: Operator::Divide,
+                lhs: Box::new(convert_expression(imported_idents, unit, lhs)),
+                rhs: Box::new(convert_expression(imported_idents, unit, rhs)),
+            }],
+        },
+        ditto_ast::Expression::BinOp {
+            operator,
+            box lhs,
+            box rhs,
+            ..
+        } => Expression::Operator {
+            op: match operator {
+                ditto_ast::BinOp::Add => Operator::Add,
+                ditto_ast::BinOp::Subtract => Operator::Subtract,
+                ditto_ast::BinOp::Multiply => Operator::Multiply,
+                ditto_ast::BinOp::Divide => Operator::Divide,
+                ditto_ast::BinOp::Equals => Operator::Equals,
+                ditto_ast::BinOp::NotEquals => Operator::NotEquals,
+                ditto_ast::BinOp::LessThan => Operator::LessThan,
+                ditto_ast::BinOp::LessThanOrEquals => Operator::LessThanOrEquals,
+                ditto_ast::BinOp::GreaterThan => Operator::GreaterThan,
+                ditto_ast::BinOp::GreaterThanOrEquals => Operator::GreaterThanOrEquals,
+                ditto_ast::BinOp::And => Operator::And,
+                ditto_ast::BinOp::Or => Operator::Or,
+            },
+            lhs: Box::new(convert_expression(imported_idents, unit, lhs)),
+            rhs: Box::new(convert_expression(imported_idents, unit, rhs)),
+        },
+        ditto_ast::Expression::Negate { box expression, .. } => Expression::Negate(Box::new(
+            convert_expression(imported_idents, unit, expression),
+        )),
+        // The escapes we allow in strings are all valid in JavaScript, but
+        // unescaped line breaks aren't allowed in a JavaScript string literal.
+        ditto_ast::Expression::String { value, .. } => {
+            Expression::String(value.replace('\r', "\\r").replace('\n', "\\n"))
+        }
+        // Chars are single character strings, and the escapes we allow are all
+        // valid in JavaScript too. Only an unescaped `"` needs escaping.
+        ditto_ast::Expression::Char { value, .. } if value == "\"" => {
+            Expression::String(String::from("\\\""))
+        }
+        ditto_ast::Expression::Char { value, .. } => Expression::String(value),
         ditto_ast::Expression::Float { value, .. } | ditto_ast::Expression::Int { value, .. } => {
-            Expression::Number(value)
+            Expression::Number(normalize_number(value))
         }
+        ditto_ast::Expression::InlineJs { code, .. } => Expression::Raw(code),
         ditto_ast::Expression::Array { elements, .. } => Expression::Array(
             elements
                 .into_iter()
-                .map(|element| convert_expression(imported_idents, element))
+                .map(|element| convert_expression(imported_idents, unit, element))
+                .collect(),
+        ),
+        // Tuples are fixed-length arrays
+        ditto_ast::Expression::Tuple { elements, .. } => Expression::Array(
+            elements
+                .into_iter()
+                .map(|element| convert_expression(imported_idents, unit, element))
+                .collect(),
+        ),
+        ditto_ast::Expression::Record { fields, .. } => Expression::Object(
+            fields
+                .into_iter()
+                .map(|(label, value)| (label.0, convert_expression(imported_idents, unit, value)))
                 .collect(),
         ),
+        // NOTE this also covers fields of single-constructor types, as named
+        // constructor fields are stored as properties too.
+        ditto_ast::Expression::RecordAccess {
+            box target, label, ..
+        } => Expression::PropertyAccess {
+            target: Box::new(convert_expression(imported_idents, unit, target)),
+            property: label.0,
+        },
         ditto_ast::Expression::True { .. } => Expression::True,
         ditto_ast::Expression::False { .. } => Expression::False,
-        ditto_ast::Expression::Unit { .. } => Expression::Undefined, // REVIEW could use `null` or `null` here?
+        ditto_ast::Expression::Unit { .. } => unit_expression(unit),
+    }
+}
+
+fn unit_expression(unit: UnitRepresentation) -> Expression {
+    match unit {
+        UnitRepresentation::Undefined => Expression::Undefined,
+        UnitRepresentation::Sentinel => Expression::Call {
+            function: Box::new(Expression::PropertyAccess {
+                target: Box::new(Expression::Variable(ident!("Symbol"))),
+                property: String::from("for"),
+            }),
+            arguments: vec![Expression::String(String::from(UNIT_SENTINEL_KEY))],
+        },
+    }
+}
+
+/// Key of the [UnitRepresentation::Sentinel] in the global symbol registry.
+static UNIT_SENTINEL_KEY: &str = "ditto.unit";
+
+lazy_static! {
+    static ref STRUCTURAL_EQUALITY_IDENT: Ident = ident!("$equals");
+    static ref INT_DIV_IDENT: Ident = ident!("$int_div");
+}
+
+/// Built-in constructors are represented like any other positional constructor,
+/// see `convert_module`.
+fn maybe_constructor(
+    constructor: ditto_ast::PrimConstructor,
+    arguments: Vec<Expression>,
+) -> Expression {
+    let mut elements = vec![Expression::String(constructor.as_proper_name().0)];
+    elements.extend(arguments);
+    Expression::Array(elements)
+}
+
+/// Values of these types can't be compared with `===`.
+fn is_structural_type(t: &ditto_ast::Type) -> bool {
+    !matches!(
+        t,
+        ditto_ast::Type::PrimConstructor(
+            ditto_ast::PrimType::Int
+                | ditto_ast::PrimType::Float
+                | ditto_ast::PrimType::String
+                | ditto_ast::PrimType::Char
+                | ditto_ast::PrimType::Bool
+                | ditto_ast::PrimType::Unit
+        )
+    )
+}
+
+/// Whether an expression compares arrays, records or constructors for equality.
+fn uses_structural_equality(expression: &ditto_ast::Expression) -> bool {
+    struct UsesStructuralEquality(bool);
+    impl Visitor for UsesStructuralEquality {
+        fn visit_expression(&mut self, expression: &ditto_ast::Expression) {
+            if let ditto_ast::Expression::BinOp {
+                operator: ditto_ast::BinOp::Equals | ditto_ast::BinOp::NotEquals,
+                lhs,
+                ..
+            } = expression
+            {
+                self.0 = self.0 || is_structural_type(&lhs.get_type());
+            }
+            visit::walk_expression(self, expression)
+        }
+    }
+    let mut uses_structural_equality = UsesStructuralEquality(false);
+    uses_structural_equality.visit_expression(expression);
+    uses_structural_equality.0
+}
+
+/// ```javascript
+/// function $equals(a, b) {
+///   if (a === b) {
+///     return true;
+///   }
+///   if (a instanceof Object && b instanceof Object) {
+///     const keys = Object.keys(a);
+///     return (
+///       keys.length === Object.keys(b).length &&
+///       keys.every((key) => $equals(a[key], b[key]))
+///     );
+///   }
+///   return false;
+/// }
+/// ```
+fn structural_equality_helper() -> ModuleStatement {
+    let a = ident!("a");
+    let b = ident!("b");
+    let keys = ident!("keys");
+    let key = ident!("key");
+    let instance_of_object = |ident: &Ident| Expression::Operator {
+        op: Operator::InstanceOf,
+        lhs: Box::new(Expression::Variable(ident.clone())),
+        rhs: Box::new(Expression::Variable(ident!("Object"))),
+    };
+    let object_keys = |ident: &Ident| Expression::Call {
+        function: Box::new(Expression::PropertyAccess {
+            target: Box::new(Expression::Variable(ident!("Object"))),
+            property: String::from("keys"),
+        }),
+        arguments: vec![Expression::Variable(ident.clone())],
+    };
+    let property = |ident: &Ident| Expression::IndexAccess {
+        target: Box::new(Expression::Variable(ident.clone())),
+        index: Box::new(Expression::Variable(key.clone())),
+    };
+    ModuleStatement::Function {
+        ident: STRUCTURAL_EQUALITY_IDENT.clone(),
+        parameters: vec![a.clone(), b.clone()],
+        body: Block(vec![
+            BlockStatement::If {
+                condition: Expression::Operator {
+                    op: Operator::Equals,
+                    lhs: Box::new(Expression::Variable(a.clone())),
+                    rhs: Box::new(Expression::Variable(b.clone())),
+                },
+                block: Block(vec![BlockStatement::Return(Some(Expression::True))]),
+            },
+            BlockStatement::If {
+                condition: Expression::Operator {
+                    op: Operator::And,
+                    lhs: Box::new(instance_of_object(&a)),
+                    rhs: Box::new(instance_of_object(&b)),
+                },
+                block: Block(vec![
+                    BlockStatement::ConstAssignment {
+                        ident: keys.clone(),
+                        value: object_keys(&a),
+                    },
+                    BlockStatement::Return(Some(Expression::Operator {
+                        op: Operator::And,
+                        lhs: Box::new(Expression::Operator {
+                            op: Operator::Equals,
+                            lhs: Box::new(Expression::PropertyAccess {
+                                target: Box::new(Expression::Variable(keys.clone())),
+                                property: String::from("length"),
+                            }),
+                            rhs: Box::new(Expression::PropertyAccess {
+                                target: Box::new(object_keys(&b)),
+                                property: String::from("length"),
+                            }),
+                        }),
+                        rhs: Box::new(Expression::Call {
+                            function: Box::new(Expression::PropertyAccess {
+                                target: Box::new(Expression::Variable(keys)),
+                                property: String::from("every"),
+                            }),
+                            arguments: vec![Expression::ArrowFunction {
+                                parameters: vec![key.clone()],
+                                body: Box::new(ArrowFunctionBody::Expression(Expression::Call {
+                                    function: Box::new(Expression::Variable(
+                                        STRUCTURAL_EQUALITY_IDENT.clone(),
+                                    )),
+                                    arguments: vec![property(&a), property(&b)],
+                                })),
+                            }],
+                        }),
+                    })),
+                ]),
+            },
+            BlockStatement::Return(Some(Expression::False)),
+        ]),
+    }
+}
+
+/// Whether an expression refers to `Int.div`.
+fn uses_int_div(expression: &ditto_ast::Expression) -> bool {
+    struct UsesIntDiv(bool);
+    impl Visitor for UsesIntDiv {
+        fn visit_expression(&mut self, expression: &ditto_ast::Expression) {
+            self.0 = self.0
+                || matches!(
+                    expression,
+                    ditto_ast::Expression::PrimVariable {
+                        variable: ditto_ast::PrimValue::IntDiv,
+                        ..
+                    }
+                );
+            visit::walk_expression(self, expression)
+        }
+    }
+    let mut uses_int_div = UsesIntDiv(false);
+    uses_int_div.visit_expression(expression);
+    uses_int_div.0
+}
+
+/// ```javascript
+/// function $int_div(a, b) {
+///   if (b === 0) {
+///     return ["Nothing"];
+///   }
+///   return ["Just", Math.trunc(a / b)];
+/// }
+/// ```
+fn int_div_helper() -> ModuleStatement {
+    let a = ident!("a");
+    let b = ident!("b");
+    ModuleStatement::Function {
+        ident: INT_DIV_IDENT.clone(),
+        parameters: vec![a.clone(), b.clone()],
+        body: Block(vec![
+            BlockStatement::If {
+                condition: Expression::Operator {
+                    op: Operator::Equals,
+                    lhs: Box::new(Expression::Variable(b.clone())),
+                    rhs: Box::new(Expression::Number(String::from("0"))),
+                },
+                block: Block(vec![BlockStatement::Return(Some(maybe_constructor(
+                    ditto_ast::PrimConstructor::MaybeNothing,
+                    Vec::new(),
+                )))]),
+            },
+            BlockStatement::Return(Some(maybe_constructor(
+                ditto_ast::PrimConstructor::MaybeJust,
+                vec![Expression::Call {
+                    function: Box::new(Expression::PropertyAccess {
+                        target: Box::new(Expression::Variable(ident!("Math"))),
+                        property: String::from("trunc"),
+                    }),
+                    arguments: vec![Expression::Operator {
+                        op: Operator::Divide,
+                        lhs: Box::new(Expression::Variable(a)),
+                        rhs: Box::new(Expression::Variable(b)),
+                    }],
+                }],
+            ))),
+        ]),
+    }
+}
+
+/// The names of the local variables an expression refers to.
+fn local_variables(expression: &ditto_ast::Expression) -> HashSet<ditto_ast::Name> {
+    struct LocalVariables(HashSet<ditto_ast::Name>);
+    impl Visitor for LocalVariables {
+        fn visit_expression(&mut self, expression: &ditto_ast::Expression) {
+            if let ditto_ast::Expression::LocalVariable { variable, .. } = expression {
+                self.0.insert(variable.clone());
+            }
+            visit::walk_expression(self, expression)
+        }
+    }
+    let mut local_variables = LocalVariables(HashSet::new());
+    local_variables.visit_expression(expression);
+    local_variables.0
+}
+
+/// Replaces `debug_log(message, value)` with `value`.
+struct StripDebugLogs;
+
+impl Folder for StripDebugLogs {
+    fn fold_expression(&mut self, expression: ditto_ast::Expression) -> ditto_ast::Expression {
+        match expression {
+            ditto_ast::Expression::Call {
+                function:
+                    box ditto_ast::Expression::PrimVariable {
+                        variable: ditto_ast::PrimValue::DebugLog,
+                        ..
+                    },
+                mut arguments,
+                ..
+            } if arguments.len() == 2 => match arguments.pop() {
+                Some(ditto_ast::Argument::Expression(value)) => self.fold_expression(value),
+                None => unreachable!(),
+            },
+            // Not called directly, so replace it with `(message, value) -> value`
+            ditto_ast::Expression::PrimVariable {
+                span,
+                variable_type:
+                    ditto_ast::Type::Function {
+                        mut parameters,
+                        return_type,
+                    },
+                variable: ditto_ast::PrimValue::DebugLog,
+            } if parameters.len() == 2 => {
+                let value_type = parameters.pop().unwrap();
+                let message_type = parameters.pop().unwrap();
+                let value = ditto_ast::Name(String::from("value"));
+                ditto_ast::Expression::Function {
+                    span,
+                    binders: vec![
+                        ditto_ast::FunctionBinder::Name {
+                            span,
+                            binder_type: message_type,
+                            value: ditto_ast::Name(String::from("message")),
+                        },
+                        ditto_ast::FunctionBinder::Name {
+                            span,
+                            binder_type: value_type,
+                            value: value.clone(),
+                        },
+                    ],
+                    body: Box::new(ditto_ast::Expression::LocalVariable {
+                        span,
+                        variable_type: *return_type,
+                        variable: value,
+                    }),
+                }
+            }
+            expression => fold::fold_expression(self, expression),
+        }
+    }
+}
+
+/// Replaces references to other modules' small constants with their definitions,
+/// and calls to their `@inline` functions with the function body.
+///
+/// Imports that are only used for inlined values aren't needed anymore, and
+/// won't be generated.
+struct InlineImports<'a>(&'a InlineValues);
+
+impl InlineImports<'_> {
+    /// The inlinable body of an imported value, referring to constructors
+    /// through the module that exported it.
+    fn inline_body(
+        &self,
+        variable: &ditto_ast::FullyQualifiedName,
+    ) -> Option<ditto_ast::Expression> {
+        let body = self.0.get(variable)?.clone();
+        Some(RequalifyConstructors(&variable.module_name).fold_expression(body))
+    }
+}
+
+impl Folder for InlineImports<'_> {
+    fn fold_expression(&mut self, expression: ditto_ast::Expression) -> ditto_ast::Expression {
+        match expression {
+            ditto_ast::Expression::Call {
+                span,
+                call_type,
+                function: box function @ ditto_ast::Expression::ImportedVariable { .. },
+                arguments,
+            } => {
+                let arguments = arguments
+                    .into_iter()
+                    .map(|argument| self.fold_argument(argument))
+                    .collect::<Vec<_>>();
+                let inlined = match &function {
+                    ditto_ast::Expression::ImportedVariable { variable, .. } => {
+                        self.inline_body(variable)
+                    }
+                    _ => None,
+                };
+                match inlined {
+                    // The substituted body isn't folded again, so inlining can't recurse
+                    Some(ditto_ast::Expression::Function {
+                        binders, box body, ..
+                    }) if arguments.iter().all(is_atomic_argument) => {
+                        let parameters = binders
+                            .into_iter()
+                            .zip(arguments)
+                            .filter_map(|(binder, ditto_ast::Argument::Expression(argument))| {
+                                match binder {
+                                    ditto_ast::FunctionBinder::Name { value, .. } => {
+                                        Some((value, argument))
+                                    }
+                                    ditto_ast::FunctionBinder::Wildcard { .. } => None,
+                                }
+                            })
+                            .collect();
+                        SubstituteParameters(parameters).fold_expression(body)
+                    }
+                    // Constants are still inlined when they're called
+                    Some(inlined) if !matches!(inlined, ditto_ast::Expression::Function { .. }) => {
+                        ditto_ast::Expression::Call {
+                            span,
+                            call_type,
+                            function: Box::new(inlined),
+                            arguments,
+                        }
+                    }
+                    _ => ditto_ast::Expression::Call {
+                        span,
+                        call_type,
+                        function: Box::new(function),
+                        arguments,
+                    },
+                }
+            }
+            ditto_ast::Expression::ImportedVariable { ref variable, .. } => {
+                match self.inline_body(variable) {
+                    // Functions are only inlined where they're called
+                    Some(ditto_ast::Expression::Function { .. }) | None => expression,
+                    Some(inlined) => inlined,
+                }
+            }
+            expression => fold::fold_expression(self, expression),
+        }
+    }
+}
+
+/// Arguments that can be substituted for parameters without changing when (or
+/// how often) anything is evaluated.
+fn is_atomic_argument(argument: &ditto_ast::Argument) -> bool {
+    let ditto_ast::Argument::Expression(expression) = argument;
+    matches!(
+        expression,
+        ditto_ast::Expression::String { .. }
+            | ditto_ast::Expression::Char { .. }
+            | ditto_ast::Expression::Int { .. }
+            | ditto_ast::Expression::Float { .. }
+            | ditto_ast::Expression::True { .. }
+            | ditto_ast::Expression::False { .. }
+            | ditto_ast::Expression::Unit { .. }
+            | ditto_ast::Expression::LocalVariable { .. }
+            | ditto_ast::Expression::ImportedVariable { .. }
+            | ditto_ast::Expression::ForeignVariable { .. }
+            | ditto_ast::Expression::LocalConstructor { .. }
+            | ditto_ast::Expression::ImportedConstructor { .. }
+            | ditto_ast::Expression::PrimConstructor { .. }
+    )
+}
+
+/// Replaces the parameters of an inlined function with its arguments.
+///
+/// Inlined bodies don't bind any names of their own, so nothing can be captured.
+struct SubstituteParameters(HashMap<ditto_ast::Name, ditto_ast::Expression>);
+
+impl Folder for SubstituteParameters {
+    fn fold_expression(&mut self, expression: ditto_ast::Expression) -> ditto_ast::Expression {
+        match expression {
+            ditto_ast::Expression::LocalVariable { ref variable, .. } => {
+                self.0.get(variable).cloned().unwrap_or(expression)
+            }
+            expression => fold::fold_expression(self, expression),
+        }
+    }
+}
+
+/// Refers to the local constructors of an inlined body through the module
+/// that it was exported from.
+struct RequalifyConstructors<'a>(&'a ditto_ast::FullyQualifiedModuleName);
+
+impl Folder for RequalifyConstructors<'_> {
+    fn fold_expression(&mut self, expression: ditto_ast::Expression) -> ditto_ast::Expression {
+        match fold::fold_expression(self, expression) {
+            ditto_ast::Expression::LocalConstructor {
+                span,
+                constructor_type,
+                constructor,
+            } => ditto_ast::Expression::ImportedConstructor {
+                span,
+                constructor_type,
+                constructor: ditto_ast::FullyQualifiedProperName {
+                    module_name: self.0.clone(),
+                    value: constructor,
+                },
+            },
+            expression => expression,
+        }
+    }
+}
+
+/// Folds away conditionals with literal conditions.
+struct Simplify;
+
+impl Folder for Simplify {
+    fn fold_expression(&mut self, expression: ditto_ast::Expression) -> ditto_ast::Expression {
+        // Children first, so that simplifications can cascade
+        match fold::fold_expression(self, expression) {
+            ditto_ast::Expression::If {
+                condition: box ditto_ast::Expression::True { .. },
+                box true_clause,
+                ..
+            } => true_clause,
+            ditto_ast::Expression::If {
+                condition: box ditto_ast::Expression::False { .. },
+                box false_clause,
+                ..
+            } => false_clause,
+            ditto_ast::Expression::BinOp {
+                operator: ditto_ast::BinOp::And,
+                lhs: box ditto_ast::Expression::True { .. },
+                box rhs,
+                ..
+            } => rhs,
+            ditto_ast::Expression::BinOp {
+                operator: ditto_ast::BinOp::And,
+                lhs: box lhs @ ditto_ast::Expression::False { .. },
+                ..
+            } => lhs,
+            ditto_ast::Expression::BinOp {
+                operator: ditto_ast::BinOp::Or,
+                lhs: box lhs @ ditto_ast::Expression::True { .. },
+                ..
+            } => lhs,
+            ditto_ast::Expression::BinOp {
+                operator: ditto_ast::BinOp::Or,
+                lhs: box ditto_ast::Expression::False { .. },
+                box rhs,
+                ..
+            } => rhs,
+            expression => expression,
+        }
+    }
+}
+
+/// ```javascript
+/// if (typeof foreign$f !== "function") {
+///   throw new Error("foreign value `f` should be a function");
+/// }
+/// if (foreign$x === undefined) {
+///   throw new Error("foreign value `x` is undefined");
+/// }
+/// ```
+fn foreign_value_checks(ast_module: &ditto_ast::Module) -> Vec<ModuleStatement> {
+    struct ForeignValues(HashMap<ditto_ast::Name, bool>);
+    impl Visitor for ForeignValues {
+        fn visit_expression(&mut self, expression: &ditto_ast::Expression) {
+            if let ditto_ast::Expression::ForeignVariable {
+                variable,
+                variable_type,
+                ..
+            } = expression
+            {
+                let is_function = matches!(variable_type, ditto_ast::Type::Function { .. });
+                self.0.insert(variable.clone(), is_function);
+            }
+            visit::walk_expression(self, expression)
+        }
+    }
+    let mut foreign_values = ForeignValues(HashMap::new());
+    for module_value in ast_module.values.values() {
+        foreign_values.visit_expression(&module_value.expression);
+    }
+    let mut foreign_values = foreign_values.0.into_iter().collect::<Vec<_>>();
+
+    // Sort for determinism
+    foreign_values.sort_by(|a, b| a.0.cmp(&b.0));
+
+    foreign_values
+        .into_iter()
+        .map(|(name, is_function)| {
+            let ident = Expression::Variable(mk_foreign_ident(name.0.clone()));
+            let (condition, message) = if is_function {
+                (
+                    Expression::Operator {
+                        op: Operator::NotEquals,
+                        lhs: Box::new(Expression::TypeOf(Box::new(ident))),
+                        rhs: Box::new(Expression::String(String::from("function"))),
+                    },
+                    format!("foreign value `{}` should be a function", name),
+                )
+            } else {
+                (
+                    Expression::Operator {
+                        op: Operator::Equals,
+                        lhs: Box::new(ident),
+                        rhs: Box::new(Expression::Undefined),
+                    },
+                    format!("foreign value `{}` is undefined", name),
+                )
+            };
+            ModuleStatement::If {
+                condition,
+                block: Block(vec![BlockStatement::Throw(message)]),
+            }
+        })
+        .collect()
+}
+
+/// Convert a pattern matching `target` into the `conditions` that need to hold for
+/// it to match, and the `statements` that bind its variables.
+fn convert_pattern(
+    target: Expression,
+    pattern: ditto_ast::Pattern,
+    conditions: &mut Vec<Expression>,
+    statements: &mut Vec<BlockStatement>,
+) {
+    match pattern {
+        ditto_ast::Pattern::LocalConstructor {
+            constructor,
+            field_names,
+            arguments,
+            ..
+        } => convert_constructor_pattern(
+            target,
+            constructor,
+            field_names,
+            arguments,
+            conditions,
+            statements,
+        ),
+        ditto_ast::Pattern::ImportedConstructor {
+            constructor,
+            field_names,
+            arguments,
+            ..
+        } => convert_constructor_pattern(
+            target,
+            constructor.value,
+            field_names,
+            arguments,
+            conditions,
+            statements,
+        ),
+        ditto_ast::Pattern::PrimConstructor {
+            constructor,
+            arguments,
+            ..
+        } => convert_constructor_pattern(
+            target,
+            constructor.as_proper_name(),
+            None,
+            arguments,
+            conditions,
+            statements,
+        ),
+        ditto_ast::Pattern::Tuple { elements, .. } => {
+            // No conditions of its own, as the type guarantees the length
+            for (i, element) in elements.into_iter().enumerate() {
+                let element_target = Expression::IndexAccess {
+                    target: Box::new(target.clone()),
+                    index: Box::new(Expression::Number(i.to_string())),
+                };
+                convert_pattern(element_target, element, conditions, statements);
+            }
+        }
+        ditto_ast::Pattern::Variable { name, .. } => {
+            statements.push(BlockStatement::ConstAssignment {
+                ident: Ident::from(name),
+                value: target,
+            });
+        }
+        ditto_ast::Pattern::Wildcard { .. } => {}
+    }
+}
+
+fn convert_constructor_pattern(
+    target: Expression,
+    constructor: ditto_ast::ProperName,
+    field_names: Option<Vec<ditto_ast::Name>>,
+    arguments: Vec<ditto_ast::Pattern>,
+    conditions: &mut Vec<Expression>,
+    statements: &mut Vec<BlockStatement>,
+) {
+    // See `convert_module` for how constructors are represented
+    let (tag, argument_targets) = if let Some(field_names) = field_names {
+        let tag = Expression::PropertyAccess {
+            target: Box::new(target.clone()),
+            property: String::from("$"),
+        };
+        let argument_targets = field_names
+            .into_iter()
+            .map(|field_name| Expression::PropertyAccess {
+                target: Box::new(target.clone()),
+                property: field_name.0,
+            })
+            .collect::<Vec<_>>();
+        (tag, argument_targets)
+    } else {
+        let tag = Expression::IndexAccess {
+            target: Box::new(target.clone()),
+            index: Box::new(Expression::Number(String::from("0"))),
+        };
+        let argument_targets = (1..=arguments.len())
+            .map(|i| Expression::IndexAccess {
+                target: Box::new(target.clone()),
+                index: Box::new(Expression::Number(i.to_string())),
+            })
+            .collect::<Vec<_>>();
+        (tag, argument_targets)
+    };
+    conditions.push(Expression::Operator {
+        op: Operator::Equals,
+        lhs: Box::new(tag),
+        rhs: Box::new(Expression::String(constructor.0)),
+    });
+    for (argument_target, argument) in argument_targets.into_iter().zip(arguments) {
+        convert_pattern(argument_target, argument, conditions, statements);
     }
 }
 
@@ -352,17 +1550,61 @@ fn ident_from_fully_qualified(
     Ident(string)
 }
 
+/// Leading zeros aren't allowed in JavaScript number literals (in strict mode),
+/// so `005` needs to become `5`.
+///
+/// Underscores are removed, as JavaScript only allows single separators
+/// between digits. Hexadecimal, octal and binary literals keep their radix.
+fn normalize_number(number: String) -> String {
+    let number = number.replace('_', "");
+    let (sign, digits) = match number.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", number.as_str()),
+    };
+    if let Some("0x" | "0o" | "0b") = digits.get(..2) {
+        return number;
+    }
+    let stripped = digits.trim_start_matches('0');
+    if stripped.is_empty() || stripped.starts_with('.') {
+        format!("{}0{}", sign, stripped)
+    } else {
+        format!("{}{}", sign, stripped)
+    }
+}
+
+/// The name a foreign value without an explicit foreign name is expected to be
+/// exported under.
+///
+/// Keywords are mangled with a `$` prefix, so that foreign modules can declare
+/// them as ordinary functions (e.g. `export function $new`). Runtime globals are
+/// left alone, as an import name can't shadow them.
+fn foreign_import_ident(name: ditto_ast::Name) -> Ident {
+    let ident = name.0.to_case(Case::Camel);
+    if JS_RESERVED.contains(&ident.as_str()) {
+        Ident(format!("${}", ident))
+    } else {
+        Ident(ident)
+    }
+}
+
 fn mk_foreign_ident(value: String) -> Ident {
     Ident(format!("foreign${}", name_string_to_ident_string(value)))
 }
 
 // Hmmm probably don't want to do this, as it will get messy with foreign things?
-fn name_string_to_ident_string(name_string: String) -> String {
-    mangle_reserved(name_string).to_case(Case::Camel)
+pub fn name_string_to_ident_string(name_string: String) -> String {
+    // NOTE mangling happens _after_ case conversion, as `for_` would otherwise become `for`
+    mangle_reserved(name_string.to_case(Case::Camel))
 }
 
+/// Ditto names can't start with `$`, so everything in that namespace belongs to
+/// generated code (e.g. `$match`, `$0`, `foreign$foo`).
+///
+/// Names that would collide with a JavaScript keyword, or with a global that
+/// generated code refers to, are moved out of the way into that namespace.
 fn mangle_reserved(ident: String) -> String {
-    let is_reserved = JS_RESERVED.contains(&ident.as_str());
+    let is_reserved =
+        JS_RESERVED.contains(&ident.as_str()) || RUNTIME_RESERVED.contains(&ident.as_str());
     if is_reserved {
         format!("${}", ident)
     } else {
@@ -406,4 +1648,7 @@ lazy_static! {
         "with",
         "yield",
     ]);
+    /// Globals referenced by generated code, which user values mustn't shadow.
+    static ref RUNTIME_RESERVED: HashSet<&'static str> =
+        HashSet::from_iter(vec!["console", "undefined"]);
 }
@@ -3,25 +3,78 @@
 #![warn(missing_docs)]
 
 mod ast;
+mod bundle;
 mod convert;
 mod render;
+mod source_map;
 mod ts;
 
-pub use convert::Config;
+pub use bundle::{bundle, BundleConfig, BundleError, BundleModule};
+pub use convert::{
+    exported_inline_values, Config, InlineValues, ModuleFormat, SourceMapConfig, UnitRepresentation,
+};
 
 /// Generate a JavaScript module from a ditto module.
 pub fn codegen(config: &Config, module: ditto_ast::Module) -> String {
-    render::render_module(convert::convert_module(config, module))
+    render::render_module(
+        config.module_format,
+        layout(config),
+        convert::convert_module(config, module),
+    )
 }
 
 /// Generate a JavaScript module from a ditto module, with TypeScript declarations.
-#[doc(hidden)]
 pub fn codegen_with_dts(config: &Config, module: ditto_ast::Module) -> (String, String) {
-    let dts = ts::generate_declarations(config, &module.module_name, &module.exports);
-    let js = render::render_module(convert::convert_module(config, module));
+    let dts = codegen_dts(config, &module);
+    let js = render::render_module(
+        config.module_format,
+        layout(config),
+        convert::convert_module(config, module),
+    );
     (js, dts)
 }
 
+/// Generate TypeScript declarations for the JavaScript generated from a ditto module.
+///
+/// Declarations cover everything in `module.exports`: types (along with their
+/// constructors) and values.
+pub fn codegen_dts(config: &Config, module: &ditto_ast::Module) -> String {
+    ts::generate_declarations(config, &module.module_name, &module.exports)
+}
+
+/// Generate a JavaScript module from a ditto module, with a source map.
+///
+/// The JavaScript references the source map as `config.source_map.file` with a
+/// `.map` extension.
+///
+/// Panics if `config.source_map` isn't set.
+pub fn codegen_with_sourcemap(config: &Config, module: ditto_ast::Module) -> (String, String) {
+    let source_map_config = config
+        .source_map
+        .as_ref()
+        .expect("source map config is required");
+    let (mut js, origins) = render::render_module_with_origins(
+        config.module_format,
+        layout(config),
+        convert::convert_module(config, module),
+    );
+    js.push_str(&format!(
+        "//# sourceMappingURL={}.map",
+        source_map_config.file
+    ));
+    js.push_str(render::NEWLINE);
+    let source_map = source_map::generate_source_map(source_map_config, &origins);
+    (js, source_map)
+}
+
+fn layout(config: &Config) -> render::Layout {
+    if config.pretty {
+        render::Layout::PRETTY
+    } else {
+        render::Layout::COMPACT
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate as js;
@@ -41,11 +94,127 @@ mod tests {
             &js::Config {
                 module_name_to_path: Box::new(module_name_to_path),
                 foreign_module_path: "./foreign.js".into(),
+                strip_debug: false,
+                optimize: false,
+                inline_values: js::InlineValues::new(),
+                runtime_checks: false,
+                banner: None,
+                module_format: js::ModuleFormat::Esm,
+                unit: js::UnitRepresentation::Undefined,
+                pretty: false,
+                source_map: None,
+            },
+            ast_module,
+        ))
+    }
+
+    #[snapshot_test::snapshot_lf(
+        input = "golden-tests/javascript/commonjs/(.*).ditto",
+        output = "golden-tests/javascript/commonjs/${1}.js"
+    )]
+    fn javascript_commonjs(input: &str) -> String {
+        let cst_module = cst::Module::parse(input).unwrap();
+        let everything = mk_everything();
+        let (ast_module, _warnings) = checker::check_module(&everything, cst_module).unwrap();
+        prettier(&js::codegen(
+            &js::Config {
+                module_name_to_path: Box::new(module_name_to_path),
+                foreign_module_path: "./foreign.js".into(),
+                strip_debug: false,
+                optimize: false,
+                inline_values: js::InlineValues::new(),
+                runtime_checks: false,
+                banner: None,
+                module_format: js::ModuleFormat::Cjs,
+                unit: js::UnitRepresentation::Undefined,
+                pretty: false,
+                source_map: None,
             },
             ast_module,
         ))
     }
 
+    /// Code laid out by ditto itself, rather than by prettier.
+    #[snapshot_test::snapshot_lf(
+        input = "golden-tests/javascript/pretty/(.*).ditto",
+        output = "golden-tests/javascript/pretty/${1}.js"
+    )]
+    fn javascript_pretty(input: &str) -> String {
+        let cst_module = cst::Module::parse(input).unwrap();
+        let everything = mk_everything();
+        let (ast_module, _warnings) = checker::check_module(&everything, cst_module).unwrap();
+        js::codegen(
+            &js::Config {
+                module_name_to_path: Box::new(module_name_to_path),
+                foreign_module_path: "./foreign.js".into(),
+                strip_debug: false,
+                optimize: false,
+                inline_values: js::InlineValues::new(),
+                runtime_checks: false,
+                banner: None,
+                module_format: js::ModuleFormat::Esm,
+                unit: js::UnitRepresentation::Undefined,
+                pretty: true,
+                source_map: None,
+            },
+            ast_module,
+        )
+    }
+
+    /// Code generated with `unit` represented by a sentinel, laid out by ditto.
+    #[snapshot_test::snapshot_lf(
+        input = "golden-tests/javascript/sentinel_unit/(.*).ditto",
+        output = "golden-tests/javascript/sentinel_unit/${1}.js"
+    )]
+    fn javascript_sentinel_unit(input: &str) -> String {
+        let cst_module = cst::Module::parse(input).unwrap();
+        let everything = mk_everything();
+        let (ast_module, _warnings) = checker::check_module(&everything, cst_module).unwrap();
+        js::codegen(
+            &js::Config {
+                module_name_to_path: Box::new(module_name_to_path),
+                foreign_module_path: "./foreign.js".into(),
+                strip_debug: false,
+                optimize: false,
+                inline_values: js::InlineValues::new(),
+                runtime_checks: false,
+                banner: None,
+                module_format: js::ModuleFormat::Esm,
+                unit: js::UnitRepresentation::Sentinel,
+                pretty: true,
+                source_map: None,
+            },
+            ast_module,
+        )
+    }
+
+    /// Optimized code, with the constants from `Data.Stuff` inlined.
+    #[snapshot_test::snapshot_lf(
+        input = "golden-tests/javascript/optimized/(.*).ditto",
+        output = "golden-tests/javascript/optimized/${1}.js"
+    )]
+    fn javascript_optimized(input: &str) -> String {
+        let cst_module = cst::Module::parse(input).unwrap();
+        let everything = mk_everything();
+        let (ast_module, _warnings) = checker::check_module(&everything, cst_module).unwrap();
+        js::codegen(
+            &js::Config {
+                module_name_to_path: Box::new(module_name_to_path),
+                foreign_module_path: "./foreign.js".into(),
+                strip_debug: false,
+                optimize: true,
+                inline_values: mk_inline_values(&everything),
+                runtime_checks: false,
+                banner: None,
+                module_format: js::ModuleFormat::Esm,
+                unit: js::UnitRepresentation::Undefined,
+                pretty: true,
+                source_map: None,
+            },
+            ast_module,
+        )
+    }
+
     #[snapshot_test::snapshot_lf(
         input = "golden-tests/typescript/(.*).ditto",
         output = "golden-tests/typescript/${1}.d.ts"
@@ -59,6 +228,15 @@ mod tests {
                 &js::Config {
                     module_name_to_path: Box::new(module_name_to_path),
                     foreign_module_path: "./foreign.js".into(),
+                    strip_debug: false,
+                    optimize: false,
+                    inline_values: js::InlineValues::new(),
+                    runtime_checks: false,
+                    banner: None,
+                    module_format: js::ModuleFormat::Esm,
+                    unit: js::UnitRepresentation::Undefined,
+                    pretty: false,
+                    source_map: None,
                 },
                 ast_module,
             )
@@ -66,6 +244,532 @@ mod tests {
         )
     }
 
+    #[test]
+    fn it_preserves_integer_literals() {
+        assert_eq!(
+            eval_numeric_literals(&[
+                "0",
+                "5",
+                "005",
+                "1_000_000",
+                "0xFF",
+                "0x00_ff",
+                "0o17",
+                "0b1010",
+                "0b1__0",
+                // Number.MAX_SAFE_INTEGER
+                "9007199254740991",
+                // Beyond MAX_SAFE_INTEGER integers get rounded, as they would in JavaScript
+                "9007199254740993",
+                "18446744073709551616",
+            ]),
+            vec![
+                "0",
+                "5",
+                "5",
+                "1000000",
+                "255",
+                "255",
+                "15",
+                "10",
+                "2",
+                "9007199254740991",
+                "9007199254740992",
+                "18446744073709551616",
+            ]
+        );
+    }
+
+    #[test]
+    fn it_preserves_float_literals() {
+        // NOTE: negative zero isn't covered as there aren't negative literals (yet)
+        let too_big = format!("1{}.0", "0".repeat(309));
+        assert_eq!(
+            eval_numeric_literals(&[
+                "0.0",
+                "00.5",
+                "5.00",
+                "0.1",
+                "1_000.000_1",
+                "3.141592653589793238462643383279",
+                "0.000000000000000000000000000001",
+                "123456789.123456789",
+                "1000000000000000000000000000000.0",
+                &too_big,
+            ]),
+            vec![
+                "0",
+                "0.5",
+                "5",
+                "0.1",
+                "1000.0001",
+                "3.141592653589793",
+                "1e-30",
+                "123456789.12345679",
+                "1000000000000000019884624838656",
+                "Infinity",
+            ]
+        );
+    }
+
+    #[test]
+    fn it_lowers_float_special_values() {
+        assert_eq!(
+            eval_numeric_literals(&[
+                "Float.infinity",
+                "Float.neg_infinity",
+                "Float.nan",
+                "-Float.neg_infinity",
+            ]),
+            vec!["Infinity", "-Infinity", "NaN", "Infinity"]
+        );
+    }
+
+    #[test]
+    fn it_truncates_integer_division() {
+        assert_eq!(
+            eval_numeric_literals(&["7 / 2", "-7 / 2", "6 / 3", "1 / 3"]),
+            vec!["3", "-3", "2", "0"]
+        );
+    }
+
+    #[test]
+    fn it_checks_the_divisor_of_int_div() {
+        let source = r#"
+            module Test.Div exports (..);
+            results = [Int.div(7, 2), Int.div(-7, 2), Int.div(7, 0), Int.div(0, 0)];
+        "#;
+        assert_eq!(
+            eval_module(source, "console.log(JSON.stringify(results));").trim(),
+            r#"[["Just",3],["Just",-3],["Nothing"],["Nothing"]]"#
+        );
+    }
+
+    /// Generate code for an array of numeric literals and evaluate it with node,
+    /// returning the runtime values.
+    ///
+    /// Integral values are printed as `BigInt`s so that any loss of precision is visible.
+    fn eval_numeric_literals(literals: &[&str]) -> Vec<String> {
+        let source = format!(
+            "module Test.Numbers exports (..); numbers = [{}];",
+            literals.join(", ")
+        );
+        eval_module(
+            &source,
+            "numbers.forEach(n => console.log(Number.isInteger(n) ? BigInt(n).toString() : String(n)));",
+        )
+        .lines()
+        .map(String::from)
+        .collect()
+    }
+
+    /// Generate code for a module, append `epilogue` and evaluate it with node,
+    /// returning what was logged.
+    fn eval_module(source: &str, epilogue: &str) -> String {
+        let cst_module = cst::Module::parse(source).unwrap();
+        let (ast_module, _warnings) =
+            checker::check_module(&checker::Everything::default(), cst_module).unwrap();
+        let mut js = js::codegen(
+            &js::Config {
+                module_name_to_path: Box::new(module_name_to_path),
+                foreign_module_path: "./foreign.js".into(),
+                strip_debug: false,
+                optimize: false,
+                inline_values: js::InlineValues::new(),
+                runtime_checks: false,
+                banner: None,
+                module_format: js::ModuleFormat::Esm,
+                unit: js::UnitRepresentation::Undefined,
+                pretty: false,
+                source_map: None,
+            },
+            ast_module,
+        );
+        js.push_str(epilogue);
+        run_node(&js)
+    }
+
+    #[test]
+    fn it_logs_debug_values() {
+        let source = r#"
+            module Test.Debug exports (..);
+            five = debug_log("five", 5);
+            log = debug_log;
+            fives = log("fives", [five, five]);
+        "#;
+        let js = |strip_debug| {
+            let cst_module = cst::Module::parse(source).unwrap();
+            let (ast_module, _warnings) =
+                checker::check_module(&checker::Everything::default(), cst_module).unwrap();
+            let mut js = js::codegen(
+                &js::Config {
+                    module_name_to_path: Box::new(module_name_to_path),
+                    foreign_module_path: "./foreign.js".into(),
+                    strip_debug,
+                    optimize: false,
+                    inline_values: js::InlineValues::new(),
+                    runtime_checks: false,
+                    banner: None,
+                    module_format: js::ModuleFormat::Esm,
+                    unit: js::UnitRepresentation::Undefined,
+                    pretty: false,
+                    source_map: None,
+                },
+                ast_module,
+            );
+            js.push_str("console.log(five, fives);");
+            js
+        };
+        assert_eq!(run_node(&js(false)), "five 5\nfives [ 5, 5 ]\n5 [ 5, 5 ]\n");
+        assert_eq!(run_node(&js(true)), "5 [ 5, 5 ]\n");
+    }
+
+    #[test]
+    fn it_simplifies_known_conditions_when_optimizing() {
+        let source = r#"
+            module Test exports (..);
+            pick = (a: Int, b: Int): Int -> if true || a == b then a else b;
+        "#;
+        let js = |optimize| {
+            let cst_module = cst::Module::parse(source).unwrap();
+            let (ast_module, _warnings) =
+                checker::check_module(&checker::Everything::default(), cst_module).unwrap();
+            js::codegen(
+                &js::Config {
+                    module_name_to_path: Box::new(module_name_to_path),
+                    foreign_module_path: "./foreign.js".into(),
+                    strip_debug: false,
+                    optimize,
+                    inline_values: js::InlineValues::new(),
+                    runtime_checks: false,
+                    banner: None,
+                    module_format: js::ModuleFormat::Esm,
+                    unit: js::UnitRepresentation::Undefined,
+                    pretty: false,
+                    source_map: None,
+                },
+                ast_module,
+            )
+        };
+        assert!(js(false).contains('?'));
+        assert!(js(true).contains("function pick(a,b){return a;}"));
+    }
+
+    #[test]
+    fn it_only_inlines_imported_constants_when_optimizing() {
+        let source = r#"
+            module Test exports (..);
+            import Data.Stuff (five);
+            ten = [five, five];
+        "#;
+        let js = |optimize| {
+            let cst_module = cst::Module::parse(source).unwrap();
+            let everything = mk_everything();
+            let (ast_module, _warnings) = checker::check_module(&everything, cst_module).unwrap();
+            js::codegen(
+                &js::Config {
+                    module_name_to_path: Box::new(module_name_to_path),
+                    foreign_module_path: "./foreign.js".into(),
+                    strip_debug: false,
+                    optimize,
+                    inline_values: mk_inline_values(&everything),
+                    runtime_checks: false,
+                    banner: None,
+                    module_format: js::ModuleFormat::Esm,
+                    unit: js::UnitRepresentation::Undefined,
+                    pretty: false,
+                    source_map: None,
+                },
+                ast_module,
+            )
+        };
+        assert!(js(false).contains("const ten = [Data$Stuff$five,Data$Stuff$five,];"));
+        assert_eq!(js(true), "const ten = [5,5,];\nexport {ten};\n");
+    }
+
+    #[test]
+    fn it_checks_foreign_values_at_runtime() {
+        let source = r#"
+            module Test exports (..);
+            foreign map_impl : ((a) -> b, Array(a)) -> Array(b);
+            foreign "default" as default_impl : Int;
+            xs = map_impl((x) -> x, [default_impl]);
+        "#;
+        let cst_module = cst::Module::parse(source).unwrap();
+        let (ast_module, _warnings) =
+            checker::check_module(&checker::Everything::default(), cst_module).unwrap();
+        let js = js::codegen(
+            &js::Config {
+                module_name_to_path: Box::new(module_name_to_path),
+                foreign_module_path: "./foreign.js".into(),
+                strip_debug: false,
+                optimize: false,
+                inline_values: js::InlineValues::new(),
+                runtime_checks: true,
+                banner: Some(String::from("Generated, do not edit")),
+                module_format: js::ModuleFormat::Esm,
+                unit: js::UnitRepresentation::Undefined,
+                pretty: false,
+                source_map: None,
+            },
+            ast_module,
+        );
+        let lines = js.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "// Generated, do not edit");
+        assert_eq!(
+            lines[2],
+            "if (foreign$defaultImpl===undefined){throw new Error(\"foreign value `default_impl` is undefined\");}"
+        );
+        assert_eq!(
+            lines[3],
+            "if (typeof foreign$mapImpl!==\"function\"){throw new Error(\"foreign value `map_impl` should be a function\");}"
+        );
+    }
+
+    #[test]
+    fn it_generates_source_maps() {
+        let source = "module Test exports (..);\n\ntype Five = Five;\n\nfive = 5;\n";
+        let cst_module = cst::Module::parse(source).unwrap();
+        let (ast_module, _warnings) =
+            checker::check_module(&checker::Everything::default(), cst_module).unwrap();
+        let (js, source_map) = js::codegen_with_sourcemap(
+            &js::Config {
+                module_name_to_path: Box::new(module_name_to_path),
+                foreign_module_path: "./foreign.js".into(),
+                strip_debug: false,
+                optimize: false,
+                inline_values: js::InlineValues::new(),
+                runtime_checks: false,
+                banner: None,
+                module_format: js::ModuleFormat::Esm,
+                unit: js::UnitRepresentation::Undefined,
+                pretty: false,
+                source_map: Some(js::SourceMapConfig {
+                    file: String::from("Test.js"),
+                    source_path: String::from("../src/Test.ditto"),
+                    source: String::from(source),
+                }),
+            },
+            ast_module,
+        );
+        assert_eq!(
+            js.lines().collect::<Vec<_>>(),
+            vec![
+                "const Five = [\"Five\",];",
+                "const five = 5;",
+                "export {Five,five};",
+                "//# sourceMappingURL=Test.js.map"
+            ]
+        );
+        // The `Five` constructor maps to line 2, column 12 and `five` maps to line 4, column 0
+        assert_eq!(
+            source_map,
+            r#"{"version":3,"file":"Test.js","sources":["../src/Test.ditto"],"names":[],"mappings":"AAEY;AAEZ"}"#
+        );
+    }
+
+    #[test]
+    fn it_generates_identical_code_across_runs() {
+        let source = r#"
+            module Test exports (..);
+            import (test-stuff) Data.Stuff (Maybe(..), id);
+            import Data.Stuff as S;
+            type Colour = Red | Green | Blue;
+            type Pair(a, b) = Pair(a, b);
+            foreign log_colour : (Colour) -> Unit;
+            is_red = (colour) -> match colour with | Red -> true | _ -> false end;
+            unwrap = (maybe, default: Int) ->
+                match maybe with
+                | Just(Pair(colour, _)) -> match colour with | Red -> S.five | _ -> default end
+                | Nothing -> default
+                end;
+            lets = (x) ->
+                let
+                    y = [x, id(x)];
+                    z = [y, y];
+                in
+                z;
+            ping = (n: Int): Int -> if n == 0 then n else pong(n - 1);
+            pong = (n: Int): Int -> if n == 0 then n else ping(n - 1);
+            logged = log_colour(Green);
+        "#;
+        let generate = || {
+            let cst_module = cst::Module::parse(source).unwrap();
+            let (ast_module, _warnings) =
+                checker::check_module(&mk_everything(), cst_module).unwrap();
+            js::codegen(
+                &js::Config {
+                    module_name_to_path: Box::new(module_name_to_path),
+                    foreign_module_path: "./foreign.js".into(),
+                    strip_debug: false,
+                    optimize: false,
+                    inline_values: js::InlineValues::new(),
+                    runtime_checks: false,
+                    banner: None,
+                    module_format: js::ModuleFormat::Esm,
+                    unit: js::UnitRepresentation::Undefined,
+                    pretty: false,
+                    source_map: None,
+                },
+                ast_module,
+            )
+        };
+        let expected = generate();
+        for _ in 0..10 {
+            assert_eq!(generate(), expected);
+        }
+    }
+
+    #[test]
+    fn it_bundles_modules_into_a_script() {
+        let check = |source: &str| {
+            let cst_module = cst::Module::parse(source).unwrap();
+            let (ast_module, _warnings) =
+                checker::check_module(&mk_everything(), cst_module).unwrap();
+            js::BundleModule {
+                module_name: (None, ast_module.module_name.clone()),
+                module: ast_module,
+                foreign_module_path: "./foreign.js".into(),
+            }
+        };
+        let bundle = |modules| {
+            js::bundle(
+                modules,
+                &js::BundleConfig {
+                    package_name: ast::package_name!("test-bundle"),
+                    strip_debug: false,
+                    optimize: false,
+                    runtime_checks: false,
+                    banner: None,
+                    unit: js::UnitRepresentation::Undefined,
+                },
+            )
+        };
+        let main = "module Main exports (..); import Data.Stuff (five); ten = [five, five];";
+
+        let js = bundle(vec![check(DATA_STUFF), check(main)]).unwrap();
+        assert!(js.starts_with("var testBundle = (async () => {"), "{}", js);
+        assert!(!js.contains("import "), "{}", js);
+        assert!(!js.contains("export "), "{}", js);
+
+        let err = bundle(vec![check(main)]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Main imports Data.Stuff, which isn't in the bundle"
+        );
+    }
+
+    #[test]
+    fn typescript_declarations_compile_strictly() {
+        use std::{fs, process::Command};
+
+        let dir = std::env::temp_dir().join(format!("ditto-codegen-js-tsc-{}", std::process::id()));
+        let write = |path: &str, contents: &str| {
+            let path = dir.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        };
+
+        // All of the golden declarations...
+        for entry in fs::read_dir("golden-tests/typescript").unwrap() {
+            let path = entry.unwrap().path();
+            let file_name = path.file_name().unwrap().to_str().unwrap();
+            if file_name.ends_with(".d.ts") {
+                write(
+                    &format!("golden/{}", file_name),
+                    &fs::read_to_string(&path).unwrap(),
+                );
+            }
+        }
+
+        // ...and a project with modules referencing each other's types
+        let config = js::Config {
+            module_name_to_path: Box::new(module_name_to_path),
+            foreign_module_path: "./foreign.js".into(),
+            strip_debug: false,
+            optimize: false,
+            inline_values: js::InlineValues::new(),
+            runtime_checks: false,
+            banner: None,
+            module_format: js::ModuleFormat::Esm,
+            unit: js::UnitRepresentation::Undefined,
+            pretty: false,
+            source_map: None,
+        };
+        let shapes = r#"
+            module Shapes exports (..);
+            import Data.Stuff (Maybe(..));
+            type Shape = Circle(Float) | Square(Float);
+            type Tagged(a) = Tagged(String, a);
+            biggest = (shapes: Array(Shape)): Maybe(Shape) -> Nothing;
+            tag = (a) -> Tagged("tag", a);
+        "#;
+        let main = r#"
+            module Main exports (..);
+            import (test-stuff) Data.Stuff as Package;
+            import Shapes (Shape(..), Tagged, tag);
+            tagged_shape : Tagged(Shape) = tag(Square(2.0));
+            maybe_shape = Package.Just(Circle(1.0));
+        "#;
+        let mut everything = mk_everything();
+        for source in [DATA_STUFF, shapes, main] {
+            let cst_module = cst::Module::parse(source).unwrap();
+            let (ast_module, _warnings) = checker::check_module(&everything, cst_module).unwrap();
+            let dts = js::codegen_dts(&config, &ast_module);
+            let path = module_name_to_path((None, ast_module.module_name.clone()));
+            write(&format!("{}.d.ts", path), &dts);
+            if source == DATA_STUFF {
+                write(&format!("test-stuff/{}.d.ts", path), &dts);
+            }
+            everything
+                .modules
+                .insert(ast_module.module_name, ast_module.exports);
+        }
+
+        // Non-relative imports are resolved from here
+        write(
+            "tsconfig.json",
+            r#"{ "compilerOptions": { "baseUrl": "." } }"#,
+        );
+
+        let output = Command::new("node")
+            .arg("../../node_modules/typescript/bin/tsc")
+            .args(["--strict", "--isolatedModules", "--noEmit", "--project"])
+            .arg(&dir)
+            .output()
+            .unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(
+            output.status.success(),
+            "tsc failed:\n{}",
+            String::from_utf8_lossy(&output.stdout)
+        );
+    }
+
+    /// Evaluate a JavaScript module with node, returning whatever it logged.
+    fn run_node(js: &str) -> String {
+        use std::{
+            io::Write,
+            process::{Command, Stdio},
+        };
+
+        let mut child = Command::new("node")
+            .arg("--input-type=module")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        let child_stdin = child.stdin.as_mut().unwrap();
+        child_stdin.write_all(js.as_bytes()).unwrap();
+        // Close stdin to finish and avoid indefinite blocking
+        drop(child_stdin);
+
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success(), "node failed to evaluate:\n{}", js);
+        String::from_utf8(output.stdout).unwrap()
+    }
+
     /// Use prettier to make sure the generated code is valid syntactically.
     fn prettier(text: &str) -> String {
         use std::{
@@ -94,17 +798,23 @@ mod tests {
         String::from_utf8(output.stdout).unwrap()
     }
 
-    fn mk_everything() -> checker::Everything {
-        let source = r#"
-            module Data.Stuff exports (..);
-            type Maybe(a) = Just(a) | Nothing;
-            type Five = Five;
-            five : Int = 5;
-            five_string = "five" ;
+    static DATA_STUFF: &str = r#"
+        module Data.Stuff exports (..);
+        type Maybe(a) = Just(a) | Nothing;
+        type Five = Five;
+        five : Int = 5;
+        five_string = "five" ;
+        just_five = Just(5);
+        @no_inline
+        seed : Int = 42;
+        @inline
+        add_five = (n: Int): Int -> n + 5;
 
-            id = (a) -> a;
-        "#;
-        let cst_module = cst::Module::parse(source).unwrap();
+        id = (a) -> a;
+    "#;
+
+    fn mk_everything() -> checker::Everything {
+        let cst_module = cst::Module::parse(DATA_STUFF).unwrap();
         let (ast_module, _warnings) =
             checker::check_module(&checker::Everything::default(), cst_module).unwrap();
         let exports = ast_module.exports;
@@ -124,6 +834,27 @@ mod tests {
         }
     }
 
+    fn mk_inline_values(everything: &checker::Everything) -> js::InlineValues {
+        let package_modules = everything
+            .packages
+            .iter()
+            .flat_map(|(package_name, modules)| {
+                modules.iter().map(move |(module_name, exports)| {
+                    ((Some(package_name.clone()), module_name.clone()), exports)
+                })
+            });
+        let modules = everything
+            .modules
+            .iter()
+            .map(|(module_name, exports)| ((None, module_name.clone()), exports));
+        package_modules
+            .chain(modules)
+            .flat_map(|(module_name, exports)| {
+                js::exported_inline_values(&module_name, exports).collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
     fn module_name_to_path((package_name, module_name): ast::FullyQualifiedModuleName) -> String {
         let module_path = module_name
             .0
@@ -1,75 +1,274 @@
-use crate::ast::{
-    ArrowFunctionBody, Block, BlockStatement, Expression, Ident, ImportStatement, Module,
-    ModuleStatement,
+use crate::{
+    ast::{
+        ArrowFunctionBody, Block, BlockStatement, Expression, Ident, ImportStatement, Module,
+        ModuleStatement, Operator, ReexportStatement,
+    },
+    convert::ModuleFormat,
 };
+use ditto_ast::Span;
+
+pub fn render_module(module_format: ModuleFormat, layout: Layout, module: Module) -> String {
+    render_module_with_origins(module_format, layout, module).0
+}
 
-pub fn render_module(module: Module) -> String {
+/// Render a module, along with the (zero-based) line that each statement with
+/// a known origin was rendered on.
+pub fn render_module_with_origins(
+    module_format: ModuleFormat,
+    layout: Layout,
+    module: Module,
+) -> (String, Vec<(usize, Span)>) {
     let mut accum = String::new();
-    module.render(&mut accum);
-    accum
+    if let Some(ref banner) = module.banner {
+        for line in banner.lines() {
+            accum.push_str("// ");
+            accum.push_str(line);
+            accum.push_str(NEWLINE);
+        }
+    }
+    match module_format {
+        ModuleFormat::Esm => {
+            module.imports.iter().for_each(|import| {
+                import.render_layout(layout, &mut accum);
+                accum.push_str(NEWLINE);
+            });
+            module.reexports.iter().for_each(|reexport| {
+                reexport.render_layout(layout, &mut accum);
+                accum.push_str(NEWLINE);
+            });
+        }
+        ModuleFormat::Cjs => {
+            // ES modules are always strict, so make sure we behave the same
+            accum.push_str("\"use strict\";");
+            accum.push_str(NEWLINE);
+            module.imports.iter().for_each(|import| {
+                CommonJsImport(import).render_layout(layout, &mut accum);
+                accum.push_str(NEWLINE);
+            });
+        }
+    }
+
+    let mut line = accum.matches('\n').count();
+    let mut origins = Vec::new();
+    module.statements.iter().for_each(|(stmt, span)| {
+        if let Some(span) = span {
+            origins.push((line, *span));
+        }
+        let start = accum.len();
+        stmt.render_layout(layout, &mut accum);
+        accum.push_str(NEWLINE);
+        line += accum[start..].matches('\n').count();
+    });
+
+    let mut exports = module
+        .exports
+        .iter()
+        .map(|ident| ident.0.clone())
+        .collect::<Vec<_>>();
+    if module_format == ModuleFormat::Cjs {
+        // CommonJS has no `export ... from`, so forward the properties instead
+        for ReexportStatement { idents, path } in module.reexports.iter() {
+            exports.extend(idents.iter().map(|ident| {
+                if layout.pretty {
+                    format!("{0}: require(\"{1}\").{0}", ident.0, path)
+                } else {
+                    format!("{0}:require(\"{1}\").{0}", ident.0, path)
+                }
+            }));
+        }
+    }
+    let exports = if layout.pretty && !exports.is_empty() {
+        format!("{{ {} }}", exports.join(", "))
+    } else {
+        format!("{{{}}}", exports.join(","))
+    };
+    match module_format {
+        ModuleFormat::Esm => accum.push_str(&format!("export {};", exports)),
+        ModuleFormat::Cjs => accum.push_str(&format!("module.exports = {};", exports)),
+    }
+    accum.push_str(NEWLINE);
+    (accum, origins)
 }
 
 #[cfg(windows)]
-static NEWLINE: &str = "\r\n";
+pub(crate) static NEWLINE: &str = "\r\n";
 
 #[cfg(not(windows))]
-static NEWLINE: &str = "\n";
+pub(crate) static NEWLINE: &str = "\n";
+
+static INDENT: &str = "  ";
 
 pub(crate) trait Render {
     // REVIEW I doubt pushing to a String like this is the most efficient solution?
     fn render(&self, accum: &mut String);
 }
 
-impl Render for Module {
-    fn render(&self, accum: &mut String) {
-        self.imports.iter().for_each(|import| {
-            import.render(accum);
-            accum.push_str(NEWLINE);
-        });
-        self.statements.iter().for_each(|stmt| {
-            stmt.render(accum);
+/// How generated code is laid out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Layout {
+    /// Whether code is spread over indented lines, rather than kept as compact as possible.
+    pretty: bool,
+    /// The current level of indentation, if `pretty`.
+    depth: usize,
+}
+
+impl Layout {
+    pub const COMPACT: Self = Self {
+        pretty: false,
+        depth: 0,
+    };
+
+    pub const PRETTY: Self = Self {
+        pretty: true,
+        depth: 0,
+    };
+
+    fn indented(self) -> Self {
+        Self {
+            depth: self.depth + 1,
+            ..self
+        }
+    }
+
+    /// Start a new (indented) line, if laying out prettily.
+    fn newline(self, accum: &mut String) {
+        if self.pretty {
             accum.push_str(NEWLINE);
-        });
+            for _ in 0..self.depth {
+                accum.push_str(INDENT);
+            }
+        }
+    }
 
-        accum.push_str("export {");
-        accum.push_str(
-            &self
-                .exports
-                .iter()
-                .map(|ident| ident.0.as_str())
-                .collect::<Vec<_>>()
-                .join(","),
-        );
-        accum.push_str("};");
-        accum.push_str(NEWLINE);
+    /// A single space, if laying out prettily.
+    fn space(self, accum: &mut String) {
+        if self.pretty {
+            accum.push(' ');
+        }
     }
 }
 
-impl Render for ImportStatement {
-    fn render(&self, accum: &mut String) {
-        accum.push_str("import {");
-        for (aliased, ident) in self.idents.iter() {
-            accum.push_str(&format!("{} as {}", aliased.0, ident.0));
+/// Like [Render], but for code that can be laid out over multiple lines.
+trait RenderLayout {
+    fn render_layout(&self, layout: Layout, accum: &mut String);
+}
+
+/// Compact rendering is the default.
+macro_rules! impl_render_compact {
+    ($($renderable:ty),*) => {
+        $(
+            impl Render for $renderable {
+                fn render(&self, accum: &mut String) {
+                    self.render_layout(Layout::COMPACT, accum)
+                }
+            }
+        )*
+    };
+}
+
+impl_render_compact!(
+    ImportStatement,
+    ModuleStatement,
+    Block,
+    BlockStatement,
+    Expression,
+    ArrowFunctionBody
+);
+
+/// Render comma separated items.
+///
+/// Compact lists always have a trailing comma, whereas pretty ones never do.
+fn render_comma_separated<T>(
+    items: &[T],
+    layout: Layout,
+    accum: &mut String,
+    render_item: impl Fn(&T, &mut String),
+) {
+    for (i, item) in items.iter().enumerate() {
+        if layout.pretty && i > 0 {
+            accum.push_str(", ");
+        }
+        render_item(item, accum);
+        if !layout.pretty {
             accum.push(',');
         }
-        accum.push_str(&format!("}} from \"{}\";", self.path));
     }
 }
 
-impl Render for ModuleStatement {
-    fn render(&self, accum: &mut String) {
+/// Render the names of function parameters, e.g. `a,b` or `a, b`.
+fn render_parameters(parameters: &[Ident], layout: Layout, accum: &mut String) {
+    accum.push_str(
+        &parameters
+            .iter()
+            .map(|ident| ident.0.as_str())
+            .collect::<Vec<&str>>()
+            .join(if layout.pretty { ", " } else { "," }),
+    );
+}
+
+/// Render `{a,b,}` or `{ a, b }`.
+fn render_braces<T>(
+    items: &[T],
+    layout: Layout,
+    accum: &mut String,
+    render_item: impl Fn(&T, &mut String),
+) {
+    accum.push('{');
+    if !items.is_empty() {
+        layout.space(accum);
+        render_comma_separated(items, layout, accum, render_item);
+        layout.space(accum);
+    }
+    accum.push('}');
+}
+
+impl RenderLayout for ImportStatement {
+    fn render_layout(&self, layout: Layout, accum: &mut String) {
+        accum.push_str("import ");
+        render_braces(&self.idents, layout, accum, |(aliased, ident), accum| {
+            accum.push_str(&format!("{} as {}", aliased.0, ident.0));
+        });
+        accum.push_str(&format!(" from \"{}\";", self.path));
+    }
+}
+
+impl RenderLayout for ReexportStatement {
+    fn render_layout(&self, layout: Layout, accum: &mut String) {
+        accum.push_str("export ");
+        render_braces(&self.idents, layout, accum, |ident, accum| {
+            accum.push_str(&ident.0);
+        });
+        accum.push_str(&format!(" from \"{}\";", self.path));
+    }
+}
+
+/// Renders an [ImportStatement] using `require(...)`.
+struct CommonJsImport<'a>(&'a ImportStatement);
+
+impl RenderLayout for CommonJsImport<'_> {
+    fn render_layout(&self, layout: Layout, accum: &mut String) {
+        accum.push_str("const ");
+        render_braces(&self.0.idents, layout, accum, |(aliased, ident), accum| {
+            accum.push_str(&format!("{}: {}", aliased.0, ident.0));
+        });
+        accum.push_str(&format!(" = require(\"{}\");", self.0.path));
+    }
+}
+
+impl RenderLayout for ModuleStatement {
+    fn render_layout(&self, layout: Layout, accum: &mut String) {
         match self {
             Self::LetDeclaration { ident } => {
                 accum.push_str(&format!("let {ident};", ident = ident.0));
             }
             Self::ConstAssignment { ident, value } => {
                 accum.push_str(&format!("const {ident} = ", ident = ident.0));
-                value.render(accum);
+                value.render_layout(layout, accum);
                 accum.push(';');
             }
             Self::Assignment { ident, value } => {
                 accum.push_str(&format!("{ident} = ", ident = ident.0));
-                value.render(accum);
+                value.render_layout(layout, accum);
                 accum.push(';');
             }
             Self::Function {
@@ -77,84 +276,92 @@ impl Render for ModuleStatement {
                 parameters,
                 body,
             } => {
-                accum.push_str(&format!(
-                    "function {ident}({parameters})",
-                    ident = ident.0,
-                    parameters = parameters
-                        .iter()
-                        .map(|ident| ident.0.as_str())
-                        .collect::<Vec<&str>>()
-                        .join(",")
-                ));
-                body.render(accum);
+                accum.push_str(&format!("function {ident}(", ident = ident.0));
+                render_parameters(parameters, layout, accum);
+                accum.push(')');
+                layout.space(accum);
+                body.render_layout(layout, accum);
+            }
+            Self::If { condition, block } => {
+                accum.push_str("if (");
+                condition.render_layout(layout, accum);
+                accum.push(')');
+                layout.space(accum);
+                block.render_layout(layout, accum);
             }
         }
     }
 }
 
-impl Render for Block {
-    fn render(&self, accum: &mut String) {
+impl RenderLayout for Block {
+    fn render_layout(&self, layout: Layout, accum: &mut String) {
         accum.push('{');
         self.0.iter().for_each(|stmt| {
-            stmt.render(accum);
+            layout.indented().newline(accum);
+            stmt.render_layout(layout.indented(), accum);
         });
+        if !self.0.is_empty() {
+            layout.newline(accum);
+        }
         accum.push('}');
     }
 }
 
-impl Render for BlockStatement {
-    fn render(&self, accum: &mut String) {
+impl RenderLayout for BlockStatement {
+    fn render_layout(&self, layout: Layout, accum: &mut String) {
         match self {
             Self::Return(None) => {
                 accum.push_str("return;");
             }
             Self::Return(Some(expression)) => {
                 accum.push_str("return ");
-                expression.render(accum);
+                expression.render_layout(layout, accum);
                 accum.push(';');
             }
-            Self::_ConstAssignment { ident, value } => {
+            Self::ConstAssignment { ident, value } => {
                 accum.push_str(&format!("const {ident} = ", ident = ident.0));
-                value.render(accum);
+                value.render_layout(layout, accum);
+                accum.push(';');
+            }
+            Self::If { condition, block } => {
+                accum.push_str("if (");
+                condition.render_layout(layout, accum);
+                accum.push(')');
+                layout.space(accum);
+                block.render_layout(layout, accum);
+            }
+            Self::Throw(message) => {
+                accum.push_str(&format!("throw new Error(\"{}\");", message));
+            }
+            Self::Expression(expression) => {
+                expression.render_layout(layout, accum);
                 accum.push(';');
             }
         }
     }
 }
 
-impl Render for Expression {
-    fn render(&self, accum: &mut String) {
+impl RenderLayout for Expression {
+    fn render_layout(&self, layout: Layout, accum: &mut String) {
         match self {
             Self::Variable(ident) => {
                 accum.push_str(&ident.0);
             }
             Self::ArrowFunction { parameters, body } => {
-                accum.push_str(&format!(
-                    "({parameters}) => ",
-                    parameters = parameters
-                        .iter()
-                        .map(|ident| ident.0.as_str())
-                        .collect::<Vec<&str>>()
-                        .join(",")
-                ));
-                body.render(accum)
+                accum.push('(');
+                render_parameters(parameters, layout, accum);
+                accum.push_str(") => ");
+                body.render_layout(layout, accum)
             }
             Self::Call {
                 function,
                 arguments,
             } => {
                 let function_needs_parens = matches!(**function, Self::ArrowFunction { .. });
-                if function_needs_parens {
-                    accum.push('(')
-                }
-                function.render(accum);
-                if function_needs_parens {
-                    accum.push(')')
-                }
+                render_with_parens(function, function_needs_parens, layout, accum);
                 accum.push('(');
-                arguments.iter().for_each(|arg| {
-                    arg.render(accum);
-                    accum.push(',');
+                render_comma_separated(arguments, layout, accum, |arg, accum| {
+                    arg.render_layout(layout, accum);
                 });
                 accum.push(')');
             }
@@ -167,26 +374,93 @@ impl Render for Expression {
                     **condition,
                     Self::ArrowFunction { .. } | Self::Conditional { .. }
                 );
-                if condition_needs_parens {
-                    accum.push('(');
-                }
-                condition.render(accum);
-                if condition_needs_parens {
-                    accum.push(')');
+                render_with_parens(condition, condition_needs_parens, layout, accum);
+                accum.push_str(if layout.pretty { " ? " } else { "?" });
+                true_clause.render_layout(layout, accum);
+                accum.push_str(if layout.pretty { " : " } else { ":" });
+                false_clause.render_layout(layout, accum);
+            }
+            Self::Operator { op, lhs, rhs } => {
+                // NOTE we don't try to be clever about JavaScript precedence,
+                // operands that are themselves (different) operators are always
+                // wrapped in parens.
+                let needs_parens = |operand: &Self, is_rhs: bool| match operand {
+                    Self::Operator { op: operand_op, .. } => {
+                        operand_op != op || (is_rhs && !op.is_associative())
+                    }
+                    Self::ArrowFunction { .. } | Self::Conditional { .. } => true,
+                    // Avoid `a--b`
+                    Self::Negate(_) => is_rhs,
+                    Self::Number(number_string) => is_rhs && number_string.starts_with('-'),
+                    _ => false,
+                };
+                render_with_parens(lhs, needs_parens(lhs, false), layout, accum);
+                let op = match op {
+                    Operator::And => "&&",
+                    Operator::Equals => "===",
+                    Operator::Add => "+",
+                    Operator::Subtract => "-",
+                    Operator::Multiply => "*",
+                    Operator::Divide => "/",
+                    Operator::NotEquals => "!==",
+                    Operator::LessThan => "<",
+                    Operator::LessThanOrEquals => "<=",
+                    Operator::GreaterThan => ">",
+                    Operator::GreaterThanOrEquals => ">=",
+                    Operator::InstanceOf => "instanceof",
+                    Operator::Or => "||",
+                };
+                if layout.pretty || op == "instanceof" {
+                    accum.push_str(&format!(" {} ", op));
+                } else {
+                    accum.push_str(op);
                 }
-                accum.push('?');
-                true_clause.render(accum);
-                accum.push(':');
-                false_clause.render(accum);
+                render_with_parens(rhs, needs_parens(rhs, true), layout, accum);
+            }
+            Self::Not(operand) => {
+                accum.push('!');
+                render_with_parens(operand, target_needs_parens(operand), layout, accum);
+            }
+            Self::Negate(operand) => {
+                accum.push('-');
+                // Avoid `--a`
+                let operand_needs_parens = match **operand {
+                    Self::Negate(_) => true,
+                    Self::Number(ref number_string) => number_string.starts_with('-'),
+                    _ => target_needs_parens(operand),
+                };
+                render_with_parens(operand, operand_needs_parens, layout, accum);
+            }
+            Self::TypeOf(operand) => {
+                accum.push_str("typeof ");
+                render_with_parens(operand, target_needs_parens(operand), layout, accum);
+            }
+            Self::IndexAccess { target, index } => {
+                render_with_parens(target, target_needs_parens(target), layout, accum);
+                accum.push('[');
+                index.render_layout(layout, accum);
+                accum.push(']');
+            }
+            Self::PropertyAccess { target, property } => {
+                render_with_parens(target, target_needs_parens(target), layout, accum);
+                accum.push('.');
+                accum.push_str(property);
             }
             Self::Array(expressions) => {
                 accum.push('[');
-                expressions.iter().for_each(|expr| {
-                    expr.render(accum);
-                    accum.push(',');
+                render_comma_separated(expressions, layout, accum, |expr, accum| {
+                    expr.render_layout(layout, accum);
                 });
                 accum.push(']');
             }
+            Self::Object(properties) => {
+                render_braces(properties, layout, accum, |(key, value), accum| {
+                    accum.push_str(key);
+                    accum.push(':');
+                    layout.space(accum);
+                    value.render_layout(layout, accum);
+                });
+            }
             Self::Number(number_string) => {
                 accum.push_str(number_string);
             }
@@ -204,15 +478,51 @@ impl Render for Expression {
             Self::Undefined => {
                 accum.push_str("undefined");
             }
+            Self::Raw(code) => {
+                // Always parenthesized, so that precedence can't leak
+                accum.push('(');
+                accum.push_str(code);
+                accum.push(')');
+            }
         }
     }
 }
 
-impl Render for ArrowFunctionBody {
-    fn render(&self, accum: &mut String) {
+fn target_needs_parens(target: &Expression) -> bool {
+    matches!(
+        target,
+        Expression::ArrowFunction { .. }
+            | Expression::Conditional { .. }
+            | Expression::Operator { .. }
+            | Expression::Number(_)
+            | Expression::Object(_)
+    )
+}
+
+fn render_with_parens(
+    expression: &Expression,
+    needs_parens: bool,
+    layout: Layout,
+    accum: &mut String,
+) {
+    if needs_parens {
+        accum.push('(');
+    }
+    expression.render_layout(layout, accum);
+    if needs_parens {
+        accum.push(')');
+    }
+}
+
+impl RenderLayout for ArrowFunctionBody {
+    fn render_layout(&self, layout: Layout, accum: &mut String) {
         match self {
-            Self::_Block(block) => block.render(accum),
-            Self::Expression(expression) => expression.render(accum),
+            Self::Block(block) => block.render_layout(layout, accum),
+            Self::Expression(expression @ Expression::Object(_)) => {
+                // Otherwise the braces would be parsed as a block
+                render_with_parens(expression, true, layout, accum);
+            }
+            Self::Expression(expression) => expression.render_layout(layout, accum),
         }
     }
 }
@@ -239,8 +549,34 @@ mod tests {
         assert_render!(Expression::True, "true");
         assert_render!(Expression::False, "false");
         assert_render!(Expression::Undefined, "undefined");
+        assert_render!(Expression::Raw("1 + 2".to_string()), "(1 + 2)");
+        assert_render!(
+            Expression::Operator {
+                op: Operator::NotEquals,
+                lhs: Box::new(Expression::TypeOf(Box::new(Expression::Variable(ident!(
+                    "f"
+                ))))),
+                rhs: Box::new(Expression::String("function".to_string())),
+            },
+            "typeof f!==\"function\""
+        );
 
         assert_render!(Expression::Number("42".to_string()), "42");
+
+        assert_render!(
+            Expression::Object(vec![
+                ("$".to_string(), Expression::String("Person".to_string())),
+                ("age".to_string(), Expression::Number("5".to_string())),
+            ]),
+            "{$:\"Person\",age:5,}"
+        );
+        assert_render!(
+            Expression::ArrowFunction {
+                parameters: vec![],
+                body: Box::new(ArrowFunctionBody::Expression(Expression::Object(vec![])))
+            },
+            "() => ({})"
+        );
         assert_render!(Expression::String("five".to_string()), "\"five\"");
 
         assert_render!(Expression::Variable(ident!("foo")), "foo");
@@ -257,7 +593,7 @@ mod tests {
         assert_render!(
             Expression::ArrowFunction {
                 parameters: vec![ident!("a")],
-                body: Box::new(ArrowFunctionBody::_Block(Block(vec![
+                body: Box::new(ArrowFunctionBody::Block(Block(vec![
                     BlockStatement::Return(Some(Expression::String("hello".to_string())))
                 ]))),
             },
@@ -334,6 +670,84 @@ mod tests {
             },
             "(true?true:false)?false?0:1:false?2:3"
         );
+
+        assert_render!(
+            Expression::Operator {
+                op: Operator::And,
+                lhs: Box::new(Expression::Operator {
+                    op: Operator::Equals,
+                    lhs: Box::new(Expression::IndexAccess {
+                        target: Box::new(Expression::Variable(ident!("a"))),
+                        index: Box::new(Expression::Number("0".to_string())),
+                    }),
+                    rhs: Box::new(Expression::String("Just".to_string())),
+                }),
+                rhs: Box::new(Expression::Operator {
+                    op: Operator::Equals,
+                    lhs: Box::new(Expression::PropertyAccess {
+                        target: Box::new(Expression::Variable(ident!("a"))),
+                        property: "$".to_string(),
+                    }),
+                    rhs: Box::new(Expression::String("Person".to_string())),
+                }),
+            },
+            "(a[0]===\"Just\")&&(a.$===\"Person\")"
+        );
+
+        assert_render!(
+            Expression::Not(Box::new(Expression::Operator {
+                op: Operator::InstanceOf,
+                lhs: Box::new(Expression::Variable(ident!("a"))),
+                rhs: Box::new(Expression::Variable(ident!("Object"))),
+            })),
+            "!(a instanceof Object)"
+        );
+
+        assert_render!(
+            Expression::Operator {
+                op: Operator::Subtract,
+                lhs: Box::new(Expression::Operator {
+                    op: Operator::Subtract,
+                    lhs: Box::new(Expression::Number("1".to_string())),
+                    rhs: Box::new(Expression::Number("2".to_string())),
+                }),
+                rhs: Box::new(Expression::Operator {
+                    op: Operator::Subtract,
+                    lhs: Box::new(Expression::Number("3".to_string())),
+                    rhs: Box::new(Expression::Operator {
+                        op: Operator::Multiply,
+                        lhs: Box::new(Expression::Number("4".to_string())),
+                        rhs: Box::new(Expression::Number("5".to_string())),
+                    }),
+                }),
+            },
+            "1-2-(3-(4*5))"
+        );
+
+        assert_render!(
+            Expression::Negate(Box::new(Expression::Variable(ident!("a")))),
+            "-a"
+        );
+        assert_render!(
+            Expression::Negate(Box::new(Expression::Negate(Box::new(
+                Expression::Variable(ident!("a"))
+            )))),
+            "-(-a)"
+        );
+        assert_render!(
+            Expression::Negate(Box::new(Expression::Number("-5".to_string()))),
+            "-(-5)"
+        );
+        assert_render!(
+            Expression::Operator {
+                op: Operator::Subtract,
+                lhs: Box::new(Expression::Number("-1".to_string())),
+                rhs: Box::new(Expression::Negate(Box::new(Expression::Variable(ident!(
+                    "a"
+                ))))),
+            },
+            "-1-(-a)"
+        );
     }
 
     #[test]
@@ -343,6 +757,31 @@ mod tests {
             "return true;"
         );
         assert_render!(BlockStatement::Return(None), "return;");
+        assert_render!(
+            BlockStatement::ConstAssignment {
+                ident: ident!("a"),
+                value: Expression::True,
+            },
+            "const a = true;"
+        );
+        assert_render!(
+            BlockStatement::If {
+                condition: Expression::Variable(ident!("a")),
+                block: Block(vec![BlockStatement::Return(Some(Expression::True))]),
+            },
+            "if (a){return true;}"
+        );
+        assert_render!(
+            BlockStatement::Throw("Pattern match error".to_string()),
+            "throw new Error(\"Pattern match error\");"
+        );
+        assert_render!(
+            BlockStatement::Expression(Expression::Call {
+                function: Box::new(Expression::Variable(ident!("f"))),
+                arguments: vec![Expression::True],
+            }),
+            "f(true,);"
+        );
     }
 
     #[test]
@@ -353,6 +792,76 @@ mod tests {
         );
     }
 
+    #[test]
+    fn it_renders_pretty_code() {
+        assert_render_pretty!(
+            Expression::Object(vec![
+                ("$".to_string(), Expression::String("Person".to_string())),
+                ("age".to_string(), Expression::Number("5".to_string())),
+            ]),
+            "{ $: \"Person\", age: 5 }"
+        );
+        assert_render_pretty!(Expression::Object(vec![]), "{}");
+        assert_render_pretty!(
+            Expression::Call {
+                function: Box::new(Expression::Variable(ident!("f"))),
+                arguments: vec![Expression::True, Expression::Array(vec![Expression::False])]
+            },
+            "f(true, [false])"
+        );
+        assert_render_pretty!(
+            Expression::Conditional {
+                condition: Box::new(Expression::True),
+                true_clause: Box::new(Expression::Number("0".to_string())),
+                false_clause: Box::new(Expression::Number("1".to_string())),
+            },
+            "true ? 0 : 1"
+        );
+        assert_render_pretty!(
+            Expression::Operator {
+                op: Operator::And,
+                lhs: Box::new(Expression::Operator {
+                    op: Operator::Equals,
+                    lhs: Box::new(Expression::Variable(ident!("a"))),
+                    rhs: Box::new(Expression::String("Just".to_string())),
+                }),
+                rhs: Box::new(Expression::Variable(ident!("b"))),
+            },
+            "(a === \"Just\") && b"
+        );
+        assert_render_pretty!(
+            ModuleStatement::Function {
+                ident: ident!("f"),
+                parameters: vec![ident!("a"), ident!("b")],
+                body: Block(vec![
+                    BlockStatement::If {
+                        condition: Expression::Variable(ident!("a")),
+                        block: Block(vec![BlockStatement::Return(Some(
+                            Expression::ArrowFunction {
+                                parameters: vec![],
+                                body: Box::new(ArrowFunctionBody::Block(Block(vec![
+                                    BlockStatement::Return(Some(Expression::Variable(ident!(
+                                        "b"
+                                    ))))
+                                ]))),
+                            }
+                        ))]),
+                    },
+                    BlockStatement::Return(None),
+                ]),
+            },
+            "function f(a, b) {\n  if (a) {\n    return () => {\n      return b;\n    };\n  }\n  return;\n}"
+        );
+        assert_render_pretty!(
+            ModuleStatement::Function {
+                ident: ident!("f"),
+                parameters: vec![],
+                body: Block(vec![]),
+            },
+            "function f() {}"
+        );
+    }
+
     #[test]
     fn it_renders_module_statements() {
         assert_render!(
@@ -385,6 +894,13 @@ mod tests {
             },
             "huh = 42;"
         );
+        assert_render!(
+            ModuleStatement::If {
+                condition: Expression::Variable(ident!("a")),
+                block: Block(vec![BlockStatement::Throw("nope".to_string())]),
+            },
+            "if (a){throw new Error(\"nope\");}"
+        );
     }
 }
 
@@ -399,4 +915,18 @@ mod test_macros {
     }
 
     pub(super) use assert_render;
+
+    macro_rules! assert_render_pretty {
+        ($renderable:expr, $want:expr) => {{
+            let mut accum = String::new();
+            $crate::render::RenderLayout::render_layout(
+                &$renderable,
+                $crate::render::Layout::PRETTY,
+                &mut accum,
+            );
+            assert_eq!(accum.replace($crate::render::NEWLINE, "\n"), $want);
+        }};
+    }
+
+    pub(super) use assert_render_pretty;
 }
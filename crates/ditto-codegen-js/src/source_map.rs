@@ -0,0 +1,141 @@
+//! Version 3 source maps, see <https://sourcemaps.info/spec.html>.
+//!
+//! Mappings are per-line: each generated statement maps back to the start of
+//! the ditto declaration that it came from.
+
+use crate::convert::SourceMapConfig;
+use ditto_ast::Span;
+
+/// Generate a source map for generated code, where each of the `origins` maps a
+/// (zero-based) generated line to the span of ditto code it came from.
+pub fn generate_source_map(config: &SourceMapConfig, origins: &[(usize, Span)]) -> String {
+    let line_starts = std::iter::once(0)
+        .chain(config.source.match_indices('\n').map(|(i, _)| i + 1))
+        .collect::<Vec<_>>();
+
+    let mut mappings = String::new();
+    let mut generated_line = 0;
+    let mut previous_source_line = 0;
+    let mut previous_source_column = 0;
+    for (line, span) in origins {
+        if *line < generated_line {
+            // Only the first statement on a line is mapped
+            continue;
+        }
+        while generated_line < *line {
+            mappings.push(';');
+            generated_line += 1;
+        }
+        let source_line = match line_starts.binary_search(&span.start_offset) {
+            Ok(i) => i,
+            Err(i) => i - 1,
+        };
+        let source_column = config.source[line_starts[source_line]..span.start_offset]
+            .encode_utf16()
+            .count() as i64;
+        let source_line = source_line as i64;
+
+        // [generated column, source index, source line, source column]
+        encode_vlq(0, &mut mappings);
+        encode_vlq(0, &mut mappings);
+        encode_vlq(source_line - previous_source_line, &mut mappings);
+        encode_vlq(source_column - previous_source_column, &mut mappings);
+        previous_source_line = source_line;
+        previous_source_column = source_column;
+        // Move on, so that later statements on the same line are skipped
+        mappings.push(';');
+        generated_line += 1;
+    }
+
+    format!(
+        r#"{{"version":3,"file":{},"sources":[{}],"names":[],"mappings":{}}}"#,
+        json_string(&config.file),
+        json_string(&config.source_path),
+        json_string(mappings.trim_end_matches(';'))
+    )
+}
+
+static BASE64: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn encode_vlq(value: i64, accum: &mut String) {
+    // The sign is stored in the least significant bit
+    let mut vlq = if value < 0 {
+        ((-value) << 1) | 1
+    } else {
+        value << 1
+    };
+    loop {
+        let mut digit = vlq & 0b11111;
+        vlq >>= 5;
+        if vlq > 0 {
+            // Continuation bit
+            digit |= 0b100000;
+        }
+        accum.push(BASE64[digit as usize] as char);
+        if vlq == 0 {
+            break;
+        }
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut accum = String::from('"');
+    for c in value.chars() {
+        match c {
+            '"' => accum.push_str("\\\""),
+            '\\' => accum.push_str("\\\\"),
+            '\n' => accum.push_str("\\n"),
+            '\r' => accum.push_str("\\r"),
+            '\t' => accum.push_str("\\t"),
+            c if c.is_control() => accum.push_str(&format!("\\u{:04x}", c as u32)),
+            c => accum.push(c),
+        }
+    }
+    accum.push('"');
+    accum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vlq(value: i64) -> String {
+        let mut accum = String::new();
+        encode_vlq(value, &mut accum);
+        accum
+    }
+
+    #[test]
+    fn it_encodes_vlqs() {
+        assert_eq!(vlq(0), "A");
+        assert_eq!(vlq(1), "C");
+        assert_eq!(vlq(-1), "D");
+        assert_eq!(vlq(15), "e");
+        assert_eq!(vlq(16), "gB");
+        assert_eq!(vlq(-17), "jB");
+        assert_eq!(vlq(1000), "w+B");
+    }
+
+    #[test]
+    fn it_maps_lines() {
+        let config = SourceMapConfig {
+            file: String::from("Test.js"),
+            source_path: String::from("../src/Test.ditto"),
+            source: String::from("module Test exports (..);\nfive = 5;\n  \"five\" = 5;\n"),
+        };
+        let five = config.source.find("five").unwrap();
+        let quoted = config.source.find("\"five\"").unwrap();
+        let source_map = generate_source_map(
+            &config,
+            &[
+                (1, Span::new(five, five + 4)),
+                (1, Span::new(quoted, quoted + 6)),
+                (3, Span::new(quoted, quoted + 6)),
+            ],
+        );
+        assert_eq!(
+            source_map,
+            r#"{"version":3,"file":"Test.js","sources":["../src/Test.ditto"],"names":[],"mappings":";AACA;;AACE"}"#
+        );
+    }
+}
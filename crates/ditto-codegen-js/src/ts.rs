@@ -2,7 +2,7 @@
 use crate::{
     ast::{ident, Ident},
     render::Render,
-    Config,
+    Config, UnitRepresentation,
 };
 use ditto_ast as ast;
 use std::{
@@ -33,7 +33,7 @@ fn convert_exports(
     macro_rules! convert_type {
         ($ast_type:expr, $type_from_variable:expr) => {{
             let (converted_type, referenced_modules) =
-                convert_type($ast_type, module_name, $type_from_variable);
+                convert_type($ast_type, module_name, config.unit, $type_from_variable);
 
             imports.extend(referenced_modules.into_iter().map(|module_name| {
                 (
@@ -47,15 +47,20 @@ fn convert_exports(
     }
 
     for (type_name, exported_type) in exports.types.iter() {
-        let mut type_generics = match &exported_type.kind {
-            ast::Kind::Type => Vec::new(),
-            ast::Kind::Variable(_) => unreachable!(),
-            ast::Kind::Function { parameters, .. } => parameters
-                .iter()
-                .enumerate()
-                .map(|(i, _)| mk_type_variable_ident(i))
-                .collect(),
-        };
+        let type_generics = exports
+            .constructors
+            .values()
+            .filter(|constructor| constructor.return_type_name == *type_name)
+            .find_map(|constructor| constructor_type_generics(&constructor.constructor_type))
+            .unwrap_or_else(|| match &exported_type.kind {
+                ast::Kind::Type => Vec::new(),
+                ast::Kind::Variable(_) => unreachable!(),
+                ast::Kind::Function { parameters, .. } => parameters
+                    .iter()
+                    .enumerate()
+                    .map(|(i, _)| mk_type_variable_ident(i))
+                    .collect(),
+            });
         let mut constructor_types = Vec::new();
         for (constructor_name, constructor) in exports.constructors.iter() {
             if constructor.return_type_name == *type_name {
@@ -72,14 +77,22 @@ fn convert_exports(
                             ));
                         }
                     }
-                    (constructor_name.0.clone(), Type::Tuple(types))
+                    if let Some(ref field_names) = constructor.field_names {
+                        let properties = std::iter::once(String::from("$"))
+                            .chain(field_names.iter().map(|name| name.0.clone()))
+                            .zip(types)
+                            .collect();
+                        (constructor_name.0.clone(), Type::Object(properties))
+                    } else {
+                        (constructor_name.0.clone(), Type::Tuple(types))
+                    }
                 })
             }
         }
         if cfg!(debug_assertions) {
             // Sort for determinsim
+            // (type generics are positional, so they're left alone)
             constructor_types.sort_by(|a, b| a.0.cmp(&b.0));
-            type_generics.sort_by(|a, b| a.0.cmp(&b.0));
         }
 
         let type_name = Ident::from(type_name.clone());
@@ -157,15 +170,42 @@ fn mk_type_variable_ident(i: usize) -> Ident {
     ident!(format!("T{}", i))
 }
 
+/// Generics for a type, named after the type variables its constructors return.
+///
+/// This keeps the generics in a type declaration consistent with those used
+/// in the constructor fields. Returns `None` if the return type isn't
+/// applied to type variables.
+fn constructor_type_generics(constructor_type: &ast::Type) -> Option<Vec<Ident>> {
+    let return_type = match constructor_type {
+        ast::Type::Function {
+            box return_type, ..
+        } => return_type,
+        other => other,
+    };
+    if let ast::Type::Call { arguments, .. } = return_type {
+        arguments
+            .iter()
+            .map(|argument| match argument {
+                ast::Type::Variable { var, .. } => Some(mk_type_variable_ident(*var)),
+                _ => None,
+            })
+            .collect()
+    } else {
+        None
+    }
+}
+
 fn convert_type(
     ast_type: &ast::Type,
     current_module_name: &ast::ModuleName,
+    unit: UnitRepresentation,
     type_from_variable: Box<dyn Fn(usize) -> Type>,
 ) -> (Type, HashSet<ast::FullyQualifiedModuleName>) {
     let mut referenced_modules = HashSet::new();
     let converted = convert_type_rec(
         ast_type,
         current_module_name,
+        unit,
         &type_from_variable,
         &mut referenced_modules,
         true,
@@ -176,6 +216,7 @@ fn convert_type(
 fn convert_type_rec(
     ast_type: &ast::Type,
     current_module_name: &ast::ModuleName,
+    unit: UnitRepresentation,
     type_from_variable: &dyn Fn(usize) -> Type,
     referenced_modules: &mut HashSet<ast::FullyQualifiedModuleName>,
     // TypeScript doesn't support higher-kinds
@@ -183,18 +224,25 @@ fn convert_type_rec(
     need_kind_type: bool,
 ) -> Type {
     match ast_type {
-        ast::Type::PrimConstructor(ast::PrimType::String) => ident!("string").into(),
+        ast::Type::PrimConstructor(ast::PrimType::String | ast::PrimType::Char) => {
+            ident!("string").into()
+        }
         ast::Type::PrimConstructor(ast::PrimType::Float) => ident!("number").into(),
         ast::Type::PrimConstructor(ast::PrimType::Int) => ident!("number").into(),
         ast::Type::PrimConstructor(ast::PrimType::Array) => {
             if need_kind_type {
-                ident!("any").into()
+                ident!("unknown").into()
             } else {
                 ident!("Array").into()
             }
         }
         ast::Type::PrimConstructor(ast::PrimType::Bool) => ident!("boolean").into(),
-        ast::Type::PrimConstructor(ast::PrimType::Unit) => ident!("undefined").into(),
+        // Only meaningful when applied, see below
+        ast::Type::PrimConstructor(ast::PrimType::Maybe) => ident!("unknown").into(),
+        ast::Type::PrimConstructor(ast::PrimType::Unit) => match unit {
+            UnitRepresentation::Undefined => ident!("undefined").into(),
+            UnitRepresentation::Sentinel => ident!("symbol").into(),
+        },
 
         ast::Type::Variable {
             var, variable_kind, ..
@@ -209,7 +257,7 @@ fn convert_type_rec(
                 ast::Kind::Function { .. } => {
                     // No need to check `need_kind_type` because TypeScript doesn't support
                     // higher-kinded generics,
-                    ident!("any").into()
+                    ident!("unknown").into()
                 }
             }
         }
@@ -219,7 +267,7 @@ fn convert_type_rec(
             ..
         } => {
             if need_kind_type && *constructor_kind != ast::Kind::Type {
-                return ident!("any").into();
+                return ident!("unknown").into();
             }
             if canonical_value.module_name.0.is_none()
                 && canonical_value.module_name.1 == *current_module_name
@@ -240,12 +288,29 @@ fn convert_type_rec(
             arguments,
         } => {
             if let ast::Type::Variable { .. } = function {
-                return ident!("any").into();
+                return ident!("unknown").into();
+            }
+
+            // Built-in constructors are represented like any other positional constructor
+            if let ast::Type::PrimConstructor(ast::PrimType::Maybe) = function {
+                let argument = convert_type_rec(
+                    &arguments[0],
+                    current_module_name,
+                    unit,
+                    type_from_variable,
+                    referenced_modules,
+                    true,
+                );
+                return Type::Union(vec![
+                    Type::Tuple(vec![Type::StringLiteral(String::from("Just")), argument]),
+                    Type::Tuple(vec![Type::StringLiteral(String::from("Nothing"))]),
+                ]);
             }
 
             let converted = convert_type_rec(
                 function,
                 current_module_name,
+                unit,
                 type_from_variable,
                 referenced_modules,
                 false,
@@ -258,6 +323,7 @@ fn convert_type_rec(
                             convert_type_rec(
                                 t,
                                 current_module_name,
+                                unit,
                                 type_from_variable,
                                 referenced_modules,
                                 true,
@@ -285,6 +351,7 @@ fn convert_type_rec(
                         convert_type_rec(
                             t,
                             current_module_name,
+                            unit,
                             type_from_variable,
                             referenced_modules,
                             true,
@@ -295,6 +362,7 @@ fn convert_type_rec(
             let return_type = Box::new(convert_type_rec(
                 return_type,
                 current_module_name,
+                unit,
                 type_from_variable,
                 referenced_modules,
                 true,
@@ -304,6 +372,39 @@ fn convert_type_rec(
                 return_type,
             }
         }
+        ast::Type::Record { fields } => Type::Object(
+            fields
+                .iter()
+                .map(|(label, t)| {
+                    (
+                        label.0.clone(),
+                        convert_type_rec(
+                            t,
+                            current_module_name,
+                            unit,
+                            type_from_variable,
+                            referenced_modules,
+                            true,
+                        ),
+                    )
+                })
+                .collect(),
+        ),
+        ast::Type::Tuple { elements } => Type::Tuple(
+            elements
+                .iter()
+                .map(|t| {
+                    convert_type_rec(
+                        t,
+                        current_module_name,
+                        unit,
+                        type_from_variable,
+                        referenced_modules,
+                        true,
+                    )
+                })
+                .collect(),
+        ),
     }
 }
 
@@ -326,8 +427,10 @@ struct DeclarationModule {
 impl Render for DeclarationModule {
     fn render(&self, accum: &mut String) {
         for (ident, path) in self.imports.iter() {
+            // Declarations only ever reference types from other modules
+            // (which matters for `isolatedModules`)
             accum.push_str(&format!(
-                "import * as {ident} from \"{path}\";\n",
+                "import type * as {ident} from \"{path}\";\n",
                 ident = ident.0
             ));
         }
@@ -389,7 +492,8 @@ impl Render for ExportDeclaration {
 
                 accum.push_str(" = ");
                 if constructor_types.is_empty() {
-                    accum.push_str("any"); // REVIEW
+                    // Never `any`, which would be an implicit escape hatch for consumers
+                    accum.push_str("unknown");
                 } else {
                     let len = constructor_types.len();
                     for (i, constructor_type) in constructor_types.iter().enumerate() {
@@ -460,6 +564,8 @@ enum Type {
         return_type: Box<Type>,
     },
     Tuple(Vec<Type>),
+    Object(Vec<(String, Type)>),
+    Union(Vec<Type>),
 }
 
 impl From<Ident> for Type {
@@ -487,6 +593,16 @@ impl Render for Type {
                 }
                 accum.push(']');
             }
+            Self::Object(properties) => {
+                accum.push_str("{ ");
+                for (key, type_) in properties.iter() {
+                    accum.push_str(key);
+                    accum.push_str(": ");
+                    type_.render(accum);
+                    accum.push_str("; ");
+                }
+                accum.push('}');
+            }
             Self::Ident(ident) => ident.render(accum),
             Self::Apply {
                 applied_type,
@@ -509,6 +625,22 @@ impl Render for Type {
             } => {
                 render_function_type(parameters, return_type, accum, true);
             }
+            Self::Union(types) => {
+                let types_len = types.len();
+                for (i, type_) in types.iter().enumerate() {
+                    // `() => A | B` would be parsed as `() => (A | B)`
+                    if let Self::Function { .. } = type_ {
+                        accum.push('(');
+                        type_.render(accum);
+                        accum.push(')');
+                    } else {
+                        type_.render(accum);
+                    }
+                    if i < types_len - 1 {
+                        accum.push_str(" | ");
+                    }
+                }
+            }
         }
     }
 }
@@ -1,18 +1,23 @@
 //! # The ditto config file
 #![warn(missing_docs)]
 
+mod license;
+mod lockfile;
 mod package_set;
 #[cfg(test)]
 mod tests;
+mod validation;
 
 use miette::{Diagnostic, IntoDiagnostic, WrapErr};
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     path::{Path, PathBuf},
 };
 use thiserror::Error;
 
+pub use license::*;
+pub use lockfile::*;
 pub use package_set::*;
 
 /// `"ditto.toml"`
@@ -34,6 +39,22 @@ pub struct Config {
     /// Name of the package being compiled.
     pub name: PackageName,
 
+    /// Version of the package being compiled.
+    pub version: Option<semver::Version>,
+
+    /// A short description of the package.
+    pub description: Option<String>,
+
+    /// License of the package, as an SPDX license expression.
+    pub license: Option<License>,
+
+    /// URL of the package's source repository.
+    pub repository: Option<String>,
+
+    /// People who wrote the package.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub authors: Vec<String>,
+
     /// Code generation targets.
     #[serde(default)]
     pub targets: HashSet<Target>,
@@ -42,6 +63,36 @@ pub struct Config {
     #[serde(default)]
     pub dependencies: Dependencies,
 
+    /// Codes of checker warnings that should be suppressed.
+    #[serde(
+        default,
+        rename = "allow-warnings",
+        skip_serializing_if = "HashSet::is_empty"
+    )]
+    pub allow_warnings: HashSet<String>,
+
+    /// Names of modules that are entry points, which aren't expected to export anything.
+    #[serde(
+        default,
+        rename = "entry-modules",
+        skip_serializing_if = "HashSet::is_empty"
+    )]
+    pub entry_modules: HashSet<String>,
+
+    /// Per-code warning levels, which take precedence over `allow-warnings`,
+    /// and complexity budgets.
+    #[serde(default, skip_serializing_if = "LintsConfig::is_empty")]
+    pub lints: LintsConfig,
+
+    /// Formatter configuration.
+    #[serde(default, skip_serializing_if = "FmtConfig::is_default")]
+    pub fmt: FmtConfig,
+
+    /// Overrides for the build profiles, i.e. the `[profile.dev]` and
+    /// `[profile.release]` tables.
+    #[serde(default, skip_serializing_if = "ProfilesConfig::is_default")]
+    pub profile: ProfilesConfig,
+
     /// Location of ditto source (`*.ditto`) files.
     ///
     /// This is effectively hardcoded to `"src"` for the time being,
@@ -82,8 +133,18 @@ impl Config {
         Self {
             required_ditto_version: None,
             name,
+            version: None,
+            description: None,
+            license: None,
+            repository: None,
+            authors: Default::default(), // empty
             dependencies: Default::default(),
-            targets: Default::default(), // empty
+            targets: Default::default(),        // empty
+            allow_warnings: Default::default(), // empty
+            entry_modules: Default::default(),  // empty
+            lints: Default::default(),          // empty
+            fmt: Default::default(),
+            profile: Default::default(),
             src_dir: default_src(),
             codegen_js_config: Default::default(), // nada
             ditto_dir: default_ditto_dir(),
@@ -96,6 +157,38 @@ impl Config {
         self.targets.contains(&Target::Nodejs) || self.targets.contains(&Target::Web)
     }
 
+    /// The effective [LintLevel] for a warning code, without any command line overrides.
+    pub fn lint_level(&self, code: &str) -> LintLevel {
+        self.configured_lint_level(code).unwrap_or(LintLevel::Warn)
+    }
+
+    /// The [LintLevel] for a warning code, if this config mentions it at all.
+    pub fn configured_lint_level(&self, code: &str) -> Option<LintLevel> {
+        if let Some(level) = self.lints.levels.get(code) {
+            Some(*level)
+        } else if self.allow_warnings.contains(code) {
+            Some(LintLevel::Allow)
+        } else {
+            None
+        }
+    }
+
+    /// The settings for a build profile, with any overrides from the config applied.
+    pub fn profile(&self, name: ProfileName) -> Profile {
+        let overrides = match name {
+            ProfileName::Dev => &self.profile.dev,
+            ProfileName::Release => &self.profile.release,
+        };
+        let defaults = name.defaults();
+        Profile {
+            name,
+            optimize: overrides.optimize.unwrap_or(defaults.optimize),
+            runtime_checks: overrides.runtime_checks.unwrap_or(defaults.runtime_checks),
+            banner: overrides.banner.unwrap_or(defaults.banner),
+            artifacts: overrides.artifacts.unwrap_or(defaults.artifacts),
+        }
+    }
+
     /// Resolve packages, taking into account `extends` and overrides/additions listed in the
     /// config.
     pub fn resolve_packages(&self) -> miette::Result<&PackageSetPackages> {
@@ -103,8 +196,9 @@ impl Config {
     }
 
     /// This method only really exists for testing. Use the `read_config` function.
-    fn parse(_name: &str, input: &str) -> Result<Config, ParseError> {
-        toml::from_str(input).map_err(|toml_error| {
+    fn parse(name: &str, input: &str) -> miette::Result<Config> {
+        // Syntax errors short-circuit, as there's nothing more we can check
+        let table = toml::from_str::<toml::value::Table>(input).map_err(|toml_error| {
             // TODO try and get this working nicely
             //if let Some((line, col)) = toml_error.line_col() {
             //    let offset = miette::SourceOffset::from_location(input, line, col).offset();
@@ -117,6 +211,23 @@ impl Config {
             ParseError::Unlocated {
                 description: toml_error.to_string(),
             }
+        })?;
+
+        let problems = validation::check_config(input, &table);
+        if !problems.is_empty() {
+            return Err(validation::InvalidConfig {
+                input: miette::NamedSource::new(name, input.to_string()),
+                problems,
+            }
+            .into());
+        }
+
+        // Every key checked out, so this shouldn't fail
+        toml::from_str(input).map_err(|toml_error| {
+            ParseError::Unlocated {
+                description: toml_error.to_string(),
+            }
+            .into()
         })
     }
 }
@@ -148,6 +259,33 @@ pub struct CodegenJsConfig {
     /// package is built as a dependency.
     #[serde(rename = "package-json")]
     pub package_json_additions: Option<serde_json::Map<String, serde_json::Value>>,
+    /// Whether to generate TypeScript declarations (`.d.ts`) alongside the JavaScript.
+    #[serde(default)]
+    pub declarations: bool,
+    /// How generated modules are laid out in the output directory.
+    #[serde(default)]
+    pub layout: JsLayout,
+    /// Whether to generate source maps (`.js.map`) alongside the JavaScript.
+    #[serde(default, rename = "source-maps")]
+    pub source_maps: bool,
+    /// Whether `js "..." : Type` expressions are allowed.
+    ///
+    /// Inline JavaScript is emitted verbatim and its type is trusted, so it's
+    /// off by default.
+    #[serde(default, rename = "allow-inline-js")]
+    pub allow_inline_js: bool,
+    /// How `unit` is represented in the generated JavaScript.
+    #[serde(default)]
+    pub unit: JsUnit,
+    /// Which module system the generated JavaScript uses.
+    #[serde(default, rename = "module-format")]
+    pub module_format: JsModuleFormat,
+    /// Whether generated files are named with the extension for their module
+    /// format (`.mjs` or `.cjs`) rather than `.js`.
+    ///
+    /// NodeJS otherwise decides how to load a `.js` file from the nearest `package.json`.
+    #[serde(default, rename = "module-extensions")]
+    pub module_extensions: bool,
 }
 
 impl Default for CodegenJsConfig {
@@ -156,6 +294,13 @@ impl Default for CodegenJsConfig {
             dist_dir: default_js_dist_dir(),
             packages_dir: default_js_packages_dir(),
             package_json_additions: None,
+            declarations: false,
+            layout: JsLayout::default(),
+            source_maps: false,
+            allow_inline_js: false,
+            unit: JsUnit::default(),
+            module_format: JsModuleFormat::default(),
+            module_extensions: false,
         }
     }
 }
@@ -165,6 +310,13 @@ impl CodegenJsConfig {
         self.dist_dir == default_js_dist_dir()
             && self.packages_dir == default_js_packages_dir()
             && self.package_json_additions.is_none()
+            && !self.declarations
+            && self.layout == JsLayout::default()
+            && !self.source_maps
+            && !self.allow_inline_js
+            && self.unit == JsUnit::default()
+            && self.module_format == JsModuleFormat::default()
+            && !self.module_extensions
     }
 }
 
@@ -176,6 +328,57 @@ fn default_js_packages_dir() -> PathBuf {
     PathBuf::from("packages")
 }
 
+/// Where generated JavaScript modules are written, relative to the output directory.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, Hash, Eq, PartialEq)]
+pub enum JsLayout {
+    /// Every module is a file named after the module, e.g. `Data.Stuff.js`.
+    #[serde(rename = "flat")]
+    Flat,
+    /// Every module name segment is a directory, e.g. `Data/Stuff.js`.
+    #[serde(rename = "nested")]
+    Nested,
+}
+
+impl Default for JsLayout {
+    fn default() -> Self {
+        Self::Flat
+    }
+}
+
+/// How `unit` values are represented in generated JavaScript.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, Hash, Eq, PartialEq)]
+pub enum JsUnit {
+    /// `undefined`, which is what foreign functions returning nothing produce.
+    #[serde(rename = "undefined")]
+    Undefined,
+    /// A shared sentinel, `Symbol.for("ditto.unit")`.
+    #[serde(rename = "sentinel")]
+    Sentinel,
+}
+
+impl Default for JsUnit {
+    fn default() -> Self {
+        Self::Undefined
+    }
+}
+
+/// The module system used by generated JavaScript.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, Hash, Eq, PartialEq)]
+pub enum JsModuleFormat {
+    /// ES modules, using `import` and `export`.
+    #[serde(rename = "esm")]
+    Esm,
+    /// CommonJS modules, using `require` and `module.exports`.
+    #[serde(rename = "commonjs")]
+    CommonJs,
+}
+
+impl Default for JsModuleFormat {
+    fn default() -> Self {
+        Self::Esm
+    }
+}
+
 /// Code generation targets.
 #[derive(Clone, Debug, Deserialize, Serialize, Hash, Eq, PartialEq)]
 pub enum Target {
@@ -187,6 +390,396 @@ pub enum Target {
     Nodejs,
 }
 
+/// The `[lints]` table.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct LintsConfig {
+    /// Complexity budgets, which are all off by default.
+    #[serde(default, skip_serializing_if = "ComplexityLints::is_default")]
+    pub complexity: ComplexityLints,
+
+    /// Naming conventions, which are all off by default.
+    #[serde(default, skip_serializing_if = "NamingLints::is_default")]
+    pub naming: NamingLints,
+
+    /// Per-code warning levels.
+    #[serde(flatten)]
+    pub levels: Lints,
+}
+
+impl LintsConfig {
+    fn is_empty(&self) -> bool {
+        self.complexity.is_default() && self.naming.is_default() && self.levels.is_empty()
+    }
+}
+
+/// The type of `config.lints.levels`, for convenience.
+pub type Lints = HashMap<String, LintLevel>;
+
+/// The `[lints.complexity]` table.
+///
+/// These limits are checked against the syntax of each module, so they don't need
+/// the module to typecheck.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ComplexityLints {
+    /// Maximum number of top-level declarations in a module.
+    #[serde(rename = "max-declarations")]
+    pub max_declarations: Option<usize>,
+
+    /// Maximum number of binders for a single function.
+    #[serde(rename = "max-function-binders")]
+    pub max_function_binders: Option<usize>,
+
+    /// Maximum nesting depth of an expression.
+    #[serde(rename = "max-nesting-depth")]
+    pub max_nesting_depth: Option<usize>,
+
+    /// Maximum number of lines in a module.
+    #[serde(rename = "max-module-lines")]
+    pub max_module_lines: Option<usize>,
+}
+
+impl ComplexityLints {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// The `[lints.naming]` table.
+///
+/// Like complexity limits, naming conventions are checked against the syntax of
+/// each module.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct NamingLints {
+    /// Values and binders should be `snake_case`.
+    #[serde(rename = "snake-case", skip_serializing_if = "Option::is_none")]
+    pub snake_case: Option<LintLevel>,
+
+    /// Types and constructors should be `UpperCamelCase`.
+    #[serde(rename = "upper-camel-case", skip_serializing_if = "Option::is_none")]
+    pub upper_camel_case: Option<LintLevel>,
+
+    /// Module name segments should be capitalized words, e.g. `Data.Maybe`.
+    #[serde(
+        rename = "capitalized-modules",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub capitalized_modules: Option<LintLevel>,
+
+    /// Names shouldn't contain consecutive underscores.
+    #[serde(
+        rename = "consecutive-underscores",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub consecutive_underscores: Option<LintLevel>,
+
+    /// Names shouldn't end with an underscore, unless they're unused binders.
+    #[serde(
+        rename = "trailing-underscore",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub trailing_underscore: Option<LintLevel>,
+
+    /// Names matching any of these patterns are exempt from naming lints.
+    ///
+    /// A `*` matches any sequence of characters, so `FFI_*` permits an
+    /// `FFI_` prefix.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+}
+
+impl NamingLints {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether a name matches one of the `allow` patterns.
+    pub fn is_allowed(&self, name: &str) -> bool {
+        self.allow
+            .iter()
+            .any(|pattern| matches_pattern(pattern, name))
+    }
+}
+
+fn matches_pattern(pattern: &str, name: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == name,
+        Some((prefix, rest)) => {
+            let name = match name.strip_prefix(prefix) {
+                Some(name) => name,
+                None => return false,
+            };
+            // Try every possible expansion of the `*`
+            (0..=name.len())
+                .filter(|i| name.is_char_boundary(*i))
+                .any(|i| matches_pattern(rest, &name[i..]))
+        }
+    }
+}
+
+/// The `[fmt]` table.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct FmtConfig {
+    /// The formatting preset to use.
+    #[serde(default)]
+    pub style: FmtStyle,
+    /// Whether to sort the items of export and import lists.
+    #[serde(default, rename = "sort-lists")]
+    pub sort_lists: bool,
+}
+
+impl FmtConfig {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Resolve the preset, along with any other settings, to concrete formatter options.
+    pub fn options(&self) -> FmtOptions {
+        FmtOptions {
+            sort_lists: self.sort_lists,
+            ..self.style.options()
+        }
+    }
+}
+
+/// Named formatter presets.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, Hash, Eq, PartialEq)]
+pub enum FmtStyle {
+    /// The standard layout.
+    #[serde(rename = "default")]
+    Default,
+    /// Wider lines, no trailing commas and no blank lines between declarations.
+    #[serde(rename = "compact")]
+    Compact,
+    /// Narrower lines and extra space between declarations.
+    #[serde(rename = "expanded")]
+    Expanded,
+}
+
+impl Default for FmtStyle {
+    fn default() -> Self {
+        Self::Default
+    }
+}
+
+impl FmtStyle {
+    /// Resolve this preset to concrete formatter options.
+    pub fn options(self) -> FmtOptions {
+        match self {
+            Self::Default => FmtOptions {
+                max_width: 80,
+                trailing_commas: true,
+                blank_lines_between_declarations: 1,
+                sort_lists: false,
+            },
+            Self::Compact => FmtOptions {
+                max_width: 100,
+                trailing_commas: false,
+                blank_lines_between_declarations: 0,
+                sort_lists: false,
+            },
+            Self::Expanded => FmtOptions {
+                max_width: 60,
+                trailing_commas: true,
+                blank_lines_between_declarations: 2,
+                sort_lists: false,
+            },
+        }
+    }
+}
+
+/// Concrete formatter options, as resolved from an [FmtStyle].
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+pub struct FmtOptions {
+    /// Lines longer than this are broken where possible.
+    pub max_width: u32,
+    /// Whether lists that are broken over multiple lines get a trailing comma.
+    pub trailing_commas: bool,
+    /// Number of blank lines separating top-level declarations that were
+    /// separated by (any number of) blank lines in the source.
+    pub blank_lines_between_declarations: usize,
+    /// Whether the items of export and import lists are sorted by name.
+    ///
+    /// A comment before an item starts a new section, and items are only
+    /// sorted within their section.
+    pub sort_lists: bool,
+}
+
+impl Default for FmtOptions {
+    fn default() -> Self {
+        FmtStyle::default().options()
+    }
+}
+
+/// The `[profile]` table.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ProfilesConfig {
+    /// Overrides for the `dev` profile.
+    #[serde(default, skip_serializing_if = "ProfileConfig::is_default")]
+    pub dev: ProfileConfig,
+    /// Overrides for the `release` profile.
+    #[serde(default, skip_serializing_if = "ProfileConfig::is_default")]
+    pub release: ProfileConfig,
+}
+
+impl ProfilesConfig {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// A `[profile.*]` table, where anything that isn't set falls back to the
+/// defaults for that profile.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ProfileConfig {
+    /// Whether to simplify the generated code.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub optimize: Option<bool>,
+
+    /// Whether generated code checks foreign values when it's loaded.
+    #[serde(rename = "runtime-checks", skip_serializing_if = "Option::is_none")]
+    pub runtime_checks: Option<bool>,
+
+    /// Whether generated files start with a comment saying where they came from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub banner: Option<bool>,
+
+    /// How build artifacts are serialized.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub artifacts: Option<ArtifactFormat>,
+}
+
+impl ProfileConfig {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// The name of a build profile.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, Hash, Eq, PartialEq)]
+pub enum ProfileName {
+    /// Fast builds for local development.
+    #[serde(rename = "dev")]
+    Dev,
+    /// Slower, optimized builds for shipping.
+    #[serde(rename = "release")]
+    Release,
+}
+
+impl Default for ProfileName {
+    fn default() -> Self {
+        Self::Dev
+    }
+}
+
+impl ProfileName {
+    /// All the profile names, for listing valid options.
+    pub const ALL: &'static [&'static str] = &["dev", "release"];
+
+    /// The name as it's written in the config and on the command line.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Dev => "dev",
+            Self::Release => "release",
+        }
+    }
+
+    /// Parse a name as written by [ProfileName::as_str].
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "dev" => Some(Self::Dev),
+            "release" => Some(Self::Release),
+            _ => None,
+        }
+    }
+
+    /// The settings for this profile when there are no overrides.
+    pub fn defaults(self) -> Profile {
+        match self {
+            Self::Dev => Profile {
+                name: self,
+                optimize: false,
+                runtime_checks: true,
+                banner: false,
+                artifacts: ArtifactFormat::Json,
+            },
+            Self::Release => Profile {
+                name: self,
+                optimize: true,
+                runtime_checks: false,
+                banner: true,
+                artifacts: ArtifactFormat::Cbor,
+            },
+        }
+    }
+}
+
+/// Concrete build settings, as resolved by [Config::profile].
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+pub struct Profile {
+    /// Which profile these settings are for.
+    pub name: ProfileName,
+    /// Whether to simplify the generated code.
+    pub optimize: bool,
+    /// Whether generated code checks foreign values when it's loaded.
+    pub runtime_checks: bool,
+    /// Whether generated files start with a comment saying where they came from.
+    pub banner: bool,
+    /// How build artifacts are serialized.
+    pub artifacts: ArtifactFormat,
+}
+
+/// Serialization formats for build artifacts.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, Hash, Eq, PartialEq)]
+pub enum ArtifactFormat {
+    /// Readable, but slow and large.
+    #[serde(rename = "json")]
+    Json,
+    /// Compact binary.
+    #[serde(rename = "cbor")]
+    Cbor,
+}
+
+impl ArtifactFormat {
+    /// All the formats, for listing valid options.
+    pub const ALL: &'static [&'static str] = &["json", "cbor"];
+
+    /// The format as it's written in the config and on the command line.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Cbor => "cbor",
+        }
+    }
+
+    /// Parse a format as written by [ArtifactFormat::as_str].
+    pub fn parse(format: &str) -> Option<Self> {
+        match format {
+            "json" => Some(Self::Json),
+            "cbor" => Some(Self::Cbor),
+            _ => None,
+        }
+    }
+}
+
+/// How a checker warning should be treated.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, Hash, Eq, PartialEq)]
+pub enum LintLevel {
+    /// Suppress the warning.
+    #[serde(rename = "allow")]
+    Allow,
+    /// Report the warning (the default).
+    #[serde(rename = "warn")]
+    Warn,
+    /// Report the warning as an error, failing the build.
+    #[serde(rename = "deny")]
+    Deny,
+}
+
 #[derive(Error, Debug, Diagnostic)]
 enum ParseError {
     // TODO nicer syntax errors
@@ -219,10 +812,8 @@ pub fn read_config<P: AsRef<Path>>(path: P) -> miette::Result<Config> {
             path.as_ref().as_os_str()
         ))?;
 
-    Config::parse(&path.as_ref().to_string_lossy(), &contents)
-        .map_err(miette::Report::from)
-        .wrap_err(format!(
-            "error reading config at {:?}",
-            path.as_ref().as_os_str()
-        ))
+    Config::parse(&path.as_ref().to_string_lossy(), &contents).wrap_err(format!(
+        "error reading config at {:?}",
+        path.as_ref().as_os_str()
+    ))
 }
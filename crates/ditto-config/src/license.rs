@@ -0,0 +1,121 @@
+use serde::Serialize;
+use validated_newtype::validated_newtype;
+
+validated_newtype! {
+    /// An [SPDX license expression](https://spdx.github.io/spdx-spec/SPDX-license-expressions/),
+    /// such as `"MIT"` or `"(Apache-2.0 OR MIT)"`.
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+    String => pub License
+    if |string: &str| is_license_expression(string);
+    error "license must be an SPDX license expression, e.g. \"MIT\" or \"(Apache-2.0 OR MIT)\""
+}
+
+impl License {
+    /// Unwrap a [License] into a `String`.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+    /// Get the inner string of a [License].
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Checks the _syntax_ of an SPDX license expression.
+///
+/// License identifiers aren't checked against the SPDX license list.
+fn is_license_expression(string: &str) -> bool {
+    let spaced = string.replace('(', " ( ").replace(')', " ) ");
+    let tokens = spaced.split_whitespace().collect::<Vec<_>>();
+    let mut position = 0;
+    parse_compound(&tokens, &mut position) && position == tokens.len()
+}
+
+// compound = simple (("AND" | "OR") simple)*
+fn parse_compound(tokens: &[&str], position: &mut usize) -> bool {
+    if !parse_simple(tokens, position) {
+        return false;
+    }
+    while let Some(&token) = tokens.get(*position) {
+        if !is_operator(token, "AND") && !is_operator(token, "OR") {
+            break;
+        }
+        *position += 1;
+        if !parse_simple(tokens, position) {
+            return false;
+        }
+    }
+    true
+}
+
+// simple = (license-id "+"? | "(" compound ")") ("WITH" license-id)?
+fn parse_simple(tokens: &[&str], position: &mut usize) -> bool {
+    match tokens.get(*position) {
+        Some(&"(") => {
+            *position += 1;
+            if !parse_compound(tokens, position) || tokens.get(*position) != Some(&")") {
+                return false;
+            }
+            *position += 1;
+        }
+        Some(&token) if is_license_id(token.strip_suffix('+').unwrap_or(token)) => {
+            *position += 1;
+        }
+        _ => return false,
+    }
+    if let Some(&token) = tokens.get(*position) {
+        if is_operator(token, "WITH") {
+            *position += 1;
+            match tokens.get(*position) {
+                Some(&exception) if is_license_id(exception) => *position += 1,
+                _ => return false,
+            }
+        }
+    }
+    true
+}
+
+fn is_operator(token: &str, operator: &str) -> bool {
+    token == operator || token == operator.to_lowercase()
+}
+
+fn is_license_id(token: &str) -> bool {
+    !token.is_empty()
+        && !["AND", "OR", "WITH", "and", "or", "with"].contains(&token)
+        && token
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_license_expression;
+
+    #[test]
+    fn it_accepts_license_expressions() {
+        assert!(is_license_expression("MIT"));
+        assert!(is_license_expression("BSD-3-Clause"));
+        assert!(is_license_expression("GPL-2.0+"));
+        assert!(is_license_expression("Apache-2.0 OR MIT"));
+        assert!(is_license_expression("(Apache-2.0 OR MIT)"));
+        assert!(is_license_expression(
+            "GPL-2.0-or-later WITH Classpath-exception-2.0"
+        ));
+        assert!(is_license_expression(
+            "(MIT AND (LGPL-2.1+ OR BSD-3-Clause))"
+        ));
+        assert!(is_license_expression("LicenseRef-my-license"));
+    }
+
+    #[test]
+    fn it_rejects_invalid_license_expressions() {
+        assert!(!is_license_expression(""));
+        assert!(!is_license_expression("MIT OR"));
+        assert!(!is_license_expression("OR MIT"));
+        assert!(!is_license_expression("MIT Apache-2.0"));
+        assert!(!is_license_expression("(MIT"));
+        assert!(!is_license_expression("MIT)"));
+        assert!(!is_license_expression("MIT WITH"));
+        assert!(!is_license_expression("My License!"));
+    }
+}
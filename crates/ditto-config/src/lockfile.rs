@@ -0,0 +1,80 @@
+use crate::{PackageName, PackageSpec};
+use miette::{IntoDiagnostic, WrapErr};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// `"ditto.lock"`
+pub static LOCKFILE_NAME: &str = "ditto.lock";
+
+static LOCKFILE_HEADER: &str = "# This file is generated by `ditto make`, don't edit it by hand.\n";
+
+/// The exact package sources a build was resolved against.
+///
+/// This is written alongside the config file so that subsequent builds can check
+/// they're using the same inputs.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Lockfile {
+    /// Resolved packages, sorted by name.
+    #[serde(default, rename = "package")]
+    pub packages: Vec<LockedPackage>,
+}
+
+/// A single resolved package.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct LockedPackage {
+    /// Name of the package.
+    pub name: PackageName,
+    /// The version the package declared at the time it was resolved, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<semver::Version>,
+    /// SHA-256 of the package contents at the time it was resolved.
+    pub hash: String,
+    /// Where the package came from.
+    pub source: PackageSpec,
+}
+
+impl Lockfile {
+    /// Render the lockfile as TOML.
+    pub fn to_toml_string(&self) -> String {
+        let mut string = String::from(LOCKFILE_HEADER);
+        if !self.packages.is_empty() {
+            string.push('\n');
+            // Serializing a struct of strings and paths shouldn't fail
+            string.push_str(&toml::to_string(self).unwrap());
+        }
+        string
+    }
+}
+
+/// Read in a lockfile, returning `None` if it doesn't exist.
+pub fn read_lockfile<P: AsRef<Path>>(path: P) -> miette::Result<Option<Lockfile>> {
+    if !path.as_ref().exists() {
+        return Ok(None);
+    }
+    let contents = std::fs::read_to_string(&path)
+        .into_diagnostic()
+        .wrap_err(format!(
+            "error reading lockfile at {:?}",
+            path.as_ref().as_os_str()
+        ))?;
+
+    toml::from_str(&contents)
+        .into_diagnostic()
+        .wrap_err(format!(
+            "error reading lockfile at {:?}",
+            path.as_ref().as_os_str()
+        ))
+        .map(Some)
+}
+
+/// Write a lockfile.
+pub fn write_lockfile<P: AsRef<Path>>(path: P, lockfile: &Lockfile) -> miette::Result<()> {
+    std::fs::write(&path, lockfile.to_toml_string())
+        .into_diagnostic()
+        .wrap_err(format!(
+            "error writing lockfile at {:?}",
+            path.as_ref().as_os_str()
+        ))
+}
@@ -66,12 +66,72 @@ pub type PackageSetPackages = HashMap<PackageName, PackageSpec>;
 
 /// The specification of a single package's location.
 #[derive(Clone, Hash, Debug, Deserialize, Serialize, PartialEq, Eq)]
-#[serde(untagged)]
+#[serde(untagged, try_from = "RawPackageSpec")]
 pub enum PackageSpec {
     /// A local package.
     Path {
         /// Path to the local package.
         path: PathBuf,
+        /// Versions of the package that can be installed, if it declares a
+        /// `version`.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        version: Option<semver::VersionReq>,
     },
     // TODO Url
 }
+
+/// Every package source that might be written in a config, so that the ones
+/// we can't install yet are rejected explicitly rather than failing to match
+/// any [PackageSpec].
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawPackageSpec {
+    path: Option<PathBuf>,
+    git: Option<String>,
+    url: Option<String>,
+    // A requirement alongside `path`, otherwise a registry package
+    version: Option<String>,
+    // Only meaningful alongside `git`
+    rev: Option<String>,
+    tag: Option<String>,
+    branch: Option<String>,
+}
+
+impl TryFrom<RawPackageSpec> for PackageSpec {
+    type Error = String;
+
+    fn try_from(raw: RawPackageSpec) -> Result<Self, Self::Error> {
+        let unsupported = if raw.git.is_some()
+            || raw.rev.is_some()
+            || raw.tag.is_some()
+            || raw.branch.is_some()
+        {
+            Some("git")
+        } else if raw.url.is_some() {
+            Some("url")
+        } else if raw.version.is_some() && raw.path.is_none() {
+            Some("registry")
+        } else {
+            None
+        };
+        match (raw.path, unsupported) {
+            (_, Some(kind)) => Err(format!(
+                "{} packages aren't supported yet, only `path` packages are",
+                kind
+            )),
+            (Some(path), None) => {
+                // Alongside a `path`, the `version` is a requirement on the package
+                let version = raw
+                    .version
+                    .map(|version| {
+                        version
+                            .parse::<semver::VersionReq>()
+                            .map_err(|err| format!("invalid version requirement: {}", err))
+                    })
+                    .transpose()?;
+                Ok(Self::Path { path, version })
+            }
+            (None, None) => Err(String::from("missing field `path`")),
+        }
+    }
+}
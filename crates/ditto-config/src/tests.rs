@@ -23,7 +23,10 @@ mod macros {
 
 mod successes {
     use super::macros::assert_parses;
-    use crate::{CodegenJsConfig, Config};
+    use crate::{
+        ArtifactFormat, CodegenJsConfig, Config, FmtOptions, FmtStyle, JsLayout, JsModuleFormat,
+        JsUnit, LintLevel, ProfileName,
+    };
 
     #[test]
     fn it_parses_a_minimal_config() {
@@ -78,6 +81,15 @@ mod successes {
             foo = { path = "../test" }
         "#
         );
+        assert_parses!(
+            r#"
+            name = "test"
+            dependencies = ["foo"]
+
+            [package-set.packages] 
+            foo = { path = "../test", version = "^1.2" }
+        "#
+        );
     }
 
     #[test]
@@ -103,6 +115,221 @@ mod successes {
         );
     }
 
+    #[test]
+    fn it_parses_allowed_warnings() {
+        let config = assert_parses!(
+            r#"
+            name = "test" 
+            allow-warnings = ["polymorphic-foreign-value"]
+        "#
+        );
+        assert!(config.allow_warnings.contains("polymorphic-foreign-value"));
+    }
+
+    #[test]
+    fn it_parses_entry_modules() {
+        let config = assert_parses!(
+            r#"
+            name = "test"
+            entry-modules = ["Main", "Scripts.Build"]
+        "#
+        );
+        assert!(config.entry_modules.contains("Main"));
+        assert!(config.entry_modules.contains("Scripts.Build"));
+    }
+
+    #[test]
+    fn it_parses_lints() {
+        let config = assert_parses!(
+            r#"
+            name = "test" 
+            allow-warnings = ["unused-import", "unused-function-binder"]
+            [lints]
+            unused-function-binder = "deny"
+            polymorphic-foreign-value = "allow"
+        "#
+        );
+        assert_eq!(config.lint_level("unused-function-binder"), LintLevel::Deny);
+        assert_eq!(
+            config.lint_level("polymorphic-foreign-value"),
+            LintLevel::Allow
+        );
+        assert_eq!(config.lint_level("unused-import"), LintLevel::Allow);
+        assert_eq!(
+            config.lint_level("unused-value-declaration"),
+            LintLevel::Warn
+        );
+    }
+
+    #[test]
+    fn it_parses_complexity_lints() {
+        let config = assert_parses!(
+            r#"
+            name = "test"
+            [lints]
+            unused-import = "deny"
+            [lints.complexity]
+            max-declarations = 50
+            max-nesting-depth = 10
+        "#
+        );
+        assert_eq!(config.lint_level("unused-import"), LintLevel::Deny);
+        assert_eq!(config.lints.complexity.max_declarations, Some(50));
+        assert_eq!(config.lints.complexity.max_function_binders, None);
+        assert_eq!(config.lints.complexity.max_nesting_depth, Some(10));
+        assert_eq!(config.lints.complexity.max_module_lines, None);
+    }
+
+    #[test]
+    fn it_parses_naming_lints() {
+        let config = assert_parses!(
+            r#"
+            name = "test"
+            [lints.naming]
+            snake-case = "deny"
+            trailing-underscore = "warn"
+            allow = ["FFI_*", "exactly"]
+        "#
+        );
+        let naming = &config.lints.naming;
+        assert_eq!(naming.snake_case, Some(LintLevel::Deny));
+        assert_eq!(naming.upper_camel_case, None);
+        assert_eq!(naming.trailing_underscore, Some(LintLevel::Warn));
+        assert!(naming.is_allowed("FFI_Handle"));
+        assert!(naming.is_allowed("FFI_"));
+        assert!(naming.is_allowed("exactly"));
+        assert!(!naming.is_allowed("exactly_not"));
+        assert!(!naming.is_allowed("MyFFI_Handle"));
+    }
+
+    #[test]
+    fn it_parses_fmt_styles() {
+        let config = assert_parses!(
+            r#"
+            name = "test"
+        "#
+        );
+        assert_eq!(config.fmt.style, FmtStyle::Default);
+        assert_eq!(config.fmt.style.options(), FmtOptions::default());
+
+        let config = assert_parses!(
+            r#"
+            name = "test"
+            [fmt]
+            style = "compact"
+        "#
+        );
+        assert_eq!(config.fmt.style, FmtStyle::Compact);
+        assert_eq!(
+            config.fmt.style.options(),
+            FmtOptions {
+                max_width: 100,
+                trailing_commas: false,
+                blank_lines_between_declarations: 0,
+                sort_lists: false,
+            }
+        );
+
+        let config = assert_parses!(
+            r#"
+            name = "test"
+            [fmt]
+            style = "expanded"
+        "#
+        );
+        assert_eq!(config.fmt.style, FmtStyle::Expanded);
+        assert_eq!(config.fmt.style.options().max_width, 60);
+
+        let config = assert_parses!(
+            r#"
+            name = "test"
+            [fmt]
+            style = "compact"
+            sort-lists = true
+        "#
+        );
+        assert!(!config.fmt.style.options().sort_lists);
+        assert_eq!(
+            config.fmt.options(),
+            FmtOptions {
+                sort_lists: true,
+                ..FmtStyle::Compact.options()
+            }
+        );
+    }
+
+    #[test]
+    fn it_parses_profiles() {
+        let config = assert_parses!(
+            r#"
+            name = "test"
+        "#
+        );
+        assert_eq!(
+            config.profile(ProfileName::Dev),
+            ProfileName::Dev.defaults()
+        );
+        assert_eq!(
+            config.profile(ProfileName::Release),
+            ProfileName::Release.defaults()
+        );
+
+        let config = assert_parses!(
+            r#"
+            name = "test"
+            [profile.dev]
+            artifacts = "cbor"
+            [profile.release]
+            runtime-checks = true
+            banner = false
+        "#
+        );
+        let dev = config.profile(ProfileName::Dev);
+        assert_eq!(dev.artifacts, ArtifactFormat::Cbor);
+        assert!(!dev.optimize);
+        let release = config.profile(ProfileName::Release);
+        assert!(release.optimize);
+        assert!(release.runtime_checks);
+        assert!(!release.banner);
+        assert_eq!(release.artifacts, ArtifactFormat::Cbor);
+    }
+
+    #[test]
+    fn it_parses_package_metadata() {
+        let config = assert_parses!(
+            r#"
+            name = "test"
+            version = "1.2.3"
+            description = "A test package"
+            license = "(Apache-2.0 OR MIT)"
+            repository = "https://github.com/example/test"
+            authors = ["Jo Bloggs <jo@example.com>"]
+        "#,
+            Config {
+                version: Some(_),
+                description: Some(_),
+                license: Some(_),
+                repository: Some(_),
+                ..
+            }
+        );
+        assert_eq!(config.version.unwrap().to_string(), "1.2.3");
+        assert_eq!(config.license.unwrap().as_str(), "(Apache-2.0 OR MIT)");
+        assert_eq!(config.authors, vec!["Jo Bloggs <jo@example.com>"]);
+    }
+
+    #[test]
+    fn it_parses_the_readme_example() {
+        let readme = include_str!("../README.md");
+        let example = readme
+            .split("```toml")
+            .nth(1)
+            .and_then(|rest| rest.split("```").next())
+            .unwrap();
+        let result = Config::parse("README.md", example);
+        assert!(result.is_ok(), "{:?}", result.unwrap_err());
+    }
+
     #[test]
     fn it_parses_js_package_json() {
         assert_parses!(
@@ -115,6 +342,99 @@ mod successes {
             Config {
                 codegen_js_config: CodegenJsConfig {
                     package_json_additions: Some(_),
+                    declarations: false,
+                    ..
+                },
+                ..
+            }
+        );
+        assert_parses!(
+            r#"
+            name = "test"
+            [codegen-js]
+            declarations = true
+        "#,
+            Config {
+                codegen_js_config: CodegenJsConfig {
+                    declarations: true,
+                    layout: JsLayout::Flat,
+                    ..
+                },
+                ..
+            }
+        );
+        assert_parses!(
+            r#"
+            name = "test"
+            [codegen-js]
+            layout = "nested"
+        "#,
+            Config {
+                codegen_js_config: CodegenJsConfig {
+                    layout: JsLayout::Nested,
+                    source_maps: false,
+                    ..
+                },
+                ..
+            }
+        );
+        assert_parses!(
+            r#"
+            name = "test"
+            [codegen-js]
+            source-maps = true
+        "#,
+            Config {
+                codegen_js_config: CodegenJsConfig {
+                    source_maps: true,
+                    allow_inline_js: false,
+                    ..
+                },
+                ..
+            }
+        );
+        assert_parses!(
+            r#"
+            name = "test"
+            [codegen-js]
+            allow-inline-js = true
+        "#,
+            Config {
+                codegen_js_config: CodegenJsConfig {
+                    allow_inline_js: true,
+                    unit: JsUnit::Undefined,
+                    ..
+                },
+                ..
+            }
+        );
+        assert_parses!(
+            r#"
+            name = "test"
+            [codegen-js]
+            unit = "sentinel"
+        "#,
+            Config {
+                codegen_js_config: CodegenJsConfig {
+                    unit: JsUnit::Sentinel,
+                    module_format: JsModuleFormat::Esm,
+                    module_extensions: false,
+                    ..
+                },
+                ..
+            }
+        );
+        assert_parses!(
+            r#"
+            name = "test"
+            [codegen-js]
+            module-format = "commonjs"
+            module-extensions = true
+        "#,
+            Config {
+                codegen_js_config: CodegenJsConfig {
+                    module_format: JsModuleFormat::CommonJs,
+                    module_extensions: true,
                     ..
                 },
                 ..
@@ -125,6 +445,7 @@ mod successes {
 
 mod errors {
     use super::macros::assert_error;
+    use crate::Config;
 
     #[test]
     fn it_errors_for_empty_input() {
@@ -141,6 +462,121 @@ mod errors {
         );
     }
 
+    #[test]
+    fn it_errors_for_invalid_lint_levels() {
+        assert_error!(
+            r#"
+            name = "test" 
+            [lints]
+            unused-import = "forbid"
+        "#
+        );
+    }
+
+    #[test]
+    fn it_errors_for_unknown_complexity_lints() {
+        assert_error!(
+            r#"
+            name = "test"
+            [lints.complexity]
+            max-everything = 1
+        "#
+        );
+    }
+
+    #[test]
+    fn it_errors_for_unknown_naming_lints() {
+        assert_error!(
+            r#"
+            name = "test"
+            [lints.naming]
+            kebab-case = "deny"
+        "#
+        );
+    }
+
+    #[test]
+    fn it_errors_for_unknown_fmt_styles() {
+        assert_error!(
+            r#"
+            name = "test"
+            [fmt]
+            style = "fancy"
+        "#
+        );
+        assert_error!(
+            r#"
+            name = "test"
+            [fmt]
+            max-width = 120
+        "#
+        );
+    }
+
+    #[test]
+    fn it_errors_for_unknown_profile_settings() {
+        assert_error!(
+            r#"
+            name = "test"
+            [profile.test]
+            optimize = true
+        "#
+        );
+        assert_error!(
+            r#"
+            name = "test"
+            [profile.release]
+            opt-level = 3
+        "#
+        );
+        assert_error!(
+            r#"
+            name = "test"
+            [profile.dev]
+            artifacts = "yaml"
+        "#
+        );
+    }
+
+    #[test]
+    fn it_errors_for_invalid_package_metadata() {
+        assert_error!(
+            r#"
+            name = "test" 
+            version = "1.0"
+        "#
+        );
+        assert_error!(
+            r#"
+            name = "test" 
+            license = "MIT OR"
+        "#
+        );
+        assert_error!(
+            r#"
+            name = "test" 
+            authors = "Jo Bloggs"
+        "#
+        );
+    }
+
+    #[test]
+    fn it_reports_every_problem() {
+        let report = Config::parse(
+            "ditto.toml",
+            r#"
+            name = "NAH"
+            targets = ["nah"]
+            colour = "blue"
+            [fmt]
+            style = "fancy"
+        "#,
+        )
+        .unwrap_err();
+        assert_eq!(report.labels().unwrap().count(), 4);
+        assert!(report.help().is_none());
+    }
+
     #[test]
     fn it_errors_for_bad_package_names() {
         assert_error!(
@@ -163,6 +599,81 @@ mod errors {
         "#
         );
     }
+
+    #[test]
+    fn it_errors_for_unsupported_package_specs() {
+        for (spec, want) in [
+            (
+                r#"{ git = "https://example.com/foo.git", rev = "main" }"#,
+                "git packages aren't supported yet",
+            ),
+            (
+                r#"{ url = "https://example.com/foo.tar.gz" }"#,
+                "url packages aren't supported yet",
+            ),
+            (
+                r#"{ version = "1.0.0" }"#,
+                "registry packages aren't supported yet",
+            ),
+            ("{}", "missing field `path`"),
+            (
+                r#"{ path = "../foo", version = "not a version" }"#,
+                "invalid version requirement",
+            ),
+        ] {
+            let report = Config::parse(
+                "ditto.toml",
+                &format!(
+                    "name = \"test\"\ndependencies = [\"foo\"]\n[package-set.packages]\nfoo = {}\n",
+                    spec
+                ),
+            )
+            .unwrap_err();
+            let labels = report
+                .labels()
+                .unwrap()
+                .filter_map(|label| label.label().map(String::from))
+                .collect::<Vec<_>>();
+            assert!(
+                labels.iter().any(|label| label.contains(want)),
+                "{:?} doesn't mention {:?}",
+                labels,
+                want
+            );
+        }
+    }
+}
+
+mod lockfiles {
+    use crate::{LockedPackage, Lockfile, PackageName, PackageSpec};
+
+    #[test]
+    fn it_round_trips_lockfiles() {
+        let lockfile = Lockfile {
+            packages: vec![LockedPackage {
+                name: PackageName::new_unchecked("foo".to_string()),
+                version: Some(semver::Version::new(1, 2, 0)),
+                hash: "0123456789abcdef".to_string(),
+                source: PackageSpec::Path {
+                    path: "../foo".into(),
+                    version: Some("^1.2".parse().unwrap()),
+                },
+            }],
+        };
+        let rendered = lockfile.to_toml_string();
+        assert!(rendered.contains("name = \"foo\""), "{}", rendered);
+        assert!(rendered.contains("version = \"1.2.0\""), "{}", rendered);
+        assert_eq!(toml::from_str::<Lockfile>(&rendered).unwrap(), lockfile);
+    }
+
+    #[test]
+    fn it_renders_empty_lockfiles() {
+        let rendered = Lockfile::default().to_toml_string();
+        assert_eq!(
+            toml::from_str::<Lockfile>(&rendered).unwrap(),
+            Lockfile::default()
+        );
+    }
 }
 
 #[snapshot_test::snapshot_lf(
@@ -171,7 +682,7 @@ mod errors {
 )]
 fn golden(input: &str) -> String {
     let parse_error = crate::Config::parse("ditto.toml", input).unwrap_err();
-    render_diagnostic(&parse_error)
+    render_diagnostic(&*parse_error)
 }
 
 fn render_diagnostic(diagnostic: &dyn miette::Diagnostic) -> String {
@@ -0,0 +1,167 @@
+use crate::{
+    CodegenJsConfig, Dependencies, FmtConfig, License, LintsConfig, PackageName, PackageSet,
+    ProfilesConfig, Target,
+};
+use miette::{Diagnostic, LabeledSpan, NamedSource, Severity, SourceCode, SourceSpan};
+use serde::de::DeserializeOwned;
+use std::{collections::HashSet, fmt};
+use thiserror::Error;
+
+/// Everything wrong with a config that is at least valid TOML.
+#[derive(Error, Debug)]
+#[error("invalid config")]
+pub(crate) struct InvalidConfig {
+    pub(crate) input: NamedSource,
+    pub(crate) problems: Vec<Problem>,
+}
+
+/// A single problem with a config.
+#[derive(Debug)]
+pub(crate) struct Problem {
+    /// Where the offending key is, if we could find it.
+    pub(crate) location: Option<SourceSpan>,
+    pub(crate) description: String,
+}
+
+impl Diagnostic for InvalidConfig {
+    fn severity(&self) -> Option<Severity> {
+        Some(Severity::Error)
+    }
+
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        Some(&self.input)
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        if self
+            .problems
+            .iter()
+            .all(|problem| problem.location.is_none())
+        {
+            return None;
+        }
+        Some(Box::new(self.problems.iter().filter_map(|problem| {
+            let location = problem.location?;
+            Some(LabeledSpan::new(
+                Some(problem.description.clone()),
+                location.offset(),
+                location.len(),
+            ))
+        })))
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        let unlocated = self
+            .problems
+            .iter()
+            .filter(|problem| problem.location.is_none())
+            .map(|problem| problem.description.as_str())
+            .collect::<Vec<_>>();
+        if unlocated.is_empty() {
+            None
+        } else {
+            Some(Box::new(unlocated.join("\n")))
+        }
+    }
+}
+
+type Check = fn(toml::Value) -> Result<(), toml::de::Error>;
+
+fn check<T: DeserializeOwned>(value: toml::Value) -> Result<(), toml::de::Error> {
+    value.try_into::<T>().map(|_| ())
+}
+
+/// Every top-level key, and how to check its value in isolation.
+///
+/// IMPORTANT keep this in sync with the fields of [crate::Config].
+static KEYS: &[(&str, Check)] = &[
+    ("ditto-version", check::<semver::VersionReq>),
+    ("name", check::<PackageName>),
+    ("version", check::<semver::Version>),
+    ("description", check::<String>),
+    ("license", check::<License>),
+    ("repository", check::<String>),
+    ("authors", check::<Vec<String>>),
+    ("targets", check::<HashSet<Target>>),
+    ("dependencies", check::<Dependencies>),
+    ("allow-warnings", check::<HashSet<String>>),
+    ("entry-modules", check::<HashSet<String>>),
+    ("lints", check::<LintsConfig>),
+    ("fmt", check::<FmtConfig>),
+    ("profile", check::<ProfilesConfig>),
+    ("codegen-js", check::<CodegenJsConfig>),
+    ("package-set", check::<PackageSet>),
+];
+
+/// Check each key of a parsed config separately, so that we can report
+/// all of the problems at once rather than stopping at the first one.
+pub(crate) fn check_config(input: &str, table: &toml::value::Table) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    if !table.contains_key("name") {
+        problems.push(Problem {
+            location: None,
+            description: "missing field `name`".to_string(),
+        });
+    }
+    for (key, value) in table {
+        let description = match KEYS
+            .iter()
+            .find(|(known_key, _)| *known_key == key.as_str())
+        {
+            None => format!("unknown key `{}`", key),
+            Some((_, check)) => match check(value.clone()) {
+                Ok(()) => continue,
+                Err(err) => err.to_string(),
+            },
+        };
+        let location = find_key(input, key);
+        problems.push(Problem {
+            description: if location.is_some() {
+                description
+            } else {
+                format!("{} for key `{}`", description, key)
+            },
+            location,
+        });
+    }
+    problems
+}
+
+/// Find where a top-level key is defined, either as a `key = ...` pair before
+/// any table headers, or as a `[key]` (or `[key.subkey]`) table header.
+fn find_key(input: &str, key: &str) -> Option<SourceSpan> {
+    let mut offset = 0;
+    let mut in_root_table = true;
+    for line in input.split_inclusive('\n') {
+        let line_offset = offset;
+        offset += line.len();
+
+        let trimmed = line.trim_start();
+        let (text, terminators) = match trimmed.strip_prefix('[') {
+            Some(header) => {
+                in_root_table = false;
+                let header = header.strip_prefix('[').unwrap_or(header);
+                (header.trim_start(), &['.', ']'][..])
+            }
+            None if in_root_table => (trimmed, &['.', '='][..]),
+            None => continue,
+        };
+        if let Some(len) = strip_key(text, key, terminators) {
+            let start = line_offset + (line.len() - text.len());
+            return Some(SourceSpan::from((start, len)));
+        }
+    }
+    None
+}
+
+/// If `text` starts with `key` (either bare or quoted) followed by one of the
+/// `terminators`, returns the length of the key as written.
+fn strip_key(text: &str, key: &str, terminators: &[char]) -> Option<usize> {
+    let quoted = format!("\"{}\"", key);
+    [key, quoted.as_str()].iter().find_map(|written| {
+        let rest = text.strip_prefix(written)?;
+        rest.trim_start()
+            .starts_with(terminators)
+            .then(|| written.len())
+    })
+}
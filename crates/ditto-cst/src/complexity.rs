@@ -0,0 +1,177 @@
+//! Measures of syntactic complexity.
+//!
+//! These only need a module to parse, so they're cheap to compute and work for
+//! code that doesn't typecheck.
+
+use crate::{Expression, Module};
+
+/// The number of top-level declarations in a module.
+pub fn count_declarations(module: &Module) -> usize {
+    module.declarations.len()
+}
+
+/// The number of lines in a module's source.
+pub fn count_lines(source: &str) -> usize {
+    source.lines().count()
+}
+
+/// The largest number of binders of any function in an expression,
+/// or zero if there are no functions.
+pub fn max_function_binders(expression: &Expression) -> usize {
+    let binders = match expression {
+        Expression::Function { parameters, .. } => parameters
+            .value
+            .as_ref()
+            .map_or(0, |parameters| parameters.iter().count()),
+        _ => 0,
+    };
+    sub_expressions(expression)
+        .into_iter()
+        .map(max_function_binders)
+        .fold(binders, usize::max)
+}
+
+/// How deeply nested an expression is.
+///
+/// An expression without any sub-expressions has a depth of one.
+pub fn nesting_depth(expression: &Expression) -> usize {
+    1 + sub_expressions(expression)
+        .into_iter()
+        .map(nesting_depth)
+        .max()
+        .unwrap_or(0)
+}
+
+pub(crate) fn sub_expressions(expression: &Expression) -> Vec<&Expression> {
+    match expression {
+        Expression::Parens(parens) => vec![parens.value.as_ref()],
+        Expression::Function { body, .. } => vec![body.as_ref()],
+        Expression::Call {
+            function,
+            arguments,
+        } => {
+            let mut expressions = vec![function.as_ref()];
+            if let Some(arguments) = &arguments.value {
+                expressions.extend(arguments.iter().map(|argument| argument.as_ref()));
+            }
+            expressions
+        }
+        Expression::If {
+            condition,
+            true_clause,
+            false_clause,
+            ..
+        } => vec![
+            condition.as_ref(),
+            true_clause.as_ref(),
+            false_clause.as_ref(),
+        ],
+        Expression::Let {
+            head_declaration,
+            tail_declarations,
+            expression,
+            ..
+        } => {
+            let mut expressions = vec![&head_declaration.expression];
+            expressions.extend(
+                tail_declarations
+                    .iter()
+                    .map(|declaration| &declaration.expression),
+            );
+            expressions.push(expression.as_ref());
+            expressions
+        }
+        Expression::Match {
+            expression,
+            head_arm,
+            tail_arms,
+            ..
+        } => {
+            let mut expressions = vec![expression.as_ref(), &head_arm.expression];
+            expressions.extend(tail_arms.iter().map(|arm| arm.expression.as_ref()));
+            expressions
+        }
+        Expression::BinOp { lhs, rhs, .. } => vec![lhs.as_ref(), rhs.as_ref()],
+        Expression::RecordAccess { target, .. } => vec![target.as_ref()],
+        Expression::Negate { expression, .. } => vec![expression.as_ref()],
+        Expression::RecordConstructor { fields, .. } | Expression::Record(fields) => fields
+            .value
+            .iter()
+            .map(|field| field.value.as_ref())
+            .collect(),
+        Expression::Array(brackets) => brackets.value.as_ref().map_or_else(Vec::new, |elements| {
+            elements.iter().map(|element| element.as_ref()).collect()
+        }),
+        Expression::Tuple(parens) => parens
+            .value
+            .iter()
+            .map(|element| element.as_ref())
+            .collect(),
+        Expression::Constructor(_)
+        | Expression::Variable(_)
+        | Expression::Unit(_)
+        | Expression::True(_)
+        | Expression::False(_)
+        | Expression::String(_)
+        | Expression::Char(_)
+        | Expression::Hole(_)
+        | Expression::Int(_)
+        | Expression::Float(_)
+        | Expression::InlineJs { .. } => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_counts_declarations() {
+        let module = Module::parse("module Test exports (..);").unwrap();
+        assert_eq!(count_declarations(&module), 0);
+
+        let module = Module::parse(
+            "module Test exports (..); type Five = Five; foreign five: Int; five_again = five;",
+        )
+        .unwrap();
+        assert_eq!(count_declarations(&module), 3);
+    }
+
+    #[test]
+    fn it_counts_lines() {
+        assert_eq!(count_lines(""), 0);
+        assert_eq!(count_lines("module Test exports (..);"), 1);
+        assert_eq!(count_lines("module Test exports (..);\n"), 1);
+        assert_eq!(count_lines("module Test exports (..);\n\nfive = 5;\n"), 3);
+    }
+
+    #[test]
+    fn it_measures_function_binders() {
+        assert_eq!(max_function_binders(&parse("5")), 0);
+        assert_eq!(max_function_binders(&parse("() -> 5")), 0);
+        assert_eq!(max_function_binders(&parse("(a) -> a")), 1);
+        assert_eq!(max_function_binders(&parse("(a, b, c,) -> a")), 3);
+        assert_eq!(max_function_binders(&parse("(a) -> (b, c) -> a")), 2);
+        assert_eq!(max_function_binders(&parse("[(a) -> a, (a, b) -> a]")), 2);
+        assert_eq!(
+            max_function_binders(&parse("let f = (a, b, c) -> a; in f")),
+            3
+        );
+    }
+
+    #[test]
+    fn it_measures_nesting_depth() {
+        assert_eq!(nesting_depth(&parse("5")), 1);
+        assert_eq!(nesting_depth(&parse("[]")), 1);
+        assert_eq!(nesting_depth(&parse("[5]")), 2);
+        assert_eq!(nesting_depth(&parse("[[5], 5]")), 3);
+        assert_eq!(nesting_depth(&parse("f(x)")), 2);
+        assert_eq!(nesting_depth(&parse("(a) -> (b) -> a")), 3);
+        assert_eq!(nesting_depth(&parse("if a then [b] else c")), 3);
+        assert_eq!(nesting_depth(&parse("match x with | y -> [[y]] end")), 4);
+    }
+
+    fn parse(input: &str) -> Expression {
+        Expression::parse(input).unwrap()
+    }
+}
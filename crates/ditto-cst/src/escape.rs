@@ -0,0 +1,91 @@
+use std::ops::Range;
+
+/// Evaluate the escape sequences in the source text of a string literal
+/// (the text between the quotes).
+///
+/// The supported escapes are `\"`, `\\`, `\n`, `\t`, `\r` and `\u{XXXX}`
+/// (with one to six hex digits naming a valid code point).
+///
+/// On failure, returns the byte range of the offending escape sequence.
+pub fn unescape_string(source: &str) -> Result<String, Range<usize>> {
+    let mut value = String::with_capacity(source.len());
+    let mut chars = source.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        let escaped = match chars.next() {
+            Some((_, '"')) => '"',
+            Some((_, '\\')) => '\\',
+            Some((_, 'n')) => '\n',
+            Some((_, 't')) => '\t',
+            Some((_, 'r')) => '\r',
+            Some((_, 'u')) => {
+                let mut end = start + 2;
+                let mut digits = String::new();
+                let mut closed = false;
+                if let Some((_, '{')) = chars.peek() {
+                    chars.next();
+                    end += 1;
+                    while let Some(&(i, c)) = chars.peek() {
+                        if c == '}' {
+                            chars.next();
+                            end = i + 1;
+                            closed = true;
+                            break;
+                        }
+                        if !c.is_ascii_hexdigit() || digits.len() == 6 {
+                            end = i;
+                            break;
+                        }
+                        chars.next();
+                        digits.push(c);
+                        end = i + 1;
+                    }
+                }
+                if !closed || digits.is_empty() {
+                    return Err(start..end);
+                }
+                u32::from_str_radix(&digits, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or(start..end)?
+            }
+            Some((i, c)) => return Err(start..i + c.len_utf8()),
+            None => return Err(start..start + 1),
+        };
+        value.push(escaped);
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::unescape_string;
+
+    #[test]
+    fn it_unescapes_strings() {
+        assert_eq!(unescape_string("hello").unwrap(), "hello");
+        assert_eq!(
+            unescape_string(r#"\"quoted\" \\ \n\t\r"#).unwrap(),
+            "\"quoted\" \\ \n\t\r"
+        );
+        assert_eq!(unescape_string(r"\u{1F600}!").unwrap(), "\u{1F600}!");
+        assert_eq!(unescape_string(r"\u{41}").unwrap(), "A");
+        // Raw line breaks are fine
+        assert_eq!(unescape_string("a\nb").unwrap(), "a\nb");
+    }
+
+    #[test]
+    fn it_rejects_bad_escapes() {
+        assert_eq!(unescape_string(r"ab\qc"), Err(2..4));
+        assert_eq!(unescape_string(r"\0"), Err(0..2));
+        assert_eq!(unescape_string(r"\u41"), Err(0..2));
+        assert_eq!(unescape_string(r"\u{}"), Err(0..4));
+        assert_eq!(unescape_string(r"\u{1234567}"), Err(0..9));
+        assert_eq!(unescape_string(r"\u{D800}"), Err(0..8));
+        assert_eq!(unescape_string(r"\u{12"), Err(0..5));
+        assert_eq!(unescape_string("\\"), Err(0..1));
+    }
+}
@@ -1,7 +1,10 @@
 use crate::{
-    BracketsList, Colon, ElseKeyword, FalseKeyword, IfKeyword, Name, Parens, ParensList,
-    QualifiedName, QualifiedProperName, RightArrow, StringToken, ThenKeyword, TrueKeyword, Type,
-    UnitKeyword,
+    Asterisk, BangEquals, Braces, BracesList1, BracketsList, Colon, CommaSep1, Dot,
+    DoubleAmpersand, DoubleDot, DoubleEquals, DoublePipe, ElseKeyword, EmptyToken, EndKeyword,
+    Equals, FalseKeyword, IfKeyword, InKeyword, JsKeyword, LeftAngle, LeftAngleEquals, LetKeyword,
+    MatchKeyword, Minus, Name, Parens, ParensList, ParensList1, Pipe, Plus, QualifiedName,
+    QualifiedProperName, RightAngle, RightAngleEquals, RightArrow, RightPipe, Slash, StringToken,
+    ThenKeyword, TrueKeyword, Type, UnitKeyword, ValueDeclaration, Wildcard, WithKeyword,
 };
 
 /// A value expression.
@@ -16,7 +19,7 @@ pub enum Expression {
     /// ```
     Function {
         /// The parameters to be bound and added to the scope of `body`.
-        parameters: Box<ParensList<(Name, Option<TypeAnnotation>)>>,
+        parameters: Box<ParensList<(FunctionBinder, Option<TypeAnnotation>)>>,
         /// Optional type annotation for `body`.
         return_type_annotation: Box<Option<TypeAnnotation>>,
         /// `->`
@@ -54,18 +57,144 @@ pub enum Expression {
         /// The expression to evaluate otherwise.
         false_clause: Box<Self>,
     },
+    /// Local value declarations.
+    ///
+    /// ```ditto
+    /// let
+    ///     five = 5;
+    ///     six: Int = 6;
+    /// in
+    /// [five, six]
+    /// ```
+    Let {
+        /// `let`
+        let_keyword: LetKeyword,
+        /// The first declaration.
+        head_declaration: Box<ValueDeclaration>,
+        /// Any subsequent declarations, which can refer to those before them.
+        tail_declarations: Vec<ValueDeclaration>,
+        /// `in`
+        in_keyword: InKeyword,
+        /// The expression the declarations are in scope for.
+        expression: Box<Self>,
+    },
+    /// A pattern matching expression.
+    ///
+    /// ```ditto
+    /// match maybe_five with
+    /// | Just(five) -> five
+    /// | Nothing -> 0
+    /// end
+    /// ```
+    Match {
+        /// `match`
+        match_keyword: MatchKeyword,
+        /// The expression being matched.
+        expression: Box<Self>,
+        /// `with`
+        with_keyword: WithKeyword,
+        /// The first arm (there must be at least one).
+        head_arm: Box<MatchArm>,
+        /// Any subsequent arms, which are tried in order.
+        tail_arms: Vec<MatchArm>,
+        /// `end`
+        end_keyword: EndKeyword,
+    },
+    /// A binary operator expression.
+    ///
+    /// ```ditto
+    /// a + b * c
+    /// ```
+    BinOp {
+        /// The left operand.
+        lhs: Box<Self>,
+        /// The operator.
+        operator: BinOp,
+        /// The right operand.
+        rhs: Box<Self>,
+    },
+    /// Numeric negation.
+    ///
+    /// ```ditto
+    /// -x
+    /// ```
+    Negate {
+        /// `-`
+        minus: Minus,
+        /// The expression being negated.
+        expression: Box<Self>,
+    },
     /// A value constructor, e.g. `Just` and `Ok`.
     Constructor(QualifiedProperName),
+    /// A constructor with named fields, applied to those fields.
+    ///
+    /// ```ditto
+    /// Person { name = "Jane", age = 42 }
+    /// ```
+    RecordConstructor {
+        /// The constructor, e.g. `Person`.
+        constructor: QualifiedProperName,
+        /// The field values, in any order.
+        fields: BracesList1<RecordField>,
+    },
+    /// An anonymous record.
+    ///
+    /// ```ditto
+    /// { name = "Jane", age = 42 }
+    /// ```
+    Record(BracesList1<RecordField>),
+    /// Accessing a record field.
+    ///
+    /// ```ditto
+    /// person.name
+    /// ```
+    RecordAccess {
+        /// The record being accessed.
+        target: Box<Self>,
+        /// `.`
+        dot: Dot,
+        /// The field being accessed.
+        label: Name,
+    },
     /// A variable. Useful for not repeating things.
     Variable(QualifiedName),
     /// `unit`
     Unit(UnitKeyword),
+    /// Inline JavaScript, which is trusted to have the annotated type.
+    ///
+    /// ```ditto
+    /// js "Date.now()" : Int
+    /// ```
+    InlineJs {
+        /// `js`
+        js_keyword: JsKeyword,
+        /// The JavaScript expression.
+        ///
+        /// Note that the string token value _doesn't_ include the quotes.
+        code: StringToken,
+        /// The type of the JavaScript expression.
+        type_annotation: TypeAnnotation,
+    },
+    /// A typed hole, which always fails type-checking but reports the type it
+    /// should have.
+    ///
+    /// ```ditto
+    /// ?help
+    /// ```
+    ///
+    /// Note that the token value _doesn't_ include the `?`.
+    Hole(StringToken),
     /// `true`
     True(TrueKeyword),
     /// `false`
     False(FalseKeyword),
     /// `"this is a string"`
     String(StringToken),
+    /// `'c'`
+    ///
+    /// The value is the source text between the quotes, so escape sequences
+    /// are preserved for the formatter.
+    Char(StringToken),
     /// `5`
     ///
     /// The value is a [StringToken] because:
@@ -86,6 +215,200 @@ pub enum Expression {
     Float(StringToken),
     /// `[this, is, an, array]`
     Array(BracketsList<Box<Self>>),
+    /// A fixed-length tuple of values.
+    ///
+    /// ```ditto
+    /// (5, "five")
+    /// (5,)
+    /// ```
+    ///
+    /// A single element tuple needs a trailing comma, to distinguish it from
+    /// an expression wrapped in parentheses.
+    Tuple(ParensList1<Box<Self>>),
+}
+
+/// A binary operator.
+#[derive(Debug, Clone)]
+pub enum BinOp {
+    /// `+`
+    Plus(Plus),
+    /// `-`
+    Minus(Minus),
+    /// `*`
+    Times(Asterisk),
+    /// `/`
+    Divide(Slash),
+    /// `==`
+    Equals(DoubleEquals),
+    /// `!=`
+    NotEquals(BangEquals),
+    /// `<`
+    LessThan(LeftAngle),
+    /// `<=`
+    LessThanOrEquals(LeftAngleEquals),
+    /// `>`
+    GreaterThan(RightAngle),
+    /// `>=`
+    GreaterThanOrEquals(RightAngleEquals),
+    /// `&&`
+    And(DoubleAmpersand),
+    /// `||`
+    Or(DoublePipe),
+    /// `|>`
+    ///
+    /// `x |> f` is sugar for `f(x)`.
+    Pipe(RightPipe),
+}
+
+impl BinOp {
+    /// How tightly this operator binds, higher binds tighter.
+    ///
+    /// All operators are left associative.
+    pub fn precedence(&self) -> u8 {
+        match self {
+            Self::Pipe(_) => 0,
+            Self::Or(_) => 1,
+            Self::And(_) => 2,
+            Self::Equals(_)
+            | Self::NotEquals(_)
+            | Self::LessThan(_)
+            | Self::LessThanOrEquals(_)
+            | Self::GreaterThan(_)
+            | Self::GreaterThanOrEquals(_) => 3,
+            Self::Plus(_) | Self::Minus(_) => 4,
+            Self::Times(_) | Self::Divide(_) => 5,
+        }
+    }
+
+    /// The underlying operator token.
+    pub fn token(&self) -> &EmptyToken {
+        match self {
+            Self::Plus(plus) => &plus.0,
+            Self::Minus(minus) => &minus.0,
+            Self::Times(asterisk) => &asterisk.0,
+            Self::Divide(slash) => &slash.0,
+            Self::Equals(double_equals) => &double_equals.0,
+            Self::NotEquals(bang_equals) => &bang_equals.0,
+            Self::LessThan(left_angle) => &left_angle.0,
+            Self::LessThanOrEquals(left_angle_equals) => &left_angle_equals.0,
+            Self::GreaterThan(right_angle) => &right_angle.0,
+            Self::GreaterThanOrEquals(right_angle_equals) => &right_angle_equals.0,
+            Self::And(double_ampersand) => &double_ampersand.0,
+            Self::Or(double_pipe) => &double_pipe.0,
+            Self::Pipe(right_pipe) => &right_pipe.0,
+        }
+    }
+}
+
+/// A labelled value, such as `name = "Jane"`.
+#[derive(Debug, Clone)]
+pub struct RecordField {
+    /// The field name.
+    pub label: Name,
+    /// `=`
+    pub equals: Equals,
+    /// The field value.
+    pub value: Box<Expression>,
+}
+
+/// A single arm of a `match` expression.
+///
+/// ```ditto
+/// | Just(five) -> five
+/// ```
+#[derive(Debug, Clone)]
+pub struct MatchArm {
+    /// `|`
+    pub pipe: Pipe,
+    /// The pattern to match against.
+    pub pattern: Pattern,
+    /// `->`
+    pub right_arrow: RightArrow,
+    /// The expression to evaluate if the pattern matches.
+    pub expression: Box<Expression>,
+}
+
+/// A function parameter.
+#[derive(Debug, Clone)]
+pub enum FunctionBinder {
+    /// A name to be bound in the function body.
+    Name(Name),
+    /// `_`
+    ///
+    /// Ignores the argument, without binding it.
+    Wildcard(Wildcard),
+}
+
+/// A pattern to be matched.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    /// A constructor pattern, with optional argument patterns.
+    ///
+    /// ```ditto
+    /// Just(x)
+    /// Nothing
+    /// ```
+    Constructor {
+        /// The constructor, e.g. `Just`.
+        constructor: QualifiedProperName,
+        /// Patterns for the constructor arguments.
+        arguments: Option<ParensList1<Box<Self>>>,
+    },
+    /// A pattern for a constructor with named fields, matched by label.
+    ///
+    /// ```ditto
+    /// Person { name = n, age = 30 }
+    /// Person { name = n, .. }
+    /// ```
+    RecordConstructor {
+        /// The constructor, e.g. `Person`.
+        constructor: QualifiedProperName,
+        /// Patterns for the named fields.
+        fields: Braces<RecordPatternFields>,
+    },
+    /// A tuple pattern.
+    ///
+    /// ```ditto
+    /// (x, Just(y))
+    /// ```
+    Tuple(ParensList1<Box<Self>>),
+    /// A variable pattern, which matches anything and binds it to a name.
+    Variable {
+        /// The name to be bound.
+        name: Name,
+    },
+    /// `_`
+    ///
+    /// Matches anything, without binding it.
+    Wildcard(Wildcard),
+}
+
+/// The fields of a [Pattern::RecordConstructor].
+///
+/// ```ditto
+/// name = n, age = 30
+/// name = n, ..
+/// ```
+#[derive(Debug, Clone)]
+pub struct RecordPatternFields {
+    /// The labelled field patterns.
+    pub fields: CommaSep1<RecordPatternField>,
+    /// `..`, if any fields that aren't listed should be ignored.
+    ///
+    /// NOTE this always follows a trailing comma.
+    pub rest: Option<DoubleDot>,
+}
+
+/// A labelled pattern, such as `name = n`.
+#[derive(Debug, Clone)]
+pub struct RecordPatternField {
+    /// The field name.
+    pub label: Name,
+    /// `=`
+    pub equals: Equals,
+    /// The pattern to match the field against.
+    pub pattern: Box<Pattern>,
 }
 
 /// `: String`
@@ -1,6 +1,8 @@
 use crate::{
-    Brackets, Expression, ModuleName, Name, PackageName, Parens, ProperName, QualifiedName,
-    QualifiedProperName, Span, Token, Type, TypeAnnotation, TypeCallFunction,
+    Attribute, BinOp, Braces, Brackets, Declaration, Expression, FunctionBinder, MatchArm,
+    ModuleName, Name, PackageName, Parens, Pattern, ProperName, QualifiedName, QualifiedProperName,
+    RecordPatternField, RecordTypeField, Span, Token, Type, TypeAnnotation, TypeCallFunction,
+    TypeDeclaration, ValueDeclaration,
 };
 
 impl<Value> Token<Value> {
@@ -64,6 +66,48 @@ impl ModuleName {
     }
 }
 
+impl Declaration {
+    /// Get the source span.
+    pub fn get_span(&self) -> Span {
+        match self {
+            Self::Value(value_declaration) => value_declaration.get_span(),
+            Self::Type(box TypeDeclaration::WithConstructors {
+                type_keyword,
+                semicolon,
+                ..
+            })
+            | Self::Type(box TypeDeclaration::WithoutConstructors {
+                type_keyword,
+                semicolon,
+                ..
+            }) => type_keyword.0.get_span().merge(&semicolon.0.get_span()),
+            Self::ForeignValue(foreign_value_declaration) => foreign_value_declaration
+                .foreign_keyword
+                .0
+                .get_span()
+                .merge(&foreign_value_declaration.semicolon.0.get_span()),
+        }
+    }
+}
+
+impl ValueDeclaration {
+    /// Get the source span.
+    pub fn get_span(&self) -> Span {
+        let start = match self.attributes.first() {
+            Some(attribute) => attribute.get_span(),
+            None => self.name.get_span(),
+        };
+        start.merge(&self.semicolon.0.get_span())
+    }
+}
+
+impl Attribute {
+    /// Get the source span.
+    pub fn get_span(&self) -> Span {
+        self.at.0.get_span().merge(&self.name.get_span())
+    }
+}
+
 impl Expression {
     /// Get the source span.
     pub fn get_span(&self) -> Span {
@@ -71,6 +115,14 @@ impl Expression {
             Self::Parens(parens) => parens.get_span(),
             Self::Variable(qualified_name) => qualified_name.get_span(),
             Self::Constructor(qualified_proper_name) => qualified_proper_name.get_span(),
+            Self::RecordConstructor {
+                constructor,
+                fields,
+            } => constructor
+                .get_span()
+                .merge(&fields.close_brace.0.get_span()),
+            Self::Record(braces) => braces.get_span(),
+            Self::RecordAccess { target, label, .. } => target.get_span().merge(&label.get_span()),
             Self::Call {
                 function,
                 arguments,
@@ -85,13 +137,89 @@ impl Expression {
                 false_clause,
                 ..
             } => if_keyword.0.get_span().merge(&false_clause.get_span()),
+            Self::Let {
+                let_keyword,
+                expression,
+                ..
+            } => let_keyword.0.get_span().merge(&expression.get_span()),
+            Self::Match {
+                match_keyword,
+                end_keyword,
+                ..
+            } => match_keyword.0.get_span().merge(&end_keyword.0.get_span()),
+            Self::BinOp { lhs, rhs, .. } => lhs.get_span().merge(&rhs.get_span()),
+            Self::Negate { minus, expression } => minus.0.get_span().merge(&expression.get_span()),
             Self::String(string_token) => string_token.get_span(),
+            Self::Char(char_token) => char_token.get_span(),
+            Self::Hole(hole_token) => hole_token.get_span(),
             Self::Int(int_token) => int_token.get_span(),
             Self::Float(float_token) => float_token.get_span(),
             Self::Array(brackets) => brackets.get_span(),
+            Self::Tuple(parens) => parens.get_span(),
             Self::True(true_keyword) => true_keyword.0.get_span(),
             Self::False(false_keyword) => false_keyword.0.get_span(),
             Self::Unit(unit_keyword) => unit_keyword.0.get_span(),
+            Self::InlineJs {
+                js_keyword,
+                type_annotation,
+                ..
+            } => js_keyword.0.get_span().merge(&type_annotation.get_span()),
+        }
+    }
+}
+
+impl BinOp {
+    /// Get the source span.
+    pub fn get_span(&self) -> Span {
+        self.token().get_span()
+    }
+}
+
+impl MatchArm {
+    /// Get the source span.
+    pub fn get_span(&self) -> Span {
+        self.pipe.0.get_span().merge(&self.expression.get_span())
+    }
+}
+
+impl Pattern {
+    /// Get the source span.
+    pub fn get_span(&self) -> Span {
+        match self {
+            Self::Constructor {
+                constructor,
+                arguments: None,
+            } => constructor.get_span(),
+            Self::Constructor {
+                constructor,
+                arguments: Some(arguments),
+            } => constructor
+                .get_span()
+                .merge(&arguments.close_paren.0.get_span()),
+            Self::RecordConstructor {
+                constructor,
+                fields,
+            } => constructor.get_span().merge(&fields.get_span()),
+            Self::Tuple(parens) => parens.get_span(),
+            Self::Variable { name } => name.get_span(),
+            Self::Wildcard(wildcard) => wildcard.0.get_span(),
+        }
+    }
+}
+
+impl RecordPatternField {
+    /// Get the source span.
+    pub fn get_span(&self) -> Span {
+        self.label.get_span().merge(&self.pattern.get_span())
+    }
+}
+
+impl FunctionBinder {
+    /// Get the source span.
+    pub fn get_span(&self) -> Span {
+        match self {
+            Self::Name(name) => name.get_span(),
+            Self::Wildcard(wildcard) => wildcard.0.get_span(),
         }
     }
 }
@@ -102,6 +230,7 @@ impl Type {
         match self {
             Self::Parens(parens) => parens.get_span(),
             Self::Variable(qualified_name) => qualified_name.get_span(),
+            Self::Wildcard(wildcard) => wildcard.0.get_span(),
             Self::Constructor(qualified_proper_name) => qualified_proper_name.get_span(),
             Self::Call {
                 function,
@@ -118,10 +247,21 @@ impl Type {
                 .0
                 .get_span()
                 .merge(&return_type.get_span()),
+            Self::Record(braces) => braces.get_span(),
+            Self::Tuple(parens) => parens.get_span(),
         }
     }
 }
 
+impl RecordTypeField {
+    /// Get the source span.
+    pub fn get_span(&self) -> Span {
+        self.label
+            .get_span()
+            .merge(&self.type_annotation.get_span())
+    }
+}
+
 impl TypeAnnotation {
     /// Get the source span.
     pub fn get_span(&self) -> Span {
@@ -149,6 +289,16 @@ impl<T> Parens<T> {
     }
 }
 
+impl<T> Braces<T> {
+    /// Get the source span.
+    pub fn get_span(&self) -> Span {
+        self.open_brace
+            .0
+            .get_span()
+            .merge(&self.close_brace.0.get_span())
+    }
+}
+
 impl<T> Brackets<T> {
     /// Get the source span.
     pub fn get_span(&self) -> Span {
@@ -0,0 +1,46 @@
+use crate::{complexity::sub_expressions, Declaration, Expression, Module, Span};
+
+impl Module {
+    /// The spans of every inline JavaScript expression in this module.
+    ///
+    /// Inline JavaScript is unsafe, so build tools need to be able to reject it.
+    pub fn inline_js_spans(&self) -> Vec<Span> {
+        let mut spans = Vec::new();
+        for declaration in self.declarations.iter() {
+            if let Declaration::Value(value_declaration) = declaration {
+                collect_inline_js_spans(&value_declaration.expression, &mut spans);
+            }
+        }
+        spans
+    }
+}
+
+fn collect_inline_js_spans(expression: &Expression, spans: &mut Vec<Span>) {
+    if let Expression::InlineJs { .. } = expression {
+        spans.push(expression.get_span());
+    }
+    for expression in sub_expressions(expression) {
+        collect_inline_js_spans(expression, spans);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Module;
+
+    #[test]
+    fn it_finds_inline_js() {
+        let source = r#"module Test exports (..);
+now = js "Date.now()" : Int;
+five = [5, if true then js "5" : Int else 5];
+js = 5;
+"#;
+        let module = Module::parse(source).unwrap();
+        let spans = module
+            .inline_js_spans()
+            .into_iter()
+            .map(|span| &source[span.start_offset..span.end_offset])
+            .collect::<Vec<_>>();
+        assert_eq!(spans, vec![r#"js "Date.now()" : Int"#, r#"js "5" : Int"#]);
+    }
+}
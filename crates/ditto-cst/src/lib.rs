@@ -2,15 +2,21 @@
 #![feature(box_patterns)]
 #![warn(missing_docs)]
 
+pub mod complexity;
+mod escape;
 mod expression;
 mod get_span;
+mod inline_js;
 mod module;
 mod name;
+pub mod naming;
 mod parser;
 mod syntax;
 mod token;
 mod r#type;
+pub mod validate;
 
+pub use escape::unescape_string;
 pub use expression::*;
 pub use module::*;
 pub use name::*;
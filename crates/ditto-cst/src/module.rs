@@ -1,7 +1,7 @@
 use crate::{
-    AsKeyword, Comment, DoubleDot, Equals, ExportsKeyword, Expression, ForeignKeyword,
-    ImportKeyword, ModuleKeyword, ModuleName, Name, PackageName, Parens, ParensList1, Pipe,
-    ProperName, Semicolon, Type, TypeAnnotation, TypeKeyword,
+    AsKeyword, At, BracesList1, Comment, DoubleDot, Equals, ExportsKeyword, Expression,
+    ForeignKeyword, ImportKeyword, ModuleKeyword, ModuleName, Name, PackageName, Parens,
+    ParensList1, Pipe, ProperName, Semicolon, StringToken, Type, TypeAnnotation, TypeKeyword,
 };
 use std::iter;
 
@@ -21,6 +21,8 @@ pub struct Module {
 /// `module Some.Module exports (..);`
 #[derive(Debug, Clone)]
 pub struct Header {
+    /// Attributes, such as `@no_optimize`.
+    pub attributes: Vec<Attribute>,
     /// `module`
     pub module_keyword: ModuleKeyword,
     /// `Some.Module`
@@ -41,6 +43,8 @@ pub type Everything = Parens<DoubleDot>;
 pub enum Exports {
     /// `(..)`
     Everything(Everything),
+    /// `()`, which is only useful for entry points.
+    Nothing(Parens<()>),
     /// `(Foo, Bar(..), baz)`
     List(Box<ParensList1<Export>>),
 }
@@ -51,7 +55,13 @@ pub enum Export {
     /// `foo`
     Value(Name),
     /// `Foo` or `Foo(..)`
+    ///
+    /// Note that `Foo` might also refer to a single constructor.
     Type(ProperName, Option<Everything>),
+    /// `module Some.Module`
+    ///
+    /// Re-exports everything exported by an imported module.
+    Module(ModuleKeyword, ModuleName),
 }
 
 /// `import (some_package) Some.Module as Alias (..);`
@@ -83,6 +93,8 @@ pub enum Import {
     /// `foo`
     Value(Name),
     /// `Foo` or `Foo(..)`
+    ///
+    /// Note that `Foo` might also refer to a single constructor.
     Type(ProperName, Option<Everything>),
 }
 
@@ -104,6 +116,10 @@ pub enum Declaration {
 /// ```
 #[derive(Debug, Clone)]
 pub struct ValueDeclaration {
+    /// Attributes, such as `@no_inline`.
+    ///
+    /// These are always empty for `let` bindings, which can't have attributes.
+    pub attributes: Vec<Attribute>,
     /// Name of this value.
     pub name: Name,
     /// Optional type of the value.
@@ -116,6 +132,38 @@ pub struct ValueDeclaration {
     pub semicolon: Semicolon,
 }
 
+impl Header {
+    /// Does this header have an attribute called `name`?
+    pub fn has_attribute(&self, name: &str) -> bool {
+        self.attributes
+            .iter()
+            .any(|attribute| attribute.name.0.value == name)
+    }
+}
+
+impl ValueDeclaration {
+    /// Does this declaration have an attribute called `name`?
+    pub fn has_attribute(&self, name: &str) -> bool {
+        self.attributes
+            .iter()
+            .any(|attribute| attribute.name.0.value == name)
+    }
+}
+
+/// An annotation that changes how a module or declaration is compiled.
+///
+/// ```ditto
+/// @no_inline
+/// max_retries = 3;
+/// ```
+#[derive(Debug, Clone)]
+pub struct Attribute {
+    /// `@`
+    pub at: At,
+    /// The name of the attribute, e.g. `no_inline`.
+    pub name: Name,
+}
+
 /// Introducing a new type.
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone)]
@@ -215,18 +263,72 @@ pub struct Constructor<P = Pipe> {
     /// `Just`
     pub constructor_name: ProperName,
     /// Optional type fields for this constructor.
-    pub fields: Option<ParensList1<Type>>,
+    pub fields: Option<ConstructorFields>,
+}
+
+/// The fields of a [Constructor].
+#[derive(Debug, Clone)]
+pub enum ConstructorFields {
+    /// ```ditto
+    /// Just(a)
+    /// ```
+    Positional(ParensList1<Type>),
+    /// ```ditto
+    /// User(name: String, age: Int)
+    /// ```
+    ///
+    /// Applied positionally, like [ConstructorFields::Positional], but the
+    /// labels are tracked like [ConstructorFields::Named].
+    Labelled(ParensList1<ConstructorField>),
+    /// ```ditto
+    /// Person { name: String, age: Int }
+    /// ```
+    Named(BracesList1<ConstructorField>),
+}
+
+impl ConstructorFields {
+    /// Iterate through the field types, in declaration order.
+    pub fn types(&self) -> Box<dyn iter::Iterator<Item = &Type> + '_> {
+        match self {
+            Self::Positional(parens) => Box::new(parens.value.iter()),
+            Self::Labelled(parens) => {
+                Box::new(parens.value.iter().map(|field| &field.type_annotation.1))
+            }
+            Self::Named(braces) => {
+                Box::new(braces.value.iter().map(|field| &field.type_annotation.1))
+            }
+        }
+    }
+}
+
+/// A named constructor field.
+///
+/// ```ditto
+/// name: String
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConstructorField {
+    /// The field name.
+    pub label: Name,
+    /// The type of the field.
+    pub type_annotation: TypeAnnotation,
 }
 
 /// A foreign value import.
 ///
 /// ```ditto
 /// foreign foo : Int;
+/// foreign "if" as if_impl : (Bool, a, a) -> a;
 /// ```
 #[derive(Debug, Clone)]
 pub struct ForeignValueDeclaration {
     /// `foreign`
     pub foreign_keyword: ForeignKeyword,
+    /// Optional JavaScript name of the value being imported, for when it
+    /// isn't a legal ditto name.
+    ///
+    /// Note that the string token value _doesn't_ include the quotes.
+    pub foreign_name: Option<(StringToken, AsKeyword)>,
     /// The name of the value being imported.
     pub name: Name,
     /// The type of the value being imported.
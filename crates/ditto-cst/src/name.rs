@@ -62,8 +62,8 @@ impl QualifiedProperName {
 }
 
 impl ModuleName {
-    #[cfg(test)]
-    pub(crate) fn render(&self) -> String {
+    /// Render the module name without any whitespace or comments, e.g. `Some.Module`.
+    pub fn render(&self) -> String {
         let mut rendered = self
             .init
             .iter()
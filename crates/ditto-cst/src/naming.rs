@@ -0,0 +1,722 @@
+//! Naming conventions.
+//!
+//! Like [complexity](crate::complexity) measures, these only need a module to
+//! parse.
+//!
+//! Every violation carries the conventional version of the offending name. If
+//! the name is a binder, and renaming it (and its references) can't change
+//! the meaning of the program, the violation also carries the [Edit]s needed
+//! to apply the rename. Top-level names never get edits, as they might be
+//! referenced by other modules.
+
+use crate::{
+    complexity::sub_expressions, Declaration, Expression, FunctionBinder, Module, Name, Pattern,
+    Span, TypeDeclaration, ValueDeclaration,
+};
+
+/// A naming convention.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NamingRule {
+    /// Values and binders should be `snake_case`.
+    SnakeCase,
+    /// Types and constructors should be `UpperCamelCase`.
+    UpperCamelCase,
+    /// Module name segments should be capitalized words, without underscores.
+    CapitalizedModules,
+    /// Names shouldn't contain consecutive underscores.
+    ConsecutiveUnderscores,
+    /// Names shouldn't end with an underscore, unless they're unused binders.
+    TrailingUnderscore,
+}
+
+impl NamingRule {
+    /// The configuration key for this rule.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::SnakeCase => "snake-case",
+            Self::UpperCamelCase => "upper-camel-case",
+            Self::CapitalizedModules => "capitalized-modules",
+            Self::ConsecutiveUnderscores => "consecutive-underscores",
+            Self::TrailingUnderscore => "trailing-underscore",
+        }
+    }
+}
+
+/// The sort of thing being named.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NameKind {
+    /// A top-level value or foreign value.
+    Value,
+    /// A function, `let` or pattern binder.
+    Binder,
+    /// A type.
+    Type,
+    /// A type constructor.
+    Constructor,
+    /// A segment of a module name.
+    ModuleSegment,
+}
+
+/// A name that doesn't follow a [NamingRule].
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamingViolation {
+    /// The rule that was broken.
+    pub rule: NamingRule,
+    /// What's being named.
+    pub kind: NameKind,
+    /// The offending name.
+    pub name: String,
+    /// Where the offending name is bound.
+    pub span: Span,
+    /// A conventional alternative name, if there is one.
+    pub suggestion: Option<String>,
+    /// Edits that rename the binder to the suggested name, if that's safe.
+    pub edits: Option<Vec<Edit>>,
+}
+
+/// A replacement of some source text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edit {
+    /// The source to be replaced.
+    pub span: Span,
+    /// The replacement text.
+    pub replacement: String,
+}
+
+/// Apply non-overlapping edits to some source text.
+pub fn apply_edits(source: &str, edits: &[Edit]) -> String {
+    let mut edits = edits.iter().collect::<Vec<_>>();
+    edits.sort_by_key(|edit| edit.span.start_offset);
+    edits.dedup();
+    let mut output = String::with_capacity(source.len());
+    let mut offset = 0;
+    for edit in edits {
+        output.push_str(&source[offset..edit.span.start_offset]);
+        output.push_str(&edit.replacement);
+        offset = edit.span.end_offset;
+    }
+    output.push_str(&source[offset..]);
+    output
+}
+
+/// Check every name bound by a module against all the [NamingRule]s.
+pub fn check_names(module: &Module) -> Vec<NamingViolation> {
+    let mut violations = Vec::new();
+
+    let module_name = &module.header.module_name;
+    for segment in module_name
+        .init
+        .iter()
+        .map(|(proper_name, _dot)| proper_name)
+        .chain(std::iter::once(&module_name.last))
+    {
+        check_name(
+            NameKind::ModuleSegment,
+            &segment.0.value,
+            segment.get_span(),
+            None,
+            &mut violations,
+        );
+    }
+
+    for declaration in module.declarations.iter() {
+        match declaration {
+            Declaration::Value(box ValueDeclaration {
+                name, expression, ..
+            }) => {
+                check_top_level_name(name, &mut violations);
+                check_expression(expression, &mut violations);
+            }
+            Declaration::ForeignValue(foreign_value) => {
+                check_top_level_name(&foreign_value.name, &mut violations);
+            }
+            Declaration::Type(box TypeDeclaration::WithConstructors {
+                type_name,
+                head_constructor,
+                tail_constructors,
+                ..
+            }) => {
+                check_name(
+                    NameKind::Type,
+                    &type_name.0.value,
+                    type_name.get_span(),
+                    None,
+                    &mut violations,
+                );
+                let constructor_names = std::iter::once(&head_constructor.constructor_name)
+                    .chain(tail_constructors.iter().map(|ctor| &ctor.constructor_name));
+                for constructor_name in constructor_names {
+                    check_name(
+                        NameKind::Constructor,
+                        &constructor_name.0.value,
+                        constructor_name.get_span(),
+                        None,
+                        &mut violations,
+                    );
+                }
+            }
+            Declaration::Type(box TypeDeclaration::WithoutConstructors { type_name, .. }) => {
+                check_name(
+                    NameKind::Type,
+                    &type_name.0.value,
+                    type_name.get_span(),
+                    None,
+                    &mut violations,
+                );
+            }
+        }
+    }
+    violations
+}
+
+fn check_top_level_name(name: &Name, violations: &mut Vec<NamingViolation>) {
+    check_name(
+        NameKind::Value,
+        &name.0.value,
+        name.get_span(),
+        None,
+        violations,
+    );
+}
+
+/// The scope of a binder, and the other names bound alongside it.
+struct Scope<'a> {
+    siblings: Vec<&'a Name>,
+    /// References to the binder.
+    references: Vec<Span>,
+    /// Whether this scope mentions a name at all, in which case renaming the
+    /// binder to that name could change the meaning of the program.
+    mentions: Box<dyn Fn(&str) -> bool + 'a>,
+}
+
+fn check_expression(expression: &Expression, violations: &mut Vec<NamingViolation>) {
+    match expression {
+        Expression::Function {
+            parameters, body, ..
+        } => {
+            let binders = parameters
+                .value
+                .as_ref()
+                .map_or_else(Vec::new, |parameters| {
+                    parameters
+                        .iter()
+                        .filter_map(|(binder, _)| match binder {
+                            FunctionBinder::Name(name) => Some(name),
+                            FunctionBinder::Wildcard(_) => None,
+                        })
+                        .collect::<Vec<_>>()
+                });
+            for binder in binders.iter() {
+                let scope = Scope {
+                    siblings: binders.clone(),
+                    references: references(body, &binder.0.value),
+                    mentions: Box::new(|name| mentions(body, name)),
+                };
+                check_binder(binder, scope, violations);
+            }
+            check_expression(body, violations);
+        }
+        Expression::Let {
+            head_declaration,
+            tail_declarations,
+            expression,
+            ..
+        } => {
+            let declarations = std::iter::once(head_declaration.as_ref())
+                .chain(tail_declarations.iter())
+                .collect::<Vec<_>>();
+            for (i, declaration) in declarations.iter().enumerate() {
+                let later = &declarations[i + 1..];
+                let scope = Scope {
+                    siblings: Vec::new(),
+                    references: let_references(later, expression, &declaration.name.0.value),
+                    mentions: Box::new(move |name| {
+                        later.iter().any(|declaration| {
+                            declaration.name.0.value == name
+                                || mentions(&declaration.expression, name)
+                        }) || mentions(expression, name)
+                    }),
+                };
+                check_binder(&declaration.name, scope, violations);
+                check_expression(&declaration.expression, violations);
+            }
+            check_expression(expression, violations);
+        }
+        Expression::Match {
+            expression,
+            head_arm,
+            tail_arms,
+            ..
+        } => {
+            check_expression(expression, violations);
+            for arm in std::iter::once(head_arm.as_ref()).chain(tail_arms.iter()) {
+                let mut binders = Vec::new();
+                pattern_binders(&arm.pattern, &mut binders);
+                for binder in binders.iter() {
+                    let scope = Scope {
+                        siblings: binders.clone(),
+                        references: references(&arm.expression, &binder.0.value),
+                        mentions: Box::new(|name| mentions(&arm.expression, name)),
+                    };
+                    check_binder(binder, scope, violations);
+                }
+                check_expression(&arm.expression, violations);
+            }
+        }
+        _ => {
+            for expression in sub_expressions(expression) {
+                check_expression(expression, violations);
+            }
+        }
+    }
+}
+
+fn check_binder(binder: &Name, scope: Scope, violations: &mut Vec<NamingViolation>) {
+    let name = &binder.0.value;
+    let span = binder.get_span();
+    let unused = scope.references.is_empty();
+    let start = violations.len();
+    check_name(NameKind::Binder, name, span, Some(unused), violations);
+
+    let suggestion = match violations[start..]
+        .first()
+        .and_then(|violation| violation.suggestion.clone())
+    {
+        Some(suggestion) => suggestion,
+        None => return,
+    };
+    let is_safe = is_valid_name(&suggestion)
+        && !scope
+            .siblings
+            .iter()
+            .any(|sibling| sibling.0.value == suggestion)
+        && !(scope.mentions)(&suggestion);
+    if !is_safe {
+        return;
+    }
+    let edits = std::iter::once(span)
+        .chain(scope.references)
+        .map(|span| Edit {
+            span,
+            replacement: suggestion.clone(),
+        })
+        .collect::<Vec<_>>();
+    for violation in violations[start..].iter_mut() {
+        violation.edits = Some(edits.clone());
+    }
+}
+
+/// Check a single name against every rule that applies to it.
+///
+/// `unused` is only known for binders.
+fn check_name(
+    kind: NameKind,
+    name: &str,
+    span: Span,
+    unused: Option<bool>,
+    violations: &mut Vec<NamingViolation>,
+) {
+    let mut rules = Vec::new();
+    match kind {
+        NameKind::Value | NameKind::Binder => {
+            if name.chars().any(char::is_uppercase) {
+                rules.push(NamingRule::SnakeCase);
+            }
+        }
+        NameKind::Type | NameKind::Constructor => {
+            if name.contains('_') {
+                rules.push(NamingRule::UpperCamelCase);
+            }
+        }
+        NameKind::ModuleSegment => {
+            if name.contains('_') {
+                rules.push(NamingRule::CapitalizedModules);
+            }
+        }
+    }
+    if name.contains("__") {
+        rules.push(NamingRule::ConsecutiveUnderscores);
+    }
+    // A trailing underscore is how binders are marked as intentionally unused
+    if name.ends_with('_') && unused != Some(true) {
+        rules.push(NamingRule::TrailingUnderscore);
+    }
+
+    let suggestion = match kind {
+        NameKind::Value | NameKind::Binder => {
+            to_snake_case(name, unused == Some(true) && name.ends_with('_'))
+        }
+        NameKind::Type | NameKind::Constructor | NameKind::ModuleSegment => {
+            to_upper_camel_case(name)
+        }
+    };
+    let suggestion =
+        Some(suggestion).filter(|suggestion| suggestion != name && !suggestion.is_empty());
+    violations.extend(rules.into_iter().map(|rule| NamingViolation {
+        rule,
+        kind,
+        name: name.to_string(),
+        span,
+        suggestion: suggestion.clone(),
+        edits: None,
+    }));
+}
+
+/// Convert a name to `snake_case`, e.g. `parseHTTPRequest` becomes `parse_http_request`.
+fn to_snake_case(name: &str, keep_trailing_underscore: bool) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut snake = String::with_capacity(name.len());
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            let previous = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).map_or(false, |next| next.is_lowercase());
+            if previous.is_lowercase()
+                || previous.is_ascii_digit()
+                || (previous.is_uppercase() && next_is_lower)
+            {
+                snake.push('_');
+            }
+        }
+        snake.extend(c.to_lowercase());
+    }
+    let mut snake = snake
+        .split('_')
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("_");
+    if keep_trailing_underscore {
+        snake.push('_');
+    }
+    snake
+}
+
+/// Convert a name to `UpperCamelCase`, e.g. `Http_Request` becomes `HttpRequest`.
+fn to_upper_camel_case(name: &str) -> String {
+    name.split('_')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+const KEYWORDS: [&str; 17] = [
+    "true", "false", "unit", "if", "then", "else", "module", "exports", "import", "as", "type",
+    "foreign", "let", "in", "match", "with", "end",
+];
+
+fn is_valid_name(name: &str) -> bool {
+    !KEYWORDS.contains(&name) && Name::parse(name).map_or(false, |parsed| parsed.0.value == name)
+}
+
+fn pattern_binders<'a>(pattern: &'a Pattern, binders: &mut Vec<&'a Name>) {
+    match pattern {
+        Pattern::Variable { name } => binders.push(name),
+        Pattern::Constructor { arguments, .. } => {
+            if let Some(arguments) = arguments {
+                for argument in arguments.value.iter() {
+                    pattern_binders(argument, binders);
+                }
+            }
+        }
+        Pattern::RecordConstructor { fields, .. } => {
+            for field in fields.value.fields.iter() {
+                pattern_binders(&field.pattern, binders);
+            }
+        }
+        Pattern::Tuple(patterns) => {
+            for pattern in patterns.value.iter() {
+                pattern_binders(pattern, binders);
+            }
+        }
+        Pattern::Wildcard(_) => {}
+    }
+}
+
+/// Spans of the unqualified variables in an expression that refer to `name`,
+/// taking shadowing into account.
+fn references(expression: &Expression, name: &str) -> Vec<Span> {
+    let mut spans = Vec::new();
+    collect_references(expression, name, &mut spans);
+    spans
+}
+
+fn let_references(declarations: &[&ValueDeclaration], body: &Expression, name: &str) -> Vec<Span> {
+    let mut spans = Vec::new();
+    for declaration in declarations {
+        // `let` declarations can't refer to themselves
+        collect_references(&declaration.expression, name, &mut spans);
+        if declaration.name.0.value == name {
+            return spans;
+        }
+    }
+    collect_references(body, name, &mut spans);
+    spans
+}
+
+fn collect_references(expression: &Expression, name: &str, spans: &mut Vec<Span>) {
+    match expression {
+        Expression::Variable(variable) => {
+            if variable.module_name.is_none() && variable.value.0.value == name {
+                spans.push(variable.value.get_span());
+            }
+        }
+        Expression::Function {
+            parameters, body, ..
+        } => {
+            let shadowed = parameters.value.as_ref().map_or(false, |parameters| {
+                parameters.iter().any(|(binder, _)| match binder {
+                    FunctionBinder::Name(binder) => binder.0.value == name,
+                    FunctionBinder::Wildcard(_) => false,
+                })
+            });
+            if !shadowed {
+                collect_references(body, name, spans);
+            }
+        }
+        Expression::Let {
+            head_declaration,
+            tail_declarations,
+            expression,
+            ..
+        } => {
+            let declarations = std::iter::once(head_declaration.as_ref())
+                .chain(tail_declarations.iter())
+                .collect::<Vec<_>>();
+            spans.extend(let_references(&declarations, expression, name));
+        }
+        Expression::Match {
+            expression,
+            head_arm,
+            tail_arms,
+            ..
+        } => {
+            collect_references(expression, name, spans);
+            for arm in std::iter::once(head_arm.as_ref()).chain(tail_arms.iter()) {
+                let mut binders = Vec::new();
+                pattern_binders(&arm.pattern, &mut binders);
+                if !binders.iter().any(|binder| binder.0.value == name) {
+                    collect_references(&arm.expression, name, spans);
+                }
+            }
+        }
+        _ => {
+            for expression in sub_expressions(expression) {
+                collect_references(expression, name, spans);
+            }
+        }
+    }
+}
+
+/// Whether `name` appears anywhere in an expression, as either a variable or a binder.
+fn mentions(expression: &Expression, name: &str) -> bool {
+    let binds = match expression {
+        Expression::Variable(variable) => {
+            variable.module_name.is_none() && variable.value.0.value == name
+        }
+        Expression::Function { parameters, .. } => {
+            parameters.value.as_ref().map_or(false, |parameters| {
+                parameters.iter().any(|(binder, _)| match binder {
+                    FunctionBinder::Name(binder) => binder.0.value == name,
+                    FunctionBinder::Wildcard(_) => false,
+                })
+            })
+        }
+        Expression::Let {
+            head_declaration,
+            tail_declarations,
+            ..
+        } => std::iter::once(head_declaration.as_ref())
+            .chain(tail_declarations.iter())
+            .any(|declaration| declaration.name.0.value == name),
+        Expression::Match {
+            head_arm,
+            tail_arms,
+            ..
+        } => std::iter::once(head_arm.as_ref())
+            .chain(tail_arms.iter())
+            .any(|arm| {
+                let mut binders = Vec::new();
+                pattern_binders(&arm.pattern, &mut binders);
+                binders.iter().any(|binder| binder.0.value == name)
+            }),
+        _ => false,
+    };
+    binds
+        || sub_expressions(expression)
+            .into_iter()
+            .any(|expression| mentions(expression, name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(source: &str) -> Vec<(NamingRule, String, Option<String>, bool)> {
+        let module = Module::parse(source).unwrap();
+        check_names(&module)
+            .into_iter()
+            .map(|violation| {
+                (
+                    violation.rule,
+                    violation.name,
+                    violation.suggestion,
+                    violation.edits.is_some(),
+                )
+            })
+            .collect()
+    }
+
+    fn violation(
+        rule: NamingRule,
+        name: &str,
+        suggestion: Option<&str>,
+        fixable: bool,
+    ) -> (NamingRule, String, Option<String>, bool) {
+        (
+            rule,
+            name.to_string(),
+            suggestion.map(String::from),
+            fixable,
+        )
+    }
+
+    #[test]
+    fn it_accepts_conventional_names() {
+        assert!(check(
+            "module Data.Http exports (..);
+            type HttpRequest = Get | Post(Int);
+            foreign get_request : (String) -> HttpRequest;
+            identity = (a) -> a;
+            "
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn it_checks_snake_case() {
+        assert_eq!(
+            check("module Test exports (..); fiveFive = 55; foreign parseHTTPRequest : Int;"),
+            vec![
+                violation(NamingRule::SnakeCase, "fiveFive", Some("five_five"), false),
+                violation(
+                    NamingRule::SnakeCase,
+                    "parseHTTPRequest",
+                    Some("parse_http_request"),
+                    false
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_checks_upper_camel_case() {
+        assert_eq!(
+            check("module Test exports (..); type Http_request = Not_found;"),
+            vec![
+                violation(
+                    NamingRule::UpperCamelCase,
+                    "Http_request",
+                    Some("HttpRequest"),
+                    false
+                ),
+                violation(
+                    NamingRule::UpperCamelCase,
+                    "Not_found",
+                    Some("NotFound"),
+                    false
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_checks_module_segments() {
+        assert_eq!(
+            check("module Data.Http_client exports (..);"),
+            vec![violation(
+                NamingRule::CapitalizedModules,
+                "Http_client",
+                Some("HttpClient"),
+                false
+            )]
+        );
+    }
+
+    #[test]
+    fn it_checks_consecutive_underscores() {
+        assert_eq!(
+            check("module Test exports (..); five__five = 55;"),
+            vec![violation(
+                NamingRule::ConsecutiveUnderscores,
+                "five__five",
+                Some("five_five"),
+                false
+            )]
+        );
+    }
+
+    #[test]
+    fn it_checks_trailing_underscores() {
+        assert_eq!(
+            check("module Test exports (..); five_ = 5; const = (a, b_) -> a; used = (a_) -> a_;"),
+            vec![
+                violation(NamingRule::TrailingUnderscore, "five_", Some("five"), false),
+                violation(NamingRule::TrailingUnderscore, "a_", Some("a"), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_only_fixes_binders_that_are_safe_to_rename() {
+        // Top-level names are never fixed
+        assert!(!check("module Test exports (..); fiveFive = 55;")[0].3);
+        // Function, let and pattern binders are
+        assert_eq!(
+            check(
+                "module Test exports (..);
+                f = (myArg) -> let myLet = myArg; in match myLet with | Just(myPat) -> myPat end;"
+            ),
+            vec![
+                violation(NamingRule::SnakeCase, "myArg", Some("my_arg"), true),
+                violation(NamingRule::SnakeCase, "myLet", Some("my_let"), true),
+                violation(NamingRule::SnakeCase, "myPat", Some("my_pat"), true),
+            ]
+        );
+        // Unless the new name would clash with a sibling binder..
+        assert!(!check("module Test exports (..); f = (myArg, my_arg) -> myArg;")[0].3);
+        // ..or capture a variable in scope
+        assert!(!check("module Test exports (..); f = (myArg) -> [my_arg, myArg];")[0].3);
+        // ..or produce a keyword
+        assert_eq!(
+            check("module Test exports (..); f = (in_) -> in_;"),
+            vec![violation(
+                NamingRule::TrailingUnderscore,
+                "in_",
+                Some("in"),
+                false
+            )]
+        );
+    }
+
+    #[test]
+    fn it_renames_every_reference() {
+        let source = "module Test exports (..);
+f = (myArg) -> [myArg, (myArg) -> myArg, let x = myArg; myArg = 5; in myArg, Foo.myArg];
+";
+        let module = Module::parse(source).unwrap();
+        let violations = check_names(&module);
+        assert_eq!(violations.len(), 3);
+        let edits = violations[0].edits.clone().unwrap();
+        assert_eq!(
+            apply_edits(source, &edits),
+            "module Test exports (..);
+f = (my_arg) -> [my_arg, (myArg) -> myArg, let x = my_arg; myArg = 5; in myArg, Foo.myArg];
+"
+        );
+    }
+}
@@ -1,7 +1,8 @@
 use super::{parse_rule, Result, Rule};
 use crate::{
-    Constructor, Equals, Expression, ForeignKeyword, ForeignValueDeclaration, Name, ParensList1,
-    Pipe, ProperName, Semicolon, Type, TypeAnnotation, TypeDeclaration, TypeKeyword,
+    AsKeyword, At, Attribute, BracesList1, Constructor, ConstructorField, ConstructorFields,
+    Equals, Expression, ForeignKeyword, ForeignValueDeclaration, Name, ParensList1, Pipe,
+    ProperName, Semicolon, StringToken, Type, TypeAnnotation, TypeDeclaration, TypeKeyword,
     ValueDeclaration,
 };
 use pest::iterators::Pair;
@@ -73,7 +74,13 @@ impl ValueDeclaration {
     }
     pub(super) fn from_pair(pair: Pair<Rule>) -> Self {
         let mut inner = pair.into_inner();
-        let name = Name::from_pair(inner.next().unwrap());
+        let mut attributes = Vec::new();
+        let mut next = inner.next().unwrap();
+        while next.as_rule() == Rule::attribute {
+            attributes.push(Attribute::from_pair(next));
+            next = inner.next().unwrap();
+        }
+        let name = Name::from_pair(next);
         let (type_annotation, equals) = {
             let next = inner.next().unwrap();
             if next.as_rule() == Rule::type_annotation {
@@ -88,6 +95,7 @@ impl ValueDeclaration {
         let expression = Expression::from_pair(inner.next().unwrap());
         let semicolon = Semicolon::from_pair(inner.next().unwrap());
         Self {
+            attributes,
             name,
             type_annotation,
             equals,
@@ -97,6 +105,16 @@ impl ValueDeclaration {
     }
 }
 
+impl Attribute {
+    pub(super) fn from_pair(pair: Pair<Rule>) -> Self {
+        debug_assert_eq!(pair.as_rule(), Rule::attribute);
+        let mut inner = pair.into_inner();
+        let at = At::from_pair(inner.next().unwrap());
+        let name = Name::from_pair(inner.next().unwrap());
+        Self { at, name }
+    }
+}
+
 impl ForeignValueDeclaration {
     /// Parse a [ForeignValueDeclaration].
     pub fn parse(input: &str) -> Result<Self> {
@@ -108,11 +126,28 @@ impl ForeignValueDeclaration {
     pub(super) fn from_pair(pair: Pair<Rule>) -> Self {
         let mut inner = pair.into_inner();
         let foreign_keyword = ForeignKeyword::from_pair(inner.next().unwrap());
-        let name = Name::from_pair(inner.next().unwrap());
+        let mut next = inner.next().unwrap();
+        let foreign_name = if next.as_rule() == Rule::module_declaration_foreign_name {
+            let mut foreign_name_inner = next.into_inner();
+            let string_token =
+                StringToken::from_pairs(&mut foreign_name_inner.next().unwrap().into_inner());
+            let string_token = StringToken {
+                // Remove the surrounding quotes
+                value: string_token.value[1..string_token.value.len() - 1].to_owned(),
+                ..string_token
+            };
+            let as_keyword = AsKeyword::from_pair(foreign_name_inner.next().unwrap());
+            next = inner.next().unwrap();
+            Some((string_token, as_keyword))
+        } else {
+            None
+        };
+        let name = Name::from_pair(next);
         let type_annotation = TypeAnnotation::from_pair(inner.next().unwrap());
         let semicolon = Semicolon::from_pair(inner.next().unwrap());
         Self {
             foreign_keyword,
+            foreign_name,
             name,
             type_annotation,
             semicolon,
@@ -125,9 +160,7 @@ impl Constructor {
         let mut inner = pair.into_inner();
         let pipe = Pipe::from_pair(inner.next().unwrap());
         let constructor_name = ProperName::from_pair(inner.next().unwrap());
-        let fields = inner
-            .next()
-            .map(|fields_pair| ParensList1::list1_from_pair(fields_pair, Type::from_pair));
+        let fields = inner.next().map(ConstructorFields::from_pair);
         Self {
             pipe,
             constructor_name,
@@ -136,6 +169,35 @@ impl Constructor {
     }
 }
 
+impl ConstructorFields {
+    fn from_pair(pair: Pair<Rule>) -> Self {
+        match pair.as_rule() {
+            Rule::module_declaration_constructor_fields => {
+                Self::Positional(ParensList1::list1_from_pair(pair, Type::from_pair))
+            }
+            Rule::module_declaration_constructor_labelled_fields => Self::Labelled(
+                ParensList1::list1_from_pair(pair, ConstructorField::from_pair),
+            ),
+            Rule::module_declaration_constructor_named_fields => Self::Named(
+                BracesList1::list1_from_pair(pair, ConstructorField::from_pair),
+            ),
+            other => unreachable!("{:#?}", other),
+        }
+    }
+}
+
+impl ConstructorField {
+    fn from_pair(pair: Pair<Rule>) -> Self {
+        let mut inner = pair.into_inner();
+        let label = Name::from_pair(inner.next().unwrap());
+        let type_annotation = TypeAnnotation::from_pair(inner.next().unwrap());
+        Self {
+            label,
+            type_annotation,
+        }
+    }
+}
+
 impl Constructor<Option<Pipe>> {
     fn from_pair_optional_pipe(pair: Pair<Rule>) -> Self {
         let mut inner = pair.into_inner();
@@ -150,9 +212,7 @@ impl Constructor<Option<Pipe>> {
                 (None, constructor_name)
             }
         };
-        let fields = inner
-            .next()
-            .map(|fields_pair| ParensList1::list1_from_pair(fields_pair, Type::from_pair));
+        let fields = inner.next().map(ConstructorFields::from_pair);
         Self {
             pipe,
             constructor_name,
@@ -164,11 +224,22 @@ impl Constructor<Option<Pipe>> {
 #[cfg(test)]
 mod tests {
     use super::test_macros::*;
-    use crate::{Constructor, ForeignValueDeclaration, TypeDeclaration, ValueDeclaration};
+    use crate::{
+        Constructor, ConstructorFields, ForeignValueDeclaration, StringToken, TypeDeclaration,
+        ValueDeclaration,
+    };
 
     #[test]
     fn it_parses_value_declarations() {
         assert_value_declaration!("five : Int = 5;", ValueDeclaration { .. });
+        assert_value_declaration!(
+            "@no_inline five = 5;",
+            ValueDeclaration { ref attributes, .. } if attributes.len() == 1
+        );
+        assert_value_declaration!(
+            "-- docs\n@no_inline\nfive = 5;",
+            ValueDeclaration { ref attributes, .. } if attributes[0].at.0.leading_comments.len() == 1
+        );
     }
 
     #[test]
@@ -214,6 +285,47 @@ mod tests {
                 ..
             } if tail_constructors.len() == 1
         );
+        assert_type_declaration!(
+            "type Person = Person { name : String, age : Int };",
+            TypeDeclaration::WithConstructors {
+                head_constructor: Constructor {
+                    fields: Some(ConstructorFields::Named(ref braces))
+                    , ..
+                },
+                ..
+            } if braces.value.iter().count() == 2
+        );
+        assert_type_declaration!(
+            "type Shape = | Circle { radius : Float, } | Square(Float);",
+            TypeDeclaration::WithConstructors {
+                head_constructor: Constructor {
+                    fields: Some(ConstructorFields::Named(_))
+                    , ..
+                },
+                ref tail_constructors,
+                ..
+            } if matches!(tail_constructors[0].fields, Some(ConstructorFields::Positional(_)))
+        );
+        assert_type_declaration!(
+            "type User = User(name: String, age: Int);",
+            TypeDeclaration::WithConstructors {
+                head_constructor: Constructor {
+                    fields: Some(ConstructorFields::Labelled(ref parens))
+                    , ..
+                },
+                ..
+            } if parens.value.iter().map(|field| field.label.0.value.as_str()).eq(["name", "age"])
+        );
+        assert_type_declaration!(
+            "type Pair(a) = Pair(a, a);",
+            TypeDeclaration::WithConstructors {
+                head_constructor: Constructor {
+                    fields: Some(ConstructorFields::Positional(_)),
+                    ..
+                },
+                ..
+            }
+        );
         assert_type_declaration!("type Unknown;", TypeDeclaration::WithoutConstructors { .. });
         assert_type_declaration!(
             "type Foo(a, b);",
@@ -226,7 +338,21 @@ mod tests {
         assert_foreign_value_declaration!("foreign five : Int;", ForeignValueDeclaration { .. });
         assert_foreign_value_declaration!(
             "foreign map_impl : ((a) -> b, Array(a)) -> Array(b);",
-            ForeignValueDeclaration { .. }
+            ForeignValueDeclaration {
+                foreign_name: None,
+                ..
+            }
+        );
+        assert_foreign_value_declaration!(
+            r#"foreign "if" as if_impl : (Bool, a, a) -> a;"#,
+            ForeignValueDeclaration {
+                foreign_name: Some((StringToken { ref value, .. }, _)),
+                ..
+            } if value == "if"
+        );
+        assert_foreign_value_declaration!(
+            r#"foreign "default" as default_export : Int;"#,
+            ForeignValueDeclaration { ref name, .. } if name.0.value == "default_export"
         );
     }
 }
@@ -1,10 +1,16 @@
 use super::{parse_rule, Result, Rule};
 use crate::{
-    BracketsList, Colon, ElseKeyword, Expression, FalseKeyword, IfKeyword, Name, Parens,
-    ParensList, QualifiedName, QualifiedProperName, RightArrow, StringToken, ThenKeyword,
-    TrueKeyword, Type, TypeAnnotation, UnitKeyword,
+    Asterisk, BangEquals, BinOp, Braces, BracesList1, BracketsList, CloseBrace, Colon, CommaSep1,
+    Dot, DoubleAmpersand, DoubleDot, DoubleEquals, DoublePipe, ElseKeyword, EndKeyword, Equals,
+    Expression, FalseKeyword, FunctionBinder, IfKeyword, InKeyword, JsKeyword, LeftAngle,
+    LeftAngleEquals, LetKeyword, MatchArm, MatchKeyword, Minus, Name, OpenBrace, Parens,
+    ParensList, ParensList1, Pattern, Pipe, Plus, QualifiedName, QualifiedProperName, RecordField,
+    RecordPatternField, RecordPatternFields, RightAngle, RightAngleEquals, RightArrow, RightPipe,
+    Slash, StringToken, ThenKeyword, TrueKeyword, Type, TypeAnnotation, UnitKeyword,
+    ValueDeclaration, Wildcard, WithKeyword,
 };
 use pest::iterators::Pair;
+use std::iter::Peekable;
 
 impl Expression {
     /// Parse a single [Expression].
@@ -16,39 +22,75 @@ impl Expression {
     pub(super) fn from_pair(pair: Pair<Rule>) -> Self {
         match pair.as_rule() {
             Rule::expression_constructor => Self::Constructor(QualifiedProperName::from_pair(pair)),
+            Rule::expression_record_constructor => {
+                let mut inner = pair.into_inner();
+                let constructor = QualifiedProperName::from_pair(inner.next().unwrap());
+                let fields =
+                    BracesList1::list1_from_pair(inner.next().unwrap(), RecordField::from_pair);
+                Self::RecordConstructor {
+                    constructor,
+                    fields,
+                }
+            }
+            Rule::expression_record => {
+                Self::Record(BracesList1::list1_from_pair(pair, RecordField::from_pair))
+            }
             Rule::expression_variable => Self::Variable(QualifiedName::from_pair(pair)),
             Rule::expression_parens => Self::Parens(Parens::from_pair(pair, |expr_pair| {
                 Box::new(Self::from_pair(expr_pair))
             })),
-            Rule::expression_call => {
-                let mut inner = pair.into_inner();
-                let function = Box::new(Self::from_pair(inner.next().unwrap()));
-                let arguments = ParensList::list_from_pair(inner.next().unwrap(), |expr_pair| {
+            Rule::expression_tuple => {
+                Self::Tuple(ParensList1::list1_from_pair(pair, |expr_pair| {
                     Box::new(Self::from_pair(expr_pair))
-                });
-                inner.fold(
-                    Self::Call {
-                        function,
-                        arguments,
-                    },
-                    |accum, next| {
-                        let arguments = ParensList::list_from_pair(next, |expr_pair| {
+                }))
+            }
+            Rule::expression_operators => {
+                let mut inner = pair.into_inner();
+                let head = Self::from_pair(inner.next().unwrap());
+                let mut tail = Vec::new();
+                while let Some(operator_pair) = inner.next() {
+                    let operator = BinOp::from_pair(operator_pair);
+                    let operand = Self::from_pair(inner.next().unwrap());
+                    tail.push((operator, operand));
+                }
+                Self::climb_operators(head, 0, &mut tail.into_iter().peekable())
+            }
+            Rule::expression_negate => {
+                let mut inner = pair.into_inner();
+                let minus = Minus::from_pair(inner.next().unwrap());
+                let expression = Box::new(Self::from_pair(inner.next().unwrap()));
+                Self::Negate { minus, expression }
+            }
+            Rule::expression_postfix => {
+                let mut inner = pair.into_inner();
+                let head = Self::from_pair(inner.next().unwrap());
+                inner.fold(head, |accum, next| match next.as_rule() {
+                    Rule::expression_call_arguments => Self::Call {
+                        function: Box::new(accum),
+                        arguments: ParensList::list_from_pair(next, |expr_pair| {
                             Box::new(Self::from_pair(expr_pair))
-                        });
-                        Self::Call {
-                            function: Box::new(accum),
-                            arguments,
-                        }
+                        }),
                     },
-                )
+                    Rule::expression_record_access => {
+                        let mut inner = next.into_inner();
+                        let dot = Dot::from_pair(inner.next().unwrap());
+                        let label = Name::from_pair(inner.next().unwrap());
+                        Self::RecordAccess {
+                            target: Box::new(accum),
+                            dot,
+                            label,
+                        }
+                    }
+                    other => unreachable!("{:#?} {:#?}", other, next.into_inner()),
+                })
             }
             Rule::expression_function => {
                 let mut inner = pair.into_inner();
                 let parameters = ParensList::list_from_pair(inner.next().unwrap(), |param_pair| {
                     let mut param_inner = param_pair.into_inner();
-                    let name = Name::from_pair(param_inner.next().unwrap());
+                    let binder = FunctionBinder::from_pair(param_inner.next().unwrap());
                     let type_annotation = param_inner.next().map(TypeAnnotation::from_pair);
-                    (name, type_annotation)
+                    (binder, type_annotation)
                 });
                 let arrow_or_type_annotation = inner.next().unwrap();
                 if arrow_or_type_annotation.as_rule() == Rule::return_type_annotation {
@@ -91,6 +133,50 @@ impl Expression {
                     false_clause,
                 }
             }
+            Rule::expression_let => {
+                let mut inner = pair.into_inner();
+                let let_keyword = LetKeyword::from_pair(inner.next().unwrap());
+                let head_declaration = Box::new(ValueDeclaration::from_pair(inner.next().unwrap()));
+                let mut tail_declarations = Vec::new();
+                let mut next = inner.next().unwrap();
+                while next.as_rule() == Rule::expression_let_declaration {
+                    tail_declarations.push(ValueDeclaration::from_pair(next));
+                    next = inner.next().unwrap();
+                }
+                let in_keyword = InKeyword::from_pair(next);
+                let expression = Box::new(Self::from_pair(inner.next().unwrap()));
+                Self::Let {
+                    let_keyword,
+                    head_declaration,
+                    tail_declarations,
+                    in_keyword,
+                    expression,
+                }
+            }
+            Rule::expression_match => {
+                let mut inner = pair.into_inner();
+                let match_keyword = MatchKeyword::from_pair(inner.next().unwrap());
+                let expression = Box::new(Self::from_pair(inner.next().unwrap()));
+                let with_keyword = WithKeyword::from_pair(inner.next().unwrap());
+                let head_arm = Box::new(MatchArm::from_pair(inner.next().unwrap()));
+                let mut tail_arms = Vec::new();
+                let mut end_keyword = None;
+                for pair in inner {
+                    if pair.as_rule() == Rule::end_keyword {
+                        end_keyword = Some(EndKeyword::from_pair(pair));
+                    } else {
+                        tail_arms.push(MatchArm::from_pair(pair));
+                    }
+                }
+                Self::Match {
+                    match_keyword,
+                    expression,
+                    with_keyword,
+                    head_arm,
+                    tail_arms,
+                    end_keyword: end_keyword.unwrap(),
+                }
+            }
             Rule::expression_integer => Expression::Int(StringToken::from_pairs(
                 &mut pair.into_inner().next().unwrap().into_inner(),
             )),
@@ -107,6 +193,26 @@ impl Expression {
                 };
                 Expression::String(string_token)
             }
+            Rule::expression_char => {
+                let char_token =
+                    StringToken::from_pairs(&mut pair.into_inner().next().unwrap().into_inner());
+                let char_token = StringToken {
+                    // Remove the surrounding quotes
+                    value: char_token.value[1..char_token.value.len() - 1].to_owned(),
+                    ..char_token
+                };
+                Expression::Char(char_token)
+            }
+            Rule::expression_hole => {
+                let hole_token =
+                    StringToken::from_pairs(&mut pair.into_inner().next().unwrap().into_inner());
+                let hole_token = StringToken {
+                    // Remove the leading `?`
+                    value: hole_token.value[1..].to_owned(),
+                    ..hole_token
+                };
+                Expression::Hole(hole_token)
+            }
             Rule::expression_array => {
                 let elements = BracketsList::list_from_pair(pair, |expr_pair| {
                     Box::new(Self::from_pair(expr_pair))
@@ -122,11 +228,187 @@ impl Expression {
             Rule::expression_unit => {
                 Expression::Unit(UnitKeyword::from_pair(pair.into_inner().next().unwrap()))
             }
+            Rule::expression_inline_js => {
+                let mut inner = pair.into_inner();
+                let js_keyword = JsKeyword::from_pair(inner.next().unwrap());
+                let code = StringToken::from_pairs(&mut inner.next().unwrap().into_inner());
+                let code = StringToken {
+                    // Remove the surrounding quotes
+                    value: code.value[1..code.value.len() - 1].to_owned(),
+                    ..code
+                };
+                let type_annotation = TypeAnnotation::from_pair(inner.next().unwrap());
+                Expression::InlineJs {
+                    js_keyword,
+                    code,
+                    type_annotation,
+                }
+            }
+            other => unreachable!("{:#?} {:#?}", other, pair.into_inner()),
+        }
+    }
+
+    /// Precedence climbing over a flat sequence of operators and operands.
+    ///
+    /// All operators are left associative.
+    fn climb_operators(
+        mut lhs: Self,
+        min_precedence: u8,
+        tail: &mut Peekable<impl Iterator<Item = (BinOp, Self)>>,
+    ) -> Self {
+        while let Some((operator, _)) = tail.peek() {
+            let precedence = operator.precedence();
+            if precedence < min_precedence {
+                break;
+            }
+            let (operator, mut rhs) = tail.next().unwrap();
+            while let Some((next_operator, _)) = tail.peek() {
+                if next_operator.precedence() <= precedence {
+                    break;
+                }
+                rhs = Self::climb_operators(rhs, precedence + 1, tail);
+            }
+            lhs = Self::BinOp {
+                lhs: Box::new(lhs),
+                operator,
+                rhs: Box::new(rhs),
+            };
+        }
+        lhs
+    }
+}
+
+impl BinOp {
+    fn from_pair(pair: Pair<Rule>) -> Self {
+        match pair.as_rule() {
+            Rule::plus => Self::Plus(Plus::from_pair(pair)),
+            Rule::minus => Self::Minus(Minus::from_pair(pair)),
+            Rule::asterisk => Self::Times(Asterisk::from_pair(pair)),
+            Rule::slash => Self::Divide(Slash::from_pair(pair)),
+            Rule::double_equals => Self::Equals(DoubleEquals::from_pair(pair)),
+            Rule::bang_equals => Self::NotEquals(BangEquals::from_pair(pair)),
+            Rule::left_angle => Self::LessThan(LeftAngle::from_pair(pair)),
+            Rule::left_angle_equals => Self::LessThanOrEquals(LeftAngleEquals::from_pair(pair)),
+            Rule::right_angle => Self::GreaterThan(RightAngle::from_pair(pair)),
+            Rule::right_angle_equals => {
+                Self::GreaterThanOrEquals(RightAngleEquals::from_pair(pair))
+            }
+            Rule::double_ampersand => Self::And(DoubleAmpersand::from_pair(pair)),
+            Rule::double_pipe => Self::Or(DoublePipe::from_pair(pair)),
+            Rule::right_pipe => Self::Pipe(RightPipe::from_pair(pair)),
+            other => unreachable!("{:#?} {:#?}", other, pair.into_inner()),
+        }
+    }
+}
+
+impl MatchArm {
+    fn from_pair(pair: Pair<Rule>) -> Self {
+        debug_assert_eq!(pair.as_rule(), Rule::expression_match_arm);
+        let mut inner = pair.into_inner();
+        let pipe = Pipe::from_pair(inner.next().unwrap());
+        let pattern = Pattern::from_pair(inner.next().unwrap());
+        let right_arrow = RightArrow::from_pair(inner.next().unwrap());
+        let expression = Box::new(Expression::from_pair(inner.next().unwrap()));
+        Self {
+            pipe,
+            pattern,
+            right_arrow,
+            expression,
+        }
+    }
+}
+
+impl FunctionBinder {
+    fn from_pair(pair: Pair<Rule>) -> Self {
+        match pair.as_rule() {
+            Rule::name => Self::Name(Name::from_pair(pair)),
+            Rule::wildcard => Self::Wildcard(Wildcard::from_pair(pair)),
+            other => unreachable!("{:#?} {:#?}", other, pair.into_inner()),
+        }
+    }
+}
+
+impl Pattern {
+    fn from_pair(pair: Pair<Rule>) -> Self {
+        match pair.as_rule() {
+            Rule::pattern_constructor => {
+                let mut inner = pair.into_inner();
+                let constructor = QualifiedProperName::from_pairs(&mut inner);
+                let arguments = inner.next().map(|arguments_pair| {
+                    ParensList1::list1_from_pair(arguments_pair, |pattern_pair| {
+                        Box::new(Self::from_pair(pattern_pair))
+                    })
+                });
+                Self::Constructor {
+                    constructor,
+                    arguments,
+                }
+            }
+            Rule::pattern_record_constructor => {
+                let mut inner = pair.into_inner();
+                let constructor = QualifiedProperName::from_pairs(&mut inner);
+                let fields = RecordPatternFields::from_pair(inner.next().unwrap());
+                Self::RecordConstructor {
+                    constructor,
+                    fields,
+                }
+            }
+            Rule::pattern_tuple => {
+                Self::Tuple(ParensList1::list1_from_pair(pair, |pattern_pair| {
+                    Box::new(Self::from_pair(pattern_pair))
+                }))
+            }
+            Rule::pattern_variable => Self::Variable {
+                name: Name::from_pair(pair.into_inner().next().unwrap()),
+            },
+            Rule::pattern_wildcard => {
+                Self::Wildcard(Wildcard::from_pair(pair.into_inner().next().unwrap()))
+            }
             other => unreachable!("{:#?} {:#?}", other, pair.into_inner()),
         }
     }
 }
 
+impl RecordPatternFields {
+    fn from_pair(pair: Pair<Rule>) -> Braces<Self> {
+        debug_assert_eq!(pair.as_rule(), Rule::pattern_record_constructor_fields);
+        let mut inner = pair.into_inner();
+        let open_brace = OpenBrace::from_pair(inner.next().unwrap());
+        let mut rest = inner.collect::<Vec<_>>();
+        let close_brace = CloseBrace::from_pair(rest.pop().unwrap());
+        let double_dot = if rest.last().unwrap().as_rule() == Rule::double_dot {
+            Some(DoubleDot::from_pair(rest.pop().unwrap()))
+        } else {
+            None
+        };
+        let (head, tail) = rest.split_first().unwrap();
+        let fields = CommaSep1::from_pairs(head, tail, RecordPatternField::from_pair);
+        Braces {
+            open_brace,
+            value: Self {
+                fields,
+                rest: double_dot,
+            },
+            close_brace,
+        }
+    }
+}
+
+impl RecordPatternField {
+    fn from_pair(pair: Pair<Rule>) -> Self {
+        debug_assert_eq!(pair.as_rule(), Rule::pattern_record_field);
+        let mut inner = pair.into_inner();
+        let label = Name::from_pair(inner.next().unwrap());
+        let equals = Equals::from_pair(inner.next().unwrap());
+        let pattern = Box::new(Pattern::from_pair(inner.next().unwrap()));
+        Self {
+            label,
+            equals,
+            pattern,
+        }
+    }
+}
+
 impl TypeAnnotation {
     pub(super) fn from_pair(pair: Pair<Rule>) -> Self {
         let mut inner = pair.into_inner();
@@ -136,10 +418,28 @@ impl TypeAnnotation {
     }
 }
 
+impl RecordField {
+    fn from_pair(pair: Pair<Rule>) -> Self {
+        debug_assert_eq!(pair.as_rule(), Rule::expression_record_field);
+        let mut inner = pair.into_inner();
+        let label = Name::from_pair(inner.next().unwrap());
+        let equals = Equals::from_pair(inner.next().unwrap());
+        let value = Box::new(Expression::from_pair(inner.next().unwrap()));
+        Self {
+            label,
+            equals,
+            value,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::test_macros::*;
-    use crate::{Brackets, CommaSep1, Expression, Parens, StringToken};
+    use crate::{
+        BinOp, Braces, Brackets, CommaSep1, Expression, MatchArm, Parens, Pattern,
+        RecordPatternFields, StringToken,
+    };
 
     #[test]
     fn it_parses_constructors() {
@@ -147,6 +447,80 @@ mod tests {
         assert_parses!("Some_Module.R2d2", Expression::Constructor(_));
     }
 
+    #[test]
+    fn it_parses_record_constructors() {
+        assert_parses!(
+            r#"Person { name = "Jane", age = 42 }"#,
+            Expression::RecordConstructor { fields, .. } if fields.value.iter().count() == 2
+        );
+        assert_parses!(
+            "Some.Thing { x = Just(5), }",
+            Expression::RecordConstructor { fields, .. } if fields.value.trailing_comma.is_some()
+        );
+        assert_parses!("f(Pair { x = 1, y = 2 })", Expression::Call { .. });
+    }
+
+    #[test]
+    fn it_parses_records() {
+        assert_parses!(
+            r#"{ name = "Jane", age = 42 }"#,
+            Expression::Record(fields) if fields.value.iter().count() == 2
+        );
+        assert_parses!(
+            "{ x = { y = 5 }, }",
+            Expression::Record(fields) if fields.value.trailing_comma.is_some()
+        );
+        assert_parses!("f({ x = 1 })", Expression::Call { .. });
+    }
+
+    #[test]
+    fn it_parses_record_accesses() {
+        assert_parses!(
+            "person.name",
+            Expression::RecordAccess {
+                target: box Expression::Variable(_),
+                ..
+            }
+        );
+        assert_parses!(
+            "a.b.c",
+            Expression::RecordAccess {
+                target: box Expression::RecordAccess {
+                    target: box Expression::Variable(_),
+                    ..
+                },
+                label,
+                ..
+            } if label.0.value == "c"
+        );
+        assert_parses!(
+            "Module.value.field",
+            Expression::RecordAccess {
+                target: box Expression::Variable(_),
+                ..
+            }
+        );
+        assert_parses!(
+            "f(x).a(y)",
+            Expression::Call {
+                function: box Expression::RecordAccess {
+                    target: box Expression::Call { .. },
+                    ..
+                },
+                ..
+            }
+        );
+        assert_parses!("{ a = 5 }.a", Expression::RecordAccess { .. });
+        assert_parses!(
+            "a.b + c.d",
+            Expression::BinOp {
+                lhs: box Expression::RecordAccess { .. },
+                rhs: box Expression::RecordAccess { .. },
+                ..
+            }
+        );
+    }
+
     #[test]
     fn it_parses_variables() {
         assert_parses!("a__Abc12_", Expression::Variable(_));
@@ -175,6 +549,41 @@ mod tests {
             "--leading\n--leading0\n10 --trailing",
             Expression::Int(StringToken { value, .. }) if value == "10"
         );
+        assert_parses!(
+            "0xFF",
+            Expression::Int(StringToken { value, .. }) if value == "0xFF"
+        );
+        assert_parses!(
+            "0o7_55",
+            Expression::Int(StringToken { value, .. }) if value == "0o7_55"
+        );
+        assert_parses!(
+            "0b1010_1010",
+            Expression::Int(StringToken { value, .. }) if value == "0b1010_1010"
+        );
+        assert_parses!(
+            "0x_dead_BEEF",
+            Expression::Int(StringToken { value, .. }) if value == "0x_dead_BEEF"
+        );
+    }
+
+    #[test]
+    fn it_rejects_bad_integers() {
+        let error_span = |input| {
+            let err = crate::Expression::parse(input).unwrap_err();
+            (err.span.start_offset, err.span.end_offset)
+        };
+        // No digits
+        assert_eq!(error_span("0x"), (0, 2));
+        assert_eq!(error_span("0b__"), (0, 4));
+        // Trailing underscores
+        assert_eq!(error_span("1_000_"), (5, 6));
+        assert_eq!(error_span("0xFF__"), (4, 6));
+        // Digits that don't belong to the radix
+        assert_eq!(error_span("0b102"), (4, 5));
+        assert_eq!(error_span("0o8"), (2, 3));
+        assert_eq!(error_span("0xFG"), (3, 4));
+        assert_eq!(error_span("[1, 2, 0x]"), (7, 9));
     }
 
     #[test]
@@ -215,11 +624,19 @@ mod tests {
             r#" " padded " "#,
             Expression::String(StringToken { value, .. }) if value == " padded "
         );
-        // FIXME escape sequences
-        // assert_parses!(
-        //     r#" "\n\r\t\"\\" "#,
-        //     Expression::Literal(Literal::String(StringToken { value, .. })) if value == r#"\n\r\t\"\\"#
-        // );
+        // Escape sequences are kept as they appear in the source
+        assert_parses!(
+            r#" "\n\r\t\"\\" "#,
+            Expression::String(StringToken { value, .. }) if value == r#"\n\r\t\"\\"#
+        );
+        assert_parses!(
+            r#" "\u{1F600} \"quoted\"" "#,
+            Expression::String(StringToken { value, .. }) if value == r#"\u{1F600} \"quoted\""#
+        );
+        let err = crate::Expression::parse(r#" "bad \q escape" "#).unwrap_err();
+        assert_eq!((err.span.start_offset, err.span.end_offset), (6, 8));
+        let err = crate::Expression::parse(r#" "\u{110000}" "#).unwrap_err();
+        assert_eq!((err.span.start_offset, err.span.end_offset), (2, 12));
         assert_parses!(
             r#" "Hello, 世界" "#,
             Expression::String(StringToken { value, .. }) if value == "Hello, 世界"
@@ -230,6 +647,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn it_parses_chars() {
+        assert_parses!(
+            "'a'",
+            Expression::Char(StringToken { value, .. }) if value == "a"
+        );
+        assert_parses!(
+            "'世'",
+            Expression::Char(StringToken { value, .. }) if value == "世"
+        );
+        assert_parses!(
+            "'\"'",
+            Expression::Char(StringToken { value, .. }) if value == "\""
+        );
+        assert_parses!(
+            r"'\n'",
+            Expression::Char(StringToken { value, .. }) if value == r"\n"
+        );
+        assert_parses!(
+            r"'\''",
+            Expression::Char(StringToken { value, .. }) if value == r"\'"
+        );
+        assert_parses!(
+            r"'\\'",
+            Expression::Char(StringToken { value, .. }) if value == r"\\"
+        );
+        assert_parses!(
+            r"'\u{1F600}'",
+            Expression::Char(StringToken { value, .. }) if value == r"\u{1F600}"
+        );
+        assert!(crate::Expression::parse("''").is_err());
+        assert!(crate::Expression::parse("'ab'").is_err());
+        assert!(crate::Expression::parse("'''").is_err());
+        assert!(crate::Expression::parse(r"'\q'").is_err());
+        assert!(crate::Expression::parse(r"'\u{1234567}'").is_err());
+    }
+
     #[test]
     fn it_parses_arrays() {
         assert_parses!("[]", Expression::Array(Brackets { value: None, .. }));
@@ -268,6 +722,35 @@ mod tests {
         assert_parses!("unit", Expression::Unit(_));
     }
 
+    #[test]
+    fn it_parses_holes() {
+        assert_parses!(
+            "?help",
+            Expression::Hole(StringToken { value, .. }) if value == "help"
+        );
+        assert_parses!("f(?x, 5)", Expression::Call { .. });
+        assert_parses!("?x + 1", Expression::BinOp { .. });
+        assert!(crate::Expression::parse("?").is_err());
+        assert!(crate::Expression::parse("? help").is_err());
+        assert!(crate::Expression::parse("?Help").is_err());
+    }
+
+    #[test]
+    fn it_parses_inline_js() {
+        assert_parses!(
+            r#"js "Date.now()" : Int"#,
+            Expression::InlineJs {
+                code: StringToken { value, .. },
+                ..
+            } if value == "Date.now()"
+        );
+        assert_parses!(r#"js "(a) => a" : (a) -> a"#, Expression::InlineJs { .. });
+        assert_parses!(r#"f(js "1" : Int, js)"#, Expression::Call { .. });
+        // `js` is only a keyword when it's followed by a string
+        assert_parses!("js", Expression::Variable(_));
+        assert_parses!("jsx", Expression::Variable(_));
+    }
+
     #[test]
     fn it_parses_ifs() {
         assert_parses!("if true then 1 else 0", Expression::If { .. });
@@ -281,6 +764,107 @@ mod tests {
         );
     }
 
+    #[test]
+    fn it_parses_lets() {
+        assert_parses!(
+            "let five = 5; in five",
+            Expression::Let { tail_declarations, .. } if tail_declarations.is_empty()
+        );
+        assert_parses!(
+            "let five: Int = 5; six = 6; in [five, six]",
+            Expression::Let { tail_declarations, .. } if tail_declarations.len() == 1
+        );
+        assert_parses!(
+            "let x = let y = 5; in y; in let z = x; in z",
+            Expression::Let { .. }
+        );
+        assert_parses!("letter", Expression::Variable(_));
+        assert_parses!("f(inner)", Expression::Call { .. });
+    }
+
+    #[test]
+    fn it_parses_matches() {
+        assert_parses!(
+            "match x with | y -> y end",
+            Expression::Match { tail_arms, .. } if tail_arms.is_empty()
+        );
+        assert_parses!(
+            "match maybe with | Just(x) -> x | Nothing -> 0 end",
+            Expression::Match { tail_arms, .. } if tail_arms.len() == 1
+        );
+        assert_parses!(
+            "match f(x) with | Maybe.Just(Pair(a, _),) -> a | _ -> 0 end",
+            Expression::Match {
+                head_arm: box MatchArm {
+                    pattern: Pattern::Constructor {
+                        arguments: Some(Parens { value: arguments, .. }),
+                        ..
+                    },
+                    ..
+                },
+                ..
+            } if arguments.trailing_comma.is_some()
+        );
+        assert_parses!(
+            "match x with\n-- comment\n| _ -> unit\nend",
+            Expression::Match {
+                head_arm: box MatchArm {
+                    pattern: Pattern::Wildcard(_),
+                    ..
+                },
+                ..
+            }
+        );
+        assert_parses!(
+            "match x with | Just(y) -> match y with | z -> z end | Nothing -> 0 end",
+            Expression::Match { tail_arms, .. } if tail_arms.len() == 1
+        );
+        assert_parses!("matches", Expression::Variable(_));
+        assert_parses!("match_with(x)", Expression::Call { .. });
+        assert_parses!("endings", Expression::Variable(_));
+    }
+
+    #[test]
+    fn it_parses_record_constructor_patterns() {
+        assert_parses!(
+            "match p with | Person { name = n, age = a } -> n end",
+            Expression::Match {
+                head_arm: box MatchArm {
+                    pattern: Pattern::RecordConstructor {
+                        fields: Braces { value: RecordPatternFields { fields, rest: None }, .. },
+                        ..
+                    },
+                    ..
+                },
+                ..
+            } if fields.tail.len() == 1
+        );
+        assert_parses!(
+            "match p with | Person { name = Just(n), .. } -> n end",
+            Expression::Match {
+                head_arm: box MatchArm {
+                    pattern: Pattern::RecordConstructor {
+                        fields: Braces { value: RecordPatternFields { fields, rest: Some(_) }, .. },
+                        ..
+                    },
+                    ..
+                },
+                ..
+            } if fields.trailing_comma.is_some()
+        );
+        assert_parses!(
+            "match p with | People.Person { name = _, } -> unit end",
+            Expression::Match { .. }
+        );
+        assert!(crate::Expression::parse("match p with | Person { .. } -> unit end").is_err());
+        assert!(
+            crate::Expression::parse("match p with | Person { name = n .. } -> n end").is_err()
+        );
+        assert!(
+            crate::Expression::parse("match p with | Person { .., name = n } -> n end").is_err()
+        );
+    }
+
     #[test]
     fn it_parses_functions() {
         assert_parses!("() -> x", Expression::Function { .. });
@@ -291,6 +875,8 @@ mod tests {
             Expression::Function { .. }
         );
         assert_parses!("((x) -> x)(x)", Expression::Call { .. });
+        assert_parses!("(_, x) -> x", Expression::Function { .. });
+        assert_parses!("(_: Int, _) -> 5", Expression::Function { .. });
     }
 
     #[test]
@@ -366,6 +952,276 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn it_parses_tuples() {
+        assert_parses!(
+            "(a, b)",
+            Expression::Tuple(Parens { value: elements, .. }) if elements.iter().count() == 2
+        );
+        assert_parses!(
+            "(a,)",
+            Expression::Tuple(Parens { value: elements, .. })
+                if elements.iter().count() == 1 && elements.trailing_comma.is_some()
+        );
+        assert_parses!(
+            "(\n1,\n(2, 3),\n[4],\n)",
+            Expression::Tuple(Parens { value: elements, .. })
+                if matches!(elements.tail[0].1, box Expression::Tuple(_))
+        );
+        assert_parses!("((a, b))", Expression::Parens(_));
+        assert_parses!("(a, b) -> a", Expression::Function { .. });
+        assert_parses!("f((a, b))", Expression::Call { .. });
+        assert_parses!(
+            "match pair with | (Just(a), _) -> a | (_, b,) -> b end",
+            Expression::Match {
+                head_arm: box MatchArm {
+                    pattern: Pattern::Tuple(_),
+                    ..
+                },
+                ..
+            }
+        );
+    }
+
+    #[test]
+    fn it_parses_negation() {
+        assert_parses!(
+            "-5",
+            Expression::Negate {
+                expression: box Expression::Int(_),
+                ..
+            }
+        );
+        assert_parses!(
+            "- -x",
+            Expression::Negate {
+                expression: box Expression::Negate {
+                    expression: box Expression::Variable(_),
+                    ..
+                },
+                ..
+            }
+        );
+        assert_parses!(
+            "-f(x).y",
+            Expression::Negate {
+                expression: box Expression::RecordAccess { .. },
+                ..
+            }
+        );
+        assert_parses!(
+            "a - -b * c",
+            Expression::BinOp {
+                operator: BinOp::Minus(_),
+                rhs: box Expression::BinOp {
+                    lhs: box Expression::Negate { .. },
+                    operator: BinOp::Times(_),
+                    ..
+                },
+                ..
+            }
+        );
+        assert_parses!("-- comment\n-x", Expression::Negate { .. });
+        assert!(crate::Expression::parse("--x").is_err());
+    }
+
+    #[test]
+    fn it_parses_binary_operators() {
+        assert_parses!(
+            "1 + 2",
+            Expression::BinOp {
+                operator: BinOp::Plus(_),
+                ..
+            }
+        );
+        assert_parses!(
+            "1 - 2 - 3",
+            Expression::BinOp {
+                lhs: box Expression::BinOp {
+                    operator: BinOp::Minus(_),
+                    ..
+                },
+                operator: BinOp::Minus(_),
+                rhs: box Expression::Int(_),
+            }
+        );
+        assert_parses!(
+            "1 + 2 * 3",
+            Expression::BinOp {
+                lhs: box Expression::Int(_),
+                operator: BinOp::Plus(_),
+                rhs: box Expression::BinOp {
+                    operator: BinOp::Times(_),
+                    ..
+                },
+            }
+        );
+        assert_parses!(
+            "1 * 2 / 3 - 4",
+            Expression::BinOp {
+                lhs: box Expression::BinOp {
+                    lhs: box Expression::BinOp {
+                        operator: BinOp::Times(_),
+                        ..
+                    },
+                    operator: BinOp::Divide(_),
+                    ..
+                },
+                operator: BinOp::Minus(_),
+                rhs: box Expression::Int(_),
+            }
+        );
+        assert_parses!(
+            "(1 + 2) * f(x)",
+            Expression::BinOp {
+                lhs: box Expression::Parens(_),
+                operator: BinOp::Times(_),
+                rhs: box Expression::Call { .. },
+            }
+        );
+        assert_parses!("(a) -> a + 1", Expression::Function { .. });
+    }
+
+    #[test]
+    fn it_parses_comparison_operators() {
+        assert_parses!(
+            "1 + 2 == 3",
+            Expression::BinOp {
+                lhs: box Expression::BinOp {
+                    operator: BinOp::Plus(_),
+                    ..
+                },
+                operator: BinOp::Equals(_),
+                rhs: box Expression::Int(_),
+            }
+        );
+        assert_parses!(
+            "a != b",
+            Expression::BinOp {
+                operator: BinOp::NotEquals(_),
+                ..
+            }
+        );
+        assert_parses!(
+            "a < b",
+            Expression::BinOp {
+                operator: BinOp::LessThan(_),
+                ..
+            }
+        );
+        assert_parses!(
+            "a <= b",
+            Expression::BinOp {
+                operator: BinOp::LessThanOrEquals(_),
+                ..
+            }
+        );
+        assert_parses!(
+            "a > b",
+            Expression::BinOp {
+                operator: BinOp::GreaterThan(_),
+                ..
+            }
+        );
+        assert_parses!(
+            "a >= b * 2",
+            Expression::BinOp {
+                operator: BinOp::GreaterThanOrEquals(_),
+                rhs: box Expression::BinOp {
+                    operator: BinOp::Times(_),
+                    ..
+                },
+                ..
+            }
+        );
+    }
+
+    #[test]
+    fn it_parses_boolean_operators() {
+        assert_parses!(
+            "a && b",
+            Expression::BinOp {
+                operator: BinOp::And(_),
+                ..
+            }
+        );
+        assert_parses!(
+            "a || b && c",
+            Expression::BinOp {
+                lhs: box Expression::Variable(_),
+                operator: BinOp::Or(_),
+                rhs: box Expression::BinOp {
+                    operator: BinOp::And(_),
+                    ..
+                },
+            }
+        );
+        assert_parses!(
+            "a == 1 && b < 2",
+            Expression::BinOp {
+                lhs: box Expression::BinOp {
+                    operator: BinOp::Equals(_),
+                    ..
+                },
+                operator: BinOp::And(_),
+                rhs: box Expression::BinOp {
+                    operator: BinOp::LessThan(_),
+                    ..
+                },
+            }
+        );
+        assert_parses!(
+            "a && b && c",
+            Expression::BinOp {
+                lhs: box Expression::BinOp {
+                    operator: BinOp::And(_),
+                    ..
+                },
+                operator: BinOp::And(_),
+                rhs: box Expression::Variable(_),
+            }
+        );
+        assert_parses!(
+            "match x with | A -> a || b | B -> c end",
+            Expression::Match { .. }
+        );
+    }
+
+    #[test]
+    fn it_parses_pipes() {
+        assert_parses!(
+            "x |> f |> g",
+            Expression::BinOp {
+                lhs: box Expression::BinOp {
+                    lhs: box Expression::Variable(_),
+                    operator: BinOp::Pipe(_),
+                    rhs: box Expression::Variable(_),
+                },
+                operator: BinOp::Pipe(_),
+                rhs: box Expression::Variable(_),
+            }
+        );
+        assert_parses!(
+            "a || b |> f",
+            Expression::BinOp {
+                lhs: box Expression::BinOp {
+                    operator: BinOp::Or(_),
+                    ..
+                },
+                operator: BinOp::Pipe(_),
+                rhs: box Expression::Variable(_),
+            }
+        );
+        assert_parses!(
+            "x |> f(y)",
+            Expression::BinOp {
+                operator: BinOp::Pipe(_),
+                rhs: box Expression::Call { .. },
+                ..
+            }
+        );
+    }
 }
 
 #[cfg(test)]
@@ -1,6 +1,7 @@
 #![allow(missing_docs)]
 
-use pest::{error::Error, iterators::Pairs, Parser};
+use super::{literal::check_literals, Result};
+use pest::{iterators::Pairs, Parser};
 use pest_derive::Parser;
 
 /// The ditto language grammar.
@@ -8,6 +9,8 @@ use pest_derive::Parser;
 #[grammar = "parser/grammar.pest"]
 struct Grammar;
 
-pub(super) fn parse_rule(rule: Rule, input: &str) -> Result<Pairs<Rule>, Error<Rule>> {
-    Grammar::parse(rule, input)
+pub(super) fn parse_rule(rule: Rule, input: &str) -> Result<Pairs<Rule>> {
+    let pairs = Grammar::parse(rule, input)?;
+    check_literals(pairs.clone())?;
+    Ok(pairs)
 }
@@ -0,0 +1,66 @@
+use super::{ParseError, Result, Rule};
+use crate::{unescape_string, Span};
+use pest::iterators::{Pair, Pairs};
+
+/// The grammar is deliberately lenient about the contents of literals,
+/// so check them here where we can point at exactly what's wrong.
+pub(super) fn check_literals(pairs: Pairs<Rule>) -> Result<()> {
+    for pair in pairs.flatten() {
+        match pair.as_rule() {
+            Rule::STRING => check_string(pair)?,
+            Rule::INTEGER => check_integer(pair)?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn check_string(pair: Pair<Rule>) -> Result<()> {
+    let string = pair.as_str();
+    // Skip the opening quote
+    let offset = pair.as_span().start() + 1;
+    if let Err(range) = unescape_string(&string[1..string.len() - 1]) {
+        return Err(error(
+            offset + range.start,
+            offset + range.end,
+            &[r#"\""#, r"\\", r"\n", r"\t", r"\r", r"\u{...}"],
+        ));
+    }
+    Ok(())
+}
+
+fn check_integer(pair: Pair<Rule>) -> Result<()> {
+    let integer = pair.as_str();
+    let start = pair.as_span().start();
+    let (prefix_len, radix, expected) = match integer.get(..2) {
+        Some("0x") => (2, 16, "hexadecimal digit"),
+        Some("0o") => (2, 8, "octal digit"),
+        Some("0b") => (2, 2, "binary digit"),
+        _ => (0, 10, "digit"),
+    };
+    let digits = &integer[prefix_len..];
+    if let Some((i, _)) = digits
+        .char_indices()
+        .find(|(_, c)| *c != '_' && !c.is_digit(radix))
+    {
+        let offset = start + prefix_len + i;
+        return Err(error(offset, offset + 1, &[expected]));
+    }
+    if digits.chars().all(|c| c == '_') {
+        return Err(error(start, start + integer.len(), &[expected]));
+    }
+    let trimmed = digits.trim_end_matches('_');
+    if trimmed.len() < digits.len() {
+        let offset = start + prefix_len + trimmed.len();
+        return Err(error(offset, start + integer.len(), &[expected]));
+    }
+    Ok(())
+}
+
+fn error(start_offset: usize, end_offset: usize, expected: &[&str]) -> ParseError {
+    ParseError {
+        span: Span::new(start_offset, end_offset),
+        positives: expected.iter().map(|s| s.to_string()).collect(),
+        negatives: Vec::new(),
+    }
+}
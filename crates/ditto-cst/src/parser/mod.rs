@@ -1,6 +1,7 @@
 mod declaration;
 mod expression;
 mod grammar;
+mod literal;
 mod module;
 mod name;
 mod result;
@@ -1,9 +1,9 @@
 use super::{parse_rule, Result, Rule};
 use crate::{
-    AsKeyword, Comment, Declaration, DoubleDot, Everything, Export, Exports, ExportsKeyword,
-    ForeignValueDeclaration, Header, Import, ImportKeyword, ImportLine, ImportList, Module,
-    ModuleKeyword, ModuleName, Name, PackageName, Parens, ParensList1, ProperName, Semicolon,
-    TypeDeclaration, ValueDeclaration,
+    AsKeyword, Attribute, CloseParen, Comment, Declaration, DoubleDot, Everything, Export, Exports,
+    ExportsKeyword, ForeignValueDeclaration, Header, Import, ImportKeyword, ImportLine, ImportList,
+    Module, ModuleKeyword, ModuleName, Name, OpenParen, PackageName, Parens, ParensList1,
+    ProperName, Semicolon, TypeDeclaration, ValueDeclaration,
 };
 use pest::iterators::Pair;
 
@@ -56,12 +56,19 @@ impl Header {
 
     pub(super) fn from_pair(pair: Pair<Rule>) -> Self {
         let mut inner = pair.into_inner();
-        let module_keyword = ModuleKeyword::from_pair(inner.next().unwrap());
+        let mut attributes = Vec::new();
+        let mut next = inner.next().unwrap();
+        while next.as_rule() == Rule::attribute {
+            attributes.push(Attribute::from_pair(next));
+            next = inner.next().unwrap();
+        }
+        let module_keyword = ModuleKeyword::from_pair(next);
         let module_name = ModuleName::from_pair(inner.next().unwrap());
         let exports_keyword = ExportsKeyword::from_pair(inner.next().unwrap());
         let exports = Exports::from_pair(inner.next().unwrap());
         let semicolon = Semicolon::from_pair(inner.next().unwrap());
         Self {
+            attributes,
             module_keyword,
             module_name,
             exports_keyword,
@@ -77,6 +84,14 @@ impl Exports {
         let next = inner.next().unwrap();
         match next.as_rule() {
             Rule::everything => Self::Everything(everything_from_pair(next)),
+            Rule::module_exports_nothing => {
+                let mut inner = next.into_inner();
+                Self::Nothing(Parens {
+                    open_paren: OpenParen::from_pair(inner.next().unwrap()),
+                    value: (),
+                    close_paren: CloseParen::from_pair(inner.next().unwrap()),
+                })
+            }
             Rule::module_exports_list => Self::List(Box::new(ParensList1::list1_from_pair(
                 next,
                 Export::from_pair,
@@ -96,6 +111,12 @@ impl Export {
                 let everything = inner.next().map(everything_from_pair);
                 Self::Type(proper_name, everything)
             }
+            Rule::module_exports_list_item_module => {
+                let mut inner = pair.into_inner();
+                let module_keyword = ModuleKeyword::from_pair(inner.next().unwrap());
+                let module_name = ModuleName::from_pair(inner.next().unwrap());
+                Self::Module(module_keyword, module_name)
+            }
             other => unreachable!("{:?}", other),
         }
     }
@@ -242,6 +263,11 @@ mod tests {
             module_name = "Foo",
             exports = Exports::Everything(_)
         );
+        assert_module_header!(
+            "module Main exports ();",
+            module_name = "Main",
+            exports = Exports::Nothing(_)
+        );
         assert_module_header!(
             "module Bar.Baz exports (foo);",
             module_name = "Bar.Baz",
@@ -266,6 +292,51 @@ mod tests {
         );
     }
 
+    #[test]
+    fn it_parses_module_header_attributes() {
+        let header = crate::Header::parse("@no_optimize module Foo exports (..);").unwrap();
+        assert!(header.has_attribute("no_optimize"));
+        assert_eq!(header.module_name.render(), "Foo");
+
+        let header =
+            crate::Header::parse("-- docs\n@no_optimize\nmodule Foo exports (..);").unwrap();
+        assert_eq!(header.attributes[0].at.0.leading_comments.len(), 1);
+        assert!(header.module_keyword.0.leading_comments.is_empty());
+    }
+
+    #[test]
+    fn it_parses_module_reexports() {
+        assert_module_header!(
+            "module Prelude exports (module Data.Maybe, five, module  -- comment\n Result);",
+            module_name = "Prelude",
+            export_list = [
+                ExportPattern::Module,
+                ExportPattern::Value("five"),
+                ExportPattern::Module
+            ]
+        );
+        let header = crate::Header::parse("module Prelude exports (module Data.Maybe);").unwrap();
+        if let Exports::List(box parens_list) = header.exports {
+            assert!(matches!(
+                parens_list.value.as_vec().as_slice(),
+                [crate::Export::Module(_, module_name)] if module_name.render() == "Data.Maybe"
+            ));
+        } else {
+            panic!("expected export list")
+        }
+
+        // `module` is still a valid name
+        assert_module_header!(
+            "module Foo exports (module, moduleFoo, Foo);",
+            module_name = "Foo",
+            export_list = [
+                ExportPattern::Value("module"),
+                ExportPattern::Value("moduleFoo"),
+                ExportPattern::AbstractType("Foo")
+            ]
+        );
+    }
+
     #[test]
     fn it_parses_imports() {
         assert_import!(
@@ -421,6 +492,7 @@ mod test_macros {
         Value(&'a str),
         PublicType(&'a str),
         AbstractType(&'a str),
+        Module,
     }
     macro_rules! assert_module_header {
         ($expr:expr, module_name = $module_name:expr, exports = $exports:pat_param) => {{
@@ -446,6 +518,7 @@ mod test_macros {
                                     ExportPattern::AbstractType(proper_name.0.value.as_str()),
                                 $crate::Export::Type(proper_name, Some(_)) =>
                                     ExportPattern::PublicType(proper_name.0.value.as_str()),
+                                $crate::Export::Module(..) => ExportPattern::Module,
                             })
                             .collect::<Vec<_>>()
                             .as_slice(),
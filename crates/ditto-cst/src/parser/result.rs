@@ -1,6 +1,7 @@
 use super::Rule;
 use crate::Span;
 use miette::{Diagnostic, NamedSource, SourceSpan};
+use std::sync::Arc;
 use thiserror::Error;
 
 use pest::error::{Error, ErrorVariant, InputLocation};
@@ -27,14 +28,10 @@ impl From<Error<Rule>> for ParseError {
                 negatives,
             } => Self {
                 span: match error.location {
-                    InputLocation::Pos(offset) => Span {
-                        start_offset: offset,
-                        end_offset: offset,
-                    },
-                    InputLocation::Span((start_offset, end_offset)) => Span {
-                        start_offset,
-                        end_offset,
-                    },
+                    InputLocation::Pos(offset) => Span::new(offset, offset),
+                    InputLocation::Span((start_offset, end_offset)) => {
+                        Span::new(start_offset, end_offset)
+                    }
                 },
                 // TODO: process these to make them more useful
                 positives: positives
@@ -131,10 +128,13 @@ pub enum ParseErrorReport {
 
 impl ParseError {
     /// Create a pretty error report.
-    pub fn into_report(self, name: impl AsRef<str>, input: String) -> ParseErrorReport {
+    ///
+    /// The `input` is shared, so reporting many diagnostics for the same file
+    /// doesn't need to copy the source each time.
+    pub fn into_report(self, name: impl AsRef<str>, input: Arc<String>) -> ParseErrorReport {
         let input = if input.is_empty() {
             // fixes miette panic: get_lines should always return at least one line?
-            NamedSource::new(name, String::from("\n"))
+            NamedSource::new(name, Arc::new(String::from("\n")))
         } else {
             NamedSource::new(name, input)
         };
@@ -174,6 +174,7 @@ impl ParseError {
 #[cfg(test)]
 mod tests {
     use crate::Module;
+    use std::sync::Arc;
     #[snapshot_test::snapshot_lf(
         input = "golden-tests/parse-errors/(.*).ditto",
         output = "golden-tests/parse-errors/${1}.error"
@@ -181,7 +182,7 @@ mod tests {
     fn golden(input: &str) -> String {
         let parse_error = Module::parse(input)
             .unwrap_err()
-            .into_report("golden", input.to_string());
+            .into_report("golden", Arc::new(input.to_owned()));
         render_diagnostic(&parse_error)
     }
 
@@ -1,7 +1,7 @@
 use super::Rule;
 use crate::{
-    BracketsList, CloseBracket, CloseParen, Comma, CommaSep1, OpenBracket, OpenParen, Parens,
-    ParensList, ParensList1,
+    BracesList1, BracketsList, CloseBrace, CloseBracket, CloseParen, Comma, CommaSep1, OpenBrace,
+    OpenBracket, OpenParen, Parens, ParensList, ParensList1,
 };
 use itertools::{EitherOrBoth, Itertools};
 use pest::iterators::Pair;
@@ -100,6 +100,29 @@ impl<T> BracketsList<T> {
     }
 }
 
+impl<T> BracesList1<T> {
+    pub(super) fn list1_from_pair(
+        pair: Pair<Rule>,
+        element_from_pair: impl Fn(Pair<Rule>) -> T,
+    ) -> Self {
+        let mut inner = pair.into_inner();
+        let open_brace = OpenBrace::from_pair(inner.next().unwrap());
+        let mut rest = inner.collect::<Vec<_>>();
+        let close_brace = CloseBrace::from_pair(rest.pop().unwrap());
+        match rest.split_first() {
+            None => unreachable!(),
+            Some((head, tail)) => {
+                let value = CommaSep1::from_pairs(head, tail, element_from_pair);
+                Self {
+                    open_brace,
+                    value,
+                    close_brace,
+                }
+            }
+        }
+    }
+}
+
 impl<T> CommaSep1<T> {
     pub(super) fn from_pairs(
         head: &Pair<Rule>,
@@ -28,12 +28,15 @@ impl_from_pair!(Colon, rule = Rule::colon);
 impl_from_pair!(Semicolon, rule = Rule::semicolon);
 impl_from_pair!(OpenBracket, rule = Rule::open_bracket);
 impl_from_pair!(CloseBracket, rule = Rule::close_bracket);
+impl_from_pair!(OpenBrace, rule = Rule::open_brace);
+impl_from_pair!(CloseBrace, rule = Rule::close_brace);
 impl_from_pair!(ImportKeyword, rule = Rule::import_keyword);
 impl_from_pair!(AsKeyword, rule = Rule::as_keyword);
 impl_from_pair!(DoubleDot, rule = Rule::double_dot);
 impl_from_pair!(ModuleKeyword, rule = Rule::module_keyword);
 impl_from_pair!(ExportsKeyword, rule = Rule::exports_keyword);
 impl_from_pair!(Equals, rule = Rule::equals);
+impl_from_pair!(At, rule = Rule::at);
 impl_from_pair!(UnitKeyword, rule = Rule::unit_keyword);
 impl_from_pair!(TrueKeyword, rule = Rule::true_keyword);
 impl_from_pair!(FalseKeyword, rule = Rule::false_keyword);
@@ -42,7 +45,27 @@ impl_from_pair!(ThenKeyword, rule = Rule::then_keyword);
 impl_from_pair!(ElseKeyword, rule = Rule::else_keyword);
 impl_from_pair!(TypeKeyword, rule = Rule::type_keyword);
 impl_from_pair!(ForeignKeyword, rule = Rule::foreign_keyword);
+impl_from_pair!(JsKeyword, rule = Rule::js_keyword);
+impl_from_pair!(LetKeyword, rule = Rule::let_keyword);
+impl_from_pair!(InKeyword, rule = Rule::in_keyword);
+impl_from_pair!(MatchKeyword, rule = Rule::match_keyword);
+impl_from_pair!(WithKeyword, rule = Rule::with_keyword);
+impl_from_pair!(EndKeyword, rule = Rule::end_keyword);
+impl_from_pair!(Wildcard, rule = Rule::wildcard);
 impl_from_pair!(Pipe, rule = Rule::pipe);
+impl_from_pair!(Plus, rule = Rule::plus);
+impl_from_pair!(Minus, rule = Rule::minus);
+impl_from_pair!(Asterisk, rule = Rule::asterisk);
+impl_from_pair!(Slash, rule = Rule::slash);
+impl_from_pair!(DoubleEquals, rule = Rule::double_equals);
+impl_from_pair!(BangEquals, rule = Rule::bang_equals);
+impl_from_pair!(LeftAngle, rule = Rule::left_angle);
+impl_from_pair!(LeftAngleEquals, rule = Rule::left_angle_equals);
+impl_from_pair!(RightAngle, rule = Rule::right_angle);
+impl_from_pair!(RightAngleEquals, rule = Rule::right_angle_equals);
+impl_from_pair!(DoubleAmpersand, rule = Rule::double_ampersand);
+impl_from_pair!(DoublePipe, rule = Rule::double_pipe);
+impl_from_pair!(RightPipe, rule = Rule::right_pipe);
 
 impl StringToken {
     pub(super) fn from_pairs(pairs: &mut Pairs<Rule>) -> Self {
@@ -59,10 +82,7 @@ impl StringToken {
                     Comment(pair.as_str().to_owned())
                 });
                 return Self {
-                    span: Span {
-                        start_offset: source_span.start(),
-                        end_offset: source_span.end(),
-                    },
+                    span: Span::new(source_span.start(), source_span.end()),
                     leading_comments,
                     trailing_comment,
                     value,
@@ -87,10 +107,7 @@ impl EmptyToken {
                     Comment(pair.as_str().to_owned())
                 });
                 return Self {
-                    span: Span {
-                        start_offset: source_span.start(),
-                        end_offset: source_span.end(),
-                    },
+                    span: Span::new(source_span.start(), source_span.end()),
                     leading_comments,
                     trailing_comment,
                     value: (),
@@ -1,6 +1,7 @@
 use super::{parse_rule, Result, Rule};
 use crate::{
-    Name, Parens, ParensList, ParensList1, QualifiedProperName, RightArrow, Type, TypeCallFunction,
+    BracesList1, Name, Parens, ParensList, ParensList1, QualifiedProperName, RecordTypeField,
+    RightArrow, Type, TypeAnnotation, TypeCallFunction, Wildcard,
 };
 use pest::iterators::Pair;
 
@@ -17,9 +18,15 @@ impl Type {
             Rule::type_variable => {
                 Self::Variable(Name::from_pair(pair.into_inner().next().unwrap()))
             }
+            Rule::type_wildcard => {
+                Self::Wildcard(Wildcard::from_pair(pair.into_inner().next().unwrap()))
+            }
             Rule::type_parens => Self::Parens(Parens::from_pair(pair, |type_pair| {
                 Box::new(Self::from_pair(type_pair))
             })),
+            Rule::type_tuple => Self::Tuple(ParensList1::list1_from_pair(pair, |type_pair| {
+                Box::new(Self::from_pair(type_pair))
+            })),
             Rule::type_call => {
                 let mut inner = pair.into_inner();
                 let function = TypeCallFunction::from_pair(inner.next().unwrap());
@@ -44,6 +51,15 @@ impl Type {
                     return_type,
                 }
             }
+            Rule::type_record => Self::Record(BracesList1::list1_from_pair(pair, |field_pair| {
+                let mut field_inner = field_pair.into_inner();
+                let label = Name::from_pair(field_inner.next().unwrap());
+                let type_annotation = TypeAnnotation::from_pair(field_inner.next().unwrap());
+                RecordTypeField {
+                    label,
+                    type_annotation: Box::new(type_annotation),
+                }
+            })),
             other => panic!("unexpected rule: {:#?} {:#?}", other, pair.into_inner()),
         }
     }
@@ -68,7 +84,9 @@ impl TypeCallFunction {
 #[cfg(test)]
 mod tests {
     use super::test_macros::*;
-    use crate::{CommaSep1, Name, Parens, StringToken, Type};
+    use crate::{
+        Braces, CommaSep1, Name, Parens, RecordTypeField, StringToken, Type, TypeAnnotation,
+    };
 
     #[test]
     fn it_parses_constructors() {
@@ -84,6 +102,13 @@ mod tests {
         assert_parses!("  padded  ", Type::Variable(Name(StringToken { ref value, .. })) if value == "padded");
     }
 
+    #[test]
+    fn it_parses_wildcards() {
+        assert_parses!("_", Type::Wildcard(_));
+        assert_parses!("Array(_)", Type::Call { .. });
+        assert_parses!("(_, Int) -> _", Type::Function { .. });
+    }
+
     #[test]
     fn it_parses_functions() {
         assert_parses!("() -> a", Type::Function { .. });
@@ -121,6 +146,50 @@ mod tests {
         assert_parses!("f(a, b, c,)", Type::Call { .. });
     }
 
+    #[test]
+    fn it_parses_records() {
+        assert_parses!("{ name: String }", Type::Record(_));
+        assert_parses!("{ name: String, age: Int, }", Type::Record(_));
+        assert_parses!(
+            "{ wrapped: { inner: Maybe(a) } }",
+            Type::Record(Braces {
+                value: CommaSep1 {
+                    head: RecordTypeField {
+                        type_annotation,
+                        ..
+                    },
+                    ..
+                },
+                ..
+            }) if matches!(*type_annotation, TypeAnnotation(_, Type::Record(_)))
+        );
+        assert_parses!("(a) -> { a: a }", Type::Function { .. });
+    }
+
+    #[test]
+    fn it_parses_tuples() {
+        assert_parses!("(Int, String)", Type::Tuple(_));
+        assert_parses!(
+            "(a,)",
+            Type::Tuple(Parens {
+                value: CommaSep1 {
+                    trailing_comma: Some(_),
+                    ..
+                },
+                ..
+            })
+        );
+        assert_parses!("((a, b), Maybe(c))", Type::Tuple(_));
+        assert_parses!(
+            "(a, b) -> (b, a)",
+            Type::Function {
+                return_type: box Type::Tuple(_),
+                ..
+            }
+        );
+        assert_parses!("((a, b)) -> a", Type::Function { .. });
+    }
+
     #[test]
     fn it_parses_parens() {
         assert_parses!("(a)", Type::Parens(_));
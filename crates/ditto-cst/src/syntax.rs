@@ -1,4 +1,4 @@
-use crate::{CloseBracket, CloseParen, Comma, OpenBracket, OpenParen};
+use crate::{CloseBrace, CloseBracket, CloseParen, Comma, OpenBrace, OpenBracket, OpenParen};
 use std::iter;
 
 /// A value surrounded by parentheses.
@@ -23,6 +23,17 @@ pub struct Brackets<T> {
     pub close_bracket: CloseBracket,
 }
 
+/// A value surrounded by braces.
+#[derive(Debug, Clone)]
+pub struct Braces<T> {
+    /// `{`
+    pub open_brace: OpenBrace,
+    /// The contents of the braces.
+    pub value: T,
+    /// `}`
+    pub close_brace: CloseBrace,
+}
+
 /// A list of items surrounded by parentheses
 ///
 /// Used to represent the following:
@@ -58,6 +69,17 @@ pub type ParensList1<T> = Parens<CommaSep1<T>>;
 /// ```
 pub type BracketsList<T> = Brackets<Option<CommaSep1<T>>>;
 
+/// A non-empty list of items surrounded by braces.
+///
+/// Used to represent the following:
+///
+/// ```ditto
+/// { foo }
+/// { foo, bar }
+/// { foo, bar, baz, }
+/// ```
+pub type BracesList1<T> = Braces<CommaSep1<T>>;
+
 /// A comma-separated, non-empty list of items.
 ///
 /// Used to represent the following:
@@ -10,13 +10,41 @@ pub struct Span {
 }
 
 impl Span {
+    /// Create a new [Span].
+    ///
+    /// The `start_offset` must not come after the `end_offset`.
+    pub fn new(start_offset: usize, end_offset: usize) -> Self {
+        debug_assert!(
+            start_offset <= end_offset,
+            "malformed span: {}..{}",
+            start_offset,
+            end_offset
+        );
+        Self {
+            start_offset,
+            end_offset,
+        }
+    }
+
+    /// Does this span start before it ends?
+    pub fn is_well_formed(&self) -> bool {
+        self.start_offset <= self.end_offset
+    }
+
+    /// Does this span cover all of `other`?
+    pub fn contains(&self, other: &Self) -> bool {
+        self.start_offset <= other.start_offset && other.end_offset <= self.end_offset
+    }
+
     /// Merge two spans, returning a new [Span] spanning `self` and `other`.
     #[must_use]
     pub fn merge(&self, other: &Self) -> Self {
-        Self {
-            start_offset: self.start_offset.min(other.start_offset),
-            end_offset: self.end_offset.max(other.end_offset),
-        }
+        debug_assert!(self.is_well_formed(), "malformed span: {:?}", self);
+        debug_assert!(other.is_well_formed(), "malformed span: {:?}", other);
+        Self::new(
+            self.start_offset.min(other.start_offset),
+            self.end_offset.max(other.end_offset),
+        )
     }
 }
 
@@ -104,6 +132,10 @@ pub struct Semicolon(pub EmptyToken);
 #[derive(Debug, Clone)]
 pub struct Equals(pub EmptyToken);
 
+/// `@`
+#[derive(Debug, Clone)]
+pub struct At(pub EmptyToken);
+
 /// `(`
 #[derive(Debug, Clone)]
 pub struct OpenParen(pub EmptyToken);
@@ -120,6 +152,14 @@ pub struct OpenBracket(pub EmptyToken);
 #[derive(Debug, Clone)]
 pub struct CloseBracket(pub EmptyToken);
 
+/// `{`
+#[derive(Debug, Clone)]
+pub struct OpenBrace(pub EmptyToken);
+
+/// `}`
+#[derive(Debug, Clone)]
+pub struct CloseBrace(pub EmptyToken);
+
 /// `<-`
 #[derive(Debug, Clone)]
 pub struct LeftArrow(pub EmptyToken);
@@ -132,6 +172,58 @@ pub struct RightArrow(pub EmptyToken);
 #[derive(Debug, Clone)]
 pub struct Pipe(pub EmptyToken);
 
+/// `+`
+#[derive(Debug, Clone)]
+pub struct Plus(pub EmptyToken);
+
+/// `-`
+#[derive(Debug, Clone)]
+pub struct Minus(pub EmptyToken);
+
+/// `*`
+#[derive(Debug, Clone)]
+pub struct Asterisk(pub EmptyToken);
+
+/// `/`
+#[derive(Debug, Clone)]
+pub struct Slash(pub EmptyToken);
+
+/// `==`
+#[derive(Debug, Clone)]
+pub struct DoubleEquals(pub EmptyToken);
+
+/// `!=`
+#[derive(Debug, Clone)]
+pub struct BangEquals(pub EmptyToken);
+
+/// `<`
+#[derive(Debug, Clone)]
+pub struct LeftAngle(pub EmptyToken);
+
+/// `<=`
+#[derive(Debug, Clone)]
+pub struct LeftAngleEquals(pub EmptyToken);
+
+/// `>`
+#[derive(Debug, Clone)]
+pub struct RightAngle(pub EmptyToken);
+
+/// `>=`
+#[derive(Debug, Clone)]
+pub struct RightAngleEquals(pub EmptyToken);
+
+/// `&&`
+#[derive(Debug, Clone)]
+pub struct DoubleAmpersand(pub EmptyToken);
+
+/// `||`
+#[derive(Debug, Clone)]
+pub struct DoublePipe(pub EmptyToken);
+
+/// `|>`
+#[derive(Debug, Clone)]
+pub struct RightPipe(pub EmptyToken);
+
 /// `module`
 #[derive(Debug, Clone)]
 pub struct ModuleKeyword(pub EmptyToken);
@@ -176,6 +268,34 @@ pub struct ElseKeyword(pub EmptyToken);
 #[derive(Debug, Clone)]
 pub struct TypeKeyword(pub EmptyToken);
 
+/// `let`
+#[derive(Debug, Clone)]
+pub struct LetKeyword(pub EmptyToken);
+
+/// `in`
+#[derive(Debug, Clone)]
+pub struct InKeyword(pub EmptyToken);
+
+/// `match`
+#[derive(Debug, Clone)]
+pub struct MatchKeyword(pub EmptyToken);
+
+/// `with`
+#[derive(Debug, Clone)]
+pub struct WithKeyword(pub EmptyToken);
+
+/// `end`
+#[derive(Debug, Clone)]
+pub struct EndKeyword(pub EmptyToken);
+
+/// `_`
+#[derive(Debug, Clone)]
+pub struct Wildcard(pub EmptyToken);
+
 /// `foreign`
 #[derive(Debug, Clone)]
 pub struct ForeignKeyword(pub EmptyToken);
+
+/// `js`
+#[derive(Debug, Clone)]
+pub struct JsKeyword(pub EmptyToken);
@@ -1,4 +1,7 @@
-use crate::{Name, Parens, ParensList, ParensList1, QualifiedProperName, RightArrow};
+use crate::{
+    BracesList1, Name, Parens, ParensList, ParensList1, QualifiedProperName, RightArrow,
+    TypeAnnotation, Wildcard,
+};
 
 /// Syntax representation of expression types.
 #[derive(Debug, Clone)]
@@ -40,6 +43,38 @@ pub enum Type {
     Constructor(QualifiedProperName),
     /// A named type variable.
     Variable(Name),
+    /// A type to be inferred, for partial type annotations.
+    ///
+    /// ```ditto
+    /// Array(_)
+    /// ```
+    Wildcard(Wildcard),
+    /// An anonymous record type.
+    ///
+    /// ```ditto
+    /// { name: String, age: Int }
+    /// ```
+    Record(BracesList1<RecordTypeField>),
+    /// A fixed-length tuple type.
+    ///
+    /// ```ditto
+    /// (Int, String)
+    /// (Int,)
+    /// ```
+    Tuple(ParensList1<Box<Self>>),
+}
+
+/// A labelled field of a record [Type].
+///
+/// ```ditto
+/// name: String
+/// ```
+#[derive(Debug, Clone)]
+pub struct RecordTypeField {
+    /// The field label.
+    pub label: Name,
+    /// The type of the field.
+    pub type_annotation: Box<TypeAnnotation>,
 }
 
 /// Valid targets for a type call.
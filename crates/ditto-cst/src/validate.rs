@@ -0,0 +1,1035 @@
+//! Checks that the spans of a syntax tree are well-formed.
+//!
+//! Every node's span should start before it ends and contain the spans of its
+//! children, which should appear in source order without overlapping.
+//!
+//! The parser should always produce well-formed spans, this module exists so
+//! that it can be tested (and fuzzed).
+use crate::{
+    Attribute, BracesList1, CommaSep1, Constructor, ConstructorField, ConstructorFields,
+    Declaration, Everything, Export, Exports, Expression, ForeignValueDeclaration, Header, Import,
+    ImportLine, MatchArm, Module, ModuleName, Parens, Pattern, Qualified, RecordField,
+    RecordPatternField, RecordTypeField, Span, Type, TypeAnnotation, TypeCallFunction,
+    TypeDeclaration, ValueDeclaration,
+};
+use thiserror::Error;
+
+/// A span that doesn't fit with the rest of the tree.
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("{reason}: {span:?} in {parent:?}")]
+pub struct InvalidSpan {
+    /// What's wrong with the span.
+    pub reason: &'static str,
+    /// The offending span.
+    pub span: Span,
+    /// The span of the enclosing node.
+    pub parent: Span,
+}
+
+type Result = std::result::Result<(), InvalidSpan>;
+
+/// Check all the spans in a [Module].
+pub fn validate_module(module: &Module) -> Result {
+    let mut children = vec![header_span(&module.header)];
+    children.extend(module.imports.iter().map(import_line_span));
+    children.extend(module.declarations.iter().map(Declaration::get_span));
+    let module_span = children[1..]
+        .iter()
+        .fold(children[0], |span, child| span.merge(child));
+    check_children(module_span, &children)?;
+
+    validate_header(&module.header)?;
+    for import_line in module.imports.iter() {
+        validate_import_line(import_line)?;
+    }
+    for declaration in module.declarations.iter() {
+        validate_declaration(declaration)?;
+    }
+    Ok(())
+}
+
+/// Check all the spans in an [Expression].
+pub fn validate_expression(expression: &Expression) -> Result {
+    let span = expression.get_span();
+    match expression {
+        Expression::Parens(parens) => {
+            check_children(span, &parens_spans(parens, vec![parens.value.get_span()]))?;
+            validate_expression(&parens.value)
+        }
+        Expression::Function {
+            parameters,
+            return_type_annotation,
+            right_arrow,
+            body,
+        } => {
+            let mut children = parens_spans(
+                parameters,
+                parameters
+                    .value
+                    .as_ref()
+                    .map_or_else(Vec::new, |parameters| {
+                        comma_sep_spans(parameters, |(binder, type_annotation)| {
+                            type_annotation.as_ref().map_or_else(
+                                || binder.get_span(),
+                                |type_annotation| {
+                                    binder.get_span().merge(&type_annotation.get_span())
+                                },
+                            )
+                        })
+                    }),
+            );
+            if let Some(type_annotation) = &**return_type_annotation {
+                children.push(type_annotation.get_span());
+            }
+            children.push(right_arrow.0.get_span());
+            children.push(body.get_span());
+            check_children(span, &children)?;
+
+            if let Some(parameters) = parameters.value.as_ref() {
+                for (binder, type_annotation) in parameters.iter() {
+                    if let Some(type_annotation) = type_annotation {
+                        let parameter_span = binder.get_span().merge(&type_annotation.get_span());
+                        check_children(
+                            parameter_span,
+                            &[binder.get_span(), type_annotation.get_span()],
+                        )?;
+                        validate_type_annotation(type_annotation)?;
+                    }
+                }
+            }
+            if let Some(type_annotation) = &**return_type_annotation {
+                validate_type_annotation(type_annotation)?;
+            }
+            validate_expression(body)
+        }
+        Expression::Call {
+            function,
+            arguments,
+        } => {
+            let mut children = vec![function.get_span()];
+            children.extend(parens_spans(
+                arguments,
+                arguments.value.as_ref().map_or_else(Vec::new, |arguments| {
+                    comma_sep_spans(arguments, |argument| argument.get_span())
+                }),
+            ));
+            check_children(span, &children)?;
+
+            validate_expression(function)?;
+            if let Some(arguments) = arguments.value.as_ref() {
+                for argument in arguments.iter() {
+                    validate_expression(argument)?;
+                }
+            }
+            Ok(())
+        }
+        Expression::If {
+            if_keyword,
+            condition,
+            then_keyword,
+            true_clause,
+            else_keyword,
+            false_clause,
+        } => {
+            check_children(
+                span,
+                &[
+                    if_keyword.0.get_span(),
+                    condition.get_span(),
+                    then_keyword.0.get_span(),
+                    true_clause.get_span(),
+                    else_keyword.0.get_span(),
+                    false_clause.get_span(),
+                ],
+            )?;
+            validate_expression(condition)?;
+            validate_expression(true_clause)?;
+            validate_expression(false_clause)
+        }
+        Expression::Let {
+            let_keyword,
+            head_declaration,
+            tail_declarations,
+            in_keyword,
+            expression,
+        } => {
+            let mut children = vec![let_keyword.0.get_span(), head_declaration.get_span()];
+            children.extend(tail_declarations.iter().map(ValueDeclaration::get_span));
+            children.push(in_keyword.0.get_span());
+            children.push(expression.get_span());
+            check_children(span, &children)?;
+
+            validate_value_declaration(head_declaration)?;
+            for declaration in tail_declarations.iter() {
+                validate_value_declaration(declaration)?;
+            }
+            validate_expression(expression)
+        }
+        Expression::Match {
+            match_keyword,
+            expression,
+            with_keyword,
+            head_arm,
+            tail_arms,
+            end_keyword,
+        } => {
+            let mut children = vec![
+                match_keyword.0.get_span(),
+                expression.get_span(),
+                with_keyword.0.get_span(),
+                head_arm.get_span(),
+            ];
+            children.extend(tail_arms.iter().map(MatchArm::get_span));
+            children.push(end_keyword.0.get_span());
+            check_children(span, &children)?;
+
+            validate_expression(expression)?;
+            validate_match_arm(head_arm)?;
+            for arm in tail_arms.iter() {
+                validate_match_arm(arm)?;
+            }
+            Ok(())
+        }
+        Expression::BinOp { lhs, operator, rhs } => {
+            check_children(span, &[lhs.get_span(), operator.get_span(), rhs.get_span()])?;
+            validate_expression(lhs)?;
+            validate_expression(rhs)
+        }
+        Expression::Negate { minus, expression } => {
+            check_children(span, &[minus.0.get_span(), expression.get_span()])?;
+            validate_expression(expression)
+        }
+        Expression::RecordAccess { target, dot, label } => {
+            check_children(
+                span,
+                &[target.get_span(), dot.0.get_span(), label.get_span()],
+            )?;
+            validate_expression(target)
+        }
+        Expression::Constructor(qualified_proper_name) => {
+            validate_qualified(qualified_proper_name, |proper_name| proper_name.get_span())
+        }
+        Expression::RecordConstructor {
+            constructor,
+            fields,
+        } => {
+            let mut children = vec![constructor.get_span(), fields.open_brace.0.get_span()];
+            children.extend(comma_sep_spans(&fields.value, |field| {
+                field.label.get_span().merge(&field.value.get_span())
+            }));
+            children.push(fields.close_brace.0.get_span());
+            check_children(span, &children)?;
+
+            validate_qualified(constructor, |proper_name| proper_name.get_span())?;
+            validate_record_fields(fields)
+        }
+        Expression::Record(fields) => {
+            let mut children = vec![fields.open_brace.0.get_span()];
+            children.extend(comma_sep_spans(&fields.value, |field| {
+                field.label.get_span().merge(&field.value.get_span())
+            }));
+            children.push(fields.close_brace.0.get_span());
+            check_children(span, &children)?;
+
+            validate_record_fields(fields)
+        }
+        Expression::Variable(qualified_name) => {
+            validate_qualified(qualified_name, |name| name.get_span())
+        }
+        Expression::Array(brackets) => {
+            let mut children = vec![brackets.open_bracket.0.get_span()];
+            if let Some(elements) = brackets.value.as_ref() {
+                children.extend(comma_sep_spans(elements, |element| element.get_span()));
+            }
+            children.push(brackets.close_bracket.0.get_span());
+            check_children(span, &children)?;
+
+            if let Some(elements) = brackets.value.as_ref() {
+                for element in elements.iter() {
+                    validate_expression(element)?;
+                }
+            }
+            Ok(())
+        }
+        Expression::Tuple(parens) => {
+            check_children(
+                span,
+                &parens_spans(
+                    parens,
+                    comma_sep_spans(&parens.value, |element| element.get_span()),
+                ),
+            )?;
+            for element in parens.value.iter() {
+                validate_expression(element)?;
+            }
+            Ok(())
+        }
+        Expression::Unit(_)
+        | Expression::True(_)
+        | Expression::False(_)
+        | Expression::String(_)
+        | Expression::Char(_)
+        | Expression::Hole(_)
+        | Expression::Int(_)
+        | Expression::Float(_) => check_children(span, &[]),
+        Expression::InlineJs {
+            js_keyword,
+            code,
+            type_annotation,
+        } => {
+            check_children(
+                span,
+                &[
+                    js_keyword.0.get_span(),
+                    code.get_span(),
+                    type_annotation.get_span(),
+                ],
+            )?;
+            validate_type_annotation(type_annotation)
+        }
+    }
+}
+
+/// Check all the spans in a [Type].
+pub fn validate_type(t: &Type) -> Result {
+    let span = t.get_span();
+    match t {
+        Type::Parens(parens) => {
+            check_children(span, &parens_spans(parens, vec![parens.value.get_span()]))?;
+            validate_type(&parens.value)
+        }
+        Type::Call {
+            function,
+            arguments,
+        } => {
+            let mut children = vec![function.get_span()];
+            children.extend(parens_spans(
+                arguments,
+                comma_sep_spans(&arguments.value, |argument| argument.get_span()),
+            ));
+            check_children(span, &children)?;
+
+            if let TypeCallFunction::Constructor(qualified_proper_name) = function {
+                validate_qualified(qualified_proper_name, |proper_name| proper_name.get_span())?;
+            }
+            for argument in arguments.value.iter() {
+                validate_type(argument)?;
+            }
+            Ok(())
+        }
+        Type::Function {
+            parameters,
+            right_arrow,
+            return_type,
+        } => {
+            let mut children = parens_spans(
+                parameters,
+                parameters
+                    .value
+                    .as_ref()
+                    .map_or_else(Vec::new, |parameters| {
+                        comma_sep_spans(parameters, |parameter| parameter.get_span())
+                    }),
+            );
+            children.push(right_arrow.0.get_span());
+            children.push(return_type.get_span());
+            check_children(span, &children)?;
+
+            if let Some(parameters) = parameters.value.as_ref() {
+                for parameter in parameters.iter() {
+                    validate_type(parameter)?;
+                }
+            }
+            validate_type(return_type)
+        }
+        Type::Constructor(qualified_proper_name) => {
+            validate_qualified(qualified_proper_name, |proper_name| proper_name.get_span())
+        }
+        Type::Variable(_) | Type::Wildcard(_) => check_children(span, &[]),
+        Type::Record(braces) => {
+            let mut children = vec![braces.open_brace.0.get_span()];
+            children.extend(comma_sep_spans(&braces.value, RecordTypeField::get_span));
+            children.push(braces.close_brace.0.get_span());
+            check_children(span, &children)?;
+
+            for field in braces.value.iter() {
+                check_children(
+                    field.get_span(),
+                    &[field.label.get_span(), field.type_annotation.get_span()],
+                )?;
+                validate_type_annotation(&field.type_annotation)?;
+            }
+            Ok(())
+        }
+        Type::Tuple(parens) => {
+            check_children(
+                span,
+                &parens_spans(
+                    parens,
+                    comma_sep_spans(&parens.value, |element| element.get_span()),
+                ),
+            )?;
+            for element in parens.value.iter() {
+                validate_type(element)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+fn validate_record_fields(fields: &BracesList1<RecordField>) -> Result {
+    for field in fields.value.iter() {
+        check_children(
+            field.label.get_span().merge(&field.value.get_span()),
+            &[
+                field.label.get_span(),
+                field.equals.0.get_span(),
+                field.value.get_span(),
+            ],
+        )?;
+        validate_expression(&field.value)?;
+    }
+    Ok(())
+}
+
+fn validate_header(header: &Header) -> Result {
+    let Header {
+        attributes,
+        module_keyword,
+        module_name,
+        exports_keyword,
+        exports,
+        semicolon,
+    } = header;
+    let mut children = attributes
+        .iter()
+        .map(Attribute::get_span)
+        .collect::<Vec<_>>();
+    children.extend([
+        module_keyword.0.get_span(),
+        module_name.get_span(),
+        exports_keyword.0.get_span(),
+        exports_span(exports),
+        semicolon.0.get_span(),
+    ]);
+    check_children(header_span(header), &children)?;
+    validate_module_name(module_name)?;
+    match exports {
+        Exports::Everything(everything) => validate_everything(everything),
+        Exports::Nothing(nothing) => {
+            check_children(nothing.get_span(), &parens_spans(nothing, vec![]))
+        }
+        Exports::List(exports) => {
+            check_children(
+                exports.get_span(),
+                &parens_spans(
+                    exports,
+                    comma_sep_spans(&exports.value, |export| match export {
+                        Export::Value(name) => name.get_span(),
+                        Export::Type(proper_name, everything) => {
+                            everything_merge(proper_name.get_span(), everything)
+                        }
+                        Export::Module(module_keyword, module_name) => {
+                            module_keyword.0.get_span().merge(&module_name.get_span())
+                        }
+                    }),
+                ),
+            )?;
+            for export in exports.value.iter() {
+                match export {
+                    Export::Type(proper_name, Some(everything)) => {
+                        check_children(
+                            proper_name.get_span().merge(&everything.get_span()),
+                            &[proper_name.get_span(), everything.get_span()],
+                        )?;
+                        validate_everything(everything)?;
+                    }
+                    Export::Module(module_keyword, module_name) => {
+                        check_children(
+                            module_keyword.0.get_span().merge(&module_name.get_span()),
+                            &[module_keyword.0.get_span(), module_name.get_span()],
+                        )?;
+                        validate_module_name(module_name)?;
+                    }
+                    _ => {}
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+fn validate_import_line(import_line: &ImportLine) -> Result {
+    let ImportLine {
+        import_keyword,
+        package,
+        module_name,
+        alias,
+        imports,
+        semicolon,
+    } = import_line;
+    let mut children = vec![import_keyword.0.get_span()];
+    if let Some(package) = package {
+        children.push(package.get_span());
+    }
+    children.push(module_name.get_span());
+    if let Some((as_keyword, proper_name)) = alias {
+        children.push(as_keyword.0.get_span());
+        children.push(proper_name.get_span());
+    }
+    if let Some(imports) = imports {
+        children.push(imports.0.get_span());
+    }
+    children.push(semicolon.0.get_span());
+    check_children(import_line_span(import_line), &children)?;
+
+    if let Some(package) = package {
+        check_children(
+            package.get_span(),
+            &parens_spans(package, vec![package.value.get_span()]),
+        )?;
+    }
+    validate_module_name(module_name)?;
+    if let Some(imports) = imports {
+        check_children(
+            imports.0.get_span(),
+            &parens_spans(
+                &imports.0,
+                comma_sep_spans(&imports.0.value, |import| match import {
+                    Import::Value(name) => name.get_span(),
+                    Import::Type(proper_name, everything) => {
+                        everything_merge(proper_name.get_span(), everything)
+                    }
+                }),
+            ),
+        )?;
+        for import in imports.0.value.iter() {
+            if let Import::Type(proper_name, Some(everything)) = import {
+                check_children(
+                    proper_name.get_span().merge(&everything.get_span()),
+                    &[proper_name.get_span(), everything.get_span()],
+                )?;
+                validate_everything(everything)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn validate_declaration(declaration: &Declaration) -> Result {
+    match declaration {
+        Declaration::Value(value_declaration) => validate_value_declaration(value_declaration),
+        Declaration::Type(type_declaration) => validate_type_declaration(type_declaration),
+        Declaration::ForeignValue(foreign_value_declaration) => {
+            validate_foreign_value_declaration(foreign_value_declaration)
+        }
+    }
+}
+
+fn validate_value_declaration(value_declaration: &ValueDeclaration) -> Result {
+    let ValueDeclaration {
+        attributes,
+        name,
+        type_annotation,
+        equals,
+        expression,
+        semicolon,
+    } = value_declaration;
+    let mut children = attributes
+        .iter()
+        .map(Attribute::get_span)
+        .collect::<Vec<_>>();
+    children.push(name.get_span());
+    if let Some(type_annotation) = type_annotation {
+        children.push(type_annotation.get_span());
+    }
+    children.push(equals.0.get_span());
+    children.push(expression.get_span());
+    children.push(semicolon.0.get_span());
+    check_children(value_declaration.get_span(), &children)?;
+
+    if let Some(type_annotation) = type_annotation {
+        validate_type_annotation(type_annotation)?;
+    }
+    validate_expression(expression)
+}
+
+fn validate_type_declaration(type_declaration: &TypeDeclaration) -> Result {
+    let mut children = vec![
+        type_declaration.type_keyword().0.get_span(),
+        type_declaration.type_name().get_span(),
+    ];
+    if let Some(type_variables) = type_declaration.type_variables() {
+        children.push(type_variables.get_span());
+    }
+    let semicolon =
+        match type_declaration {
+            TypeDeclaration::WithConstructors {
+                equals,
+                head_constructor,
+                tail_constructors,
+                semicolon,
+                ..
+            } => {
+                children.push(equals.0.get_span());
+                children.push(constructor_span(head_constructor, |pipe| {
+                    pipe.as_ref().map(|pipe| pipe.0.get_span())
+                }));
+                children.extend(tail_constructors.iter().map(|constructor| {
+                    constructor_span(constructor, |pipe| Some(pipe.0.get_span()))
+                }));
+                semicolon
+            }
+            TypeDeclaration::WithoutConstructors { semicolon, .. } => semicolon,
+        };
+    children.push(semicolon.0.get_span());
+    check_children(
+        type_declaration
+            .type_keyword()
+            .0
+            .get_span()
+            .merge(&semicolon.0.get_span()),
+        &children,
+    )?;
+
+    if let Some(type_variables) = type_declaration.type_variables() {
+        check_children(
+            type_variables.get_span(),
+            &parens_spans(
+                type_variables,
+                comma_sep_spans(&type_variables.value, |name| name.get_span()),
+            ),
+        )?;
+    }
+    if let TypeDeclaration::WithConstructors {
+        head_constructor,
+        tail_constructors,
+        ..
+    } = type_declaration
+    {
+        validate_constructor(head_constructor, |pipe| {
+            pipe.as_ref().map(|pipe| pipe.0.get_span())
+        })?;
+        for constructor in tail_constructors.iter() {
+            validate_constructor(constructor, |pipe| Some(pipe.0.get_span()))?;
+        }
+    }
+    Ok(())
+}
+
+fn validate_constructor<P>(
+    constructor: &Constructor<P>,
+    pipe_span: impl Fn(&P) -> Option<Span>,
+) -> Result {
+    let mut children = Vec::new();
+    if let Some(span) = pipe_span(&constructor.pipe) {
+        children.push(span);
+    }
+    children.push(constructor.constructor_name.get_span());
+    match constructor.fields.as_ref() {
+        None => {}
+        Some(ConstructorFields::Positional(parens)) => {
+            children.push(parens.get_span());
+        }
+        Some(ConstructorFields::Labelled(parens)) => {
+            children.push(parens.get_span());
+        }
+        Some(ConstructorFields::Named(braces)) => {
+            children.push(braces.get_span());
+        }
+    }
+    check_children(constructor_span(constructor, pipe_span), &children)?;
+
+    match constructor.fields.as_ref() {
+        None => Ok(()),
+        Some(ConstructorFields::Positional(parens)) => {
+            check_children(
+                parens.get_span(),
+                &parens_spans(
+                    parens,
+                    comma_sep_spans(&parens.value, |field_type| field_type.get_span()),
+                ),
+            )?;
+            for field_type in parens.value.iter() {
+                validate_type(field_type)?;
+            }
+            Ok(())
+        }
+        Some(ConstructorFields::Labelled(parens)) => {
+            check_children(
+                parens.get_span(),
+                &parens_spans(
+                    parens,
+                    comma_sep_spans(&parens.value, constructor_field_span),
+                ),
+            )?;
+            for field in parens.value.iter() {
+                validate_constructor_field(field)?;
+            }
+            Ok(())
+        }
+        Some(ConstructorFields::Named(braces)) => {
+            let mut children = vec![braces.open_brace.0.get_span()];
+            children.extend(comma_sep_spans(&braces.value, constructor_field_span));
+            children.push(braces.close_brace.0.get_span());
+            check_children(braces.get_span(), &children)?;
+            for field in braces.value.iter() {
+                validate_constructor_field(field)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+fn validate_constructor_field(field: &ConstructorField) -> Result {
+    check_children(
+        constructor_field_span(field),
+        &[field.label.get_span(), field.type_annotation.get_span()],
+    )?;
+    validate_type_annotation(&field.type_annotation)
+}
+
+fn constructor_field_span(field: &ConstructorField) -> Span {
+    field
+        .label
+        .get_span()
+        .merge(&field.type_annotation.get_span())
+}
+
+fn validate_foreign_value_declaration(
+    foreign_value_declaration: &ForeignValueDeclaration,
+) -> Result {
+    let ForeignValueDeclaration {
+        foreign_keyword,
+        foreign_name,
+        name,
+        type_annotation,
+        semicolon,
+    } = foreign_value_declaration;
+    let mut children = vec![foreign_keyword.0.get_span()];
+    if let Some((string_token, as_keyword)) = foreign_name {
+        children.push(string_token.get_span());
+        children.push(as_keyword.0.get_span());
+    }
+    children.push(name.get_span());
+    children.push(type_annotation.get_span());
+    children.push(semicolon.0.get_span());
+    check_children(
+        foreign_keyword.0.get_span().merge(&semicolon.0.get_span()),
+        &children,
+    )?;
+    validate_type_annotation(type_annotation)
+}
+
+fn validate_match_arm(match_arm: &MatchArm) -> Result {
+    check_children(
+        match_arm.get_span(),
+        &[
+            match_arm.pipe.0.get_span(),
+            match_arm.pattern.get_span(),
+            match_arm.right_arrow.0.get_span(),
+            match_arm.expression.get_span(),
+        ],
+    )?;
+    validate_pattern(&match_arm.pattern)?;
+    validate_expression(&match_arm.expression)
+}
+
+fn validate_pattern(pattern: &Pattern) -> Result {
+    let span = pattern.get_span();
+    match pattern {
+        Pattern::Constructor {
+            constructor,
+            arguments,
+        } => {
+            let mut children = vec![constructor.get_span()];
+            if let Some(arguments) = arguments {
+                children.extend(parens_spans(
+                    arguments,
+                    comma_sep_spans(&arguments.value, |argument| argument.get_span()),
+                ));
+            }
+            check_children(span, &children)?;
+
+            validate_qualified(constructor, |proper_name| proper_name.get_span())?;
+            if let Some(arguments) = arguments {
+                for argument in arguments.value.iter() {
+                    validate_pattern(argument)?;
+                }
+            }
+            Ok(())
+        }
+        Pattern::RecordConstructor {
+            constructor,
+            fields,
+        } => {
+            let mut children = vec![constructor.get_span(), fields.open_brace.0.get_span()];
+            children.extend(comma_sep_spans(
+                &fields.value.fields,
+                RecordPatternField::get_span,
+            ));
+            if let Some(double_dot) = &fields.value.rest {
+                children.push(double_dot.0.get_span());
+            }
+            children.push(fields.close_brace.0.get_span());
+            check_children(span, &children)?;
+
+            validate_qualified(constructor, |proper_name| proper_name.get_span())?;
+            for field in fields.value.fields.iter() {
+                check_children(
+                    field.get_span(),
+                    &[
+                        field.label.get_span(),
+                        field.equals.0.get_span(),
+                        field.pattern.get_span(),
+                    ],
+                )?;
+                validate_pattern(&field.pattern)?;
+            }
+            Ok(())
+        }
+        Pattern::Tuple(parens) => {
+            check_children(
+                span,
+                &parens_spans(
+                    parens,
+                    comma_sep_spans(&parens.value, |element| element.get_span()),
+                ),
+            )?;
+            for element in parens.value.iter() {
+                validate_pattern(element)?;
+            }
+            Ok(())
+        }
+        Pattern::Variable { .. } | Pattern::Wildcard(_) => check_children(span, &[]),
+    }
+}
+
+fn validate_type_annotation(type_annotation: &TypeAnnotation) -> Result {
+    check_children(
+        type_annotation.get_span(),
+        &[
+            type_annotation.0 .0.get_span(),
+            type_annotation.1.get_span(),
+        ],
+    )?;
+    validate_type(&type_annotation.1)
+}
+
+fn validate_module_name(module_name: &ModuleName) -> Result {
+    let mut children = Vec::new();
+    for (proper_name, dot) in module_name.init.iter() {
+        children.push(proper_name.get_span());
+        children.push(dot.0.get_span());
+    }
+    children.push(module_name.last.get_span());
+    check_children(module_name.get_span(), &children)
+}
+
+fn validate_qualified<Value>(
+    qualified: &Qualified<Value>,
+    value_span: impl Fn(&Value) -> Span,
+) -> Result {
+    let value_span = value_span(&qualified.value);
+    if let Some((proper_name, dot)) = &qualified.module_name {
+        check_children(
+            proper_name.get_span().merge(&value_span),
+            &[proper_name.get_span(), dot.0.get_span(), value_span],
+        )
+    } else {
+        check_children(value_span, &[])
+    }
+}
+
+fn validate_everything(everything: &Everything) -> Result {
+    check_children(
+        everything.get_span(),
+        &parens_spans(everything, vec![everything.value.0.get_span()]),
+    )
+}
+
+/// Check that `parent` is well-formed, and that `children` are well-formed,
+/// contained within `parent`, and in order.
+fn check_children(parent: Span, children: &[Span]) -> Result {
+    if !parent.is_well_formed() {
+        return Err(InvalidSpan {
+            reason: "span ends before it starts",
+            span: parent,
+            parent,
+        });
+    }
+    let mut previous_end = parent.start_offset;
+    for child in children.iter().copied() {
+        let reason = if !child.is_well_formed() {
+            "span ends before it starts"
+        } else if !parent.contains(&child) {
+            "span isn't contained by its parent"
+        } else if child.start_offset < previous_end {
+            "span overlaps the previous span"
+        } else {
+            previous_end = child.end_offset;
+            continue;
+        };
+        return Err(InvalidSpan {
+            reason,
+            span: child,
+            parent,
+        });
+    }
+    Ok(())
+}
+
+fn parens_spans<T>(parens: &Parens<T>, value_spans: Vec<Span>) -> Vec<Span> {
+    let mut spans = vec![parens.open_paren.0.get_span()];
+    spans.extend(value_spans);
+    spans.push(parens.close_paren.0.get_span());
+    spans
+}
+
+fn comma_sep_spans<T>(comma_sep: &CommaSep1<T>, get_span: impl Fn(&T) -> Span) -> Vec<Span> {
+    let mut spans = vec![get_span(&comma_sep.head)];
+    for (comma, value) in comma_sep.tail.iter() {
+        spans.push(comma.0.get_span());
+        spans.push(get_span(value));
+    }
+    if let Some(comma) = &comma_sep.trailing_comma {
+        spans.push(comma.0.get_span());
+    }
+    spans
+}
+
+fn everything_merge(span: Span, everything: &Option<Everything>) -> Span {
+    everything
+        .as_ref()
+        .map_or(span, |everything| span.merge(&everything.get_span()))
+}
+
+fn header_span(header: &Header) -> Span {
+    let start = match header.attributes.first() {
+        Some(attribute) => attribute.get_span(),
+        None => header.module_keyword.0.get_span(),
+    };
+    start.merge(&header.semicolon.0.get_span())
+}
+
+fn exports_span(exports: &Exports) -> Span {
+    match exports {
+        Exports::Everything(everything) => everything.get_span(),
+        Exports::Nothing(nothing) => nothing.get_span(),
+        Exports::List(exports) => exports.get_span(),
+    }
+}
+
+fn import_line_span(import_line: &ImportLine) -> Span {
+    import_line
+        .import_keyword
+        .0
+        .get_span()
+        .merge(&import_line.semicolon.0.get_span())
+}
+
+fn constructor_span<P>(
+    constructor: &Constructor<P>,
+    pipe_span: impl Fn(&P) -> Option<Span>,
+) -> Span {
+    let start =
+        pipe_span(&constructor.pipe).unwrap_or_else(|| constructor.constructor_name.get_span());
+    match constructor.fields.as_ref() {
+        None => start.merge(&constructor.constructor_name.get_span()),
+        Some(ConstructorFields::Positional(parens)) => start.merge(&parens.get_span()),
+        Some(ConstructorFields::Labelled(parens)) => start.merge(&parens.get_span()),
+        Some(ConstructorFields::Named(braces)) => start.merge(&braces.get_span()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{validate_module, InvalidSpan};
+    use crate::{Module, Span};
+    use std::{fs, path::Path};
+
+    #[test]
+    fn it_validates_the_golden_corpora() {
+        let crates_dir = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+        let mut validated = 0;
+        for golden_tests in [
+            "ditto-checker/golden-tests",
+            "ditto-codegen-js/golden-tests",
+            "ditto-fmt/golden-tests",
+            "ditto-cst/golden-tests",
+        ] {
+            for path in find_ditto_files(&crates_dir.join(golden_tests)) {
+                let source = fs::read_to_string(&path).unwrap();
+                // Some of these are expected not to parse
+                if let Ok(module) = Module::parse(&source) {
+                    if let Err(err) = validate_module(&module) {
+                        panic!("{}: {}", path.to_string_lossy(), err);
+                    }
+                    validated += 1;
+                }
+            }
+        }
+        assert!(validated > 0, "no modules were validated");
+    }
+
+    #[test]
+    fn it_validates_trailing_comments_and_nested_expressions() {
+        let source = r#"
+            module Test exports (Maybe(..), five); -- comment
+            import (pkg) Some.Module as M (Type(..), value); -- comment
+            type Maybe(a) =
+                | Just(a) -- comment
+                | Nothing; -- comment
+            type Person = Person { name: String, age: Int };
+            foreign five_impl : Int;
+            five : Int = -- comment
+                let
+                    x = (y: Int): Int -> match Just(y) with
+                        | Just(z) -> z -- comment
+                        | _ -> 5 -- comment
+                    end;
+                in if true then x(5) else [1, 2, 3,] -- comment
+            ;
+        "#;
+        let module = Module::parse(source).unwrap();
+        assert_eq!(validate_module(&module), Ok(()));
+    }
+
+    #[test]
+    fn it_reports_malformed_spans() {
+        let mut module = Module::parse("module Test exports (..);").unwrap();
+        let bad_span = Span {
+            start_offset: 10,
+            end_offset: 5,
+        };
+        module.header.exports_keyword.0.span = bad_span;
+        assert_eq!(
+            validate_module(&module),
+            Err(InvalidSpan {
+                reason: "span ends before it starts",
+                span: bad_span,
+                parent: Span {
+                    start_offset: 0,
+                    end_offset: 25,
+                },
+            })
+        );
+    }
+
+    fn find_ditto_files(dir: &Path) -> Vec<std::path::PathBuf> {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                files.extend(find_ditto_files(&path));
+            } else if path
+                .extension()
+                .map_or(false, |extension| extension == "ditto")
+            {
+                files.push(path);
+            }
+        }
+        files.sort();
+        files
+    }
+}
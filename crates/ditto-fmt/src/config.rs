@@ -1,3 +1,6 @@
+use ditto_config::FmtOptions;
+use std::cell::Cell;
+
 pub static INDENT_WIDTH: u8 = 4;
 pub static MAX_WIDTH: u32 = 80;
 
@@ -6,3 +9,29 @@ pub static NEWLINE: &str = "\r\n";
 
 #[cfg(not(windows))]
 pub static NEWLINE: &str = "\n";
+
+thread_local! {
+    /// The options of the current `format_module_with_options` call.
+    ///
+    /// Some of these are only read deep inside list generation, so rather than
+    /// threading them through every `gen_*` function they're set for the
+    /// duration of the call.
+    static OPTIONS: Cell<FmtOptions> = Cell::new(FmtOptions::default());
+}
+
+/// Whether lists broken over multiple lines get a trailing comma.
+pub fn trailing_commas() -> bool {
+    OPTIONS.with(|cell| cell.get().trailing_commas)
+}
+
+/// Whether export and import lists are sorted.
+pub fn sort_lists() -> bool {
+    OPTIONS.with(|cell| cell.get().sort_lists)
+}
+
+pub fn with_options<T>(options: FmtOptions, f: impl FnOnce() -> T) -> T {
+    let previous = OPTIONS.with(|cell| cell.replace(options));
+    let result = f();
+    OPTIONS.with(|cell| cell.set(previous));
+    result
+}
@@ -4,12 +4,15 @@ use super::{
     helpers::{group, space},
     name::{gen_name, gen_proper_name},
     r#type::gen_type,
-    syntax::gen_parens_list1,
-    token::{gen_equals, gen_foreign_keyword, gen_pipe, gen_semicolon, gen_type_keyword},
+    syntax::{gen_braces_list1, gen_parens_list1},
+    token::{
+        gen_as_keyword, gen_at, gen_equals, gen_foreign_keyword, gen_pipe, gen_semicolon,
+        gen_string_token, gen_type_keyword,
+    },
 };
 use ditto_cst::{
-    Constructor, Declaration, Expression, ForeignValueDeclaration, Pipe, TypeDeclaration,
-    ValueDeclaration,
+    Constructor, ConstructorField, ConstructorFields, Declaration, Expression,
+    ForeignValueDeclaration, Pipe, StringToken, TypeDeclaration, ValueDeclaration,
 };
 use dprint_core::formatting::{
     condition_helpers, conditions, ir_helpers, ConditionResolver, ConditionResolverContext, Info,
@@ -27,8 +30,14 @@ pub fn gen_declaration(declaration: Declaration) -> PrintItems {
     }
 }
 
-fn gen_value_declaration(decl: ValueDeclaration) -> PrintItems {
+pub fn gen_value_declaration(decl: ValueDeclaration) -> PrintItems {
     let mut items = PrintItems::new();
+    // Attributes go on their own lines, above the name
+    for attribute in decl.attributes {
+        items.extend(gen_at(attribute.at));
+        items.extend(gen_name(attribute.name));
+        items.push_signal(Signal::NewLine);
+    }
     items.extend(gen_name(decl.name));
     if let Some(type_ann) = decl.type_annotation {
         items.extend(gen_type_annotation(type_ann));
@@ -41,8 +50,10 @@ fn gen_value_declaration(decl: ValueDeclaration) -> PrintItems {
     let expression_end_info = Info::new("end");
 
     let expression_has_leading_comments = decl.expression.has_leading_comments();
-    let expression_deserves_new_line_if_multi_lines =
-        matches!(decl.expression, Expression::If { .. });
+    let expression_deserves_new_line_if_multi_lines = matches!(
+        decl.expression,
+        Expression::If { .. } | Expression::Let { .. } | Expression::Match { .. }
+    );
 
     let expression_should_be_on_new_line: ConditionResolver =
         Rc::new(move |ctx: &mut ConditionResolverContext| -> Option<bool> {
@@ -56,8 +67,9 @@ fn gen_value_declaration(decl: ValueDeclaration) -> PrintItems {
                     &expression_end_info,
                 );
             }
-            // return Some(false);
-            None // NOTE I'm not sure what the implications are of None vs Some(false) ?
+            // NOTE returning `None` here leaves the condition unresolved, which can
+            // lead to different break decisions when formatting the output again
+            Some(false)
         });
 
     items.push_condition(conditions::if_true_or(
@@ -174,16 +186,55 @@ fn gen_constructor(ctor: Constructor<Option<Pipe>>) -> PrintItems {
         items.extend(space());
     }
     items.extend(gen_proper_name(ctor.constructor_name));
-    if let Some(fields) = ctor.fields {
-        items.extend(gen_parens_list1(fields, gen_type, false));
+    match ctor.fields {
+        None => {}
+        Some(ConstructorFields::Positional(fields)) => {
+            items.extend(gen_parens_list1(fields, gen_type, false));
+        }
+        Some(ConstructorFields::Labelled(fields)) => {
+            items.extend(gen_parens_list1(fields, gen_constructor_field, false));
+        }
+        Some(ConstructorFields::Named(fields)) => {
+            items.extend(space());
+            items.extend(gen_braces_list1(fields, gen_constructor_field));
+        }
     }
     items
 }
 
+fn gen_constructor_field(field: ConstructorField) -> PrintItems {
+    let ConstructorField {
+        label,
+        type_annotation,
+    } = field;
+    let mut items = PrintItems::new();
+    items.extend(gen_name(label));
+    items.extend(gen_type_annotation(type_annotation));
+    items
+}
+
+/// `foreign name:` always stays on one line, and the type follows the same
+/// rules as any other type annotation.
+///
+/// ```ditto
+/// foreign "someFunction" as some_function: (
+///     String,
+///     Int,
+/// ) -> Int;
+/// ```
 fn gen_foreign_value_declaration(decl: ForeignValueDeclaration) -> PrintItems {
     let mut items = PrintItems::new();
     items.extend(gen_foreign_keyword(decl.foreign_keyword));
     items.extend(space());
+    if let Some((foreign_name, as_keyword)) = decl.foreign_name {
+        items.extend(gen_string_token(StringToken {
+            value: format!("\"{}\"", foreign_name.value),
+            ..foreign_name
+        }));
+        items.extend(space());
+        items.extend(gen_as_keyword(as_keyword));
+        items.extend(space());
+    }
     items.extend(gen_name(decl.name));
     items.extend(gen_type_annotation(decl.type_annotation));
     items.extend(gen_semicolon(decl.semicolon));
@@ -223,6 +274,17 @@ mod tests {
             assert_fmt!("type Unit = | Unit;", "type Unit = Unit;");
             assert_fmt!("type AB = A | B;", "type AB =\n\t| A\n\t| B;");
             assert_fmt!("type Maybe(a) =\n\t-- comment\n\t| Just(a)\n\t-- comment\n\t| Nothing;");
+            assert_fmt!("type Person = Person { name: String, age: Int };");
+            assert_fmt!(
+                "type Person = Person {name : String,age:Int};",
+                "type Person = Person { name: String, age: Int };"
+            );
+            assert_fmt!("type Shape =\n\t| Circle { radius: Float }\n\t| Square(Float);");
+            assert_fmt!("type User = User(name: String, age: Int);");
+            assert_fmt!(
+                "type User = User(name : String,age:Int);",
+                "type User = User(name: String, age: Int);"
+            );
         }
     }
 
@@ -247,6 +309,9 @@ mod tests {
             assert_fmt!("foo = 5;");
             assert_fmt!("foo: Int = 5;");
             assert_fmt!("foo: Int = 5;", "foo: Int =\n\t5;", 5);
+            assert_fmt!("@no_inline\nfoo = 5;");
+            assert_fmt!("@no_inline foo = 5;", "@no_inline\nfoo = 5;");
+            assert_fmt!("-- comment\n@no_inline\nfoo = 5;");
             assert_fmt!("foo: Int =  -- comment\n\t5;");
             assert_fmt!("foo: Int =\n\t-- comment\n\t5;");
             assert_fmt!("f: (a, b) -> c =\n\t-- comment\n\t[1, 2, 3, 4, 5];");
@@ -288,6 +353,22 @@ mod tests {
             assert_fmt!("foreign foo: Int;");
             assert_fmt!("foreign  --comment\n foo: Int;");
             assert_fmt!("foreign foo: (\n\t-- comment a,\n) -> b;");
+            assert_fmt!(r#"foreign "if" as if_impl: (Bool, a, a) -> a;"#);
+            assert_fmt!(
+                r#"foreign  "default"  as  default_impl : Int;"#,
+                r#"foreign "default" as default_impl: Int;"#
+            );
+            assert_fmt!("foreign five_args: (Int, Int, Int, Int, Int) -> Int;");
+            assert_fmt!(
+                "foreign five_args: (Int, Int, Int, Int, Int) -> Int;",
+                "foreign five_args: (\n\tInt,\n\tInt,\n\tInt,\n\tInt,\n\tInt,\n) -> Int;",
+                40
+            );
+            assert_fmt!(
+                r#"foreign "fiveArgs" as five_args: (Int, Int, Int, Int, Int) -> Int;"#,
+                "foreign \"fiveArgs\" as five_args: (\n\tInt,\n\tInt,\n\tInt,\n\tInt,\n\tInt,\n) -> Int;",
+                40
+            );
         }
     }
 }
@@ -1,15 +1,26 @@
 use super::{
+    declaration::gen_value_declaration,
     has_comments::HasComments,
     helpers::{group, space},
     name::{gen_name, gen_qualified_name, gen_qualified_proper_name},
     r#type::gen_type,
-    syntax::{gen_brackets_list, gen_parens, gen_parens_list},
+    syntax::{
+        gen_braces_list1, gen_brackets_list, gen_parens, gen_parens_list, gen_parens_list1,
+        gen_record_pattern_fields, gen_tuple,
+    },
     token::{
-        gen_colon, gen_else_keyword, gen_false_keyword, gen_if_keyword, gen_right_arrow,
-        gen_string_token, gen_then_keyword, gen_true_keyword, gen_unit_keyword,
+        gen_asterisk, gen_bang_equals, gen_colon, gen_dot, gen_double_ampersand, gen_double_equals,
+        gen_double_pipe, gen_else_keyword, gen_end_keyword, gen_equals, gen_false_keyword,
+        gen_if_keyword, gen_in_keyword, gen_js_keyword, gen_left_angle, gen_left_angle_equals,
+        gen_let_keyword, gen_match_keyword, gen_minus, gen_pipe, gen_plus, gen_right_angle,
+        gen_right_angle_equals, gen_right_arrow, gen_right_pipe, gen_slash, gen_string_token,
+        gen_then_keyword, gen_true_keyword, gen_unit_keyword, gen_wildcard, gen_with_keyword,
     },
 };
-use ditto_cst::{Expression, StringToken, TypeAnnotation};
+use ditto_cst::{
+    BinOp, ElseKeyword, Expression, FunctionBinder, IfKeyword, InKeyword, MatchArm, Pattern,
+    RecordField, RecordPatternField, StringToken, ThenKeyword, TypeAnnotation, ValueDeclaration,
+};
 use dprint_core::formatting::{
     condition_helpers, conditions, ir_helpers, ConditionResolver, ConditionResolverContext, Info,
     PrintItems, Signal,
@@ -24,6 +35,45 @@ pub fn gen_expression(expr: Expression) -> PrintItems {
         Expression::False(keyword) => gen_false_keyword(keyword),
         Expression::Unit(keyword) => gen_unit_keyword(keyword),
         Expression::Constructor(constructor) => gen_qualified_proper_name(constructor),
+        Expression::RecordConstructor {
+            constructor,
+            fields,
+        } => {
+            let mut items = gen_qualified_proper_name(constructor);
+            items.extend(space());
+            items.extend(gen_braces_list1(fields, gen_record_field));
+            items
+        }
+        Expression::Record(fields) => gen_braces_list1(fields, gen_record_field),
+        Expression::RecordAccess {
+            box target,
+            dot,
+            label,
+        } => {
+            let mut items = gen_expression(target);
+            items.extend(gen_dot(dot));
+            items.extend(gen_name(label));
+            items
+        }
+        Expression::Negate {
+            minus,
+            box expression,
+        } => {
+            let minus_has_trailing_comment = minus.0.has_trailing_comment();
+            let expression_has_leading_comments = expression.has_leading_comments();
+            // Avoid `--x`, which would be a comment.
+            let expression_is_negate = matches!(expression, Expression::Negate { .. });
+            let mut items = gen_minus(minus);
+            if minus_has_trailing_comment || expression_has_leading_comments {
+                items.extend(group(gen_expression(expression), true));
+            } else {
+                if expression_is_negate {
+                    items.extend(space());
+                }
+                items.extend(gen_expression(expression));
+            }
+            items
+        }
         Expression::Variable(variable) => gen_qualified_name(variable),
         Expression::Float(token) => gen_string_token(token),
         Expression::Int(token) => gen_string_token(token),
@@ -33,9 +83,41 @@ pub fn gen_expression(expr: Expression) -> PrintItems {
             trailing_comment: token.trailing_comment,
             value: format!("\"{}\"", token.value),
         }),
+        Expression::InlineJs {
+            js_keyword,
+            code,
+            type_annotation,
+        } => {
+            let mut items = gen_js_keyword(js_keyword);
+            items.extend(space());
+            items.extend(gen_string_token(StringToken {
+                span: code.span,
+                leading_comments: code.leading_comments,
+                trailing_comment: code.trailing_comment,
+                value: format!("\"{}\"", code.value),
+            }));
+            items.extend(space());
+            items.extend(gen_type_annotation(type_annotation));
+            items
+        }
+        Expression::Char(token) => gen_string_token(StringToken {
+            span: token.span,
+            leading_comments: token.leading_comments,
+            trailing_comment: token.trailing_comment,
+            value: format!("'{}'", token.value),
+        }),
+        Expression::Hole(token) => gen_string_token(StringToken {
+            span: token.span,
+            leading_comments: token.leading_comments,
+            trailing_comment: token.trailing_comment,
+            value: format!("?{}", token.value),
+        }),
         Expression::Array(brackets) => gen_brackets_list(brackets, |box expr| {
             ir_helpers::new_line_group(gen_expression(expr))
         }),
+        Expression::Tuple(parens) => gen_tuple(parens, |box expr| {
+            ir_helpers::new_line_group(gen_expression(expr))
+        }),
         Expression::If {
             if_keyword,
             box condition,
@@ -44,6 +126,18 @@ pub fn gen_expression(expr: Expression) -> PrintItems {
             else_keyword,
             box false_clause,
         } => {
+            let (branches, else_keyword, false_clause) = flatten_if_chain(
+                IfBranch {
+                    else_keyword: None,
+                    if_keyword,
+                    condition,
+                    then_keyword,
+                    clause: true_clause,
+                },
+                else_keyword,
+                false_clause,
+            );
+
             // NOTE that we insert this start info _after_ the `if` keyword
             // because we don't want to force multi-line layout for
             //
@@ -55,7 +149,9 @@ pub fn gen_expression(expr: Expression) -> PrintItems {
 
             let end_info = Info::new("end");
 
-            let force_use_new_lines = if_keyword.0.has_trailing_comment();
+            let force_use_new_lines = branches
+                .iter()
+                .any(|branch| branch.if_keyword.0.has_trailing_comment());
             let is_multiple_lines: ConditionResolver =
                 Rc::new(move |ctx: &mut ConditionResolverContext| -> Option<bool> {
                     if force_use_new_lines {
@@ -73,18 +169,29 @@ pub fn gen_expression(expr: Expression) -> PrintItems {
                     // ```ditto
                     // if true then
                     //     yes
+                    // else if false then
+                    //     maybe
                     // else
                     //     no
                     // ```
                     let mut items = PrintItems::new();
-                    items.extend(gen_if_keyword(if_keyword.clone()));
-                    items.push_info(start_info);
-                    items.extend(space());
-                    items.extend(gen_expression(condition.clone()));
-                    items.extend(space());
-                    items.extend(gen_then_keyword(then_keyword.clone()));
-                    items.push_signal(Signal::NewLine);
-                    items.extend(ir_helpers::with_indent(gen_expression(true_clause.clone())));
+                    for (i, branch) in branches.iter().cloned().enumerate() {
+                        if let Some(else_keyword) = branch.else_keyword {
+                            items.push_signal(Signal::ExpectNewLine);
+                            items.extend(gen_else_keyword(else_keyword));
+                            items.extend(space());
+                        }
+                        items.extend(gen_if_keyword(branch.if_keyword));
+                        if i == 0 {
+                            items.push_info(start_info);
+                        }
+                        items.extend(space());
+                        items.extend(gen_expression(branch.condition));
+                        items.extend(space());
+                        items.extend(gen_then_keyword(branch.then_keyword));
+                        items.push_signal(Signal::NewLine);
+                        items.extend(ir_helpers::with_indent(gen_expression(branch.clause)));
+                    }
                     items.push_signal(Signal::ExpectNewLine);
                     items.extend(gen_else_keyword(else_keyword.clone()));
                     items.push_signal(Signal::NewLine);
@@ -97,17 +204,26 @@ pub fn gen_expression(expr: Expression) -> PrintItems {
                     // Inline
                     //
                     // ```ditto
-                    // if true then 5 else 5
+                    // if true then 5 else if false then 6 else 7
                     // ```
                     let mut items = PrintItems::new();
-                    items.extend(gen_if_keyword(if_keyword));
-                    items.push_info(start_info);
-                    items.push_signal(Signal::SpaceOrNewLine);
-                    items.extend(gen_expression(condition));
-                    items.push_signal(Signal::SpaceOrNewLine);
-                    items.extend(gen_then_keyword(then_keyword));
-                    items.push_signal(Signal::SpaceOrNewLine);
-                    items.extend(gen_expression(true_clause));
+                    for (i, branch) in branches.into_iter().enumerate() {
+                        if let Some(else_keyword) = branch.else_keyword {
+                            items.push_signal(Signal::SpaceOrNewLine);
+                            items.extend(gen_else_keyword(else_keyword));
+                            items.push_signal(Signal::SpaceOrNewLine);
+                        }
+                        items.extend(gen_if_keyword(branch.if_keyword));
+                        if i == 0 {
+                            items.push_info(start_info);
+                        }
+                        items.push_signal(Signal::SpaceOrNewLine);
+                        items.extend(gen_expression(branch.condition));
+                        items.push_signal(Signal::SpaceOrNewLine);
+                        items.extend(gen_then_keyword(branch.then_keyword));
+                        items.push_signal(Signal::SpaceOrNewLine);
+                        items.extend(gen_expression(branch.clause));
+                    }
                     items.push_signal(Signal::SpaceOrNewLine);
                     items.extend(gen_else_keyword(else_keyword));
                     items.push_signal(Signal::SpaceOrNewLine);
@@ -120,6 +236,122 @@ pub fn gen_expression(expr: Expression) -> PrintItems {
             items.push_info(end_info);
             items
         }
+        Expression::Let {
+            let_keyword,
+            box head_declaration,
+            tail_declarations,
+            in_keyword,
+            box expression,
+        } => {
+            let (declarations, in_keyword, expression) =
+                flatten_let_chain(head_declaration, tail_declarations, in_keyword, expression);
+
+            // NOTE that (as with `if`) the start info goes _after_ the `let` keyword
+            // so that leading comments don't force a multi-line layout.
+            let start_info = Info::new("start");
+
+            let end_info = Info::new("end");
+
+            let force_use_new_lines = let_keyword.0.has_trailing_comment()
+                || declarations.len() > 1
+                || declarations.iter().any(|decl| decl.has_comments())
+                || in_keyword.0.has_comments();
+            let is_multiple_lines: ConditionResolver =
+                Rc::new(move |ctx: &mut ConditionResolverContext| -> Option<bool> {
+                    if force_use_new_lines {
+                        return Some(true);
+                    }
+                    condition_helpers::is_multiple_lines(ctx, &start_info, &end_info)
+                });
+
+            let mut items: PrintItems = conditions::if_true_or(
+                "multiLineLetIfMultipleLines",
+                is_multiple_lines,
+                {
+                    // Multiline
+                    //
+                    // ```ditto
+                    // let
+                    //     five = 5;
+                    //     six = 6;
+                    // in
+                    // [five, six]
+                    // ```
+                    let mut items = PrintItems::new();
+                    items.extend(gen_let_keyword(let_keyword.clone()));
+                    items.push_info(start_info);
+                    let mut declarations_items = PrintItems::new();
+                    for (i, declaration) in declarations.iter().enumerate() {
+                        if i > 0 {
+                            declarations_items.push_signal(Signal::NewLine);
+                        }
+                        declarations_items.extend(gen_value_declaration(declaration.clone()));
+                    }
+                    items.push_signal(Signal::NewLine);
+                    items.extend(ir_helpers::with_indent(declarations_items));
+                    items.push_signal(Signal::NewLine);
+                    items.extend(gen_in_keyword(in_keyword.clone()));
+                    items.push_signal(Signal::NewLine);
+                    items.extend(gen_expression(expression.clone()));
+                    items
+                },
+                {
+                    // Inline
+                    //
+                    // ```ditto
+                    // let five = 5; in five
+                    // ```
+                    let mut items = PrintItems::new();
+                    items.extend(gen_let_keyword(let_keyword));
+                    items.push_info(start_info);
+                    for declaration in declarations {
+                        items.push_signal(Signal::SpaceOrNewLine);
+                        items.extend(gen_value_declaration(declaration));
+                    }
+                    items.push_signal(Signal::SpaceOrNewLine);
+                    items.extend(gen_in_keyword(in_keyword));
+                    items.push_signal(Signal::SpaceOrNewLine);
+                    items.extend(gen_expression(expression));
+                    items
+                },
+            )
+            .into();
+
+            items.push_info(end_info);
+            items
+        }
+        Expression::Match {
+            match_keyword,
+            box expression,
+            with_keyword,
+            box head_arm,
+            tail_arms,
+            end_keyword,
+        } => {
+            // Arms are always laid out one per line, like type declaration constructors.
+            //
+            // ```ditto
+            // match x with
+            //     | Just(y) -> y
+            //     | Nothing -> 0
+            // end
+            // ```
+            let mut items = PrintItems::new();
+            items.extend(gen_match_keyword(match_keyword));
+            items.extend(space());
+            items.extend(gen_expression(expression));
+            items.extend(space());
+            items.extend(gen_with_keyword(with_keyword));
+            let mut arms_items = PrintItems::new();
+            for arm in std::iter::once(head_arm).chain(tail_arms) {
+                arms_items.push_signal(Signal::NewLine);
+                arms_items.extend(gen_match_arm(arm));
+            }
+            items.extend(ir_helpers::with_indent(arms_items));
+            items.push_signal(Signal::NewLine);
+            items.extend(gen_end_keyword(end_keyword));
+            items
+        }
         Expression::Function {
             box parameters,
             box return_type_annotation,
@@ -127,9 +359,12 @@ pub fn gen_expression(expr: Expression) -> PrintItems {
             box body,
         } => {
             let mut items = PrintItems::new();
-            items.extend(gen_parens_list(parameters, |(name, type_annotation)| {
+            items.extend(gen_parens_list(parameters, |(binder, type_annotation)| {
                 let mut items = PrintItems::new();
-                items.extend(gen_name(name));
+                items.extend(match binder {
+                    FunctionBinder::Name(name) => gen_name(name),
+                    FunctionBinder::Wildcard(wildcard) => gen_wildcard(wildcard),
+                });
                 if let Some(type_annotation) = type_annotation {
                     items.extend(gen_type_annotation(type_annotation));
                 }
@@ -150,6 +385,35 @@ pub fn gen_expression(expr: Expression) -> PrintItems {
             ));
             items
         }
+        Expression::BinOp {
+            box lhs,
+            operator: operator @ (BinOp::And(_) | BinOp::Or(_)),
+            box rhs,
+        } => gen_boolean_chain(lhs, operator, rhs),
+        Expression::BinOp {
+            box lhs,
+            operator: operator @ BinOp::Pipe(_),
+            box rhs,
+        } => gen_pipe_chain(lhs, operator, rhs),
+        Expression::BinOp {
+            box lhs,
+            operator,
+            box rhs,
+        } => {
+            let mut items = PrintItems::new();
+            items.extend(gen_expression(lhs));
+            items.extend(space());
+
+            let operator_has_trailing_comment = operator.token().has_trailing_comment();
+            items.extend(gen_binop(operator));
+
+            let rhs_has_leading_comments = rhs.has_leading_comments();
+            items.extend(group(
+                gen_expression(rhs),
+                operator_has_trailing_comment || rhs_has_leading_comments,
+            ));
+            items
+        }
         Expression::Call {
             box function,
             arguments,
@@ -164,6 +428,354 @@ pub fn gen_expression(expr: Expression) -> PrintItems {
     }
 }
 
+fn gen_binop(operator: BinOp) -> PrintItems {
+    match operator {
+        BinOp::Plus(plus) => gen_plus(plus),
+        BinOp::Minus(minus) => gen_minus(minus),
+        BinOp::Times(asterisk) => gen_asterisk(asterisk),
+        BinOp::Divide(slash) => gen_slash(slash),
+        BinOp::Equals(double_equals) => gen_double_equals(double_equals),
+        BinOp::NotEquals(bang_equals) => gen_bang_equals(bang_equals),
+        BinOp::LessThan(left_angle) => gen_left_angle(left_angle),
+        BinOp::LessThanOrEquals(left_angle_equals) => gen_left_angle_equals(left_angle_equals),
+        BinOp::GreaterThan(right_angle) => gen_right_angle(right_angle),
+        BinOp::GreaterThanOrEquals(right_angle_equals) => {
+            gen_right_angle_equals(right_angle_equals)
+        }
+        BinOp::And(double_ampersand) => gen_double_ampersand(double_ampersand),
+        BinOp::Or(double_pipe) => gen_double_pipe(double_pipe),
+        BinOp::Pipe(right_pipe) => gen_right_pipe(right_pipe),
+    }
+}
+
+/// Generates a chain of `&&` (or `||`) operators.
+///
+/// Like call arguments, the chain either fits on one line or is broken
+/// with one operand per line.
+fn gen_boolean_chain(lhs: Expression, operator: BinOp, rhs: Expression) -> PrintItems {
+    let (head, tail) = flatten_operator_chain(lhs, operator, rhs);
+
+    let start_info = Info::new("start");
+    let end_info = Info::new("end");
+
+    let force_use_new_lines = tail.iter().any(|(operator, operand)| {
+        operator.token().has_trailing_comment() || operand.has_leading_comments()
+    });
+    let is_multiple_lines: ConditionResolver =
+        Rc::new(move |ctx: &mut ConditionResolverContext| -> Option<bool> {
+            if force_use_new_lines {
+                return Some(true);
+            }
+            condition_helpers::is_multiple_lines(ctx, &start_info, &end_info)
+        });
+
+    let mut items = PrintItems::new();
+    items.push_info(start_info);
+    items.extend(gen_expression(head));
+    items.push_condition(conditions::if_true_or(
+        "multiLineBooleanChainIfMultipleLines",
+        is_multiple_lines,
+        {
+            // Multiline
+            //
+            // ```ditto
+            // a &&
+            //     b &&
+            //     c
+            // ```
+            let mut items = PrintItems::new();
+            for (operator, operand) in tail.iter().cloned() {
+                items.extend(space());
+                items.extend(gen_binop(operator));
+                items.push_signal(Signal::NewLine);
+                items.extend(ir_helpers::with_indent(gen_expression(operand)));
+            }
+            items
+        },
+        {
+            // Inline
+            //
+            // ```ditto
+            // a && b && c
+            // ```
+            let mut items = PrintItems::new();
+            for (operator, operand) in tail {
+                items.extend(space());
+                items.extend(gen_binop(operator));
+                items.push_signal(Signal::SpaceOrNewLine);
+                items.extend(gen_expression(operand));
+            }
+            items
+        },
+    ));
+    items.push_info(end_info);
+    items
+}
+
+/// Generates a chain of `|>` operators.
+///
+/// Chains that don't fit on one line are broken before each `|>`.
+fn gen_pipe_chain(lhs: Expression, operator: BinOp, rhs: Expression) -> PrintItems {
+    let (head, tail) = flatten_operator_chain(lhs, operator, rhs);
+
+    let start_info = Info::new("start");
+    let end_info = Info::new("end");
+
+    // A trailing comment anywhere but the last step would swallow the rest
+    // of an inline chain
+    let force_use_new_lines = head.has_comments()
+        || tail.iter().enumerate().any(|(i, (operator, operand))| {
+            operator.token().has_comments()
+                || operand.has_leading_comments()
+                || (i + 1 < tail.len() && operand.has_comments())
+        });
+    let is_multiple_lines: ConditionResolver =
+        Rc::new(move |ctx: &mut ConditionResolverContext| -> Option<bool> {
+            if force_use_new_lines {
+                return Some(true);
+            }
+            condition_helpers::is_multiple_lines(ctx, &start_info, &end_info)
+        });
+
+    let mut items = PrintItems::new();
+    items.push_info(start_info);
+    items.extend(gen_expression(head));
+    items.push_condition(conditions::if_true_or(
+        "multiLinePipeChainIfMultipleLines",
+        is_multiple_lines,
+        {
+            // Multiline
+            //
+            // ```ditto
+            // x
+            //     |> f
+            //     |> g
+            // ```
+            let mut items = PrintItems::new();
+            for (operator, operand) in tail.iter().cloned() {
+                let operand_on_new_line =
+                    operator.token().has_trailing_comment() || operand.has_leading_comments();
+                let mut pipe_items = gen_binop(operator);
+                if operand_on_new_line {
+                    pipe_items.push_signal(Signal::NewLine);
+                } else {
+                    pipe_items.extend(space());
+                }
+                pipe_items.extend(gen_expression(operand));
+                items.push_signal(Signal::NewLine);
+                items.extend(ir_helpers::with_indent(pipe_items));
+            }
+            items
+        },
+        {
+            // Inline
+            //
+            // ```ditto
+            // x |> f |> g
+            // ```
+            let mut items = PrintItems::new();
+            for (operator, operand) in tail {
+                items.push_signal(Signal::SpaceOrNewLine);
+                items.extend(gen_binop(operator));
+                items.extend(space());
+                items.extend(gen_expression(operand));
+            }
+            items
+        },
+    ));
+    items.push_info(end_info);
+    items
+}
+
+/// Collect left-nested uses of the same operator, so that `a && b && c`
+/// becomes `a` followed by `[(&&, b), (&&, c)]`.
+fn flatten_operator_chain(
+    lhs: Expression,
+    operator: BinOp,
+    rhs: Expression,
+) -> (Expression, Vec<(BinOp, Expression)>) {
+    let chain_operator = std::mem::discriminant(&operator);
+    let mut tail = vec![(operator, rhs)];
+    let mut head = lhs;
+    loop {
+        match head {
+            Expression::BinOp {
+                box lhs,
+                operator,
+                box rhs,
+            } if std::mem::discriminant(&operator) == chain_operator => {
+                tail.push((operator, rhs));
+                head = lhs;
+            }
+            _ => {
+                tail.reverse();
+                return (head, tail);
+            }
+        }
+    }
+}
+
+/// Collapse directly nested `let` expressions into a single block of declarations,
+/// so that a chain of `let`s doesn't get indented one level per declaration.
+///
+/// We can't do this if there are comments between the links of the chain, as they'd
+/// be lost.
+/// A single `if condition then clause` of an `else if` chain.
+#[derive(Clone)]
+struct IfBranch {
+    /// `else`, for all but the first branch.
+    else_keyword: Option<ElseKeyword>,
+    if_keyword: IfKeyword,
+    condition: Expression,
+    then_keyword: ThenKeyword,
+    clause: Expression,
+}
+
+/// Flattens nested conditionals in the false clause, so that `else if` chains
+/// can be laid out without each branch adding a level of indentation.
+fn flatten_if_chain(
+    head_branch: IfBranch,
+    mut else_keyword: ElseKeyword,
+    mut false_clause: Expression,
+) -> (Vec<IfBranch>, ElseKeyword, Expression) {
+    let mut branches = vec![head_branch];
+    loop {
+        match false_clause {
+            Expression::If {
+                if_keyword,
+                box condition,
+                then_keyword,
+                box true_clause,
+                else_keyword: next_else_keyword,
+                false_clause: box next_false_clause,
+            } if !else_keyword.0.has_trailing_comment() && !if_keyword.0.has_leading_comments() => {
+                branches.push(IfBranch {
+                    else_keyword: Some(else_keyword),
+                    if_keyword,
+                    condition,
+                    then_keyword,
+                    clause: true_clause,
+                });
+                else_keyword = next_else_keyword;
+                false_clause = next_false_clause;
+            }
+            _ => return (branches, else_keyword, false_clause),
+        }
+    }
+}
+
+fn flatten_let_chain(
+    head_declaration: ValueDeclaration,
+    tail_declarations: Vec<ValueDeclaration>,
+    mut in_keyword: InKeyword,
+    mut expression: Expression,
+) -> (Vec<ValueDeclaration>, InKeyword, Expression) {
+    let mut declarations = vec![head_declaration];
+    declarations.extend(tail_declarations);
+    loop {
+        match expression {
+            Expression::Let {
+                let_keyword,
+                box head_declaration,
+                tail_declarations,
+                in_keyword: next_in_keyword,
+                expression: box next_expression,
+            } if !in_keyword.0.has_comments() && !let_keyword.0.has_comments() => {
+                declarations.push(head_declaration);
+                declarations.extend(tail_declarations);
+                in_keyword = next_in_keyword;
+                expression = next_expression;
+            }
+            _ => return (declarations, in_keyword, expression),
+        }
+    }
+}
+
+fn gen_match_arm(arm: MatchArm) -> PrintItems {
+    let MatchArm {
+        pipe,
+        pattern,
+        right_arrow,
+        box expression,
+    } = arm;
+    let mut items = PrintItems::new();
+    items.extend(gen_pipe(pipe));
+    items.extend(space());
+    items.extend(gen_pattern(pattern));
+    items.extend(space());
+
+    let right_arrow_has_trailing_comment = right_arrow.0.has_trailing_comment();
+    items.extend(gen_right_arrow(right_arrow));
+
+    let expression_has_leading_comments = expression.has_leading_comments();
+    items.extend(group(
+        gen_expression(expression),
+        right_arrow_has_trailing_comment || expression_has_leading_comments,
+    ));
+    items
+}
+
+fn gen_pattern(pattern: Pattern) -> PrintItems {
+    match pattern {
+        Pattern::Constructor {
+            constructor,
+            arguments,
+        } => {
+            let mut items = gen_qualified_proper_name(constructor);
+            if let Some(arguments) = arguments {
+                items.extend(gen_parens_list1(
+                    arguments,
+                    |box pattern| gen_pattern(pattern),
+                    false,
+                ));
+            }
+            items
+        }
+        Pattern::RecordConstructor {
+            constructor,
+            fields,
+        } => {
+            let mut items = gen_qualified_proper_name(constructor);
+            items.extend(space());
+            items.extend(gen_record_pattern_fields(fields, gen_record_pattern_field));
+            items
+        }
+        Pattern::Variable { name } => gen_name(name),
+        Pattern::Wildcard(wildcard) => gen_wildcard(wildcard),
+        Pattern::Tuple(parens) => gen_tuple(parens, |box pattern| gen_pattern(pattern)),
+    }
+}
+
+fn gen_record_pattern_field(field: RecordPatternField) -> PrintItems {
+    let RecordPatternField {
+        label,
+        equals,
+        box pattern,
+    } = field;
+    let mut items = PrintItems::new();
+    items.extend(gen_name(label));
+    items.extend(space());
+    items.extend(gen_equals(equals));
+    items.extend(space());
+    items.extend(gen_pattern(pattern));
+    items
+}
+
+fn gen_record_field(field: RecordField) -> PrintItems {
+    let RecordField {
+        label,
+        equals,
+        box value,
+    } = field;
+    let mut items = PrintItems::new();
+    items.extend(gen_name(label));
+    items.extend(space());
+    items.extend(gen_equals(equals));
+    items.extend(space());
+    items.extend(ir_helpers::new_line_group(gen_expression(value)));
+    items
+}
+
 pub fn gen_type_annotation(type_annotation: TypeAnnotation) -> PrintItems {
     let mut items = PrintItems::new();
     items.extend(gen_colon(type_annotation.0));
@@ -176,6 +788,54 @@ pub fn gen_type_annotation(type_annotation: TypeAnnotation) -> PrintItems {
 mod tests {
     use crate::test_macros::assert_expression_fmt as assert_fmt;
 
+    #[test]
+    fn it_formats_record_constructors() {
+        assert_fmt!(r#"Person { name = "Jane", age = 42 }"#);
+        assert_fmt!(r#"Person{name="Jane"}"#, r#"Person { name = "Jane" }"#);
+        assert_fmt!("Some.Thing { x = Just(5) }");
+    }
+
+    #[test]
+    fn it_formats_records() {
+        assert_fmt!(r#"{ name = "Jane", age = 42 }"#);
+        assert_fmt!("{x=1,}", "{ x = 1 }");
+        assert_fmt!("{ x = { y = 5 } }");
+        assert_fmt!("{ name = 1, age = 2 }", "{\n\tname = 1,\n\tage = 2,\n}", 10);
+    }
+
+    #[test]
+    fn it_formats_holes() {
+        assert_fmt!("?help");
+        assert_fmt!("f(?a, ?b)");
+        assert_fmt!("-- comment\n?help");
+    }
+
+    #[test]
+    fn it_formats_inline_js() {
+        assert_fmt!(r#"js "Date.now()" : Int"#);
+        assert_fmt!(r#"js"Date.now()":Int"#, r#"js "Date.now()" : Int"#);
+        assert_fmt!(r#"js "(a, b) => a + b" : (Int, Int) -> Int"#);
+    }
+
+    #[test]
+    fn it_formats_tuples() {
+        assert_fmt!("(a, b)");
+        assert_fmt!("(a,)");
+        assert_fmt!("( a ,b, )", "(a, b)");
+        assert_fmt!("(a ,)", "(a,)");
+        assert_fmt!("((a, b), c)");
+        assert_fmt!("match pair with\n\t| (a, _) -> a\nend");
+        assert_fmt!("match single with\n\t| (a,) -> a\nend");
+    }
+
+    #[test]
+    fn it_formats_record_accesses() {
+        assert_fmt!("person.name");
+        assert_fmt!("a.b.c");
+        assert_fmt!("f(x).a(y)");
+        assert_fmt!("{ a = 5 }.a");
+    }
+
     #[test]
     fn it_formats_empty_arrays() {
         assert_fmt!("[]");
@@ -236,7 +896,16 @@ mod tests {
     #[test]
     fn it_formats_literals() {
         assert_fmt!("\"test\"");
+        assert_fmt!(r#""\"quoted\" \\ \u{1F600}\n""#);
+        assert_fmt!("'t'");
+        assert_fmt!(r"'\n'");
+        assert_fmt!(r"'\''");
+        assert_fmt!(r"'\u{1F600}'");
         assert_fmt!("12345");
+        assert_fmt!("1_000_000");
+        assert_fmt!("0xFF_ff");
+        assert_fmt!("0o755");
+        assert_fmt!("0b1010_1010");
         assert_fmt!("12345.00");
     }
 
@@ -266,6 +935,8 @@ mod tests {
     #[test]
     fn it_formats_functions() {
         assert_fmt!("() -> foo");
+        assert_fmt!("(_, x) -> x");
+        assert_fmt!("(_:Int,_) -> 5", "(_: Int, _) -> 5");
         assert_fmt!(
             "(really_long_argument) -> foo",
             "(really_long_argument) ->\n\tfoo",
@@ -291,6 +962,148 @@ mod tests {
         assert_fmt!("() ->\n\t-- comment\n\t[5]");
     }
 
+    #[test]
+    fn it_formats_lets() {
+        assert_fmt!("let five = 5; in five");
+        assert_fmt!("let  five=5;in\nfive", "let five = 5; in five");
+        assert_fmt!("let five = 5; in five", "let five = 5; in five", 21);
+        assert_fmt!("let five = 5; in five", "let\n\tfive = 5;\nin\nfive", 20);
+        assert_fmt!(
+            "let five: Int = 5; six = 6; in [five, six]",
+            "let\n\tfive: Int = 5;\n\tsix = 6;\nin\n[five, six]"
+        );
+        assert_fmt!("let  -- comment\n\tfive = 5;\nin\nfive");
+        assert_fmt!("let\n\t-- comment\n\tfive = 5;\nin\nfive");
+        assert_fmt!("let\n\tfive = 5;  -- comment\nin\nfive");
+        assert_fmt!(
+            "let xs = [aaaaaaa, bbbbbbb]; in xs",
+            "let\n\txs = [\n\t\taaaaaaa,\n\t\tbbbbbbb,\n\t];\nin\nxs",
+            20
+        );
+    }
+
+    #[test]
+    fn it_flattens_let_chains() {
+        assert_fmt!(
+            "let a = 1; in let b = 2; in [a, b]",
+            "let\n\ta = 1;\n\tb = 2;\nin\n[a, b]"
+        );
+        assert_fmt!(
+            "let a = 1; in let b = 2; c = 3; in let d = 4; in [a, b, c, d]",
+            "let\n\ta = 1;\n\tb = 2;\n\tc = 3;\n\td = 4;\nin\n[a, b, c, d]"
+        );
+        assert_fmt!(
+            "let a = 1; in let b = 2; in let c = 3; in let d = 4; in let e = 5; in let f = 6; in let g = 7; in let h = 8; in h",
+            "let\n\ta = 1;\n\tb = 2;\n\tc = 3;\n\td = 4;\n\te = 5;\n\tf = 6;\n\tg = 7;\n\th = 8;\nin\nh"
+        );
+        assert_fmt!(
+            "let a = 1; in let b = 2; in let c = 3; in let d = 4; in let e = 5; in let f = 6; in let g = 7; in let h = 8; in h",
+            "let\n\ta = 1;\n\tb = 2;\n\tc = 3;\n\td = 4;\n\te = 5;\n\tf = 6;\n\tg = 7;\n\th = 8;\nin\nh",
+            10
+        );
+        // Comments between links break the chain
+        assert_fmt!(
+            "let a = 1; in\n-- comment\nlet b = 2; in let c = 3; in [a, b, c]",
+            "let\n\ta = 1;\nin\n-- comment\nlet\n\tb = 2;\n\tc = 3;\nin\n[a, b, c]"
+        );
+        assert_fmt!(
+            "let a = 1; in  -- comment\nlet b = 2; in b",
+            "let\n\ta = 1;\nin  -- comment\nlet b = 2; in b"
+        );
+    }
+
+    #[test]
+    fn it_formats_matches() {
+        assert_fmt!("match x with\n\t| y -> y\nend");
+        assert_fmt!(
+            "match maybe with | Just(a) -> a | Nothing -> 0 end",
+            "match maybe with\n\t| Just(a) -> a\n\t| Nothing -> 0\nend"
+        );
+        assert_fmt!(
+            "match  pair  with | Pair( a,_ ) -> a end",
+            "match pair with\n\t| Pair(a, _) -> a\nend"
+        );
+        assert_fmt!(
+            "match person with | Person {name=n,..} -> n end",
+            "match person with\n\t| Person { name = n, .. } -> n\nend"
+        );
+        assert_fmt!(
+            "match shape with | Circle { radius = r, } -> r end",
+            "match shape with\n\t| Circle { radius = r } -> r\nend"
+        );
+        assert_fmt!("match x with\n\t-- comment\n\t| _ -> unit\nend");
+        assert_fmt!(
+            "match x with | _ -> loooooooooooooooooong end",
+            "match x with\n\t| _ ->\n\t\tloooooooooooooooooong\nend",
+            20
+        );
+    }
+
+    #[test]
+    fn it_formats_negation() {
+        assert_fmt!("-5");
+        assert_fmt!("- 5", "-5");
+        assert_fmt!("-x.y");
+        assert_fmt!("- -x");
+        assert_fmt!("-(-x)");
+        assert_fmt!("a - -b");
+        assert_fmt!("-  -- comment\n\t5");
+        assert_fmt!("- -- comment\n5", "-  -- comment\n\t5");
+        assert_fmt!("-- comment\n-5");
+    }
+
+    #[test]
+    fn it_formats_binary_operators() {
+        assert_fmt!("1 + 2");
+        assert_fmt!("1+2*3-4/5", "1 + 2 * 3 - 4 / 5");
+        assert_fmt!("(1 + 2) * 3");
+        assert_fmt!("f(x) - g(y)");
+        assert_fmt!("looooooong + looooooong", "looooooong +\n\tlooooooong", 15);
+        assert_fmt!("a +  -- comment\n\tb");
+        assert_fmt!("1+2==3", "1 + 2 == 3");
+        assert_fmt!("a != b");
+        assert_fmt!("a<b", "a < b");
+        assert_fmt!("a <= b");
+        assert_fmt!("a>b", "a > b");
+        assert_fmt!("a >= b");
+    }
+
+    #[test]
+    fn it_formats_boolean_operators() {
+        assert_fmt!("a&&b", "a && b");
+        assert_fmt!("a || b");
+        assert_fmt!("a || b && c");
+        assert_fmt!("a == 1 && b < 2 || c");
+        assert_fmt!(
+            "looooooong && looooooong && looooooong",
+            "looooooong &&\n\tlooooooong &&\n\tlooooooong",
+            20
+        );
+        assert_fmt!(
+            "looooooong || looooooong && looooooong",
+            "looooooong ||\n\tlooooooong && looooooong",
+            30
+        );
+        assert_fmt!("a &&  -- comment\n\tb &&\n\tc");
+        assert_fmt!("a &&\n\t-- comment\n\tb");
+    }
+
+    #[test]
+    fn it_formats_pipes() {
+        assert_fmt!("x|>f", "x |> f");
+        assert_fmt!("x |> f |> g(y)");
+        assert_fmt!("a && b |> f");
+        assert_fmt!(
+            "looooooong |> looooooong |> looooooong",
+            "looooooong\n\t|> looooooong\n\t|> looooooong",
+            20
+        );
+        assert_fmt!("x\n\t-- comment\n\t|> f\n\t|> g");
+        assert_fmt!("x\n\t|>  -- comment\n\tf");
+        assert_fmt!("x |> f -- comment\n|> g", "x\n\t|> f  -- comment\n\t|> g");
+        assert_fmt!("x |> f |> g  -- comment");
+    }
+
     #[test]
     fn it_formats_conditionals() {
         assert_fmt!("if true then 5 else 5");
@@ -303,5 +1116,29 @@ mod tests {
             "if true then\n\tloooooooooooooooooong\nelse\n\t5",
             20
         );
+        assert_fmt!("[if true then 5 else 5]", "[if true then 5 else 5]", 24);
+        assert_fmt!(
+            "[if true then 5 else 5, 5]",
+            "[\n\tif true then\n\t\t5\n\telse\n\t\t5,\n\t5,\n]",
+            20
+        );
+    }
+
+    #[test]
+    fn it_formats_else_if_chains() {
+        assert_fmt!("if a then 1 else if b then 2 else if c then 3 else 4");
+        assert_fmt!(
+            "if a then 1 else if b then 2 else if c then 3 else 4",
+            "if a then\n\t1\nelse if b then\n\t2\nelse if c then\n\t3\nelse\n\t4",
+            20
+        );
+        assert_fmt!(
+            "if a then\n\t1\nelse\n\tif b then\n\t\t2\n\telse\n\t\tif c then\n\t\t\t3\n\t\telse\n\t\t\t4",
+            "if a then\n\t1\nelse if b then\n\t2\nelse if c then\n\t3\nelse\n\t4",
+            20
+        );
+        assert_fmt!(
+            "if a then\n\t1\n-- comment\nelse if b then\n\t2\nelse if c then\n\t3\nelse\n\t4"
+        );
     }
 }
@@ -21,12 +21,23 @@ impl HasComments for Expression {
             Self::False(keyword) => keyword.0.has_comments(),
             Self::Unit(keyword) => keyword.0.has_comments(),
             Self::String(token) => token.has_comments(),
+            Self::Char(token) => token.has_comments(),
+            Self::Hole(token) => token.has_comments(),
             Self::Int(token) => token.has_comments(),
             Self::Float(token) => token.has_comments(),
             Self::Constructor(constructor) => constructor.has_comments(),
+            Self::RecordConstructor {
+                constructor,
+                fields,
+            } => constructor.has_comments() || fields.has_comments(),
+            Self::Record(braces) => braces.has_comments(),
+            Self::RecordAccess { target, dot, label } => {
+                target.has_comments() || dot.0.has_comments() || label.has_comments()
+            }
             Self::Variable(variable) => variable.has_comments(),
             Self::Parens(parens) => parens.has_comments(),
             Self::Array(brackets) => brackets.has_comments(),
+            Self::Tuple(parens) => parens.has_comments(),
             Self::If {
                 if_keyword,
                 condition,
@@ -42,6 +53,34 @@ impl HasComments for Expression {
                     || else_keyword.0.has_comments()
                     || false_clause.has_comments()
             }
+            Self::Let {
+                let_keyword,
+                head_declaration,
+                tail_declarations,
+                in_keyword,
+                expression,
+            } => {
+                let_keyword.0.has_comments()
+                    || head_declaration.has_comments()
+                    || tail_declarations.iter().any(|decl| decl.has_comments())
+                    || in_keyword.0.has_comments()
+                    || expression.has_comments()
+            }
+            Self::Match {
+                match_keyword,
+                expression,
+                with_keyword,
+                head_arm,
+                tail_arms,
+                end_keyword,
+            } => {
+                match_keyword.0.has_comments()
+                    || expression.has_comments()
+                    || with_keyword.0.has_comments()
+                    || head_arm.has_comments()
+                    || tail_arms.has_comments()
+                    || end_keyword.0.has_comments()
+            }
             Self::Function {
                 parameters,
                 return_type_annotation,
@@ -57,6 +96,19 @@ impl HasComments for Expression {
                 function,
                 arguments,
             } => function.has_comments() || arguments.has_comments(),
+            Self::BinOp { lhs, operator, rhs } => {
+                lhs.has_comments() || operator.has_comments() || rhs.has_comments()
+            }
+            Self::Negate { minus, expression } => {
+                minus.0.has_comments() || expression.has_comments()
+            }
+            Self::InlineJs {
+                js_keyword,
+                code,
+                type_annotation,
+            } => {
+                js_keyword.0.has_comments() || code.has_comments() || type_annotation.has_comments()
+            }
         }
     }
 
@@ -66,15 +118,89 @@ impl HasComments for Expression {
             Self::False(keyword) => keyword.0.has_leading_comments(),
             Self::Unit(keyword) => keyword.0.has_leading_comments(),
             Self::String(token) => token.has_leading_comments(),
+            Self::Char(token) => token.has_leading_comments(),
+            Self::Hole(token) => token.has_leading_comments(),
             Self::Int(token) => token.has_leading_comments(),
             Self::Float(token) => token.has_leading_comments(),
             Self::Constructor(constructor) => constructor.has_leading_comments(),
+            Self::RecordConstructor { constructor, .. } => constructor.has_leading_comments(),
+            Self::Record(braces) => braces.open_brace.0.has_leading_comments(),
+            Self::RecordAccess { target, .. } => target.has_leading_comments(),
             Self::Variable(variable) => variable.has_leading_comments(),
             Self::Parens(parens) => parens.open_paren.0.has_leading_comments(),
             Self::Array(brackets) => brackets.open_bracket.0.has_leading_comments(),
+            Self::Tuple(parens) => parens.open_paren.0.has_leading_comments(),
             Self::If { if_keyword, .. } => if_keyword.0.has_leading_comments(),
+            Self::Let { let_keyword, .. } => let_keyword.0.has_leading_comments(),
+            Self::Match { match_keyword, .. } => match_keyword.0.has_leading_comments(),
             Self::Function { box parameters, .. } => parameters.open_paren.0.has_leading_comments(),
             Self::Call { function, .. } => function.has_leading_comments(),
+            Self::BinOp { lhs, .. } => lhs.has_leading_comments(),
+            Self::Negate { minus, .. } => minus.0.has_leading_comments(),
+            Self::InlineJs { js_keyword, .. } => js_keyword.0.has_leading_comments(),
+        }
+    }
+}
+
+impl HasComments for BinOp {
+    fn has_comments(&self) -> bool {
+        self.token().has_comments()
+    }
+    fn has_leading_comments(&self) -> bool {
+        self.token().has_leading_comments()
+    }
+}
+
+impl HasComments for MatchArm {
+    fn has_comments(&self) -> bool {
+        self.pipe.0.has_comments()
+            || self.pattern.has_comments()
+            || self.right_arrow.0.has_comments()
+            || self.expression.has_comments()
+    }
+    fn has_leading_comments(&self) -> bool {
+        self.pipe.0.has_leading_comments()
+    }
+}
+
+impl HasComments for Pattern {
+    fn has_comments(&self) -> bool {
+        match self {
+            Self::Constructor {
+                constructor,
+                arguments,
+            } => constructor.has_comments() || arguments.has_comments(),
+            Self::RecordConstructor {
+                constructor,
+                fields,
+            } => constructor.has_comments() || fields.has_comments(),
+            Self::Variable { name } => name.has_comments(),
+            Self::Wildcard(wildcard) => wildcard.0.has_comments(),
+            Self::Tuple(parens) => parens.has_comments(),
+        }
+    }
+    fn has_leading_comments(&self) -> bool {
+        match self {
+            Self::Constructor { constructor, .. } => constructor.has_leading_comments(),
+            Self::RecordConstructor { constructor, .. } => constructor.has_leading_comments(),
+            Self::Variable { name } => name.has_leading_comments(),
+            Self::Wildcard(wildcard) => wildcard.0.has_leading_comments(),
+            Self::Tuple(parens) => parens.open_paren.0.has_leading_comments(),
+        }
+    }
+}
+
+impl HasComments for FunctionBinder {
+    fn has_comments(&self) -> bool {
+        match self {
+            Self::Name(name) => name.has_comments(),
+            Self::Wildcard(wildcard) => wildcard.0.has_comments(),
+        }
+    }
+    fn has_leading_comments(&self) -> bool {
+        match self {
+            Self::Name(name) => name.has_leading_comments(),
+            Self::Wildcard(wildcard) => wildcard.0.has_leading_comments(),
         }
     }
 }
@@ -84,6 +210,7 @@ impl HasComments for Type {
         match self {
             Self::Parens(parens) => parens.has_comments(),
             Self::Variable(variable) => variable.has_comments(),
+            Self::Wildcard(wildcard) => wildcard.0.has_comments(),
             Self::Constructor(constructor) => constructor.has_comments(),
             Self::Function {
                 parameters,
@@ -98,15 +225,20 @@ impl HasComments for Type {
                 function,
                 arguments,
             } => function.has_comments() || arguments.has_comments(),
+            Self::Record(braces) => braces.has_comments(),
+            Self::Tuple(parens) => parens.has_comments(),
         }
     }
     fn has_leading_comments(&self) -> bool {
         match self {
             Self::Parens(parens) => parens.open_paren.0.has_leading_comments(),
             Self::Variable(variable) => variable.has_leading_comments(),
+            Self::Wildcard(wildcard) => wildcard.0.has_leading_comments(),
             Self::Constructor(constructor) => constructor.has_leading_comments(),
             Self::Function { parameters, .. } => parameters.open_paren.0.has_leading_comments(),
             Self::Call { function, .. } => function.has_leading_comments(),
+            Self::Record(braces) => braces.open_brace.0.has_leading_comments(),
+            Self::Tuple(parens) => parens.open_paren.0.has_leading_comments(),
         }
     }
 }
@@ -126,6 +258,25 @@ impl HasComments for TypeCallFunction {
     }
 }
 
+impl HasComments for ValueDeclaration {
+    fn has_comments(&self) -> bool {
+        self.attributes
+            .iter()
+            .any(|attribute| attribute.at.0.has_comments() || attribute.name.has_comments())
+            || self.name.has_comments()
+            || self.type_annotation.has_comments()
+            || self.equals.0.has_comments()
+            || self.expression.has_comments()
+            || self.semicolon.0.has_comments()
+    }
+    fn has_leading_comments(&self) -> bool {
+        match self.attributes.first() {
+            Some(attribute) => attribute.at.0.has_leading_comments(),
+            None => self.name.has_leading_comments(),
+        }
+    }
+}
+
 impl HasComments for TypeAnnotation {
     fn has_comments(&self) -> bool {
         self.0 .0.has_comments() || self.1.has_comments()
@@ -146,6 +297,62 @@ impl<T: HasComments> HasComments for Parens<T> {
     }
 }
 
+impl HasComments for RecordField {
+    fn has_comments(&self) -> bool {
+        self.label.has_comments() || self.equals.0.has_comments() || self.value.has_comments()
+    }
+    fn has_leading_comments(&self) -> bool {
+        self.label.has_leading_comments()
+    }
+}
+
+impl HasComments for RecordPatternFields {
+    fn has_comments(&self) -> bool {
+        self.fields.has_comments() || self.rest.has_comments()
+    }
+    fn has_leading_comments(&self) -> bool {
+        self.fields.has_leading_comments()
+    }
+}
+
+impl HasComments for RecordPatternField {
+    fn has_comments(&self) -> bool {
+        self.label.has_comments() || self.equals.0.has_comments() || self.pattern.has_comments()
+    }
+    fn has_leading_comments(&self) -> bool {
+        self.label.has_leading_comments()
+    }
+}
+
+impl HasComments for ConstructorField {
+    fn has_comments(&self) -> bool {
+        self.label.has_comments() || self.type_annotation.has_comments()
+    }
+    fn has_leading_comments(&self) -> bool {
+        self.label.has_leading_comments()
+    }
+}
+
+impl HasComments for RecordTypeField {
+    fn has_comments(&self) -> bool {
+        self.label.has_comments() || self.type_annotation.has_comments()
+    }
+    fn has_leading_comments(&self) -> bool {
+        self.label.has_leading_comments()
+    }
+}
+
+impl<T: HasComments> HasComments for Braces<T> {
+    fn has_comments(&self) -> bool {
+        self.open_brace.0.has_comments()
+            || self.value.has_comments()
+            || self.close_brace.0.has_comments()
+    }
+    fn has_leading_comments(&self) -> bool {
+        self.open_brace.0.has_leading_comments()
+    }
+}
+
 impl<T: HasComments> HasComments for Brackets<T> {
     fn has_comments(&self) -> bool {
         self.open_bracket.0.has_comments()
@@ -242,12 +449,31 @@ impl HasComments for Export {
             Self::Type(proper_name, everything) => {
                 proper_name.has_comments() || everything.has_comments()
             }
+            Self::Module(module_keyword, module_name) => {
+                module_keyword.0.has_comments() || module_name.has_comments()
+            }
         }
     }
     fn has_leading_comments(&self) -> bool {
         match self {
             Self::Value(name) => name.has_leading_comments(),
             Self::Type(proper_name, _everything) => proper_name.has_leading_comments(),
+            Self::Module(module_keyword, _module_name) => module_keyword.0.has_leading_comments(),
+        }
+    }
+}
+
+impl HasComments for ModuleName {
+    fn has_comments(&self) -> bool {
+        self.init
+            .iter()
+            .any(|(proper_name, dot)| proper_name.has_comments() || dot.has_comments())
+            || self.last.has_comments()
+    }
+    fn has_leading_comments(&self) -> bool {
+        match self.init.first() {
+            Some((proper_name, _dot)) => proper_name.has_leading_comments(),
+            None => self.last.has_leading_comments(),
         }
     }
 }
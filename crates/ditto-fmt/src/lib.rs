@@ -13,30 +13,72 @@ mod syntax;
 mod token;
 mod r#type;
 
-use config::{INDENT_WIDTH, MAX_WIDTH, NEWLINE};
+use config::{with_options, INDENT_WIDTH, NEWLINE};
+pub use ditto_config::FmtOptions;
 
 /// Pretty-print a CST module.
-pub fn format_module(module: ditto_cst::Module) -> String {
-    dprint_core::formatting::format(
-        || module::gen_module(module),
-        dprint_core::formatting::PrintOptions {
-            // NOTE these _aren't_ configurable!
-            // Nobody needs a configurable formatter...
-            // "Gofmt's style is no one's favorite, yet gofmt is everyone's favorite" — Rob Pike.
-            indent_width: INDENT_WIDTH,
-            max_width: MAX_WIDTH,
-            use_tabs: false, // nah
-            new_line_text: NEWLINE,
-        },
-    )
+///
+/// The `source` the module was parsed from is needed to see where
+/// declarations were separated by blank lines.
+pub fn format_module(module: ditto_cst::Module, source: &str) -> String {
+    format_module_with_options(module, source, FmtOptions::default())
+}
+
+/// Pretty-print a CST module using the given preset options.
+pub fn format_module_with_options(
+    module: ditto_cst::Module,
+    source: &str,
+    options: FmtOptions,
+) -> String {
+    with_options(options, || {
+        dprint_core::formatting::format(
+            || module::gen_module(module, source, options.blank_lines_between_declarations),
+            dprint_core::formatting::PrintOptions {
+                // NOTE only the max width is configurable, via presets.
+                // "Gofmt's style is no one's favorite, yet gofmt is everyone's favorite" — Rob Pike.
+                indent_width: INDENT_WIDTH,
+                max_width: options.max_width,
+                use_tabs: false, // nah
+                new_line_text: NEWLINE,
+            },
+        )
+    })
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::FmtOptions;
+
     #[snapshot_test::snapshot(input = "golden-tests/(.*).ditto")]
     fn golden(input: &str) -> String {
+        assert_idempotent_fmt(input, FmtOptions::default())
+    }
+
+    #[snapshot_test::snapshot(input = "golden-tests/compact/(.*).ditto")]
+    fn golden_compact(input: &str) -> String {
+        assert_idempotent_fmt(input, ditto_config::FmtStyle::Compact.options())
+    }
+
+    #[snapshot_test::snapshot(input = "golden-tests/sorted/(.*).ditto")]
+    fn golden_sorted(input: &str) -> String {
+        assert_idempotent_fmt(
+            input,
+            FmtOptions {
+                sort_lists: true,
+                ..FmtOptions::default()
+            },
+        )
+    }
+
+    /// Formats the input, and checks that formatting the output again doesn't change it.
+    fn assert_idempotent_fmt(input: &str, options: FmtOptions) -> String {
         let cst_module = ditto_cst::Module::parse(input).unwrap();
-        crate::format_module(cst_module)
+        let formatted = crate::format_module_with_options(cst_module, input, options);
+
+        let cst_module = ditto_cst::Module::parse(&formatted).unwrap();
+        let reformatted = crate::format_module_with_options(cst_module, &formatted, options);
+        similar_asserts::assert_str_eq!(formatted: &formatted, reformatted: &reformatted);
+        formatted
     }
 }
 
@@ -70,6 +112,11 @@ mod test_macros {
             let items =
                 $crate::expression::gen_expression(ditto_cst::Expression::parse($source).unwrap());
             $crate::test_macros::assert_fmt!(items, $source, $want, $max_width);
+
+            // Formatting should be idempotent
+            let items =
+                $crate::expression::gen_expression(ditto_cst::Expression::parse($want).unwrap());
+            $crate::test_macros::assert_fmt!(items, $want, $want, $max_width);
         }};
     }
 
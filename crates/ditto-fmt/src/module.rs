@@ -2,16 +2,23 @@ use super::{
     declaration::gen_declaration,
     helpers::space,
     name::{gen_module_name, gen_name, gen_package_name, gen_proper_name},
-    syntax::{gen_parens, gen_parens_list1},
+    syntax::{gen_parens, gen_sorted_parens_list1},
     token::{
-        gen_as_keyword, gen_close_paren, gen_double_dot, gen_exports_keyword, gen_import_keyword,
-        gen_module_keyword, gen_open_paren, gen_semicolon,
+        gen_as_keyword, gen_at, gen_close_paren, gen_double_dot, gen_exports_keyword,
+        gen_import_keyword, gen_module_keyword, gen_open_paren, gen_semicolon,
     },
 };
-use ditto_cst::{Everything, Export, Exports, Header, Import, ImportLine, ImportList, Module};
+use ditto_cst::{
+    Comment, Declaration, Everything, Export, Exports, Header, Import, ImportLine, ImportList,
+    Module, ModuleName,
+};
 use dprint_core::formatting::{PrintItems, Signal};
 
-pub fn gen_module(module: Module) -> PrintItems {
+pub fn gen_module(
+    module: Module,
+    source: &str,
+    blank_lines_between_declarations: usize,
+) -> PrintItems {
     let mut items = PrintItems::new();
     items.extend(gen_module_header(module.header));
     items.push_signal(Signal::NewLine);
@@ -55,9 +62,25 @@ pub fn gen_module(module: Module) -> PrintItems {
 
     let module_declarations_empty = module.declarations.is_empty();
     let declarations_len = module.declarations.len();
-    for declaration in module.declarations {
-        items.push_signal(Signal::NewLine);
+    let separated_declarations = module
+        .declarations
+        .windows(2)
+        .map(|pair| has_blank_line_between(source, &pair[0], &pair[1]))
+        .collect::<Vec<_>>();
+    for (i, declaration) in module.declarations.into_iter().enumerate() {
         items.push_signal(Signal::NewLine);
+        // Always separate the first declaration from the header,
+        // otherwise only keep the declarations apart if they were apart in the source
+        let blank_lines = if i == 0 {
+            1
+        } else if separated_declarations[i - 1] {
+            blank_lines_between_declarations
+        } else {
+            0
+        };
+        for _ in 0..blank_lines {
+            items.push_signal(Signal::NewLine);
+        }
         items.extend(gen_declaration(declaration));
     }
 
@@ -77,8 +100,35 @@ pub fn gen_module(module: Module) -> PrintItems {
     items
 }
 
+/// Was there at least one blank line between two declarations in the original source?
+///
+/// Comments between the declarations don't count as blank lines, they'll
+/// be kept attached to the second declaration.
+fn has_blank_line_between(source: &str, previous: &Declaration, next: &Declaration) -> bool {
+    let start = previous.get_span().end_offset;
+    let end = next.get_span().start_offset;
+    let gap = match source.get(start..end) {
+        Some(gap) => gap,
+        // Spans don't line up with the source, just keep things tidy
+        None => return true,
+    };
+    // The first line is the rest of the line containing the previous declaration,
+    // and the last line is any indentation before the next declaration.
+    let lines = gap.split('\n').collect::<Vec<_>>();
+    lines.len() > 2
+        && lines[1..lines.len() - 1]
+            .iter()
+            .any(|line| line.trim().is_empty())
+}
+
 fn gen_module_header(header: Header) -> PrintItems {
     let mut items = PrintItems::new();
+    // Attributes go on their own lines, above the `module` keyword
+    for attribute in header.attributes {
+        items.extend(gen_at(attribute.at));
+        items.extend(gen_name(attribute.name));
+        items.push_signal(Signal::NewLine);
+    }
     items.extend(gen_module_keyword(header.module_keyword));
     items.extend(space());
     items.extend(gen_module_name(header.module_name));
@@ -93,7 +143,19 @@ fn gen_module_header(header: Header) -> PrintItems {
 fn gen_exports(exports: Exports) -> PrintItems {
     match exports {
         Exports::Everything(everything) => gen_everything(everything),
-        Exports::List(box list) => gen_parens_list1(list, gen_export, true),
+        Exports::Nothing(nothing) => {
+            let mut items = PrintItems::new();
+            items.extend(gen_open_paren(nothing.open_paren));
+            items.extend(gen_close_paren(nothing.close_paren));
+            items
+        }
+        Exports::List(box list) => gen_sorted_parens_list1(
+            list,
+            gen_export,
+            true,
+            export_sort_key,
+            export_leading_comments,
+        ),
     }
 }
 
@@ -101,6 +163,33 @@ fn gen_everything(everything: Everything) -> PrintItems {
     gen_parens(everything, gen_double_dot)
 }
 
+/// Module re-exports come first, then everything else alphabetically.
+fn export_sort_key(export: &Export) -> (bool, String) {
+    match export {
+        Export::Module(_module_keyword, module_name) => (false, module_name_sort_key(module_name)),
+        Export::Value(name) => (true, name.0.value.clone()),
+        Export::Type(proper_name, _everything) => (true, proper_name.0.value.clone()),
+    }
+}
+
+fn export_leading_comments(export: &mut Export) -> &mut Vec<Comment> {
+    match export {
+        Export::Module(module_keyword, _module_name) => &mut module_keyword.0.leading_comments,
+        Export::Value(name) => &mut name.0.leading_comments,
+        Export::Type(proper_name, _everything) => &mut proper_name.0.leading_comments,
+    }
+}
+
+fn module_name_sort_key(module_name: &ModuleName) -> String {
+    module_name
+        .init
+        .iter()
+        .map(|(proper_name, _dot)| proper_name.0.value.as_str())
+        .chain(std::iter::once(module_name.last.0.value.as_str()))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
 fn gen_export(export: Export) -> PrintItems {
     match export {
         Export::Value(name) => gen_name(name),
@@ -112,6 +201,13 @@ fn gen_export(export: Export) -> PrintItems {
             }
             items
         }
+        Export::Module(module_keyword, module_name) => {
+            let mut items = PrintItems::new();
+            items.extend(gen_module_keyword(module_keyword));
+            items.extend(space());
+            items.extend(gen_module_name(module_name));
+            items
+        }
     }
 }
 
@@ -141,7 +237,27 @@ fn gen_import_line(import_line: ImportLine) -> PrintItems {
 }
 
 fn gen_import_list(import_list: ImportList) -> PrintItems {
-    gen_parens_list1(import_list.0, gen_import, true)
+    gen_sorted_parens_list1(
+        import_list.0,
+        gen_import,
+        true,
+        import_sort_key,
+        import_leading_comments,
+    )
+}
+
+fn import_sort_key(import: &Import) -> String {
+    match import {
+        Import::Value(name) => name.0.value.clone(),
+        Import::Type(proper_name, _everything) => proper_name.0.value.clone(),
+    }
+}
+
+fn import_leading_comments(import: &mut Import) -> &mut Vec<Comment> {
+    match import {
+        Import::Value(name) => &mut name.0.leading_comments,
+        Import::Type(proper_name, _everything) => &mut proper_name.0.leading_comments,
+    }
 }
 
 fn gen_import(import: Import) -> PrintItems {
@@ -179,6 +295,8 @@ mod tests {
         fn it_formats_module_headers() {
             assert_fmt!("module Test exports (..);");
             assert_fmt!("module Foo.Bar.Baz exports (..);");
+            assert_fmt!("module Main exports ();");
+            assert_fmt!("module Main exports (  );", "module Main exports ();");
             assert_fmt!("module T exports (foo);", "module T exports (\n\tfoo,\n);");
             assert_fmt!(
                 "module T exports (foo,bar,baz);",
@@ -196,6 +314,12 @@ mod tests {
 
             assert_fmt!("module T exports (foo,);", "module T exports (\n\tfoo,\n);");
             assert_fmt!("-- comment\nmodule Test exports (..);");
+            assert_fmt!("@no_optimize\nmodule Test exports (..);");
+            assert_fmt!(
+                "@no_optimize module Test exports (..);",
+                "@no_optimize\nmodule Test exports (..);"
+            );
+            assert_fmt!("-- comment\n@no_optimize\nmodule Test exports (..);");
             assert_fmt!("module  -- comment\n Test exports (..);");
             assert_fmt!("module Test  -- comment\n exports (..);");
             assert_fmt!("module Test exports  -- comment\n (..);");
@@ -220,6 +344,120 @@ mod tests {
                 "module T exports (\n\tfoo,\n\t-- comment\n);"
             );
         }
+
+        #[test]
+        fn it_formats_module_reexports() {
+            assert_fmt!(
+                "module Prelude exports (five, module Data.Maybe, Five, module   Data.Array);",
+                "module Prelude exports (\n\tfive,\n\tmodule Data.Maybe,\n\tFive,\n\tmodule Data.Array,\n);"
+            );
+            assert_fmt!("module Prelude exports (\n\tmodule  -- comment\n\t Data.Maybe,\n);");
+            assert_fmt!("module Prelude exports (\n\t-- comment\n\tmodule Data.Maybe,\n);");
+        }
+
+        macro_rules! assert_sorted_fmt {
+            ($source:expr, $want:expr) => {{
+                let options = ditto_config::FmtOptions {
+                    sort_lists: true,
+                    ..Default::default()
+                };
+                let items = $crate::config::with_options(options, || {
+                    $crate::module::gen_module_header(ditto_cst::Header::parse($source).unwrap())
+                });
+                $crate::test_macros::assert_fmt!(items, $source, $want, $crate::config::MAX_WIDTH);
+            }};
+        }
+
+        #[test]
+        fn it_sorts_exports_with_their_comments() {
+            assert_sorted_fmt!(
+                "module T exports (foo, bar);",
+                "module T exports (\n\tbar,\n\tfoo,\n);"
+            );
+            assert_sorted_fmt!(
+                "module Prelude exports (five, module Data.Maybe, Five, module   Data.Array);",
+                "module Prelude exports (\n\tmodule Data.Array,\n\tmodule Data.Maybe,\n\tFive,\n\tfive,\n);"
+            );
+            assert_sorted_fmt!(
+                "module T exports (\n\tfoo,  -- why foo\n\tbar,  -- why bar\n);",
+                "module T exports (\n\tbar,  -- why bar\n\tfoo,  -- why foo\n);"
+            );
+            assert_sorted_fmt!(
+                "module T exports (foo,  -- why foo\n bar);",
+                "module T exports (\n\tbar,\n\tfoo,  -- why foo\n);"
+            );
+            assert_sorted_fmt!(
+                "module T exports (\n\tfoo,\n\tBar(..),  -- why Bar\n\t-- footer\n);",
+                "module T exports (\n\tBar(..),  -- why Bar\n\tfoo,\n\t-- footer\n);"
+            );
+        }
+
+        #[test]
+        fn it_sorts_exports_within_commented_sections() {
+            assert_sorted_fmt!(
+                "module T exports (\n\t-- why foo\n\tfoo,\n\t-- why bar\n\tbar,\n);",
+                "module T exports (\n\t-- why foo\n\tfoo,\n\t-- why bar\n\tbar,\n);"
+            );
+            assert_sorted_fmt!(
+                "module T exports (\n\tzed,\n\t-- ## Values\n\tfoo,\n\tbar,\n\t-- ## Types\n\tBaz,\n\tBar(..),\n);",
+                "module T exports (\n\tzed,\n\t-- ## Values\n\tbar,\n\tfoo,\n\t-- ## Types\n\tBar(..),\n\tBaz,\n);"
+            );
+        }
+    }
+
+    mod declarations {
+        macro_rules! assert_fmt {
+            ($source:expr, $want:expr) => {{
+                assert_fmt!($source, $want, ditto_config::FmtOptions::default())
+            }};
+            ($source:expr, $want:expr, $options:expr) => {{
+                let module = ditto_cst::Module::parse($source).unwrap();
+                let formatted = $crate::format_module_with_options(module, $source, $options);
+                similar_asserts::assert_str_eq!(got: formatted, want: $want);
+            }};
+        }
+
+        #[test]
+        fn it_keeps_blank_lines_between_declarations() {
+            assert_fmt!(
+                "module Test exports (..);\nfive = 5;\nsix = 6;\n\nseven = 7;",
+                "module Test exports (..);\n\n\nfive = 5;\nsix = 6;\n\nseven = 7;\n"
+            );
+            assert_fmt!(
+                "module Test exports (..);\nfive = 5;\n\n\n\nsix = 6;\n  \t\n \nseven = 7;",
+                "module Test exports (..);\n\n\nfive = 5;\n\nsix = 6;\n\nseven = 7;\n"
+            );
+            assert_fmt!(
+                "module Test exports (..);\nfive = 5; six = 6;\ntype Seven = Seven;\n\nforeign eight: Int;",
+                "module Test exports (..);\n\n\nfive = 5;\nsix = 6;\ntype Seven = Seven;\n\nforeign eight: Int;\n"
+            );
+            assert_fmt!(
+                "module Test exports (..);\nfive = 5;\n\nsix = 6;\nseven = 7;",
+                "module Test exports (..);\n\n\nfive = 5;\nsix = 6;\nseven = 7;\n",
+                ditto_config::FmtStyle::Compact.options()
+            );
+            assert_fmt!(
+                "module Test exports (..);\nfive = 5;\n\nsix = 6;\nseven = 7;",
+                "module Test exports (..);\n\n\nfive = 5;\n\n\nsix = 6;\nseven = 7;\n",
+                ditto_config::FmtStyle::Expanded.options()
+            );
+        }
+
+        #[test]
+        fn it_keeps_comments_attached_to_declarations() {
+            assert_fmt!(
+                "module Test exports (..);\nfive = 5;  -- five\n-- six\nsix = 6;",
+                "module Test exports (..);\n\n\nfive = 5;  -- five\n-- six\nsix = 6;\n"
+            );
+            assert_fmt!(
+                "module Test exports (..);\nfive = 5;\n\n\n-- six\n-- six\nsix = 6;",
+                "module Test exports (..);\n\n\nfive = 5;\n\n-- six\n-- six\nsix = 6;\n"
+            );
+            assert_fmt!(
+                "module Test exports (..);\nfive = 5;\n-- six\n\nsix = 6;",
+                "module Test exports (..);\n\n\nfive = 5;\n\n-- six\nsix = 6;\n"
+            );
+        }
     }
 
     mod import_lines {
@@ -237,6 +475,19 @@ mod tests {
             }};
         }
 
+        macro_rules! assert_sorted_fmt {
+            ($source:expr, $want:expr) => {{
+                let options = ditto_config::FmtOptions {
+                    sort_lists: true,
+                    ..Default::default()
+                };
+                let items = $crate::config::with_options(options, || {
+                    $crate::module::gen_import_line(ditto_cst::ImportLine::parse($source).unwrap())
+                });
+                $crate::test_macros::assert_fmt!(items, $source, $want, $crate::config::MAX_WIDTH);
+            }};
+        }
+
         #[test]
         fn it_formats_import_lines() {
             assert_fmt!("import Foo;");
@@ -250,7 +501,20 @@ mod tests {
             assert_fmt!("import Foo (\n\tfoo,\n\tBar(..),\n);");
             assert_fmt!("import (pkg) Foo (\n\tfoo,\n\tBar(..),\n);");
             assert_fmt!("import  -- comment\n (pkg) Foo;");
+            assert_fmt!("import Foo as  -- comment\n F;");
             assert_fmt!("import Foo (\n\tBar(  -- comment\n\t\t..\n\t),\n);");
         }
+
+        #[test]
+        fn it_sorts_imports_within_commented_sections() {
+            assert_sorted_fmt!(
+                "import Foo (foo, Bar, baz);",
+                "import Foo (\n\tBar,\n\tbaz,\n\tfoo,\n);"
+            );
+            assert_sorted_fmt!(
+                "import Foo (\n\t-- Used for sorting.\n\tsort_by,\n\tOrdering(..),\n\tfilter,  -- needed by `run`\n);",
+                "import Foo (\n\t-- Used for sorting.\n\tOrdering(..),\n\tfilter,  -- needed by `run`\n\tsort_by,\n);"
+            );
+        }
     }
 }
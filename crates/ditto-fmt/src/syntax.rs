@@ -1,8 +1,15 @@
 use super::{
+    config::{sort_lists, trailing_commas},
     has_comments::HasComments,
-    token::{gen_close_bracket, gen_close_paren, gen_comma, gen_open_bracket, gen_open_paren},
+    token::{
+        gen_close_brace, gen_close_bracket, gen_close_paren, gen_comma, gen_double_dot,
+        gen_open_brace, gen_open_bracket, gen_open_paren,
+    },
+};
+use ditto_cst::{
+    Braces, BracesList1, BracketsList, Comma, CommaSep1, Comment, DoubleDot, Parens, ParensList,
+    ParensList1, RecordPatternField, RecordPatternFields,
 };
-use ditto_cst::{BracketsList, Comma, CommaSep1, Parens, ParensList, ParensList1};
 use dprint_core::formatting::{
     condition_helpers, conditions, ir_helpers, ConditionResolver, ConditionResolverContext, Info,
     PrintItems, Signal,
@@ -84,7 +91,7 @@ where
 
     items.extend(gen_open_paren(parens.open_paren));
     let gen_separated_values_result =
-        gen_comma_sep1_new(parens.value, gen_element, force_use_new_lines);
+        gen_comma_sep1_new(parens.value, gen_element, force_use_new_lines, false);
     let element_items = gen_separated_values_result.items;
     items.extend(element_items);
     items.extend(gen_close_paren(parens.close_paren));
@@ -102,7 +109,7 @@ where
     let mut items = PrintItems::new();
     items.extend(gen_open_bracket(brackets.open_bracket));
     if let Some(elements) = brackets.value {
-        let gen_separated_values_result = gen_comma_sep1_new(elements, gen_element, false);
+        let gen_separated_values_result = gen_comma_sep1_new(elements, gen_element, false, false);
         let element_items = gen_separated_values_result.items;
         items.extend(element_items);
     }
@@ -110,30 +117,235 @@ where
     items
 }
 
+/// Like [gen_parens_list1], but a lone element always keeps its trailing comma
+/// so that it isn't mistaken for parentheses.
+///
+/// ```ditto
+/// (foo,)
+/// (foo, bar)
+/// ```
+pub fn gen_tuple<T, GenElement>(parens: ParensList1<T>, gen_element: GenElement) -> PrintItems
+where
+    T: HasComments + Clone,
+    GenElement: FnOnce(T) -> PrintItems + Copy,
+{
+    let is_single = parens.value.tail.is_empty();
+    let mut items = PrintItems::new();
+    items.extend(gen_open_paren(parens.open_paren));
+    let force_use_new_lines = parens.value.has_comments();
+    let gen_separated_values_result = gen_comma_separated(
+        comma_sep1_elements(parens.value),
+        gen_element,
+        force_use_new_lines,
+        false,
+        if is_single {
+            TrailingComma::Always
+        } else {
+            TrailingComma::IfMultiLine
+        },
+    );
+    items.extend(gen_separated_values_result.items);
+    items.extend(gen_close_paren(parens.close_paren));
+    items
+}
+
+/// Braces are padded with spaces when the list fits on a single line.
+///
+/// ```ditto
+/// { foo, bar }
+/// ```
+pub fn gen_braces_list1<T, GenElement>(
+    braces: BracesList1<T>,
+    gen_element: GenElement,
+) -> PrintItems
+where
+    T: HasComments + Clone,
+    GenElement: FnOnce(T) -> PrintItems + Copy,
+{
+    let mut items = PrintItems::new();
+    items.extend(gen_open_brace(braces.open_brace));
+    let gen_separated_values_result = gen_comma_sep1_new(braces.value, gen_element, false, true);
+    items.extend(gen_separated_values_result.items);
+    items.extend(gen_close_brace(braces.close_brace));
+    items
+}
+
+/// Like [gen_braces_list1], but the fields can end with a `..`, which is laid
+/// out like another element.
+///
+/// ```ditto
+/// { foo = a, .. }
+/// ```
+pub fn gen_record_pattern_fields<GenField>(
+    braces: Braces<RecordPatternFields>,
+    gen_field: GenField,
+) -> PrintItems
+where
+    GenField: FnOnce(RecordPatternField) -> PrintItems + Copy,
+{
+    let force_use_new_lines = braces.value.has_comments();
+    let RecordPatternFields { fields, rest } = braces.value;
+    let mut elements = comma_sep1_elements(fields)
+        .into_iter()
+        .map(|(field, comma)| (RecordPatternElement::Field(field), comma))
+        .collect::<Vec<_>>();
+    let trailing_comma = if let Some(double_dot) = rest {
+        elements.push((RecordPatternElement::Rest(double_dot), None));
+        TrailingComma::Never
+    } else {
+        TrailingComma::IfMultiLine
+    };
+
+    let mut items = PrintItems::new();
+    items.extend(gen_open_brace(braces.open_brace));
+    let gen_separated_values_result = gen_comma_separated(
+        elements,
+        |element| match element {
+            RecordPatternElement::Field(field) => gen_field(field),
+            RecordPatternElement::Rest(double_dot) => gen_double_dot(double_dot),
+        },
+        force_use_new_lines,
+        true,
+        trailing_comma,
+    );
+    items.extend(gen_separated_values_result.items);
+    items.extend(gen_close_brace(braces.close_brace));
+    items
+}
+
+enum RecordPatternElement {
+    Field(RecordPatternField),
+    Rest(DoubleDot),
+}
+
+/// Like [gen_parens_list1], but the elements are sorted by `sort_key` if
+/// sorting is enabled.
+///
+/// Leading comments (found with `leading_comments`) start a new section, and
+/// elements are only sorted within their section. The comments stay at the top
+/// of their section, so they keep heading the same group of elements. Each
+/// element is moved along with the comma that followed it, so comments trailing
+/// its comma stay with that element.
+pub fn gen_sorted_parens_list1<T, GenElement, SortKey, Key>(
+    parens: ParensList1<T>,
+    gen_element: GenElement,
+    force_use_new_lines: bool,
+    sort_key: SortKey,
+    leading_comments: fn(&mut T) -> &mut Vec<Comment>,
+) -> PrintItems
+where
+    T: HasComments + Clone,
+    GenElement: FnOnce(T) -> PrintItems + Copy,
+    SortKey: Fn(&T) -> Key,
+    Key: Ord,
+{
+    let mut items = PrintItems::new();
+
+    items.extend(gen_open_paren(parens.open_paren));
+    let force_use_new_lines = force_use_new_lines || parens.value.has_comments();
+    let mut elements = comma_sep1_elements(parens.value);
+    if sort_lists() {
+        let mut section_start = 0;
+        for i in 1..=elements.len() {
+            if i < elements.len() && leading_comments(&mut elements[i].0).is_empty() {
+                continue;
+            }
+            let section = &mut elements[section_start..i];
+            let heading = std::mem::take(leading_comments(&mut section[0].0));
+            section.sort_by_key(|(element, _comma)| sort_key(element));
+            *leading_comments(&mut section[0].0) = heading;
+            section_start = i;
+        }
+    }
+    let gen_separated_values_result = gen_comma_separated(
+        elements,
+        gen_element,
+        force_use_new_lines,
+        false,
+        TrailingComma::IfMultiLine,
+    );
+    items.extend(gen_separated_values_result.items);
+    items.extend(gen_close_paren(parens.close_paren));
+    items
+}
+
 fn gen_comma_sep1_new<T: HasComments, GenElement>(
     comma_sep1: CommaSep1<T>,
     gen_element: GenElement,
     force_use_new_lines: bool,
+    single_line_spaces: bool,
 ) -> ir_helpers::GenSeparatedValuesResult
 where
     GenElement: FnOnce(T) -> PrintItems + Copy,
 {
     let force_use_new_lines = force_use_new_lines || comma_sep1.has_comments();
+    gen_comma_separated(
+        comma_sep1_elements(comma_sep1),
+        gen_element,
+        force_use_new_lines,
+        single_line_spaces,
+        TrailingComma::IfMultiLine,
+    )
+}
+
+/// Pairs each element with the comma that followed it (if any).
+fn comma_sep1_elements<T>(comma_sep1: CommaSep1<T>) -> Vec<(T, Option<Comma>)> {
     let CommaSep1 {
         head,
         tail,
         trailing_comma,
     } = comma_sep1;
 
+    let mut elements = Vec::with_capacity(tail.len() + 1);
+    let mut element = head;
+    for (comma, next_element) in tail {
+        elements.push((element, Some(comma)));
+        element = next_element;
+    }
+    elements.push((element, trailing_comma));
+    elements
+}
+
+/// When the last element of a list is followed by a comma.
+#[derive(Clone, Copy)]
+enum TrailingComma {
+    /// When the list is broken over multiple lines (and trailing commas are enabled).
+    IfMultiLine,
+    /// Always, even on a single line.
+    Always,
+    /// Never, as the last element doesn't allow one (e.g. `..`).
+    Never,
+}
+
+fn gen_comma_separated<T, GenElement>(
+    elements: Vec<(T, Option<Comma>)>,
+    gen_element: GenElement,
+    force_use_new_lines: bool,
+    single_line_spaces: bool,
+    trailing_comma: TrailingComma,
+) -> ir_helpers::GenSeparatedValuesResult
+where
+    GenElement: FnOnce(T) -> PrintItems + Copy,
+{
     ir_helpers::gen_separated_values(
         |is_multi_line_or_hanging_ref| {
-            let generate_value = |element: T, comma: Option<Comma>| {
+            let generate_value = |element: T, comma: Option<Comma>, is_last: bool| {
                 let mut items = gen_element(element);
-                items.push_condition(conditions::if_true(
-                    "commaIfMultiLine",
-                    is_multi_line_or_hanging_ref.create_resolver(),
-                    comma.map_or_else(|| ",".into(), gen_comma),
-                ));
+                // A trailing comma is kept regardless if dropping it would lose comments
+                let keep_comma = !is_last
+                    || trailing_commas()
+                    || comma.as_ref().map_or(false, |comma| comma.has_comments());
+                if is_last && matches!(trailing_comma, TrailingComma::Always) {
+                    items.extend(comma.map_or_else(|| ",".into(), gen_comma));
+                } else if is_last && matches!(trailing_comma, TrailingComma::Never) {
+                    // Nothing to lose, as there can't be a comma here
+                } else if keep_comma {
+                    items.push_condition(conditions::if_true(
+                        "commaIfMultiLine",
+                        is_multi_line_or_hanging_ref.create_resolver(),
+                        comma.map_or_else(|| ",".into(), gen_comma),
+                    ));
+                }
                 ir_helpers::GeneratedValue {
                     items,
                     lines_span: None, // ?
@@ -142,29 +354,19 @@ where
                 }
             };
 
-            if tail.is_empty() {
-                vec![generate_value(head, trailing_comma)]
-            } else {
-                let mut generated_values = Vec::new();
-                let tail_len = tail.len();
-                let mut element = head;
-                for (i, (comma, next_element)) in tail.into_iter().enumerate() {
-                    generated_values.push(generate_value(element, Some(comma)));
-                    if i == tail_len - 1 {
-                        generated_values.push(generate_value(next_element, trailing_comma));
-                        break;
-                    }
-                    element = next_element;
-                }
-                generated_values
-            }
+            let elements_len = elements.len();
+            elements
+                .into_iter()
+                .enumerate()
+                .map(|(i, (element, comma))| generate_value(element, comma, i == elements_len - 1))
+                .collect()
         },
         ir_helpers::GenSeparatedValuesOptions {
             prefer_hanging: false,
             force_use_new_lines,
             allow_blank_lines: false,
-            single_line_space_at_start: false,
-            single_line_space_at_end: false,
+            single_line_space_at_start: single_line_spaces,
+            single_line_space_at_end: single_line_spaces,
             single_line_separator: ", ".into(),
             indent_width: 4,
             multi_line_options: ir_helpers::MultiLineOptions {
@@ -37,16 +37,38 @@ gen_empty_token_like!(gen_as_keyword, cst::AsKeyword, "as");
 gen_empty_token_like!(gen_type_keyword, cst::TypeKeyword, "type");
 gen_empty_token_like!(gen_import_keyword, cst::ImportKeyword, "import");
 gen_empty_token_like!(gen_foreign_keyword, cst::ForeignKeyword, "foreign");
+gen_empty_token_like!(gen_let_keyword, cst::LetKeyword, "let");
+gen_empty_token_like!(gen_in_keyword, cst::InKeyword, "in");
+gen_empty_token_like!(gen_match_keyword, cst::MatchKeyword, "match");
+gen_empty_token_like!(gen_with_keyword, cst::WithKeyword, "with");
+gen_empty_token_like!(gen_end_keyword, cst::EndKeyword, "end");
+gen_empty_token_like!(gen_js_keyword, cst::JsKeyword, "js");
+gen_empty_token_like!(gen_wildcard, cst::Wildcard, "_");
 gen_empty_token_like!(gen_open_bracket, cst::OpenBracket, "[");
+gen_empty_token_like!(gen_open_brace, cst::OpenBrace, "{");
 gen_empty_token_like!(gen_pipe, cst::Pipe, "|");
 gen_empty_token_like!(gen_open_paren, cst::OpenParen, "(");
 gen_empty_token_like!(gen_comma, cst::Comma, ",");
 gen_empty_token_like!(gen_equals, cst::Equals, "=");
+gen_empty_token_like!(gen_at, cst::At, "@");
 gen_empty_token_like!(gen_dot, cst::Dot, ".");
 gen_empty_token_like!(gen_double_dot, cst::DoubleDot, "..");
 gen_empty_token_like!(gen_colon, cst::Colon, ":");
 gen_empty_token_like!(gen_semicolon, cst::Semicolon, ";");
 gen_empty_token_like!(gen_right_arrow, cst::RightArrow, "->");
+gen_empty_token_like!(gen_plus, cst::Plus, "+");
+gen_empty_token_like!(gen_minus, cst::Minus, "-");
+gen_empty_token_like!(gen_asterisk, cst::Asterisk, "*");
+gen_empty_token_like!(gen_slash, cst::Slash, "/");
+gen_empty_token_like!(gen_double_equals, cst::DoubleEquals, "==");
+gen_empty_token_like!(gen_bang_equals, cst::BangEquals, "!=");
+gen_empty_token_like!(gen_left_angle, cst::LeftAngle, "<");
+gen_empty_token_like!(gen_left_angle_equals, cst::LeftAngleEquals, "<=");
+gen_empty_token_like!(gen_right_angle, cst::RightAngle, ">");
+gen_empty_token_like!(gen_right_angle_equals, cst::RightAngleEquals, ">=");
+gen_empty_token_like!(gen_double_ampersand, cst::DoubleAmpersand, "&&");
+gen_empty_token_like!(gen_double_pipe, cst::DoublePipe, "||");
+gen_empty_token_like!(gen_right_pipe, cst::RightPipe, "|>");
 gen_empty_token_like!(gen_module_keyword, cst::ModuleKeyword, "module");
 gen_empty_token_like!(
     gen_close_bracket,
@@ -56,6 +78,14 @@ gen_empty_token_like!(
         indent_leading_comments: true,
     }
 );
+gen_empty_token_like!(
+    gen_close_brace,
+    cst::CloseBrace,
+    "}",
+    GenTokenOptions {
+        indent_leading_comments: true,
+    }
+);
 gen_empty_token_like!(
     gen_close_paren,
     cst::CloseParen,
@@ -1,18 +1,21 @@
 use super::{
+    expression::gen_type_annotation,
     has_comments::HasComments,
     helpers::{group, space},
     name::{gen_name, gen_qualified_proper_name},
-    syntax::{gen_parens, gen_parens_list, gen_parens_list1},
-    token::gen_right_arrow,
+    syntax::{gen_braces_list1, gen_parens, gen_parens_list, gen_parens_list1, gen_tuple},
+    token::{gen_right_arrow, gen_wildcard},
 };
-use ditto_cst::{Type, TypeCallFunction};
+use ditto_cst::{RecordTypeField, Type, TypeCallFunction};
 use dprint_core::formatting::{ir_helpers, PrintItems};
 
 pub fn gen_type(t: Type) -> PrintItems {
     match t {
         // TODO remove redundant parens?
         Type::Parens(parens) => gen_parens(parens, |box t| gen_type(t)),
+        Type::Tuple(parens) => gen_tuple(parens, |box t| gen_type(t)),
         Type::Variable(name) => gen_name(name),
+        Type::Wildcard(wildcard) => gen_wildcard(wildcard),
         Type::Constructor(constructor) => gen_qualified_proper_name(constructor),
         Type::Call {
             function,
@@ -54,6 +57,15 @@ pub fn gen_type(t: Type) -> PrintItems {
             ));
             items
         }
+        Type::Record(fields) => gen_braces_list1(fields, |field| {
+            let RecordTypeField {
+                label,
+                type_annotation,
+            } = field;
+            let mut items = gen_name(label);
+            items.extend(gen_type_annotation(type_annotation));
+            items
+        }),
     }
 }
 
@@ -78,6 +90,13 @@ mod tests {
         assert_fmt!("  a_123   ", "a_123");
     }
 
+    #[test]
+    fn it_formats_wildcards() {
+        assert_fmt!("_");
+        assert_fmt!("Array( _ )", "Array(_)");
+        assert_fmt!("(_) -> Maybe(_)");
+    }
+
     #[test]
     fn it_formats_constructors() {
         assert_fmt!("Foo");
@@ -101,6 +120,26 @@ mod tests {
         assert_fmt!("Foo(\n\t-- comment\n\ta,\n)");
     }
 
+    #[test]
+    fn it_formats_tuples() {
+        assert_fmt!("(Int, String)");
+        assert_fmt!("(Int,)");
+        assert_fmt!("( Int ,String, )", "(Int, String)");
+        assert_fmt!("(a) -> (a, a)");
+    }
+
+    #[test]
+    fn it_formats_records() {
+        assert_fmt!("{ name: String, age: Int }");
+        assert_fmt!("{name:String,}", "{ name: String }");
+        assert_fmt!("(a) -> { wrapped: a }");
+        assert_fmt!(
+            "{ name: String, age: Int }",
+            "{\n\tname: String,\n\tage: Int,\n}",
+            10
+        );
+    }
+
     #[test]
     fn it_formats_functions() {
         assert_fmt!("() -> a");
@@ -0,0 +1,205 @@
+use ditto_make::{IndexEntry, IndexEntryKind};
+use lsp_types::{CompletionItem, CompletionItemKind, Documentation, Position, Range, TextEdit};
+use std::path::Path;
+
+/// Complete the name being typed with things that can be imported from installed
+/// packages, adding the import line if the module isn't imported already.
+///
+/// The index is read on every request, so that it's never out of date with
+/// the installed packages.
+pub fn get_completions(index_dir: &Path, source: &str, position: Position) -> Vec<CompletionItem> {
+    let prefix = match name_before(source, position) {
+        Some(prefix) => prefix,
+        None => return Vec::new(),
+    };
+    let entries = match ditto_make::read_index(index_dir) {
+        Ok(entries) => entries,
+        Err(err) => {
+            log::warn!("error reading index: {:?}", err);
+            return Vec::new();
+        }
+    };
+    // If the module doesn't parse this far then we don't know where imports go
+    let imports = ditto_cst::parse_header_and_imports(source).ok();
+    entries
+        .into_iter()
+        .filter(|entry| entry.name.starts_with(prefix))
+        .map(|entry| {
+            let additional_text_edits = imports
+                .as_ref()
+                .and_then(|(header, import_lines)| {
+                    import_edit(source, header, import_lines, &entry)
+                })
+                .map(|edit| vec![edit]);
+            CompletionItem {
+                label: entry.name.clone(),
+                kind: Some(match entry.kind {
+                    IndexEntryKind::Value => CompletionItemKind::VALUE,
+                    IndexEntryKind::Type => CompletionItemKind::ENUM,
+                    IndexEntryKind::Constructor => CompletionItemKind::ENUM_MEMBER,
+                }),
+                detail: Some(entry.render_import()),
+                documentation: match (entry.kind, &entry.summary) {
+                    (IndexEntryKind::Value, Some(summary)) => Some(Documentation::String(format!(
+                        "{} : {}",
+                        entry.name, summary
+                    ))),
+                    _ => None,
+                },
+                additional_text_edits,
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+/// The (unqualified) name that ends at `position`, if there is one.
+fn name_before(source: &str, position: Position) -> Option<&str> {
+    let line = source.lines().nth(position.line as usize)?;
+    // Positions count UTF-16 code units
+    let mut end = line.len();
+    let mut character = 0;
+    for (i, c) in line.char_indices() {
+        if character >= position.character as usize {
+            end = i;
+            break;
+        }
+        character += c.len_utf16();
+    }
+    let line = &line[..end];
+    let start = line
+        .rfind(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .map_or(0, |i| i + 1);
+    if start == end || line[..start].ends_with('.') {
+        return None;
+    }
+    Some(&line[start..])
+}
+
+/// An edit adding the import line for `entry`, unless its module is already imported.
+fn import_edit(
+    source: &str,
+    header: &ditto_cst::Header,
+    import_lines: &[ditto_cst::ImportLine],
+    entry: &IndexEntry,
+) -> Option<TextEdit> {
+    let already_imported = import_lines.iter().any(|import_line| {
+        import_line.module_name.render() == entry.module
+            && import_line
+                .package
+                .as_ref()
+                .map_or(false, |parens| parens.value.0.value == entry.package)
+    });
+    if already_imported {
+        return None;
+    }
+    let (offset, new_text) = match import_lines.last() {
+        Some(import_line) => (
+            import_line.semicolon.0.get_span().end_offset,
+            format!("\n{}", entry.render_import()),
+        ),
+        None => (
+            header.semicolon.0.get_span().end_offset,
+            format!("\n\n{}", entry.render_import()),
+        ),
+    };
+    let position = offset_to_position(source, offset);
+    Some(TextEdit {
+        range: Range {
+            start: position,
+            end: position,
+        },
+        new_text,
+    })
+}
+
+fn offset_to_position(source: &str, offset: usize) -> Position {
+    let before = &source[..offset];
+    let line = before.matches('\n').count();
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let character = before[line_start..].encode_utf16().count();
+    Position {
+        line: line as u32,
+        character: character as u32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::get_completions;
+    use ditto_make::{mk_index_path, IndexEntry, IndexEntryKind, PackageIndex};
+    use lsp_types::{CompletionItem, Position, Range, TextEdit};
+
+    #[test]
+    fn it_completes_names_from_the_index() {
+        let index_dir = tempfile::tempdir().unwrap();
+        PackageIndex {
+            hash: String::new(),
+            entries: vec![IndexEntry {
+                name: String::from("with_default"),
+                package: String::from("maybe"),
+                module: String::from("Data.Maybe"),
+                kind: IndexEntryKind::Value,
+                summary: None,
+            }],
+            reexports: Vec::new(),
+        }
+        .write(&mk_index_path(index_dir.path(), "maybe"))
+        .unwrap();
+        let complete = |source: &str, line, character| {
+            get_completions(index_dir.path(), source, Position { line, character })
+        };
+        let import_edits = |completions: Vec<CompletionItem>| {
+            completions
+                .into_iter()
+                .map(|completion| (completion.label, completion.additional_text_edits))
+                .collect::<Vec<_>>()
+        };
+        let insert_at = |line, character, new_text: &str| TextEdit {
+            range: Range {
+                start: Position { line, character },
+                end: Position { line, character },
+            },
+            new_text: new_text.to_string(),
+        };
+
+        // The import goes after the last import line
+        let source = "module Test exports (..);\n\nimport (other) Other;\n\nfive = with_d";
+        assert_eq!(
+            import_edits(complete(source, 4, 13)),
+            vec![(
+                String::from("with_default"),
+                Some(vec![insert_at(
+                    2,
+                    21,
+                    "\nimport (maybe) Data.Maybe (with_default);"
+                )])
+            )]
+        );
+
+        // ...or after the module header, if there aren't any
+        let source = "module Test exports (..);\n\nfive = with_d";
+        assert_eq!(
+            import_edits(complete(source, 2, 13)),
+            vec![(
+                String::from("with_default"),
+                Some(vec![insert_at(
+                    0,
+                    25,
+                    "\n\nimport (maybe) Data.Maybe (with_default);"
+                )])
+            )]
+        );
+
+        // Nothing is added if the module is already imported
+        let source =
+            "module Test exports (..);\n\nimport (maybe) Data.Maybe as M;\n\nfive = with_d";
+        assert_eq!(
+            import_edits(complete(source, 4, 13)),
+            vec![(String::from("with_default"), None)]
+        );
+
+        // Names that don't match aren't completed
+        assert!(complete("module Test exports (..);\n\nfive = fiv", 2, 10).is_empty());
+    }
+}
@@ -1,12 +1,13 @@
 #![doc = include_str!("../README.md")]
 #![warn(missing_docs)]
 
+mod completion;
 mod semantic_tokens;
 
 use log::debug;
 use miette::IntoDiagnostic;
 use serde_json as json;
-use std::collections::HashMap;
+use std::{collections::HashMap, path::PathBuf};
 use url::Url;
 
 /// Run the language server.
@@ -35,6 +36,7 @@ pub fn main() -> miette::Result<()> {
             ),
         ),
         document_formatting_provider: Some(lsp_types::OneOf::Left(true)),
+        completion_provider: Some(lsp_types::CompletionOptions::default()),
         //definition_provider: Some(lsp_types::OneOf::Left(true)),
         ..Default::default()
     };
@@ -58,7 +60,12 @@ pub fn main() -> miette::Result<()> {
 fn main_loop(connection: lsp_server::Connection, params: json::Value) -> miette::Result<()> {
     debug!("starting ditto-lsp main loop");
 
-    let _params: lsp_types::InitializeParams = json::from_value(params).unwrap();
+    let params: lsp_types::InitializeParams = json::from_value(params).unwrap();
+
+    let index_dir = params
+        .root_uri
+        .and_then(|root_uri| root_uri.to_file_path().ok())
+        .and_then(find_index_dir);
 
     let mut trees = Trees::new();
 
@@ -68,7 +75,7 @@ fn main_loop(connection: lsp_server::Connection, params: json::Value) -> miette:
                 if connection.handle_shutdown(&req).into_diagnostic()? {
                     return Ok(());
                 }
-                use lsp_types::request::{Formatting, SemanticTokensFullRequest};
+                use lsp_types::request::{Completion, Formatting, SemanticTokensFullRequest};
 
                 // TODO break out some `handle` function to enforce that requests
                 // are always responded to (correctly).
@@ -101,7 +108,7 @@ fn main_loop(connection: lsp_server::Connection, params: json::Value) -> miette:
                             if let Some((_, contents)) = trees.get(&params.text_document.uri) {
                                 match ditto_cst::Module::parse(contents) {
                                     Ok(module) => {
-                                        let formatted = ditto_fmt::format_module(module);
+                                        let formatted = ditto_fmt::format_module(module, contents);
                                         let edit = lsp_types::TextEdit {
                                             range: lsp_types::Range {
                                                 start: lsp_types::Position {
@@ -156,7 +163,31 @@ fn main_loop(connection: lsp_server::Connection, params: json::Value) -> miette:
                             }
                             continue 'msg_loop;
                         }
-                        Err(_req) => (),
+                        Err(req) => match cast_request::<Completion>(req) {
+                            Ok((request_id, params)) => {
+                                let text_document_position = params.text_document_position;
+                                let items = match (
+                                    &index_dir,
+                                    trees.get(&text_document_position.text_document.uri),
+                                ) {
+                                    (Some(index_dir), Some((_, source))) => {
+                                        completion::get_completions(
+                                            index_dir,
+                                            source,
+                                            text_document_position.position,
+                                        )
+                                    }
+                                    _ => Vec::new(),
+                                };
+                                respond::<Completion>(
+                                    Ok(Some(lsp_types::CompletionResponse::Array(items))),
+                                    request_id,
+                                    &connection,
+                                )?;
+                                continue 'msg_loop;
+                            }
+                            Err(_req) => (),
+                        },
                     },
                 };
             }
@@ -187,6 +218,12 @@ fn main_loop(connection: lsp_server::Connection, params: json::Value) -> miette:
     Ok(())
 }
 
+/// Find the package index of the ditto project at `root`, if it is one.
+fn find_index_dir(root: PathBuf) -> Option<PathBuf> {
+    let config = ditto_config::read_config(root.join(ditto_config::CONFIG_FILE_NAME)).ok()?;
+    Some(ditto_make::mk_index_dir(&root.join(config.ditto_dir)))
+}
+
 /// Parsed trees, updated on text document change notifications.
 struct Trees(HashMap<Url, (tree_sitter::Tree, String)>);
 
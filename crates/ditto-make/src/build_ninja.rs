@@ -1,12 +1,17 @@
-use crate::{common, compile};
+use crate::{common, compile, index};
 use ditto_ast as ast;
-use ditto_config::{read_config, Config, PackageName};
+use ditto_checker as checker;
+use ditto_config::{
+    read_config, ArtifactFormat, Config, JsLayout, JsModuleFormat, JsUnit, LintLevel, Lints,
+    PackageName, Profile, ProfileName,
+};
 use ditto_cst as cst;
-use miette::{bail, Diagnostic, IntoDiagnostic, NamedSource, Result, SourceSpan};
+use miette::{bail, miette, Diagnostic, IntoDiagnostic, NamedSource, Result, SourceSpan};
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fmt,
     path::{Path, PathBuf},
+    sync::Arc,
 };
 use thiserror::Error;
 
@@ -23,10 +28,42 @@ pub type PackageSources = HashMap<PackageName, Sources>;
 
 /// The type of function returned by [generate_build_ninja] that can be used to retrieve
 /// compilation warnings.
+///
+/// This can be called after a failed build too, in which case only the warnings
+/// of modules that were checked successfully are returned.
 pub type GetWarnings = impl FnOnce() -> Result<Vec<miette::Report>>;
 
+/// Things that can go wrong when planning a build.
+#[derive(Error, Debug)]
+pub enum PlanError {
+    /// Module headers that couldn't be parsed.
+    ///
+    /// Every file is checked before this is returned, so there's a report
+    /// for each module that failed to parse.
+    #[error("{} module{} failed to parse", .0.len(), if .0.len() == 1 { "" } else { "s" })]
+    ModuleParseErrors(Vec<miette::Report>),
+    /// Any other error.
+    #[error("{0}")]
+    Other(miette::Report),
+}
+
+impl From<miette::Report> for PlanError {
+    fn from(report: miette::Report) -> Self {
+        Self::Other(report)
+    }
+}
+
 /// Generates a [build.ninja](https://ninja-build.org/manual.html#_writing_your_own_ninja_files)
 /// file and also returns a function for retrieving compiler warnings once `ninja` has run.
+///
+/// `lint_overrides` take precedence over any warning levels set in the config.
+///
+/// Artifacts for each `profile` are kept in their own subdirectory of `build_dir`,
+/// so switching profiles doesn't clobber the other profile's work.
+///
+/// If `emit_only` names a module in the current package, only that module and
+/// its (transitive) dependencies are built.
+#[allow(clippy::too_many_arguments)]
 pub fn generate_build_ninja(
     build_dir: PathBuf,
     ditto_bin: PathBuf,
@@ -34,13 +71,40 @@ pub fn generate_build_ninja(
     compile_subcommand: &'static str,
     sources: Sources,
     package_sources: PackageSources,
-) -> Result<(BuildNinja, GetWarnings)> {
+    lint_overrides: &Lints,
+    profile: ProfileName,
+    emit_only: Option<&str>,
+) -> std::result::Result<(BuildNinja, GetWarnings), PlanError> {
     // TODO make this more concurrent!
     let config = read_config(&sources.config)?;
 
-    // Initial build.ninja file, extended later
-    let mut build_ninja = BuildNinja::new(&build_dir, &ditto_bin, compile_subcommand, &config);
+    let lint_levels = resolve_lint_levels(&config, lint_overrides)?;
+
+    let profile = config.profile(profile);
+
+    // NOTE `build_dir` is still the ninja `builddir`, so that both profiles
+    // share a `.ninja_log`. That way generated JavaScript (which isn't kept apart)
+    // is rebuilt when switching profiles, as the command that generates it changes.
+    let artifacts_dir = build_dir.join(profile.name.as_str());
 
+    // Initial build.ninja file, extended later
+    let mut build_ninja = BuildNinja::new(
+        &build_dir,
+        artifacts_dir.clone(),
+        &ditto_bin,
+        compile_subcommand,
+        &config,
+        &lint_levels,
+        profile,
+    );
+
+    let js_declarations = config.codegen_js_config.declarations;
+    let js_source_maps = config.codegen_js_config.source_maps;
+    let js_layout = config.codegen_js_config.layout;
+    let js_extensions = common::JsExtensions::new(
+        config.codegen_js_config.module_format,
+        config.codegen_js_config.module_extensions,
+    );
     let js_dirs = if config.targets_js() {
         let dist_dir = config.codegen_js_config.dist_dir;
         let packages_dir = config.codegen_js_config.packages_dir;
@@ -58,6 +122,10 @@ pub fn generate_build_ninja(
     };
 
     let (graph, graph_nodes) = prepare_build_graph(sources, package_sources, ditto_version)?;
+    let graph_nodes = match emit_only {
+        Some(module_name) => prune_build_graph(&graph, graph_nodes, module_name)?,
+        None => graph_nodes,
+    };
 
     // Paths to serialized warnings, so the caller can replay them
     let mut checker_warnings_paths: Vec<PathBuf> = Vec::new();
@@ -65,14 +133,14 @@ pub fn generate_build_ninja(
     for (node_index, node) in graph_nodes.clone() {
         let node_string = node.to_string();
         let ast_path = mk_ast_path(
-            build_dir.clone(),
+            artifacts_dir.clone(),
             &node.package_name,
             &node.module_name,
             common::EXTENSION_AST,
         );
 
         let ast_exports_path = mk_ast_path(
-            build_dir.clone(),
+            artifacts_dir.clone(),
             &node.package_name,
             &node.module_name,
             common::EXTENSION_AST_EXPORTS,
@@ -80,7 +148,7 @@ pub fn generate_build_ninja(
 
         let checker_warnings_path = if node.package_name.is_none() {
             let checker_warnings_path = mk_ast_path(
-                build_dir.clone(),
+                artifacts_dir.clone(),
                 &node.package_name,
                 &node.module_name,
                 common::EXTENSION_CHECKER_WARNINGS,
@@ -96,7 +164,7 @@ pub fn generate_build_ninja(
             .map(|idx| {
                 let dep_node = graph_nodes.get(&idx).unwrap();
                 mk_ast_path(
-                    build_dir.clone(),
+                    artifacts_dir.clone(),
                     &dep_node.package_name,
                     &dep_node.module_name,
                     common::EXTENSION_AST_EXPORTS,
@@ -105,25 +173,61 @@ pub fn generate_build_ninja(
             .collect::<Vec<_>>();
 
         if let Some((ref dist_dir, ref packages_dir)) = js_dirs {
-            let js_path = if let Some(package_name) = node.package_name {
-                let mut js_path = packages_dir.clone();
-                js_path.push(package_name.as_str());
-                js_path.push(common::module_name_to_file_stem(node.module_name));
-                js_path.set_extension(common::EXTENSION_JS);
-                js_path
+            let js_dir = if let Some(ref package_name) = node.package_name {
+                let mut js_dir = packages_dir.clone();
+                js_dir.push(package_name.as_str());
+                js_dir
+            } else {
+                dist_dir.clone()
+            };
+            let js_path = js_dir.join(common::module_name_to_output_path(
+                node.module_name.clone(),
+                js_layout,
+                js_extensions.js,
+            ));
+            let dts_path = if js_declarations {
+                Some(js_dir.join(common::module_name_to_output_path(
+                    node.module_name.clone(),
+                    js_layout,
+                    js_extensions.dts,
+                )))
+            } else {
+                None
+            };
+            let map_path = if js_source_maps {
+                Some(js_dir.join(common::module_name_to_output_path(
+                    node.module_name.clone(),
+                    js_layout,
+                    js_extensions.js_map,
+                )))
+            } else {
+                None
+            };
+            // Optimized builds inline small constants from dependencies
+            let inline_source_paths = if profile.optimize {
+                dependency_ast_export_paths.clone()
             } else {
-                let mut js_path = dist_dir.clone();
-                js_path.push(common::module_name_to_file_stem(node.module_name));
-                js_path.set_extension(common::EXTENSION_JS);
-                js_path
+                Vec::new()
             };
             build_ninja.builds.push(Build::new_js(
                 node_string.clone(),
                 js_path,
+                dts_path,
+                map_path,
                 ast_path.clone(),
+                inline_source_paths,
             ));
         }
 
+        // Entry points aren't expected to export anything
+        let is_entry_module = node.package_name.is_none()
+            && config.entry_modules.contains(&node.module_name.to_string());
+        let allowed_warnings: &[&str] = if is_entry_module {
+            &["no-exports"]
+        } else {
+            &[]
+        };
+
         build_ninja.builds.push(Build::new_ast(
             node_string,
             ast_path,
@@ -131,6 +235,7 @@ pub fn generate_build_ninja(
             checker_warnings_path,
             node.source_path,
             dependency_ast_export_paths,
+            allowed_warnings,
         ));
     }
 
@@ -138,6 +243,11 @@ pub fn generate_build_ninja(
     let get_warnings = move || {
         let mut warnings = Vec::new();
         for warnings_path in checker_warnings_paths {
+            // Modules that failed (or weren't built because of a failure)
+            // don't have any warnings
+            if !warnings_path.exists() {
+                continue;
+            }
             let warnings_bundle =
                 common::deserialize::<Option<compile::WarningsBundle>>(&warnings_path)?;
 
@@ -147,11 +257,12 @@ pub fn generate_build_ninja(
                 warnings: warning_reports,
             }) = warnings_bundle
             {
-                let source = std::sync::Arc::new(source);
-                warnings.extend(warning_reports.into_iter().map(|warning_report| {
-                    miette::Report::from(warning_report)
-                        .with_source_code(miette::NamedSource::new(&name, source.clone()))
-                }))
+                let source = Arc::new(source);
+                warnings.extend(
+                    warning_reports
+                        .into_iter()
+                        .map(|report| report.with_source_code(&name, source.clone())),
+                )
             }
         }
         Ok(warnings)
@@ -169,8 +280,12 @@ fn mk_ast_path(
     if let Some(package_name) = package_name {
         base.push(package_name.as_str());
     }
-    base.push(common::module_name_to_file_stem(module_name.clone()));
-    base.set_extension(extension);
+    // Build artifacts are always flat
+    base.push(common::module_name_to_output_path(
+        module_name.clone(),
+        JsLayout::Flat,
+        extension,
+    ));
     base
 }
 
@@ -199,10 +314,14 @@ fn prepare_build_graph(
     sources: Sources,
     package_sources: PackageSources,
     ditto_version: &semver::Version,
-) -> Result<(BuildGraph, BuildGraphNodes)> {
+) -> std::result::Result<(BuildGraph, BuildGraphNodes), PlanError> {
     let mut build_graph = BuildGraph::new();
     let mut build_graph_nodes = BuildGraphNodes::new();
 
+    // Collected rather than returned immediately, so that all parse errors
+    // can be reported at once
+    let mut parse_errors: Vec<miette::Report> = Vec::new();
+
     let current_config = read_config(&sources.config)?;
 
     let all_sources = package_sources
@@ -221,7 +340,7 @@ fn prepare_build_graph(
         // Check ditto version requirement
         if let Some(required_ditto_version) = config.required_ditto_version {
             if !required_ditto_version.matches(ditto_version) {
-                bail!(
+                return Err(miette!(
                     "ditto version requirement not met for {}: current version = {}, wanted = {}",
                     package_name.map_or("current_package".into(), |package_name| format!(
                         "{:?}",
@@ -229,7 +348,8 @@ fn prepare_build_graph(
                     )),
                     ditto_version,
                     required_ditto_version
-                );
+                )
+                .into());
             }
         }
 
@@ -242,11 +362,12 @@ fn prepare_build_graph(
                     .map(|target| serde_json::to_string(target).unwrap())
                     .collect::<Vec<_>>()
                     .join(", ");
-                bail!(
+                return Err(miette!(
                     "package {:?} doesn't support targets: {}",
                     package_name.as_str(),
                     unsupported
-                );
+                )
+                .into());
             }
         }
 
@@ -268,16 +389,25 @@ fn prepare_build_graph(
 
         // TODO make this more async?
         for source_path in sources.ditto.iter() {
-            let (header, imports) = read_module_header_and_imports(source_path)?;
+            let source: Arc<String> = std::fs::read_to_string(source_path)
+                .into_diagnostic()?
+                .into();
+            let (header, imports) = match cst::parse_header_and_imports(&source) {
+                Ok(header_and_imports) => header_and_imports,
+                Err(err) => {
+                    let report = err.into_report(&source_path.to_string_lossy(), source);
+                    parse_errors.push(report.into());
+                    continue;
+                }
+            };
             let module_name_span = header.module_name.get_span();
             let module_name = ast::ModuleName::from(header.module_name);
 
             // Make sure we haven't seen a file with this module name before,
             // otherwise ninja will throw a wobbly
             if let Some(other_file) = module_names_seen.remove(&module_name) {
-                let source = std::fs::read_to_string(source_path).into_diagnostic()?;
                 let input = NamedSource::new(source_path.to_string_lossy(), source);
-                return Err(DuplicateModuleError {
+                return Err(miette::Report::from(DuplicateModuleError {
                     input,
                     module_name: module_name.to_string(),
                     module_name_span: (
@@ -286,7 +416,7 @@ fn prepare_build_graph(
                     )
                         .into(),
                     other_file: other_file.to_string_lossy().into_owned(),
-                }
+                })
                 .into());
             }
             module_names_seen.insert(module_name.clone(), source_path.clone());
@@ -302,6 +432,10 @@ fn prepare_build_graph(
         }
     }
 
+    if !parse_errors.is_empty() {
+        return Err(PlanError::ModuleParseErrors(parse_errors));
+    }
+
     // Add the edges
     for (node_index, node) in build_graph_nodes.iter() {
         for import_line in node.imports.iter() {
@@ -341,6 +475,35 @@ fn prepare_build_graph(
     Ok((build_graph, build_graph_nodes))
 }
 
+/// Keep only the module named `module_name` (in the current package) and the
+/// modules it depends on.
+fn prune_build_graph(
+    build_graph: &BuildGraph,
+    mut build_graph_nodes: BuildGraphNodes,
+    module_name: &str,
+) -> Result<BuildGraphNodes> {
+    let start = build_graph_nodes
+        .iter()
+        .find(|(_, node)| {
+            node.package_name.is_none() && node.module_name.to_string() == module_name
+        })
+        .map(|(node_index, _)| *node_index)
+        .ok_or_else(|| {
+            miette!(
+                "can't emit `{}`, no module with that name in this package",
+                module_name
+            )
+        })?;
+
+    let mut reachable = HashSet::new();
+    let mut dfs = petgraph::visit::Dfs::new(build_graph, start);
+    while let Some(node_index) = dfs.next(build_graph) {
+        reachable.insert(node_index);
+    }
+    build_graph_nodes.retain(|node_index, _| reachable.contains(node_index));
+    Ok(build_graph_nodes)
+}
+
 fn check_for_cycles(build_graph: &BuildGraph) -> Result<()> {
     let sccs = petgraph::algo::kosaraju_scc(&build_graph);
     for scc in sccs {
@@ -373,36 +536,145 @@ fn check_for_cycles(build_graph: &BuildGraph) -> Result<()> {
 /// A representation of the [ninja file syntax](https://github.com/ninja-build/ninja/blob/master/misc/ninja_syntax.py).
 #[derive(Debug)]
 pub struct BuildNinja {
+    artifacts_dir: PathBuf,
+    profile: ProfileName,
     variables: HashMap<String, String>,
     rules: Vec<Rule>,
     builds: Vec<Build>,
 }
 
+/// Work out the [LintLevel] for every warning code, erroring for unknown codes.
+fn resolve_lint_levels(
+    config: &Config,
+    lint_overrides: &Lints,
+) -> Result<HashMap<&'static str, LintLevel>> {
+    let known_codes = checker::Warning::CODES;
+    let mentioned_codes = config
+        .allow_warnings
+        .iter()
+        .chain(config.lints.levels.keys())
+        .chain(lint_overrides.keys());
+    for code in mentioned_codes {
+        if !known_codes.contains(&code.as_str()) {
+            bail!(
+                "unknown warning code `{}`, expected one of: {}",
+                code,
+                known_codes.join(", ")
+            );
+        }
+    }
+    Ok(known_codes
+        .iter()
+        .map(|code| {
+            let level = lint_overrides
+                .get(*code)
+                .copied()
+                .or_else(|| config.configured_lint_level(code))
+                .unwrap_or_else(|| default_lint_level(code));
+            (*code, level)
+        })
+        .collect())
+}
+
+/// Most warnings are on by default, but some have to be opted in to.
+fn default_lint_level(code: &str) -> LintLevel {
+    if checker::Warning::ALLOWED_BY_DEFAULT.contains(&code) {
+        LintLevel::Allow
+    } else {
+        LintLevel::Warn
+    }
+}
+
 impl BuildNinja {
     fn new(
         build_dir: &Path,
+        artifacts_dir: PathBuf,
         ditto_bin: &Path,
         compile_subcommand: &'static str,
         config: &Config,
+        lint_levels: &HashMap<&'static str, LintLevel>,
+        profile: Profile,
     ) -> Self {
         let build_dir_variable = (
             String::from("builddir"),
             build_dir.to_string_lossy().into_owned(),
         );
         let variables = HashMap::from_iter(vec![(build_dir_variable)]);
-        let mut rules = vec![Rule::new_ast(build_dir, ditto_bin, compile_subcommand)];
+        // Only installed packages are indexed, so there's nothing to suggest without them
+        let index_dir =
+            (!config.dependencies.is_empty()).then(|| index::mk_index_dir(&config.ditto_dir));
+        let mut rules = vec![Rule::new_ast(
+            &artifacts_dir,
+            index_dir.as_deref(),
+            ditto_bin,
+            compile_subcommand,
+            lint_levels,
+            config.codegen_js_config.allow_inline_js,
+            profile.artifacts,
+        )];
 
         if config.targets_js() {
-            rules.push(Rule::new_js(ditto_bin, compile_subcommand));
-            rules.push(Rule::new_package_json(ditto_bin, compile_subcommand));
+            rules.push(Rule::new_js(
+                &artifacts_dir,
+                ditto_bin,
+                compile_subcommand,
+                config.codegen_js_config.layout,
+                config.codegen_js_config.unit,
+                config.codegen_js_config.module_format,
+                config.codegen_js_config.module_extensions,
+                profile,
+            ));
+            rules.push(Rule::new_package_json(
+                ditto_bin,
+                compile_subcommand,
+                config.codegen_js_config.module_format,
+            ));
         }
 
         Self {
+            artifacts_dir,
+            profile: profile.name,
             variables,
             rules,
             builds: Vec::new(),
         }
     }
+    /// Iterate over build edges as `(description, outputs, inputs)`.
+    pub(crate) fn edges(&self) -> impl Iterator<Item = (String, &[PathBuf], &[PathBuf])> {
+        self.builds.iter().map(|build| {
+            let description = build
+                .variables
+                .get("description")
+                .cloned()
+                .unwrap_or_else(|| build.rule_name.clone());
+            (
+                description,
+                build.outputs.as_slice(),
+                build.inputs.as_slice(),
+            )
+        })
+    }
+
+    /// Iterate over the `(outputs, inputs)` of module checking edges.
+    ///
+    /// The first output is always the `.ast` file and the second the `.ast-exports` file.
+    pub(crate) fn ast_edges(&self) -> impl Iterator<Item = (&[PathBuf], &[PathBuf])> {
+        self.builds
+            .iter()
+            .filter(|build| build.rule_name == RULE_NAME_AST)
+            .map(|build| (build.outputs.as_slice(), build.inputs.as_slice()))
+    }
+
+    /// The directory where build artifacts are written.
+    pub(crate) fn build_dir(&self) -> &Path {
+        &self.artifacts_dir
+    }
+
+    /// The profile this build is for.
+    pub(crate) fn profile(&self) -> ProfileName {
+        self.profile
+    }
+
     /// Render to `build.ninja` file syntax.
     pub fn into_syntax(self) -> String {
         self.into_syntax_with(|path| path.to_string_lossy().into_owned())
@@ -464,6 +736,9 @@ static RULE_NAME_AST: &str = "ast";
 static RULE_NAME_JS: &str = "js";
 static RULE_NAME_PACKAGE_JSON: &str = "package_json";
 
+/// Per-module warning flags for the `ast` rule, which expands to nothing when unset.
+static VARIABLE_MODULE_LINTS: &str = "module_lints";
+
 #[derive(Debug)]
 struct Rule {
     name: String,
@@ -471,33 +746,123 @@ struct Rule {
 }
 
 impl Rule {
-    fn new_ast(build_dir: &Path, ditto_bin: &Path, compile: &str) -> Self {
-        use compile::{ARG_BUILD_DIR, ARG_INPUTS as i, ARG_OUTPUTS as o, SUBCOMMAND_AST as ast};
+    fn new_ast(
+        build_dir: &Path,
+        index_dir: Option<&Path>,
+        ditto_bin: &Path,
+        compile: &str,
+        lint_levels: &HashMap<&'static str, LintLevel>,
+        allow_inline_js: bool,
+        artifacts: ArtifactFormat,
+    ) -> Self {
+        use compile::{
+            ARG_ALLOW_INLINE_JS, ARG_ALLOW_WARNING, ARG_ARTIFACTS, ARG_BUILD_DIR, ARG_DENY_WARNING,
+            ARG_INDEX_DIR, ARG_INPUTS as i, ARG_OUTPUTS as o, ARG_WARN_WARNING,
+            SUBCOMMAND_AST as ast,
+        };
         let ditto = ditto_bin.to_string_lossy();
         let build_dir = build_dir.to_string_lossy();
+        let index_dir = index_dir.map_or_else(String::new, |index_dir| {
+            format!(" --{ARG_INDEX_DIR} {}", index_dir.to_string_lossy())
+        });
+        // Sorted, so that the generated command is stable
+        let mut lint_levels = lint_levels.iter().collect::<Vec<_>>();
+        lint_levels.sort_by_key(|(code, _)| **code);
+        let lints = lint_levels
+            .into_iter()
+            // Only pass levels that differ from the default
+            .filter(|(code, level)| **level != default_lint_level(code))
+            .map(|(code, level)| match level {
+                LintLevel::Allow => format!(" --{ARG_ALLOW_WARNING} {code}"),
+                LintLevel::Warn => format!(" --{ARG_WARN_WARNING} {code}"),
+                LintLevel::Deny => format!(" --{ARG_DENY_WARNING} {code}"),
+            })
+            .collect::<String>();
+        // The root package's setting applies to the whole build, dependencies included
+        let inline_js = if allow_inline_js {
+            format!(" --{ARG_ALLOW_INLINE_JS}")
+        } else {
+            String::new()
+        };
+        // JSON is the default
+        let artifacts = match artifacts {
+            ArtifactFormat::Json => String::new(),
+            ArtifactFormat::Cbor => format!(" --{ARG_ARTIFACTS} {}", artifacts.as_str()),
+        };
         Self {
             name: RULE_NAME_AST.to_string(),
             command: format!(
-                "{ditto} {compile} {ast} --{ARG_BUILD_DIR} {build_dir} -{i} ${{in}} -{o} ${{out}}"
+                "{ditto} {compile} {ast} --{ARG_BUILD_DIR} {build_dir}{index_dir}{artifacts}{inline_js}{lints}${{{VARIABLE_MODULE_LINTS}}} -{i} ${{in}} -{o} ${{out}}"
             ),
         }
     }
 
-    fn new_js(ditto_bin: &Path, compile: &str) -> Self {
-        use compile::{ARG_INPUTS as i, ARG_OUTPUTS as o, SUBCOMMAND_JS as js};
+    #[allow(clippy::too_many_arguments)]
+    fn new_js(
+        build_dir: &Path,
+        ditto_bin: &Path,
+        compile: &str,
+        layout: JsLayout,
+        unit: JsUnit,
+        module_format: JsModuleFormat,
+        module_extensions: bool,
+        profile: Profile,
+    ) -> Self {
+        use compile::{
+            ARG_BANNER, ARG_BUILD_DIR, ARG_INPUTS as i, ARG_LAYOUT, ARG_MODULE_EXTENSIONS,
+            ARG_OPTIMIZE, ARG_OUTPUTS as o, ARG_RUNTIME_CHECKS, ARG_UNIT, LAYOUT_NESTED,
+            SUBCOMMAND_JS as js, UNIT_SENTINEL,
+        };
         let ditto = ditto_bin.to_string_lossy();
+        // Flat is the default, so existing build files are unchanged
+        let layout = match layout {
+            JsLayout::Flat => String::new(),
+            JsLayout::Nested => format!(" --{ARG_LAYOUT} {LAYOUT_NESTED}"),
+        };
+        // Likewise for `undefined`
+        let unit = match unit {
+            JsUnit::Undefined => String::new(),
+            JsUnit::Sentinel => format!(" --{ARG_UNIT} {UNIT_SENTINEL}"),
+        };
+        // Likewise for ES modules with `.js` extensions
+        let module_format = module_format_flag(module_format);
+        let module_extensions = if module_extensions {
+            format!(" --{ARG_MODULE_EXTENSIONS}")
+        } else {
+            String::new()
+        };
+        let profile_flags = [
+            (profile.optimize, ARG_OPTIMIZE),
+            (profile.runtime_checks, ARG_RUNTIME_CHECKS),
+            (profile.banner, ARG_BANNER),
+        ]
+        .into_iter()
+        .filter(|(enabled, _)| *enabled)
+        .map(|(_, arg)| format!(" --{arg}"))
+        .collect::<String>();
+        // Optimized builds read dependency exports, which need placing in packages
+        let build_dir = if profile.optimize {
+            format!(" --{ARG_BUILD_DIR} {}", build_dir.to_string_lossy())
+        } else {
+            String::new()
+        };
         Self {
             name: RULE_NAME_JS.to_string(),
-            command: format!("{ditto} {compile} {js} -{i} ${{in}} -{o} ${{out}}"),
+            command: format!(
+                "{ditto} {compile} {js}{layout}{unit}{module_format}{module_extensions}{profile_flags}{build_dir} -{i} ${{in}} -{o} ${{out}}"
+            ),
         }
     }
 
-    fn new_package_json(ditto_bin: &Path, compile: &str) -> Self {
+    fn new_package_json(ditto_bin: &Path, compile: &str, module_format: JsModuleFormat) -> Self {
         use compile::{ARG_INPUTS as i, ARG_OUTPUTS as o, SUBCOMMAND_PACKAGE_JSON as package_json};
         let ditto = ditto_bin.to_string_lossy();
+        let module_format = module_format_flag(module_format);
         Self {
             name: RULE_NAME_PACKAGE_JSON.to_string(),
-            command: format!("{ditto} {compile} {package_json} -{i} ${{in}} -{o} ${{out}}"),
+            command: format!(
+                "{ditto} {compile} {package_json}{module_format} -{i} ${{in}} -{o} ${{out}}"
+            ),
         }
     }
 
@@ -507,6 +872,15 @@ impl Rule {
     }
 }
 
+/// ES modules are the default, so only CommonJS needs a flag.
+fn module_format_flag(module_format: JsModuleFormat) -> String {
+    use compile::{ARG_MODULE_FORMAT, MODULE_FORMAT_COMMONJS};
+    match module_format {
+        JsModuleFormat::Esm => String::new(),
+        JsModuleFormat::CommonJs => format!(" --{ARG_MODULE_FORMAT} {MODULE_FORMAT_COMMONJS}"),
+    }
+}
+
 #[derive(Debug)]
 struct Build {
     outputs: Vec<PathBuf>,
@@ -523,6 +897,7 @@ impl Build {
         checker_warnings_path: Option<PathBuf>,
         ditto_source_path: PathBuf,
         dependency_ast_export_paths: Vec<PathBuf>,
+        allowed_warnings: &[&str],
     ) -> Self {
         let mut outputs = vec![ast_path, ast_exports_path];
         if let Some(checker_warnings_path) = checker_warnings_path {
@@ -532,26 +907,43 @@ impl Build {
         inputs.extend(dependency_ast_export_paths);
         inputs.push(ditto_source_path);
 
+        let mut variables = HashMap::from_iter(vec![(
+            String::from("description"),
+            format!("Checking {}", module_descriptor),
+        )]);
+        if !allowed_warnings.is_empty() {
+            use compile::ARG_ALLOW_WARNING;
+            // `$ ` is an escaped space, as ninja strips leading whitespace from values
+            let module_lints = allowed_warnings
+                .iter()
+                .map(|code| format!("$ --{ARG_ALLOW_WARNING} {code}"))
+                .collect::<String>();
+            variables.insert(String::from(VARIABLE_MODULE_LINTS), module_lints);
+        }
+
         Self {
             outputs,
             rule_name: String::from(RULE_NAME_AST),
             inputs,
-            variables: HashMap::from_iter(vec![(
-                String::from("description"),
-                format!("Checking {}", module_descriptor),
-            )]),
+            variables,
         }
     }
 
     fn new_js(
         module_descriptor: String,
         js_path: PathBuf,
-        //dts_path: PathBuf,
+        dts_path: Option<PathBuf>,
+        map_path: Option<PathBuf>,
         ast_path: PathBuf,
+        inline_source_paths: Vec<PathBuf>,
     ) -> Self {
-        let outputs = vec![js_path /*, dts_path */];
+        // Declarations and source maps come out of the same edge, so the AST is only read once
+        let mut outputs = vec![js_path];
+        outputs.extend(dts_path);
+        outputs.extend(map_path);
 
-        let inputs = vec![ast_path];
+        let mut inputs = vec![ast_path];
+        inputs.extend(inline_source_paths);
 
         Self {
             outputs,
@@ -628,9 +1020,3 @@ static NEWLINE: &str = "\r\n";
 
 #[cfg(not(windows))]
 static NEWLINE: &str = "\n";
-
-fn read_module_header_and_imports(path: &Path) -> Result<(cst::Header, Vec<cst::ImportLine>)> {
-    let contents = std::fs::read_to_string(path).into_diagnostic()?;
-    cst::parse_header_and_imports(&contents)
-        .map_err(|err| err.into_report(&path.to_string_lossy(), contents).into())
-}
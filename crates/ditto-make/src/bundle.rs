@@ -0,0 +1,149 @@
+use crate::{build_ninja::BuildNinja, common, compile::CodegenOptions};
+use ditto_ast as ast;
+use ditto_codegen_js as js;
+use ditto_config::{JsUnit, PackageName};
+use miette::{bail, IntoDiagnostic, Result, WrapErr};
+use std::{
+    collections::HashMap,
+    env,
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// The modules of a build, in the order they should be bundled.
+///
+/// This should be created before the build is run (so that problems are reported early),
+/// and written after a successful build.
+#[derive(Debug)]
+pub struct BundlePlan {
+    package_name: ast::PackageName,
+    modules: Vec<BundlePlanModule>,
+    unit: JsUnit,
+    codegen_options: CodegenOptions,
+}
+
+#[derive(Debug, Clone)]
+struct BundlePlanModule {
+    package_name: Option<ast::PackageName>,
+    ast_path: PathBuf,
+}
+
+impl BundlePlan {
+    /// Work out the bundle order for a [BuildNinja].
+    ///
+    /// The `codegen_options` should be those of the build's profile, so that the
+    /// bundle matches the modules generated alongside it.
+    pub fn new(
+        package_name: &PackageName,
+        build_ninja: &BuildNinja,
+        unit: JsUnit,
+        codegen_options: CodegenOptions,
+    ) -> Result<Self> {
+        let build_dir = build_ninja.build_dir();
+
+        let mut ast_edges = build_ninja.ast_edges().collect::<Vec<_>>();
+        // Sort for determinism
+        ast_edges.sort_by(|a, b| a.0.cmp(b.0));
+
+        let mut graph = petgraph::Graph::<BundlePlanModule, ()>::new();
+        let mut ast_exports_nodes = HashMap::new();
+        let mut nodes = Vec::new();
+        for (outputs, _inputs) in ast_edges.iter() {
+            let ast_path = outputs[0].clone();
+            let package_name = common::build_path_package_name(build_dir, &ast_path);
+
+            let node_index = graph.add_node(BundlePlanModule {
+                package_name,
+                ast_path,
+            });
+            ast_exports_nodes.insert(outputs[1].clone(), node_index);
+            nodes.push(node_index);
+        }
+
+        for ((_outputs, inputs), node_index) in ast_edges.iter().zip(nodes) {
+            for input in inputs.iter() {
+                if let Some(dependency_index) = ast_exports_nodes.get(input) {
+                    graph.add_edge(*dependency_index, node_index, ());
+                }
+            }
+        }
+
+        match petgraph::algo::toposort(&graph, None) {
+            Ok(sorted) => Ok(Self {
+                package_name: ast::PackageName(package_name.as_str().to_owned()),
+                modules: sorted.into_iter().map(|idx| graph[idx].clone()).collect(),
+                unit,
+                codegen_options,
+            }),
+            Err(cycle) => {
+                let module = &graph[cycle.node_id()];
+                bail!(
+                    "can't bundle modules that form a cycle, including: {}",
+                    module.ast_path.with_extension("").to_string_lossy()
+                )
+            }
+        }
+    }
+
+    /// Generate the bundle from built ASTs and write it to `bundle_path`.
+    pub fn write(&self, bundle_path: &Path) -> Result<()> {
+        let current_dir = env::current_dir().into_diagnostic()?;
+        let bundle_dir = current_dir.join(bundle_path.parent().unwrap_or_else(|| Path::new("")));
+
+        let mut modules = Vec::new();
+        for BundlePlanModule {
+            package_name,
+            ast_path,
+        } in self.modules.iter()
+        {
+            let (ditto_input_path, _source_hash, module) =
+                common::deserialize::<(String, String, ast::Module)>(ast_path)
+                    .wrap_err(format!("error reading {}", ast_path.to_string_lossy()))?;
+
+            let mut foreign_module_path = current_dir.join(ditto_input_path);
+            foreign_module_path.set_extension(common::EXTENSION_JS);
+            let foreign_module_path =
+                pathdiff::diff_paths(foreign_module_path, &bundle_dir).unwrap();
+            let foreign_module_path = common::path_to_import_specifier(&foreign_module_path);
+
+            modules.push(js::BundleModule {
+                module_name: (package_name.clone(), module.module_name.clone()),
+                module,
+                foreign_module_path,
+            });
+        }
+
+        let bundle = js::bundle(
+            modules,
+            &js::BundleConfig {
+                package_name: self.package_name.clone(),
+                strip_debug: self.codegen_options.strip_debug,
+                optimize: self.codegen_options.optimize,
+                runtime_checks: self.codegen_options.runtime_checks,
+                banner: self
+                    .codegen_options
+                    .banner
+                    .then(|| String::from("Generated by ditto, do not edit")),
+                unit: common::js_unit_representation(self.unit),
+            },
+        )
+        .into_diagnostic()
+        .wrap_err("error generating bundle")?;
+
+        let mut bundle_file = File::create(bundle_path)
+            .into_diagnostic()
+            .wrap_err(format!(
+                "error creating bundle {}",
+                bundle_path.to_string_lossy()
+            ))?;
+        bundle_file
+            .write_all(bundle.as_bytes())
+            .into_diagnostic()
+            .wrap_err(format!(
+                "error writing bundle {}",
+                bundle_path.to_string_lossy()
+            ))?;
+        Ok(())
+    }
+}
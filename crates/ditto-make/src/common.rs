@@ -1,9 +1,16 @@
-use ditto_ast::ModuleName;
+use crate::utils::simplify_path;
+use ditto_ast::{ModuleName, PackageName};
+use ditto_codegen_js as js;
+use ditto_config::{ArtifactFormat, JsLayout, JsModuleFormat, JsUnit};
 use miette::{IntoDiagnostic, Result};
-use serde::{de::DeserializeOwned, Serialize};
+use serde::{
+    de::{DeserializeOwned, IgnoredAny},
+    Serialize,
+};
+use sha2::{Digest, Sha256};
 use std::{
     fs::File,
-    io::{BufReader, Write},
+    io::{BufRead, BufReader, Write},
     path::{Path, PathBuf},
 };
 
@@ -11,29 +18,311 @@ pub const EXTENSION_AST: &str = "ast";
 pub const EXTENSION_AST_EXPORTS: &str = "ast-exports";
 pub const EXTENSION_DITTO: &str = "ditto";
 pub const EXTENSION_JS: &str = "js";
+pub const EXTENSION_DTS: &str = "d.ts";
+pub const EXTENSION_JS_MAP: &str = "js.map";
+pub const EXTENSION_MJS: &str = "mjs";
+pub const EXTENSION_DMTS: &str = "d.mts";
+pub const EXTENSION_MJS_MAP: &str = "mjs.map";
+pub const EXTENSION_CJS: &str = "cjs";
+pub const EXTENSION_DCTS: &str = "d.cts";
+pub const EXTENSION_CJS_MAP: &str = "cjs.map";
 pub const EXTENSION_CHECKER_WARNINGS: &str = "checker-warnings";
+/// Marks a module that failed to check, and records the module that caused it.
+pub const EXTENSION_AST_FAILED: &str = "ast-failed";
+
+/// Extensions for a module's generated JavaScript, and the files that go with it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JsExtensions {
+    pub js: &'static str,
+    pub dts: &'static str,
+    pub js_map: &'static str,
+}
+
+impl JsExtensions {
+    /// Plain `.js` extensions, unless `module_extensions` asks for the ones
+    /// matching the `module_format`.
+    pub fn new(module_format: JsModuleFormat, module_extensions: bool) -> Self {
+        match (module_format, module_extensions) {
+            (_, false) => Self::default(),
+            (JsModuleFormat::Esm, true) => Self {
+                js: EXTENSION_MJS,
+                dts: EXTENSION_DMTS,
+                js_map: EXTENSION_MJS_MAP,
+            },
+            (JsModuleFormat::CommonJs, true) => Self {
+                js: EXTENSION_CJS,
+                dts: EXTENSION_DCTS,
+                js_map: EXTENSION_CJS_MAP,
+            },
+        }
+    }
+}
+
+impl Default for JsExtensions {
+    fn default() -> Self {
+        Self {
+            js: EXTENSION_JS,
+            dts: EXTENSION_DTS,
+            js_map: EXTENSION_JS_MAP,
+        }
+    }
+}
 
 pub fn module_name_to_file_stem(module_name: ModuleName) -> PathBuf {
     module_name.into_string(".").into()
 }
 
-/// Serialize a value using a JSON if this is a debug build, and CBOR otherwise.
-pub fn serialize<W: Write, T: Serialize>(writer: W, value: &T) -> Result<()> {
-    if cfg!(debug_assertions) {
-        serde_json::to_writer_pretty(writer, value).into_diagnostic()
+/// Where a module's generated `extension` file lives, relative to the output directory.
+///
+/// NOTE can't use `set_extension` here, as flat file stems contain dots.
+pub fn module_name_to_output_path(
+    module_name: ModuleName,
+    layout: JsLayout,
+    extension: &str,
+) -> PathBuf {
+    let file_stem = match layout {
+        JsLayout::Flat => module_name_to_file_stem(module_name),
+        JsLayout::Nested => module_name
+            .0
+            .into_iter()
+            .map(|proper_name| proper_name.0)
+            .collect(),
+    };
+    let mut file_name = file_stem.into_os_string();
+    file_name.push(".");
+    file_name.push(extension);
+    file_name.into()
+}
+
+/// Where a module's generated JavaScript lives, as a `/` separated specifier
+/// relative to the output directory.
+pub fn module_name_to_js_specifier(
+    module_name: ModuleName,
+    layout: JsLayout,
+    extension: &str,
+) -> String {
+    module_name_to_output_path(module_name, layout, extension)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+/// The codegen equivalent of the `unit` config option.
+pub fn js_unit_representation(unit: JsUnit) -> js::UnitRepresentation {
+    match unit {
+        JsUnit::Undefined => js::UnitRepresentation::Undefined,
+        JsUnit::Sentinel => js::UnitRepresentation::Sentinel,
+    }
+}
+
+/// The codegen equivalent of the `module-format` config option.
+pub fn js_module_format(module_format: JsModuleFormat) -> js::ModuleFormat {
+    match module_format {
+        JsModuleFormat::Esm => js::ModuleFormat::Esm,
+        JsModuleFormat::CommonJs => js::ModuleFormat::Cjs,
+    }
+}
+
+/// Work out which package a build artifact belongs to, given its path.
+///
+/// Artifacts for the current package live directly in the `build_dir`,
+/// whereas package artifacts live in a subdirectory named after the package.
+///
+/// Paths are compared segment-wise, so mixed separators are fine.
+pub fn build_path_package_name(build_dir: &Path, path: &Path) -> Option<PackageName> {
+    let build_dir_segments = path_segments(build_dir);
+    let segments = path_segments(path);
+    match segments.strip_prefix(build_dir_segments.as_slice()) {
+        Some([package_name, _file_name]) => Some(PackageName(package_name.clone())),
+        _ => None,
+    }
+}
+
+/// Convert a relative path to a JavaScript import specifier.
+///
+/// Specifiers always use forward slashes, regardless of platform,
+/// and relative specifiers need to start with `./` or `../`.
+pub fn path_to_import_specifier(path: &Path) -> String {
+    let specifier = simplify_path(path).to_string_lossy().replace('\\', "/");
+    if specifier.starts_with("./") || specifier.starts_with("../") {
+        specifier
     } else {
-        ciborium::ser::into_writer(value, writer).into_diagnostic()
+        format!("./{}", specifier)
     }
 }
 
-/// Deserialize a value using a JSON if this is a debug build, and CBOR otherwise.
+/// Split a path on both `/` and `\`, ignoring empty and `.` segments.
+fn path_segments(path: &Path) -> Vec<String> {
+    simplify_path(path)
+        .to_string_lossy()
+        .split(|c| c == '/' || c == '\\')
+        .filter(|segment| !segment.is_empty() && *segment != ".")
+        .map(String::from)
+        .collect()
+}
+
+/// Serialize a value in the given format.
+pub fn serialize<W: Write, T: Serialize>(
+    format: ArtifactFormat,
+    writer: W,
+    value: &T,
+) -> Result<()> {
+    match format {
+        ArtifactFormat::Json => serde_json::to_writer_pretty(writer, value).into_diagnostic(),
+        ArtifactFormat::Cbor => ciborium::ser::into_writer(value, writer).into_diagnostic(),
+    }
+}
+
+/// Deserialize a value written by [serialize], in whichever format it was written.
+///
+/// JSON always starts with an ASCII character, whereas the CBOR for everything
+/// we serialize (arrays, maps and `null`) never does.
 pub fn deserialize<T: DeserializeOwned>(path: &Path) -> Result<T> {
     let file = File::open(path).into_diagnostic()?;
-    let reader = BufReader::new(file);
+    let mut reader = BufReader::new(file);
 
-    if cfg!(debug_assertions) {
+    let is_json = reader
+        .fill_buf()
+        .into_diagnostic()?
+        .first()
+        .map_or(true, u8::is_ascii);
+    if is_json {
         serde_json::from_reader(reader).into_diagnostic()
     } else {
         ciborium::de::from_reader(reader).into_diagnostic()
     }
 }
+
+/// Hash the contents of a source file.
+///
+/// This is the hash recorded in `.ast` headers and build manifests,
+/// so the two need to agree. It's a SHA-256 (as hex) so that it's stable
+/// across toolchains.
+pub fn hash_contents(contents: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(contents))
+}
+
+/// Read the hash of the source that an `.ast` artifact was compiled from,
+/// without deserializing the whole AST.
+pub fn read_ast_source_hash(path: &Path) -> Result<String> {
+    let (_, source_hash, _) = deserialize::<(IgnoredAny, String, IgnoredAny)>(path)?;
+    Ok(source_hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        build_path_package_name, deserialize, module_name_to_js_specifier,
+        module_name_to_output_path, path_to_import_specifier, serialize, JsExtensions,
+    };
+    use ditto_ast::{module_name, PackageName};
+    use ditto_config::{ArtifactFormat, JsLayout, JsModuleFormat};
+    use std::{fs::File, path::PathBuf};
+
+    #[test]
+    fn it_gets_package_names_from_build_paths() {
+        let build_dir = PathBuf::from(".ditto/build/0.0.1");
+        assert_eq!(
+            build_path_package_name(
+                &build_dir,
+                &PathBuf::from(".ditto/build/0.0.1/A.ast-exports")
+            ),
+            None
+        );
+        assert_eq!(
+            build_path_package_name(
+                &build_dir,
+                &PathBuf::from(".ditto/build/0.0.1/foo/A.ast-exports")
+            ),
+            Some(PackageName(String::from("foo")))
+        );
+        assert_eq!(
+            build_path_package_name(
+                &PathBuf::from(r".ditto\build\0.0.1\"),
+                &PathBuf::from(r".ditto\build\0.0.1\foo\A.ast-exports")
+            ),
+            Some(PackageName(String::from("foo")))
+        );
+        assert_eq!(
+            build_path_package_name(
+                &PathBuf::from(r".\.ditto\build\0.0.1"),
+                &PathBuf::from(".ditto/build/0.0.1/A.ast-exports")
+            ),
+            None
+        );
+        assert_eq!(
+            build_path_package_name(
+                &PathBuf::from(r"C:\project\.ditto\build\0.0.1"),
+                &PathBuf::from(r"\\?\C:\project\.ditto\build\0.0.1\foo\A.ast-exports")
+            ),
+            Some(PackageName(String::from("foo")))
+        );
+    }
+
+    #[test]
+    fn it_deserializes_either_artifact_format() {
+        let dir = tempfile::tempdir().unwrap();
+        for format in [ArtifactFormat::Json, ArtifactFormat::Cbor] {
+            let path = dir.path().join("artifact");
+            let value = (String::from("A.ditto"), 42_u64, Some(vec![1, 2, 3]));
+            serialize(format, File::create(&path).unwrap(), &value).unwrap();
+            assert_eq!(
+                deserialize::<(String, u64, Option<Vec<u8>>)>(&path).unwrap(),
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn it_lays_out_output_paths() {
+        let module_name = module_name!("Data", "Stuff");
+        assert_eq!(
+            module_name_to_output_path(module_name.clone(), JsLayout::Flat, "js"),
+            PathBuf::from("Data.Stuff.js")
+        );
+        assert_eq!(
+            module_name_to_output_path(module_name.clone(), JsLayout::Nested, "js"),
+            ["Data", "Stuff.js"].iter().collect::<PathBuf>()
+        );
+        assert_eq!(
+            module_name_to_output_path(module_name, JsLayout::Nested, "d.ts"),
+            ["Data", "Stuff.d.ts"].iter().collect::<PathBuf>()
+        );
+    }
+
+    #[test]
+    fn it_picks_extensions_for_the_module_format() {
+        assert_eq!(
+            JsExtensions::new(JsModuleFormat::CommonJs, false),
+            JsExtensions::default()
+        );
+        assert_eq!(JsExtensions::new(JsModuleFormat::Esm, true).js, "mjs");
+        let extensions = JsExtensions::new(JsModuleFormat::CommonJs, true);
+        assert_eq!(
+            module_name_to_js_specifier(
+                module_name!("Data", "Stuff"),
+                JsLayout::Nested,
+                extensions.js
+            ),
+            "Data/Stuff.cjs"
+        );
+        assert_eq!(extensions.dts, "d.cts");
+        assert_eq!(extensions.js_map, "cjs.map");
+    }
+
+    #[test]
+    fn it_converts_paths_to_import_specifiers() {
+        assert_eq!(
+            path_to_import_specifier(&PathBuf::from("../src/A.js")),
+            "../src/A.js"
+        );
+        assert_eq!(
+            path_to_import_specifier(&PathBuf::from(r"..\src\Some.Module.js")),
+            "../src/Some.Module.js"
+        );
+        assert_eq!(
+            path_to_import_specifier(&PathBuf::from(r"src\A.js")),
+            "./src/A.js"
+        );
+        assert_eq!(path_to_import_specifier(&PathBuf::from("A.js")), "./A.js");
+    }
+}
@@ -2,26 +2,57 @@ use clap::{Arg, ArgMatches, Command};
 use ditto_ast as ast;
 use ditto_checker as checker;
 use ditto_codegen_js as js;
-use ditto_config::read_config;
+use ditto_config::{read_config, ArtifactFormat, JsLayout, JsModuleFormat, JsUnit, Profile};
 use ditto_cst as cst;
-use miette::{miette, IntoDiagnostic, NamedSource, Report, Result};
+use miette::{miette, IntoDiagnostic, Result, WrapErr};
 use std::{
-    collections::HashMap,
-    fs::File,
+    collections::{HashMap, HashSet},
+    env,
+    fs::{self, File},
     io::{Read, Write},
     path::{Path, PathBuf},
+    sync::Arc,
 };
 
-use crate::common;
+use crate::{
+    common,
+    index::{self, IndexEntryKind},
+};
 
 pub static SUBCOMMAND_AST: &str = "ast";
 pub static SUBCOMMAND_JS: &str = "js";
 pub static SUBCOMMAND_PACKAGE_JSON: &str = "package_json";
+pub static SUBCOMMAND_FILE: &str = "file";
 
 pub static ARG_BUILD_DIR: &str = "build-dir";
+pub static ARG_INDEX_DIR: &str = "index-dir";
+pub static ARG_ALLOW_WARNING: &str = "allow";
+pub static ARG_WARN_WARNING: &str = "warn";
+pub static ARG_DENY_WARNING: &str = "deny";
+pub static ARG_NO_DEBUG: &str = "no-debug";
+pub static ARG_OUT_DIR: &str = "out-dir";
+pub static ARG_DTS: &str = "dts";
+pub static ARG_LAYOUT: &str = "layout";
+pub static ARG_UNIT: &str = "unit";
+pub static ARG_ALLOW_INLINE_JS: &str = "allow-inline-js";
+pub static ARG_ARTIFACTS: &str = "artifacts";
+pub static ARG_OPTIMIZE: &str = "optimize";
+pub static ARG_RUNTIME_CHECKS: &str = "runtime-checks";
+pub static ARG_BANNER: &str = "banner";
+pub static ARG_MODULE_FORMAT: &str = "module-format";
+pub static ARG_MODULE_EXTENSIONS: &str = "module-extensions";
 pub static ARG_INPUTS: char = 'i';
 pub static ARG_OUTPUTS: char = 'o';
 
+pub static LAYOUT_FLAT: &str = "flat";
+pub static LAYOUT_NESTED: &str = "nested";
+
+pub static UNIT_UNDEFINED: &str = "undefined";
+pub static UNIT_SENTINEL: &str = "sentinel";
+
+pub static MODULE_FORMAT_ESM: &str = "esm";
+pub static MODULE_FORMAT_COMMONJS: &str = "commonjs";
+
 /// The internal compile CLI.
 pub fn command(name: &str) -> Command<'_> {
     let arg_input = || {
@@ -52,6 +83,14 @@ pub fn command(name: &str) -> Command<'_> {
             .multiple_values(true)
     };
 
+    let arg_module_format = || {
+        Arg::new("module-format")
+            .long(ARG_MODULE_FORMAT)
+            .takes_value(true)
+            .possible_values([MODULE_FORMAT_ESM, MODULE_FORMAT_COMMONJS])
+            .default_value(MODULE_FORMAT_ESM)
+    };
+
     Command::new(name)
         .subcommand(
             Command::new(SUBCOMMAND_AST)
@@ -61,25 +100,129 @@ pub fn command(name: &str) -> Command<'_> {
                         .required(true)
                         .takes_value(true),
                 )
+                .arg(
+                    Arg::new("index-dir")
+                        .long(ARG_INDEX_DIR)
+                        .takes_value(true)
+                        .help("Index of installed packages, used to suggest imports"),
+                )
+                .arg(
+                    Arg::new("allow")
+                        .long(ARG_ALLOW_WARNING)
+                        .takes_value(true)
+                        .multiple_occurrences(true),
+                )
+                .arg(
+                    Arg::new("warn")
+                        .long(ARG_WARN_WARNING)
+                        .takes_value(true)
+                        .multiple_occurrences(true),
+                )
+                .arg(
+                    Arg::new("deny")
+                        .long(ARG_DENY_WARNING)
+                        .takes_value(true)
+                        .multiple_occurrences(true),
+                )
+                .arg(
+                    Arg::new("allow-inline-js")
+                        .long(ARG_ALLOW_INLINE_JS)
+                        .takes_value(false),
+                )
+                .arg(
+                    Arg::new("artifacts")
+                        .long(ARG_ARTIFACTS)
+                        .takes_value(true)
+                        .possible_values(ArtifactFormat::ALL)
+                        .default_value(ArtifactFormat::Json.as_str()),
+                )
                 .arg(arg_inputs())
                 .arg(arg_outputs()),
         )
         .subcommand(
             Command::new(SUBCOMMAND_JS)
+                .arg(
+                    Arg::new("build-dir")
+                        .long(ARG_BUILD_DIR)
+                        .takes_value(true)
+                        .help("Where dependency exports live, needed to inline their constants"),
+                )
+                .arg(Arg::new("no-debug").long(ARG_NO_DEBUG).takes_value(false))
+                .arg(Arg::new("optimize").long(ARG_OPTIMIZE).takes_value(false))
+                .arg(
+                    Arg::new("runtime-checks")
+                        .long(ARG_RUNTIME_CHECKS)
+                        .takes_value(false),
+                )
+                .arg(Arg::new("banner").long(ARG_BANNER).takes_value(false))
+                .arg(
+                    Arg::new("layout")
+                        .long(ARG_LAYOUT)
+                        .takes_value(true)
+                        .possible_values([LAYOUT_FLAT, LAYOUT_NESTED])
+                        .default_value(LAYOUT_FLAT),
+                )
+                .arg(
+                    Arg::new("unit")
+                        .long(ARG_UNIT)
+                        .takes_value(true)
+                        .possible_values([UNIT_UNDEFINED, UNIT_SENTINEL])
+                        .default_value(UNIT_UNDEFINED),
+                )
+                .arg(arg_module_format())
+                .arg(
+                    Arg::new("module-extensions")
+                        .long(ARG_MODULE_EXTENSIONS)
+                        .takes_value(false),
+                )
                 .arg(arg_inputs())
                 .arg(arg_outputs()),
         )
         .subcommand(
             Command::new(SUBCOMMAND_PACKAGE_JSON)
+                .arg(arg_module_format())
                 .arg(arg_input())
                 .arg(arg_output()),
         )
+        .subcommand(
+            Command::new(SUBCOMMAND_FILE)
+                .about("Compile a single module to JavaScript, without a project")
+                .arg(
+                    Arg::new("file")
+                        .required(true)
+                        .takes_value(true)
+                        .help("The .ditto file to compile"),
+                )
+                .arg(
+                    Arg::new("out-dir")
+                        .long(ARG_OUT_DIR)
+                        .takes_value(true)
+                        .default_value(".")
+                        .help("Directory to write the generated JavaScript to"),
+                )
+                .arg(
+                    Arg::new("dts")
+                        .long(ARG_DTS)
+                        .help("Also generate TypeScript declarations"),
+                )
+                .arg(
+                    Arg::new("no-debug")
+                        .long(ARG_NO_DEBUG)
+                        .help("Strip debug calls from the generated JavaScript"),
+                )
+                .arg(
+                    Arg::new("allow-inline-js")
+                        .long(ARG_ALLOW_INLINE_JS)
+                        .help("Allow inline JavaScript expressions"),
+                ),
+        )
 }
 
 /// Run the program given matches from [compile].
 pub fn run(matches: &ArgMatches) -> Result<()> {
     if let Some(matches) = matches.subcommand_matches(SUBCOMMAND_AST) {
         let build_dir = matches.value_of("build-dir").unwrap();
+        let index_dir = matches.value_of("index-dir");
 
         let inputs = matches.values_of("inputs").unwrap();
         let input_strings = inputs
@@ -93,8 +236,49 @@ pub fn run(matches: &ArgMatches) -> Result<()> {
             .map(|output| output.to_owned())
             .collect::<Vec<_>>();
 
-        run_ast(build_dir, input_strings, output_strings)
+        let allowed_warnings = matches
+            .values_of("allow")
+            .map(|values| values.map(|value| value.to_owned()).collect())
+            .unwrap_or_default();
+
+        let denied_warnings: HashSet<String> = matches
+            .values_of("deny")
+            .map(|values| values.map(|value| value.to_owned()).collect())
+            .unwrap_or_default();
+
+        let warned_warnings: HashSet<String> = matches
+            .values_of("warn")
+            .map(|values| values.map(|value| value.to_owned()).collect())
+            .unwrap_or_default();
+
+        // Opt-in lints are only checked if they've been switched on
+        let opted_in =
+            |code: &str| warned_warnings.contains(code) || denied_warnings.contains(code);
+        let check_options = checker::CheckOptions {
+            single_use_imports: opted_in("single-use-import"),
+        };
+
+        let allow_inline_js = matches.is_present("allow-inline-js");
+
+        let artifacts = matches
+            .value_of("artifacts")
+            .and_then(ArtifactFormat::parse)
+            .unwrap();
+
+        run_ast(
+            build_dir,
+            index_dir,
+            check_options,
+            allowed_warnings,
+            denied_warnings,
+            allow_inline_js,
+            artifacts,
+            input_strings,
+            output_strings,
+        )
     } else if let Some(matches) = matches.subcommand_matches(SUBCOMMAND_JS) {
+        let build_dir = matches.value_of("build-dir");
+
         let inputs = matches.values_of("inputs").unwrap();
         let input_strings = inputs
             .into_iter()
@@ -107,50 +291,201 @@ pub fn run(matches: &ArgMatches) -> Result<()> {
             .map(|output| output.to_owned())
             .collect::<Vec<_>>();
 
-        run_js(input_strings, output_strings)
+        let layout = if matches.value_of("layout") == Some(LAYOUT_NESTED) {
+            JsLayout::Nested
+        } else {
+            JsLayout::Flat
+        };
+
+        let unit = if matches.value_of("unit") == Some(UNIT_SENTINEL) {
+            JsUnit::Sentinel
+        } else {
+            JsUnit::Undefined
+        };
+
+        let module_format = module_format_value(matches);
+        let extensions =
+            common::JsExtensions::new(module_format, matches.is_present("module-extensions"));
+
+        let codegen_options = CodegenOptions {
+            strip_debug: matches.is_present("no-debug"),
+            optimize: matches.is_present("optimize"),
+            runtime_checks: matches.is_present("runtime-checks"),
+            banner: matches.is_present("banner"),
+        };
+
+        run_js(
+            build_dir,
+            codegen_options,
+            layout,
+            unit,
+            module_format,
+            extensions,
+            input_strings,
+            output_strings,
+        )
     } else if let Some(matches) = matches.subcommand_matches(SUBCOMMAND_PACKAGE_JSON) {
         let input = matches.value_of("input").unwrap();
         let output = matches.value_of("output").unwrap();
-        run_package_json(input, output)
+        run_package_json(input, output, module_format_value(matches))
+    } else if let Some(matches) = matches.subcommand_matches(SUBCOMMAND_FILE) {
+        let file = matches.value_of("file").unwrap();
+        let out_dir = matches.value_of("out-dir").unwrap();
+        let dts = matches.is_present("dts");
+        let strip_debug = matches.is_present("no-debug");
+        let allow_inline_js = matches.is_present("allow-inline-js");
+        run_file(file, out_dir, dts, strip_debug, allow_inline_js)
     } else {
         unreachable!()
     }
 }
 
+fn module_format_value(matches: &ArgMatches) -> JsModuleFormat {
+    if matches.value_of("module-format") == Some(MODULE_FORMAT_COMMONJS) {
+        JsModuleFormat::CommonJs
+    } else {
+        JsModuleFormat::Esm
+    }
+}
+
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct WarningsBundle {
     pub name: String,
     pub source: String,
-    // REVIEW these warnings should really be in a deterministic order!
+    /// In source order, as reported by the checker.
     pub warnings: Vec<checker::WarningReport>,
 }
 
-fn run_ast(build_dir: &str, inputs: Vec<String>, outputs: Vec<String>) -> Result<()> {
+/// A module wasn't checked because one of its dependencies failed to check.
+///
+/// This is reported as a short note rather than a full diagnostic, as the
+/// root cause will have been reported already.
+#[derive(Debug, thiserror::Error, miette::Diagnostic)]
+#[error("skipped {module} because {failed_dependency} failed")]
+#[diagnostic(severity(Advice))]
+pub struct SkippedModule {
+    /// The module that wasn't checked.
+    pub module: String,
+    /// The module that failed, which might be a transitive dependency.
+    pub failed_dependency: String,
+}
+
+static SKIPPED_MODULE_NOTE_PREFIX: &str = "note: skipped ";
+
+impl SkippedModule {
+    /// Render as a single line, which can be picked out by [SkippedModule::parse_note].
+    pub fn render_note(&self) -> String {
+        format!("note: {}", self)
+    }
+
+    /// Get the skipped module name from a line printed by [SkippedModule::render_note].
+    pub fn parse_note(line: &str) -> Option<&str> {
+        line.strip_prefix(SKIPPED_MODULE_NOTE_PREFIX)
+            .and_then(|rest| rest.split_once(" because "))
+            .map(|(module, _)| module)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn run_ast(
+    build_dir: &str,
+    index_dir: Option<&str>,
+    check_options: checker::CheckOptions,
+    allowed_warnings: HashSet<String>,
+    denied_warnings: HashSet<String>,
+    allow_inline_js: bool,
+    artifacts: ArtifactFormat,
+    inputs: Vec<String>,
+    outputs: Vec<String>,
+) -> Result<()> {
+    let failed_marker_path = outputs
+        .iter()
+        .map(Path::new)
+        .find(|path| path.extension() == Some(common::EXTENSION_AST.as_ref()))
+        .map(|path| path.with_extension(common::EXTENSION_AST_FAILED));
+
+    let result = check_ast(
+        build_dir,
+        index_dir,
+        check_options,
+        allowed_warnings,
+        denied_warnings,
+        allow_inline_js,
+        artifacts,
+        inputs,
+        outputs.clone(),
+    );
+
+    if let Some(failed_marker_path) = failed_marker_path {
+        if let Err(ref err) = result {
+            // Remove stale exports, so that dependents will know not to bother,
+            // and stale warnings, so that they aren't reported alongside the error
+            for output in outputs.iter().map(Path::new) {
+                let extension = output.extension();
+                if extension == Some(common::EXTENSION_AST_EXPORTS.as_ref())
+                    || extension == Some(common::EXTENSION_CHECKER_WARNINGS.as_ref())
+                {
+                    let _ = fs::remove_file(output);
+                }
+            }
+            let failed_module = match err.downcast_ref::<SkippedModule>() {
+                Some(skipped) => skipped.failed_dependency.clone(),
+                None => failed_marker_path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+            };
+            fs::write(&failed_marker_path, failed_module).into_diagnostic()?;
+        } else if failed_marker_path.exists() {
+            fs::remove_file(&failed_marker_path).into_diagnostic()?;
+        }
+    }
+    result
+}
+
+#[allow(clippy::too_many_arguments)]
+fn check_ast(
+    build_dir: &str,
+    index_dir: Option<&str>,
+    check_options: checker::CheckOptions,
+    allowed_warnings: HashSet<String>,
+    denied_warnings: HashSet<String>,
+    allow_inline_js: bool,
+    artifacts: ArtifactFormat,
+    inputs: Vec<String>,
+    outputs: Vec<String>,
+) -> Result<()> {
     let mut ditto_input = None;
     let mut everything = checker::Everything::default();
+    let mut failed_dependency = None;
 
     for input in inputs {
         let path = Path::new(&input);
         match full_extension(path) {
             Some(common::EXTENSION_DITTO) => {
+                // Hash exactly the bytes we compile, as the file might have
+                // changed since the build was planned (e.g. in watch mode)
                 let mut file = File::open(path).into_diagnostic()?;
-                let mut contents = String::new();
-                file.read_to_string(&mut contents).into_diagnostic()?;
-                ditto_input = Some((path.to_string_lossy().into_owned(), contents));
+                let mut bytes = Vec::new();
+                file.read_to_end(&mut bytes).into_diagnostic()?;
+                let source_hash = common::hash_contents(&bytes);
+                let contents = String::from_utf8(bytes).into_diagnostic()?;
+                ditto_input = Some((
+                    path.to_string_lossy().into_owned(),
+                    source_hash,
+                    Arc::from(contents),
+                ));
             }
             Some(common::EXTENSION_AST_EXPORTS) => {
+                let failed_marker_path = path.with_extension(common::EXTENSION_AST_FAILED);
+                if !path.exists() && failed_marker_path.exists() {
+                    failed_dependency =
+                        Some(fs::read_to_string(failed_marker_path).into_diagnostic()?);
+                    continue;
+                }
                 let (module_name, module_exports) = common::deserialize(path)?;
 
-                let mut package_name = None;
-                if let Some(parent) = path.parent() {
-                    if parent.to_str() != Some(build_dir) {
-                        let dir = parent
-                            .file_name()
-                            .and_then(|file_name| file_name.to_str())
-                            .unwrap();
-                        package_name = Some(ditto_ast::PackageName(dir.to_owned()));
-                    }
-                }
+                let package_name = common::build_path_package_name(Path::new(build_dir), path);
 
                 if let Some(package_name) = package_name {
                     if let Some(package) = everything.packages.get_mut(&package_name) {
@@ -168,13 +503,61 @@ fn run_ast(build_dir: &str, inputs: Vec<String>, outputs: Vec<String>) -> Result
         }
     }
 
-    let (ditto_input_name, ditto_input_source) = ditto_input.unwrap();
+    let (ditto_input_name, ditto_input_hash, ditto_input_source): (String, String, Arc<String>) =
+        ditto_input.unwrap();
 
     let cst = cst::Module::parse(&ditto_input_source)
         .map_err(|err| err.into_report(&ditto_input_name, ditto_input_source.clone()))?;
 
-    let (ast, warnings) = checker::check_module(&everything, cst)
-        .map_err(|err| err.into_report(&ditto_input_name, ditto_input_source.clone()))?;
+    if !allow_inline_js {
+        check_no_inline_js(&cst, &ditto_input_name, ditto_input_source.clone())?;
+    }
+
+    if let Some(failed_dependency) = failed_dependency {
+        let module = ast::ModuleName::from(cst.header.module_name).to_string();
+        return Err(SkippedModule {
+            module,
+            failed_dependency,
+        }
+        .into());
+    }
+
+    let (ast, warnings) = checker::check_module_with_options(&everything, cst, check_options)
+        .map_err(|err| {
+            let imports = index_dir
+                .map(|index_dir| suggest_imports(Path::new(index_dir), &err))
+                .unwrap_or_default();
+            err.into_report_with_imports(&ditto_input_name, ditto_input_source.clone(), imports)
+        })?;
+
+    // Type variables are numbered in whatever order the checker got to them,
+    // renumber them so that irrelevant edits don't change the serialized outputs
+    // (and so don't trigger rebuilds of dependents)
+    let ast = ast.normalize_type_variables();
+
+    let (denied, warnings): (Vec<_>, Vec<_>) = warnings
+        .into_iter()
+        .filter(|warning| !allowed_warnings.contains(warning.code()))
+        .partition(|warning| denied_warnings.contains(warning.code()));
+
+    if !denied.is_empty() {
+        // Fail before writing any outputs, so that ninja will try again next time
+        let denied_len = denied.len();
+        for warning in denied {
+            eprintln!(
+                "{:?}",
+                warning
+                    .into_report()
+                    .with_source_code(&ditto_input_name, ditto_input_source.clone())
+            );
+        }
+        return Err(miette!(
+            "{} denied warning{} in {}",
+            denied_len,
+            if denied_len == 1 { "" } else { "s" },
+            ditto_input_name
+        ));
+    }
 
     let warnings = warnings
         .into_iter()
@@ -187,11 +570,15 @@ fn run_ast(build_dir: &str, inputs: Vec<String>, outputs: Vec<String>) -> Result
         match full_extension(path) {
             Some(common::EXTENSION_AST) => {
                 let file = File::create(path).into_diagnostic()?;
-                common::serialize(file, &(&ditto_input_name, &ast))?;
+                common::serialize(
+                    artifacts,
+                    file,
+                    &(&ditto_input_name, &ditto_input_hash, &ast),
+                )?;
             }
             Some(common::EXTENSION_AST_EXPORTS) => {
                 let file = File::create(path).into_diagnostic()?;
-                common::serialize(file, &(&ast.module_name, &ast.exports))?;
+                common::serialize(artifacts, file, &(&ast.module_name, &ast.exports))?;
             }
             Some(common::EXTENSION_CHECKER_WARNINGS) => {
                 let file = File::create(path).into_diagnostic()?;
@@ -200,24 +587,22 @@ fn run_ast(build_dir: &str, inputs: Vec<String>, outputs: Vec<String>) -> Result
                 } else {
                     Some(WarningsBundle {
                         name: ditto_input_name.clone(),
-                        source: ditto_input_source.clone(),
+                        source: ditto_input_source.to_string(),
                         warnings: warnings.clone(),
                     })
                 };
-                common::serialize(file, &warnings_bundle)?;
+                common::serialize(artifacts, file, &warnings_bundle)?;
                 print_warnings = false;
             }
             other => panic!("unexpected output extension: {:#?}", other),
         }
     }
 
-    if print_warnings && !warnings.is_empty() {
-        let source = std::sync::Arc::new(ditto_input_source);
+    if print_warnings {
         for warning in warnings {
             eprintln!(
                 "{:?}",
-                Report::from(warning)
-                    .with_source_code(NamedSource::new(&ditto_input_name, source.clone()))
+                warning.with_source_code(&ditto_input_name, ditto_input_source.clone())
             );
         }
     }
@@ -225,86 +610,351 @@ fn run_ast(build_dir: &str, inputs: Vec<String>, outputs: Vec<String>) -> Result
     Ok(())
 }
 
-fn run_js(inputs: Vec<String>, outputs: Vec<String>) -> Result<()> {
+/// Settings for generating JavaScript, which are set by the build profile.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CodegenOptions {
+    /// Remove `debug_log` calls.
+    pub strip_debug: bool,
+    /// Simplify the generated code.
+    pub optimize: bool,
+    /// Check foreign values when a module is loaded.
+    pub runtime_checks: bool,
+    /// Start generated files with a comment.
+    pub banner: bool,
+}
+
+impl CodegenOptions {
+    /// The options for building with `profile`.
+    pub fn for_profile(profile: Profile) -> Self {
+        Self {
+            strip_debug: false,
+            optimize: profile.optimize,
+            runtime_checks: profile.runtime_checks,
+            banner: profile.banner,
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_js(
+    build_dir: Option<&str>,
+    codegen_options: CodegenOptions,
+    layout: JsLayout,
+    unit: JsUnit,
+    module_format: JsModuleFormat,
+    extensions: common::JsExtensions,
+    inputs: Vec<String>,
+    outputs: Vec<String>,
+) -> Result<()> {
     let mut ditto_input_path = None;
     let mut ast = None;
-    let mut js_output_path = None;
-    //let mut dts_output_path = None;
+    let mut inline_values = js::InlineValues::new();
 
     for input in inputs {
         let path = Path::new(&input);
         match full_extension(path) {
             Some(common::EXTENSION_AST) => {
-                let (deserialized_path, deserialized_ast) =
-                    common::deserialize::<(String, ast::Module)>(path)?;
+                let (deserialized_path, _source_hash, deserialized_ast) =
+                    common::deserialize::<(String, String, ast::Module)>(path)?;
                 ditto_input_path = Some(deserialized_path);
                 ast = Some(deserialized_ast);
             }
-            other => return Err(miette!("unexpected input extension: {:#?}", other)),
-        }
-    }
-
-    for output in outputs {
-        let path = Path::new(&output);
-        match full_extension(path) {
-            Some(common::EXTENSION_JS) => {
-                js_output_path = Some(path.to_path_buf());
+            // Dependency exports are only passed to optimized builds, for inlining
+            Some(common::EXTENSION_AST_EXPORTS) => {
+                let build_dir = build_dir
+                    .ok_or_else(|| miette!("--{} is needed to read {}", ARG_BUILD_DIR, input))?;
+                let (module_name, module_exports): (ast::ModuleName, ast::ModuleExports) =
+                    common::deserialize(path)?;
+                let package_name = common::build_path_package_name(Path::new(build_dir), path);
+                inline_values.extend(js::exported_inline_values(
+                    &(package_name, module_name),
+                    &module_exports,
+                ));
             }
-            //Some(common::EXTENSION_DTS) => {
-            //    dts_output_path = Some(path.to_path_buf());
-            //}
-            other => return Err(miette!("unexpected output extension: {:#?}", other)),
+            other => return Err(miette!("unexpected input extension: {:#?}", other)),
         }
     }
 
     // Make sure we got everything we expected
     let ditto_input_path = ditto_input_path.ok_or_else(|| miette!("AST input not specified"))?;
     let ast = ast.ok_or_else(|| miette!("AST input not specified"))?;
-    let js_output_path = js_output_path.ok_or_else(|| miette!("JS output not specified"))?;
-    //let dts_output_path =
-    //    dts_output_path.ok_or_else(|| miette!("TypeScript declaration output not specified"))?;
+    let (js_output_path, dts_output_path, map_output_path) = js_output_paths(outputs, extensions)?;
+    let js_output_dir = js_output_path.parent().unwrap();
 
-    let mut foreign_module_path = PathBuf::from(ditto_input_path);
-    foreign_module_path.set_extension(common::EXTENSION_JS);
-    let foreign_module_path =
-        pathdiff::diff_paths(foreign_module_path, js_output_path.parent().unwrap()).unwrap();
-
-    let js = js::codegen(
-        &js::Config {
-            // We don't want platform specific path seperators here,
-            // NodeJS will handle Unix slash paths
-            foreign_module_path: path_slash::PathBufExt::to_slash_lossy(&foreign_module_path),
-            module_name_to_path: Box::new(move |(package_name, module_name)| match package_name {
-                Some(package_name) => {
-                    format!(
-                        "{}/{}.{}",
-                        package_name,
-                        common::module_name_to_file_stem(module_name).to_string_lossy(),
-                        common::EXTENSION_JS
-                    )
-                }
-                None => {
-                    // Assume that JS files from the same ditto project are always going to be generated
-                    // into a flat directory
-                    format!(
-                        "./{}.{}",
-                        common::module_name_to_file_stem(module_name).to_string_lossy(),
-                        common::EXTENSION_JS
-                    )
-                }
-            }),
-        },
-        ast,
+    let mut config = js_config(
+        Path::new(&ditto_input_path),
+        js_output_dir,
+        &ast.module_name,
+        layout,
+        unit,
+        module_format,
+        extensions,
+        codegen_options,
     );
+    config.inline_values = inline_values;
+
+    if let Some(dts_output_path) = dts_output_path {
+        // Generate everything from the one deserialized AST
+        let dts = js::codegen_dts(&config, &ast);
+        let mut dts_file = File::create(&dts_output_path).into_diagnostic()?;
+        dts_file.write_all(dts.as_bytes()).into_diagnostic()?;
+    }
 
+    let js = if let Some(map_output_path) = map_output_path {
+        let source = fs::read_to_string(&ditto_input_path)
+            .into_diagnostic()
+            .wrap_err(format!("error reading {}", ditto_input_path))?;
+        let source_path =
+            pathdiff::diff_paths(Path::new(&ditto_input_path), js_output_dir).unwrap();
+        config.source_map = Some(js::SourceMapConfig {
+            file: js_output_path
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .into_owned(),
+            // Source map paths are URLs, so always use forward slashes
+            source_path: source_path.to_string_lossy().replace('\\', "/"),
+            source,
+        });
+        let (js, source_map) = js::codegen_with_sourcemap(&config, ast);
+        let mut map_file = File::create(&map_output_path).into_diagnostic()?;
+        map_file
+            .write_all(source_map.as_bytes())
+            .into_diagnostic()?;
+        js
+    } else {
+        js::codegen(&config, ast)
+    };
     let mut js_file = File::create(&js_output_path).into_diagnostic()?;
     js_file.write_all(js.as_bytes()).into_diagnostic()?;
 
     Ok(())
 }
 
+/// Codegen config for `module_name` compiled from `ditto_input_path` into `js_output_dir`.
+///
+/// Foreign modules are expected to be siblings of the ditto source.
+#[allow(clippy::too_many_arguments)]
+fn js_config(
+    ditto_input_path: &Path,
+    js_output_dir: &Path,
+    module_name: &ast::ModuleName,
+    layout: JsLayout,
+    unit: JsUnit,
+    module_format: JsModuleFormat,
+    extensions: common::JsExtensions,
+    codegen_options: CodegenOptions,
+) -> js::Config {
+    let mut foreign_module_path = ditto_input_path.to_path_buf();
+    foreign_module_path.set_extension(common::EXTENSION_JS);
+    let foreign_module_path = pathdiff::diff_paths(foreign_module_path, js_output_dir).unwrap();
+    // We don't want platform specific path seperators here,
+    // NodeJS will handle Unix slash paths
+    let foreign_module_path = common::path_to_import_specifier(&foreign_module_path);
+
+    // JS files from the same package are all generated into the same directory,
+    // so work out how to get back to it from this module
+    let package_root = match layout {
+        JsLayout::Flat => String::from("./"),
+        JsLayout::Nested => match usize::from(module_name.0.len()) - 1 {
+            0 => String::from("./"),
+            depth => "../".repeat(depth),
+        },
+    };
+
+    js::Config {
+        foreign_module_path,
+        module_name_to_path: Box::new(move |(package_name, module_name)| {
+            let specifier = common::module_name_to_js_specifier(module_name, layout, extensions.js);
+            match package_name {
+                Some(package_name) => format!("{}/{}", package_name, specifier),
+                None => format!("{}{}", package_root, specifier),
+            }
+        }),
+        strip_debug: codegen_options.strip_debug,
+        optimize: codegen_options.optimize,
+        inline_values: js::InlineValues::new(),
+        runtime_checks: codegen_options.runtime_checks,
+        banner: codegen_options.banner.then(|| {
+            format!(
+                "Generated by ditto from {}, do not edit",
+                ditto_input_path.to_string_lossy().replace('\\', "/")
+            )
+        }),
+        module_format: common::js_module_format(module_format),
+        unit: common::js_unit_representation(unit),
+        pretty: false,
+        source_map: None,
+    }
+}
+
+/// Inline JavaScript was used without being allowed by the package config.
+#[derive(Debug, thiserror::Error, miette::Diagnostic)]
+#[error("inline JavaScript isn't allowed")]
+#[diagnostic(help("set `allow-inline-js = true` under [codegen-js] in ditto.toml"))]
+struct InlineJsNotAllowed {
+    #[source_code]
+    input: miette::NamedSource,
+    #[label("inline JavaScript used here")]
+    span: miette::SourceSpan,
+}
+
+/// Reject inline JavaScript, which has to be opted into.
+fn check_no_inline_js(cst: &cst::Module, name: &str, source: Arc<String>) -> Result<()> {
+    if let Some(span) = cst.inline_js_spans().into_iter().next() {
+        return Err(InlineJsNotAllowed {
+            input: miette::NamedSource::new(name, source),
+            span: (span.start_offset, span.end_offset - span.start_offset).into(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Import lines from the package index that would fix an unknown name.
+///
+/// The index is only consulted once checking has failed, as it isn't needed otherwise.
+fn suggest_imports(index_dir: &Path, err: &checker::TypeError) -> Vec<String> {
+    let (name, kind) = match err.unknown_name() {
+        Some(checker::UnknownName::Value(name)) => (name.0, IndexEntryKind::Value),
+        Some(checker::UnknownName::Type(name)) => (name.0, IndexEntryKind::Type),
+        Some(checker::UnknownName::Constructor(name)) => (name.0, IndexEntryKind::Constructor),
+        None => return Vec::new(),
+    };
+    // Suggestions are a nicety, so a missing or broken index isn't an error
+    let entries = index::read_index(index_dir).unwrap_or_default();
+    let mut imports = entries
+        .into_iter()
+        .filter(|entry| entry.kind == kind && entry.name == name)
+        .map(|entry| entry.render_import())
+        .collect::<Vec<_>>();
+    imports.dedup();
+    imports
+}
+
+/// Compiles a single, self-contained module straight to JavaScript.
+///
+/// There's no project, so the module can't import anything.
+fn run_file(
+    input: &str,
+    out_dir: &str,
+    dts: bool,
+    strip_debug: bool,
+    allow_inline_js: bool,
+) -> Result<()> {
+    let mut contents = String::new();
+    File::open(input)
+        .and_then(|mut file| file.read_to_string(&mut contents))
+        .into_diagnostic()
+        .wrap_err(format!("error reading {}", input))?;
+    let source: Arc<String> = Arc::from(contents);
+
+    let cst = cst::Module::parse(&source).map_err(|err| err.into_report(input, source.clone()))?;
+
+    if !allow_inline_js {
+        check_no_inline_js(&cst, input, source.clone())?;
+    }
+
+    if let Some(import_line) = cst.imports.first() {
+        return Err(miette!(
+            "can't import {} in {}: standalone mode cannot resolve imports; create a project",
+            ast::ModuleName::from(import_line.module_name.clone()),
+            input
+        ));
+    }
+
+    let (ast, warnings) = checker::check_module(&checker::Everything::default(), cst)
+        .map_err(|err| err.into_report(input, source.clone()))?;
+
+    for warning in warnings {
+        eprintln!(
+            "{:?}",
+            warning
+                .into_report()
+                .with_source_code(input, source.clone())
+        );
+    }
+
+    // The foreign module path is relative, so both paths need to be comparable
+    let current_dir = env::current_dir().into_diagnostic()?;
+    let input_path = current_dir.join(input);
+    let out_dir = current_dir.join(out_dir);
+    fs::create_dir_all(&out_dir)
+        .into_diagnostic()
+        .wrap_err(format!("error creating {}", out_dir.to_string_lossy()))?;
+
+    // NOTE can't use `set_extension` here, as module file stems contain dots
+    let file_stem = common::module_name_to_file_stem(ast.module_name.clone());
+    let output_path =
+        |extension: &str| out_dir.join(format!("{}.{}", file_stem.to_string_lossy(), extension));
+
+    // There are no imports, so the layout doesn't matter
+    let config = js_config(
+        &input_path,
+        &out_dir,
+        &ast.module_name,
+        JsLayout::Flat,
+        JsUnit::default(),
+        JsModuleFormat::default(),
+        common::JsExtensions::default(),
+        CodegenOptions {
+            strip_debug,
+            ..CodegenOptions::default()
+        },
+    );
+    let js = if dts {
+        let (js, dts) = js::codegen_with_dts(&config, ast);
+        let mut dts_file = File::create(output_path(common::EXTENSION_DTS)).into_diagnostic()?;
+        dts_file.write_all(dts.as_bytes()).into_diagnostic()?;
+        js
+    } else {
+        js::codegen(&config, ast)
+    };
+    let mut js_file = File::create(output_path(common::EXTENSION_JS)).into_diagnostic()?;
+    js_file.write_all(js.as_bytes()).into_diagnostic()?;
+
+    Ok(())
+}
+
+/// Sort the outputs of the `js` subcommand into the JavaScript output,
+/// and optionally the TypeScript declaration and source map outputs.
+fn js_output_paths(
+    outputs: Vec<String>,
+    extensions: common::JsExtensions,
+) -> Result<(PathBuf, Option<PathBuf>, Option<PathBuf>)> {
+    let mut js_output_path = None;
+    let mut dts_output_path = None;
+    let mut map_output_path = None;
+    for output in outputs {
+        let path = PathBuf::from(output);
+        // NOTE can't use `full_extension` here, as module file stems contain dots
+        let file_name = path.file_name().and_then(|file_name| file_name.to_str());
+        match file_name {
+            Some(file_name) if file_name.ends_with(&format!(".{}", extensions.dts)) => {
+                dts_output_path = Some(path);
+            }
+            Some(file_name) if file_name.ends_with(&format!(".{}", extensions.js_map)) => {
+                map_output_path = Some(path);
+            }
+            Some(file_name) if file_name.ends_with(&format!(".{}", extensions.js)) => {
+                js_output_path = Some(path);
+            }
+            _ => {
+                return Err(miette!(
+                    "unexpected output extension: {:#?}",
+                    full_extension(&path)
+                ))
+            }
+        }
+    }
+    let js_output_path = js_output_path.ok_or_else(|| miette!("JS output not specified"))?;
+    Ok((js_output_path, dts_output_path, map_output_path))
+}
+
 /// Generates a `package.json` from a `ditto.toml` input.
-fn run_package_json(input: &str, output: &str) -> Result<()> {
+///
+/// The `module_format` is that of the whole build, rather than the package's own.
+fn run_package_json(input: &str, output: &str, module_format: JsModuleFormat) -> Result<()> {
     use serde_json::{json, Map, Value};
 
     let config = read_config(input)?;
@@ -312,7 +962,6 @@ fn run_package_json(input: &str, output: &str) -> Result<()> {
     // https://stackoverflow.com/a/68558580/17263155
     let value = json!({
         "name": config.name.into_string(),
-        "type": "module",
         "dependencies": config
             .dependencies
             .into_iter()
@@ -327,8 +976,33 @@ fn run_package_json(input: &str, output: &str) -> Result<()> {
         unreachable!()
     };
 
+    // NodeJS treats `.js` files as CommonJS unless told otherwise
+    if module_format == JsModuleFormat::Esm {
+        object.insert(String::from("type"), Value::String(String::from("module")));
+    }
+
+    // Optional metadata is only included when it's set
+    if let Some(version) = config.version {
+        object.insert(String::from("version"), Value::String(version.to_string()));
+    }
+    if let Some(description) = config.description {
+        object.insert(String::from("description"), Value::String(description));
+    }
+    if let Some(license) = config.license {
+        object.insert(
+            String::from("license"),
+            Value::String(license.into_string()),
+        );
+    }
+    if let Some(repository) = config.repository {
+        object.insert(String::from("repository"), Value::String(repository));
+    }
+    if !config.authors.is_empty() {
+        object.insert(String::from("contributors"), json!(config.authors));
+    }
+
     if let Some(additions) = config.codegen_js_config.package_json_additions {
-        // NOTE "name" and "type" can't be overriden
+        // NOTE fields generated from the config (e.g. "name" and "type") can't be overriden
         object = merge_objects(additions, object)
     }
 
@@ -382,3 +1056,199 @@ fn full_extension(path: &Path) -> Option<&str> {
         .and_then(|str| str.split_once('.'))
         .map(|parts| parts.1)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{js_output_paths, run_package_json, suggest_imports};
+    use crate::{
+        common::JsExtensions,
+        index::{mk_index_path, IndexEntry, IndexEntryKind, PackageIndex},
+    };
+    use ditto_checker as checker;
+    use ditto_config::JsModuleFormat;
+    use ditto_cst as cst;
+    use miette::Diagnostic;
+    use std::{path::PathBuf, sync::Arc};
+
+    #[test]
+    fn it_sorts_js_outputs() {
+        assert_eq!(
+            js_output_paths(
+                vec![String::from("dist/Some.Module.js")],
+                JsExtensions::default()
+            )
+            .unwrap(),
+            (PathBuf::from("dist/Some.Module.js"), None, None)
+        );
+        assert_eq!(
+            js_output_paths(
+                vec![
+                    String::from("dist/Some.Module.d.ts"),
+                    String::from("dist/Some.Module.js"),
+                ],
+                JsExtensions::default()
+            )
+            .unwrap(),
+            (
+                PathBuf::from("dist/Some.Module.js"),
+                Some(PathBuf::from("dist/Some.Module.d.ts")),
+                None
+            )
+        );
+        assert_eq!(
+            js_output_paths(
+                vec![
+                    String::from("dist/Some.Module.js.map"),
+                    String::from("dist/Some.Module.js"),
+                ],
+                JsExtensions::default()
+            )
+            .unwrap(),
+            (
+                PathBuf::from("dist/Some.Module.js"),
+                None,
+                Some(PathBuf::from("dist/Some.Module.js.map"))
+            )
+        );
+        assert!(js_output_paths(
+            vec![String::from("dist/Some.Module.d.ts")],
+            JsExtensions::default()
+        )
+        .is_err());
+        assert!(js_output_paths(
+            vec![String::from("dist/Some.Module.ts")],
+            JsExtensions::default()
+        )
+        .is_err());
+
+        let extensions = JsExtensions::new(JsModuleFormat::CommonJs, true);
+        assert_eq!(
+            js_output_paths(
+                vec![
+                    String::from("dist/Some.Module.cjs.map"),
+                    String::from("dist/Some.Module.d.cts"),
+                    String::from("dist/Some.Module.cjs"),
+                ],
+                extensions
+            )
+            .unwrap(),
+            (
+                PathBuf::from("dist/Some.Module.cjs"),
+                Some(PathBuf::from("dist/Some.Module.d.cts")),
+                Some(PathBuf::from("dist/Some.Module.cjs.map"))
+            )
+        );
+        assert!(js_output_paths(vec![String::from("dist/Some.Module.js")], extensions).is_err());
+    }
+
+    #[test]
+    fn it_includes_metadata_in_package_json() {
+        let value = package_json(
+            r#"
+            name = "test"
+            version = "1.2.3"
+            description = "Testing"
+            license = "MIT"
+            repository = "https://example.com/test"
+            authors = ["Someone <someone@example.com>"]
+            "#,
+            JsModuleFormat::Esm,
+        );
+        assert_eq!(value["version"], "1.2.3");
+        assert_eq!(value["description"], "Testing");
+        assert_eq!(value["license"], "MIT");
+        assert_eq!(value["repository"], "https://example.com/test");
+        assert_eq!(
+            value["contributors"],
+            serde_json::json!(["Someone <someone@example.com>"])
+        );
+
+        // Fields that aren't configured can come from the additions
+        let value = package_json(
+            r#"
+            name = "test"
+
+            [codegen-js.package-json]
+            version = "2.0.0"
+            "#,
+            JsModuleFormat::Esm,
+        );
+        assert_eq!(value["version"], "2.0.0");
+        assert_eq!(value["name"], "test");
+
+        let value = package_json(r#"name = "test""#, JsModuleFormat::Esm);
+        assert!(value.get("version").is_none());
+    }
+
+    #[test]
+    fn it_only_sets_the_module_type_for_es_modules() {
+        let value = package_json(r#"name = "test""#, JsModuleFormat::Esm);
+        assert_eq!(value["type"], "module");
+
+        let value = package_json(r#"name = "test""#, JsModuleFormat::CommonJs);
+        assert!(value.get("type").is_none());
+    }
+
+    #[test]
+    fn it_suggests_imports_from_the_index() {
+        let index_dir = tempfile::tempdir().unwrap();
+        let entry = |name: &str, kind| IndexEntry {
+            name: name.to_string(),
+            package: String::from("maybe"),
+            module: String::from("Data.Maybe"),
+            kind,
+            summary: None,
+        };
+        PackageIndex {
+            hash: String::new(),
+            entries: vec![
+                entry("with_default", IndexEntryKind::Value),
+                entry("Maybe", IndexEntryKind::Type),
+            ],
+            reexports: Vec::new(),
+        }
+        .write(&mk_index_path(index_dir.path(), "maybe"))
+        .unwrap();
+
+        let source = "module Test exports (..);\n\nfive = with_default(5);\n";
+        let cst = cst::Module::parse(source).unwrap();
+        let err = checker::check_module(&checker::Everything::default(), cst).unwrap_err();
+        let imports = suggest_imports(index_dir.path(), &err);
+        assert_eq!(
+            imports,
+            vec![String::from("import (maybe) Data.Maybe (with_default);")]
+        );
+
+        let report = err.into_report_with_imports("test", Arc::new(source.to_string()), imports);
+        assert_eq!(
+            report.help().unwrap().to_string(),
+            "try importing it:\n`import (maybe) Data.Maybe (with_default);`"
+        );
+        let labels = report.labels().unwrap().collect::<Vec<_>>();
+        assert_eq!(labels.len(), 1);
+        let offset = source.find("with_default").unwrap();
+        assert_eq!(labels[0].offset(), offset);
+        assert_eq!(labels[0].len(), "with_default".len());
+
+        // Names of a different kind aren't suggested
+        let source = "module Test exports (..);\n\nnothing = Maybe;\n";
+        let cst = cst::Module::parse(source).unwrap();
+        let err = checker::check_module(&checker::Everything::default(), cst).unwrap_err();
+        assert!(suggest_imports(index_dir.path(), &err).is_empty());
+    }
+
+    fn package_json(config: &str, module_format: JsModuleFormat) -> serde_json::Value {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("ditto.toml");
+        let output = dir.path().join("package.json");
+        std::fs::write(&input, config).unwrap();
+        run_package_json(
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+            module_format,
+        )
+        .unwrap();
+        let contents = std::fs::read_to_string(output).unwrap();
+        serde_json::from_str::<serde_json::Value>(&contents).unwrap()
+    }
+}
@@ -0,0 +1,179 @@
+//! An index of everything that can be imported from installed packages.
+//!
+//! The index is written by `ditto pkg` whenever packages are checked, one file
+//! per package, and read here whenever something needs to suggest an import.
+use miette::{IntoDiagnostic, Result, WrapErr};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Something that can be imported.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct IndexEntry {
+    /// The exported name, e.g. `Just` or `with_default`.
+    pub name: String,
+    /// The package providing this name.
+    pub package: String,
+    /// The module exporting this name, e.g. `Data.Maybe`.
+    pub module: String,
+    /// What sort of thing this is.
+    pub kind: IndexEntryKind,
+    /// A short description of the type, if it's known without typechecking.
+    ///
+    /// For constructors this is the type being constructed, if that type is
+    /// exported too.
+    pub summary: Option<String>,
+}
+
+impl IndexEntry {
+    /// The import line that brings this entry into scope.
+    pub fn render_import(&self) -> String {
+        let import = match (self.kind, &self.summary) {
+            (IndexEntryKind::Constructor, Some(type_name)) => format!("{}(..)", type_name),
+            _ => self.name.clone(),
+        };
+        format!("import ({}) {} ({});", self.package, self.module, import)
+    }
+}
+
+/// The different things that can be imported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IndexEntryKind {
+    /// A value, e.g. `with_default`.
+    Value,
+    /// A type, e.g. `Maybe`.
+    Type,
+    /// A constructor, e.g. `Just`.
+    Constructor,
+}
+
+/// A module that re-exports everything exported by another module
+/// (`exports (module Some.Module)`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexReexport {
+    /// The package of the re-exporting module.
+    pub package: String,
+    /// The re-exporting module.
+    pub module: String,
+    /// The package of the re-exported module.
+    pub from_package: String,
+    /// The re-exported module.
+    pub from_module: String,
+}
+
+/// The index for a single package, as stored on disk.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PackageIndex {
+    /// Hash of the package contents this index was built from.
+    pub hash: String,
+    /// Everything the package declares and exports.
+    pub entries: Vec<IndexEntry>,
+    /// Modules of the package that re-export other modules.
+    ///
+    /// These are resolved when the index is read, as the re-exported module
+    /// might belong to another package.
+    pub reexports: Vec<IndexReexport>,
+}
+
+impl PackageIndex {
+    /// Read the index at `index_path`, if there is one.
+    pub fn read(index_path: &Path) -> Result<Option<Self>> {
+        if !index_path.exists() {
+            return Ok(None);
+        }
+        let contents = fs::read_to_string(index_path)
+            .into_diagnostic()
+            .wrap_err(format!(
+                "error reading index {}",
+                index_path.to_string_lossy()
+            ))?;
+        serde_json::from_str(&contents)
+            .into_diagnostic()
+            .wrap_err(format!(
+                "error parsing index {}",
+                index_path.to_string_lossy()
+            ))
+            .map(Some)
+    }
+
+    /// Write the index to `index_path`.
+    pub fn write(&self, index_path: &Path) -> Result<()> {
+        let contents = serde_json::to_string(self).into_diagnostic()?;
+        fs::write(index_path, contents)
+            .into_diagnostic()
+            .wrap_err(format!(
+                "error writing index {}",
+                index_path.to_string_lossy()
+            ))
+    }
+}
+
+/// Read every entry of the index, including those that are re-exported.
+///
+/// Nothing is cached, so this should be called when the index is needed,
+/// and is only as up to date as the installed packages when they were last checked.
+pub fn read_index(index_dir: &Path) -> Result<Vec<IndexEntry>> {
+    if !index_dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut index_paths = fs::read_dir(index_dir)
+        .into_diagnostic()?
+        .map(|entry| entry.map(|entry| entry.path()).into_diagnostic())
+        .collect::<Result<Vec<_>>>()?;
+    // Sort for determinism
+    index_paths.sort();
+
+    let mut entries = Vec::new();
+    let mut reexports = Vec::new();
+    for index_path in index_paths {
+        // Unreadable indexes will be rebuilt when packages are next checked
+        if let Ok(Some(package_index)) = PackageIndex::read(&index_path) {
+            entries.extend(package_index.entries);
+            reexports.extend(package_index.reexports);
+        }
+    }
+
+    // Re-exports can be chained, so keep going until nothing new turns up
+    let mut seen = entries.iter().cloned().collect::<HashSet<_>>();
+    loop {
+        let mut reexported = Vec::new();
+        for reexport in reexports.iter() {
+            for entry in entries.iter() {
+                if entry.package != reexport.from_package || entry.module != reexport.from_module {
+                    continue;
+                }
+                let entry = IndexEntry {
+                    package: reexport.package.clone(),
+                    module: reexport.module.clone(),
+                    ..entry.clone()
+                };
+                if seen.insert(entry.clone()) {
+                    reexported.push(entry);
+                }
+            }
+        }
+        if reexported.is_empty() {
+            return Ok(entries);
+        }
+        entries.extend(reexported);
+    }
+}
+
+/// Where the index is kept, given the `.ditto` directory.
+pub fn mk_index_dir(ditto_dir: &Path) -> PathBuf {
+    let mut path = ditto_dir.to_path_buf();
+    path.push("index");
+    path
+}
+
+/// Where the index for `package_name` is kept.
+pub fn mk_index_path(index_dir: &Path, package_name: &str) -> PathBuf {
+    let mut path = index_dir.to_path_buf();
+    path.push(package_name);
+    path.set_extension("json");
+    path
+}
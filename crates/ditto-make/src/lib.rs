@@ -3,10 +3,21 @@
 #![warn(missing_docs)]
 
 mod build_ninja;
+mod bundle;
 mod common;
 mod compile;
+mod index;
+mod manifest;
 mod utils;
 
-pub use build_ninja::{generate_build_ninja, BuildNinja, GetWarnings, PackageSources, Sources};
-pub use compile::{command as command_compile, run as run_compile};
-pub use utils::find_ditto_files;
+pub use build_ninja::{
+    generate_build_ninja, BuildNinja, GetWarnings, PackageSources, PlanError, Sources,
+};
+pub use bundle::BundlePlan;
+pub use compile::{command as command_compile, run as run_compile, CodegenOptions, SkippedModule};
+pub use index::{
+    mk_index_dir, mk_index_path, read_index, IndexEntry, IndexEntryKind, IndexReexport,
+    PackageIndex,
+};
+pub use manifest::{BuildManifest, RebuildExplanation, RebuildReason, StaleSource};
+pub use utils::{find_ditto_files, simplify_path};
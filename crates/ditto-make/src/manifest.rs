@@ -0,0 +1,554 @@
+use crate::{build_ninja::BuildNinja, common};
+use ditto_config::{ArtifactFormat, ProfileName};
+use miette::{IntoDiagnostic, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+/// A record of the inputs to every build edge, used to explain rebuilds.
+///
+/// This should be written after each successful build.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BuildManifest {
+    ditto_version: String,
+    config_hash: Option<String>,
+    profile: ProfileName,
+    edges: HashMap<String, ManifestEdge>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct ManifestEdge {
+    description: String,
+    outputs: Vec<PathBuf>,
+    /// Content hashes of the edge inputs, `None` if the input didn't exist.
+    inputs: HashMap<PathBuf, Option<String>>,
+}
+
+/// Why a build edge needs to be rerun.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RebuildReason {
+    /// The compiler has changed since the last build.
+    CompilerVersionChanged,
+    /// The ditto config has changed since the last build.
+    ConfigChanged,
+    /// The last build was for a different profile.
+    ProfileChanged(ProfileName),
+    /// There's no record of this edge being built.
+    NotPreviouslyBuilt,
+    /// An output of this edge has gone missing.
+    OutputMissing(PathBuf),
+    /// A source file has changed.
+    SourceChanged(PathBuf),
+    /// The exports of a dependency have changed.
+    DependencyExportsChanged(PathBuf),
+    /// A dependency is being rebuilt, so its exports might change.
+    DependencyRebuilt(String),
+}
+
+impl fmt::Display for RebuildReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CompilerVersionChanged => write!(f, "compiler version changed"),
+            Self::ConfigChanged => write!(f, "config changed"),
+            Self::ProfileChanged(previous) => {
+                write!(f, "profile changed (was {})", previous.as_str())
+            }
+            Self::NotPreviouslyBuilt => write!(f, "not previously built"),
+            Self::OutputMissing(path) => write!(f, "output missing: {}", path.to_string_lossy()),
+            Self::SourceChanged(path) => write!(f, "source changed: {}", path.to_string_lossy()),
+            Self::DependencyExportsChanged(path) => {
+                write!(f, "dependency exports changed: {}", path.to_string_lossy())
+            }
+            Self::DependencyRebuilt(description) => {
+                write!(f, "dependency out of date ({})", description)
+            }
+        }
+    }
+}
+
+/// A source file that changed while it was being built, so the artifacts
+/// compiled from it are already out of date.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaleSource {
+    /// The source file that changed.
+    pub source: PathBuf,
+    /// The outputs of the build edge that compiled it.
+    pub outputs: Vec<PathBuf>,
+}
+
+/// A single out-of-date build edge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RebuildExplanation {
+    /// What the build edge does, e.g. `"Checking Data.Maybe"`.
+    pub description: String,
+    /// The proximate reason for the rebuild.
+    pub reason: RebuildReason,
+}
+
+impl BuildManifest {
+    /// Record the current state of the inputs to a build plan.
+    pub fn new(
+        build_ninja: &BuildNinja,
+        ditto_version: &semver::Version,
+        config_path: &Path,
+    ) -> Result<Self> {
+        let mut edges = HashMap::new();
+        for (description, outputs, inputs) in build_ninja.edges() {
+            let inputs = inputs
+                .iter()
+                .map(|input| Ok((input.clone(), hash_file(input)?)))
+                .collect::<Result<HashMap<_, _>>>()?;
+            edges.insert(
+                edge_key(outputs),
+                ManifestEdge {
+                    description,
+                    outputs: outputs.to_vec(),
+                    inputs,
+                },
+            );
+        }
+        Ok(Self {
+            ditto_version: ditto_version.to_string(),
+            config_hash: hash_file(config_path)?,
+            profile: build_ninja.profile(),
+            edges,
+        })
+    }
+
+    /// Re-hash all the recorded inputs, e.g. after a build has produced
+    /// intermediate artifacts.
+    ///
+    /// Sources are recorded with the hash that was compiled, according to the
+    /// `.ast` header, rather than whatever is on disk now.
+    pub fn refresh(mut self) -> Result<Self> {
+        for edge in self.edges.values_mut() {
+            let compiled_hash = edge.compiled_source_hash()?;
+            for (input, hash) in edge.inputs.iter_mut() {
+                *hash = match compiled_hash {
+                    Some(ref compiled_hash) if is_source(input) => Some(compiled_hash.clone()),
+                    _ => hash_file(input)?,
+                };
+            }
+        }
+        Ok(self)
+    }
+
+    /// Find sources that have changed since they were compiled,
+    /// i.e. that were edited while the build was running.
+    ///
+    /// Should be called on a [refreshed](Self::refresh) manifest.
+    /// Results are sorted by source path.
+    pub fn stale_sources(&self) -> Result<Vec<StaleSource>> {
+        let mut stale_sources = Vec::new();
+        for edge in self.edges.values() {
+            for (input, hash) in edge.inputs.iter() {
+                if is_source(input) && hash_file(input)? != *hash {
+                    stale_sources.push(StaleSource {
+                        source: input.clone(),
+                        outputs: edge.outputs.clone(),
+                    });
+                }
+            }
+        }
+        stale_sources.sort_by(|a, b| a.source.cmp(&b.source));
+        Ok(stale_sources)
+    }
+
+    /// Read a previously written manifest, if there is one.
+    pub fn read(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        // A manifest that fails to deserialize (i.e. written by a different
+        // compiler version) is as good as no manifest.
+        Ok(common::deserialize(path).ok())
+    }
+
+    /// Write the manifest for the next build to compare against.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let file = fs::File::create(path).into_diagnostic()?;
+        common::serialize(ArtifactFormat::Json, file, self)
+    }
+
+    /// The profile that was built.
+    pub fn profile(&self) -> ProfileName {
+        self.profile
+    }
+
+    /// Check that this manifest was produced by the given compiler version and
+    /// config, returning the reason if it wasn't.
+    pub fn check_provenance(
+        &self,
+        ditto_version: &semver::Version,
+        config_path: &Path,
+    ) -> Result<Option<RebuildReason>> {
+        Ok(global_reason(
+            (
+                &ditto_version.to_string(),
+                hash_file(config_path)?.as_deref(),
+                self.profile,
+            ),
+            (
+                &self.ditto_version,
+                self.config_hash.as_deref(),
+                self.profile,
+            ),
+        ))
+    }
+
+    /// Explain which edges of the current build plan are out of date relative
+    /// to a `previous` manifest.
+    ///
+    /// Explanations are sorted by description.
+    pub fn explain(&self, previous: &Self) -> Vec<RebuildExplanation> {
+        let global_reason = global_reason(
+            (
+                &self.ditto_version,
+                self.config_hash.as_deref(),
+                self.profile,
+            ),
+            (
+                &previous.ditto_version,
+                previous.config_hash.as_deref(),
+                previous.profile,
+            ),
+        );
+
+        let mut reasons: HashMap<&str, (&ManifestEdge, RebuildReason)> = HashMap::new();
+        for (key, edge) in self.edges.iter() {
+            let reason = if let Some(ref reason) = global_reason {
+                Some(reason.clone())
+            } else if let Some(previous_edge) = previous.edges.get(key) {
+                direct_reason(edge, previous_edge)
+            } else {
+                Some(RebuildReason::NotPreviouslyBuilt)
+            };
+            if let Some(reason) = reason {
+                reasons.insert(key, (edge, reason));
+            }
+        }
+
+        // Anything downstream of an out-of-date edge will also be rebuilt
+        loop {
+            let out_of_date_outputs = reasons
+                .values()
+                .flat_map(|(edge, _)| {
+                    edge.outputs
+                        .iter()
+                        .map(|output| (output, edge.description.as_str()))
+                })
+                .collect::<HashMap<_, _>>();
+
+            let mut more_reasons = Vec::new();
+            for (key, edge) in self.edges.iter() {
+                if reasons.contains_key(key.as_str()) {
+                    continue;
+                }
+                let mut dependencies = edge
+                    .inputs
+                    .keys()
+                    .filter_map(|input| out_of_date_outputs.get(input))
+                    .collect::<Vec<_>>();
+                dependencies.sort();
+                if let Some(dependency) = dependencies.first() {
+                    more_reasons.push((
+                        key.as_str(),
+                        edge,
+                        RebuildReason::DependencyRebuilt(dependency.to_string()),
+                    ));
+                }
+            }
+            if more_reasons.is_empty() {
+                break;
+            }
+            for (key, edge, reason) in more_reasons {
+                reasons.insert(key, (edge, reason));
+            }
+        }
+
+        let mut explanations = reasons
+            .into_values()
+            .map(|(edge, reason)| RebuildExplanation {
+                description: edge.description.clone(),
+                reason,
+            })
+            .collect::<Vec<_>>();
+        explanations.sort_by(|a, b| a.description.cmp(&b.description));
+        explanations
+    }
+}
+
+impl ManifestEdge {
+    /// The source hash recorded by this edge's `.ast` output, if it has one.
+    fn compiled_source_hash(&self) -> Result<Option<String>> {
+        let ast_output = self.outputs.iter().find(|output| {
+            output.extension().and_then(|ext| ext.to_str()) == Some(common::EXTENSION_AST)
+        });
+        match ast_output {
+            Some(ast_output) if ast_output.exists() => {
+                common::read_ast_source_hash(ast_output).map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+fn is_source(path: &Path) -> bool {
+    path.extension().and_then(|ext| ext.to_str()) == Some(common::EXTENSION_DITTO)
+}
+
+fn global_reason(
+    (ditto_version, config_hash, profile): (&str, Option<&str>, ProfileName),
+    (previous_ditto_version, previous_config_hash, previous_profile): (
+        &str,
+        Option<&str>,
+        ProfileName,
+    ),
+) -> Option<RebuildReason> {
+    if ditto_version != previous_ditto_version {
+        Some(RebuildReason::CompilerVersionChanged)
+    } else if config_hash != previous_config_hash {
+        Some(RebuildReason::ConfigChanged)
+    } else if profile != previous_profile {
+        Some(RebuildReason::ProfileChanged(previous_profile))
+    } else {
+        None
+    }
+}
+
+fn direct_reason(edge: &ManifestEdge, previous_edge: &ManifestEdge) -> Option<RebuildReason> {
+    let mut outputs = edge.outputs.iter().collect::<Vec<_>>();
+    outputs.sort();
+    if let Some(missing) = outputs.into_iter().find(|output| !output.exists()) {
+        return Some(RebuildReason::OutputMissing(missing.clone()));
+    }
+    let mut inputs = edge.inputs.iter().collect::<Vec<_>>();
+    inputs.sort();
+    for (input, hash) in inputs {
+        if previous_edge.inputs.get(input) == Some(hash) {
+            continue;
+        }
+        let extension = input.extension().and_then(|ext| ext.to_str());
+        return Some(if extension == Some(common::EXTENSION_AST_EXPORTS) {
+            RebuildReason::DependencyExportsChanged(input.clone())
+        } else {
+            RebuildReason::SourceChanged(input.clone())
+        });
+    }
+    None
+}
+
+fn edge_key(outputs: &[PathBuf]) -> String {
+    let mut outputs = outputs
+        .iter()
+        .map(|output| output.to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+    outputs.sort();
+    outputs.join(" ")
+}
+
+fn hash_file(path: &Path) -> Result<Option<String>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents = fs::read(path).into_diagnostic()?;
+    Ok(Some(common::hash_contents(&contents)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BuildManifest, ManifestEdge, RebuildReason, StaleSource};
+    use ditto_config::{ArtifactFormat, ProfileName};
+    use std::{
+        collections::{HashMap, HashSet},
+        fs,
+        path::PathBuf,
+    };
+
+    /// `(description, outputs, (input, hash)s)`
+    type TestEdge<'a> = (&'a str, Vec<&'a str>, Vec<(&'a str, u64)>);
+
+    fn mk_manifest(edges: Vec<TestEdge>) -> BuildManifest {
+        BuildManifest {
+            ditto_version: String::from("0.0.0-test"),
+            config_hash: Some(String::from("0")),
+            profile: ProfileName::Dev,
+            edges: edges
+                .into_iter()
+                .map(|(description, outputs, inputs)| {
+                    let outputs = outputs.into_iter().map(PathBuf::from).collect::<Vec<_>>();
+                    (
+                        super::edge_key(&outputs),
+                        ManifestEdge {
+                            description: description.to_string(),
+                            outputs,
+                            inputs: inputs
+                                .into_iter()
+                                .map(|(input, hash)| (PathBuf::from(input), Some(hash.to_string())))
+                                .collect::<HashMap<_, _>>(),
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    // NOTE outputs need to exist for edges to be considered up to date,
+    // so we use files that we know are there.
+    static A_OUTPUT: &str = "fixtures/all-good/src/A.ditto";
+    static B_OUTPUT: &str = "fixtures/all-good/src/B.ditto";
+
+    #[test]
+    fn it_explains_rebuilds() {
+        let previous = mk_manifest(vec![
+            ("Checking A", vec![A_OUTPUT], vec![("A.ditto", 1)]),
+            (
+                "Checking B",
+                vec![B_OUTPUT],
+                vec![("B.ditto", 1), (A_OUTPUT, 1)],
+            ),
+        ]);
+        assert!(previous.explain(&previous).is_empty());
+
+        let current = mk_manifest(vec![
+            ("Checking A", vec![A_OUTPUT], vec![("A.ditto", 2)]),
+            (
+                "Checking B",
+                vec![B_OUTPUT],
+                vec![("B.ditto", 1), (A_OUTPUT, 1)],
+            ),
+        ]);
+        let explanations = current.explain(&previous);
+        assert_eq!(explanations.len(), 2);
+        assert_eq!(
+            explanations[0].reason,
+            RebuildReason::SourceChanged(PathBuf::from("A.ditto"))
+        );
+        assert_eq!(
+            explanations[1].reason,
+            RebuildReason::DependencyRebuilt(String::from("Checking A"))
+        );
+    }
+
+    #[test]
+    fn it_explains_missing_outputs() {
+        let previous = mk_manifest(vec![(
+            "Checking A",
+            vec!["not/a/real/output.ast"],
+            vec![("A.ditto", 1)],
+        )]);
+        let explanations = previous.explain(&previous);
+        assert_eq!(
+            explanations[0].reason,
+            RebuildReason::OutputMissing(PathBuf::from("not/a/real/output.ast"))
+        );
+    }
+
+    #[test]
+    fn it_explains_global_changes() {
+        let previous = mk_manifest(vec![("Checking A", vec![A_OUTPUT], vec![("A.ditto", 1)])]);
+        let mut current = previous.clone();
+        current.profile = ProfileName::Release;
+        assert_eq!(
+            current.explain(&previous)[0].reason,
+            RebuildReason::ProfileChanged(ProfileName::Dev)
+        );
+        current.config_hash = Some(String::from("1"));
+        assert_eq!(
+            current.explain(&previous)[0].reason,
+            RebuildReason::ConfigChanged
+        );
+        current.ditto_version = String::from("1.0.0");
+        assert_eq!(
+            current.explain(&previous)[0].reason,
+            RebuildReason::CompilerVersionChanged
+        );
+    }
+
+    #[test]
+    fn it_trusts_compiled_source_hashes() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("A.ditto");
+        let ast = dir.path().join("A.ast");
+        let ast_exports = dir.path().join("A.ast-exports");
+
+        let compiled = "module A exports (..);\n\nfive = 5;\n";
+        let edited = "module A exports (..);\n\nfive = 6;\n";
+        fs::write(&source, compiled).unwrap();
+
+        // Plan the build
+        let mut planned = mk_manifest(vec![(
+            "Checking A",
+            vec![ast.to_str().unwrap(), ast_exports.to_str().unwrap()],
+            vec![],
+        )]);
+        for edge in planned.edges.values_mut() {
+            edge.inputs
+                .insert(source.clone(), super::hash_file(&source).unwrap());
+        }
+
+        // Compile, then simulate an edit landing before the build finishes
+        crate::compile::run_ast(
+            dir.path().to_str().unwrap(),
+            None,
+            Default::default(),
+            HashSet::new(),
+            HashSet::new(),
+            false,
+            ArtifactFormat::Json,
+            vec![source.to_string_lossy().into_owned()],
+            vec![
+                ast.to_string_lossy().into_owned(),
+                ast_exports.to_string_lossy().into_owned(),
+            ],
+        )
+        .unwrap();
+        fs::write(&source, edited).unwrap();
+
+        let built = planned.refresh().unwrap();
+        let edge = built.edges.values().next().unwrap();
+        assert_eq!(
+            edge.inputs.get(&source),
+            Some(&Some(crate::common::hash_contents(compiled.as_bytes())))
+        );
+        assert_eq!(
+            built.stale_sources().unwrap(),
+            vec![StaleSource {
+                source: source.clone(),
+                outputs: vec![ast.clone(), ast_exports.clone()],
+            }]
+        );
+
+        // The next build should know that the edit hasn't been compiled
+        let mut current = built.clone();
+        for edge in current.edges.values_mut() {
+            edge.inputs
+                .insert(source.clone(), super::hash_file(&source).unwrap());
+        }
+        assert_eq!(
+            current.explain(&built)[0].reason,
+            RebuildReason::SourceChanged(source.clone())
+        );
+
+        // Recompiling catches up
+        crate::compile::run_ast(
+            dir.path().to_str().unwrap(),
+            None,
+            Default::default(),
+            HashSet::new(),
+            HashSet::new(),
+            false,
+            ArtifactFormat::Json,
+            vec![source.to_string_lossy().into_owned()],
+            vec![
+                ast.to_string_lossy().into_owned(),
+                ast_exports.to_string_lossy().into_owned(),
+            ],
+        )
+        .unwrap();
+        assert!(built.refresh().unwrap().stale_sources().unwrap().is_empty());
+    }
+}
@@ -13,15 +13,48 @@ pub fn find_ditto_files<P: AsRef<Path>>(root: P) -> io::Result<Vec<PathBuf>> {
         if entry.file_type().is_file() {
             let path = entry.path();
             if path.extension() == Some(OsStr::new("ditto")) {
-                files.push(path.to_path_buf())
+                files.push(simplify_path(path))
             }
         }
     }
     Ok(files)
 }
 
+/// Strips the verbatim prefix (`\\?\`) from a Windows path, such as those
+/// returned by [std::fs::canonicalize].
+///
+/// Verbatim paths aren't understood by a lot of tools (including ninja and NodeJS).
+pub fn simplify_path(path: &Path) -> PathBuf {
+    let string = path.to_string_lossy();
+    if let Some(unc) = string.strip_prefix(r"\\?\UNC\") {
+        PathBuf::from(format!(r"\\{}", unc))
+    } else if let Some(rest) = string.strip_prefix(r"\\?\") {
+        PathBuf::from(rest)
+    } else {
+        path.to_path_buf()
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use std::path::PathBuf;
+
+    #[test]
+    fn it_simplifies_verbatim_paths() {
+        assert_eq!(
+            super::simplify_path(&PathBuf::from(r"\\?\C:\project\src\A.ditto")),
+            PathBuf::from(r"C:\project\src\A.ditto")
+        );
+        assert_eq!(
+            super::simplify_path(&PathBuf::from(r"\\?\UNC\server\share\src\A.ditto")),
+            PathBuf::from(r"\\server\share\src\A.ditto")
+        );
+        assert_eq!(
+            super::simplify_path(&PathBuf::from("src/A.ditto")),
+            PathBuf::from("src/A.ditto")
+        );
+    }
+
     #[test]
     fn it_walks_as_expected() {
         let mut paths = super::find_ditto_files("fixtures/all-good/src")
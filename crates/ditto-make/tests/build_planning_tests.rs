@@ -27,6 +27,12 @@ macro_rules! test_with_current_dir {
 
 macro_rules! assert_build_ninja {
     ($dir:expr, $name:ident) => {
+        assert_build_ninja!($dir, $name, None);
+    };
+    ($dir:expr, $name:ident, $emit_only:expr) => {
+        assert_build_ninja!($dir, $name, $emit_only, ditto_config::ProfileName::Dev);
+    };
+    ($dir:expr, $name:ident, $emit_only:expr, $profile:expr) => {
         test_with_current_dir!($dir, $name, {
             let ditto_sources = ditto_make::find_ditto_files("./src")?;
             let sources = ditto_make::Sources {
@@ -45,7 +51,8 @@ macro_rules! assert_build_ninja {
                     dep_sources,
                 );
             }
-            let (build_file, _) = generate_build_ninja(sources, package_sources).unwrap();
+            let (build_file, _) =
+                generate_build_ninja(sources, package_sources, $profile, $emit_only).unwrap();
             let want = std::fs::read_to_string("./build.ninja")?;
             let got = build_file.into_syntax_path_slash();
             similar_asserts::assert_str_eq!(got: got, want: want);
@@ -56,6 +63,9 @@ macro_rules! assert_build_ninja {
 
 macro_rules! assert_build_ninja_error {
     ($dir:expr, $name:ident, $error_string:expr) => {
+        assert_build_ninja_error!($dir, $name, None, $error_string);
+    };
+    ($dir:expr, $name:ident, $emit_only:expr, $error_string:expr) => {
         test_with_current_dir!($dir, $name, {
             let ditto_sources = ditto_make::find_ditto_files("./src")?;
             let sources = ditto_make::Sources {
@@ -74,7 +84,12 @@ macro_rules! assert_build_ninja_error {
                     dep_sources,
                 );
             }
-            let err = generate_build_ninja(sources, package_sources)
+            let err = generate_build_ninja(
+                sources,
+                package_sources,
+                ditto_config::ProfileName::Dev,
+                $emit_only,
+            )
                 .map(|(build_ninja, _)| build_ninja)
                 .unwrap_err();
             similar_asserts::assert_str_eq!(got: err.to_string(), want: $error_string);
@@ -86,6 +101,41 @@ macro_rules! assert_build_ninja_error {
 assert_build_ninja!("./fixtures/all-good", builds_a_javascript_project);
 assert_build_ninja!("./fixtures/missing-module", it_ignores_bad_imports);
 assert_build_ninja!("./fixtures/no-codegen", it_works_without_targets);
+assert_build_ninja!(
+    "./fixtures/declarations",
+    it_generates_declarations_in_the_same_edge
+);
+assert_build_ninja!(
+    "./fixtures/nested-layout",
+    it_generates_nested_javascript_paths
+);
+assert_build_ninja!(
+    "./fixtures/commonjs",
+    it_generates_commonjs_with_matching_extensions
+);
+assert_build_ninja!(
+    "./fixtures/source-maps",
+    it_generates_source_maps_in_the_same_edge
+);
+assert_build_ninja!(
+    "./fixtures/inline-js",
+    it_allows_inline_js_for_the_whole_build
+);
+assert_build_ninja!(
+    "./fixtures/entry-modules",
+    it_allows_entry_modules_to_export_nothing
+);
+assert_build_ninja!(
+    "./fixtures/emit-only",
+    it_only_builds_the_emitted_module_and_its_dependencies,
+    Some("C")
+);
+assert_build_ninja!(
+    "./fixtures/release-profile",
+    it_builds_release_artifacts_separately,
+    None,
+    ditto_config::ProfileName::Release
+);
 
 assert_build_ninja_error!(
     "./fixtures/target-mismatch",
@@ -112,11 +162,89 @@ assert_build_ninja_error!(
     it_fails_for_self_referencing_modules,
     "module `A` can't import itself!"
 );
+assert_build_ninja_error!(
+    "./fixtures/header-parse-errors",
+    it_reports_all_header_parse_errors,
+    "2 modules failed to parse"
+);
+assert_build_ninja_error!(
+    "./fixtures/emit-only",
+    it_fails_to_emit_unknown_modules,
+    Some("Nope"),
+    "can't emit `Nope`, no module with that name in this package"
+);
+assert_build_ninja_error!(
+    "./fixtures/emit-only",
+    it_only_emits_modules_from_the_current_package,
+    Some("Dep"),
+    "can't emit `Dep`, no module with that name in this package"
+);
+
+test_with_current_dir!(
+    "./fixtures/partial-failure",
+    it_collects_warnings_when_other_modules_fail,
+    {
+        let sources = ditto_make::Sources {
+            config: std::path::PathBuf::from(ditto_config::CONFIG_FILE_NAME),
+            ditto: ditto_make::find_ditto_files("./src")?,
+        };
+        let (_, get_warnings) = generate_build_ninja(
+            sources,
+            ditto_make::PackageSources::new(),
+            ditto_config::ProfileName::Dev,
+            None,
+        )
+        .unwrap();
+
+        // Do what ninja would: `Bad` used to build (with a warning),
+        // but now fails, while `Warn` builds with a warning
+        let build_dir = std::path::Path::new("builddir/dev");
+        std::fs::create_dir_all(build_dir)?;
+        let compile_ast = |source: &str, module: &str| {
+            let outputs = ["ast", "ast-exports", "checker-warnings"]
+                .map(|extension| format!("builddir/dev/{}.{}", module, extension));
+            let mut args = vec![
+                "compile",
+                "ast",
+                "--build-dir",
+                "builddir/dev",
+                "-i",
+                source,
+                "-o",
+            ];
+            args.extend(outputs.iter().map(String::as_str));
+            let matches = ditto_make::command_compile("compile")
+                .try_get_matches_from(args)
+                .unwrap();
+            ditto_make::run_compile(&matches)
+        };
+        let previously_good = build_dir.join("Good.ditto");
+        std::fs::write(
+            &previously_good,
+            "module Bad exports (five);\n\nfive = 5;\n\nsix = 6;\n",
+        )?;
+        let previous = compile_ast(previously_good.to_str().unwrap(), "Bad");
+        let failed = compile_ast("./src/Bad.ditto", "Bad");
+        let succeeded = compile_ast("./src/Warn.ditto", "Warn");
+        let warnings = get_warnings();
+        std::fs::remove_dir_all("builddir")?;
+
+        assert!(previous.is_ok(), "{:?}", previous.unwrap_err());
+        assert!(failed.is_err());
+        assert!(succeeded.is_ok(), "{:?}", succeeded.unwrap_err());
+        let warnings = warnings.unwrap();
+        assert_eq!(warnings.len(), 1, "{:?}", warnings);
+        assert!(format!("{:?}", warnings[0]).contains("Warn.ditto"));
+        Ok(())
+    }
+);
 
 fn generate_build_ninja(
     sources: ditto_make::Sources,
     package_sources: ditto_make::PackageSources,
-) -> miette::Result<(ditto_make::BuildNinja, ditto_make::GetWarnings)> {
+    profile: ditto_config::ProfileName,
+    emit_only: Option<&str>,
+) -> Result<(ditto_make::BuildNinja, ditto_make::GetWarnings), ditto_make::PlanError> {
     ditto_make::generate_build_ninja(
         std::path::PathBuf::from("builddir"),
         std::path::PathBuf::from("ditto"),
@@ -124,5 +252,8 @@ fn generate_build_ninja(
         "compile",
         sources,
         package_sources,
+        &Default::default(),
+        profile,
+        emit_only,
     )
 }
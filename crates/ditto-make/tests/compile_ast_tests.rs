@@ -0,0 +1,120 @@
+use std::{fs, path::Path};
+
+fn compile_ast(build_dir: &Path, inputs: &[&Path], outputs: &[&Path]) -> miette::Result<()> {
+    let mut args = vec!["compile", "ast", "--build-dir", path_str(build_dir), "-i"];
+    args.extend(inputs.iter().map(|input| path_str(input)));
+    args.push("-o");
+    args.extend(outputs.iter().map(|output| path_str(output)));
+    let matches = ditto_make::command_compile("compile")
+        .try_get_matches_from(args)
+        .unwrap();
+    ditto_make::run_compile(&matches)
+}
+
+fn path_str(path: &Path) -> &str {
+    path.to_str().unwrap()
+}
+
+#[test]
+fn it_skips_modules_whose_dependencies_failed() {
+    let dir = tempfile::tempdir().unwrap();
+    let build_dir = dir.path().join("builddir");
+    fs::create_dir(&build_dir).unwrap();
+
+    let core = dir.path().join("Core.ditto");
+    let api = dir.path().join("Api.ditto");
+    let main = dir.path().join("Main.ditto");
+    fs::write(&core, "module Core exports (..);\n\nfive: Int = true;\n").unwrap();
+    fs::write(
+        &api,
+        "module Api exports (..);\n\nimport Core;\n\nsix = 6;\n",
+    )
+    .unwrap();
+    fs::write(
+        &main,
+        "module Main exports (..);\n\nimport Api;\n\nseven = 7;\n",
+    )
+    .unwrap();
+
+    let ast = |name: &str| build_dir.join(format!("{}.ast", name));
+    let exports = |name: &str| build_dir.join(format!("{}.ast-exports", name));
+
+    // The root cause is a real error
+    let err = compile_ast(&build_dir, &[&core], &[&ast("Core"), &exports("Core")]).unwrap_err();
+    assert!(err.downcast_ref::<ditto_make::SkippedModule>().is_none());
+
+    // ...and everything downstream is skipped, naming the root cause
+    let err = compile_ast(
+        &build_dir,
+        &[&exports("Core"), &api],
+        &[&ast("Api"), &exports("Api")],
+    )
+    .unwrap_err();
+    let skipped = err.downcast_ref::<ditto_make::SkippedModule>().unwrap();
+    assert_eq!(skipped.module, "Api");
+    assert_eq!(skipped.failed_dependency, "Core");
+
+    let err = compile_ast(
+        &build_dir,
+        &[&exports("Api"), &main],
+        &[&ast("Main"), &exports("Main")],
+    )
+    .unwrap_err();
+    let skipped = err.downcast_ref::<ditto_make::SkippedModule>().unwrap();
+    assert_eq!(skipped.module, "Main");
+    assert_eq!(skipped.failed_dependency, "Core");
+    assert_eq!(
+        skipped.render_note(),
+        "note: skipped Main because Core failed"
+    );
+    assert_eq!(
+        ditto_make::SkippedModule::parse_note(&skipped.render_note()),
+        Some("Main")
+    );
+
+    // Fixing the root cause clears the way
+    fs::write(&core, "module Core exports (..);\n\nfive: Int = 5;\n").unwrap();
+    compile_ast(&build_dir, &[&core], &[&ast("Core"), &exports("Core")]).unwrap();
+    compile_ast(
+        &build_dir,
+        &[&exports("Core"), &api],
+        &[&ast("Api"), &exports("Api")],
+    )
+    .unwrap();
+    assert!(!build_dir.join("Core.ast-failed").exists());
+    assert!(!build_dir.join("Api.ast-failed").exists());
+}
+
+#[test]
+fn it_normalizes_exports_across_irrelevant_edits() {
+    let dir = tempfile::tempdir().unwrap();
+    let build_dir = dir.path().join("builddir");
+    fs::create_dir(&build_dir).unwrap();
+
+    let module = dir.path().join("Util.ditto");
+    let ast = build_dir.join("Util.ast");
+    let exports = build_dir.join("Util.ast-exports");
+
+    fs::write(
+        &module,
+        "module Util exports (identity, always);\n\nidentity = (a) -> a;\n\nalways = (a, b) -> a;\n",
+    )
+    .unwrap();
+    compile_ast(&build_dir, &[&module], &[&ast, &exports]).unwrap();
+    let before = fs::read(&exports).unwrap();
+
+    // A private helper gets inferred first, using up type variables,
+    // and the exported implementations change without changing their types
+    fs::write(
+        &module,
+        "module Util exports (identity, always);\n\nhelper = (x, y, z) -> [x, y, z];\n\nidentity = (b) -> if true then b else b;\n\nalways = (a, c) -> identity(a);\n",
+    )
+    .unwrap();
+    compile_ast(&build_dir, &[&module], &[&ast, &exports]).unwrap();
+    let after = fs::read(&exports).unwrap();
+
+    assert_eq!(
+        String::from_utf8_lossy(&before),
+        String::from_utf8_lossy(&after)
+    );
+}
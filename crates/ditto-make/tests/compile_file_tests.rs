@@ -0,0 +1,97 @@
+use std::{fs, path::Path};
+
+fn compile_file(args: &[&str]) -> miette::Result<()> {
+    let matches = ditto_make::command_compile("compile")
+        .try_get_matches_from(["compile", "file"].iter().chain(args.iter()).copied())
+        .unwrap();
+    ditto_make::run_compile(&matches)
+}
+
+fn path_str(path: &Path) -> &str {
+    path.to_str().unwrap()
+}
+
+#[test]
+fn it_compiles_a_single_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("Foo.ditto");
+    let out_dir = dir.path().join("out");
+    fs::write(
+        &input,
+        "module Foo exports (..);\n\nforeign get_five: () -> Int;\n\nfive: Int = get_five();\n",
+    )
+    .unwrap();
+
+    compile_file(&[path_str(&input), "--out-dir", path_str(&out_dir)]).unwrap();
+
+    let js = fs::read_to_string(out_dir.join("Foo.js")).unwrap();
+    // Foreign modules are siblings of the source
+    assert!(js.contains(r#"from "../Foo.js""#), "{}", js);
+    assert!(js.contains("five"), "{}", js);
+    assert!(!out_dir.join("Foo.d.ts").exists());
+}
+
+#[test]
+fn it_compiles_a_single_file_with_declarations() {
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("Some.Module.ditto");
+    let out_dir = dir.path().join("out");
+    fs::write(
+        &input,
+        "module Some.Module exports (..);\n\nfive: Int = 5;\n",
+    )
+    .unwrap();
+
+    compile_file(&[path_str(&input), "--out-dir", path_str(&out_dir), "--dts"]).unwrap();
+
+    assert!(out_dir.join("Some.Module.js").exists());
+    assert!(out_dir.join("Some.Module.d.ts").exists());
+}
+
+#[test]
+fn it_rejects_imports_in_standalone_mode() {
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("Foo.ditto");
+    fs::write(
+        &input,
+        "module Foo exports (..);\n\nimport Data.Stuff;\n\nfive: Int = 5;\n",
+    )
+    .unwrap();
+
+    let err = compile_file(&[path_str(&input), "--out-dir", path_str(dir.path())]).unwrap_err();
+    let message = err.to_string();
+    assert!(message.contains("Data.Stuff"), "{}", message);
+    assert!(
+        message.contains("standalone mode cannot resolve imports"),
+        "{}",
+        message
+    );
+}
+
+#[test]
+fn it_rejects_inline_js_unless_allowed() {
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("Foo.ditto");
+    let out_dir = dir.path().join("out");
+    fs::write(
+        &input,
+        "module Foo exports (..);\n\nnow = js \"Date.now()\" : Int;\n",
+    )
+    .unwrap();
+
+    let err = compile_file(&[path_str(&input), "--out-dir", path_str(&out_dir)]).unwrap_err();
+    let message = err.to_string();
+    assert!(message.contains("inline JavaScript"), "{}", message);
+    assert!(!out_dir.join("Foo.js").exists());
+
+    compile_file(&[
+        path_str(&input),
+        "--out-dir",
+        path_str(&out_dir),
+        "--allow-inline-js",
+    ])
+    .unwrap();
+
+    let js = fs::read_to_string(out_dir.join("Foo.js")).unwrap();
+    assert!(js.contains("(Date.now())"), "{}", js);
+}
@@ -0,0 +1,68 @@
+use std::{fs, path::Path};
+
+fn compile_ast(build_dir: &Path, inputs: &[&Path], outputs: &[&Path]) -> miette::Result<()> {
+    let mut args = vec!["compile", "ast", "--build-dir", path_str(build_dir), "-i"];
+    args.extend(inputs.iter().map(|input| path_str(input)));
+    args.push("-o");
+    args.extend(outputs.iter().map(|output| path_str(output)));
+    compile(args)
+}
+
+fn compile_js(flags: &[&str], input: &Path, output: &Path) -> miette::Result<()> {
+    let mut args = vec!["compile", "js"];
+    args.extend(flags);
+    args.extend(["-i", path_str(input), "-o", path_str(output)]);
+    compile(args)
+}
+
+fn compile(args: Vec<&str>) -> miette::Result<()> {
+    let matches = ditto_make::command_compile("compile")
+        .try_get_matches_from(args)
+        .unwrap();
+    ditto_make::run_compile(&matches)
+}
+
+fn path_str(path: &Path) -> &str {
+    path.to_str().unwrap()
+}
+
+#[test]
+fn it_imports_commonjs_modules_with_matching_extensions() {
+    let dir = tempfile::tempdir().unwrap();
+    let build_dir = dir.path().join("builddir");
+    let dist_dir = dir.path().join("dist");
+    fs::create_dir(&build_dir).unwrap();
+    fs::create_dir(&dist_dir).unwrap();
+
+    let core = dir.path().join("Core.ditto");
+    let main = dir.path().join("Main.ditto");
+    fs::write(&core, "module Core exports (five);\n\nfive = 5;\n").unwrap();
+    fs::write(
+        &main,
+        "module Main exports (..);\n\nimport Core;\n\nalso_five = Core.five;\n",
+    )
+    .unwrap();
+
+    let ast = |name: &str| build_dir.join(format!("{}.ast", name));
+    let exports = |name: &str| build_dir.join(format!("{}.ast-exports", name));
+    compile_ast(&build_dir, &[&core], &[&ast("Core"), &exports("Core")]).unwrap();
+    compile_ast(
+        &build_dir,
+        &[&exports("Core"), &main],
+        &[&ast("Main"), &exports("Main")],
+    )
+    .unwrap();
+
+    let js = dist_dir.join("Main.cjs");
+    compile_js(
+        &["--module-format", "commonjs", "--module-extensions"],
+        &ast("Main"),
+        &js,
+    )
+    .unwrap();
+
+    let js = fs::read_to_string(js).unwrap();
+    assert!(js.contains(r#"require("./Core.cjs")"#), "{}", js);
+    assert!(js.contains("module.exports"), "{}", js);
+    assert!(!js.contains("import "), "{}", js);
+}